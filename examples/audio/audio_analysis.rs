@@ -0,0 +1,99 @@
+//! Analyses the default input device and draws the results.
+//!
+//! The mel-scaled bands of the spectrum are drawn as bars, the circle pulses with each detected
+//! beat and the estimated tempo and pitch are displayed above.
+use nannou::prelude::*;
+use nannou_audio as audio;
+use nannou_audio::analysis::{self, Analyzer, Reader, Scale};
+use nannou_audio::Buffer;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    _stream: audio::Stream<Analyzer>,
+    reader: Reader,
+    // Decays towards zero after each beat.
+    pulse: f32,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window().view(view).build().unwrap();
+
+    // Initialise the audio host so we can spawn an audio stream.
+    let audio_host = audio::Host::new();
+
+    // The analyzer lives on the audio thread while the reader is kept within our model.
+    let (analyzer, reader) = analysis::Builder::new().scale(Scale::Mel).bands(48).build();
+
+    let stream = audio_host
+        .new_input_stream(analyzer)
+        .capture(capture)
+        .build()
+        .unwrap();
+
+    Model {
+        _stream: stream,
+        reader,
+        pulse: 0.0,
+    }
+}
+
+// Analyse each captured buffer.
+fn capture(analyzer: &mut Analyzer, buffer: &Buffer) {
+    analyzer.process(buffer);
+}
+
+fn update(_app: &App, model: &mut Model, _update: Update) {
+    model.reader.update();
+    model.pulse *= 0.85;
+    if model.reader.take_beat() {
+        model.pulse = 1.0;
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    let win = app.window_rect();
+    let features = model.reader.features();
+
+    // Draw each band as a bar, normalised to decibels.
+    let mut columns = vec![0.0; features.bands.len()];
+    features.columns(&mut columns);
+    let bar_w = win.w() / columns.len().max(1) as f32;
+    for (i, &column) in columns.iter().enumerate() {
+        let h = column * win.h() * 0.5;
+        let x = win.left() + bar_w * (i as f32 + 0.5);
+        let hue = i as f32 / columns.len() as f32;
+        draw.rect()
+            .x_y(x, win.bottom() + h * 0.5)
+            .w_h(bar_w * 0.8, h)
+            .hsv(hue, 0.7, 0.9);
+    }
+
+    // Pulse with each beat and grow with the overall loudness.
+    let radius = 40.0 + features.rms * 400.0 + model.pulse * 40.0;
+    draw.ellipse()
+        .x_y(0.0, win.h() * 0.15)
+        .radius(radius)
+        .color(rgba(1.0, 1.0, 1.0, 0.2 + model.pulse * 0.8));
+
+    let bpm = match features.bpm {
+        Some(bpm) => format!("{:.1} BPM", bpm),
+        None => "-- BPM".to_string(),
+    };
+    let pitch = match features.pitch {
+        Some(hz) => format!("{:.1} Hz", hz),
+        None => "-- Hz".to_string(),
+    };
+    let text = format!("{}    {}", bpm, pitch);
+    draw.text(&text)
+        .x_y(0.0, win.top() - 30.0)
+        .w(win.w())
+        .color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
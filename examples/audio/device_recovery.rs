@@ -0,0 +1,109 @@
+//! Keep playing through audio devices being disconnected and reconnected.
+//!
+//! A sine tone plays on the default output device. Disconnect the device, e.g. by unplugging a USB
+//! interface, and the stream is rebuilt on the default device that remains, keeping the phase of
+//! the tone. Reconnect it and the stream continues on whichever device it recovered to. Changes to
+//! the available devices and the state of the stream are listed in the window.
+use nannou::prelude::*;
+use nannou_audio as audio;
+use nannou_audio::stream::recovery::{Event, Recovery};
+use nannou_audio::{Buffer, DeviceWatcher};
+use std::f64::consts::PI;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    stream: audio::Stream<Audio>,
+    watcher: DeviceWatcher,
+    // The most recent device changes and stream transitions, newest last.
+    log: Vec<String>,
+}
+
+struct Audio {
+    phase: f64,
+    hz: f64,
+}
+
+const MAX_LOG_LINES: usize = 16;
+
+fn model(app: &App) -> Model {
+    app.new_window().view(view).build().unwrap();
+
+    let audio_host = audio::Host::new();
+    let watcher = audio_host.watch_devices();
+
+    let model = Audio {
+        phase: 0.0,
+        hz: 440.0,
+    };
+    let stream = audio_host
+        .new_output_stream(model)
+        .render(audio)
+        .recovery(Recovery::new())
+        .build()
+        .unwrap();
+
+    Model {
+        stream,
+        watcher,
+        log: vec![],
+    }
+}
+
+fn audio(audio: &mut Audio, buffer: &mut Buffer) {
+    let sample_rate = buffer.sample_rate() as f64;
+    let volume = 0.25;
+    for frame in buffer.frames_mut() {
+        let sine_amp = (2.0 * PI * audio.phase).sin() as f32;
+        audio.phase = (audio.phase + audio.hz / sample_rate) % 1.0;
+        for channel in frame {
+            *channel = sine_amp * volume;
+        }
+    }
+}
+
+fn update(_app: &App, model: &mut Model, _update: Update) {
+    for event in model.watcher.events() {
+        model.log.push(format!("{:?}", event));
+    }
+
+    // Recovery is driven from here, as the stream must be rebuilt on the thread that owns it.
+    for event in model.stream.poll_recovery() {
+        let line = match event {
+            Event::DeviceLost { device } => format!("lost {}", device),
+            Event::Recovered { devices } => format!("recovered on {}", devices.join(", ")),
+            Event::RetryFailed { err } => format!("retry failed: {}", err),
+        };
+        model.log.push(line);
+    }
+
+    let excess = model.log.len().saturating_sub(MAX_LOG_LINES);
+    model.log.drain(..excess);
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(DIMGRAY);
+
+    let status = if model.stream.is_lost() {
+        "stream lost, waiting for a device..."
+    } else {
+        "stream playing"
+    };
+    let mut text = format!("{}\n\n", status);
+    for line in &model.log {
+        text.push_str(line);
+        text.push('\n');
+    }
+    let win = app.window_rect().pad(20.0);
+    draw.text(&text)
+        .wh(win.wh())
+        .xy(win.xy())
+        .left_justify()
+        .align_text_top()
+        .color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,109 @@
+// Renders audio offline in sync with captured frames.
+//
+// Each frame is captured as a PNG image to `/<path_to_nannou>/nannou/offline_render/` while the
+// audio for the duration of each frame is rendered to `offline_render.wav` within the same
+// directory. As the audio is rendered on demand rather than by an audio device, the two remain in
+// sync regardless of how long each frame takes to capture. Capturing stops after `SECS` seconds.
+
+use nannou::prelude::*;
+use nannou_audio::{offline, Buffer};
+use std::f64::consts::PI;
+
+// The rate at which frames are captured.
+const FPS: f64 = 60.0;
+// The duration of the rendering.
+const SECS: f64 = 5.0;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    stream: offline::Stream<Audio>,
+}
+
+struct Audio {
+    phase: f64,
+    hz: f64,
+    // The current amplitude, driven by the frame number.
+    amp: f64,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window().size(480, 480).view(view).build().unwrap();
+
+    let model = Audio {
+        phase: 0.0,
+        hz: 220.0,
+        amp: 0.0,
+    };
+    let mut stream = offline::Host::new()
+        .new_output_stream(model)
+        .render(audio)
+        .build();
+
+    let dir = capture_directory(app);
+    std::fs::create_dir_all(&dir).expect("failed to create capture directory");
+    stream
+        .record(dir.join("offline_render.wav"))
+        .expect("failed to start recording");
+
+    Model { stream }
+}
+
+fn audio(audio: &mut Audio, buffer: &mut Buffer) {
+    let sample_rate = buffer.sample_rate() as f64;
+    for frame in buffer.frames_mut() {
+        let amp = ((2.0 * PI * audio.phase).sin() * audio.amp) as f32;
+        audio.phase = (audio.phase + audio.hz / sample_rate) % 1.0;
+        for channel in frame {
+            *channel = amp;
+        }
+    }
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    let nth = app.elapsed_frames();
+    let t = nth as f64 / FPS;
+    if t >= SECS {
+        if model.stream.is_recording() {
+            let frames = model.stream.stop_recording().unwrap();
+            println!("Finished rendering {} frames of audio", frames);
+        }
+        return;
+    }
+
+    // Update the audio for this frame, then render all audio up until the next frame.
+    model.stream.send(|audio| {
+        audio.amp = (t * PI).sin().abs() * 0.5;
+        audio.hz = 220.0 + 220.0 * (t / SECS);
+    });
+    model.stream.render_until_secs((nth + 1) as f64 / FPS);
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    let audio = model.stream.model();
+    let radius = 20.0 + audio.amp as f32 * 300.0;
+    let hue = (audio.hz / 440.0) as f32;
+    draw.ellipse().radius(radius).hsv(hue, 0.8, 0.9);
+
+    draw.to_frame(app, &frame).unwrap();
+
+    // Capture the frame until rendering is finished.
+    if !model.stream.is_recording() {
+        return;
+    }
+    let file_path = capture_directory(app)
+        .join(format!("{:03}", frame.nth()))
+        .with_extension("png");
+    app.main_window().capture_frame(file_path);
+}
+
+fn capture_directory(app: &App) -> std::path::PathBuf {
+    app.project_path()
+        .expect("failed to locate `project_path`")
+        .join(app.exe_name().unwrap())
+}
@@ -1,25 +1,21 @@
-//! Records a WAV file using the default input device and default input format.
+//! Records the default input device to a WAV file using a `Recorder`.
 //!
-//! The input data is recorded to "$CARGO_MANIFEST_DIR/recorded.wav".
+//! Press space to start and stop recording. Each recording is written to a new
+//! "recorded_<n>.wav" file within the current directory. Press S while recording to split the
+//! recording into a new file without dropping any samples.
 use nannou::prelude::*;
 use nannou_audio as audio;
+use nannou_audio::recorder::Recorder;
 use nannou_audio::Buffer;
-use std::fs::File;
-use std::io::BufWriter;
-extern crate hound;
-
-type WavWriter = hound::WavWriter<BufWriter<File>>;
 
 fn main() {
     nannou::app(model).run();
 }
 
 struct Model {
-    stream: audio::Stream<CaptureModel>,
-}
-
-struct CaptureModel {
-    writer: WavWriter,
+    _stream: audio::Stream<()>,
+    recorder: Recorder,
+    take: usize,
 }
 
 fn model(app: &App) -> Model {
@@ -33,43 +29,47 @@ fn model(app: &App) -> Model {
     // Initialise the audio host so we can spawn an audio stream.
     let audio_host = audio::Host::new();
 
-    // Create a writer
-    let spec = get_spec(&audio_host);
-    let writer = hound::WavWriter::create("recorded.wav", spec).unwrap();
-    let capture_model = CaptureModel { writer };
+    // The recorder writes the audio delivered to the tap on its own thread.
+    let (recorder, tap) = Recorder::new();
 
+    // Attach the tap to an input stream. The captured audio is forwarded to the recorder before
+    // being delivered to the capture function.
     let stream = audio_host
-        .new_input_stream(capture_model)
-        .capture(capture_fn)
+        .new_input_stream(())
+        .capture(capture)
+        .tap(tap)
         .build()
         .unwrap();
 
-    Model { stream }
+    Model {
+        _stream: stream,
+        recorder,
+        take: 0,
+    }
 }
 
-// A function that captures the audio from the buffer and
-// writes it into the the WavWriter.
-fn capture_fn(audio: &mut CaptureModel, buffer: &Buffer) {
-    // When the program ends, writer is dropped and data gets written to the disk.
-    for frame in buffer.frames() {
-        for sample in frame {
-            audio
-                .writer
-                .write_sample(*sample)
-                .expect("error while writing the sample");
-        }
-    }
+// The recorder handles writing the audio, so there is nothing to do with the captured buffer.
+fn capture(_audio: &mut (), _buffer: &Buffer) {}
+
+fn next_path(model: &mut Model) -> String {
+    model.take += 1;
+    format!("recorded_{}.wav", model.take)
 }
 
 fn key_pressed(_app: &App, model: &mut Model, key: Key) {
     match key {
         Key::Space => {
-            if model.stream.is_paused() {
-                model.stream.play().unwrap();
-            } else if model.stream.is_playing() {
-                model.stream.pause().unwrap();
+            if model.recorder.is_recording() {
+                model.recorder.stop().unwrap();
+            } else {
+                let path = next_path(model);
+                model.recorder.start(path).unwrap();
             }
         }
+        Key::S if model.recorder.is_recording() => {
+            let path = next_path(model);
+            model.recorder.split(path).unwrap();
+        }
         _ => {}
     }
 }
@@ -77,32 +77,15 @@ fn key_pressed(_app: &App, model: &mut Model, key: Key) {
 fn view(app: &App, model: &Model, frame: Frame) {
     frame.clear(DIMGRAY);
 
-    if model.stream.is_playing() && app.elapsed_frames() % 30 < 20 {
+    // Report any errors that occurred on the recorder's writer thread.
+    for err in model.recorder.errors() {
+        eprintln!("recording error: {}", err);
+    }
+
+    if model.recorder.is_recording() && app.elapsed_frames() % 30 < 20 {
         let draw = app.draw();
         draw.ellipse().w_h(100.0, 100.0).color(RED);
 
         draw.to_frame(app, &frame).unwrap();
     }
 }
-
-// Get specification from default device format.
-fn get_spec(audio_host: &nannou_audio::Host) -> hound::WavSpec {
-    let default_input_config = audio_host
-        .default_input_device()
-        .unwrap()
-        .default_input_config()
-        .unwrap();
-
-    let (bits_per_sample, sample_format) = match default_input_config.sample_format() {
-        audio::cpal::SampleFormat::I16 => (16, hound::SampleFormat::Int),
-        audio::cpal::SampleFormat::U16 => (16, hound::SampleFormat::Int),
-        audio::cpal::SampleFormat::F32 => (32, hound::SampleFormat::Float),
-    };
-
-    hound::WavSpec {
-        channels: default_input_config.channels(),
-        sample_rate: default_input_config.sample_rate().0,
-        bits_per_sample,
-        sample_format,
-    }
-}
@@ -1,5 +1,9 @@
+//! Press space to play an instance of the sound with a random pan and pitch. Press S to stop all
+//! playing sounds.
+
 use nannou::prelude::*;
 use nannou_audio as audio;
+use nannou_audio::player::{Player, Sample, Voice};
 use nannou_audio::Buffer;
 
 fn main() {
@@ -8,10 +12,11 @@ fn main() {
 
 struct Model {
     stream: audio::Stream<Audio>,
+    sound: Sample,
 }
 
 struct Audio {
-    sounds: Vec<audrey::read::BufFileReader>,
+    player: Player,
 }
 
 fn model(app: &App) -> Model {
@@ -22,63 +27,50 @@ fn model(app: &App) -> Model {
         .build()
         .unwrap();
 
+    // Decode the sound into memory up front so that playing it is cheap.
+    let assets = app.assets_path().expect("could not find assets directory");
+    let path = assets.join("sounds").join("thumbpiano.wav");
+    let sound = Sample::from_file(path).expect("failed to load sound");
+
     // Initialise the audio host so we can spawn an audio stream.
     let audio_host = audio::Host::new();
 
     // Initialise the state that we want to live on the audio thread.
-    let sounds = vec![];
-    let model = Audio { sounds };
+    let model = Audio {
+        player: Player::new(),
+    };
     let stream = audio_host
         .new_output_stream(model)
         .render(audio)
         .build()
         .unwrap();
-    Model { stream }
+    Model { stream, sound }
 }
 
 // A function that renders the given `Audio` to the given `Buffer`.
-// In this case we play the audio file.
+// In this case we mix all playing voices onto the buffer.
 fn audio(audio: &mut Audio, buffer: &mut Buffer) {
-    let mut have_ended = vec![];
-    let len_frames = buffer.len_frames();
-
-    // Sum all of the sounds onto the buffer.
-    for (i, sound) in audio.sounds.iter_mut().enumerate() {
-        let mut frame_count = 0;
-        let file_frames = sound.frames::<[f32; 2]>().filter_map(Result::ok);
-        for (frame, file_frame) in buffer.frames_mut().zip(file_frames) {
-            for (sample, file_sample) in frame.iter_mut().zip(&file_frame) {
-                *sample += *file_sample;
-            }
-            frame_count += 1;
-        }
-
-        // If the sound yielded less samples than are in the buffer, it must have ended.
-        if frame_count < len_frames {
-            have_ended.push(i);
-        }
-    }
-
-    // Remove all sounds that have ended.
-    for i in have_ended.into_iter().rev() {
-        audio.sounds.remove(i);
-    }
+    audio.player.render(buffer);
 }
 
-fn key_pressed(app: &App, model: &mut Model, key: Key) {
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
     match key {
         // Start playing another instance of the sound.
         Key::Space => {
-            let assets = app.assets_path().expect("could not find assets directory");
-            let path = assets.join("sounds").join("thumbpiano.wav");
-            let sound = audrey::open(path).expect("failed to load sound");
+            let voice = Voice::new(&model.sound)
+                .pan(random_range(-1.0, 1.0))
+                .pitch(random_range(-12.0, 12.0));
             model
                 .stream
                 .send(move |audio| {
-                    audio.sounds.push(sound);
+                    audio.player.play(voice);
                 })
                 .ok();
         }
+        // Fade out all playing instances.
+        Key::S => {
+            model.stream.send(|audio| audio.player.stop_all()).ok();
+        }
         _ => {}
     }
 }
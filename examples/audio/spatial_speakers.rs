@@ -0,0 +1,162 @@
+//! Position sources of sound around a ring of speakers.
+//!
+//! A sine tone orbits the listener while a source of noise follows the mouse. Both are rendered to
+//! a ring of eight speakers via vector base amplitude panning (VBAP). Press D to switch to distance
+//! based amplitude panning (DBAP) and V to switch back.
+//!
+//! The example requests an eight channel output stream and will fail if the default output device
+//! offers fewer channels.
+
+use nannou::prelude::*;
+use nannou::rand::{rngs::SmallRng, Rng, SeedableRng};
+use nannou_audio as audio;
+use nannou_audio::spatial::{Layout, Source, Spatializer};
+use nannou_audio::Buffer;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    stream: audio::Stream<Audio>,
+    layout: Layout,
+    orbit_position: Point3,
+    mouse_position: Point3,
+}
+
+struct Audio {
+    phase: f32,
+    rng: SmallRng,
+    orbit: Source,
+    mouse: Source,
+    // A buffer to re-use for generating the audio of each source.
+    scratch: Vec<f32>,
+}
+
+const SAMPLE_RATE: u32 = 44_100;
+const SPEAKERS: usize = 8;
+
+// The radius of the speaker ring in metres and the number of points drawn per metre.
+const RING_RADIUS: f32 = 2.0;
+const SCALE: f32 = 120.0;
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(640, 640)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+
+    let layout = Layout::ring(SPEAKERS, RING_RADIUS);
+    let spatializer = Spatializer::vbap(layout.clone()).unwrap();
+    let orbit_position = pt3(0.0, RING_RADIUS, 0.0);
+    let mouse_position = pt3(0.0, 0.0, 0.0);
+    let audio_model = Audio {
+        phase: 0.0,
+        rng: SmallRng::from_seed([0; 16]),
+        orbit: spatializer.source(orbit_position),
+        mouse: spatializer.source(mouse_position),
+        scratch: Vec::with_capacity(1024),
+    };
+
+    let audio_host = audio::Host::new();
+    let stream = audio_host
+        .new_output_stream(audio_model)
+        .render(audio)
+        .channels(spatializer.channels())
+        .sample_rate(SAMPLE_RATE)
+        .build()
+        .unwrap();
+
+    Model {
+        stream,
+        layout,
+        orbit_position,
+        mouse_position,
+    }
+}
+
+fn audio(audio: &mut Audio, output: &mut Buffer) {
+    let frames = output.len_frames();
+
+    // A sine tone for the orbiting source.
+    audio.scratch.clear();
+    for _ in 0..frames {
+        audio.phase = (audio.phase + 330.0 / SAMPLE_RATE as f32) % 1.0;
+        audio.scratch.push((audio.phase * 2.0 * PI).sin() * 0.2);
+    }
+    audio.orbit.render(&audio.scratch, output);
+
+    // Quiet noise for the source following the mouse.
+    audio.scratch.clear();
+    for _ in 0..frames {
+        audio
+            .scratch
+            .push((audio.rng.gen::<f32>() * 2.0 - 1.0) * 0.05);
+    }
+    audio.mouse.render(&audio.scratch, output);
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    // Orbit once every eight seconds just outside of the ring.
+    let angle = app.time * 2.0 * PI / 8.0;
+    let orbit = pt3(angle.sin(), angle.cos(), 0.0) * RING_RADIUS * 1.2;
+    let mouse = pt3(app.mouse.x, app.mouse.y, 0.0) / SCALE;
+    model.orbit_position = orbit;
+    model.mouse_position = mouse;
+    model
+        .stream
+        .send(move |audio| {
+            audio.orbit.set_position(orbit);
+            audio.mouse.set_position(mouse);
+        })
+        .ok();
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    let spatializer = match key {
+        Key::V => Spatializer::vbap(model.layout.clone()).unwrap(),
+        Key::D => Spatializer::dbap(model.layout.clone()).unwrap(),
+        _ => return,
+    };
+    // Create the new sources on the main thread, then swap them in on the audio thread.
+    let orbit = spatializer.source(model.orbit_position);
+    let mouse = spatializer.source(model.mouse_position);
+    model
+        .stream
+        .send(move |audio| {
+            audio.orbit = orbit;
+            audio.mouse = mouse;
+        })
+        .ok();
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    // The speakers.
+    for speaker in model.layout.speakers() {
+        let [x, y, _] = speaker.position;
+        draw.rect()
+            .x_y(x * SCALE, y * SCALE)
+            .w_h(16.0, 16.0)
+            .color(GRAY);
+    }
+
+    // The listener and the sources.
+    draw.ellipse().radius(10.0).color(BLUE);
+    let orbit = model.orbit_position * SCALE;
+    draw.ellipse()
+        .x_y(orbit.x, orbit.y)
+        .radius(8.0)
+        .color(ORANGE);
+    let mouse = model.mouse_position * SCALE;
+    draw.ellipse()
+        .x_y(mouse.x, mouse.y)
+        .radius(8.0)
+        .color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,141 @@
+//! A simple synthesiser built from a graph of audio nodes.
+//!
+//! Press space to play a note. The mouse's x position controls the cutoff of the filter while the
+//! y position controls the pitch. Press R to toggle the reverb on and off.
+use nannou::prelude::*;
+use nannou_audio as audio;
+use nannou_audio::graph::{
+    self, Adsr, Biquad, EdgeId, FilterKind, Graph, NodeId, Oscillator, Processor, Reverb, Waveform,
+};
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    _stream: audio::Stream<Processor>,
+    graph: Graph,
+    osc: NodeId,
+    filter: NodeId,
+    env: NodeId,
+    reverb: NodeId,
+    // The connections that route the filter through the reverb, if enabled.
+    reverb_edges: Option<(EdgeId, EdgeId)>,
+    dry_edge: Option<EdgeId>,
+}
+
+const SAMPLE_RATE: u32 = 44_100;
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .key_pressed(key_pressed)
+        .key_released(key_released)
+        .view(view)
+        .build()
+        .unwrap();
+
+    // Build the graph: oscillator -> envelope -> filter -> output.
+    let (mut graph, processor) = Graph::new(2, SAMPLE_RATE);
+    let osc = graph
+        .add(Oscillator::new(Waveform::Saw, 110.0).amplitude(0.3))
+        .unwrap();
+    let env = graph
+        .add(
+            Adsr::new(1)
+                .attack(0.005)
+                .decay(0.2)
+                .sustain(0.6)
+                .release(0.5),
+        )
+        .unwrap();
+    let filter = graph
+        .add(Biquad::new(FilterKind::LowPass, 1, 1_000.0).q(4.0))
+        .unwrap();
+    let reverb = graph.add(Reverb::new().room_size(0.8).mix(0.4)).unwrap();
+    graph.connect(osc, env).unwrap();
+    graph.connect(env, filter).unwrap();
+    let dry_edge = graph.connect(filter, graph.output()).unwrap();
+    graph.commit().unwrap();
+
+    // The processor renders the graph on the audio thread.
+    let audio_host = audio::Host::new();
+    let stream = audio_host
+        .new_output_stream(processor)
+        .render(graph::render)
+        .sample_rate(SAMPLE_RATE)
+        .build()
+        .unwrap();
+
+    Model {
+        _stream: stream,
+        graph,
+        osc,
+        filter,
+        env,
+        reverb,
+        reverb_edges: None,
+        dry_edge: Some(dry_edge),
+    }
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    let win = app.window_rect();
+    let x = map_range(app.mouse.x, win.left(), win.right(), 0.0, 1.0);
+    let y = map_range(app.mouse.y, win.bottom(), win.top(), 0.0, 1.0);
+    let cutoff = 100.0 * 100.0f32.powf(x.clamp(0.0, 1.0));
+    let hz = 55.0 * 8.0f32.powf(y.clamp(0.0, 1.0));
+    model
+        .graph
+        .set(model.filter, Biquad::FREQUENCY, cutoff)
+        .unwrap();
+    model
+        .graph
+        .set(model.osc, Oscillator::FREQUENCY, hz)
+        .unwrap();
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    match key {
+        Key::Space => model.graph.set(model.env, Adsr::GATE, 1.0).unwrap(),
+        // Swap between routing the filter directly to the output and through the reverb. The
+        // connections fade so that the change does not click.
+        Key::R => {
+            let output = model.graph.output();
+            match model.reverb_edges.take() {
+                Some((a, b)) => {
+                    model.graph.disconnect(a).unwrap();
+                    model.graph.disconnect(b).unwrap();
+                    let dry = model.graph.connect(model.filter, output).unwrap();
+                    model.dry_edge = Some(dry);
+                }
+                None => {
+                    if let Some(dry) = model.dry_edge.take() {
+                        model.graph.disconnect(dry).unwrap();
+                    }
+                    let a = model.graph.connect(model.filter, model.reverb).unwrap();
+                    let b = model.graph.connect(model.reverb, output).unwrap();
+                    model.reverb_edges = Some((a, b));
+                }
+            }
+            model.graph.commit().unwrap();
+        }
+        _ => {}
+    }
+}
+
+fn key_released(_app: &App, model: &mut Model, key: Key) {
+    if let Key::Space = key {
+        model.graph.set(model.env, Adsr::GATE, 0.0).unwrap();
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    let color = if model.reverb_edges.is_some() {
+        DARKSLATEBLUE
+    } else {
+        DIMGRAY
+    };
+    draw.background().color(color);
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,112 @@
+//! Visualise the notes held on a MIDI controller.
+//!
+//! Connects to the first available MIDI input port, or creates a virtual port named "nannou" to
+//! which other software can connect if there are none. Each held note is drawn as a circle, the
+//! mod wheel (CC 1) scales the circles and the pitch bend wheel shifts them sideways.
+use nannou::prelude::*;
+use nannou_midi as midi;
+use std::collections::BTreeMap;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    receiver: midi::Receiver,
+    // The velocity of each held note, keyed by channel and key.
+    held: BTreeMap<(u8, u8), u8>,
+    modulation: u8,
+    pitch_bend: i16,
+    // The most recently received messages, newest last.
+    log: Vec<String>,
+}
+
+const MAX_LOG_LINES: usize = 12;
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .title("MIDI Receiver")
+        .view(view)
+        .build()
+        .unwrap();
+
+    let receiver = if midi::input_ports().unwrap().is_empty() {
+        midi::Receiver::create_virtual("nannou").unwrap()
+    } else {
+        midi::Receiver::connect_index(0).unwrap()
+    };
+
+    Model {
+        receiver,
+        held: BTreeMap::new(),
+        modulation: 0,
+        pitch_bend: midi::msg::PITCH_BEND_CENTER,
+        log: vec![],
+    }
+}
+
+fn update(_app: &App, model: &mut Model, _update: Update) {
+    // Receive any pending MIDI messages.
+    for (msg, _timestamp) in model.receiver.try_iter() {
+        // Skip the clock and active sensing messages that some devices send continuously.
+        if msg.is_real_time() {
+            continue;
+        }
+        match msg {
+            midi::Message::NoteOn {
+                channel,
+                key,
+                velocity,
+            } if velocity > 0 => {
+                model.held.insert((channel, key), velocity);
+            }
+            // A note on with a velocity of zero also releases the key.
+            midi::Message::NoteOn { channel, key, .. }
+            | midi::Message::NoteOff { channel, key, .. } => {
+                model.held.remove(&(channel, key));
+            }
+            midi::Message::ControlChange {
+                controller: 1,
+                value,
+                ..
+            } => model.modulation = value,
+            midi::Message::PitchBend { value, .. } => model.pitch_bend = value,
+            _ => (),
+        }
+        model.log.push(format!("{}", msg));
+    }
+
+    let excess = model.log.len().saturating_sub(MAX_LOG_LINES);
+    model.log.drain(..excess);
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    let win = app.window_rect();
+    let bend = model.pitch_bend as f32 / -(midi::msg::PITCH_BEND_MIN as f32);
+    let scale = 1.0 + model.modulation as f32 / 127.0 * 3.0;
+    for (&(channel, key), &velocity) in &model.held {
+        let x = map_range(key as f32, 0.0, 127.0, win.left(), win.right()) + bend * 100.0;
+        let y = map_range(channel as f32, 0.0, 15.0, win.bottom(), win.top()) * 0.8;
+        let radius = (4.0 + velocity as f32 / 127.0 * 20.0) * scale;
+        let hue = key as f32 / 12.0 % 1.0;
+        draw.ellipse().x_y(x, y).radius(radius).hsv(hue, 0.7, 1.0);
+    }
+
+    let mut text = format!("Listening on {}\n\n", model.receiver.port_name());
+    for line in &model.log {
+        text.push_str(line);
+        text.push('\n');
+    }
+    let pad = win.pad(20.0);
+    draw.text(&text)
+        .wh(pad.wh())
+        .xy(pad.xy())
+        .left_justify()
+        .align_text_top()
+        .color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,111 @@
+//! Discover other instances of this example on the local network and share the position of the
+//! mouse with all of them via a multicast group.
+//!
+//! Run this example on multiple machines on the same network, or multiple times on one machine.
+use nannou::prelude::*;
+use nannou_osc as osc;
+use std::net::{Ipv4Addr, SocketAddrV4};
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    discovery: osc::discovery::Discovery,
+    sender: osc::Sender,
+    receiver: osc::Receiver,
+    // The most recent mouse position received from each peer.
+    cursors: Vec<(String, Point2)>,
+}
+
+// The multicast group to which mouse positions are sent.
+const GROUP: Ipv4Addr = Ipv4Addr::new(239, 255, 78, 1);
+const PORT: u16 = 34256;
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .title("OSC Discovery")
+        .view(view)
+        .build()
+        .unwrap();
+
+    // Multiple instances on the same machine may receive from the same group, so allow the
+    // address to be reused.
+    let receiver = osc::Receiver::builder()
+        .port(PORT)
+        .reuse_address(true)
+        .join_multicast_v4(GROUP, Ipv4Addr::UNSPECIFIED)
+        .build()
+        .unwrap();
+    let sender = osc::Sender::builder()
+        .multicast_loop_v4(true)
+        .build()
+        .unwrap();
+
+    let name = format!("nannou-{}", std::process::id());
+    let discovery = osc::discovery::Discovery::new(&name, PORT).unwrap();
+
+    Model {
+        discovery,
+        sender,
+        receiver,
+        cursors: vec![],
+    }
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    for event in model.discovery.update() {
+        println!("{:?}", event);
+    }
+
+    let mouse = app.mouse.position();
+    let args = vec![osc::Type::Float(mouse.x), osc::Type::Float(mouse.y)];
+    let group = SocketAddrV4::new(GROUP, PORT);
+    model.sender.send(("/cursor", args), group).ok();
+
+    // Match received positions with the names of discovered peers by IP address.
+    for (packet, addr) in model.receiver.try_iter() {
+        for msg in packet.into_msgs() {
+            let args = msg.args.unwrap_or_default();
+            let (x, y) = match osc::args::extract::<(f32, f32)>(&args) {
+                Ok(xy) => xy,
+                Err(_) => continue,
+            };
+            let name = model
+                .discovery
+                .peers()
+                .iter()
+                .find(|peer| peer.addr.ip() == addr.ip())
+                .map(|peer| peer.name.clone())
+                .unwrap_or_else(|| addr.to_string());
+            match model.cursors.iter_mut().find(|(n, _)| *n == name) {
+                Some(cursor) => cursor.1 = pt2(x, y),
+                None => model.cursors.push((name, pt2(x, y))),
+            }
+        }
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(DARKSLATEGRAY);
+
+    for (name, position) in &model.cursors {
+        draw.ellipse().xy(*position).radius(10.0).color(ORANGE);
+        draw.text(name).xy(*position + vec2(0.0, 20.0)).color(WHITE);
+    }
+
+    let win = app.window_rect().pad(20.0);
+    let mut text = format!("{} peers discovered\n", model.discovery.peers().len());
+    for peer in model.discovery.peers() {
+        text.push_str(&format!("{} at {}\n", peer.name, peer.addr));
+    }
+    draw.text(&text)
+        .wh(win.wh())
+        .xy(win.xy())
+        .left_justify()
+        .align_text_top()
+        .color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
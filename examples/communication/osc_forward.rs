@@ -0,0 +1,83 @@
+//! Receive OSC packets on a background thread and forward them to the app as they arrive.
+//!
+//! Unlike the `osc_receiver` example, the app does not poll the receiver on each update. Instead,
+//! each packet is sent to the app via an `app::Proxy`, waking it up immediately even though the
+//! app uses `LoopMode::Wait`.
+//!
+//! Run alongside the `osc_sender` example.
+use nannou::prelude::*;
+use nannou_osc as osc;
+use std::net::SocketAddr;
+
+fn main() {
+    nannou::app(model).event(event).run();
+}
+
+struct Model {
+    // Receives packets on a background thread until dropped.
+    _forwarder: osc::recv::Forwarder,
+    received_packets: Vec<(SocketAddr, osc::Packet)>,
+}
+
+// The custom event sent to the app for each received packet.
+struct Received(SocketAddr, osc::Packet);
+
+// Make sure this matches the `TARGET_PORT` in the `osc_sender.rs` example.
+const PORT: u16 = 34254;
+
+fn model(app: &App) -> Model {
+    app.set_loop_mode(LoopMode::Wait);
+    app.new_window()
+        .title("OSC Forward")
+        .size(1400, 480)
+        .view(view)
+        .build()
+        .unwrap();
+
+    // Forward each packet to the app as soon as it is received.
+    let proxy = app.create_proxy();
+    let _forwarder = osc::receiver(PORT)
+        .unwrap()
+        .forward(move |packet, addr| {
+            proxy.send_event(Received(addr, packet)).ok();
+        })
+        .unwrap();
+
+    Model {
+        _forwarder,
+        received_packets: vec![],
+    }
+}
+
+fn event(_app: &App, model: &mut Model, event: Event) {
+    if let Event::User(user_event) = event {
+        if let Ok(Received(addr, packet)) = user_event.downcast::<Received>() {
+            model.received_packets.push((addr, packet));
+
+            // We'll display 10 packets at a time, so remove any excess.
+            let max_packets = 10;
+            let excess = model.received_packets.len().saturating_sub(max_packets);
+            model.received_packets.drain(..excess);
+        }
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(DARKBLUE);
+
+    let mut text = format!("Listening on port {}\nReceived packets:\n", PORT);
+    for &(addr, ref packet) in model.received_packets.iter().rev() {
+        text.push_str(&format!("{}: {:?}\n", addr, packet));
+    }
+
+    let win = app.window_rect().pad(20.0);
+    draw.text(&text)
+        .wh(win.wh())
+        .xy(win.xy())
+        .left_justify()
+        .align_text_top()
+        .color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
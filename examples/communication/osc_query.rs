@@ -0,0 +1,112 @@
+//! Publish the parameters of a sketch via OSCQuery so that control surfaces like TouchOSC or
+//! Chataigne can discover and control them.
+//!
+//! Point the control surface at `http://<this machine's IP>:9001`. Visiting the same address in
+//! a web browser shows the published namespace as JSON.
+use nannou::prelude::*;
+use nannou_osc as osc;
+use nannou_osc::query::{Access, Param, Range};
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    server: osc::query::Server<Circle>,
+    circle: Circle,
+}
+
+// The parameters controlled by remote clients.
+struct Circle {
+    radius: f32,
+    hue: f32,
+    filled: bool,
+}
+
+// The port on which OSC values are received.
+const OSC_PORT: u16 = 9000;
+// The port on which the namespace is served via HTTP and WebSocket.
+const HTTP_PORT: u16 = 9001;
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .title("OSCQuery")
+        .view(view)
+        .build()
+        .unwrap();
+
+    let circle = Circle {
+        radius: 100.0,
+        hue: 0.5,
+        filled: true,
+    };
+
+    let mut server = osc::query::Server::bind("nannou", OSC_PORT, HTTP_PORT).unwrap();
+    let radius = Param::new("/circle/radius", vec![osc::Type::Float(circle.radius)])
+        .range(Range::new(osc::Type::Float(0.0), osc::Type::Float(300.0)))
+        .unit("distance.px")
+        .description("The radius of the circle");
+    let hue = Param::new("/circle/hue", vec![osc::Type::Float(circle.hue)])
+        .range(Range::new(osc::Type::Float(0.0), osc::Type::Float(1.0)));
+    let filled = Param::new("/circle/filled", vec![osc::Type::Bool(circle.filled)]);
+    // A read-only parameter that is updated by the sketch itself.
+    let time = Param::new("/time", vec![osc::Type::Float(0.0)])
+        .access(Access::Read)
+        .unit("time.s");
+    server
+        .add(radius, |circle: &mut Circle, _addr, (radius,): (f32,)| {
+            circle.radius = radius;
+        })
+        .unwrap()
+        .add(hue, |circle: &mut Circle, _addr, (hue,): (f32,)| {
+            circle.hue = hue;
+        })
+        .unwrap()
+        .add(filled, |circle: &mut Circle, _addr, (filled,): (bool,)| {
+            circle.filled = filled;
+        })
+        .unwrap()
+        .add_value(time)
+        .unwrap();
+
+    Model { server, circle }
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    for err in model.server.poll(&mut model.circle) {
+        eprintln!("{}", err);
+    }
+    // Clients listening to `/time` receive each new value.
+    let time = vec![osc::Type::Float(app.time)];
+    model.server.set("/time", time).unwrap();
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    let color = hsl(model.circle.hue, 0.8, 0.5);
+    if model.circle.filled {
+        draw.ellipse().radius(model.circle.radius).color(color);
+    } else {
+        draw.ellipse()
+            .radius(model.circle.radius)
+            .no_fill()
+            .stroke(color)
+            .stroke_weight(4.0);
+    }
+
+    let win = app.window_rect().pad(20.0);
+    let text = format!(
+        "OSC port: {}\nOSCQuery: http://localhost:{}",
+        OSC_PORT, HTTP_PORT
+    );
+    draw.text(&text)
+        .wh(win.wh())
+        .xy(win.xy())
+        .left_justify()
+        .align_text_top()
+        .color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
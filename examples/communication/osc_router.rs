@@ -0,0 +1,110 @@
+//! Dispatch received OSC messages to handlers by address pattern.
+//!
+//! Run alongside the `osc_sender` example, which sends the position of the mouse along with
+//! presses and releases of its buttons.
+use nannou::prelude::*;
+use nannou_osc as osc;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    receiver: osc::Receiver,
+    router: osc::Router<Cursor>,
+    cursor: Cursor,
+}
+
+// The state of the remote mouse, updated by the router's handlers.
+#[derive(Default)]
+struct Cursor {
+    position: Point2,
+    pressed: bool,
+    // The most recent messages that could not be handled.
+    errors: Vec<String>,
+}
+
+// Make sure this matches the `TARGET_PORT` in the `osc_sender.rs` example.
+const PORT: u16 = 34254;
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .title("OSC Router")
+        .view(view)
+        .build()
+        .unwrap();
+
+    let receiver = osc::receiver(PORT).unwrap();
+
+    // Register a handler for each address pattern. The arguments of each message are extracted
+    // into the tuple of types expected by the handler.
+    let mut router = osc::Router::new();
+    router
+        .route(
+            "/example/mouse_moved/",
+            |cursor: &mut Cursor, _addr, (x, y): (f32, f32)| {
+                cursor.position = pt2(x, y);
+            },
+        )
+        .unwrap()
+        .route(
+            "/example/mouse_{pressed,released}/",
+            |cursor: &mut Cursor, addr, (_button,): (String,)| {
+                cursor.pressed = addr.ends_with("pressed/");
+            },
+        )
+        .unwrap()
+        .unmatched(|cursor: &mut Cursor, msg| {
+            cursor
+                .errors
+                .push(format!("unexpected message {}", msg.addr));
+        });
+
+    Model {
+        receiver,
+        router,
+        cursor: Cursor::default(),
+    }
+}
+
+fn update(_app: &App, model: &mut Model, _update: Update) {
+    for (packet, _addr) in model.receiver.try_iter() {
+        for err in model.router.dispatch(&mut model.cursor, packet) {
+            model.cursor.errors.push(format!("{}", err));
+        }
+    }
+    // Deliver any bundles scheduled for the future whose time has arrived.
+    for err in model.router.poll(&mut model.cursor) {
+        model.cursor.errors.push(format!("{}", err));
+    }
+
+    let max_errors = 5;
+    let excess = model.cursor.errors.len().saturating_sub(max_errors);
+    model.cursor.errors.drain(..excess);
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(DARKSLATEGRAY);
+
+    let color = if model.cursor.pressed { ORANGE } else { WHITE };
+    draw.ellipse()
+        .xy(model.cursor.position)
+        .radius(20.0)
+        .color(color);
+
+    let win = app.window_rect().pad(20.0);
+    let mut text = format!("Listening on port {}\n", PORT);
+    for err in &model.cursor.errors {
+        text.push_str(err);
+        text.push('\n');
+    }
+    draw.text(&text)
+        .wh(win.wh())
+        .xy(win.xy())
+        .left_justify()
+        .align_text_top()
+        .color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,38 @@
+//! A simple example demonstrating outlined and glowing text via `draw.text(..).outline(..)` and
+//! `draw.text(..).glow(..)`.
+//!
+//! Text with effects is drawn from a signed distance field, so it remains crisp while it is
+//! scaled and rotated.
+
+use nannou::prelude::*;
+
+fn main() {
+    nannou::sketch(view).run()
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(rgb(0.05, 0.05, 0.1));
+
+    let t = app.time;
+    let scale = 1.0 + (t * 0.5).sin() * 0.75;
+
+    // Scale and rotate the text over time.
+    draw.scale(scale)
+        .rotate(t * 0.25)
+        .text("nannou")
+        .color(WHITE)
+        .font_size(48)
+        .w(400.0)
+        .outline(2.0, rgb(0.9, 0.2, 0.4))
+        .glow(4.0, rgba(1.0, 0.4, 0.6, 0.8));
+
+    draw.text("outlined")
+        .color(BLACK)
+        .font_size(32)
+        .w(400.0)
+        .outline(3.0, WHITE)
+        .y(-120.0);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,47 @@
+//! A simple example demonstrating how to lay text along a path with `draw.text().on_path(..)`.
+//!
+//! Each glyph is also animated via `glyph_transform`, which may modify the position, rotation,
+//! scale and color of each glyph before it is drawn.
+
+use nannou::prelude::*;
+
+fn main() {
+    nannou::sketch(view).run()
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(WHITE);
+
+    // A wave spanning the width of the window.
+    let win_rect = app.main_window().rect().pad(40.0);
+    let t = app.time;
+    let mut builder = geom::path().move_to(pt2(win_rect.left(), 0.0));
+    let resolution = 100;
+    for i in 1..=resolution {
+        let x = map_range(i, 0, resolution, win_rect.left(), win_rect.right());
+        let y = (x * 0.01 + t).sin() * win_rect.h() * 0.25;
+        builder = builder.line_to(pt2(x, y));
+    }
+    let path = builder.build();
+
+    // Draw the path itself.
+    let points = path.measure(32).divide(200).collect::<Vec<_>>();
+    draw.polyline().weight(1.0).color(GRAY).points(points);
+
+    // Lay the text along the path, bouncing each glyph out of phase with its neighbours.
+    let text = "Text laid along a path, one glyph at a time.";
+    draw.text(text)
+        .color(BLACK)
+        .font_size(32)
+        .no_line_wrap()
+        .on_path(&path)
+        .path_align(text::Align::Middle)
+        .glyph_transform(move |i, _info, transform| {
+            let phase = t * 4.0 - i as f32 * 0.5;
+            transform.scale = vec2(1.0, 1.0) * (1.0 + phase.sin() * 0.25);
+            transform.color = Some(lin_srgba(phase.cos() * 0.5 + 0.5, 0.0, 0.5, 1.0));
+        });
+
+    draw.to_frame(app, &frame).unwrap();
+}
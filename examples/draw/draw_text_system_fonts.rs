@@ -0,0 +1,64 @@
+//! Lists the font families installed on the system, drawing each family name in its regular and
+//! bold faces.
+//!
+//! Fonts are selected by family, weight and style via `text::font::system::Query`.
+
+use nannou::prelude::*;
+use nannou::text::font::system::{self, Query, Weight};
+use nannou::text::Font;
+
+struct Model {
+    families: Vec<(String, Font, Option<Font>)>,
+}
+
+fn main() {
+    nannou::app(model).simple_window(view).run();
+}
+
+fn model(_app: &App) -> Model {
+    let db = system::database();
+    let families = db
+        .families()
+        .into_iter()
+        .filter_map(|family| {
+            let regular = Query::new(family).load().ok()?;
+            let bold = Query::new(family).weight(Weight::BOLD).load().ok();
+            Some((family.to_string(), regular, bold))
+        })
+        .take(12)
+        .collect();
+    Model { families }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(WHITE);
+
+    let win = app.window_rect();
+    if model.families.is_empty() {
+        draw.text("No system fonts found").color(BLACK);
+    }
+
+    let line_h = win.h() / (model.families.len() + 1) as f32;
+    for (i, (family, regular, bold)) in model.families.iter().enumerate() {
+        let y = win.top() - line_h * (i + 1) as f32;
+        draw.text(family)
+            .font(regular.clone())
+            .font_size(18)
+            .color(BLACK)
+            .w(win.w() * 0.5)
+            .x(win.left() + win.w() * 0.25)
+            .y(y);
+        if let Some(bold) = bold {
+            draw.text(family)
+                .font(bold.clone())
+                .font_size(18)
+                .color(BLACK)
+                .w(win.w() * 0.5)
+                .x(win.right() - win.w() * 0.25)
+                .y(y);
+        }
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -49,6 +49,7 @@ fn event(_app: &App, _model: &mut Model, event: Event) {
         Event::Update(_dt) => {}
         Event::Suspended => {}
         Event::Resumed => {}
+        Event::User(_event) => {}
     }
 }
 
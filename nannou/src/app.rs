@@ -9,7 +9,7 @@
 //! - [**LoopMode**](./enum.LoopMode.html) - describes the behaviour of the application event loop.
 
 use crate::draw;
-use crate::event::{self, Event, Key, LoopEvent, Update};
+use crate::event::{self, Event, Key, LoopEvent, Update, UserEvent};
 use crate::frame::{Frame, RawFrame};
 use crate::geom;
 use crate::state;
@@ -172,10 +172,10 @@ struct DrawState {
 pub type DrawScalar = geom::scalar::Default;
 
 /// A handle to the **App** that can be shared across threads. This may be used to "wake up" the
-/// **App**'s inner event loop.
+/// **App**'s inner event loop or to send it custom events.
 #[derive(Clone)]
 pub struct Proxy {
-    event_loop_proxy: winit::event_loop::EventLoopProxy<()>,
+    event_loop_proxy: winit::event_loop::EventLoopProxy<ProxyEvent>,
     // Indicates whether or not the events loop is currently asleep.
     //
     // This is set to `true` each time the events loop is ready to return and the `LoopMode` is
@@ -185,6 +185,11 @@ pub struct Proxy {
     event_loop_is_asleep: Arc<AtomicBool>,
 }
 
+// The type of user event sent to the winit event loop by the **Proxy**.
+//
+// `None` is sent to wake up the event loop without emitting an event.
+type ProxyEvent = Option<UserEvent>;
+
 // State related specifically to the application loop, shared between loop modes.
 struct LoopState {
     updates_since_event: usize,
@@ -423,7 +428,7 @@ where
     /// initialised on the main thread.
     pub fn run(self) {
         // Start the winit window event loop.
-        let event_loop = winit::event_loop::EventLoop::new();
+        let event_loop = winit::event_loop::EventLoop::<ProxyEvent>::with_user_event();
 
         // Create the proxy used to awaken the event loop.
         let event_loop_proxy = event_loop.create_proxy();
@@ -868,12 +873,42 @@ impl Proxy {
     /// to be called more than necessary.
     pub fn wakeup(&self) -> Result<(), winit::event_loop::EventLoopClosed<()>> {
         if self.event_loop_is_asleep.load(atomic::Ordering::Relaxed) {
-            self.event_loop_proxy.send_event(())?;
+            self.event_loop_proxy
+                .send_event(None)
+                .map_err(|_| winit::event_loop::EventLoopClosed(()))?;
             self.event_loop_is_asleep
                 .store(false, atomic::Ordering::Relaxed);
         }
         Ok(())
     }
+
+    /// Send a custom event to the application.
+    ///
+    /// The event wakes up the **App**'s inner event loop and is emitted to the user's `event`
+    /// function as an `Event::User`, where it may be retrieved via `UserEvent::downcast_ref`. This
+    /// makes it possible to respond to events produced on other threads (e.g. messages received
+    /// from the network) as soon as they occur, even when using `LoopMode::Wait`.
+    ///
+    /// Unlike `wakeup`, each call sends the event regardless of whether or not the event loop is
+    /// asleep. Returns the event if the event loop no longer exists.
+    pub fn send_event<T>(&self, event: T) -> Result<(), winit::event_loop::EventLoopClosed<T>>
+    where
+        T: std::any::Any + Send,
+    {
+        let event = Some(UserEvent::new(event));
+        if let Err(winit::event_loop::EventLoopClosed(event)) =
+            self.event_loop_proxy.send_event(event)
+        {
+            let event = event
+                .expect("the sent event is always `Some`")
+                .downcast::<T>()
+                .expect("the sent event is always of type `T`");
+            return Err(winit::event_loop::EventLoopClosed(event));
+        }
+        self.event_loop_is_asleep
+            .store(false, atomic::Ordering::Relaxed);
+        Ok(())
+    }
 }
 
 impl draw::Draw {
@@ -944,20 +979,20 @@ pub(crate) enum EventLoopWindowTarget {
     // Ownership over the event loop.
     //
     // This is the state before the `EventLoop::run` begins.
-    Owned(winit::event_loop::EventLoop<()>),
+    Owned(winit::event_loop::EventLoop<ProxyEvent>),
     // A pointer to the target for building windows.
     //
     // This is the state during `EventLoop::run`. This pointer becomes invalid following
     // `EventLoop::run`, so it is essential to take care that we are in the correct state when
     // using this pointer.
-    Pointer(*const winit::event_loop::EventLoopWindowTarget<()>),
+    Pointer(*const winit::event_loop::EventLoopWindowTarget<ProxyEvent>),
 }
 
 impl EventLoopWindowTarget {
     // Take a reference to the inner event loop window target.
     //
     // This method is solely used during `window::Builder::build` to allow for
-    pub(crate) fn as_ref(&self) -> &winit::event_loop::EventLoopWindowTarget<()> {
+    pub(crate) fn as_ref(&self) -> &winit::event_loop::EventLoopWindowTarget<ProxyEvent> {
         match *self {
             EventLoopWindowTarget::Owned(ref event_loop) => (&**event_loop),
             EventLoopWindowTarget::Pointer(ptr) => {
@@ -967,7 +1002,7 @@ impl EventLoopWindowTarget {
                 // `EventLoopWindowTarget` is dropped immediately after `EventLoop::run` completes.
                 // This allows us to take care of abiding by the `EventLoopWindowTarget` lifetime
                 // manually while avoiding having the lifetime propagate up through the `App` type.
-                unsafe { &*ptr as &winit::event_loop::EventLoopWindowTarget<()> }
+                unsafe { &*ptr as &winit::event_loop::EventLoopWindowTarget<ProxyEvent> }
             }
         }
     }
@@ -1161,6 +1196,18 @@ fn run_loop<M, E>(
             // Ignore wake-up events for now. Currently, these can only be triggered via the app proxy.
             winit::event::Event::NewEvents(_) => {}
 
+            // Emit custom events sent via the app proxy to the user's event function.
+            winit::event::Event::UserEvent(ref mut user_event) => {
+                loop_state.updates_since_event = 0;
+                if let (Some(model), Some(event_fn), Some(user_event)) =
+                    (model.as_mut(), event_fn, user_event.take())
+                {
+                    if let Some(event) = E::from_user_event(user_event, &app) {
+                        event_fn(&app, model, event);
+                    }
+                }
+            }
+
             // Track the number of updates since the last I/O event.
             // This is necessary for the `Wait` loop mode to behave correctly.
             ref _other_event => {
@@ -1338,7 +1385,7 @@ fn process_and_emit_winit_event<'a, M, E>(
     app: &mut App,
     model: &mut M,
     event_fn: Option<EventFn<M, E>>,
-    winit_event: &winit::event::Event<'a, ()>,
+    winit_event: &winit::event::Event<'a, ProxyEvent>,
 ) -> bool
 where
     M: 'static,
@@ -0,0 +1,61 @@
+use crate::draw::primitive::mesh::DrawingMesh;
+use crate::draw::Draw;
+use crate::geom::graph::node;
+use crate::geom::{self, Point3};
+use crate::math::{BaseFloat, Matrix4};
+
+/// A type used to draw the nodes of a **geom::Graph**, each with its world transform.
+pub struct Graph<'a, S = geom::scalar::Default>
+where
+    S: 'a + BaseFloat,
+{
+    draw: &'a Draw<S>,
+    graph: &'a geom::Graph<S>,
+}
+
+/// Begin drawing the given graph.
+pub fn new<'a, S>(draw: &'a Draw<S>, graph: &'a geom::Graph<S>) -> Graph<'a, S>
+where
+    S: BaseFloat,
+{
+    Graph { draw, graph }
+}
+
+impl<'a, S> Graph<'a, S>
+where
+    S: BaseFloat,
+{
+    /// Draw each node of the graph with the given function.
+    ///
+    /// `draw_node` is called once for each node in order of discovery within a
+    /// depth-first-search from the graph's origin. The given **Draw** instance is transformed by
+    /// the node's **Transform** so that any geometry drawn with it is positioned, oriented and
+    /// scaled relative to the node.
+    ///
+    /// Nested `Node::Graph`s may be drawn by calling `draw.graph(nested_graph)` with the given
+    /// **Draw** instance.
+    pub fn nodes<F>(self, mut draw_node: F)
+    where
+        F: FnMut(&Draw<S>, node::Index),
+    {
+        let mut dfs = node::Dfs::new(self.graph);
+        while let Some((n, transform)) = dfs.next_transform(self.graph) {
+            let draw = self.draw.transform(Matrix4::from(transform));
+            draw_node(&draw, n);
+        }
+    }
+
+    /// Draw the triangles produced for each node by `triangles_fn` as a single mesh.
+    ///
+    /// The triangles of each node are transformed by the node's **Transform**. The resulting
+    /// **Drawing** may be used to specify the color of the mesh.
+    pub fn triangles<F, I>(self, triangles_fn: F) -> DrawingMesh<'a, S>
+    where
+        F: Fn(&node::Index) -> I,
+        I: IntoIterator<Item = geom::Tri<Point3<S>>>,
+    {
+        let mut dfs = node::Dfs::new(self.graph);
+        let tris: Vec<_> = self.graph.triangles(&mut dfs, triangles_fn).collect();
+        self.draw.mesh().tris(tris)
+    }
+}
@@ -13,6 +13,7 @@ use std::rc::Rc;
 
 pub use self::background::Background;
 pub use self::drawing::{Drawing, DrawingContext};
+pub use self::graph::Graph;
 use self::mesh::vertex::Color;
 pub use self::mesh::Mesh;
 use self::primitive::Primitive;
@@ -21,6 +22,7 @@ pub use self::theme::Theme;
 
 pub mod background;
 mod drawing;
+pub mod graph;
 pub mod mesh;
 pub mod primitive;
 pub mod properties;
@@ -502,6 +504,11 @@ where
         background::new(self)
     }
 
+    /// Begin drawing the nodes of the given **geom::Graph**.
+    pub fn graph<'a>(&'a self, graph: &'a geom::Graph<S>) -> Graph<'a, S> {
+        graph::new(self, graph)
+    }
+
     /// Add the given type to be drawn.
     pub fn a<T>(&self, primitive: T) -> Drawing<T, S>
     where
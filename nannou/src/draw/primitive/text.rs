@@ -1,3 +1,4 @@
+use crate::color::conv::IntoLinSrgba;
 use crate::draw::drawing::DrawingContext;
 use crate::draw::primitive::Primitive;
 use crate::draw::properties::spatial::{self, dimension, orientation, position};
@@ -6,8 +7,10 @@ use crate::draw::properties::{
 };
 use crate::draw::{self, theme, Drawing};
 use crate::geom::{self, Vector2};
-use crate::math::{BaseFloat, Zero};
+use crate::math::{BaseFloat, Matrix4, Zero};
 use crate::text::{self, Align, Font, FontSize, Justify, Layout, Scalar, Wrap};
+use std::fmt;
+use std::rc::Rc;
 
 /// Properties related to drawing the **Text** primitive.
 #[derive(Clone, Debug)]
@@ -23,8 +26,58 @@ pub struct Text<S = geom::scalar::Default> {
 pub struct Style {
     pub color: Option<LinSrgba>,
     pub layout: text::layout::Builder,
+    pub spans: Vec<text::Span>,
+    pub on_path: Option<OnPath>,
+    pub glyph_transform: Option<GlyphTransformFn>,
+    pub outline: Option<Outline>,
+    pub glow: Option<Glow>,
 }
 
+/// Describes how text is laid along a path.
+///
+/// See `Text::transforms_on_path` for details.
+#[derive(Clone, Debug)]
+pub struct OnPath {
+    /// The measured path along which the text is laid.
+    pub path: geom::path::Measure,
+    /// The distance along the path at which the text begins.
+    pub offset: Scalar,
+    /// The alignment of the text within the remaining length of the path.
+    pub align: Align,
+}
+
+/// An outline drawn around the edge of each glyph.
+///
+/// Outlined text is always drawn from the renderer's distance field cache.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Outline {
+    /// The width of the outline beyond the edge of each glyph.
+    pub width: Scalar,
+    /// The color of the outline.
+    pub color: LinSrgba,
+}
+
+/// A glow that fades out from the edge of each glyph, or from the edge of its outline if it has
+/// one.
+///
+/// Glowing text is always drawn from the renderer's distance field cache.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Glow {
+    /// The distance over which the glow fades out.
+    pub radius: Scalar,
+    /// The color of the glow at the edge of the glyph.
+    pub color: LinSrgba,
+}
+
+/// A function that may modify the transform of each glyph before it is drawn.
+///
+/// The function is called with the index of the glyph, its layout information and its transform.
+#[derive(Clone)]
+pub struct GlyphTransformFn(pub Rc<dyn Fn(usize, &text::glyph::Info, &mut text::glyph::Transform)>);
+
+/// The number of samples used to measure each curve of a path along which text is laid.
+pub const DEFAULT_PATH_SAMPLES_PER_CURVE: usize = 32;
+
 /// The drawing context for the **Text** primitive.
 pub type DrawingText<'a, S = geom::scalar::Default> = Drawing<'a, Text<S>, S>;
 
@@ -94,6 +147,14 @@ impl<S> Text<S> {
         self.map_layout(|l| l.font(font))
     }
 
+    /// Specify the chain of fonts that are searched for characters missing from the `font`.
+    pub fn fallback_fonts<I>(self, fonts: I) -> Self
+    where
+        I: IntoIterator<Item = Font>,
+    {
+        self.map_layout(|l| l.fallback_fonts(fonts))
+    }
+
     /// Describe the end along the *x* axis to which the text should be aligned.
     pub fn justify(self, justify: Justify) -> Self {
         self.map_layout(|l| l.justify(justify))
@@ -114,6 +175,11 @@ impl<S> Text<S> {
         self.map_layout(|l| l.right_justify())
     }
 
+    /// Align wrapped lines to both ends of the bounding **Rect**'s *x* axis range.
+    pub fn full_justify(self) -> Self {
+        self.map_layout(|l| l.full_justify())
+    }
+
     /// Specify how much vertical space should separate each line of text.
     pub fn line_spacing(self, spacing: Scalar) -> Self {
         self.map_layout(|l| l.line_spacing(spacing))
@@ -146,6 +212,81 @@ impl<S> Text<S> {
         self.map_layout(|l| l.layout(layout))
     }
 
+    /// Apply the given style to the given byte range of the text.
+    ///
+    /// Where spans overlap, the style of the span that was added last takes precedence.
+    pub fn span(mut self, range: std::ops::Range<usize>, style: text::span::Style) -> Self {
+        self.style.spans.push(text::Span::new(range, style));
+        self
+    }
+
+    /// Lay the text along the given path.
+    ///
+    /// The path is described relative to the position of the text.
+    pub fn on_path(mut self, path: &geom::Path) -> Self {
+        let path = path.measure(DEFAULT_PATH_SAMPLES_PER_CURVE);
+        let (offset, align) = match self.style.on_path.take() {
+            Some(on_path) => (on_path.offset, on_path.align),
+            None => (0.0, Align::Start),
+        };
+        self.style.on_path = Some(OnPath {
+            path,
+            offset,
+            align,
+        });
+        self
+    }
+
+    /// The distance along the path at which the text begins.
+    ///
+    /// Has no effect unless the text is laid along a path via `on_path`.
+    pub fn path_offset(mut self, offset: Scalar) -> Self {
+        if let Some(ref mut on_path) = self.style.on_path {
+            on_path.offset = offset;
+        }
+        self
+    }
+
+    /// Align the text within the length of the path following the offset.
+    ///
+    /// Has no effect unless the text is laid along a path via `on_path`.
+    pub fn path_align(mut self, align: Align) -> Self {
+        if let Some(ref mut on_path) = self.style.on_path {
+            on_path.align = align;
+        }
+        self
+    }
+
+    /// Specify a function that may modify the position, rotation, scale and color of each glyph
+    /// before it is drawn.
+    pub fn glyph_transform<F>(mut self, f: F) -> Self
+    where
+        F: 'static + Fn(usize, &text::glyph::Info, &mut text::glyph::Transform),
+    {
+        self.style.glyph_transform = Some(GlyphTransformFn(Rc::new(f)));
+        self
+    }
+
+    /// Draw an outline of the given width and color around the edge of each glyph.
+    pub fn outline<C>(mut self, width: Scalar, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = color.into_lin_srgba();
+        self.style.outline = Some(Outline { width, color });
+        self
+    }
+
+    /// Draw a glow of the given radius and color around the edge of each glyph.
+    pub fn glow<C>(mut self, radius: Scalar, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = color.into_lin_srgba();
+        self.style.glow = Some(Glow { radius, color });
+        self
+    }
+
     /// Specify the entire styling for the **Text**.
     pub fn with_style(mut self, style: Style) -> Self {
         self.style = style;
@@ -182,6 +323,17 @@ where
         self.map_ty(|ty| ty.font(font))
     }
 
+    /// Specify the chain of fonts that are searched for characters missing from the `font`.
+    ///
+    /// Each character is displayed with the first font in the chain that contains a glyph for
+    /// it. See `text::font::Map::fallback_fonts`.
+    pub fn fallback_fonts<I>(self, fonts: I) -> Self
+    where
+        I: IntoIterator<Item = text::Font>,
+    {
+        self.map_ty(|ty| ty.fallback_fonts(fonts))
+    }
+
     /// Build the **Text** with the given **Style**.
     pub fn with_style(self, style: Style) -> Self {
         self.map_ty(|ty| ty.with_style(style))
@@ -207,6 +359,20 @@ where
         self.map_ty(|ty| ty.right_justify())
     }
 
+    /// Align wrapped lines to both ends of the bounding **Rect**'s *x* axis range.
+    ///
+    /// Extra space is added between words in order to achieve this alignment.
+    pub fn full_justify(self) -> Self {
+        self.map_ty(|ty| ty.full_justify())
+    }
+
+    /// Apply the given style to the given byte range of the text.
+    ///
+    /// This allows for changing the font, font size, color or baseline of parts of the text.
+    pub fn span(self, range: std::ops::Range<usize>, style: text::span::Style) -> Self {
+        self.map_ty(|ty| ty.span(range, style))
+    }
+
     /// Specify how much vertical space should separate each line of text.
     pub fn line_spacing(self, spacing: text::Scalar) -> Self {
         self.map_ty(|ty| ty.line_spacing(spacing))
@@ -238,6 +404,62 @@ where
     pub fn layout(self, layout: &Layout) -> Self {
         self.map_ty(|ty| ty.layout(layout))
     }
+
+    /// Lay the text along the given path, rotating each glyph to follow the path.
+    ///
+    /// The path is described relative to the position of the text. The baseline of the first line
+    /// follows the path while following lines are offset along the path's normal.
+    pub fn on_path(self, path: &geom::Path) -> Self {
+        self.map_ty(|ty| ty.on_path(path))
+    }
+
+    /// The distance along the path at which the text begins.
+    ///
+    /// Has no effect unless the text is laid along a path via `on_path`.
+    pub fn path_offset(self, offset: text::Scalar) -> Self {
+        self.map_ty(|ty| ty.path_offset(offset))
+    }
+
+    /// Align the text within the length of the path following the offset.
+    ///
+    /// Has no effect unless the text is laid along a path via `on_path`.
+    pub fn path_align(self, align: Align) -> Self {
+        self.map_ty(|ty| ty.path_align(align))
+    }
+
+    /// Specify a function that may modify the position, rotation, scale and color of each glyph
+    /// before it is drawn.
+    ///
+    /// The function is called with the index of each glyph, its layout information and the
+    /// transform that will be used to draw it. This is applied after laying the text along a path.
+    pub fn glyph_transform<F>(self, f: F) -> Self
+    where
+        F: 'static + Fn(usize, &text::glyph::Info, &mut text::glyph::Transform),
+    {
+        self.map_ty(|ty| ty.glyph_transform(f))
+    }
+
+    /// Draw an outline of the given width and color around the edge of each glyph.
+    ///
+    /// The outline cannot extend beyond the renderer's distance field range, which is described
+    /// in pixels at the renderer's distance field scale.
+    pub fn outline<C>(self, width: text::Scalar, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        self.map_ty(|ty| ty.outline(width, color))
+    }
+
+    /// Draw a glow of the given radius and color around the edge of each glyph.
+    ///
+    /// The glow fades out from the edge of the outline if there is one. Like the outline, the glow
+    /// cannot extend beyond the renderer's distance field range.
+    pub fn glow<C>(self, radius: text::Scalar, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        self.map_ty(|ty| ty.glow(radius, color))
+    }
 }
 
 impl draw::renderer::RenderPrimitive for Text<f32> {
@@ -251,7 +473,15 @@ impl draw::renderer::RenderPrimitive for Text<f32> {
             style,
             text,
         } = self;
-        let Style { color, layout } = style;
+        let Style {
+            color,
+            layout,
+            spans,
+            on_path,
+            glyph_transform,
+            outline,
+            glow,
+        } = style;
         let layout = layout.build();
         let (maybe_x, maybe_y, maybe_z) = (
             spatial.dimensions.x,
@@ -272,18 +502,54 @@ impl draw::renderer::RenderPrimitive for Text<f32> {
         let color = color.unwrap_or_else(|| ctxt.theme.fill_lin_srgba(&theme::Primitive::Text));
 
         let text_str = &ctxt.text_buffer[text.clone()];
-        let text = text::text(text_str).layout(&layout).build(rect);
+        let text = text::text(text_str)
+            .layout(&layout)
+            .spans(spans)
+            .build(rect);
+
+        // Determine the transform of each glyph.
+        let mut glyph_transforms = match on_path {
+            None => text.glyph_transforms(),
+            Some(OnPath {
+                ref path,
+                offset,
+                align,
+            }) => text.transforms_on_path(path, offset, align),
+        };
+        if let Some(GlyphTransformFn(f)) = glyph_transform {
+            let infos = text.glyph_infos().iter();
+            for (i, (info, transform)) in infos.zip(&mut glyph_transforms).enumerate() {
+                f(i, info, transform);
+            }
+        }
+
+        // Determine the transform to apply to all points.
+        let global_transform = ctxt.transform;
+        let local_transform = spatial.position.transform() * spatial.orientation.transform();
+        let transform = global_transform * local_transform;
+
+        // Text with effects is always drawn from a distance field.
+        let distance_field = match ctxt.glyph_mode {
+            draw::renderer::GlyphMode::Bitmap => outline.is_some() || glow.is_some(),
+            draw::renderer::GlyphMode::Sdf | draw::renderer::GlyphMode::Msdf => true,
+        };
+        if distance_field {
+            let glyphs = text.glyph_infos().iter().zip(&glyph_transforms);
+            return render_distance_field_glyphs(
+                ctxt, mesh, glyphs, &transform, color, outline, glow,
+            );
+        }
 
         // Queue the glyphs to be cached
-        let font_id = text::font::id(text.font());
         let positioned_glyphs: Vec<_> = text
             .rt_glyphs(
                 ctxt.output_attachment_size,
                 ctxt.output_attachment_scale_factor,
             )
             .collect();
-        for glyph in positioned_glyphs.iter() {
-            ctxt.glyph_cache.queue_glyph(font_id.index(), glyph.clone());
+        for (glyph, info) in positioned_glyphs.iter().zip(text.glyph_infos()) {
+            ctxt.glyph_cache
+                .queue_glyph(info.font_id.index(), glyph.clone());
         }
 
         // Cache the enqueued glyphs within the pixel buffer.
@@ -321,11 +587,6 @@ impl draw::renderer::RenderPrimitive for Text<f32> {
             }
         }
 
-        // Determine the transform to apply to all points.
-        let global_transform = ctxt.transform;
-        let local_transform = spatial.position.transform() * spatial.orientation.transform();
-        let transform = global_transform * local_transform;
-
         // A function for converting RustType rects to nannou rects.
         let scale_factor = ctxt.output_attachment_scale_factor;
         let (out_w, out_h) = ctxt.output_attachment_size.into();
@@ -339,47 +600,18 @@ impl draw::renderer::RenderPrimitive for Text<f32> {
         };
 
         // Extend the mesh with a rect for each displayed glyph.
-        for g in positioned_glyphs {
-            if let Ok(Some((uv_rect, screen_rect))) = ctxt.glyph_cache.rect_for(font_id.index(), &g)
+        let glyphs = positioned_glyphs
+            .iter()
+            .zip(text.glyph_infos())
+            .zip(&glyph_transforms);
+        for ((g, info), glyph_transform) in glyphs {
+            if let Ok(Some((uv_rect, screen_rect))) =
+                ctxt.glyph_cache.rect_for(info.font_id.index(), g)
             {
                 let rect = to_nannou_rect(screen_rect);
-
-                // Create a mesh-compatible vertex from the position and tex_coords.
-                let v = |position, tex_coords: [f32; 2]| -> draw::mesh::Vertex {
-                    let p = geom::Point3::from(position);
-                    let p = cgmath::Transform::transform_point(&transform, p.into());
-                    let point = draw::mesh::vertex::Point::from(p);
-                    draw::mesh::vertex::new(point, color, tex_coords.into())
-                };
-
-                // The sides of the UV rect.
-                let uv_l = uv_rect.min.x;
-                let uv_t = uv_rect.min.y;
-                let uv_r = uv_rect.max.x;
-                let uv_b = uv_rect.max.y;
-
-                // Insert the vertices.
-                let bottom_left = v(rect.bottom_left(), [uv_l, uv_b]);
-                let bottom_right = v(rect.bottom_right(), [uv_r, uv_b]);
-                let top_left = v(rect.top_left(), [uv_l, uv_t]);
-                let top_right = v(rect.top_right(), [uv_r, uv_t]);
-                let start_ix = mesh.points().len() as u32;
-                mesh.push_vertex(top_left);
-                mesh.push_vertex(bottom_left);
-                mesh.push_vertex(bottom_right);
-                mesh.push_vertex(top_right);
-
-                // Now the indices.
-                let tl_ix = start_ix;
-                let bl_ix = start_ix + 1;
-                let br_ix = start_ix + 2;
-                let tr_ix = start_ix + 3;
-                mesh.push_index(tl_ix);
-                mesh.push_index(bl_ix);
-                mesh.push_index(br_ix);
-                mesh.push_index(tl_ix);
-                mesh.push_index(br_ix);
-                mesh.push_index(tr_ix);
+                let color = glyph_transform.color.unwrap_or(color);
+                let corner = |p| glyph_transform.transform_point(info.position, p);
+                push_glyph_quad(mesh, &transform, corner, rect, uv_rect, color);
             }
         }
 
@@ -387,6 +619,122 @@ impl draw::renderer::RenderPrimitive for Text<f32> {
     }
 }
 
+// Draw each glyph from the renderer's distance field cache along with its outline and glow.
+fn render_distance_field_glyphs<'a, I>(
+    ctxt: draw::renderer::RenderContext,
+    mesh: &mut draw::Mesh,
+    glyphs: I,
+    transform: &Matrix4<f32>,
+    color: LinSrgba,
+    outline: Option<Outline>,
+    glow: Option<Glow>,
+) -> draw::renderer::PrimitiveRender
+where
+    I: Clone + Iterator<Item = (&'a text::glyph::Info, &'a text::glyph::Transform)>,
+{
+    let draw::renderer::RenderContext {
+        distance_field_cache: cache,
+        glyph_effects,
+        ..
+    } = ctxt;
+
+    // Cache the distance field of each glyph.
+    for (info, _) in glyphs.clone() {
+        cache.queue_glyph(info.font_id.index(), info.glyph.clone());
+    }
+    match cache.cache_queued() {
+        Ok(updated) => cache.requires_upload |= updated,
+        Err(err) => eprintln!("failed to cache queued glyphs: {}", err),
+    }
+
+    let rgba = |c: LinSrgba| [c.red, c.green, c.blue, c.alpha];
+    for (info, glyph_transform) in glyphs {
+        let entry = match cache.rect_for(info.font_id.index(), info.glyph.id()) {
+            None => continue,
+            Some(entry) => entry,
+        };
+
+        // Fields are generated at a fixed scale, so scale the bounds and effects to the glyph.
+        let scale = info.glyph.scale().y / cache.scale();
+        let b = entry.bounds;
+        let p = info.position;
+        let rect = geom::Rect::from_corners(
+            geom::pt2(p.x + b.left() * scale, p.y + b.bottom() * scale),
+            geom::pt2(p.x + b.right() * scale, p.y + b.top() * scale),
+        );
+        let effects = draw::renderer::GlyphEffects {
+            outline_color: outline.map(|o| rgba(o.color)).unwrap_or_default(),
+            glow_color: glow.map(|g| rgba(g.color)).unwrap_or_default(),
+            outline_width: outline.map(|o| o.width / scale).unwrap_or(0.0),
+            glow_radius: glow.map(|g| g.radius / scale).unwrap_or(0.0),
+            range: cache.range(),
+        };
+
+        let color = glyph_transform.color.unwrap_or(color);
+        let corner = |p| glyph_transform.transform_point(info.position, p);
+        push_glyph_quad(mesh, transform, corner, rect, entry.uv, color);
+        glyph_effects.extend((0..4).map(|_| effects));
+    }
+
+    let vertex_mode = match cache.field() {
+        text::sdf::Field::Sdf => draw::renderer::VertexMode::Sdf,
+        text::sdf::Field::Msdf => draw::renderer::VertexMode::Msdf,
+    };
+    draw::renderer::PrimitiveRender::vertex_mode(vertex_mode)
+}
+
+// Push the four vertices and six indices of a single textured glyph quad.
+//
+// Each corner of the `rect` is first mapped by `corner` before applying the `transform`.
+fn push_glyph_quad<F>(
+    mesh: &mut draw::Mesh,
+    transform: &Matrix4<f32>,
+    corner: F,
+    rect: geom::Rect,
+    uv_rect: text::rt::Rect<f32>,
+    color: LinSrgba,
+) where
+    F: Fn(geom::Point2) -> geom::Point2,
+{
+    // Create a mesh-compatible vertex from the position and tex_coords.
+    let v = |position, tex_coords: [f32; 2]| -> draw::mesh::Vertex {
+        let position = corner(position);
+        let p = geom::Point3::from(position);
+        let p = cgmath::Transform::transform_point(transform, p.into());
+        let point = draw::mesh::vertex::Point::from(p);
+        draw::mesh::vertex::new(point, color, tex_coords.into())
+    };
+
+    // The sides of the UV rect.
+    let uv_l = uv_rect.min.x;
+    let uv_t = uv_rect.min.y;
+    let uv_r = uv_rect.max.x;
+    let uv_b = uv_rect.max.y;
+
+    // Insert the vertices.
+    let bottom_left = v(rect.bottom_left(), [uv_l, uv_b]);
+    let bottom_right = v(rect.bottom_right(), [uv_r, uv_b]);
+    let top_left = v(rect.top_left(), [uv_l, uv_t]);
+    let top_right = v(rect.top_right(), [uv_r, uv_t]);
+    let start_ix = mesh.points().len() as u32;
+    mesh.push_vertex(top_left);
+    mesh.push_vertex(bottom_left);
+    mesh.push_vertex(bottom_right);
+    mesh.push_vertex(top_right);
+
+    // Now the indices.
+    let tl_ix = start_ix;
+    let bl_ix = start_ix + 1;
+    let br_ix = start_ix + 2;
+    let tr_ix = start_ix + 3;
+    mesh.push_index(tl_ix);
+    mesh.push_index(bl_ix);
+    mesh.push_index(br_ix);
+    mesh.push_index(tl_ix);
+    mesh.push_index(br_ix);
+    mesh.push_index(tr_ix);
+}
+
 impl<S> SetOrientation<S> for Text<S> {
     fn properties(&mut self) -> &mut orientation::Properties<S> {
         SetOrientation::properties(&mut self.spatial)
@@ -411,6 +759,12 @@ impl<S> SetColor<ColorScalar> for Text<S> {
     }
 }
 
+impl fmt::Debug for GlyphTransformFn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "GlyphTransformFn")
+    }
+}
+
 // Primitive conversions.
 
 impl<S> From<Text<S>> for Primitive<S> {
@@ -40,6 +40,9 @@ pub struct RenderContext<'a> {
     pub text_buffer: &'a str,
     pub theme: &'a draw::Theme,
     pub glyph_cache: &'a mut GlyphCache,
+    pub glyph_mode: GlyphMode,
+    pub distance_field_cache: &'a mut DistanceFieldCache,
+    pub glyph_effects: &'a mut Vec<GlyphEffects>,
     pub fill_tessellator: &'a mut FillTessellator,
     pub stroke_tessellator: &'a mut StrokeTessellator,
     pub output_attachment_size: Vector2, // logical coords
@@ -55,6 +58,52 @@ pub struct GlyphCache {
     pub requires_upload: bool,
 }
 
+/// A cache of glyph distance fields used by the text primitive.
+pub struct DistanceFieldCache {
+    /// Tracks glyphs and their location within the cache along with the cache's texels.
+    pub cache: text::sdf::Cache,
+    /// Will be set to `true` after the cache has been updated if the texture requires re-uploading.
+    pub requires_upload: bool,
+}
+
+/// The way in which the glyphs of the text primitive are rasterised.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum GlyphMode {
+    /// Glyphs are rasterised into the glyph cache at the scale at which they are displayed.
+    ///
+    /// This produces the most accurate results for small text with an identity transform.
+    Bitmap,
+    /// Glyphs are drawn from a single-channel signed distance field.
+    ///
+    /// Text remains crisp at any scale, rotation or zoom, though sharp corners are rounded when
+    /// glyphs are displayed much larger than the renderer's `distance_field_scale`.
+    Sdf,
+    /// Glyphs are drawn from a multi-channel signed distance field.
+    ///
+    /// Like `Sdf`, but preserves sharp corners at large scales at the cost of more expensive glyph
+    /// generation.
+    Msdf,
+}
+
+/// Per-vertex data describing the effects applied to distance field text.
+///
+/// All distances are described in texels of the distance field cache. Vertices of all other
+/// primitives use the `Default`, which disables all effects.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct GlyphEffects {
+    /// The color of the outline.
+    pub outline_color: [f32; 4],
+    /// The color of the glow.
+    pub glow_color: [f32; 4],
+    /// The width of the outline surrounding each glyph.
+    pub outline_width: f32,
+    /// The distance beyond each glyph (and its outline) over which the glow fades.
+    pub glow_radius: f32,
+    /// The range of the distance field, i.e. `text::sdf::Cache::range`.
+    pub range: f32,
+}
+
 /// A top-level indicator of whether or not
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 #[repr(u32)]
@@ -67,17 +116,29 @@ pub enum VertexMode {
     ///
     /// Uses the color values, but multiplies the alpha by the glyph cache texture's red value.
     Text = 2,
+    /// A special mode used by the text primitive when drawing from a single-channel distance
+    /// field.
+    ///
+    /// Uses the color values and the `GlyphEffects` of each vertex.
+    Sdf = 3,
+    /// A special mode used by the text primitive when drawing from a multi-channel distance field.
+    ///
+    /// Uses the color values and the `GlyphEffects` of each vertex.
+    Msdf = 4,
 }
 
 /// A helper type aimed at simplifying the rendering of conrod primitives via wgpu.
 #[derive(Debug)]
 pub struct Renderer {
     glyph_cache: GlyphCache,
+    glyph_mode: GlyphMode,
+    distance_field_cache: DistanceFieldCache,
     vs_mod: wgpu::ShaderModule,
     fs_mod: wgpu::ShaderModule,
     // One pipeline per unique Pipeline ID (combination of blend, topology and component type).
     pipelines: HashMap<PipelineId, wgpu::RenderPipeline>,
     glyph_cache_texture: wgpu::Texture,
+    distance_field_cache_texture: wgpu::Texture,
     depth_texture: wgpu::Texture,
     depth_texture_view: wgpu::TextureView,
     default_texture: wgpu::Texture,
@@ -95,6 +156,7 @@ pub struct Renderer {
     render_commands: Vec<RenderCommand>,
     mesh: draw::Mesh,
     vertex_mode_buffer: Vec<VertexMode>,
+    glyph_effects_buffer: Vec<GlyphEffects>,
     uniform_buffer: wgpu::Buffer,
 }
 
@@ -105,6 +167,10 @@ pub struct Builder {
     pub glyph_cache_size: [u32; 2],
     pub glyph_cache_scale_tolerance: f32,
     pub glyph_cache_position_tolerance: f32,
+    pub glyph_mode: GlyphMode,
+    pub distance_field_cache_size: [u32; 2],
+    pub distance_field_scale: f32,
+    pub distance_field_range: f32,
 }
 
 /// Commands that map to wgpu encodable commands.
@@ -202,6 +268,15 @@ impl fmt::Debug for GlyphCache {
     }
 }
 
+impl fmt::Debug for DistanceFieldCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DistanceFieldCache")
+            .field("cache", &self.cache)
+            .field("requires_upload", &self.requires_upload)
+            .finish()
+    }
+}
+
 impl PrimitiveRender {
     /// Specify a vertex mode for the primitive render.
     pub fn vertex_mode(vertex_mode: VertexMode) -> Self {
@@ -238,6 +313,15 @@ impl Builder {
     /// The default position tolerance for the glyph cache.
     pub const DEFAULT_GLYPH_CACHE_POSITION_TOLERANCE: f32 =
         Renderer::DEFAULT_GLYPH_CACHE_POSITION_TOLERANCE;
+    /// The default way in which glyphs are rasterised.
+    pub const DEFAULT_GLYPH_MODE: GlyphMode = Renderer::DEFAULT_GLYPH_MODE;
+    /// The default size for the inner distance field cache.
+    pub const DEFAULT_DISTANCE_FIELD_CACHE_SIZE: [u32; 2] =
+        Renderer::DEFAULT_DISTANCE_FIELD_CACHE_SIZE;
+    /// The default scale at which glyph distance fields are generated.
+    pub const DEFAULT_DISTANCE_FIELD_SCALE: f32 = Renderer::DEFAULT_DISTANCE_FIELD_SCALE;
+    /// The default range of glyph distance fields.
+    pub const DEFAULT_DISTANCE_FIELD_RANGE: f32 = Renderer::DEFAULT_DISTANCE_FIELD_RANGE;
 
     /// Begin building a new **draw::Renderer**.
     pub fn new() -> Self {
@@ -246,6 +330,10 @@ impl Builder {
             glyph_cache_size: Self::DEFAULT_GLYPH_CACHE_SIZE,
             glyph_cache_scale_tolerance: Self::DEFAULT_GLYPH_CACHE_SCALE_TOLERANCE,
             glyph_cache_position_tolerance: Self::DEFAULT_GLYPH_CACHE_POSITION_TOLERANCE,
+            glyph_mode: Self::DEFAULT_GLYPH_MODE,
+            distance_field_cache_size: Self::DEFAULT_DISTANCE_FIELD_CACHE_SIZE,
+            distance_field_scale: Self::DEFAULT_DISTANCE_FIELD_SCALE,
+            distance_field_range: Self::DEFAULT_DISTANCE_FIELD_RANGE,
         }
     }
 
@@ -285,6 +373,42 @@ impl Builder {
         self
     }
 
+    /// Specify the way in which the glyphs of all text are rasterised.
+    ///
+    /// By default, glyphs are rasterised into the glyph cache at the scale at which they are
+    /// displayed. Scaled, rotated or zoomed text may appear blurry or cause the cache to be
+    /// frequently rebuilt. `GlyphMode::Sdf` and `GlyphMode::Msdf` instead draw glyphs from a
+    /// distance field that remains crisp at any transform.
+    ///
+    /// Text with an outline or glow is always drawn from a distance field.
+    pub fn glyph_mode(mut self, mode: GlyphMode) -> Self {
+        self.glyph_mode = mode;
+        self
+    }
+
+    /// The dimensions of the texture used to cache glyph distance fields.
+    pub fn distance_field_cache_size(mut self, size: [u32; 2]) -> Self {
+        self.distance_field_cache_size = size;
+        self
+    }
+
+    /// The scale in pixels per em at which glyph distance fields are generated.
+    ///
+    /// Larger scales preserve more detail at the cost of more space within the cache.
+    pub fn distance_field_scale(mut self, scale: f32) -> Self {
+        self.distance_field_scale = scale;
+        self
+    }
+
+    /// The distance in pixels at the `distance_field_scale` on either side of each glyph's
+    /// outline that is described by its distance field.
+    ///
+    /// Outlines and glows cannot extend further than this distance from the glyph.
+    pub fn distance_field_range(mut self, range: f32) -> Self {
+        self.distance_field_range = range;
+        self
+    }
+
     /// Build the **draw::Renderer** ready to target an output attachment of the given descriptor.
     pub fn build_from_texture_descriptor(
         self,
@@ -311,16 +435,13 @@ impl Builder {
         sample_count: u32,
         output_color_format: wgpu::TextureFormat,
     ) -> Renderer {
-        Renderer::new(
+        Renderer::from_builder(
+            self,
             device,
             output_attachment_size,
             output_scale_factor,
             sample_count,
             output_color_format,
-            self.depth_format,
-            self.glyph_cache_size,
-            self.glyph_cache_scale_tolerance,
-            self.glyph_cache_position_tolerance,
         )
     }
 }
@@ -344,6 +465,21 @@ impl GlyphCache {
     }
 }
 
+impl DistanceFieldCache {
+    fn new(size: [u32; 2], mode: GlyphMode, scale: f32, range: f32) -> Self {
+        let field = match mode {
+            GlyphMode::Msdf => text::sdf::Field::Msdf,
+            GlyphMode::Bitmap | GlyphMode::Sdf => text::sdf::Field::Sdf,
+        };
+        let cache = text::sdf::Cache::new(size, field, scale, range);
+        let requires_upload = false;
+        DistanceFieldCache {
+            cache,
+            requires_upload,
+        }
+    }
+}
+
 impl Renderer {
     /// The default depth format
     pub const DEFAULT_DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
@@ -355,6 +491,17 @@ impl Renderer {
     pub const DEFAULT_GLYPH_CACHE_POSITION_TOLERANCE: f32 = 0.1;
     /// The texture format of the inner glyph cache.
     pub const GLYPH_CACHE_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
+    /// The default way in which glyphs are rasterised.
+    pub const DEFAULT_GLYPH_MODE: GlyphMode = GlyphMode::Bitmap;
+    /// The default size for the inner distance field cache.
+    pub const DEFAULT_DISTANCE_FIELD_CACHE_SIZE: [u32; 2] = [1024; 2];
+    /// The default scale at which glyph distance fields are generated.
+    pub const DEFAULT_DISTANCE_FIELD_SCALE: f32 = text::sdf::Cache::DEFAULT_SCALE;
+    /// The default range of glyph distance fields.
+    pub const DEFAULT_DISTANCE_FIELD_RANGE: f32 = text::sdf::Cache::DEFAULT_RANGE;
+    /// The texture format of the inner distance field cache.
+    pub const DISTANCE_FIELD_CACHE_TEXTURE_FORMAT: wgpu::TextureFormat =
+        wgpu::TextureFormat::Rgba8Unorm;
 
     /// Create a new **Renderer**, ready to target an output attachment with the given size, sample
     /// count and color format.
@@ -366,6 +513,9 @@ impl Renderer {
     ///
     /// The `glyph_cache_size` will be used to create a texture on which glyphs will be stored for
     /// efficient look-up.
+    ///
+    /// Glyphs are rasterised via the `DEFAULT_GLYPH_MODE`. See the **RendererBuilder** for drawing
+    /// glyphs from distance fields.
     pub fn new(
         device: &wgpu::Device,
         output_attachment_size: [u32; 2],
@@ -377,12 +527,55 @@ impl Renderer {
         glyph_cache_scale_tolerance: f32,
         glyph_cache_position_tolerance: f32,
     ) -> Self {
+        let builder = Builder {
+            depth_format,
+            glyph_cache_size,
+            glyph_cache_scale_tolerance,
+            glyph_cache_position_tolerance,
+            ..Builder::new()
+        };
+        Self::from_builder(
+            builder,
+            device,
+            output_attachment_size,
+            output_scale_factor,
+            sample_count,
+            output_color_format,
+        )
+    }
+
+    // Create a new **Renderer** described by the given builder.
+    fn from_builder(
+        builder: Builder,
+        device: &wgpu::Device,
+        output_attachment_size: [u32; 2],
+        output_scale_factor: f32,
+        sample_count: u32,
+        output_color_format: wgpu::TextureFormat,
+    ) -> Self {
+        let Builder {
+            depth_format,
+            glyph_cache_size,
+            glyph_cache_scale_tolerance,
+            glyph_cache_position_tolerance,
+            glyph_mode,
+            distance_field_cache_size,
+            distance_field_scale,
+            distance_field_range,
+        } = builder;
+
         // Construct the glyph cache.
         let glyph_cache = GlyphCache::new(
             glyph_cache_size,
             glyph_cache_scale_tolerance,
             glyph_cache_position_tolerance,
         );
+        let distance_field_cache = DistanceFieldCache::new(
+            distance_field_cache_size,
+            glyph_mode,
+            distance_field_scale,
+            distance_field_range,
+        );
 
         // Load shader modules.
         let vs_mod = wgpu::shader_from_spirv_bytes(device, include_bytes!("shaders/vert.spv"));
@@ -397,6 +590,14 @@ impl Renderer {
             .build(device);
         let glyph_cache_texture_view = glyph_cache_texture.create_default_view();
 
+        // Create the distance field cache texture.
+        let distance_field_cache_texture = wgpu::TextureBuilder::new()
+            .size(distance_field_cache_size)
+            .usage(wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST)
+            .format(Self::DISTANCE_FIELD_CACHE_TEXTURE_FORMAT)
+            .build(device);
+        let distance_field_cache_texture_view = distance_field_cache_texture.create_default_view();
+
         // Create the depth texture.
         let depth_texture =
             create_depth_texture(device, output_attachment_size, depth_format, sample_count);
@@ -427,6 +628,7 @@ impl Renderer {
             &text_bind_group_layout,
             &text_sampler,
             &glyph_cache_texture_view,
+            &distance_field_cache_texture_view,
         );
 
         // Initialise the sampler set with the default sampler.
@@ -445,12 +647,16 @@ impl Renderer {
         let render_commands = vec![];
         let mesh = Default::default();
         let vertex_mode_buffer = vec![];
+        let glyph_effects_buffer = vec![];
 
         Self {
             vs_mod,
             fs_mod,
             glyph_cache,
+            glyph_mode,
+            distance_field_cache,
             glyph_cache_texture,
+            distance_field_cache_texture,
             depth_texture,
             depth_texture_view,
             default_texture,
@@ -469,6 +675,7 @@ impl Renderer {
             render_commands,
             mesh,
             vertex_mode_buffer,
+            glyph_effects_buffer,
             uniform_buffer,
         }
     }
@@ -478,6 +685,7 @@ impl Renderer {
         self.render_commands.clear();
         self.mesh.clear();
         self.vertex_mode_buffer.clear();
+        self.glyph_effects_buffer.clear();
     }
 
     /// Generate a list of `RenderCommand`s from the given **Draw** instance and prepare any
@@ -568,6 +776,9 @@ impl Renderer {
                         fill_tessellator: &mut fill_tessellator,
                         stroke_tessellator: &mut stroke_tessellator,
                         glyph_cache: &mut self.glyph_cache,
+                        glyph_mode: self.glyph_mode,
+                        distance_field_cache: &mut self.distance_field_cache,
+                        glyph_effects: &mut self.glyph_effects_buffer,
                         output_attachment_size: Vector2::new(px_to_pt(w_px), px_to_pt(h_px)),
                         output_attachment_scale_factor: scale_factor,
                     };
@@ -677,6 +888,11 @@ impl Renderer {
                     let mode = render.vertex_mode;
                     let new_vs = self.mesh.points().len() - self.vertex_mode_buffer.len();
                     self.vertex_mode_buffer.extend((0..new_vs).map(|_| mode));
+
+                    // Extend the glyph effects channel for primitives that do not specify effects.
+                    let vertex_count = self.mesh.points().len();
+                    self.glyph_effects_buffer
+                        .resize(vertex_count, GlyphEffects::default());
                 }
             }
         }
@@ -770,6 +986,8 @@ impl Renderer {
             ref pipelines,
             ref glyph_cache,
             ref glyph_cache_texture,
+            ref mut distance_field_cache,
+            ref distance_field_cache_texture,
             ref mut depth_texture,
             ref mut depth_texture_view,
             ref uniform_bind_group,
@@ -777,6 +995,7 @@ impl Renderer {
             ref texture_bind_groups,
             ref mesh,
             ref vertex_mode_buffer,
+            ref glyph_effects_buffer,
             ref mut render_commands,
             ref uniform_buffer,
             scale_factor: ref mut old_scale_factor,
@@ -787,6 +1006,11 @@ impl Renderer {
         if glyph_cache.requires_upload {
             glyph_cache_texture.upload_data(device, encoder, &glyph_cache.pixel_buffer);
         }
+        if distance_field_cache.requires_upload {
+            let pixels = distance_field_cache.pixels();
+            distance_field_cache_texture.upload_data(device, encoder, pixels);
+            distance_field_cache.requires_upload = false;
+        }
 
         // Resize the depth texture if the output attachment size has changed.
         let depth_size = depth_texture.size();
@@ -833,11 +1057,13 @@ impl Renderer {
         let colors_bytes = colors_as_bytes(mesh.colors());
         let tex_coords_bytes = tex_coords_as_bytes(mesh.tex_coords());
         let modes_bytes = vertex_modes_as_bytes(vertex_mode_buffer);
+        let effects_bytes = glyph_effects_as_bytes(glyph_effects_buffer);
         let indices_bytes = indices_as_bytes(mesh.indices());
         let point_buffer = device.create_buffer_with_data(points_bytes, vertex_usage);
         let color_buffer = device.create_buffer_with_data(colors_bytes, vertex_usage);
         let tex_coords_buffer = device.create_buffer_with_data(tex_coords_bytes, vertex_usage);
         let mode_buffer = device.create_buffer_with_data(modes_bytes, vertex_usage);
+        let effects_buffer = device.create_buffer_with_data(effects_bytes, vertex_usage);
         let index_buffer = device.create_buffer_with_data(indices_bytes, wgpu::BufferUsage::INDEX);
 
         // If the scale factor or window size has changed, update the uniforms for vertex scaling.
@@ -862,6 +1088,7 @@ impl Renderer {
         render_pass.set_vertex_buffer(1, &color_buffer, 0, 0);
         render_pass.set_vertex_buffer(2, &tex_coords_buffer, 0, 0);
         render_pass.set_vertex_buffer(3, &mode_buffer, 0, 0);
+        render_pass.set_vertex_buffer(4, &effects_buffer, 0, 0);
 
         // Set the uniform and text bind groups here.
         render_pass.set_bind_group(0, uniform_bind_group, &[]);
@@ -969,6 +1196,19 @@ impl DerefMut for GlyphCache {
     }
 }
 
+impl Deref for DistanceFieldCache {
+    type Target = text::sdf::Cache;
+    fn deref(&self) -> &Self::Target {
+        &self.cache
+    }
+}
+
+impl DerefMut for DistanceFieldCache {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.cache
+    }
+}
+
 fn create_depth_texture(
     device: &wgpu::Device,
     size: [u32; 2],
@@ -1015,6 +1255,12 @@ fn create_text_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout
             wgpu::TextureViewDimension::D2,
             wgpu::texture_format_to_component_type(Renderer::GLYPH_CACHE_TEXTURE_FORMAT),
         )
+        .sampled_texture(
+            wgpu::ShaderStage::FRAGMENT,
+            false,
+            wgpu::TextureViewDimension::D2,
+            wgpu::texture_format_to_component_type(Renderer::DISTANCE_FIELD_CACHE_TEXTURE_FORMAT),
+        )
         .build(device)
 }
 
@@ -1048,10 +1294,12 @@ fn create_text_bind_group(
     layout: &wgpu::BindGroupLayout,
     sampler: &wgpu::Sampler,
     glyph_cache_texture_view: &wgpu::TextureViewHandle,
+    distance_field_cache_texture_view: &wgpu::TextureViewHandle,
 ) -> wgpu::BindGroup {
     wgpu::BindGroupBuilder::new()
         .sampler(sampler)
         .texture_view(glyph_cache_texture_view)
+        .texture_view(distance_field_cache_texture_view)
         .build(device, layout)
 }
 
@@ -1089,6 +1337,11 @@ fn create_render_pipeline(
         .add_vertex_buffer::<draw::mesh::vertex::Color>(&wgpu::vertex_attr_array![1 => Float4])
         .add_vertex_buffer::<draw::mesh::vertex::TexCoords>(&wgpu::vertex_attr_array![2 => Float2])
         .add_vertex_buffer::<VertexMode>(&wgpu::vertex_attr_array![3 => Uint])
+        .add_vertex_buffer::<GlyphEffects>(&wgpu::vertex_attr_array![
+            4 => Float4,
+            5 => Float4,
+            6 => Float3
+        ])
         .depth_format(depth_format)
         .sample_count(sample_count)
         .color_blend(color_blend)
@@ -1141,6 +1394,10 @@ fn vertex_modes_as_bytes(data: &[VertexMode]) -> &[u8] {
     unsafe { wgpu::bytes::from_slice(data) }
 }
 
+fn glyph_effects_as_bytes(data: &[GlyphEffects]) -> &[u8] {
+    unsafe { wgpu::bytes::from_slice(data) }
+}
+
 fn indices_as_bytes(data: &[u32]) -> &[u8] {
     unsafe { wgpu::bytes::from_slice(data) }
 }
@@ -8,15 +8,62 @@
 
 layout(set = 1, binding = 0) uniform sampler text_sampler;
 layout(set = 1, binding = 1) uniform texture2D text;
+layout(set = 1, binding = 2) uniform texture2D distance_field;
 layout(set = 2, binding = 0) uniform sampler tex_sampler;
 layout(set = 2, binding = 1) uniform texture2D tex;
 
 layout(location = 0) in vec4 v_color;
 layout(location = 1) in vec2 v_tex_coords;
 layout(location = 2) flat in uint v_mode;
+layout(location = 3) flat in vec4 v_outline_color;
+layout(location = 4) flat in vec4 v_glow_color;
+// The outline width, glow radius and distance field range in distance field texels.
+layout(location = 5) flat in vec3 v_effects;
 
 layout(location = 0) out vec4 f_color;
 
+float median(float r, float g, float b) {
+    return max(min(r, g), min(max(r, g), b));
+}
+
+// Composite `src` over `dst`.
+vec4 over(vec4 src, vec4 dst) {
+    float a = src.a + dst.a * (1.0 - src.a);
+    vec3 rgb = (src.rgb * src.a + dst.rgb * dst.a * (1.0 - src.a)) / max(a, 0.0001);
+    return vec4(rgb, a);
+}
+
+// Text rendered from a signed distance field, along with its outline and glow.
+vec4 distance_field_text(bool multi_channel) {
+    vec4 s = texture(sampler2D(distance_field, text_sampler), v_tex_coords);
+    float outline_width = v_effects.x;
+    float glow_radius = v_effects.y;
+    float range = v_effects.z;
+
+    // Signed distances to the outline in distance field texels, positive inside the glyph.
+    float sd = multi_channel ? median(s.r, s.g, s.b) : s.a;
+    float dist = (sd - 0.5) * 2.0 * range;
+    float true_dist = (s.a - 0.5) * 2.0 * range;
+
+    // The number of distance field texels covered by a single screen pixel, for antialiasing.
+    vec2 texels = vec2(textureSize(sampler2D(distance_field, text_sampler), 0));
+    vec2 texels_per_px = fwidth(v_tex_coords) * texels;
+    float aa = max(0.5 * (texels_per_px.x + texels_per_px.y), 0.0001);
+
+    float fill = clamp(dist / aa + 0.5, 0.0, 1.0);
+    vec4 color = vec4(v_color.rgb, v_color.a * fill);
+    if (outline_width > 0.0) {
+        float outline = clamp((dist + outline_width) / aa + 0.5, 0.0, 1.0);
+        color = over(color, vec4(v_outline_color.rgb, v_outline_color.a * outline));
+    }
+    if (glow_radius > 0.0) {
+        float edge = outline_width > 0.0 ? outline_width : 0.0;
+        float glow = clamp(1.0 + (true_dist + edge) / glow_radius, 0.0, 1.0);
+        color = over(color, vec4(v_glow_color.rgb, v_glow_color.a * glow * glow));
+    }
+    return color;
+}
+
 void main() {
     // Color
     if (v_mode == uint(0)) {
@@ -31,6 +78,14 @@ void main() {
         float tex_a = texture(sampler2D(text, text_sampler), v_tex_coords).r;
         f_color = vec4(v_color.rgb, v_color.a * tex_a);
 
+    // Single-channel distance field text
+    } else if (v_mode == uint(3)) {
+        f_color = distance_field_text(false);
+
+    // Multi-channel distance field text
+    } else if (v_mode == uint(4)) {
+        f_color = distance_field_text(true);
+
     // Unhandled mode - Indicate error with red.
     } else {
         f_color = vec4(1.0, 0.0, 0.0, 1.0);
@@ -14,14 +14,23 @@ layout(location = 0) in vec3 position;
 layout(location = 1) in vec4 color;
 layout(location = 2) in vec2 tex_coords;
 layout(location = 3) in uint mode;
+layout(location = 4) in vec4 outline_color;
+layout(location = 5) in vec4 glow_color;
+layout(location = 6) in vec3 effects;
 
 layout(location = 0) out vec4 v_color;
 layout(location = 1) out vec2 v_tex_coords;
 layout(location = 2) flat out uint v_mode;
+layout(location = 3) flat out vec4 v_outline_color;
+layout(location = 4) flat out vec4 v_glow_color;
+layout(location = 5) flat out vec3 v_effects;
 
 void main() {
     gl_Position = uniforms.proj * vec4(position, 1.0);
     v_color = color;
     v_tex_coords = tex_coords;
     v_mode = mode;
+    v_outline_color = outline_color;
+    v_glow_color = glow_color;
+    v_effects = effects;
 }
@@ -9,6 +9,7 @@
 use crate::geom::{self, Point2, Vector2};
 use crate::window;
 use crate::App;
+use std::any::Any;
 use std::path::PathBuf;
 use winit;
 
@@ -21,6 +22,13 @@ pub use winit::event::{
 pub trait LoopEvent: 'static + From<Update> {
     /// Produce a loop event from the given winit event.
     fn from_winit_event<'a, T>(_: &winit::event::Event<'a, T>, _: &App) -> Option<Self>;
+
+    /// Produce a loop event from a custom event sent to the app via an **app::Proxy**.
+    ///
+    /// By default, custom events are ignored.
+    fn from_user_event(_: UserEvent, _: &App) -> Option<Self> {
+        None
+    }
 }
 
 /// Update event, emitted on each pass of an application loop.
@@ -64,8 +72,16 @@ pub enum Event {
     Suspended,
     /// The application has been awakened.
     Resumed,
+
+    /// A custom event sent to the app via **app::Proxy::send_event**, e.g. from another thread.
+    User(UserEvent),
 }
 
+/// A custom event of any type sent to the app loop via an **app::Proxy**.
+///
+/// Use `downcast_ref` or `downcast` to retrieve the original value.
+pub struct UserEvent(Box<dyn Any + Send>);
+
 /// The event associated with a touch at a single point.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct TouchEvent {
@@ -344,6 +360,59 @@ impl LoopEvent for Event {
         };
         Some(event)
     }
+
+    /// Convert the given custom event to a nannou `Event`.
+    fn from_user_event(event: UserEvent, _: &App) -> Option<Self> {
+        Some(Event::User(event))
+    }
+}
+
+impl UserEvent {
+    /// Wrap the given value so that it may be sent to the app loop.
+    pub fn new<T>(event: T) -> Self
+    where
+        T: Any + Send,
+    {
+        UserEvent(Box::new(event))
+    }
+
+    /// Whether or not the event is of type `T`.
+    pub fn is<T>(&self) -> bool
+    where
+        T: Any,
+    {
+        self.0.is::<T>()
+    }
+
+    /// A reference to the event if it is of type `T`.
+    pub fn downcast_ref<T>(&self) -> Option<&T>
+    where
+        T: Any,
+    {
+        self.0.downcast_ref::<T>()
+    }
+
+    /// A mutable reference to the event if it is of type `T`.
+    pub fn downcast_mut<T>(&mut self) -> Option<&mut T>
+    where
+        T: Any,
+    {
+        self.0.downcast_mut::<T>()
+    }
+
+    /// Take ownership of the event if it is of type `T`, otherwise return it unchanged.
+    pub fn downcast<T>(self) -> Result<T, Self>
+    where
+        T: Any,
+    {
+        self.0.downcast::<T>().map(|t| *t).map_err(UserEvent)
+    }
+}
+
+impl std::fmt::Debug for UserEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_tuple("UserEvent").finish()
+    }
 }
 
 impl From<Update> for Event {
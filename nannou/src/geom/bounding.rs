@@ -0,0 +1,265 @@
+//! Items related to the minimum bounding shapes of a set of 2D vertices.
+//!
+//! See the `bounding_rect` and `bounding_cuboid` functions within the `geom` module for
+//! axis-aligned bounds.
+
+use crate::geom::{hull, scalar, Ellipse, Point2, Quad, Rect, Vector2, Vertex, Vertex2d};
+use crate::math::num_traits::{cast, Float, Zero};
+use crate::math::BaseFloat;
+
+/// A circle described by its centre and radius.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Circle<S = scalar::Default> {
+    /// The position of the centre of the circle.
+    pub center: Point2<S>,
+    /// The distance from the centre to the edge of the circle.
+    pub radius: S,
+}
+
+/// A rectangle that may be rotated about its centre.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OrientedRect<S = scalar::Default> {
+    /// The position of the centre of the rectangle.
+    pub center: Point2<S>,
+    /// The width and height of the rectangle prior to rotation.
+    pub w_h: Vector2<S>,
+    /// The counter-clockwise rotation of the rectangle about its centre in radians.
+    pub radians: S,
+}
+
+impl<S> Circle<S>
+where
+    S: BaseFloat,
+{
+    /// Construct a circle from its centre and radius.
+    pub fn new(center: Point2<S>, radius: S) -> Self {
+        Circle { center, radius }
+    }
+
+    /// Whether or not the given point lies within the circle.
+    pub fn contains(&self, p: Point2<S>) -> bool {
+        (p - self.center).magnitude2() <= self.radius * self.radius
+    }
+
+    /// The area of the circle.
+    pub fn area(&self) -> S {
+        pi::<S>() * self.radius * self.radius
+    }
+
+    /// The circumference of the circle.
+    pub fn circumference(&self) -> S {
+        cast::<_, S>(2.0).unwrap() * pi::<S>() * self.radius
+    }
+
+    /// The `Rect` that bounds the circle.
+    pub fn bounding_rect(&self) -> Rect<S> {
+        let d = self.radius + self.radius;
+        Rect::from_xy_wh(self.center, Vector2 { x: d, y: d })
+    }
+
+    /// Describe the circle as an `Ellipse` with the given resolution.
+    pub fn ellipse(&self, resolution: usize) -> Ellipse<S> {
+        Ellipse::new(self.bounding_rect(), resolution)
+    }
+}
+
+impl<S> OrientedRect<S>
+where
+    S: BaseFloat,
+{
+    /// The axis-aligned `Rect` with the same centre and dimensions, prior to rotation.
+    pub fn rect(&self) -> Rect<S> {
+        Rect::from_xy_wh(self.center, self.w_h)
+    }
+
+    /// The area of the rectangle.
+    pub fn area(&self) -> S {
+        self.w_h.x * self.w_h.y
+    }
+
+    /// The four corners of the rotated rectangle.
+    ///
+    /// The corners are yielded in the same order as `Rect::corners`, i.e. starting from the
+    /// top-left corner prior to rotation and continuing clockwise.
+    pub fn corners(&self) -> Quad<Point2<S>> {
+        let rect = Rect::from_xy_wh(
+            Point2 {
+                x: S::zero(),
+                y: S::zero(),
+            },
+            self.w_h,
+        );
+        let center = self.center;
+        let radians = self.radians;
+        rect.corners().map_vertices(|p| center + p.rotate(radians))
+    }
+
+    /// Whether or not the given point lies within the rotated rectangle.
+    pub fn contains(&self, p: Point2<S>) -> bool {
+        let local = (p - self.center).rotate(-self.radians);
+        let half = self.w_h / cast::<_, S>(2.0).unwrap();
+        local.x.abs() <= half.x && local.y.abs() <= half.y
+    }
+}
+
+/// The smallest circle that contains all of the given vertices.
+///
+/// Returns `None` if the given iterator is empty.
+///
+/// The circle is found using Welzl's algorithm over the convex hull of the vertices.
+pub fn circle<I>(vertices: I) -> Option<Circle<<I::Item as Vertex>::Scalar>>
+where
+    I: IntoIterator,
+    I::Item: Vertex2d,
+    <I::Item as Vertex>::Scalar: BaseFloat,
+{
+    let points: Vec<_> = hull::convex_hull(vertices)
+        .into_iter()
+        .map(Vertex2d::point2)
+        .collect();
+    let first = *points.first()?;
+    let mut c = Circle::new(first, <I::Item as Vertex>::Scalar::zero());
+    for i in 1..points.len() {
+        if contains_approx(&c, points[i]) {
+            continue;
+        }
+        c = Circle::new(points[i], <I::Item as Vertex>::Scalar::zero());
+        for j in 0..i {
+            if contains_approx(&c, points[j]) {
+                continue;
+            }
+            c = circle_from_2(points[i], points[j]);
+            for k in 0..j {
+                if contains_approx(&c, points[k]) {
+                    continue;
+                }
+                c = circle_from_3(points[i], points[j], points[k]);
+            }
+        }
+    }
+    Some(c)
+}
+
+/// The rectangle of minimum area that contains all of the given vertices.
+///
+/// Returns `None` if the given iterator is empty.
+///
+/// The minimum area rectangle always has one edge collinear with an edge of the convex hull of the
+/// vertices. Each edge of the hull is tested in turn.
+pub fn oriented_rect<I>(vertices: I) -> Option<OrientedRect<<I::Item as Vertex>::Scalar>>
+where
+    I: IntoIterator,
+    I::Item: Vertex2d,
+    <I::Item as Vertex>::Scalar: BaseFloat,
+{
+    let points: Vec<_> = hull::convex_hull(vertices)
+        .into_iter()
+        .map(Vertex2d::point2)
+        .collect();
+    let first = *points.first()?;
+    let zero = <I::Item as Vertex>::Scalar::zero();
+    let two = cast::<_, <I::Item as Vertex>::Scalar>(2.0).unwrap();
+    let mut best = OrientedRect {
+        center: first,
+        w_h: Vector2 { x: zero, y: zero },
+        radians: zero,
+    };
+    let mut best_area = None;
+    for (i, &a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        let dir = (b - a).normalize();
+        if dir.is_zero() {
+            continue;
+        }
+        let perp = Vector2 {
+            x: -dir.y,
+            y: dir.x,
+        };
+        let first_u = first.dot(dir);
+        let first_v = first.dot(perp);
+        let init = (first_u, first_u, first_v, first_v);
+        let (min_u, max_u, min_v, max_v) =
+            points
+                .iter()
+                .fold(init, |(min_u, max_u, min_v, max_v), &p| {
+                    let (u, v) = (p.dot(dir), p.dot(perp));
+                    (min_u.min(u), max_u.max(u), min_v.min(v), max_v.max(v))
+                });
+        let w_h = Vector2 {
+            x: max_u - min_u,
+            y: max_v - min_v,
+        };
+        let area = w_h.x * w_h.y;
+        if best_area.map(|best| area < best).unwrap_or(true) {
+            let center_u = (min_u + max_u) / two;
+            let center_v = (min_v + max_v) / two;
+            best_area = Some(area);
+            best = OrientedRect {
+                center: dir * center_u + perp * center_v,
+                w_h,
+                radians: dir.angle(),
+            };
+        }
+    }
+    Some(best)
+}
+
+// PI as the given scalar type.
+fn pi<S>() -> S
+where
+    S: BaseFloat,
+{
+    cast(std::f64::consts::PI).unwrap()
+}
+
+// Whether or not the circle contains the point, allowing for some floating point error.
+fn contains_approx<S>(c: &Circle<S>, p: Point2<S>) -> bool
+where
+    S: BaseFloat,
+{
+    let tolerance = cast::<_, S>(1e-5).unwrap();
+    let r = c.radius + tolerance * (S::one() + c.radius);
+    (p - c.center).magnitude2() <= r * r
+}
+
+// The smallest circle passing through both points.
+fn circle_from_2<S>(a: Point2<S>, b: Point2<S>) -> Circle<S>
+where
+    S: BaseFloat,
+{
+    let two = cast::<_, S>(2.0).unwrap();
+    let center = (a + b) / two;
+    Circle::new(center, (a - center).magnitude())
+}
+
+// The circle passing through all three points.
+//
+// Falls back to the circle through the two most distant points if the points are collinear.
+fn circle_from_3<S>(a: Point2<S>, b: Point2<S>, c: Point2<S>) -> Circle<S>
+where
+    S: BaseFloat,
+{
+    let two = cast::<_, S>(2.0).unwrap();
+    let (ab, ac) = (b - a, c - a);
+    let d = two * ab.perp_dot(ac);
+    if d == S::zero() {
+        let pairs = [(a, b), (a, c), (b, c)];
+        let (p, q) = pairs
+            .iter()
+            .cloned()
+            .max_by(|x, y| {
+                let dx = (x.1 - x.0).magnitude2();
+                let dy = (y.1 - y.0).magnitude2();
+                dx.partial_cmp(&dy).unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap();
+        return circle_from_2(p, q);
+    }
+    let ab2 = ab.magnitude2();
+    let ac2 = ac.magnitude2();
+    let offset = Vector2 {
+        x: (ac.y * ab2 - ab.y * ac2) / d,
+        y: (ab.x * ac2 - ac.x * ab2) / d,
+    };
+    Circle::new(a + offset, offset.magnitude())
+}
@@ -0,0 +1,649 @@
+//! Items related to evaluating, measuring and sampling parametric curves.
+//!
+//! The **Curve** trait describes any curve that may be evaluated at some parameter `t` within the
+//! range `0.0..=1.0`. Implementations are provided for the **QuadraticBezier** and **CubicBezier**
+//! segment types along with the piecewise **Spline** type found within the `spline` module.
+//!
+//! The **ArcLength** type re-parametrises a curve by distance. This is useful for sampling points
+//! at equal distances along a curve, querying the tangent or normal at some distance, and
+//! splitting or trimming a curve at some distance.
+
+use crate::geom::{scalar, Point2, Vector2};
+use crate::math::num_traits::{cast, Float, One, ToPrimitive, Zero};
+use crate::math::{BaseFloat, InnerSpace};
+use std::cmp::Ordering;
+
+/// The default number of samples used when producing an **ArcLength** table for a curve.
+pub const DEFAULT_ARC_LENGTH_SAMPLES: usize = 64;
+
+/// Types that describe a continuous curve parametrised over the range `0.0..=1.0`.
+pub trait Curve {
+    /// The scalar type used to describe both the curve parameter and positions along the curve.
+    type Scalar: BaseFloat;
+    /// The type of point along the curve, e.g. `Point2` or `Point3`.
+    type Point: InnerSpace<Scalar = Self::Scalar>;
+
+    /// The position along the curve at the given parameter `t`.
+    fn point(&self, t: Self::Scalar) -> Self::Point;
+
+    /// The first derivative of the curve with respect to `t`.
+    fn derivative(&self, t: Self::Scalar) -> Self::Point;
+
+    /// The normalised direction of the curve at the parameter `t`.
+    ///
+    /// Returns a zero vector if the derivative at `t` is zero.
+    fn tangent(&self, t: Self::Scalar) -> Self::Point {
+        normalize(self.derivative(t))
+    }
+
+    /// The position at the beginning of the curve.
+    fn start(&self) -> Self::Point {
+        self.point(Self::Scalar::zero())
+    }
+
+    /// The position at the end of the curve.
+    fn end(&self) -> Self::Point {
+        self.point(Self::Scalar::one())
+    }
+
+    /// Produce an iterator yielding `n` points evaluated at uniformly spaced parameters.
+    ///
+    /// The first and last points yielded are always the start and end of the curve. Note that
+    /// points that are uniformly spaced by parameter are not necessarily uniformly spaced by
+    /// distance. See **ArcLength** for the latter.
+    fn points(&self, n: usize) -> Points<&Self>
+    where
+        Self: Sized,
+    {
+        points(self, n)
+    }
+
+    /// Produce an arc length table for the curve by sampling it at `samples` uniformly spaced
+    /// parameters.
+    ///
+    /// Note that this consumes the curve. As `&C` also implements `Curve`, a reference may be
+    /// measured in order to avoid moving or cloning the curve.
+    fn arc_length(self, samples: usize) -> ArcLength<Self>
+    where
+        Self: Sized,
+    {
+        ArcLength::new(self, samples)
+    }
+
+    /// Approximate the total length of the curve using `DEFAULT_ARC_LENGTH_SAMPLES` samples.
+    fn length(&self) -> Self::Scalar
+    where
+        Self: Sized,
+    {
+        ArcLength::new(self, DEFAULT_ARC_LENGTH_SAMPLES).length()
+    }
+}
+
+/// Curves that may be split into two curves of the same type.
+pub trait Split: Curve + Sized {
+    /// Split the curve at the given parameter `t`.
+    ///
+    /// The first curve describes the range `0.0..=t` and the second describes `t..=1.0`.
+    fn split(&self, t: Self::Scalar) -> (Self, Self);
+
+    /// Produce the section of the curve that lies between the given parameters.
+    ///
+    /// Both `start` and `end` are clamped to the range `0.0..=1.0`. If `start` is greater than
+    /// `end` the two are swapped.
+    fn trim(&self, start: Self::Scalar, end: Self::Scalar) -> Self {
+        let (start, end) = clamped_range(start, end);
+        let (head, _) = self.split(end);
+        if end <= Self::Scalar::zero() {
+            return head;
+        }
+        let (_, section) = head.split(start / end);
+        section
+    }
+}
+
+/// Curves whose points are 2-dimensional.
+pub trait Curve2d: Curve {
+    /// The normal of the curve at the given parameter `t`.
+    ///
+    /// The normal is the tangent rotated 90 degrees counter-clockwise.
+    fn normal(&self, t: Self::Scalar) -> Self::Point;
+}
+
+/// A quadratic bezier curve segment.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct QuadraticBezier<V = Point2<scalar::Default>> {
+    /// The start of the curve.
+    pub from: V,
+    /// The control point.
+    pub ctrl: V,
+    /// The end of the curve.
+    pub to: V,
+}
+
+/// A cubic bezier curve segment.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CubicBezier<V = Point2<scalar::Default>> {
+    /// The start of the curve.
+    pub from: V,
+    /// The control point associated with the start of the curve.
+    pub ctrl1: V,
+    /// The control point associated with the end of the curve.
+    pub ctrl2: V,
+    /// The end of the curve.
+    pub to: V,
+}
+
+/// A table mapping distance along a curve to the curve's parameter.
+///
+/// The table is produced by sampling the curve at uniformly spaced parameters and accumulating the
+/// distance between each sample. Queries by distance are linearly interpolated between samples,
+/// so accuracy increases with the number of samples.
+#[derive(Clone, Debug)]
+pub struct ArcLength<C>
+where
+    C: Curve,
+{
+    curve: C,
+    // The accumulated length at each sample. The first element is always zero.
+    lengths: Vec<C::Scalar>,
+}
+
+/// An iterator yielding points at uniformly spaced parameters along a curve.
+#[derive(Clone, Debug)]
+pub struct Points<C> {
+    curve: C,
+    index: usize,
+    count: usize,
+}
+
+/// An iterator yielding points at uniformly spaced distances along a curve.
+#[derive(Clone, Debug)]
+pub struct EquidistantPoints<'a, C>
+where
+    C: Curve,
+{
+    arc_length: &'a ArcLength<C>,
+    step: C::Scalar,
+    index: usize,
+    count: usize,
+}
+
+/// Produce an iterator yielding `n` points evaluated at uniformly spaced parameters along the
+/// given curve.
+pub fn points<C>(curve: C, n: usize) -> Points<C>
+where
+    C: Curve,
+{
+    Points {
+        curve,
+        index: 0,
+        count: n,
+    }
+}
+
+impl<V, S> QuadraticBezier<V>
+where
+    V: InnerSpace<Scalar = S>,
+    S: BaseFloat,
+{
+    /// Construct a new quadratic bezier segment.
+    pub fn new(from: V, ctrl: V, to: V) -> Self {
+        QuadraticBezier { from, ctrl, to }
+    }
+
+    /// Describe the same curve as a cubic bezier segment.
+    pub fn to_cubic(&self) -> CubicBezier<V> {
+        let two_thirds = cast::<_, S>(2.0 / 3.0).unwrap();
+        CubicBezier {
+            from: self.from,
+            ctrl1: self.from + (self.ctrl - self.from) * two_thirds,
+            ctrl2: self.to + (self.ctrl - self.to) * two_thirds,
+            to: self.to,
+        }
+    }
+}
+
+impl<V, S> CubicBezier<V>
+where
+    V: InnerSpace<Scalar = S>,
+    S: BaseFloat,
+{
+    /// Construct a new cubic bezier segment.
+    pub fn new(from: V, ctrl1: V, ctrl2: V, to: V) -> Self {
+        CubicBezier {
+            from,
+            ctrl1,
+            ctrl2,
+            to,
+        }
+    }
+
+    /// A straight line segment from `from` to `to` described as a cubic bezier.
+    ///
+    /// The control points are placed so that the curve's parameter maps linearly to distance.
+    pub fn line(from: V, to: V) -> Self {
+        let third = cast::<_, S>(1.0 / 3.0).unwrap();
+        let delta = to - from;
+        CubicBezier {
+            from,
+            ctrl1: from + delta * third,
+            ctrl2: to - delta * third,
+            to,
+        }
+    }
+
+    /// Construct the cubic bezier equivalent of a hermite curve segment with the given endpoints
+    /// and tangents.
+    pub fn hermite(from: V, from_tangent: V, to: V, to_tangent: V) -> Self {
+        let three = cast::<_, S>(3.0).unwrap();
+        CubicBezier {
+            from,
+            ctrl1: from + from_tangent / three,
+            ctrl2: to - to_tangent / three,
+            to,
+        }
+    }
+}
+
+impl<C> ArcLength<C>
+where
+    C: Curve,
+{
+    /// Produce an arc length table for the given curve by sampling it at `samples` uniformly
+    /// spaced parameters.
+    ///
+    /// At least two samples are always taken.
+    pub fn new(curve: C, samples: usize) -> Self {
+        let samples = std::cmp::max(samples, 2);
+        let mut lengths = Vec::with_capacity(samples);
+        let mut total = C::Scalar::zero();
+        let mut last = curve.start();
+        lengths.push(total);
+        let denom: C::Scalar = cast(samples - 1).unwrap();
+        for i in 1..samples {
+            let t = cast::<_, C::Scalar>(i).unwrap() / denom;
+            let p = curve.point(t);
+            total = total + (p - last).magnitude();
+            lengths.push(total);
+            last = p;
+        }
+        ArcLength { curve, lengths }
+    }
+
+    /// A reference to the measured curve.
+    pub fn curve(&self) -> &C {
+        &self.curve
+    }
+
+    /// Consume the table and return the measured curve.
+    pub fn into_curve(self) -> C {
+        self.curve
+    }
+
+    /// The total length of the curve.
+    pub fn length(&self) -> C::Scalar {
+        *self
+            .lengths
+            .last()
+            .expect("arc length table is never empty")
+    }
+
+    /// The curve parameter at the given distance along the curve.
+    ///
+    /// The distance is clamped to the range `0.0..=self.length()`.
+    pub fn t_at(&self, distance: C::Scalar) -> C::Scalar {
+        let zero = C::Scalar::zero();
+        let one = C::Scalar::one();
+        let len = self.length();
+        if len <= zero || distance <= zero {
+            return zero;
+        }
+        if distance >= len {
+            return one;
+        }
+        // Find the first sample whose accumulated length is greater than the distance.
+        let ix = match self
+            .lengths
+            .binary_search_by(|l| l.partial_cmp(&distance).unwrap_or(Ordering::Less))
+        {
+            Ok(ix) => return self.t_at_index(ix),
+            Err(ix) => ix,
+        };
+        let (a, b) = (self.lengths[ix - 1], self.lengths[ix]);
+        let seg = b - a;
+        let frac = if seg > zero {
+            (distance - a) / seg
+        } else {
+            zero
+        };
+        let ta = self.t_at_index(ix - 1);
+        let tb = self.t_at_index(ix);
+        ta + (tb - ta) * frac
+    }
+
+    /// The distance along the curve at the given curve parameter `t`.
+    pub fn distance_at(&self, t: C::Scalar) -> C::Scalar {
+        let zero = C::Scalar::zero();
+        let one = C::Scalar::one();
+        if t <= zero {
+            return zero;
+        }
+        if t >= one {
+            return self.length();
+        }
+        let last_ix: C::Scalar = cast(self.lengths.len() - 1).unwrap();
+        let pos = t * last_ix;
+        let ix = std::cmp::min(pos.floor().to_usize().unwrap(), self.lengths.len() - 2);
+        let frac = pos - cast(ix).unwrap();
+        let a = self.lengths[ix];
+        let b = self.lengths[ix + 1];
+        a + (b - a) * frac
+    }
+
+    /// The position at the given distance along the curve.
+    pub fn point_at(&self, distance: C::Scalar) -> C::Point {
+        self.curve.point(self.t_at(distance))
+    }
+
+    /// The normalised direction of the curve at the given distance along the curve.
+    pub fn tangent_at(&self, distance: C::Scalar) -> C::Point {
+        self.curve.tangent(self.t_at(distance))
+    }
+
+    /// Produce an iterator yielding points spaced `spacing` apart along the curve, beginning with
+    /// the start of the curve.
+    ///
+    /// The end of the curve is only yielded if the length is a multiple of `spacing`. Yields only
+    /// the start of the curve if `spacing` is not greater than zero.
+    pub fn equidistant_points(&self, spacing: C::Scalar) -> EquidistantPoints<C> {
+        let zero = C::Scalar::zero();
+        let count = if spacing > zero {
+            (self.length() / spacing).floor().to_usize().unwrap_or(0) + 1
+        } else {
+            1
+        };
+        EquidistantPoints {
+            arc_length: self,
+            step: spacing,
+            index: 0,
+            count,
+        }
+    }
+
+    /// Produce an iterator yielding `n` points at uniformly spaced distances along the curve.
+    ///
+    /// The first and last points yielded are always the start and end of the curve.
+    pub fn divide(&self, n: usize) -> EquidistantPoints<C> {
+        let step = if n > 1 {
+            self.length() / cast(n - 1).unwrap()
+        } else {
+            C::Scalar::zero()
+        };
+        EquidistantPoints {
+            arc_length: self,
+            step,
+            index: 0,
+            count: n,
+        }
+    }
+
+    /// Split the curve at the given distance.
+    pub fn split_at(&self, distance: C::Scalar) -> (C, C)
+    where
+        C: Split,
+    {
+        self.curve.split(self.t_at(distance))
+    }
+
+    /// Produce the section of the curve between the `start` and `end` distances.
+    pub fn trim(&self, start: C::Scalar, end: C::Scalar) -> C
+    where
+        C: Split,
+    {
+        self.curve.trim(self.t_at(start), self.t_at(end))
+    }
+
+    // The curve parameter of the sample at the given index.
+    fn t_at_index(&self, ix: usize) -> C::Scalar {
+        let last_ix: C::Scalar = cast(self.lengths.len() - 1).unwrap();
+        cast::<_, C::Scalar>(ix).unwrap() / last_ix
+    }
+}
+
+impl<C, S> ArcLength<C>
+where
+    C: Curve<Scalar = S, Point = Vector2<S>>,
+    S: BaseFloat,
+{
+    /// The normal of the curve at the given distance along the curve.
+    pub fn normal_at(&self, distance: S) -> Vector2<S> {
+        perp(self.tangent_at(distance))
+    }
+}
+
+// Curve implementations.
+
+impl<'a, C> Curve for &'a C
+where
+    C: Curve,
+{
+    type Scalar = C::Scalar;
+    type Point = C::Point;
+    fn point(&self, t: Self::Scalar) -> Self::Point {
+        (**self).point(t)
+    }
+    fn derivative(&self, t: Self::Scalar) -> Self::Point {
+        (**self).derivative(t)
+    }
+    fn tangent(&self, t: Self::Scalar) -> Self::Point {
+        (**self).tangent(t)
+    }
+}
+
+impl<V, S> Curve for QuadraticBezier<V>
+where
+    V: InnerSpace<Scalar = S>,
+    S: BaseFloat,
+{
+    type Scalar = S;
+    type Point = V;
+
+    fn point(&self, t: Self::Scalar) -> Self::Point {
+        let two = cast::<_, S>(2.0).unwrap();
+        let mt = S::one() - t;
+        self.from * (mt * mt) + self.ctrl * (two * mt * t) + self.to * (t * t)
+    }
+
+    fn derivative(&self, t: Self::Scalar) -> Self::Point {
+        let two = cast::<_, S>(2.0).unwrap();
+        let mt = S::one() - t;
+        (self.ctrl - self.from) * (two * mt) + (self.to - self.ctrl) * (two * t)
+    }
+
+    fn start(&self) -> Self::Point {
+        self.from
+    }
+
+    fn end(&self) -> Self::Point {
+        self.to
+    }
+}
+
+impl<V, S> Curve for CubicBezier<V>
+where
+    V: InnerSpace<Scalar = S>,
+    S: BaseFloat,
+{
+    type Scalar = S;
+    type Point = V;
+
+    fn point(&self, t: Self::Scalar) -> Self::Point {
+        let three = cast::<_, S>(3.0).unwrap();
+        let mt = S::one() - t;
+        self.from * (mt * mt * mt)
+            + self.ctrl1 * (three * mt * mt * t)
+            + self.ctrl2 * (three * mt * t * t)
+            + self.to * (t * t * t)
+    }
+
+    fn derivative(&self, t: Self::Scalar) -> Self::Point {
+        let three = cast::<_, S>(3.0).unwrap();
+        let six = cast::<_, S>(6.0).unwrap();
+        let mt = S::one() - t;
+        (self.ctrl1 - self.from) * (three * mt * mt)
+            + (self.ctrl2 - self.ctrl1) * (six * mt * t)
+            + (self.to - self.ctrl2) * (three * t * t)
+    }
+
+    fn start(&self) -> Self::Point {
+        self.from
+    }
+
+    fn end(&self) -> Self::Point {
+        self.to
+    }
+}
+
+impl<V, S> Split for QuadraticBezier<V>
+where
+    V: InnerSpace<Scalar = S>,
+    S: BaseFloat,
+{
+    fn split(&self, t: Self::Scalar) -> (Self, Self) {
+        let ctrl_a = self.from.lerp(self.ctrl, t);
+        let ctrl_b = self.ctrl.lerp(self.to, t);
+        let mid = ctrl_a.lerp(ctrl_b, t);
+        let a = QuadraticBezier::new(self.from, ctrl_a, mid);
+        let b = QuadraticBezier::new(mid, ctrl_b, self.to);
+        (a, b)
+    }
+}
+
+impl<V, S> Split for CubicBezier<V>
+where
+    V: InnerSpace<Scalar = S>,
+    S: BaseFloat,
+{
+    fn split(&self, t: Self::Scalar) -> (Self, Self) {
+        let ab = self.from.lerp(self.ctrl1, t);
+        let bc = self.ctrl1.lerp(self.ctrl2, t);
+        let cd = self.ctrl2.lerp(self.to, t);
+        let abc = ab.lerp(bc, t);
+        let bcd = bc.lerp(cd, t);
+        let mid = abc.lerp(bcd, t);
+        let a = CubicBezier::new(self.from, ab, abc, mid);
+        let b = CubicBezier::new(mid, bcd, cd, self.to);
+        (a, b)
+    }
+}
+
+impl<C, S> Curve2d for C
+where
+    C: Curve<Scalar = S, Point = Vector2<S>>,
+    S: BaseFloat,
+{
+    fn normal(&self, t: S) -> Vector2<S> {
+        perp(self.tangent(t))
+    }
+}
+
+// Iterators.
+
+impl<C> Iterator for Points<C>
+where
+    C: Curve,
+{
+    type Item = C::Point;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+        let t = if self.count > 1 {
+            cast::<_, C::Scalar>(self.index).unwrap() / cast(self.count - 1).unwrap()
+        } else {
+            C::Scalar::zero()
+        };
+        self.index += 1;
+        Some(self.curve.point(t))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<C> ExactSizeIterator for Points<C>
+where
+    C: Curve,
+{
+    fn len(&self) -> usize {
+        self.count - self.index
+    }
+}
+
+impl<'a, C> Iterator for EquidistantPoints<'a, C>
+where
+    C: Curve,
+{
+    type Item = C::Point;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+        let distance = self.step * cast(self.index).unwrap();
+        self.index += 1;
+        Some(self.arc_length.point_at(distance))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, C> ExactSizeIterator for EquidistantPoints<'a, C>
+where
+    C: Curve,
+{
+    fn len(&self) -> usize {
+        self.count - self.index
+    }
+}
+
+// Helper functions.
+
+// Normalise the given vector, returning it unchanged if it is zero.
+pub(crate) fn normalize<V, S>(v: V) -> V
+where
+    V: InnerSpace<Scalar = S>,
+    S: BaseFloat,
+{
+    if v.magnitude2() > S::zero() {
+        v.normalize()
+    } else {
+        v
+    }
+}
+
+// The given vector rotated 90 degrees counter-clockwise.
+fn perp<S>(v: Vector2<S>) -> Vector2<S>
+where
+    S: BaseFloat,
+{
+    Vector2 { x: -v.y, y: v.x }
+}
+
+// Clamp both `start` and `end` to `0.0..=1.0`, ensuring `start <= end`.
+fn clamped_range<S>(start: S, end: S) -> (S, S)
+where
+    S: BaseFloat,
+{
+    let clamp = |s: S| s.max(S::zero()).min(S::one());
+    let (start, end) = (clamp(start), clamp(end));
+    if start <= end {
+        (start, end)
+    } else {
+        (end, start)
+    }
+}
@@ -110,6 +110,33 @@ where
     pub fn triangle_indices(&self) -> (TriangleVertices<S>, TriangleIndices) {
         self.circumference().triangle_indices()
     }
+
+    /// The area of the polygon described by the ellipse's circumference.
+    ///
+    /// This is the area of the ellipse as tessellated at its `resolution` and approaches the exact
+    /// area of the ellipse as the resolution increases.
+    pub fn area(self) -> S {
+        geom::polygon::area(self.circumference())
+    }
+
+    /// The length of the outline of the polygon described by the ellipse's circumference.
+    ///
+    /// This approaches the exact circumference of the ellipse as the resolution increases.
+    pub fn perimeter(self) -> S {
+        geom::polygon::perimeter(self.circumference())
+    }
+
+    /// Whether or not the given point lies within the ellipse, including its edge.
+    ///
+    /// Unlike `area` and `perimeter`, this tests against the exact ellipse rather than the polygon
+    /// described by its circumference.
+    pub fn contains(&self, p: Point2<S>) -> bool {
+        let (x, y, w, h) = self.rect.x_y_w_h();
+        let two = math::two();
+        let dx = (p.x - x) / (w / two);
+        let dy = (p.y - y) / (h / two);
+        dx * dx + dy * dy <= S::one()
+    }
 }
 
 impl<S> Section<S>
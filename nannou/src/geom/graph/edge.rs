@@ -236,7 +236,7 @@ pub fn displace<S>(v: Vector3<S>) -> [Edge<S>; 3] {
 pub fn rotate<S: BaseFloat>(e: Euler<Rad<S>>) -> [Edge<S>; 3] {
     [
         Edge::x_orientation(e.x.0),
-        Edge::y_orientation(e.x.0),
+        Edge::y_orientation(e.y.0),
         Edge::z_orientation(e.z.0),
     ]
 }
@@ -1,4 +1,4 @@
-use crate::geom::{self, Point3, Vector3};
+use crate::geom::{self, Point2, Point3, Vector3};
 use crate::math::BaseFloat;
 use daggy::petgraph::visit::{GraphBase, IntoNeighbors, Visitable};
 use daggy::{self, Walker};
@@ -589,6 +589,111 @@ where
         geom::bounding_cuboid(vertices)
     }
 
+    /// The `Cuboid` that bounds the given vertices once transformed by the given node's
+    /// **Transform**.
+    ///
+    /// Returns `None` if the node does not exist or if no vertices are given.
+    pub fn node_bounding_cuboid<I>(&self, idx: node::Index, vertices: I) -> Option<geom::Cuboid<S>>
+    where
+        I: IntoIterator<Item = Point3<S>>,
+    {
+        self.node_vertices(idx, vertices)
+            .and_then(geom::bounding_cuboid)
+    }
+
+    /// Update the given **TransformMap** with the transform of every node reachable from the
+    /// origin.
+    ///
+    /// The given `dfs` is reset before the traversal begins.
+    ///
+    /// This is useful for calculating the transform of all nodes at once, rather than calling
+    /// `node_transform` for each node which recalculates the transforms of all parents.
+    pub fn update_transform_map(
+        &self,
+        dfs: &mut node::Dfs<S>,
+        transform_map: &mut node::TransformMap<S>,
+    ) {
+        dfs.reset(self);
+        while dfs.next_transform(self).is_some() {}
+        transform_map.clone_from(dfs.transform_map());
+    }
+
+    /// Produce a **TransformMap** containing the transform of every node reachable from the
+    /// origin.
+    pub fn transform_map(&self) -> node::TransformMap<S> {
+        let mut dfs = node::Dfs::new(self);
+        let mut transform_map = node::TransformMap::default();
+        self.update_transform_map(&mut dfs, &mut transform_map);
+        transform_map
+    }
+
+    /// Find all nodes whose transformed bounds contain the given point.
+    ///
+    /// The bounds of each node are the `Cuboid` bounding the vertices produced by `vertices_fn`
+    /// once transformed by the node's **Transform**. Nodes that produce no vertices are never
+    /// picked.
+    ///
+    /// Nodes are returned in the reverse order of discovery within the depth-first-search. As
+    /// this is the order in which nodes are drawn by `Draw::graph`, the first node is the
+    /// top-most.
+    pub fn pick<F, I>(
+        &self,
+        dfs: &mut node::Dfs<S>,
+        vertices_fn: F,
+        point: Point3<S>,
+    ) -> Vec<node::Index>
+    where
+        F: Fn(&node::Index) -> I,
+        I: IntoIterator<Item = Point3<S>>,
+    {
+        self.pick_by(dfs, vertices_fn, |cuboid| cuboid.contains(point))
+    }
+
+    /// Find all nodes whose transformed bounds contain the given point along the *x* and *y*
+    /// axes, ignoring the *z* axis.
+    ///
+    /// This is useful for picking nodes in a 2D scene, e.g. with the mouse position.
+    ///
+    /// See `pick` for details on how the bounds are determined and the order of the result.
+    pub fn pick_xy<F, I>(
+        &self,
+        dfs: &mut node::Dfs<S>,
+        vertices_fn: F,
+        point: Point2<S>,
+    ) -> Vec<node::Index>
+    where
+        F: Fn(&node::Index) -> I,
+        I: IntoIterator<Item = Point3<S>>,
+    {
+        self.pick_by(dfs, vertices_fn, |cuboid| {
+            cuboid.x.contains(point.x) && cuboid.y.contains(point.y)
+        })
+    }
+
+    // Collect the nodes whose bounds satisfy the given predicate, top-most first.
+    fn pick_by<F, I, P>(
+        &self,
+        dfs: &mut node::Dfs<S>,
+        vertices_fn: F,
+        predicate: P,
+    ) -> Vec<node::Index>
+    where
+        F: Fn(&node::Index) -> I,
+        I: IntoIterator<Item = Point3<S>>,
+        P: Fn(&geom::Cuboid<S>) -> bool,
+    {
+        let mut picked = vec![];
+        while let Some((n, vertices)) = dfs.next_vertices(self, &vertices_fn) {
+            if let Some(cuboid) = geom::bounding_cuboid(vertices) {
+                if predicate(&cuboid) {
+                    picked.push(n);
+                }
+            }
+        }
+        picked.reverse();
+        picked
+    }
+
     //---------------
     // PARENT METHODS
     //---------------
@@ -3,7 +3,7 @@
 use crate::geom;
 use crate::geom::graph::Edge;
 use crate::geom::{scalar, Graph, Point3, Vector3};
-use crate::math::{self, BaseFloat, Basis3, Euler, Rad, Rotation};
+use crate::math::{self, BaseFloat, Basis3, Euler, Matrix4, Quaternion, Rad, Rotation};
 use daggy::petgraph::visit::{self, Visitable};
 use daggy::{self, Walker};
 use std::collections::HashMap;
@@ -143,6 +143,7 @@ where
     pub fn reset(&mut self, graph: &Graph<S>) {
         self.dfs.reset(graph);
         self.dfs.move_to(graph.origin());
+        self.visited.map.clear();
     }
 
     /// Keep the discovered map but clear the visit stack and restart the dfs from the given node.
@@ -160,14 +161,25 @@ where
         };
         let mut transform = Transform::default();
         for (e, parent) in graph.parents(n).iter(graph) {
-            let parent_transform = &self.visited[&parent];
+            // A node with many parents may be discovered before all of its parents are visited.
+            let parent_transform = match self.visited.get(&parent) {
+                Some(transform) => transform.clone(),
+                None => graph
+                    .node_transform(parent)
+                    .expect("no node for yielded parent"),
+            };
             let edge = &graph[e];
-            transform.apply_edge(parent_transform, edge);
+            transform.apply_edge(&parent_transform, edge);
         }
         self.visited.map.insert(n, transform.clone());
         Some((n, transform))
     }
 
+    /// The transforms of all nodes visited so far during the traversal.
+    pub fn transform_map(&self) -> &TransformMap<S> {
+        &self.visited
+    }
+
     /// Return the vertices for the next node in the DFS.
     ///
     /// Uses `Dfs::next_transform` internally.
@@ -342,41 +354,101 @@ where
     S: BaseFloat,
 {
     fn one() -> Self {
+        Transform::default()
+    }
+
+    /// A transform that moves `eye` to the origin and orients the direction towards `center`
+    /// along the *z* axis, matching the behaviour of `cgmath::Decomposed::look_at`.
+    fn look_at(eye: Point3<S>, center: Point3<S>, up: Vector3<S>) -> Self {
+        let rot = Basis3::look_at((center - eye).into(), up.into());
+        let disp = rot.rotate_vector((-eye).into()).into();
         let one = S::one();
         let (x, y, z) = (one, one, one);
-        Transform {
-            scale: Vector3 { x, y, z },
-            rot: Euler {
-                x: Rad(x),
-                y: Rad(y),
-                z: Rad(z),
-            },
-            disp: Vector3 { x, y, z },
-        }
+        let scale = Vector3 { x, y, z };
+        let rot = Euler::from(Quaternion::from(*rot.as_ref()));
+        Transform { scale, rot, disp }
     }
 
-    fn look_at(_eye: Point3<S>, _center: Point3<S>, _up: Vector3<S>) -> Self {
-        unimplemented!();
+    fn transform_vector(&self, vec: Vector3<S>) -> Vector3<S> {
+        let scaled = Vector3 {
+            x: vec.x * self.scale.x,
+            y: vec.y * self.scale.y,
+            z: vec.z * self.scale.z,
+        };
+        Basis3::from(self.rot).rotate_vector(scaled.into()).into()
     }
 
-    fn transform_vector(&self, _vec: Vector3<S>) -> Vector3<S> {
-        unimplemented!();
+    fn inverse_transform_vector(&self, vec: Vector3<S>) -> Option<Vector3<S>> {
+        if self.scale.x == S::zero() || self.scale.y == S::zero() || self.scale.z == S::zero() {
+            return None;
+        }
+        let unrotated: Vector3<S> = Basis3::from(self.rot)
+            .invert()
+            .rotate_vector(vec.into())
+            .into();
+        Some(Vector3 {
+            x: unrotated.x / self.scale.x,
+            y: unrotated.y / self.scale.y,
+            z: unrotated.z / self.scale.z,
+        })
     }
 
-    fn inverse_transform_vector(&self, _vec: Vector3<S>) -> Option<Vector3<S>> {
-        unimplemented!();
+    fn transform_point(&self, point: Point3<S>) -> Point3<S> {
+        transform_point(&self.clone().prepare(), point)
     }
 
-    fn transform_point(&self, _point: Point3<S>) -> Point3<S> {
-        unimplemented!();
+    /// Produce a transform that applies `other` followed by `self`.
+    ///
+    /// **Note:** As the **Transform** applies scale prior to rotation, the result is only exact if
+    /// the scale of `self` is uniform or `other` has no rotation. Otherwise the result would
+    /// require a shear which cannot be represented by a **Transform**.
+    fn concat(&self, other: &Self) -> Self {
+        let scale = Vector3 {
+            x: self.scale.x * other.scale.x,
+            y: self.scale.y * other.scale.y,
+            z: self.scale.z * other.scale.z,
+        };
+        let rot = Euler::from(Quaternion::from(self.rot) * Quaternion::from(other.rot));
+        let disp = self.transform_point(other.disp);
+        Transform { scale, rot, disp }
     }
 
-    fn concat(&self, _other: &Self) -> Self {
-        unimplemented!();
+    /// Produce the inverse of the transform.
+    ///
+    /// Returns `None` if the scale is zero along any axis.
+    ///
+    /// **Note:** The result is only exact if the scale is uniform or there is no rotation. See
+    /// `concat` for details.
+    fn inverse_transform(&self) -> Option<Self> {
+        let one = S::one();
+        let zero = S::zero();
+        if self.scale.x == zero || self.scale.y == zero || self.scale.z == zero {
+            return None;
+        }
+        let scale = Vector3 {
+            x: one / self.scale.x,
+            y: one / self.scale.y,
+            z: one / self.scale.z,
+        };
+        let rot = Euler::from(Quaternion::from(self.rot).invert());
+        let disp = -self.inverse_transform_vector(self.disp)?;
+        Some(Transform { scale, rot, disp })
     }
+}
 
-    fn inverse_transform(&self) -> Option<Self> {
-        unimplemented!();
+impl<S> From<Transform<S>> for Matrix4<S>
+where
+    S: BaseFloat,
+{
+    /// Produce a matrix that scales, rotates and then displaces in the same manner as the
+    /// transform.
+    ///
+    /// Useful for drawing a node's geometry with a `Draw` instance via `Draw::transform`.
+    fn from(t: Transform<S>) -> Self {
+        let scale = Matrix4::from_nonuniform_scale(t.scale.x, t.scale.y, t.scale.z);
+        let rot = Matrix4::from(t.rot);
+        let disp = Matrix4::from_translation(t.disp.into());
+        disp * rot * scale
     }
 }
 
@@ -396,6 +468,28 @@ where
     point
 }
 
+/// Apply the given transform to the given normal vector.
+///
+/// The normal is multiplied by the inverse-transpose of the scale before being rotated, ensuring
+/// that it remains perpendicular to the transformed surface under non-uniform scaling. The result
+/// is re-normalized.
+pub fn transform_normal<S>(transform: &PreparedTransform<S>, normal: Vector3<S>) -> Vector3<S>
+where
+    S: BaseFloat,
+{
+    // Use the cofactors of the scale rather than its inverse so that zero scales remain valid.
+    let scale = transform.scale;
+    let det = scale.x * scale.y * scale.z;
+    let sign = if det < S::zero() { -S::one() } else { S::one() };
+    let normal = Vector3 {
+        x: normal.x * scale.y * scale.z * sign,
+        y: normal.y * scale.x * scale.z * sign,
+        z: normal.z * scale.x * scale.y * sign,
+    };
+    let normal: Vector3<S> = transform.rot.rotate_vector(normal.into()).into();
+    normal.normalize()
+}
+
 /// Vertex types which may apply a transform and produce a resulting transform.
 pub trait ApplyTransform<S>
 where
@@ -0,0 +1,111 @@
+//! Items related to the convex hull of a set of 2D vertices.
+
+use crate::geom::{Point2, Vertex, Vertex2d};
+use crate::math::num_traits::Zero;
+use crate::math::BaseNum;
+use std::cmp::Ordering;
+
+/// Produce the convex hull of the given vertices.
+///
+/// The hull is returned as a list of the original vertices ordered counter-clockwise, beginning
+/// with the vertex with the lowest *x* (and then lowest *y*) position. Vertices that lie along an
+/// edge of the hull are omitted.
+///
+/// Returns an empty list if no vertices are given. If all vertices are coincident or collinear,
+/// the "hull" will contain only the one or two extreme vertices respectively.
+///
+/// This uses Andrew's monotone chain algorithm which runs in *O(n log n)* time.
+pub fn convex_hull<I>(vertices: I) -> Vec<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Vertex2d,
+{
+    let mut vertices: Vec<I::Item> = vertices.into_iter().collect();
+    vertices.sort_by(|a, b| cmp_point2(a.point2(), b.point2()));
+    vertices.dedup_by(|a, b| a.point2() == b.point2());
+    if vertices.len() < 3 {
+        return vertices;
+    }
+
+    let mut hull: Vec<I::Item> = Vec::with_capacity(vertices.len() * 2);
+
+    // Lower hull.
+    for &v in &vertices {
+        while hull.len() >= 2 && !is_ccw(hull[hull.len() - 2], hull[hull.len() - 1], v) {
+            hull.pop();
+        }
+        hull.push(v);
+    }
+
+    // Upper hull.
+    let lower_len = hull.len() + 1;
+    for &v in vertices.iter().rev().skip(1) {
+        while hull.len() >= lower_len && !is_ccw(hull[hull.len() - 2], hull[hull.len() - 1], v) {
+            hull.pop();
+        }
+        hull.push(v);
+    }
+
+    // The last vertex is the same as the first.
+    hull.pop();
+    hull
+}
+
+/// Whether or not the given vertices describe a convex polygon.
+///
+/// Polygons with less than three vertices are considered convex.
+pub fn is_convex<I>(vertices: I) -> bool
+where
+    I: IntoIterator,
+    I::Item: Vertex2d,
+{
+    let vertices: Vec<I::Item> = vertices.into_iter().collect();
+    let n = vertices.len();
+    if n < 3 {
+        return true;
+    }
+    let zero = <I::Item as Vertex>::Scalar::zero();
+    let mut sign = None;
+    for i in 0..n {
+        let (a, b, c) = (vertices[i], vertices[(i + 1) % n], vertices[(i + 2) % n]);
+        let cross = cross(a.point2(), b.point2(), c.point2());
+        if cross == zero {
+            continue;
+        }
+        let positive = cross > zero;
+        match sign {
+            None => sign = Some(positive),
+            Some(s) if s != positive => return false,
+            _ => (),
+        }
+    }
+    true
+}
+
+// Order points by `x` and then by `y`.
+pub(crate) fn cmp_point2<S>(a: Point2<S>, b: Point2<S>) -> Ordering
+where
+    S: PartialOrd,
+{
+    a.x.partial_cmp(&b.x)
+        .unwrap_or(Ordering::Equal)
+        .then(a.y.partial_cmp(&b.y).unwrap_or(Ordering::Equal))
+}
+
+// The z component of the cross product of `b - a` and `c - a`.
+//
+// Positive if `a`, `b`, `c` make a counter-clockwise turn.
+fn cross<S>(a: Point2<S>, b: Point2<S>, c: Point2<S>) -> S
+where
+    S: BaseNum,
+{
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+// Whether or not `a`, `b`, `c` make a strictly counter-clockwise turn.
+fn is_ccw<V>(a: V, b: V, c: V) -> bool
+where
+    V: Vertex2d,
+{
+    cross(a.point2(), b.point2(), c.point2()) > V::Scalar::zero()
+}
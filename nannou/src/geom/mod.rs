@@ -13,9 +13,12 @@ use crate::math::num_traits::cast;
 use crate::math::{BaseFloat, EuclideanSpace};
 use std::ops;
 
+pub mod bounding;
 pub mod cuboid;
+pub mod curve;
 pub mod ellipse;
 pub mod graph;
+pub mod hull;
 pub mod path;
 pub mod point;
 pub mod polygon;
@@ -23,19 +26,24 @@ pub mod quad;
 pub mod range;
 pub mod rect;
 pub mod scalar;
+pub mod spline;
 pub mod tri;
 pub mod vector;
 pub mod vertex;
 
+pub use self::bounding::{Circle, OrientedRect};
 pub use self::cuboid::Cuboid;
+pub use self::curve::{ArcLength, CubicBezier, Curve, Curve2d, QuadraticBezier};
 pub use self::ellipse::Ellipse;
 pub use self::graph::Graph;
 pub use self::path::{path, Path};
 pub use self::point::{pt2, pt3, pt4, Point2, Point3, Point4};
-pub use self::polygon::Polygon;
+pub use self::hull::convex_hull;
+pub use self::polygon::{Polygon, Winding};
 pub use self::quad::Quad;
 pub use self::range::{Align, Edge, Range};
 pub use self::rect::{Corner, Padding, Rect};
+pub use self::spline::Spline;
 pub use self::tri::Tri;
 pub use self::vector::{vec2, vec3, vec4, Vector2, Vector3, Vector4};
 pub use self::vertex::{Vertex, Vertex2d, Vertex3d};
@@ -89,6 +97,30 @@ where
     })
 }
 
+/// The smallest `Circle` that bounds the given sequence of vertices.
+///
+/// Returns `None` if the given iterator is empty.
+pub fn bounding_circle<I>(vertices: I) -> Option<Circle<<I::Item as Vertex>::Scalar>>
+where
+    I: IntoIterator,
+    I::Item: Vertex2d,
+    <I::Item as Vertex>::Scalar: BaseFloat,
+{
+    bounding::circle(vertices)
+}
+
+/// The `OrientedRect` of minimum area that bounds the given sequence of vertices.
+///
+/// Returns `None` if the given iterator is empty.
+pub fn oriented_bounding_rect<I>(vertices: I) -> Option<OrientedRect<<I::Item as Vertex>::Scalar>>
+where
+    I: IntoIterator,
+    I::Item: Vertex2d,
+    <I::Item as Vertex>::Scalar: BaseFloat,
+{
+    bounding::oriented_rect(vertices)
+}
+
 /// The `centroid` (average position) of all vertices in the given iterator.
 ///
 /// Returns `None` if the given iterator contains no vertices.
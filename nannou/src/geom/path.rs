@@ -4,7 +4,8 @@
 //! offerred by `lyon` in a way that interoperates a little more fluidly and consistently with the
 //! rest of nannou's API.
 
-use crate::geom::{Point2, Vector2};
+use crate::geom::curve::{ArcLength, CubicBezier, Curve, Curve2d, QuadraticBezier};
+use crate::geom::{Point2, Spline, Vector2};
 
 /// A wrapper around a 2D lyon path exposing a nannou-friendly API.
 pub struct Path {
@@ -16,6 +17,47 @@ pub struct Builder {
     builder: lyon::path::Builder,
 }
 
+/// A **Path** that has been measured, allowing for querying, sampling, splitting and trimming the
+/// path by distance.
+///
+/// The length of each curve within the path is approximated by sampling it at a fixed number of
+/// points. See `Path::measure` for details.
+#[derive(Clone, Debug)]
+pub struct Measure {
+    segments: Vec<MeasuredSegment>,
+    length: f32,
+}
+
+/// An iterator yielding points spaced at equal distances along a measured **Path**.
+#[derive(Clone, Debug)]
+pub struct EquidistantPoints<'a> {
+    measure: &'a Measure,
+    step: f32,
+    index: usize,
+    count: usize,
+}
+
+// A single edge within a measured path.
+#[derive(Clone, Debug)]
+struct MeasuredSegment {
+    kind: SegmentKind,
+    // Whether or not this segment is the first of a sub-path.
+    begins_subpath: bool,
+    // Whether or not this segment is the line that closes a sub-path.
+    closes_subpath: bool,
+    // The distance along the path at which this segment begins.
+    offset: f32,
+    arc_length: ArcLength<CubicBezier>,
+}
+
+// The kind of edge described by a measured segment.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum SegmentKind {
+    Line,
+    Quadratic,
+    Cubic,
+}
+
 impl Path {
     /// Begin building a new path.
     pub fn builder() -> Builder {
@@ -70,6 +112,22 @@ impl Path {
     pub fn merge(&self, other: &Self) -> Self {
         self.path.merge(&other.path).into()
     }
+
+    /// Measure the path, allowing for querying, sampling, splitting and trimming by distance.
+    ///
+    /// The length of each curve within the path is approximated by sampling it at
+    /// `samples_per_curve` uniformly spaced points. Line segments are always measured exactly.
+    pub fn measure(&self, samples_per_curve: usize) -> Measure {
+        Measure::new(self, samples_per_curve)
+    }
+
+    /// Approximate the total length of the path.
+    ///
+    /// The length of each curve is approximated using `curve::DEFAULT_ARC_LENGTH_SAMPLES`.
+    pub fn length(&self) -> f32 {
+        self.measure(crate::geom::curve::DEFAULT_ARC_LENGTH_SAMPLES)
+            .length()
+    }
 }
 
 impl Builder {
@@ -144,6 +202,20 @@ impl Builder {
         self
     }
 
+    /// Add the cubic bezier segments of the given spline to the path as a new sub-path.
+    pub fn spline(mut self, spline: &Spline) -> Self {
+        let mut segments = spline.segments().iter();
+        if let Some(first) = segments.next() {
+            self = self
+                .move_to(first.from)
+                .cubic_bezier_to(first.ctrl1, first.ctrl2, first.to);
+        }
+        for s in segments {
+            self = self.cubic_bezier_to(s.ctrl1, s.ctrl2, s.to);
+        }
+        self
+    }
+
     /// Add a closed polygon.
     pub fn polygon(mut self, points: &[Point2]) -> Self {
         self.builder.polygon(point_slice_nannou_to_lyon(points));
@@ -171,6 +243,239 @@ impl Builder {
     }
 }
 
+impl Measure {
+    /// Measure the given path.
+    ///
+    /// The length of each curve within the path is approximated by sampling it at
+    /// `samples_per_curve` uniformly spaced points. Line segments are always measured exactly.
+    pub fn new(path: &Path, samples_per_curve: usize) -> Self {
+        let mut segments = vec![];
+        let mut length = 0.0;
+        let mut begins_subpath = false;
+        for event in path.iter() {
+            let (kind, closes_subpath, curve) = match event {
+                lyon::path::PathEvent::Begin { .. } => {
+                    begins_subpath = true;
+                    continue;
+                }
+                lyon::path::PathEvent::Line { from, to } => (
+                    SegmentKind::Line,
+                    false,
+                    CubicBezier::line(from.into(), to.into()),
+                ),
+                lyon::path::PathEvent::Quadratic { from, ctrl, to } => {
+                    let quad = QuadraticBezier::new(from.into(), ctrl.into(), to.into());
+                    (SegmentKind::Quadratic, false, quad.to_cubic())
+                }
+                lyon::path::PathEvent::Cubic {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                } => {
+                    let cubic =
+                        CubicBezier::new(from.into(), ctrl1.into(), ctrl2.into(), to.into());
+                    (SegmentKind::Cubic, false, cubic)
+                }
+                lyon::path::PathEvent::End { last, first, close } => {
+                    if !close {
+                        continue;
+                    }
+                    (
+                        SegmentKind::Line,
+                        true,
+                        CubicBezier::line(last.into(), first.into()),
+                    )
+                }
+            };
+            // Lines only require their two end points to be measured exactly.
+            let samples = match kind {
+                SegmentKind::Line => 2,
+                _ => samples_per_curve,
+            };
+            let arc_length = curve.arc_length(samples);
+            let segment = MeasuredSegment {
+                kind,
+                begins_subpath,
+                closes_subpath,
+                offset: length,
+                arc_length,
+            };
+            length += segment.arc_length.length();
+            segments.push(segment);
+            begins_subpath = false;
+        }
+        Measure { segments, length }
+    }
+
+    /// The total length of the path.
+    pub fn length(&self) -> f32 {
+        self.length
+    }
+
+    /// The position at the given distance along the path.
+    ///
+    /// The distance is clamped to the length of the path. Returns the origin if the path is
+    /// empty.
+    pub fn point_at(&self, distance: f32) -> Point2 {
+        self.segment_at(distance)
+            .map(|(s, d)| s.arc_length.point_at(d))
+            .unwrap_or_else(Point2::zero)
+    }
+
+    /// The normalised direction of the path at the given distance along the path.
+    pub fn tangent_at(&self, distance: f32) -> Vector2 {
+        self.segment_at(distance)
+            .map(|(s, d)| s.arc_length.tangent_at(d))
+            .unwrap_or_else(Vector2::zero)
+    }
+
+    /// The normal of the path at the given distance along the path.
+    ///
+    /// The normal is the tangent rotated 90 degrees counter-clockwise.
+    pub fn normal_at(&self, distance: f32) -> Vector2 {
+        self.segment_at(distance)
+            .map(|(s, d)| s.arc_length.curve().normal(s.arc_length.t_at(d)))
+            .unwrap_or_else(Vector2::zero)
+    }
+
+    /// Produce an iterator yielding points spaced `spacing` apart along the path, beginning with
+    /// the start of the path.
+    ///
+    /// The resulting points may be passed directly to `draw.polyline()` or used as laser points.
+    pub fn equidistant_points(&self, spacing: f32) -> EquidistantPoints {
+        let count = if self.segments.is_empty() {
+            0
+        } else if spacing > 0.0 {
+            (self.length / spacing).floor() as usize + 1
+        } else {
+            1
+        };
+        EquidistantPoints {
+            measure: self,
+            step: spacing,
+            index: 0,
+            count,
+        }
+    }
+
+    /// Produce an iterator yielding `n` points at uniformly spaced distances along the path.
+    ///
+    /// The first and last points yielded are always the start and end of the path.
+    pub fn divide(&self, n: usize) -> EquidistantPoints {
+        let step = if n > 1 {
+            self.length / (n - 1) as f32
+        } else {
+            0.0
+        };
+        let count = if self.segments.is_empty() { 0 } else { n };
+        EquidistantPoints {
+            measure: self,
+            step,
+            index: 0,
+            count,
+        }
+    }
+
+    /// Split the path at the given distance.
+    pub fn split_at(&self, distance: f32) -> (Path, Path) {
+        (self.trim(0.0, distance), self.trim(distance, self.length))
+    }
+
+    /// Produce the section of the path between the `start` and `end` distances.
+    ///
+    /// Both distances are clamped to the length of the path. Sub-paths that lie within the given
+    /// range remain separate sub-paths within the resulting path.
+    pub fn trim(&self, start: f32, end: f32) -> Path {
+        let (start, end) = if start <= end {
+            (start, end)
+        } else {
+            (end, start)
+        };
+        let start = start.max(0.0);
+        let end = end.min(self.length);
+        let mut builder = Path::builder();
+        let mut first = true;
+        let mut subpath_complete = false;
+        for segment in &self.segments {
+            let seg_len = segment.arc_length.length();
+            let seg_start = segment.offset;
+            let seg_end = seg_start + seg_len;
+            if seg_end < start || seg_start > end {
+                continue;
+            }
+            let a = (start - seg_start).max(0.0);
+            let b = (end - seg_start).min(seg_len);
+            // Skip degenerate sections, e.g. where the range only touches the segment's end.
+            if b <= a && !(segment.closes_subpath && seg_len == 0.0) {
+                continue;
+            }
+            let curve = if a <= 0.0 && b >= seg_len {
+                *segment.arc_length.curve()
+            } else {
+                segment.arc_length.trim(a, b)
+            };
+            if segment.begins_subpath {
+                subpath_complete = a <= 0.0;
+                builder = builder.move_to(curve.from);
+            } else if first {
+                subpath_complete = false;
+                builder = builder.move_to(curve.from);
+            }
+            first = false;
+            if segment.closes_subpath && subpath_complete && b >= seg_len {
+                builder = builder.close();
+                continue;
+            }
+            builder = match segment.kind {
+                SegmentKind::Line => builder.line_to(curve.to),
+                SegmentKind::Quadratic => {
+                    // The curve is a degree-elevated quadratic, so the original control point
+                    // may be recovered from the first cubic control point.
+                    let ctrl = (curve.ctrl1 * 3.0 - curve.from) / 2.0;
+                    builder.quadratic_bezier_to(ctrl, curve.to)
+                }
+                SegmentKind::Cubic => builder.cubic_bezier_to(curve.ctrl1, curve.ctrl2, curve.to),
+            };
+        }
+        builder.build()
+    }
+
+    // The segment at the given distance along with the distance along that segment.
+    fn segment_at(&self, distance: f32) -> Option<(&MeasuredSegment, f32)> {
+        let distance = distance.max(0.0).min(self.length);
+        let ix = match self
+            .segments
+            .iter()
+            .position(|s| distance <= s.offset + s.arc_length.length())
+        {
+            Some(ix) => ix,
+            None => self.segments.len().checked_sub(1)?,
+        };
+        let segment = &self.segments[ix];
+        Some((segment, distance - segment.offset))
+    }
+}
+
+impl<'a> Iterator for EquidistantPoints<'a> {
+    type Item = Point2;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+        let distance = self.step * self.index as f32;
+        self.index += 1;
+        Some(self.measure.point_at(distance))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.count - self.index;
+        (len, Some(len))
+    }
+}
+
+impl<'a> ExactSizeIterator for EquidistantPoints<'a> {}
+
 // lyon builder traits
 
 impl lyon::path::builder::Build for Builder {
@@ -297,6 +602,12 @@ impl<'a> Into<lyon::path::PathSlice<'a>> for &'a Path {
     }
 }
 
+impl<'a> From<&'a Spline> for Path {
+    fn from(spline: &'a Spline) -> Self {
+        Path::builder().spline(spline).build()
+    }
+}
+
 // Simplified constructors
 
 /// Begin building a path.
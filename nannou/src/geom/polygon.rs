@@ -1,5 +1,8 @@
 use crate::geom::tri::{self, Tri};
-use crate::geom::{Cuboid, Rect, Vertex, Vertex2d, Vertex3d};
+use crate::geom::{Cuboid, Point2, Rect, Vertex, Vertex2d, Vertex3d};
+use crate::math::num_traits::{One, Zero};
+use crate::math::BaseFloat;
+use std::ops::Neg;
 
 /// A simple type wrapper around a list of points that describe a polygon.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -8,6 +11,15 @@ pub struct Polygon<I> {
     pub points: I,
 }
 
+/// The direction in which the vertices of a polygon are ordered.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Winding {
+    /// The vertices are ordered clockwise, producing a negative signed area.
+    Clockwise,
+    /// The vertices are ordered counter-clockwise, producing a positive signed area.
+    CounterClockwise,
+}
+
 /// An iterator yielding indices into a polygon's vertices required to triangulate the polygon.
 #[derive(Clone, Debug)]
 pub struct TriangleIndices {
@@ -65,6 +77,59 @@ where
     {
         super::bounding_cuboid(self.points)
     }
+
+    /// The signed area of the polygon.
+    ///
+    /// See the `signed_area` function for details.
+    pub fn signed_area(self) -> <I::Item as Vertex>::Scalar
+    where
+        I::Item: Vertex2d,
+        <I::Item as Vertex>::Scalar: Neg<Output = <I::Item as Vertex>::Scalar>,
+    {
+        signed_area(self.points)
+    }
+
+    /// The area of the polygon.
+    ///
+    /// See the `area` function for details.
+    pub fn area(self) -> <I::Item as Vertex>::Scalar
+    where
+        I::Item: Vertex2d,
+        <I::Item as Vertex>::Scalar: Neg<Output = <I::Item as Vertex>::Scalar>,
+    {
+        area(self.points)
+    }
+
+    /// The length of the closed outline of the polygon.
+    pub fn perimeter(self) -> <I::Item as Vertex>::Scalar
+    where
+        I::Item: Vertex2d,
+        <I::Item as Vertex>::Scalar: BaseFloat,
+    {
+        perimeter(self.points)
+    }
+
+    /// The direction in which the polygon's vertices are ordered.
+    ///
+    /// Returns `None` if the polygon has no area.
+    pub fn winding(self) -> Option<Winding>
+    where
+        I::Item: Vertex2d,
+        <I::Item as Vertex>::Scalar: Neg<Output = <I::Item as Vertex>::Scalar>,
+    {
+        winding(self.points)
+    }
+
+    /// Whether or not the given point lies within the polygon.
+    ///
+    /// Unlike `contains`, this is correct for concave and self-intersecting polygons. See the
+    /// `contains_point` function for details.
+    pub fn contains_point(self, p: &I::Item) -> bool
+    where
+        I::Item: Vertex2d,
+    {
+        contains_point(self.points, p)
+    }
 }
 
 /// An iterator that triangulates a polygon represented by a sequence of points describing its
@@ -111,7 +176,8 @@ pub fn triangle_indices(n_points: usize) -> TriangleIndices {
 /// Returns `Some` with the touched triangle if the given `Point` is over the polygon described by
 /// the given series of points.
 ///
-/// This uses the `triangles` function internally.
+/// This uses the `triangles` function internally and as a result is only accurate for convex
+/// polygons. See `contains_point` for concave polygons.
 pub fn contains<I>(points: I, point: &I::Item) -> Option<Tri<I::Item>>
 where
     I: IntoIterator,
@@ -120,6 +186,166 @@ where
     triangles(points).and_then(|ts| tri::iter_contains(ts, &point))
 }
 
+/// The signed area of the polygon described by the given series of points.
+///
+/// The area is positive if the points are ordered counter-clockwise and negative if they are
+/// ordered clockwise. The polygon is assumed to be closed, i.e. the last point connects to the
+/// first. The result is only meaningful for polygons that do not intersect themselves.
+pub fn signed_area<I>(points: I) -> <I::Item as Vertex>::Scalar
+where
+    I: IntoIterator,
+    I::Item: Vertex2d,
+    <I::Item as Vertex>::Scalar: Neg<Output = <I::Item as Vertex>::Scalar>,
+{
+    let zero = <I::Item as Vertex>::Scalar::zero();
+    let two = <I::Item as Vertex>::Scalar::one() + <I::Item as Vertex>::Scalar::one();
+    let twice_area = edges(points).fold(zero, |acc, (a, b)| acc + (a.x * b.y - b.x * a.y));
+    twice_area / two
+}
+
+/// The area of the polygon described by the given series of points.
+///
+/// The polygon is assumed to be closed. The result is only meaningful for polygons that do not
+/// intersect themselves.
+pub fn area<I>(points: I) -> <I::Item as Vertex>::Scalar
+where
+    I: IntoIterator,
+    I::Item: Vertex2d,
+    <I::Item as Vertex>::Scalar: Neg<Output = <I::Item as Vertex>::Scalar>,
+{
+    let area = signed_area(points);
+    if area < <I::Item as Vertex>::Scalar::zero() {
+        -area
+    } else {
+        area
+    }
+}
+
+/// The length of the closed outline of the polygon described by the given series of points.
+pub fn perimeter<I>(points: I) -> <I::Item as Vertex>::Scalar
+where
+    I: IntoIterator,
+    I::Item: Vertex2d,
+    <I::Item as Vertex>::Scalar: BaseFloat,
+{
+    let zero = <I::Item as Vertex>::Scalar::zero();
+    edges(points).fold(zero, |acc, (a, b)| acc + (b - a).magnitude())
+}
+
+/// The direction in which the points of the given polygon are ordered.
+///
+/// Returns `None` if the polygon has no area.
+pub fn winding<I>(points: I) -> Option<Winding>
+where
+    I: IntoIterator,
+    I::Item: Vertex2d,
+    <I::Item as Vertex>::Scalar: Neg<Output = <I::Item as Vertex>::Scalar>,
+{
+    let area = signed_area(points);
+    let zero = <I::Item as Vertex>::Scalar::zero();
+    if area > zero {
+        Some(Winding::CounterClockwise)
+    } else if area < zero {
+        Some(Winding::Clockwise)
+    } else {
+        None
+    }
+}
+
+/// The winding number of the polygon around the given point.
+///
+/// This is the number of times the polygon's outline travels counter-clockwise around the point.
+/// A winding number of `0` indicates that the point lies outside the polygon.
+pub fn winding_number<I>(points: I, point: &I::Item) -> i32
+where
+    I: IntoIterator,
+    I::Item: Vertex2d,
+{
+    let p = point.point2();
+    let zero = <I::Item as Vertex>::Scalar::zero();
+    edges(points).fold(0, |wn, (a, b)| {
+        let side = (b.x - a.x) * (p.y - a.y) - (p.x - a.x) * (b.y - a.y);
+        if a.y <= p.y {
+            if b.y > p.y && side > zero {
+                return wn + 1;
+            }
+        } else if b.y <= p.y && side < zero {
+            return wn - 1;
+        }
+        wn
+    })
+}
+
+/// Whether or not the given point lies within the polygon described by the given series of points.
+///
+/// The even-odd rule is used to determine the inside of the polygon, meaning that this is correct
+/// for concave polygons and treats regions enclosed by self-intersecting outlines an even number
+/// of times as outside.
+pub fn contains_point<I>(points: I, point: &I::Item) -> bool
+where
+    I: IntoIterator,
+    I::Item: Vertex2d,
+{
+    let p = point.point2();
+    edges(points).fold(false, |inside, (a, b)| {
+        if (a.y > p.y) != (b.y > p.y) {
+            // The x position at which the edge crosses the horizontal line through `p`.
+            let x = (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x;
+            if p.x < x {
+                return !inside;
+            }
+        }
+        inside
+    })
+}
+
+/// Whether or not the given point lies within the polygon described by the `outline` and not
+/// within any of the given `holes`.
+pub fn contains_point_with_holes<I, H>(outline: I, holes: H, point: &I::Item) -> bool
+where
+    I: IntoIterator,
+    I::Item: Vertex2d,
+    H: IntoIterator,
+    H::Item: IntoIterator<Item = I::Item>,
+{
+    contains_point(outline, point) && !holes.into_iter().any(|h| contains_point(h, point))
+}
+
+// Produce an iterator yielding each edge of the closed polygon described by the given points.
+fn edges<I>(
+    points: I,
+) -> impl Iterator<
+    Item = (
+        Point2<<I::Item as Vertex>::Scalar>,
+        Point2<<I::Item as Vertex>::Scalar>,
+    ),
+>
+where
+    I: IntoIterator,
+    I::Item: Vertex2d,
+{
+    let mut points = points.into_iter().map(Vertex2d::point2);
+    let first = points.next();
+    let mut prev = first;
+    let mut done = first.is_none();
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+        let a = prev?;
+        match points.next() {
+            Some(b) => {
+                prev = Some(b);
+                Some((a, b))
+            }
+            None => {
+                done = true;
+                first.map(|f| (a, f))
+            }
+        }
+    })
+}
+
 impl<I> Iterator for Triangles<I>
 where
     I: Iterator,
@@ -1,6 +1,6 @@
-use crate::geom::{tri, vertex, Cuboid, Range, Rect, Tri, Vertex, Vertex2d, Vertex3d};
-use crate::math::EuclideanSpace;
-use std::ops::{Deref, Index};
+use crate::geom::{polygon, tri, vertex, Cuboid, Range, Rect, Tri, Vertex, Vertex2d, Vertex3d};
+use crate::math::{BaseFloat, EuclideanSpace};
+use std::ops::{Deref, Index, Neg};
 
 /// The number of vertices in a quad.
 pub const NUM_VERTICES: u8 = 4;
@@ -108,6 +108,56 @@ where
         triangles_iter(self)
     }
 
+    /// Whether or not the given 2D vertex is contained within the 2D `Quad`.
+    ///
+    /// Unlike testing against each of the `triangles`, this is correct for concave quads.
+    pub fn contains(&self, v: &V) -> bool
+    where
+        V: Vertex2d,
+    {
+        polygon::contains_point(self.0.iter().cloned(), v)
+    }
+
+    /// The signed area of the quad.
+    ///
+    /// The area is positive if the vertices are ordered counter-clockwise.
+    pub fn signed_area(&self) -> V::Scalar
+    where
+        V: Vertex2d,
+        V::Scalar: Neg<Output = V::Scalar>,
+    {
+        polygon::signed_area(self.0.iter().cloned())
+    }
+
+    /// The area of the quad.
+    pub fn area(&self) -> V::Scalar
+    where
+        V: Vertex2d,
+        V::Scalar: Neg<Output = V::Scalar>,
+    {
+        polygon::area(self.0.iter().cloned())
+    }
+
+    /// The length of the outline of the quad.
+    pub fn perimeter(&self) -> V::Scalar
+    where
+        V: Vertex2d,
+        V::Scalar: BaseFloat,
+    {
+        polygon::perimeter(self.0.iter().cloned())
+    }
+
+    /// The direction in which the quad's vertices are ordered.
+    ///
+    /// Returns `None` if the quad has no area.
+    pub fn winding(&self) -> Option<polygon::Winding>
+    where
+        V: Vertex2d,
+        V::Scalar: Neg<Output = V::Scalar>,
+    {
+        polygon::winding(self.0.iter().cloned())
+    }
+
     /// The bounding `Rect` of the quad.
     pub fn bounding_rect(self) -> Rect<V::Scalar>
     where
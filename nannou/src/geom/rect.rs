@@ -626,6 +626,17 @@ where
         math::partial_max(self.w(), self.h())
     }
 
+    /// The area of the rectangle.
+    pub fn area(&self) -> S {
+        self.w() * self.h()
+    }
+
+    /// The length of the outline of the rectangle.
+    pub fn perimeter(&self) -> S {
+        let (w, h) = self.w_h();
+        w + w + h + h
+    }
+
     /// The left and top edges of the **Rect** along with the width and height.
     pub fn l_t_w_h(&self) -> (S, S, S, S) {
         let (w, h) = self.w_h();
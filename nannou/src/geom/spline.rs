@@ -0,0 +1,300 @@
+//! Items related to piecewise cubic splines passing through or guided by sets of points.
+//!
+//! All spline types are represented by the **Spline** type as a sequence of **CubicBezier**
+//! segments. This allows for catmull-rom, b-spline and hermite splines to be evaluated, measured
+//! and split using the same **Curve** API, and to be appended to a **path::Builder** without loss.
+
+use crate::geom::curve::{CubicBezier, Curve, Split};
+use crate::geom::{scalar, Point2};
+use crate::math::num_traits::cast;
+use crate::math::{BaseFloat, InnerSpace};
+
+/// A piecewise curve made up of a sequence of cubic bezier segments.
+///
+/// The curve parameter `t` within the range `0.0..=1.0` is distributed evenly between segments,
+/// such that each segment covers a range of `1.0 / num_segments`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spline<V = Point2<scalar::Default>> {
+    segments: Vec<CubicBezier<V>>,
+}
+
+impl<V, S> Spline<V>
+where
+    V: InnerSpace<Scalar = S>,
+    S: BaseFloat,
+{
+    /// Construct a spline from the given sequence of cubic bezier segments.
+    ///
+    /// Each segment is expected to begin where the previous segment ends.
+    pub fn from_segments<I>(segments: I) -> Self
+    where
+        I: IntoIterator<Item = CubicBezier<V>>,
+    {
+        let segments = segments.into_iter().collect();
+        Spline { segments }
+    }
+
+    /// A catmull-rom spline passing through each of the given points.
+    ///
+    /// `alpha` describes the parametrisation of the spline: `0.0` produces a uniform catmull-rom
+    /// spline, `0.5` a centripetal spline and `1.0` a chordal spline. The centripetal variant
+    /// avoids the cusps and self-intersections that uniform splines may form around sharp turns.
+    ///
+    /// Produces an empty spline if less than two points are given.
+    pub fn catmull_rom<I>(points: I, alpha: S) -> Self
+    where
+        I: IntoIterator<Item = V>,
+    {
+        let points: Vec<V> = points.into_iter().collect();
+        if points.len() < 2 {
+            return Self::from_segments(None);
+        }
+        // Extrapolate the end points so that the spline reaches the first and last points.
+        let n = points.len();
+        let two = cast::<_, S>(2.0).unwrap();
+        let first = points[0] * two - points[1];
+        let last = points[n - 1] * two - points[n - 2];
+        let point = |i: isize| -> V {
+            if i < 0 {
+                first
+            } else if i as usize >= n {
+                last
+            } else {
+                points[i as usize]
+            }
+        };
+        let segments = (0..n as isize - 1).map(|i| {
+            catmull_rom_segment(point(i - 1), point(i), point(i + 1), point(i + 2), alpha)
+        });
+        Self::from_segments(segments)
+    }
+
+    /// A closed catmull-rom spline passing through each of the given points and returning to the
+    /// first.
+    ///
+    /// See `catmull_rom` for details on `alpha`.
+    ///
+    /// Produces an empty spline if less than two points are given.
+    pub fn catmull_rom_closed<I>(points: I, alpha: S) -> Self
+    where
+        I: IntoIterator<Item = V>,
+    {
+        let points: Vec<V> = points.into_iter().collect();
+        if points.len() < 2 {
+            return Self::from_segments(None);
+        }
+        let n = points.len();
+        let point = |i: usize| points[i % n];
+        let segments = (0..n).map(|i| {
+            catmull_rom_segment(
+                point(i + n - 1),
+                point(i),
+                point(i + 1),
+                point(i + 2),
+                alpha,
+            )
+        });
+        Self::from_segments(segments)
+    }
+
+    /// A uniform cubic b-spline guided by the given control points.
+    ///
+    /// The first and last control points are repeated so that the spline begins and ends at
+    /// these points. The spline does not generally pass through the other control points.
+    ///
+    /// Produces an empty spline if less than two points are given.
+    pub fn b_spline<I>(points: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+    {
+        let points: Vec<V> = points.into_iter().collect();
+        if points.len() < 2 {
+            return Self::from_segments(None);
+        }
+        let n = points.len() as isize;
+        let point = |i: isize| points[i.max(0).min(n - 1) as usize];
+        let segments = (-2..n - 1)
+            .map(|i| b_spline_segment(point(i), point(i + 1), point(i + 2), point(i + 3)));
+        Self::from_segments(segments)
+    }
+
+    /// A closed uniform cubic b-spline guided by the given control points.
+    ///
+    /// Produces an empty spline if less than three points are given.
+    pub fn b_spline_closed<I>(points: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+    {
+        let points: Vec<V> = points.into_iter().collect();
+        if points.len() < 3 {
+            return Self::from_segments(None);
+        }
+        let n = points.len();
+        let point = |i: usize| points[i % n];
+        let segments = (0..n)
+            .map(|i| b_spline_segment(point(i + n - 1), point(i), point(i + 1), point(i + 2)));
+        Self::from_segments(segments)
+    }
+
+    /// A cubic hermite spline passing through each of the given `(point, tangent)` knots.
+    ///
+    /// Produces an empty spline if less than two knots are given.
+    pub fn hermite<I>(knots: I) -> Self
+    where
+        I: IntoIterator<Item = (V, V)>,
+    {
+        let knots: Vec<(V, V)> = knots.into_iter().collect();
+        let segments = knots.windows(2).map(|w| {
+            let ((from, from_tangent), (to, to_tangent)) = (w[0], w[1]);
+            CubicBezier::hermite(from, from_tangent, to, to_tangent)
+        });
+        Self::from_segments(segments)
+    }
+
+    /// The cubic bezier segments that make up the spline.
+    pub fn segments(&self) -> &[CubicBezier<V>] {
+        &self.segments
+    }
+
+    /// Consume the spline and return its cubic bezier segments.
+    pub fn into_segments(self) -> Vec<CubicBezier<V>> {
+        self.segments
+    }
+
+    /// Whether or not the spline contains any segments.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    // Map the given spline parameter to a segment index and the parameter within that segment.
+    fn segment_t(&self, t: S) -> (usize, S) {
+        let zero = S::zero();
+        let one = S::one();
+        let n = self.segments.len();
+        let t = t.max(zero).min(one);
+        let pos = t * cast(n).unwrap();
+        let ix = std::cmp::min(pos.floor().to_usize().unwrap(), n - 1);
+        (ix, pos - cast(ix).unwrap())
+    }
+}
+
+impl<V, S> Curve for Spline<V>
+where
+    V: InnerSpace<Scalar = S>,
+    S: BaseFloat,
+{
+    type Scalar = S;
+    type Point = V;
+
+    /// The position along the spline at `t`.
+    ///
+    /// Returns a zero vector if the spline is empty.
+    fn point(&self, t: Self::Scalar) -> Self::Point {
+        if self.segments.is_empty() {
+            return V::zero();
+        }
+        let (ix, t) = self.segment_t(t);
+        self.segments[ix].point(t)
+    }
+
+    fn derivative(&self, t: Self::Scalar) -> Self::Point {
+        if self.segments.is_empty() {
+            return V::zero();
+        }
+        let (ix, t) = self.segment_t(t);
+        // Account for each segment only covering a portion of the spline parameter range.
+        self.segments[ix].derivative(t) * cast(self.segments.len()).unwrap()
+    }
+}
+
+impl<V, S> Split for Spline<V>
+where
+    V: InnerSpace<Scalar = S>,
+    S: BaseFloat,
+{
+    /// Split the spline at the given parameter `t`.
+    ///
+    /// Note that as each spline distributes `t` evenly between segments, the resulting splines
+    /// will describe the same shape but may not share the original parametrisation.
+    fn split(&self, t: Self::Scalar) -> (Self, Self) {
+        if self.segments.is_empty() {
+            return (self.clone(), self.clone());
+        }
+        let (ix, t) = self.segment_t(t);
+        let (a, b) = self.segments[ix].split(t);
+        let mut head = self.segments[..ix].to_vec();
+        head.push(a);
+        let mut tail = vec![b];
+        tail.extend(self.segments[ix + 1..].iter().cloned());
+        (Spline { segments: head }, Spline { segments: tail })
+    }
+
+    fn trim(&self, start: Self::Scalar, end: Self::Scalar) -> Self {
+        if self.segments.is_empty() {
+            return self.clone();
+        }
+        let (start, end) = if start <= end {
+            (start, end)
+        } else {
+            (end, start)
+        };
+        let (start_ix, start_t) = self.segment_t(start);
+        let (end_ix, end_t) = self.segment_t(end);
+        if start_ix == end_ix {
+            let segment = self.segments[start_ix].trim(start_t, end_t);
+            return Spline {
+                segments: vec![segment],
+            };
+        }
+        let mut segments = vec![self.segments[start_ix].split(start_t).1];
+        segments.extend(self.segments[start_ix + 1..end_ix].iter().cloned());
+        segments.push(self.segments[end_ix].split(end_t).0);
+        Spline { segments }
+    }
+}
+
+// The cubic bezier describing the catmull-rom segment between `p1` and `p2`.
+//
+// Tangents are calculated for a non-uniform parametrisation determined by `alpha` and then scaled
+// to the unit parameter range of the segment.
+fn catmull_rom_segment<V, S>(p0: V, p1: V, p2: V, p3: V, alpha: S) -> CubicBezier<V>
+where
+    V: InnerSpace<Scalar = S>,
+    S: BaseFloat,
+{
+    let knot = |a: V, b: V| (b - a).magnitude().powf(alpha);
+    let eps = S::epsilon();
+    let mut dt1 = knot(p1, p2);
+    if dt1 <= eps {
+        dt1 = S::one();
+    }
+    let mut dt0 = knot(p0, p1);
+    if dt0 <= eps {
+        dt0 = dt1;
+    }
+    let mut dt2 = knot(p2, p3);
+    if dt2 <= eps {
+        dt2 = dt1;
+    }
+    let m1 = (p1 - p0) / dt0 - (p2 - p0) / (dt0 + dt1) + (p2 - p1) / dt1;
+    let m2 = (p2 - p1) / dt1 - (p3 - p1) / (dt1 + dt2) + (p3 - p2) / dt2;
+    CubicBezier::hermite(p1, m1 * dt1, p2, m2 * dt1)
+}
+
+// The cubic bezier describing the uniform b-spline segment with the given control points.
+fn b_spline_segment<V, S>(p0: V, p1: V, p2: V, p3: V) -> CubicBezier<V>
+where
+    V: InnerSpace<Scalar = S>,
+    S: BaseFloat,
+{
+    let two = cast::<_, S>(2.0).unwrap();
+    let three = cast::<_, S>(3.0).unwrap();
+    let four = cast::<_, S>(4.0).unwrap();
+    let six = cast::<_, S>(6.0).unwrap();
+    CubicBezier {
+        from: (p0 + p1 * four + p2) / six,
+        ctrl1: (p1 * two + p2) / three,
+        ctrl2: (p1 + p2 * two) / three,
+        to: (p1 + p2 * four + p3) / six,
+    }
+}
@@ -1,6 +1,6 @@
-use crate::geom::{vertex, Cuboid, Point2, Range, Rect, Vertex, Vertex2d, Vertex3d};
-use crate::math::{BaseNum, EuclideanSpace, Zero};
-use std::ops::Deref;
+use crate::geom::{polygon, vertex, Cuboid, Point2, Range, Rect, Vertex, Vertex2d, Vertex3d};
+use crate::math::{BaseFloat, BaseNum, EuclideanSpace, Zero};
+use std::ops::{Deref, Neg};
 
 /// The number of vertices in a triangle.
 pub const NUM_VERTICES: u8 = 3;
@@ -121,6 +121,46 @@ impl<V> Tri<V> {
         (b1 == b2) && (b2 == b3)
     }
 
+    /// The signed area of the triangle.
+    ///
+    /// The area is positive if the vertices are ordered counter-clockwise.
+    pub fn signed_area(&self) -> V::Scalar
+    where
+        V: Vertex2d,
+        V::Scalar: Neg<Output = V::Scalar>,
+    {
+        polygon::signed_area(self.0.iter().cloned())
+    }
+
+    /// The area of the triangle.
+    pub fn area(&self) -> V::Scalar
+    where
+        V: Vertex2d,
+        V::Scalar: Neg<Output = V::Scalar>,
+    {
+        polygon::area(self.0.iter().cloned())
+    }
+
+    /// The length of the outline of the triangle.
+    pub fn perimeter(&self) -> V::Scalar
+    where
+        V: Vertex2d,
+        V::Scalar: BaseFloat,
+    {
+        polygon::perimeter(self.0.iter().cloned())
+    }
+
+    /// The direction in which the triangle's vertices are ordered.
+    ///
+    /// Returns `None` if the triangle has no area.
+    pub fn winding(&self) -> Option<polygon::Winding>
+    where
+        V: Vertex2d,
+        V::Scalar: Neg<Output = V::Scalar>,
+    {
+        polygon::winding(self.0.iter().cloned())
+    }
+
     /// The bounding `Rect` of the triangle.
     pub fn bounding_rect(self) -> Rect<V::Scalar>
     where
@@ -0,0 +1,151 @@
+//! Reading and writing meshes from and to OBJ, PLY and STL files.
+//!
+//! All formats are loaded into the **TriMesh** type - a composition of the `WithIndices`,
+//! `WithColors`, `WithTexCoords` and `WithNormals` mesh adaptors. The **TriMesh** may be passed to
+//! any of the functions within the `mesh` module, or drawn directly via the `Draw` API:
+//!
+//! ```no_run
+//! # use nannou::prelude::*;
+//! # fn view(app: &App, frame: Frame) {
+//! let draw = app.draw();
+//! let mesh = nannou::mesh::file::load("teapot.obj").unwrap();
+//! draw.mesh()
+//!     .indexed_colored(mesh.colored_points(), mesh.vertex_indices());
+//! # }
+//! ```
+//!
+//! Each format supports a different set of channels:
+//!
+//! - **OBJ**: points, texture coordinates, normals and (non-standard) vertex colors.
+//! - **PLY**: points, texture coordinates, normals and colors, stored as ASCII or binary.
+//! - **STL**: points and per-face normals, stored as ASCII or binary.
+//!
+//! Channels that are not present within a file are filled with default values. The `has_colors`,
+//! `has_tex_coords` and `has_normals` methods may be used to check which channels were loaded.
+
+use crate::color::{self, LinSrgba};
+use crate::mesh::TriMesh;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+pub mod obj;
+pub mod ply;
+pub mod stl;
+
+/// The file formats supported by the `file` module.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// The Wavefront OBJ format.
+    Obj,
+    /// The Polygon File Format, also known as the Stanford Triangle Format.
+    Ply,
+    /// The stereolithography format commonly used for 3D printing.
+    Stl,
+}
+
+/// The encoding used when writing formats that support both ASCII and binary representations.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// Human readable text.
+    Ascii,
+    /// A compact binary representation.
+    Binary,
+}
+
+/// Errors that might occur while reading or writing a mesh file.
+#[derive(Debug)]
+pub enum Error {
+    /// An error occurred while reading or writing the underlying file or stream.
+    Io(io::Error),
+    /// The file format could not be determined from the file extension.
+    UnknownFormat,
+    /// The contents of the file are not valid for the format.
+    Invalid(String),
+}
+
+impl Format {
+    /// Determine the format from the extension of the given path.
+    ///
+    /// Returns `None` if the extension is unknown.
+    pub fn from_path<P>(path: P) -> Option<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match &ext[..] {
+            "obj" => Some(Format::Obj),
+            "ply" => Some(Format::Ply),
+            "stl" => Some(Format::Stl),
+            _ => None,
+        }
+    }
+}
+
+/// Load a mesh from the file at the given path.
+///
+/// The format is determined by the file extension.
+pub fn load<P>(path: P) -> Result<TriMesh, Error>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let format = Format::from_path(path).ok_or(Error::UnknownFormat)?;
+    let file = fs::File::open(path)?;
+    let reader = io::BufReader::new(file);
+    match format {
+        Format::Obj => obj::read(reader),
+        Format::Ply => ply::read(reader),
+        Format::Stl => stl::read(reader),
+    }
+}
+
+/// Save the given mesh to a file at the given path.
+///
+/// The format is determined by the file extension. PLY and STL files are written using the given
+/// `encoding`, while OBJ files are always written as ASCII.
+pub fn save<P>(path: P, mesh: &TriMesh, encoding: Encoding) -> Result<(), Error>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let format = Format::from_path(path).ok_or(Error::UnknownFormat)?;
+    let file = fs::File::create(path)?;
+    let mut writer = io::BufWriter::new(file);
+    match format {
+        Format::Obj => obj::write(mesh, &mut writer)?,
+        Format::Ply => ply::write(mesh, &mut writer, encoding)?,
+        Format::Stl => stl::write(mesh, &mut writer, encoding)?,
+    }
+    io::Write::flush(&mut writer)?;
+    Ok(())
+}
+
+// Convert a linear color to non-linear sRGB for writing.
+fn srgba(color: LinSrgba) -> color::Srgba {
+    color::Srgba::from_linear(color)
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl std::error::Error for Error {
+    fn cause(&self) -> Option<&dyn std::error::Error> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            Error::UnknownFormat | Error::Invalid(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => fmt::Display::fmt(err, f),
+            Error::UnknownFormat => write!(f, "unknown mesh file format"),
+            Error::Invalid(ref reason) => write!(f, "invalid mesh file: {}", reason),
+        }
+    }
+}
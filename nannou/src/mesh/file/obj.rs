@@ -0,0 +1,239 @@
+//! Reading and writing Wavefront OBJ files.
+//!
+//! Supports `v`, `vt`, `vn` and `f` statements. Faces with more than three vertices are
+//! triangulated as a fan. Vertex colors are read from and written to the commonly supported
+//! `v x y z r g b` extension. All other statements (e.g. groups and materials) are ignored.
+
+use super::Error;
+use crate::color::{self, IntoLinSrgba, LinSrgba};
+use crate::draw::mesh::vertex::DEFAULT_VERTEX_COLOR;
+use crate::geom::{Point2, Point3, Vector3};
+use crate::mesh::TriMesh;
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+// The indices of the point, texture coordinates and normal of a single face vertex.
+type FaceVertex = (usize, Option<usize>, Option<usize>);
+
+/// Read an OBJ file from the given reader.
+pub fn read<R>(reader: R) -> Result<TriMesh, Error>
+where
+    R: BufRead,
+{
+    let mut positions: Vec<Point3<f32>> = vec![];
+    let mut position_colors: Vec<Option<LinSrgba>> = vec![];
+    let mut tex_coords: Vec<Point2<f32>> = vec![];
+    let mut normals: Vec<Vector3<f32>> = vec![];
+
+    // OBJ indexes each attribute separately, so each unique combination becomes a vertex.
+    let mut vertex_map: HashMap<FaceVertex, u32> = HashMap::new();
+    let mut vertices: Vec<FaceVertex> = vec![];
+    let mut indices: Vec<u32> = vec![];
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = i + 1;
+        let line = match line.find('#') {
+            Some(ix) => &line[..ix],
+            None => &line[..],
+        };
+        let mut words = line.split_whitespace();
+        let keyword = match words.next() {
+            None => continue,
+            Some(word) => word,
+        };
+        match keyword {
+            "v" => {
+                let values = parse_floats(words, line_number)?;
+                if values.len() < 3 {
+                    return Err(invalid(line_number, "vertex requires three coordinates"));
+                }
+                positions.push(Point3 {
+                    x: values[0],
+                    y: values[1],
+                    z: values[2],
+                });
+                let color = match values.len() {
+                    6 | 7 => {
+                        let srgb = color::Srgb::new(values[3], values[4], values[5]);
+                        Some(srgb.into_lin_srgba())
+                    }
+                    _ => None,
+                };
+                position_colors.push(color);
+            }
+            "vt" => {
+                let values = parse_floats(words, line_number)?;
+                if values.is_empty() {
+                    return Err(invalid(line_number, "texture coordinates require a value"));
+                }
+                let y = values.get(1).cloned().unwrap_or(0.0);
+                tex_coords.push(Point2 { x: values[0], y });
+            }
+            "vn" => {
+                let values = parse_floats(words, line_number)?;
+                if values.len() < 3 {
+                    return Err(invalid(line_number, "normal requires three coordinates"));
+                }
+                normals.push(Vector3 {
+                    x: values[0],
+                    y: values[1],
+                    z: values[2],
+                });
+            }
+            "f" => {
+                let mut face = vec![];
+                for word in words {
+                    let face_vertex = parse_face_vertex(
+                        word,
+                        positions.len(),
+                        tex_coords.len(),
+                        normals.len(),
+                        line_number,
+                    )?;
+                    let index = *vertex_map.entry(face_vertex).or_insert_with(|| {
+                        vertices.push(face_vertex);
+                        vertices.len() as u32 - 1
+                    });
+                    face.push(index);
+                }
+                if face.len() < 3 {
+                    return Err(invalid(
+                        line_number,
+                        "face requires at least three vertices",
+                    ));
+                }
+                for i in 1..face.len() - 1 {
+                    indices.extend_from_slice(&[face[0], face[i], face[i + 1]]);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    let points = vertices.iter().map(|&(p, _, _)| positions[p]);
+    let mut mesh = TriMesh::new(points, indices);
+    if position_colors.iter().any(Option::is_some) {
+        let colors = vertices
+            .iter()
+            .map(|&(p, _, _)| position_colors[p].unwrap_or(DEFAULT_VERTEX_COLOR));
+        mesh = mesh.with_colors(colors);
+    }
+    if vertices.iter().any(|&(_, t, _)| t.is_some()) {
+        let zero = Point2 { x: 0.0, y: 0.0 };
+        let coords = vertices
+            .iter()
+            .map(|&(_, t, _)| t.map(|t| tex_coords[t]).unwrap_or(zero));
+        mesh = mesh.with_tex_coords(coords);
+    }
+    if vertices.iter().any(|&(_, _, n)| n.is_some()) {
+        let normals = vertices
+            .iter()
+            .map(|&(_, _, n)| n.map(|n| normals[n]).unwrap_or(Vector3::zero()));
+        mesh = mesh.with_normals(normals);
+    }
+    Ok(mesh)
+}
+
+/// Write the given mesh to the given writer in the OBJ format.
+///
+/// Only the channels that were loaded or assigned to the mesh are written.
+pub fn write<W>(mesh: &TriMesh, mut writer: W) -> io::Result<()>
+where
+    W: Write,
+{
+    for (i, p) in mesh.points().iter().enumerate() {
+        if mesh.has_colors() {
+            let c = super::srgba(mesh.colors()[i]);
+            writeln!(
+                writer,
+                "v {} {} {} {} {} {}",
+                p.x, p.y, p.z, c.red, c.green, c.blue
+            )?;
+        } else {
+            writeln!(writer, "v {} {} {}", p.x, p.y, p.z)?;
+        }
+    }
+    if mesh.has_tex_coords() {
+        for t in mesh.tex_coords() {
+            writeln!(writer, "vt {} {}", t.x, t.y)?;
+        }
+    }
+    if mesh.has_normals() {
+        for n in mesh.normals() {
+            writeln!(writer, "vn {} {} {}", n.x, n.y, n.z)?;
+        }
+    }
+    for tri in mesh.indices().chunks(3) {
+        write!(writer, "f")?;
+        for &i in tri {
+            // OBJ indices begin at `1`.
+            let i = i + 1;
+            match (mesh.has_tex_coords(), mesh.has_normals()) {
+                (false, false) => write!(writer, " {}", i)?,
+                (true, false) => write!(writer, " {}/{}", i, i)?,
+                (false, true) => write!(writer, " {}//{}", i, i)?,
+                (true, true) => write!(writer, " {}/{}/{}", i, i, i)?,
+            }
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+fn invalid(line_number: usize, reason: &str) -> Error {
+    Error::Invalid(format!("line {}: {}", line_number, reason))
+}
+
+fn parse_floats<'a, I>(words: I, line_number: usize) -> Result<Vec<f32>, Error>
+where
+    I: Iterator<Item = &'a str>,
+{
+    words
+        .map(|word| {
+            word.parse()
+                .map_err(|_| invalid(line_number, &format!("invalid number `{}`", word)))
+        })
+        .collect()
+}
+
+// Parse a single `v`, `v/vt`, `v//vn` or `v/vt/vn` face vertex, resolving relative indices.
+fn parse_face_vertex(
+    word: &str,
+    positions: usize,
+    tex_coords: usize,
+    normals: usize,
+    line_number: usize,
+) -> Result<FaceVertex, Error> {
+    let mut parts = word.split('/');
+    let position = match parts.next() {
+        Some(s) if !s.is_empty() => resolve_index(s, positions, line_number)?,
+        _ => {
+            return Err(invalid(
+                line_number,
+                "face vertex requires a position index",
+            ))
+        }
+    };
+    let tex_coord = match parts.next() {
+        Some(s) if !s.is_empty() => Some(resolve_index(s, tex_coords, line_number)?),
+        _ => None,
+    };
+    let normal = match parts.next() {
+        Some(s) if !s.is_empty() => Some(resolve_index(s, normals, line_number)?),
+        _ => None,
+    };
+    Ok((position, tex_coord, normal))
+}
+
+// Convert a one-based or negative (relative to the end) OBJ index to a zero-based index.
+fn resolve_index(s: &str, len: usize, line_number: usize) -> Result<usize, Error> {
+    let i: isize = s
+        .parse()
+        .map_err(|_| invalid(line_number, &format!("invalid index `{}`", s)))?;
+    let index = if i < 0 { len as isize + i } else { i - 1 };
+    if index < 0 || index as usize >= len {
+        return Err(invalid(line_number, &format!("index `{}` out of range", s)));
+    }
+    Ok(index as usize)
+}
@@ -0,0 +1,476 @@
+//! Reading and writing PLY (Polygon File Format) files.
+//!
+//! Supports the `ascii`, `binary_little_endian` and `binary_big_endian` formats. The `vertex`
+//! element may provide positions (`x`, `y`, `z`), normals (`nx`, `ny`, `nz`), texture coordinates
+//! (`s`/`t`, `u`/`v` or `texture_u`/`texture_v`) and colors (`red`, `green`, `blue` and optionally
+//! `alpha`). The `face` element must provide a `vertex_indices` (or `vertex_index`) list. Faces
+//! with more than three vertices are triangulated as a fan. All other elements and properties are
+//! ignored.
+
+use super::{Encoding, Error};
+use crate::color::{self, IntoLinSrgba, LinSrgba};
+use crate::geom::{Point2, Point3, Vector3};
+use crate::mesh::TriMesh;
+use std::io::{self, BufRead, Read, Write};
+
+// The byte order of a binary PLY body.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+// The scalar types supported by PLY properties.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum PropertyKind {
+    Scalar(Scalar),
+    List { count: Scalar, item: Scalar },
+}
+
+#[derive(Clone, Debug)]
+struct Property {
+    name: String,
+    kind: PropertyKind,
+}
+
+#[derive(Clone, Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+// Reads property values from the body of the file.
+enum Body<'a, R> {
+    Ascii(std::str::SplitWhitespace<'a>),
+    Binary { reader: R, big_endian: bool },
+}
+
+/// Read a PLY file from the given reader.
+pub fn read<R>(mut reader: R) -> Result<TriMesh, Error>
+where
+    R: BufRead,
+{
+    let (format, elements) = read_header(&mut reader)?;
+    let ascii;
+    let mut body = match format {
+        Format::Ascii => {
+            let mut s = String::new();
+            reader.read_to_string(&mut s)?;
+            ascii = s;
+            Body::Ascii(ascii.split_whitespace())
+        }
+        Format::BinaryLittleEndian => Body::Binary {
+            reader,
+            big_endian: false,
+        },
+        Format::BinaryBigEndian => Body::Binary {
+            reader,
+            big_endian: true,
+        },
+    };
+
+    let mut points = vec![];
+    let mut normals = vec![];
+    let mut tex_coords = vec![];
+    let mut colors: Vec<LinSrgba> = vec![];
+    let mut indices = vec![];
+    let (mut has_normals, mut has_tex_coords, mut has_colors) = (false, false, false);
+
+    for element in &elements {
+        match &element.name[..] {
+            "vertex" => {
+                let find = |names: &[&str]| {
+                    element
+                        .properties
+                        .iter()
+                        .position(|p| names.contains(&&p.name[..]))
+                };
+                let x = find(&["x"]);
+                let y = find(&["y"]);
+                let z = find(&["z"]);
+                let nx = find(&["nx"]);
+                let ny = find(&["ny"]);
+                let nz = find(&["nz"]);
+                let s = find(&["s", "u", "texture_u", "texture_s"]);
+                let t = find(&["t", "v", "texture_v", "texture_t"]);
+                let red = find(&["red", "r"]);
+                let green = find(&["green", "g"]);
+                let blue = find(&["blue", "b"]);
+                let alpha = find(&["alpha", "a"]);
+                has_normals = nx.is_some() && ny.is_some() && nz.is_some();
+                has_tex_coords = s.is_some() && t.is_some();
+                has_colors = red.is_some() && green.is_some() && blue.is_some();
+                let mut values = vec![0.0; element.properties.len()];
+                for _ in 0..element.count {
+                    for (i, property) in element.properties.iter().enumerate() {
+                        values[i] = match property.kind {
+                            PropertyKind::Scalar(ty) => body.read(ty)?,
+                            // Skip any list properties.
+                            PropertyKind::List { count, item } => {
+                                let n = body.read(count)? as usize;
+                                for _ in 0..n {
+                                    body.read(item)?;
+                                }
+                                0.0
+                            }
+                        };
+                    }
+                    let get = |ix: Option<usize>| ix.map(|ix| values[ix] as f32).unwrap_or(0.0);
+                    points.push(Point3 {
+                        x: get(x),
+                        y: get(y),
+                        z: get(z),
+                    });
+                    if has_normals {
+                        normals.push(Vector3 {
+                            x: get(nx),
+                            y: get(ny),
+                            z: get(nz),
+                        });
+                    }
+                    if has_tex_coords {
+                        tex_coords.push(Point2 {
+                            x: get(s),
+                            y: get(t),
+                        });
+                    }
+                    if has_colors {
+                        let channel = |ix: Option<usize>| match ix {
+                            None => 1.0,
+                            Some(ix) => match element.properties[ix].kind {
+                                PropertyKind::Scalar(Scalar::U8) => values[ix] as f32 / 255.0,
+                                PropertyKind::Scalar(Scalar::U16) => values[ix] as f32 / 65535.0,
+                                _ => values[ix] as f32,
+                            },
+                        };
+                        let srgba = color::Srgba::new(
+                            channel(red),
+                            channel(green),
+                            channel(blue),
+                            channel(alpha),
+                        );
+                        colors.push(srgba.into_lin_srgba());
+                    }
+                }
+            }
+            "face" => {
+                let list = element
+                    .properties
+                    .iter()
+                    .position(|p| p.name == "vertex_indices" || p.name == "vertex_index");
+                let list = list.ok_or_else(|| {
+                    Error::Invalid("face element has no `vertex_indices` list".to_string())
+                })?;
+                let mut face = vec![];
+                for _ in 0..element.count {
+                    for (i, property) in element.properties.iter().enumerate() {
+                        match property.kind {
+                            PropertyKind::Scalar(ty) => {
+                                body.read(ty)?;
+                            }
+                            PropertyKind::List { count, item } => {
+                                let n = body.read(count)? as usize;
+                                face.clear();
+                                for _ in 0..n {
+                                    face.push(body.read(item)? as u32);
+                                }
+                                if i == list {
+                                    for j in 1..face.len().saturating_sub(1) {
+                                        indices.extend_from_slice(&[face[0], face[j], face[j + 1]]);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {
+                for _ in 0..element.count {
+                    for property in &element.properties {
+                        match property.kind {
+                            PropertyKind::Scalar(ty) => {
+                                body.read(ty)?;
+                            }
+                            PropertyKind::List { count, item } => {
+                                let n = body.read(count)? as usize;
+                                for _ in 0..n {
+                                    body.read(item)?;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(&i) = indices.iter().find(|&&i| i as usize >= points.len()) {
+        return Err(Error::Invalid(format!("face index `{}` out of range", i)));
+    }
+    let mut mesh = TriMesh::new(points, indices);
+    if has_colors {
+        mesh = mesh.with_colors(colors);
+    }
+    if has_tex_coords {
+        mesh = mesh.with_tex_coords(tex_coords);
+    }
+    if has_normals {
+        mesh = mesh.with_normals(normals);
+    }
+    Ok(mesh)
+}
+
+/// Write the given mesh to the given writer in the PLY format using the given encoding.
+///
+/// Binary files are written in little endian byte order. Only the channels that were loaded or
+/// assigned to the mesh are written.
+pub fn write<W>(mesh: &TriMesh, mut writer: W, encoding: Encoding) -> io::Result<()>
+where
+    W: Write,
+{
+    let format = match encoding {
+        Encoding::Ascii => "ascii",
+        Encoding::Binary => "binary_little_endian",
+    };
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format {} 1.0", format)?;
+    writeln!(writer, "comment Created by nannou")?;
+    writeln!(writer, "element vertex {}", mesh.points().len())?;
+    for name in &["x", "y", "z"] {
+        writeln!(writer, "property float {}", name)?;
+    }
+    if mesh.has_normals() {
+        for name in &["nx", "ny", "nz"] {
+            writeln!(writer, "property float {}", name)?;
+        }
+    }
+    if mesh.has_tex_coords() {
+        for name in &["s", "t"] {
+            writeln!(writer, "property float {}", name)?;
+        }
+    }
+    if mesh.has_colors() {
+        for name in &["red", "green", "blue", "alpha"] {
+            writeln!(writer, "property uchar {}", name)?;
+        }
+    }
+    writeln!(writer, "element face {}", mesh.indices().len() / 3)?;
+    writeln!(writer, "property list uchar uint vertex_indices")?;
+    writeln!(writer, "end_header")?;
+
+    for i in 0..mesh.points().len() {
+        let p = mesh.points()[i];
+        let mut floats = vec![p.x, p.y, p.z];
+        if mesh.has_normals() {
+            let n = mesh.normals()[i];
+            floats.extend_from_slice(&[n.x, n.y, n.z]);
+        }
+        if mesh.has_tex_coords() {
+            let t = mesh.tex_coords()[i];
+            floats.extend_from_slice(&[t.x, t.y]);
+        }
+        let color = if mesh.has_colors() {
+            let c = super::srgba(mesh.colors()[i]).into_format::<u8, u8>();
+            Some([c.red, c.green, c.blue, c.alpha])
+        } else {
+            None
+        };
+        match encoding {
+            Encoding::Ascii => {
+                let mut words: Vec<String> = floats.iter().map(|f| f.to_string()).collect();
+                if let Some(c) = color {
+                    words.extend(c.iter().map(|c| c.to_string()));
+                }
+                writeln!(writer, "{}", words.join(" "))?;
+            }
+            Encoding::Binary => {
+                for f in floats {
+                    writer.write_all(&f.to_le_bytes())?;
+                }
+                if let Some(c) = color {
+                    writer.write_all(&c)?;
+                }
+            }
+        }
+    }
+
+    for tri in mesh.indices().chunks(3) {
+        match encoding {
+            Encoding::Ascii => writeln!(writer, "3 {} {} {}", tri[0], tri[1], tri[2])?,
+            Encoding::Binary => {
+                writer.write_all(&[3])?;
+                for i in tri {
+                    writer.write_all(&i.to_le_bytes())?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Read the header, leaving the reader at the beginning of the body.
+fn read_header<R>(reader: &mut R) -> Result<(Format, Vec<Element>), Error>
+where
+    R: BufRead,
+{
+    let mut line = String::new();
+    let mut read_line = |line: &mut String| -> Result<(), Error> {
+        line.clear();
+        if reader.read_line(line)? == 0 {
+            return Err(Error::Invalid("unexpected end of PLY header".to_string()));
+        }
+        Ok(())
+    };
+
+    read_line(&mut line)?;
+    if line.trim() != "ply" {
+        return Err(Error::Invalid("missing `ply` magic number".to_string()));
+    }
+
+    let mut format = None;
+    let mut elements: Vec<Element> = vec![];
+    loop {
+        read_line(&mut line)?;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.get(0).cloned() {
+            Some("format") => {
+                format = match words.get(1).cloned() {
+                    Some("ascii") => Some(Format::Ascii),
+                    Some("binary_little_endian") => Some(Format::BinaryLittleEndian),
+                    Some("binary_big_endian") => Some(Format::BinaryBigEndian),
+                    _ => return Err(Error::Invalid(format!("unknown format: {}", line.trim()))),
+                };
+            }
+            Some("element") => {
+                let (name, count) = match (words.get(1), words.get(2)) {
+                    (Some(name), Some(count)) => (name, count),
+                    _ => return Err(Error::Invalid(format!("invalid element: {}", line.trim()))),
+                };
+                let count = count
+                    .parse()
+                    .map_err(|_| Error::Invalid(format!("invalid element count: {}", count)))?;
+                let name = name.to_string();
+                let properties = vec![];
+                elements.push(Element {
+                    name,
+                    count,
+                    properties,
+                });
+            }
+            Some("property") => {
+                let element = elements.last_mut().ok_or_else(|| {
+                    Error::Invalid("property declared before any element".to_string())
+                })?;
+                let property = match &words[1..] {
+                    &["list", count, item, name] => Property {
+                        name: name.to_string(),
+                        kind: PropertyKind::List {
+                            count: parse_scalar(count)?,
+                            item: parse_scalar(item)?,
+                        },
+                    },
+                    &[ty, name] => Property {
+                        name: name.to_string(),
+                        kind: PropertyKind::Scalar(parse_scalar(ty)?),
+                    },
+                    _ => return Err(Error::Invalid(format!("invalid property: {}", line.trim()))),
+                };
+                element.properties.push(property);
+            }
+            Some("end_header") => break,
+            // Skip comments, `obj_info` and empty lines.
+            _ => (),
+        }
+    }
+
+    let format = format.ok_or_else(|| Error::Invalid("missing PLY format".to_string()))?;
+    Ok((format, elements))
+}
+
+fn parse_scalar(s: &str) -> Result<Scalar, Error> {
+    let scalar = match s {
+        "char" | "int8" => Scalar::I8,
+        "uchar" | "uint8" => Scalar::U8,
+        "short" | "int16" => Scalar::I16,
+        "ushort" | "uint16" => Scalar::U16,
+        "int" | "int32" => Scalar::I32,
+        "uint" | "uint32" => Scalar::U32,
+        "float" | "float32" => Scalar::F32,
+        "double" | "float64" => Scalar::F64,
+        _ => return Err(Error::Invalid(format!("unknown property type `{}`", s))),
+    };
+    Ok(scalar)
+}
+
+impl Scalar {
+    fn size(self) -> usize {
+        match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        }
+    }
+}
+
+impl<'a, R> Body<'a, R>
+where
+    R: Read,
+{
+    // Read the next value of the given type.
+    fn read(&mut self, ty: Scalar) -> Result<f64, Error> {
+        match *self {
+            Body::Ascii(ref mut words) => {
+                let word = words
+                    .next()
+                    .ok_or_else(|| Error::Invalid("unexpected end of PLY body".to_string()))?;
+                word.parse()
+                    .map_err(|_| Error::Invalid(format!("invalid PLY value `{}`", word)))
+            }
+            Body::Binary {
+                ref mut reader,
+                big_endian,
+            } => {
+                let mut bytes = [0u8; 8];
+                let bytes = &mut bytes[..ty.size()];
+                reader.read_exact(bytes)?;
+                if big_endian {
+                    bytes.reverse();
+                }
+                let value = match ty {
+                    Scalar::I8 => bytes[0] as i8 as f64,
+                    Scalar::U8 => bytes[0] as f64,
+                    Scalar::I16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+                    Scalar::U16 => u16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+                    Scalar::I32 => i32::from_le_bytes(four(bytes)) as f64,
+                    Scalar::U32 => u32::from_le_bytes(four(bytes)) as f64,
+                    Scalar::F32 => f32::from_le_bytes(four(bytes)) as f64,
+                    Scalar::F64 => {
+                        let mut b = [0u8; 8];
+                        b.copy_from_slice(bytes);
+                        f64::from_le_bytes(b)
+                    }
+                };
+                Ok(value)
+            }
+        }
+    }
+}
+
+fn four(bytes: &[u8]) -> [u8; 4] {
+    [bytes[0], bytes[1], bytes[2], bytes[3]]
+}
@@ -0,0 +1,202 @@
+//! Reading and writing STL (stereolithography) files.
+//!
+//! STL files describe a list of independent triangles, each with a single face normal. When read,
+//! each triangle is given its own three vertices, each with the triangle's normal. Facets with a
+//! zero normal are given the normal calculated from their winding order.
+
+use super::{Encoding, Error};
+use crate::geom::{Point3, Vector3};
+use crate::mesh::normals::face as face_normal;
+use crate::mesh::TriMesh;
+use std::io::{self, BufRead, Write};
+
+/// The size of the header at the beginning of a binary STL file.
+pub const BINARY_HEADER_LEN: usize = 80;
+
+// The size of a single triangle within a binary STL file.
+const BINARY_TRIANGLE_LEN: usize = 50;
+
+/// Read an STL file from the given reader.
+///
+/// The encoding is detected automatically.
+pub fn read<R>(mut reader: R) -> Result<TriMesh, Error>
+where
+    R: BufRead,
+{
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+    // Binary files may also begin with `solid`, so check whether the length matches first.
+    if is_binary(&bytes) {
+        read_binary(&bytes)
+    } else if is_ascii(&bytes) {
+        let text = std::str::from_utf8(&bytes)
+            .map_err(|_| Error::Invalid("ASCII STL contains invalid UTF-8".to_string()))?;
+        read_ascii(text)
+    } else {
+        Err(Error::Invalid("unrecognised STL encoding".to_string()))
+    }
+}
+
+/// Write the given mesh to the given writer in the STL format using the given encoding.
+///
+/// The normal of each facet is calculated from the winding order of its vertices.
+pub fn write<W>(mesh: &TriMesh, mut writer: W, encoding: Encoding) -> io::Result<()>
+where
+    W: Write,
+{
+    let points = mesh.points();
+    let triangles = mesh.indices().chunks(3).map(|tri| {
+        let a = points[tri[0] as usize];
+        let b = points[tri[1] as usize];
+        let c = points[tri[2] as usize];
+        (face_normal(a, b, c), [a, b, c])
+    });
+    match encoding {
+        Encoding::Ascii => {
+            writeln!(writer, "solid nannou")?;
+            for (n, vertices) in triangles {
+                writeln!(writer, "  facet normal {} {} {}", n.x, n.y, n.z)?;
+                writeln!(writer, "    outer loop")?;
+                for v in &vertices {
+                    writeln!(writer, "      vertex {} {} {}", v.x, v.y, v.z)?;
+                }
+                writeln!(writer, "    endloop")?;
+                writeln!(writer, "  endfacet")?;
+            }
+            writeln!(writer, "endsolid nannou")?;
+        }
+        Encoding::Binary => {
+            let mut header = [0u8; BINARY_HEADER_LEN];
+            let text = b"Created by nannou";
+            header[..text.len()].copy_from_slice(text);
+            writer.write_all(&header)?;
+            let count = (mesh.indices().len() / 3) as u32;
+            writer.write_all(&count.to_le_bytes())?;
+            for (n, vertices) in triangles {
+                for v in Some(n).iter().chain(vertices.iter()) {
+                    writer.write_all(&v.x.to_le_bytes())?;
+                    writer.write_all(&v.y.to_le_bytes())?;
+                    writer.write_all(&v.z.to_le_bytes())?;
+                }
+                // The "attribute byte count" is unused.
+                writer.write_all(&[0, 0])?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Whether or not the length of the given bytes matches the triangle count in the binary header.
+fn is_binary(bytes: &[u8]) -> bool {
+    if bytes.len() < BINARY_HEADER_LEN + 4 {
+        return false;
+    }
+    let count = u32_le(&bytes[BINARY_HEADER_LEN..]) as usize;
+    bytes.len() == BINARY_HEADER_LEN + 4 + count * BINARY_TRIANGLE_LEN
+}
+
+// Whether or not the given bytes begin with the `solid` keyword, ignoring leading whitespace.
+fn is_ascii(bytes: &[u8]) -> bool {
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    bytes[start..].starts_with(b"solid")
+}
+
+fn read_binary(bytes: &[u8]) -> Result<TriMesh, Error> {
+    let body = &bytes[BINARY_HEADER_LEN + 4..];
+    let mut points = vec![];
+    let mut normals = vec![];
+    for triangle in body.chunks(BINARY_TRIANGLE_LEN) {
+        let vector = |i: usize| Vector3 {
+            x: f32_le(&triangle[i * 12..]),
+            y: f32_le(&triangle[i * 12 + 4..]),
+            z: f32_le(&triangle[i * 12 + 8..]),
+        };
+        let vertices = [vector(1), vector(2), vector(3)];
+        push_facet(&mut points, &mut normals, vector(0), vertices);
+    }
+    Ok(facets_mesh(points, normals))
+}
+
+fn read_ascii(text: &str) -> Result<TriMesh, Error> {
+    let mut points = vec![];
+    let mut normals = vec![];
+    let mut normal = Vector3::zero();
+    let mut vertices = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("facet") => {
+                if words.next() != Some("normal") {
+                    return Err(invalid(line_number, "expected `facet normal`"));
+                }
+                normal = parse_vector(&mut words, line_number)?;
+                vertices.clear();
+            }
+            Some("vertex") => vertices.push(parse_vector(&mut words, line_number)?),
+            Some("endfacet") => {
+                if vertices.len() != 3 {
+                    let reason = "facet must contain exactly three vertices";
+                    return Err(invalid(line_number, reason));
+                }
+                let facet = [vertices[0], vertices[1], vertices[2]];
+                push_facet(&mut points, &mut normals, normal, facet);
+            }
+            _ => (),
+        }
+    }
+    Ok(facets_mesh(points, normals))
+}
+
+fn invalid(line_number: usize, reason: &str) -> Error {
+    Error::Invalid(format!("line {}: {}", line_number, reason))
+}
+
+fn parse_vector<'a, I>(words: &mut I, line_number: usize) -> Result<Vector3<f32>, Error>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let mut next = || {
+        words
+            .next()
+            .and_then(|w| w.parse().ok())
+            .ok_or_else(|| invalid(line_number, "expected three numbers"))
+    };
+    Ok(Vector3 {
+        x: next()?,
+        y: next()?,
+        z: next()?,
+    })
+}
+
+fn push_facet(
+    points: &mut Vec<Point3<f32>>,
+    normals: &mut Vec<Vector3<f32>>,
+    normal: Vector3<f32>,
+    vertices: [Point3<f32>; 3],
+) {
+    let [a, b, c] = vertices;
+    let normal = if normal.is_zero() {
+        face_normal(a, b, c)
+    } else {
+        normal
+    };
+    points.extend_from_slice(&vertices);
+    normals.extend_from_slice(&[normal, normal, normal]);
+}
+
+fn facets_mesh(points: Vec<Point3<f32>>, normals: Vec<Vector3<f32>>) -> TriMesh {
+    let indices = 0..points.len() as u32;
+    TriMesh::new(points, indices).with_normals(normals)
+}
+
+fn u32_le(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn f32_le(bytes: &[u8]) -> f32 {
+    f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
@@ -9,9 +9,15 @@ use std::convert::{TryFrom, TryInto};
 use std::ops::{self, Deref, DerefMut};
 
 pub mod channel;
+pub mod file;
+pub mod normals;
+pub mod shape;
+pub mod subdivide;
+pub mod tri_mesh;
 pub mod vertex;
 
 pub use self::channel::{Channel, ChannelMut};
+pub use self::tri_mesh::TriMesh;
 
 // Traits describing meshes with access to certain channels.
 
@@ -0,0 +1,65 @@
+//! Functions for calculating the normals of triangle meshes.
+//!
+//! All functions expect a slice of points along with a slice of indices, where every trio of
+//! indices describes a single triangle with a counter-clockwise winding order.
+
+use crate::geom::{Point3, Vector3};
+use crate::math::BaseFloat;
+
+/// The unit normal of the triangle with the given corners.
+///
+/// Returns a zero vector if the triangle is degenerate.
+pub fn face<S>(a: Point3<S>, b: Point3<S>, c: Point3<S>) -> Vector3<S>
+where
+    S: BaseFloat,
+{
+    (b - a).cross(c - a).normalize()
+}
+
+/// The unit normal of each triangle described by the given indices.
+pub fn faces<S>(points: &[Point3<S>], indices: &[u32]) -> Vec<Vector3<S>>
+where
+    S: BaseFloat,
+{
+    indices
+        .chunks(3)
+        .map(|tri| {
+            let [a, b, c] = corners(points, tri);
+            face(a, b, c)
+        })
+        .collect()
+}
+
+/// Smooth normals for each point, shared between all triangles that index the point.
+///
+/// Each normal is the average of the normals of the adjacent triangles, weighted by their area.
+/// Points that are not indexed by any non-degenerate triangle are given a zero normal.
+pub fn smooth<S>(points: &[Point3<S>], indices: &[u32]) -> Vec<Vector3<S>>
+where
+    S: BaseFloat,
+{
+    let mut normals = vec![Vector3::zero(); points.len()];
+    for tri in indices.chunks(3) {
+        let [a, b, c] = corners(points, tri);
+        // The magnitude of the cross product is twice the area of the triangle.
+        let weighted = (b - a).cross(c - a);
+        for &i in tri {
+            normals[i as usize] = normals[i as usize] + weighted;
+        }
+    }
+    for n in &mut normals {
+        *n = n.normalize();
+    }
+    normals
+}
+
+fn corners<S>(points: &[Point3<S>], tri: &[u32]) -> [Point3<S>; 3]
+where
+    S: BaseFloat,
+{
+    [
+        points[tri[0] as usize],
+        points[tri[1] as usize],
+        points[tri[2] as usize],
+    ]
+}
@@ -0,0 +1,265 @@
+//! Generators for common procedural meshes.
+//!
+//! Each function produces a **TriMesh** centred on the origin with counter-clockwise, outward
+//! facing triangles and unit normals. All meshes except the icosphere also have texture
+//! coordinates, where `[0.0, 0.0]` is the top-left of the texture. Colors may be assigned via
+//! `TriMesh::with_color` or `TriMesh::with_colors` before drawing with
+//! `draw.mesh().indexed_colored(..)`.
+//!
+//! The `y` axis is "up" for all shapes with an axis of symmetry.
+
+use crate::geom::{vec3, Point2, Point3, Vector3};
+use crate::mesh::TriMesh;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+/// A flat rectangle within the XY plane, facing the positive `z` axis.
+///
+/// The plane is divided into a grid of `columns` by `rows` cells, each made of two triangles.
+pub fn plane(width: f32, height: f32, columns: usize, rows: usize) -> TriMesh {
+    let (columns, rows) = (columns.max(1), rows.max(1));
+    let mut points = vec![];
+    let mut tex_coords = vec![];
+    for j in 0..=rows {
+        let v = j as f32 / rows as f32;
+        for i in 0..=columns {
+            let u = i as f32 / columns as f32;
+            points.push(Point3 {
+                x: (u - 0.5) * width,
+                y: (0.5 - v) * height,
+                z: 0.0,
+            });
+            tex_coords.push(Point2 { x: u, y: v });
+        }
+    }
+    let normals = vec![vec3(0.0, 0.0, 1.0); points.len()];
+    let indices = grid_indices(columns, rows, false, false);
+    TriMesh::new(points, indices)
+        .with_tex_coords(tex_coords)
+        .with_normals(normals)
+}
+
+/// A UV sphere with the given number of `segments` around the `y` axis and `rings` from pole to
+/// pole.
+///
+/// The points along the texture seam and at the poles are duplicated so that each may have their
+/// own texture coordinates.
+pub fn sphere(radius: f32, segments: usize, rings: usize) -> TriMesh {
+    let (segments, rings) = (segments.max(3), rings.max(2));
+    let mut points = vec![];
+    let mut tex_coords = vec![];
+    let mut normals = vec![];
+    for j in 0..=rings {
+        let v = j as f32 / rings as f32;
+        let phi = v * PI;
+        for i in 0..=segments {
+            let u = i as f32 / segments as f32;
+            let theta = u * 2.0 * PI;
+            let normal = Vector3 {
+                x: phi.sin() * theta.sin(),
+                y: phi.cos(),
+                z: phi.sin() * theta.cos(),
+            };
+            points.push(normal * radius);
+            tex_coords.push(Point2 { x: u, y: v });
+            normals.push(normal);
+        }
+    }
+    let indices = grid_indices(segments, rings, true, true);
+    TriMesh::new(points, indices)
+        .with_tex_coords(tex_coords)
+        .with_normals(normals)
+}
+
+/// A sphere produced by repeatedly subdividing an icosahedron.
+///
+/// Unlike the UV sphere, the triangles of an icosphere are all of a similar size. A
+/// `subdivisions` of `0` produces an icosahedron. Each subdivision multiplies the number of
+/// triangles by four.
+///
+/// No points are duplicated and as a result the icosphere has no texture coordinates.
+pub fn icosphere(radius: f32, subdivisions: usize) -> TriMesh {
+    let t = (1.0 + 5.0f32.sqrt()) / 2.0;
+    let mut points: Vec<Point3<f32>> = [
+        [-1.0, t, 0.0],
+        [1.0, t, 0.0],
+        [-1.0, -t, 0.0],
+        [1.0, -t, 0.0],
+        [0.0, -1.0, t],
+        [0.0, 1.0, t],
+        [0.0, -1.0, -t],
+        [0.0, 1.0, -t],
+        [t, 0.0, -1.0],
+        [t, 0.0, 1.0],
+        [-t, 0.0, -1.0],
+        [-t, 0.0, 1.0],
+    ]
+    .iter()
+    .map(|&[x, y, z]| Vector3 { x, y, z }.normalize())
+    .collect();
+    let mut indices: Vec<u32> = vec![
+        0, 11, 5, 0, 5, 1, 0, 1, 7, 0, 7, 10, 0, 10, 11, 1, 5, 9, 5, 11, 4, 11, 10, 2, 10, 7, 6, 7,
+        1, 8, 3, 9, 4, 3, 4, 2, 3, 2, 6, 3, 6, 8, 3, 8, 9, 4, 9, 5, 2, 4, 11, 6, 2, 10, 8, 6, 7, 9,
+        8, 1,
+    ];
+    for _ in 0..subdivisions {
+        let mut midpoints = HashMap::new();
+        let mut midpoint = |a: u32, b: u32| -> u32 {
+            let key = if a < b { (a, b) } else { (b, a) };
+            *midpoints.entry(key).or_insert_with(|| {
+                let p = (points[a as usize] + points[b as usize]).normalize();
+                points.push(p);
+                points.len() as u32 - 1
+            })
+        };
+        let mut subdivided = Vec::with_capacity(indices.len() * 4);
+        for tri in indices.chunks(3) {
+            let (a, b, c) = (tri[0], tri[1], tri[2]);
+            let ab = midpoint(a, b);
+            let bc = midpoint(b, c);
+            let ca = midpoint(c, a);
+            subdivided.extend_from_slice(&[a, ab, ca, b, bc, ab, c, ca, bc, ab, bc, ca]);
+        }
+        indices = subdivided;
+    }
+    let normals = points.clone();
+    let points = points.into_iter().map(|n| n * radius);
+    TriMesh::new(points, indices).with_normals(normals)
+}
+
+/// A closed cylinder with the given `height` along the `y` axis and `segments` around it.
+///
+/// The sides and each of the caps have their own points, giving a hard edge between them.
+pub fn cylinder(radius: f32, height: f32, segments: usize) -> TriMesh {
+    let segments = segments.max(3);
+    let mut points = vec![];
+    let mut tex_coords = vec![];
+    let mut normals = vec![];
+    let angle = |i: usize| i as f32 / segments as f32 * 2.0 * PI;
+
+    // The sides.
+    for j in 0..=1 {
+        let y = (0.5 - j as f32) * height;
+        for i in 0..=segments {
+            let theta = angle(i);
+            let normal = Vector3 {
+                x: theta.sin(),
+                y: 0.0,
+                z: theta.cos(),
+            };
+            points.push(Point3 {
+                x: normal.x * radius,
+                y,
+                z: normal.z * radius,
+            });
+            tex_coords.push(Point2 {
+                x: i as f32 / segments as f32,
+                y: j as f32,
+            });
+            normals.push(normal);
+        }
+    }
+    let mut indices = grid_indices(segments, 1, false, false);
+
+    // The caps, each a fan around a centre point.
+    for &(y, sign) in &[(0.5 * height, 1.0), (-0.5 * height, -1.0)] {
+        let centre = points.len() as u32;
+        let normal = Vector3 {
+            x: 0.0,
+            y: sign,
+            z: 0.0,
+        };
+        points.push(Point3 { x: 0.0, y, z: 0.0 });
+        tex_coords.push(Point2 { x: 0.5, y: 0.5 });
+        normals.push(normal);
+        for i in 0..segments {
+            let theta = angle(i);
+            let (x, z) = (theta.sin(), theta.cos());
+            points.push(Point3 {
+                x: x * radius,
+                y,
+                z: z * radius,
+            });
+            tex_coords.push(Point2 {
+                x: 0.5 + x * 0.5,
+                y: 0.5 + z * 0.5 * sign,
+            });
+            normals.push(normal);
+        }
+        for i in 0..segments as u32 {
+            let a = centre + 1 + i;
+            let b = centre + 1 + (i + 1) % segments as u32;
+            if sign > 0.0 {
+                indices.extend_from_slice(&[centre, a, b]);
+            } else {
+                indices.extend_from_slice(&[centre, b, a]);
+            }
+        }
+    }
+
+    TriMesh::new(points, indices)
+        .with_tex_coords(tex_coords)
+        .with_normals(normals)
+}
+
+/// A torus lying within the XZ plane, encircling the `y` axis.
+///
+/// `radius` describes the distance from the origin to the centre of the tube, while
+/// `tube_radius` describes the radius of the tube itself. `segments` describes the number of
+/// divisions around the `y` axis, while `tube_segments` describes the number around the tube.
+pub fn torus(radius: f32, tube_radius: f32, segments: usize, tube_segments: usize) -> TriMesh {
+    let (segments, tube_segments) = (segments.max(3), tube_segments.max(3));
+    let mut points = vec![];
+    let mut tex_coords = vec![];
+    let mut normals = vec![];
+    for j in 0..=tube_segments {
+        let v = j as f32 / tube_segments as f32;
+        let phi = v * 2.0 * PI;
+        for i in 0..=segments {
+            let u = i as f32 / segments as f32;
+            let theta = u * 2.0 * PI;
+            let outward = Vector3 {
+                x: theta.sin(),
+                y: 0.0,
+                z: theta.cos(),
+            };
+            let normal = Vector3 {
+                x: outward.x * phi.cos(),
+                y: -phi.sin(),
+                z: outward.z * phi.cos(),
+            };
+            points.push(outward * radius + normal * tube_radius);
+            tex_coords.push(Point2 { x: u, y: v });
+            normals.push(normal);
+        }
+    }
+    let indices = grid_indices(segments, tube_segments, false, false);
+    TriMesh::new(points, indices)
+        .with_tex_coords(tex_coords)
+        .with_normals(normals)
+}
+
+// Triangle indices for a grid of `(columns + 1) * (rows + 1)` points laid out row by row, where
+// columns increase to the right and rows increase downwards when viewed from the front.
+//
+// `top_pole` and `bottom_pole` indicate that the first or last row of points converge to a
+// single point, in which case the degenerate triangles are skipped.
+fn grid_indices(columns: usize, rows: usize, top_pole: bool, bottom_pole: bool) -> Vec<u32> {
+    let stride = columns as u32 + 1;
+    let mut indices = vec![];
+    for j in 0..rows as u32 {
+        for i in 0..columns as u32 {
+            let tl = j * stride + i;
+            let tr = tl + 1;
+            let bl = tl + stride;
+            let br = bl + 1;
+            if !(bottom_pole && j + 1 == rows as u32) {
+                indices.extend_from_slice(&[tl, bl, br]);
+            }
+            if !(top_pole && j == 0) {
+                indices.extend_from_slice(&[tl, br, tr]);
+            }
+        }
+    }
+    indices
+}
@@ -0,0 +1,359 @@
+//! Loop and Catmull-Clark subdivision of triangle meshes.
+//!
+//! Both schemes connect vertices via their indices. Points that are duplicated (e.g. along
+//! texture seams or hard edges) are treated as open boundaries, so `TriMesh::weld` may be used
+//! beforehand to produce a closed, smooth surface.
+//!
+//! New vertices are positioned by the subdivision rules. Their colors and texture coordinates are
+//! linearly interpolated from the vertices that they are derived from. If the mesh has a normals
+//! channel, smooth normals are recalculated for the result.
+
+use crate::color::LinSrgba;
+use crate::geom::{Point2, Point3};
+use crate::mesh::TriMesh;
+use std::collections::{HashMap, HashSet};
+use std::f32::consts::PI;
+
+// An edge between two vertices, with the lesser index first.
+type Edge = (u32, u32);
+
+// A polygon mesh used to represent the surface between subdivision iterations.
+struct Surface {
+    points: Vec<Point3<f32>>,
+    colors: Vec<LinSrgba>,
+    tex_coords: Vec<Point2<f32>>,
+    faces: Vec<Vec<u32>>,
+}
+
+// The connectivity of a **Surface** required by both subdivision schemes.
+struct Adjacency {
+    // The faces that share each edge.
+    edge_faces: HashMap<Edge, Vec<usize>>,
+    // The faces that share each vertex.
+    vertex_faces: Vec<Vec<usize>>,
+    // The vertices connected to each vertex by an edge.
+    neighbours: Vec<Vec<u32>>,
+    // The vertices connected to each vertex by an edge with only one adjacent face.
+    boundary: Vec<Vec<u32>>,
+}
+
+/// Subdivide the given mesh the given number of times using Loop subdivision.
+///
+/// Each iteration splits every triangle into four, producing a smooth surface that approximates
+/// the original. Boundary edges are smoothed as curves along the boundary.
+pub fn loop_subdivision(mesh: &TriMesh, iterations: usize) -> TriMesh {
+    if iterations == 0 {
+        return mesh.clone();
+    }
+    let mut surface = Surface::from_mesh(mesh);
+    for _ in 0..iterations {
+        surface = loop_step(&surface);
+    }
+    surface.into_mesh(mesh)
+}
+
+/// Subdivide the given mesh the given number of times using Catmull-Clark subdivision.
+///
+/// The first iteration splits each triangle into three quads, while each following iteration
+/// splits every quad into four. The resulting quads are triangulated upon completion. Boundary
+/// edges are smoothed as curves along the boundary.
+pub fn catmull_clark(mesh: &TriMesh, iterations: usize) -> TriMesh {
+    if iterations == 0 {
+        return mesh.clone();
+    }
+    let mut surface = Surface::from_mesh(mesh);
+    for _ in 0..iterations {
+        surface = catmull_clark_step(&surface);
+    }
+    surface.into_mesh(mesh)
+}
+
+fn loop_step(src: &Surface) -> Surface {
+    let adj = Adjacency::new(src);
+    let mut dst = Surface::default();
+
+    // Reposition the existing vertices.
+    for (v, &p) in src.points.iter().enumerate() {
+        let point = match boundary_point(src, &adj, v) {
+            Some(point) => point,
+            None => {
+                let neighbours = &adj.neighbours[v];
+                let k = neighbours.len() as f32;
+                let beta = (5.0 / 8.0 - (3.0 / 8.0 + (2.0 * PI / k).cos() / 4.0).powi(2)) / k;
+                let sum = sum(neighbours.iter().map(|&n| src.points[n as usize]));
+                p * (1.0 - k * beta) + sum * beta
+            }
+        };
+        dst.push(src, point, &[v as u32]);
+    }
+
+    // Insert a vertex along each edge.
+    let mut edge_points = HashMap::new();
+    for face in &src.faces {
+        for (a, b) in face_edges(face) {
+            edge_points.entry(edge(a, b)).or_insert_with(|| {
+                let faces = &adj.edge_faces[&edge(a, b)];
+                let pa = src.points[a as usize];
+                let pb = src.points[b as usize];
+                let point = if faces.len() == 2 {
+                    let opposite = faces.iter().map(|&f| {
+                        let face = &src.faces[f];
+                        let c = face.iter().find(|&&c| c != a && c != b).unwrap();
+                        src.points[*c as usize]
+                    });
+                    (pa + pb) * (3.0 / 8.0) + sum(opposite) * (1.0 / 8.0)
+                } else {
+                    (pa + pb) * 0.5
+                };
+                dst.push(src, point, &[a, b])
+            });
+        }
+    }
+
+    // Split each triangle into four.
+    for face in &src.faces {
+        let (a, b, c) = (face[0], face[1], face[2]);
+        let ab = edge_points[&edge(a, b)];
+        let bc = edge_points[&edge(b, c)];
+        let ca = edge_points[&edge(c, a)];
+        dst.faces.push(vec![a, ab, ca]);
+        dst.faces.push(vec![b, bc, ab]);
+        dst.faces.push(vec![c, ca, bc]);
+        dst.faces.push(vec![ab, bc, ca]);
+    }
+    dst
+}
+
+fn catmull_clark_step(src: &Surface) -> Surface {
+    let adj = Adjacency::new(src);
+    let mut dst = Surface::default();
+
+    let face_points: Vec<Point3<f32>> = src
+        .faces
+        .iter()
+        .map(|face| {
+            let points = face.iter().map(|&v| src.points[v as usize]);
+            sum(points) * (1.0 / face.len() as f32)
+        })
+        .collect();
+
+    // Reposition the existing vertices.
+    for (v, &p) in src.points.iter().enumerate() {
+        let point = match boundary_point(src, &adj, v) {
+            Some(point) => point,
+            None => {
+                let faces = &adj.vertex_faces[v];
+                let neighbours = &adj.neighbours[v];
+                let n = faces.len() as f32;
+                let q = sum(faces.iter().map(|&f| face_points[f])) * (1.0 / n);
+                let midpoints = neighbours
+                    .iter()
+                    .map(|&u| (p + src.points[u as usize]) * 0.5);
+                let r = sum(midpoints) * (1.0 / neighbours.len() as f32);
+                (q + r * 2.0 + p * (n - 3.0)) * (1.0 / n)
+            }
+        };
+        dst.push(src, point, &[v as u32]);
+    }
+
+    // Insert a vertex at the centre of each face.
+    let face_indices: Vec<u32> = src
+        .faces
+        .iter()
+        .zip(&face_points)
+        .map(|(face, &point)| dst.push(src, point, face))
+        .collect();
+
+    // Insert a vertex along each edge.
+    let mut edge_points = HashMap::new();
+    for face in &src.faces {
+        for (a, b) in face_edges(face) {
+            edge_points.entry(edge(a, b)).or_insert_with(|| {
+                let faces = &adj.edge_faces[&edge(a, b)];
+                let pa = src.points[a as usize];
+                let pb = src.points[b as usize];
+                let point = if faces.len() == 2 {
+                    let centres = faces.iter().map(|&f| face_points[f]);
+                    (pa + pb + sum(centres)) * 0.25
+                } else {
+                    (pa + pb) * 0.5
+                };
+                dst.push(src, point, &[a, b])
+            });
+        }
+    }
+
+    // Split each face into a quad per corner.
+    for (face, &centre) in src.faces.iter().zip(&face_indices) {
+        let len = face.len();
+        for i in 0..len {
+            let prev = face[(i + len - 1) % len];
+            let v = face[i];
+            let next = face[(i + 1) % len];
+            let quad = vec![
+                v,
+                edge_points[&edge(v, next)],
+                centre,
+                edge_points[&edge(prev, v)],
+            ];
+            dst.faces.push(quad);
+        }
+    }
+    dst
+}
+
+// The new position of the given vertex if it lies on a boundary.
+//
+// Boundary vertices are smoothed as a cubic B-spline along the boundary. Corners where more than
+// two boundary edges meet are left in place.
+fn boundary_point(src: &Surface, adj: &Adjacency, v: usize) -> Option<Point3<f32>> {
+    let p = src.points[v];
+    let boundary = &adj.boundary[v];
+    if boundary.len() == 2 {
+        let (a, b) = (boundary[0] as usize, boundary[1] as usize);
+        Some(p * 0.75 + (src.points[a] + src.points[b]) * 0.125)
+    } else if !boundary.is_empty() || adj.neighbours[v].is_empty() {
+        Some(p)
+    } else {
+        None
+    }
+}
+
+impl Surface {
+    fn from_mesh(mesh: &TriMesh) -> Self {
+        Surface {
+            points: mesh.points().to_vec(),
+            colors: mesh.colors().to_vec(),
+            tex_coords: mesh.tex_coords().to_vec(),
+            faces: mesh.indices().chunks(3).map(|tri| tri.to_vec()).collect(),
+        }
+    }
+
+    // Push a new vertex with the given point, interpolating the attributes of the given vertices
+    // within `src`.
+    fn push(&mut self, src: &Surface, point: Point3<f32>, sources: &[u32]) -> u32 {
+        let w = 1.0 / sources.len() as f32;
+        let (mut r, mut g, mut b, mut a) = (0.0, 0.0, 0.0, 0.0);
+        let mut tex_coords = Point2 { x: 0.0, y: 0.0 };
+        for &s in sources {
+            let c = src.colors[s as usize];
+            r += c.red * w;
+            g += c.green * w;
+            b += c.blue * w;
+            a += c.alpha * w;
+            tex_coords = tex_coords + src.tex_coords[s as usize] * w;
+        }
+        self.points.push(point);
+        self.colors.push(LinSrgba::new(r, g, b, a));
+        self.tex_coords.push(tex_coords);
+        self.points.len() as u32 - 1
+    }
+
+    // Triangulate the surface, preserving the channels of the original mesh.
+    fn into_mesh(self, original: &TriMesh) -> TriMesh {
+        let Surface {
+            points,
+            colors,
+            tex_coords,
+            faces,
+        } = self;
+        let mut indices = vec![];
+        for face in &faces {
+            for i in 1..face.len() - 1 {
+                indices.extend_from_slice(&[face[0], face[i], face[i + 1]]);
+            }
+        }
+        let mut mesh = TriMesh::new(points, indices);
+        if original.has_colors() {
+            mesh = mesh.with_colors(colors);
+        }
+        if original.has_tex_coords() {
+            mesh = mesh.with_tex_coords(tex_coords);
+        }
+        if original.has_normals() {
+            mesh = mesh.with_smooth_normals();
+        }
+        mesh
+    }
+}
+
+impl Default for Surface {
+    fn default() -> Self {
+        Surface {
+            points: vec![],
+            colors: vec![],
+            tex_coords: vec![],
+            faces: vec![],
+        }
+    }
+}
+
+impl Adjacency {
+    fn new(surface: &Surface) -> Self {
+        let n = surface.points.len();
+        let mut edge_faces: HashMap<Edge, Vec<usize>> = HashMap::new();
+        let mut vertex_faces = vec![vec![]; n];
+        for (f, face) in surface.faces.iter().enumerate() {
+            for (a, _) in face_edges(face) {
+                vertex_faces[a as usize].push(f);
+            }
+            for (a, b) in face_edges(face) {
+                edge_faces
+                    .entry(edge(a, b))
+                    .or_insert_with(Vec::new)
+                    .push(f);
+            }
+        }
+        // Visit edges in face order so that the results are deterministic.
+        let mut neighbours = vec![vec![]; n];
+        let mut boundary = vec![vec![]; n];
+        let mut visited = HashSet::new();
+        for face in &surface.faces {
+            for (a, b) in face_edges(face) {
+                if !visited.insert(edge(a, b)) {
+                    continue;
+                }
+                neighbours[a as usize].push(b);
+                neighbours[b as usize].push(a);
+                if edge_faces[&edge(a, b)].len() == 1 {
+                    boundary[a as usize].push(b);
+                    boundary[b as usize].push(a);
+                }
+            }
+        }
+        Adjacency {
+            edge_faces,
+            vertex_faces,
+            neighbours,
+            boundary,
+        }
+    }
+}
+
+fn edge(a: u32, b: u32) -> Edge {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+// Each edge of the given face in order.
+fn face_edges<'a>(face: &'a [u32]) -> impl 'a + Iterator<Item = (u32, u32)> {
+    let len = face.len();
+    (0..len).map(move |i| (face[i], face[(i + 1) % len]))
+}
+
+fn sum<I>(points: I) -> Point3<f32>
+where
+    I: IntoIterator<Item = Point3<f32>>,
+{
+    points.into_iter().fold(
+        Point3 {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        },
+        |acc, p| acc + p,
+    )
+}
@@ -0,0 +1,504 @@
+//! A concrete triangle mesh type along with a suite of processing methods.
+
+use crate::color::{self, IntoLinSrgba, LinSrgba};
+use crate::draw::mesh::vertex::DEFAULT_VERTEX_COLOR;
+use crate::geom::{self, Point2, Point3, Vector3};
+use crate::math::{cgmath, Matrix, Matrix3, Matrix4, SquareMatrix, Transform};
+use crate::mesh::{self, normals, subdivide, vertex};
+use crate::mesh::{MeshPoints, WithColors, WithIndices, WithNormals, WithTexCoords};
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+
+pub type Points = Vec<Point3<f32>>;
+pub type Indices = Vec<u32>;
+pub type Colors = Vec<LinSrgba>;
+pub type TexCoords = Vec<Point2<f32>>;
+pub type Normals = Vec<Vector3<f32>>;
+
+/// The vertex type produced by the **TriMesh**'s inner **MeshType**.
+pub type Vertex = vertex::WithNormal<
+    vertex::WithTexCoords<vertex::WithColor<Point3<f32>, LinSrgba>, Point2<f32>>,
+    Vector3<f32>,
+>;
+
+/// The inner mesh type used by the **TriMesh**.
+pub type MeshType = WithNormals<
+    WithTexCoords<WithColors<WithIndices<MeshPoints<Points>, Indices>, Colors>, TexCoords>,
+    Normals,
+>;
+
+/// A triangle mesh with points, indices, colors, texture coordinates and normals channels.
+///
+/// Every trio of indices describes a single triangle. The colors, texture coordinates and normals
+/// channels always contain one element per point.
+///
+/// This is the type produced by the `mesh::file` loaders and the `mesh::shape` generators. It may
+/// be passed to any of the functions within the `mesh` module, or drawn directly via the `Draw`
+/// API:
+///
+/// ```no_run
+/// # use nannou::prelude::*;
+/// # fn view(app: &App, frame: Frame) {
+/// let draw = app.draw();
+/// let sphere = nannou::mesh::shape::sphere(100.0, 32, 16).with_color(STEELBLUE);
+/// draw.mesh()
+///     .indexed_colored(sphere.colored_points(), sphere.vertex_indices());
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TriMesh {
+    mesh: MeshType,
+    channels: Channels,
+}
+
+// Tracks which optional channels were provided.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct Channels {
+    colors: bool,
+    tex_coords: bool,
+    normals: bool,
+}
+
+impl TriMesh {
+    /// Construct a mesh from the given points and triangle indices.
+    ///
+    /// All other channels are filled with default values.
+    ///
+    /// **Panics** if the number of indices is not a multiple of three.
+    pub fn new<P, I>(points: P, indices: I) -> Self
+    where
+        P: IntoIterator,
+        P::Item: Into<Point3<f32>>,
+        I: IntoIterator<Item = u32>,
+    {
+        let points: Points = points.into_iter().map(Into::into).collect();
+        let indices: Indices = indices.into_iter().collect();
+        assert_eq!(indices.len() % 3, 0, "indices must describe triangles");
+        let n = points.len();
+        let colors = vec![DEFAULT_VERTEX_COLOR; n];
+        let tex_coords = vec![Point2 { x: 0.0, y: 0.0 }; n];
+        let normals = vec![Vector3::zero(); n];
+        let mesh = mesh::from_points(points);
+        let mesh = mesh::with_indices(mesh, indices);
+        let mesh = mesh::with_colors(mesh, colors);
+        let mesh = mesh::with_tex_coords(mesh, tex_coords);
+        let mesh = mesh::with_normals(mesh, normals);
+        let channels = Channels::default();
+        TriMesh { mesh, channels }
+    }
+
+    /// Construct a mesh from the given triangles, where each triangle has its own vertices.
+    pub fn from_triangles<I, V>(triangles: I) -> Self
+    where
+        I: IntoIterator<Item = geom::Tri<V>>,
+        V: Into<Point3<f32>>,
+    {
+        let mut points = vec![];
+        for tri in triangles {
+            let [a, b, c] = tri.0;
+            points.push(a.into());
+            points.push(b.into());
+            points.push(c.into());
+        }
+        let indices = 0..points.len() as u32;
+        Self::new(points, indices)
+    }
+
+    /// Combine all of the given meshes into a single mesh.
+    ///
+    /// See the `extend` method for details.
+    pub fn merge<'a, I>(meshes: I) -> Self
+    where
+        I: IntoIterator<Item = &'a TriMesh>,
+    {
+        let mut merged = Self::default();
+        for mesh in meshes {
+            merged.extend(mesh);
+        }
+        merged
+    }
+
+    /// Assign a color channel to the mesh.
+    ///
+    /// **Panics** if the number of colors differs from the number of points.
+    pub fn with_colors<I>(mut self, colors: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: IntoLinSrgba<color::DefaultScalar>,
+    {
+        let colors: Colors = colors.into_iter().map(|c| c.into_lin_srgba()).collect();
+        assert_eq!(self.points().len(), colors.len());
+        *self.colors_mut() = colors;
+        self.channels.colors = true;
+        self
+    }
+
+    /// Assign the given color to every vertex of the mesh.
+    pub fn with_color<C>(self, color: C) -> Self
+    where
+        C: IntoLinSrgba<color::DefaultScalar>,
+    {
+        let color = color.into_lin_srgba();
+        let n = self.points().len();
+        self.with_colors((0..n).map(|_| color))
+    }
+
+    /// Assign a texture coordinates channel to the mesh.
+    ///
+    /// **Panics** if the number of texture coordinates differs from the number of points.
+    pub fn with_tex_coords<I>(mut self, tex_coords: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Point2<f32>>,
+    {
+        let tex_coords: TexCoords = tex_coords.into_iter().map(Into::into).collect();
+        assert_eq!(self.points().len(), tex_coords.len());
+        *self.tex_coords_mut() = tex_coords;
+        self.channels.tex_coords = true;
+        self
+    }
+
+    /// Assign a normals channel to the mesh.
+    ///
+    /// **Panics** if the number of normals differs from the number of points.
+    pub fn with_normals<I>(mut self, normals: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Vector3<f32>>,
+    {
+        let normals: Normals = normals.into_iter().map(Into::into).collect();
+        assert_eq!(self.points().len(), normals.len());
+        *self.normals_mut() = normals;
+        self.channels.normals = true;
+        self
+    }
+
+    /// Assign smooth normals to the mesh.
+    ///
+    /// The normal of each vertex is the area-weighted average of the normals of the triangles that
+    /// share it. Vertices are only shared via their indices, so points that are duplicated (e.g.
+    /// along a texture seam) will produce a visible crease. Use `weld` first to avoid this.
+    pub fn with_smooth_normals(self) -> Self {
+        let normals = normals::smooth(self.points(), self.indices());
+        self.with_normals(normals)
+    }
+
+    /// Assign flat normals to the mesh.
+    ///
+    /// Each triangle is given its own three vertices, each with the normal of the triangle. All
+    /// other channels are preserved.
+    pub fn with_flat_normals(self) -> Self {
+        let normals = normals::faces(self.points(), self.indices());
+        let vertices = self.indices().to_vec();
+        let indices = 0..vertices.len() as u32;
+        self.select(&vertices, indices)
+            .with_normals(normals.into_iter().flat_map(|n| vec![n; 3]))
+    }
+
+    /// Apply the given transform to the points and normals of the mesh.
+    ///
+    /// Normals are transformed by the inverse-transpose of the matrix so that they remain
+    /// perpendicular to the surface under non-uniform scaling. If the transform mirrors the mesh,
+    /// the winding order of each triangle is reversed so that they remain counter-clockwise.
+    pub fn transform(mut self, transform: Matrix4<f32>) -> Self {
+        let basis = Matrix3::from_cols(
+            transform.x.truncate(),
+            transform.y.truncate(),
+            transform.z.truncate(),
+        );
+        let normal_matrix = basis.invert().map(|m| m.transpose()).unwrap_or(basis);
+        for point in self.points_mut() {
+            let p: cgmath::Point3<f32> = (*point).into();
+            *point = transform.transform_point(p).into();
+        }
+        for normal in self.normals_mut() {
+            let n: cgmath::Vector3<f32> = (*normal).into();
+            *normal = Vector3::from(normal_matrix * n).normalize();
+        }
+        if basis.determinant() < 0.0 {
+            for tri in self.indices_mut().chunks_mut(3) {
+                tri.swap(1, 2);
+            }
+        }
+        self
+    }
+
+    /// Merge all vertices whose points lie within `epsilon` distance of one another.
+    ///
+    /// Each merged vertex retains the colors, texture coordinates and normals of the first vertex
+    /// in the group. Triangles that become degenerate as a result are removed.
+    ///
+    /// An `epsilon` of `0.0` only merges vertices with exactly equal points.
+    pub fn weld(self, epsilon: f32) -> Self {
+        let epsilon = epsilon.max(0.0);
+        let cell = |p: Point3<f32>| -> (i64, i64, i64) {
+            if epsilon > 0.0 {
+                let f = |s: f32| (s / epsilon).floor() as i64;
+                (f(p.x), f(p.y), f(p.z))
+            } else {
+                // Normalise negative zero so that it matches positive zero.
+                let f = |s: f32| (s + 0.0).to_bits() as i64;
+                (f(p.x), f(p.y), f(p.z))
+            }
+        };
+        let range = if epsilon > 0.0 { -1..=1 } else { 0..=0 };
+        let points = self.points();
+        let mut grid: HashMap<(i64, i64, i64), Vec<u32>> = HashMap::new();
+        let mut kept: Vec<u32> = vec![];
+        let mut remap: Vec<u32> = Vec::with_capacity(points.len());
+        for (i, &p) in points.iter().enumerate() {
+            let (x, y, z) = cell(p);
+            let mut found = None;
+            'search: for dx in range.clone() {
+                for dy in range.clone() {
+                    for dz in range.clone() {
+                        let key = (x + dx, y + dy, z + dz);
+                        for &j in grid.get(&key).into_iter().flatten() {
+                            let q = points[kept[j as usize] as usize];
+                            if (q - p).magnitude2() <= epsilon * epsilon {
+                                found = Some(j);
+                                break 'search;
+                            }
+                        }
+                    }
+                }
+            }
+            let j = found.unwrap_or_else(|| {
+                let j = kept.len() as u32;
+                kept.push(i as u32);
+                grid.entry((x, y, z)).or_insert_with(Vec::new).push(j);
+                j
+            });
+            remap.push(j);
+        }
+        let mut indices = vec![];
+        for tri in self.indices().chunks(3) {
+            let [a, b, c] = [
+                remap[tri[0] as usize],
+                remap[tri[1] as usize],
+                remap[tri[2] as usize],
+            ];
+            if a != b && b != c && c != a {
+                indices.extend_from_slice(&[a, b, c]);
+            }
+        }
+        self.select(&kept, indices)
+    }
+
+    /// Append the vertices and triangles of the `other` mesh onto the end of this mesh.
+    ///
+    /// A channel is considered present within the result if it was present in either mesh.
+    pub fn extend(&mut self, other: &TriMesh) {
+        let offset = self.points().len() as u32;
+        self.points_mut().extend_from_slice(other.points());
+        self.indices_mut()
+            .extend(other.indices().iter().map(|&i| i + offset));
+        self.colors_mut().extend_from_slice(other.colors());
+        self.tex_coords_mut().extend_from_slice(other.tex_coords());
+        self.normals_mut().extend_from_slice(other.normals());
+        self.channels.colors |= other.channels.colors;
+        self.channels.tex_coords |= other.channels.tex_coords;
+        self.channels.normals |= other.channels.normals;
+    }
+
+    /// Subdivide the mesh the given number of times using Loop subdivision.
+    ///
+    /// See `mesh::subdivide::loop_subdivision` for details.
+    pub fn subdivide_loop(self, iterations: usize) -> Self {
+        subdivide::loop_subdivision(&self, iterations)
+    }
+
+    /// Subdivide the mesh the given number of times using Catmull-Clark subdivision.
+    ///
+    /// See `mesh::subdivide::catmull_clark` for details.
+    pub fn subdivide_catmull_clark(self, iterations: usize) -> Self {
+        subdivide::catmull_clark(&self, iterations)
+    }
+
+    /// The number of raw vertices contained within the mesh.
+    pub fn raw_vertex_count(&self) -> usize {
+        mesh::raw_vertex_count(self)
+    }
+
+    /// The number of triangles described by the mesh's indices.
+    pub fn triangle_count(&self) -> usize {
+        mesh::triangle_count(self)
+    }
+
+    /// The **TriMesh**'s vertex position channel.
+    pub fn points(&self) -> &[Point3<f32>] {
+        mesh::Points::points(self)
+    }
+
+    /// The **TriMesh**'s vertex indices channel.
+    pub fn indices(&self) -> &[u32] {
+        mesh::Indices::indices(self)
+    }
+
+    /// The **TriMesh**'s vertex colors channel.
+    pub fn colors(&self) -> &[LinSrgba] {
+        mesh::Colors::colors(self)
+    }
+
+    /// The **TriMesh**'s vertex texture coordinates channel.
+    pub fn tex_coords(&self) -> &[Point2<f32>] {
+        mesh::TexCoords::tex_coords(self)
+    }
+
+    /// The **TriMesh**'s vertex normals channel.
+    pub fn normals(&self) -> &[Vector3<f32>] {
+        mesh::Normals::normals(self)
+    }
+
+    /// Whether or not a color channel was loaded or assigned.
+    pub fn has_colors(&self) -> bool {
+        self.channels.colors
+    }
+
+    /// Whether or not a texture coordinates channel was loaded or assigned.
+    pub fn has_tex_coords(&self) -> bool {
+        self.channels.tex_coords
+    }
+
+    /// Whether or not a normals channel was loaded or assigned.
+    pub fn has_normals(&self) -> bool {
+        self.channels.normals
+    }
+
+    /// Produce an iterator yielding each point alongside its color.
+    ///
+    /// Useful for drawing the mesh via `draw.mesh().indexed_colored(..)`.
+    pub fn colored_points<'a>(&'a self) -> impl 'a + Iterator<Item = (Point3<f32>, LinSrgba)> {
+        self.points()
+            .iter()
+            .cloned()
+            .zip(self.colors().iter().cloned())
+    }
+
+    /// Produce an iterator yielding each point alongside its texture coordinates.
+    ///
+    /// Useful for drawing the mesh via `draw.mesh().indexed_textured(..)`.
+    pub fn textured_points<'a>(&'a self) -> impl 'a + Iterator<Item = (Point3<f32>, Point2<f32>)> {
+        self.points()
+            .iter()
+            .cloned()
+            .zip(self.tex_coords().iter().cloned())
+    }
+
+    /// Produce an iterator yielding each index as a `usize`, as expected by `draw.mesh()`.
+    pub fn vertex_indices<'a>(&'a self) -> impl 'a + Iterator<Item = usize> {
+        self.indices().iter().map(|&i| i as usize)
+    }
+
+    /// Produce an iterator yielding all vertices in the order specified via the vertex indices.
+    pub fn vertices(&self) -> mesh::Vertices<&Self> {
+        mesh::vertices(self)
+    }
+
+    /// Produce an iterator yielding all triangles.
+    pub fn triangles(&self) -> mesh::Triangles<&Self> {
+        mesh::triangles(self)
+    }
+
+    // Produce a new mesh from the given source vertices and indices into those vertices,
+    // preserving all channels.
+    fn select<I>(&self, vertices: &[u32], indices: I) -> Self
+    where
+        I: IntoIterator<Item = u32>,
+    {
+        let get = |i: &u32| *i as usize;
+        let points = vertices.iter().map(|i| self.points()[get(i)]);
+        let mut mesh = TriMesh::new(points, indices);
+        *mesh.colors_mut() = vertices.iter().map(|i| self.colors()[get(i)]).collect();
+        *mesh.tex_coords_mut() = vertices.iter().map(|i| self.tex_coords()[get(i)]).collect();
+        *mesh.normals_mut() = vertices.iter().map(|i| self.normals()[get(i)]).collect();
+        mesh.channels = self.channels;
+        mesh
+    }
+
+    fn points_mut(&mut self) -> &mut Points {
+        &mut self.mesh.mesh.mesh.mesh.mesh.points
+    }
+
+    fn indices_mut(&mut self) -> &mut Indices {
+        &mut self.mesh.mesh.mesh.mesh.indices
+    }
+
+    fn colors_mut(&mut self) -> &mut Colors {
+        &mut self.mesh.mesh.mesh.colors
+    }
+
+    fn tex_coords_mut(&mut self) -> &mut TexCoords {
+        &mut self.mesh.mesh.tex_coords
+    }
+
+    fn normals_mut(&mut self) -> &mut Normals {
+        &mut self.mesh.normals
+    }
+}
+
+impl Default for TriMesh {
+    fn default() -> Self {
+        TriMesh::new(Vec::<Point3<f32>>::new(), None)
+    }
+}
+
+impl Deref for TriMesh {
+    type Target = MeshType;
+    fn deref(&self) -> &Self::Target {
+        &self.mesh
+    }
+}
+
+impl DerefMut for TriMesh {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.mesh
+    }
+}
+
+impl mesh::GetVertex<u32> for TriMesh {
+    type Vertex = Vertex;
+    fn get_vertex(&self, index: u32) -> Option<Self::Vertex> {
+        mesh::WithNormals::get_vertex(&self.mesh, index)
+    }
+}
+
+impl mesh::Points for TriMesh {
+    type Point = Point3<f32>;
+    type Points = Points;
+    fn points(&self) -> &Self::Points {
+        self.mesh.points()
+    }
+}
+
+impl mesh::Indices for TriMesh {
+    type Index = u32;
+    type Indices = Indices;
+    fn indices(&self) -> &Self::Indices {
+        self.mesh.indices()
+    }
+}
+
+impl mesh::Colors for TriMesh {
+    type Color = LinSrgba;
+    type Colors = Colors;
+    fn colors(&self) -> &Self::Colors {
+        self.mesh.colors()
+    }
+}
+
+impl mesh::TexCoords for TriMesh {
+    type TexCoord = Point2<f32>;
+    type TexCoords = TexCoords;
+    fn tex_coords(&self) -> &Self::TexCoords {
+        self.mesh.tex_coords()
+    }
+}
+
+impl mesh::Normals for TriMesh {
+    type Normal = Vector3<f32>;
+    type Normals = Normals;
+    fn normals(&self) -> &Self::Normals {
+        self.mesh.normals()
+    }
+}
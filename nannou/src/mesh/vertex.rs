@@ -2,7 +2,7 @@
 
 use crate::color::{self, IntoLinSrgba};
 use crate::geom::graph::node::{self, ApplyTransform};
-use crate::geom::{self, Point2, Point3};
+use crate::geom::{self, Point2, Point3, Vector3};
 use crate::math::BaseFloat;
 use std::ops::{Deref, DerefMut};
 
@@ -59,14 +59,14 @@ where
 impl<S, V, N> ApplyTransform<S> for WithNormal<V, N>
 where
     V: ApplyTransform<S>,
+    N: From<Vector3<S>> + Into<Vector3<S>>,
     S: BaseFloat,
 {
-    fn apply_transform(self, _transform: &node::PreparedTransform<S>) -> Self {
-        //let WithNormal { mut vertex, mut normal } = self;
-        //vertex = vertex.apply_transform(transform);
-        // TODO: Apply transform to the `normal`.
-        unimplemented!();
-        //WithNormal { vertex, normal }
+    fn apply_transform(self, transform: &node::PreparedTransform<S>) -> Self {
+        let WithNormal { mut vertex, normal } = self;
+        vertex = vertex.apply_transform(transform);
+        let normal = node::transform_normal(transform, normal.into()).into();
+        WithNormal { vertex, normal }
     }
 }
 
@@ -13,7 +13,7 @@ pub use crate::draw::Draw;
 pub use crate::event::WindowEvent::*;
 pub use crate::event::{
     AxisMotion, Event, Key, MouseButton, MouseScrollDelta, TouchEvent, TouchPhase,
-    TouchpadPressure, Update, WindowEvent,
+    TouchpadPressure, Update, UserEvent, WindowEvent,
 };
 pub use crate::frame::{Frame, RawFrame};
 pub use crate::geom::{
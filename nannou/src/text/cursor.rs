@@ -285,13 +285,7 @@ pub fn xys_per_line_from_text<'a>(
     line_spacing: Scalar,
 ) -> XysPerLineFromText<'a> {
     let line_infos = line_infos.iter().cloned();
-    let line_rects = text::line::rects(
-        line_infos.clone(),
-        font_size,
-        max_width,
-        x_align,
-        line_spacing,
-    );
+    let line_rects = text::line::rects(line_infos.clone(), max_width, x_align, line_spacing);
     let lines = line_infos.clone();
     let lines_with_rects = lines.zip(line_rects.clone());
     XysPerLineFromText {
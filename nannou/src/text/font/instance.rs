@@ -0,0 +1,332 @@
+//! Instancing of variable TrueType fonts.
+//!
+//! rusttype only ever rasterizes the default instance of a variable font. In order to render
+//! some other instance, the outline and advance of every glyph are resolved for the requested
+//! axis coordinates and written to a new, static font that may be loaded like any other.
+
+use super::{Error, Variation};
+use ttf_parser as ttf;
+
+// Tables that either describe the variations themselves or that are invalidated by rewriting
+// the glyph outlines and metrics. `glyf`, `loca` and `hmtx` are replaced.
+const DROPPED_TABLES: &[&[u8; 4]] = &[
+    b"fvar", b"avar", b"gvar", b"cvar", b"HVAR", b"VVAR", b"MVAR", b"STAT", b"hdmx", b"LTSH",
+    b"VDMX", b"DSIG", b"glyf", b"loca", b"hmtx",
+];
+
+// The font-specific name ID under which the axis coordinates of the instance are recorded.
+//
+// This ensures that each instance produces a unique `font::Id`.
+const INSTANCE_NAME_ID: u16 = 256;
+
+// A point within a glyph contour along with whether or not it lies on the curve.
+type Point = (i16, i16, bool);
+
+// Collects the outline of a single glyph as TrueType contours.
+#[derive(Default)]
+struct Contours {
+    contours: Vec<Vec<Point>>,
+    last: (f32, f32),
+}
+
+/// Produce the bytes of a static font describing the instance of the variable font at `index`
+/// within `bytes` for the given axis coordinates.
+///
+/// Axes that are not specified take on their default value.
+pub fn instantiate(bytes: &[u8], index: u32, variations: &[Variation]) -> Result<Vec<u8>, Error> {
+    let mut face = ttf::Face::from_slice(bytes, index).map_err(|_| Error::Parse)?;
+    if !face.is_variable() || face.table_data(tag(b"glyf")).is_none() {
+        return Err(Error::NotVariable);
+    }
+    for v in variations {
+        face.set_variation(tag(&v.tag), v.value)
+            .ok_or(Error::UnknownAxis(v.tag))?;
+    }
+
+    // Resolve the outline and advance of each glyph.
+    let num_glyphs = face.number_of_glyphs();
+    let mut glyf = vec![];
+    let mut loca = vec![];
+    let mut hmtx = vec![];
+    let mut max_points = 0;
+    let mut max_contours = 0;
+    let mut max_advance = 0;
+    for i in 0..num_glyphs {
+        let id = ttf::GlyphId(i);
+        let mut outline = Contours::default();
+        face.outline_glyph(id, &mut outline);
+        let contours: Vec<_> = outline
+            .contours
+            .into_iter()
+            .filter(|c| !c.is_empty())
+            .collect();
+        let n_points: usize = contours.iter().map(|c| c.len()).sum();
+        max_points = std::cmp::max(max_points, n_points);
+        max_contours = std::cmp::max(max_contours, contours.len());
+
+        loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+        let x_min = write_glyph(&contours, &mut glyf);
+
+        let advance = face.glyph_hor_advance(id).unwrap_or(0);
+        max_advance = std::cmp::max(max_advance, advance);
+        hmtx.extend_from_slice(&advance.to_be_bytes());
+        hmtx.extend_from_slice(&x_min.to_be_bytes());
+    }
+    loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+
+    // Copy the remaining tables, patching those that describe the rewritten tables.
+    let mut tables: Vec<([u8; 4], Vec<u8>)> = vec![];
+    for (tag, data) in table_records(bytes, index).ok_or(Error::Parse)? {
+        if DROPPED_TABLES.contains(&&tag) {
+            continue;
+        }
+        let mut data = data.to_vec();
+        match &tag {
+            b"head" if data.len() >= 54 => {
+                // Zero `checksumAdjustment` and use the long `loca` format.
+                data[8..12].copy_from_slice(&[0; 4]);
+                data[50..52].copy_from_slice(&1i16.to_be_bytes());
+            }
+            b"hhea" if data.len() >= 36 => {
+                data[10..12].copy_from_slice(&max_advance.to_be_bytes());
+                data[34..36].copy_from_slice(&num_glyphs.to_be_bytes());
+            }
+            b"maxp" if data.len() >= 32 => {
+                // Composite glyphs are flattened into simple glyphs.
+                data[6..8].copy_from_slice(&(max_points as u16).to_be_bytes());
+                data[8..10].copy_from_slice(&(max_contours as u16).to_be_bytes());
+                data[10..14].copy_from_slice(&[0; 4]);
+                data[28..32].copy_from_slice(&[0; 4]);
+            }
+            b"name" => {
+                let description = instance_description(&face, variations);
+                data = append_name(&data, INSTANCE_NAME_ID, &description).ok_or(Error::Parse)?;
+            }
+            _ => (),
+        }
+        tables.push((tag, data));
+    }
+    tables.push((*b"glyf", glyf));
+    tables.push((*b"loca", loca));
+    tables.push((*b"hmtx", hmtx));
+    tables.sort_by_key(|&(tag, _)| tag);
+
+    Ok(write_font(&tables))
+}
+
+fn tag(bytes: &[u8; 4]) -> ttf::Tag {
+    ttf::Tag::from_bytes(bytes)
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    let b = bytes.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let b = bytes.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+// The tag and data of each table of the font at `index`, handling font collections.
+fn table_records(bytes: &[u8], index: u32) -> Option<Vec<([u8; 4], &[u8])>> {
+    let directory = if bytes.get(0..4)? == b"ttcf" {
+        read_u32(bytes, 12 + 4 * index as usize)? as usize
+    } else {
+        0
+    };
+    let num_tables = read_u16(bytes, directory + 4)? as usize;
+    let mut tables = Vec::with_capacity(num_tables);
+    for i in 0..num_tables {
+        let record = directory + 12 + i * 16;
+        let mut tag = [0; 4];
+        tag.copy_from_slice(bytes.get(record..record + 4)?);
+        let offset = read_u32(bytes, record + 8)? as usize;
+        let len = read_u32(bytes, record + 12)? as usize;
+        tables.push((tag, bytes.get(offset..offset + len)?));
+    }
+    Some(tables)
+}
+
+// Describe the coordinates of the instance, e.g. "wght=700 wdth=100".
+fn instance_description(face: &ttf::Face, variations: &[Variation]) -> String {
+    face.variation_axes()
+        .map(|axis| {
+            let value = variations
+                .iter()
+                .rev()
+                .find(|v| tag(&v.tag) == axis.tag)
+                .map(|v| v.value.max(axis.min_value).min(axis.max_value))
+                .unwrap_or(axis.def_value);
+            let name = axis.tag.0.to_be_bytes();
+            format!("{}={}", String::from_utf8_lossy(&name), value)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Append a Windows, English, UTF-16BE record to the given `name` table.
+fn append_name(name: &[u8], name_id: u16, string: &str) -> Option<Vec<u8>> {
+    let count = read_u16(name, 2)? as usize;
+    let storage = read_u16(name, 4)? as usize;
+    let records_end = 6 + count * 12;
+    let strings = name.get(storage..)?;
+    let encoded: Vec<u8> = string
+        .encode_utf16()
+        .flat_map(|u| u.to_be_bytes().to_vec())
+        .collect();
+
+    // The table is always written as format 0, discarding the language tags of format 1.
+    let mut table = vec![];
+    table.extend_from_slice(&0u16.to_be_bytes());
+    table.extend_from_slice(&((count + 1) as u16).to_be_bytes());
+    table.extend_from_slice(&((records_end + 12) as u16).to_be_bytes());
+    table.extend_from_slice(name.get(6..records_end)?);
+    for &field in &[
+        3,
+        1,
+        0x0409,
+        name_id,
+        encoded.len() as u16,
+        strings.len() as u16,
+    ] {
+        table.extend_from_slice(&field.to_be_bytes());
+    }
+    table.extend_from_slice(strings);
+    table.extend_from_slice(&encoded);
+    Some(table)
+}
+
+// Write a simple glyph to `glyf`, returning its minimum x coordinate.
+fn write_glyph(contours: &[Vec<Point>], glyf: &mut Vec<u8>) -> i16 {
+    if contours.is_empty() {
+        return 0;
+    }
+    let points = contours.iter().flat_map(|c| c.iter().cloned());
+    let (mut x_min, mut y_min) = (i16::MAX, i16::MAX);
+    let (mut x_max, mut y_max) = (i16::MIN, i16::MIN);
+    for (x, y, _) in points.clone() {
+        x_min = std::cmp::min(x_min, x);
+        y_min = std::cmp::min(y_min, y);
+        x_max = std::cmp::max(x_max, x);
+        y_max = std::cmp::max(y_max, y);
+    }
+    for &v in &[contours.len() as i16, x_min, y_min, x_max, y_max] {
+        glyf.extend_from_slice(&v.to_be_bytes());
+    }
+    let mut end = 0;
+    for contour in contours {
+        end += contour.len();
+        glyf.extend_from_slice(&((end - 1) as u16).to_be_bytes());
+    }
+    // No instructions.
+    glyf.extend_from_slice(&0u16.to_be_bytes());
+    glyf.extend(points.clone().map(|(_, _, on_curve)| on_curve as u8));
+    let (mut last_x, mut last_y) = (0i16, 0i16);
+    let mut ys = vec![];
+    for (x, y, _) in points {
+        glyf.extend_from_slice(&x.wrapping_sub(last_x).to_be_bytes());
+        ys.extend_from_slice(&y.wrapping_sub(last_y).to_be_bytes());
+        last_x = x;
+        last_y = y;
+    }
+    glyf.extend_from_slice(&ys);
+    while glyf.len() % 4 != 0 {
+        glyf.push(0);
+    }
+    x_min
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+// Write the given tables, sorted by tag, to a new TrueType font file.
+fn write_font(tables: &[([u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let num_tables = tables.len() as u16;
+    let entry_selector = 15 - std::cmp::max(num_tables, 1).leading_zeros() as u16;
+    let search_range = (1 << entry_selector) * 16;
+    let mut font = vec![];
+    font.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+    for &v in &[
+        num_tables,
+        search_range,
+        entry_selector,
+        num_tables * 16 - search_range,
+    ] {
+        font.extend_from_slice(&v.to_be_bytes());
+    }
+
+    let mut offset = 12 + tables.len() * 16;
+    let mut head_offset = None;
+    for (tag, data) in tables {
+        if tag == b"head" {
+            head_offset = Some(offset);
+        }
+        font.extend_from_slice(tag);
+        font.extend_from_slice(&checksum(data).to_be_bytes());
+        font.extend_from_slice(&(offset as u32).to_be_bytes());
+        font.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += (data.len() + 3) & !3;
+    }
+    for (_, data) in tables {
+        font.extend_from_slice(data);
+        while font.len() % 4 != 0 {
+            font.push(0);
+        }
+    }
+
+    if let Some(head) = head_offset {
+        let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&font));
+        font[head + 8..head + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    font
+}
+
+impl Contours {
+    fn push(&mut self, x: f32, y: f32, on_curve: bool) {
+        if let Some(contour) = self.contours.last_mut() {
+            contour.push((x.round() as i16, y.round() as i16, on_curve));
+        }
+    }
+}
+
+impl ttf::OutlineBuilder for Contours {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.contours.push(vec![]);
+        self.push(x, y, true);
+        self.last = (x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.push(x, y, true);
+        self.last = (x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.push(x1, y1, false);
+        self.push(x, y, true);
+        self.last = (x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        // Approximate the cubic with a single quadratic curve.
+        let (x0, y0) = self.last;
+        let cx = (3.0 * (x1 + x2) - x0 - x) / 4.0;
+        let cy = (3.0 * (y1 + y2) - y0 - y) / 4.0;
+        self.quad_to(cx, cy, x, y);
+    }
+
+    fn close(&mut self) {
+        // The closing point is implied by the first point of the contour.
+        if let Some(contour) = self.contours.last_mut() {
+            if contour.len() > 1 && contour.first() == contour.last() {
+                contour.pop();
+            }
+        }
+    }
+}
@@ -0,0 +1,569 @@
+//! The `font::Id` and `font::Map` types, along with functions for loading fonts.
+//!
+//! Fonts installed on the system may be discovered and selected by family name, weight and style
+//! via the `system` module.
+
+use crate::text::{Font, FontCollection, GlyphId};
+use lazy_static::lazy_static;
+use rusttype::SharedBytes;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+
+mod instance;
+pub mod system;
+
+/// A type-safe wrapper around the `FontId`.
+///
+/// This is used as both:
+///
+/// - The key for the `font::Map`'s inner `HashMap`.
+/// - The `font_id` field for the rusttype::gpu_cache::Cache.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Id(usize);
+
+/// A collection of mappings from `font::Id`s to `rusttype::Font`s.
+///
+/// The `Map` also describes a chain of fallback fonts. When a font does not contain a glyph for
+/// some character, the first font in the fallback chain that does is used instead.
+#[derive(Debug)]
+pub struct Map {
+    next_index: usize,
+    map: HashMap<Id, Font>,
+    fallback: Vec<Id>,
+}
+
+/// The raw data from which a `Font` was loaded.
+///
+/// The data is required in order to shape text with the font via the `text::shape` module.
+#[derive(Clone, Debug)]
+pub struct Data {
+    /// The bytes of the font file or font collection, shared with the `Font`.
+    pub bytes: Arc<[u8]>,
+    /// The index of the font within the collection.
+    pub index: u32,
+}
+
+/// The value of a single axis of a variable font, e.g. `Variation::new(b"wght", 700.0)`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Variation {
+    /// The four-byte tag identifying the axis.
+    pub tag: [u8; 4],
+    /// The value of the axis.
+    pub value: f32,
+}
+
+/// An iterator yielding an `Id` for each new `rusttype::Font` inserted into the `Map` via the
+/// `insert_collection` method.
+pub struct NewIds {
+    index_range: std::ops::Range<usize>,
+}
+
+/// Yields the `Id` for each `Font` within the `Map`.
+#[derive(Clone)]
+pub struct Ids<'a> {
+    keys: std::collections::hash_map::Keys<'a, Id, Font>,
+}
+
+/// Returned when loading new fonts from file or bytes.
+#[derive(Debug)]
+pub enum Error {
+    /// Some error occurred while loading a `FontCollection` from a file.
+    Io(std::io::Error),
+    /// No `Font`s could be yielded from the `FontCollection`.
+    NoFont,
+    /// The font data could not be parsed.
+    Parse,
+    /// Variations were requested for a font that is not a variable TrueType font.
+    NotVariable,
+    /// The variable font has no axis with the given tag.
+    UnknownAxis([u8; 4]),
+}
+
+/// The name of the default directory that is searched for fonts.
+pub const DEFAULT_DIRECTORY_NAME: &str = "fonts";
+
+// A font file path, the index of the font within the file and the bits of each variation.
+type FileKey = (PathBuf, u32, Vec<(u32, u32)>);
+
+// A font loaded via `from_bytes`.
+//
+// Only a weak reference to the data is retained, so that the data is freed once every `Font`
+// referring to it has been dropped. The weak reference keeps the allocation itself reserved until
+// the entry is removed, so that no other data may occupy its address in the meantime.
+struct Loaded {
+    bytes: Weak<[u8]>,
+    index: u32,
+    // The address of the font's first name string, which borrows from `bytes`.
+    name: Option<usize>,
+}
+
+lazy_static! {
+    // Every font loaded via `from_bytes`, shared between all windows and threads.
+    static ref FONTS: Mutex<HashMap<Id, Loaded>> = Mutex::new(HashMap::new());
+    // The `Id` of each font loaded from a file, keyed by the path, index and variations.
+    static ref FILES: Mutex<HashMap<FileKey, Id>> = Mutex::new(HashMap::new());
+}
+
+#[cfg(feature = "notosans")]
+lazy_static! {
+    // The default font, loaded once and retained for the lifetime of the program.
+    static ref NOTOSANS: Font = from_bytes(notosans::REGULAR_TTF)
+        .expect("failed to load the `notosans::REGULAR_TTF` font");
+}
+
+impl Variation {
+    /// Specify the value of the axis with the given tag.
+    pub fn new(tag: &[u8; 4], value: f32) -> Self {
+        Variation { tag: *tag, value }
+    }
+}
+
+impl Loaded {
+    // The data of the font, if some `Font` still refers to it.
+    fn data(&self) -> Option<Data> {
+        let index = self.index;
+        self.bytes.upgrade().map(|bytes| Data { bytes, index })
+    }
+}
+
+impl Id {
+    /// Returns the inner `usize` from the `Id`.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl Map {
+    /// Construct the new, empty `Map`.
+    pub fn new() -> Self {
+        Map {
+            next_index: 0,
+            map: HashMap::default(),
+            fallback: vec![],
+        }
+    }
+
+    /// Borrow the `rusttype::Font` associated with the given `font::Id`.
+    pub fn get(&self, id: Id) -> Option<&Font> {
+        self.map.get(&id)
+    }
+
+    /// Adds the given `rusttype::Font` to the `Map` and returns a unique `Id` for it.
+    pub fn insert(&mut self, font: Font) -> Id {
+        let index = self.next_index;
+        self.next_index = index.wrapping_add(1);
+        let id = Id(index);
+        self.map.insert(id, font);
+        id
+    }
+
+    /// Insert a single `Font` into the map by loading it from the given file path.
+    pub fn insert_from_file<P>(&mut self, path: P) -> Result<Id, Error>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        let font = from_file(path)?;
+        Ok(self.insert(font))
+    }
+
+    /// Insert a single `Font` into the map by loading it from the given bytes.
+    pub fn insert_from_bytes<B>(&mut self, bytes: B) -> Result<Id, Error>
+    where
+        B: Into<SharedBytes<'static>>,
+    {
+        let font = from_bytes(bytes)?;
+        Ok(self.insert(font))
+    }
+
+    /// Specify the chain of fonts that are searched for glyphs missing from other fonts.
+    ///
+    /// Fonts are searched in the order in which they are yielded.
+    pub fn set_fallback<I>(&mut self, ids: I)
+    where
+        I: IntoIterator<Item = Id>,
+    {
+        self.fallback = ids.into_iter().collect();
+    }
+
+    /// Append the font with the given `Id` to the end of the fallback chain.
+    pub fn push_fallback(&mut self, id: Id) {
+        self.fallback.push(id);
+    }
+
+    /// The chain of fallback fonts in the order in which they are searched.
+    pub fn fallback(&self) -> &[Id] {
+        &self.fallback
+    }
+
+    /// The fonts within the fallback chain in the order in which they are searched.
+    ///
+    /// This is useful for passing to `text::Builder::fallback_fonts` or
+    /// `draw.text(..).fallback_fonts(..)`.
+    pub fn fallback_fonts(&self) -> Vec<Font> {
+        self.fallback
+            .iter()
+            .filter_map(|&id| self.get(id).cloned())
+            .collect()
+    }
+
+    /// Find the first font that contains a glyph for the given character, starting with the
+    /// font with the given `Id` and then searching the fallback chain.
+    ///
+    /// Returns `None` if no font within the chain covers the character.
+    pub fn covering(&self, id: Id, ch: char) -> Option<Id> {
+        std::iter::once(id)
+            .chain(self.fallback.iter().cloned())
+            .find(|&id| self.get(id).map(|font| covers(font, ch)).unwrap_or(false))
+    }
+
+    // /// Adds each font in the given `rusttype::FontCollection` to the `Map` and returns an
+    // /// iterator yielding a unique `Id` for each.
+    // pub fn insert_collection(&mut self, collection: FontCollection) -> NewIds {
+    //     let start_index = self.next_index;
+    //     let mut end_index = start_index;
+    //     for index in 0.. {
+    //         match collection.font_at(index) {
+    //             Some(font) => {
+    //                 self.insert(font);
+    //                 end_index += 1;
+    //             }
+    //             None => break,
+    //         }
+    //     }
+    //     NewIds { index_range: start_index..end_index }
+    // }
+
+    /// Produces an iterator yielding the `Id` for each `Font` within the `Map`.
+    pub fn ids(&self) -> Ids {
+        Ids {
+            keys: self.map.keys(),
+        }
+    }
+}
+
+/// Produce a unique ID for the given font.
+///
+/// Fonts loaded via this module are identified by the content of their data along with their
+/// index within it. Clones of a `Font` and fonts loaded from identical data share the same `Id`,
+/// while fonts with the same names but different data do not. All other fonts are identified by
+/// their names.
+pub fn id(font: &Font) -> Id {
+    if let Some(id) = loaded_id(font) {
+        return id;
+    }
+    let mut hasher = DefaultHasher::new();
+    for name in font.font_name_strings() {
+        name.hash(&mut hasher);
+    }
+    Id((hasher.finish() % std::usize::MAX as u64) as usize)
+}
+
+/// Whether or not the given font contains a glyph for the given character.
+pub fn covers(font: &Font, ch: char) -> bool {
+    font.glyph(ch).id() != GlyphId(0)
+}
+
+/// Select the font used to display the given character.
+///
+/// Returns the given `font` if it covers the character, otherwise the first font within the
+/// `fallback` chain that does. If no font covers the character, the given `font` is returned.
+pub fn fallback<'a>(font: &'a Font, fallback: &'a [Font], ch: char) -> &'a Font {
+    if fallback.is_empty() || covers(font, ch) {
+        return font;
+    }
+    fallback
+        .iter()
+        .find(|font| covers(font, ch))
+        .unwrap_or(font)
+}
+
+/// The data from which the given font was loaded.
+///
+/// Returns `None` if the font was not loaded via `from_bytes`, `from_file` or
+/// `default_notosans`.
+pub fn data(font: &Font) -> Option<Data> {
+    let id = id(font);
+    let fonts = FONTS.lock().expect("failed to acquire font cache lock");
+    fonts.get(&id).and_then(Loaded::data)
+}
+
+/// Load a `FontCollection` from a file at a given path.
+pub fn collection_from_file<P>(path: P) -> Result<FontCollection, std::io::Error>
+where
+    P: AsRef<std::path::Path>,
+{
+    use std::io::Read;
+    let path = path.as_ref();
+    let mut file = std::fs::File::open(path)?;
+    let mut file_buffer = Vec::new();
+    file.read_to_end(&mut file_buffer)?;
+    Ok(FontCollection::from_bytes(file_buffer)?)
+}
+
+/// Load a single `Font` from a file at the given path.
+///
+/// If the file is a font collection, the first font is loaded.
+pub fn from_file<P>(path: P) -> Result<Font, Error>
+where
+    P: AsRef<std::path::Path>,
+{
+    from_file_at(path, 0)
+}
+
+/// Load the font at the given index within the font file or collection at the given path.
+///
+/// Fonts are cached by their path and index, so loading the same font more than once returns a
+/// `Font` sharing the original data.
+pub fn from_file_at<P>(path: P, index: u32) -> Result<Font, Error>
+where
+    P: AsRef<std::path::Path>,
+{
+    from_file_with_variations(path, index, &[])
+}
+
+/// Load an instance of the variable font at the given index within the file at the given path.
+///
+/// Axes that are not specified take on their default value. If `variations` is empty, the font
+/// is loaded as is. Instances are cached in the same manner as `from_file_at`.
+pub fn from_file_with_variations<P>(
+    path: P,
+    index: u32,
+    variations: &[Variation],
+) -> Result<Font, Error>
+where
+    P: AsRef<std::path::Path>,
+{
+    let path = path.as_ref();
+    let key = (
+        path.to_path_buf(),
+        index,
+        variations
+            .iter()
+            .map(|v| (u32::from_be_bytes(v.tag), v.value.to_bits()))
+            .collect(),
+    );
+    let cached_id = FILES
+        .lock()
+        .expect("failed to acquire font file lock")
+        .get(&key)
+        .cloned();
+    if let Some(font) = cached_id.and_then(cached) {
+        return Ok(font);
+    }
+    let bytes = std::fs::read(path)?;
+    let font = if variations.is_empty() {
+        from_bytes_at(bytes, index)?
+    } else {
+        from_bytes_with_variations(&bytes, index, variations)?
+    };
+    FILES
+        .lock()
+        .expect("failed to acquire font file lock")
+        .insert(key, id(&font));
+    Ok(font)
+}
+
+/// Load a single `Font` from the given bytes.
+///
+/// If the bytes describe a font collection, the first font is loaded.
+///
+/// The bytes are retained so that text may be shaped with the font.
+pub fn from_bytes<B>(bytes: B) -> Result<Font, Error>
+where
+    B: Into<SharedBytes<'static>>,
+{
+    from_bytes_at(bytes, 0)
+}
+
+/// Load the font at the given index within the given font or font collection bytes.
+///
+/// The bytes are retained so that text may be shaped with the font. If the same font was already
+/// loaded from identical bytes and is still in use, a `Font` sharing the existing data is returned.
+pub fn from_bytes_at<B>(bytes: B, index: u32) -> Result<Font, Error>
+where
+    B: Into<SharedBytes<'static>>,
+{
+    let bytes: Arc<[u8]> = match bytes.into() {
+        SharedBytes::ByArc(bytes) => bytes,
+        SharedBytes::ByRef(bytes) => Arc::from(bytes),
+    };
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    index.hash(&mut hasher);
+    let id = Id((hasher.finish() % std::usize::MAX as u64) as usize);
+
+    let mut fonts = FONTS.lock().expect("failed to acquire font cache lock");
+    if let Some(data) = fonts.get(&id).and_then(Loaded::data) {
+        if data.bytes[..] == bytes[..] {
+            return font_from_data(&data);
+        }
+    }
+
+    let weak = Arc::downgrade(&bytes);
+    let font = font_from_data(&Data { bytes, index })?;
+    let name = first_name_address(&font);
+    // Forget any fonts that are no longer in use.
+    fonts.retain(|_, loaded| loaded.bytes.strong_count() > 0);
+    fonts.insert(
+        id,
+        Loaded {
+            bytes: weak,
+            index,
+            name,
+        },
+    );
+    Ok(font)
+}
+
+/// Load an instance of the variable font at the given index within the given bytes.
+///
+/// rusttype can only render the default instance of a variable font, so the outlines and metrics
+/// of the instance are resolved and written to a new, static font which is then loaded via
+/// `from_bytes`. Only variable fonts with TrueType outlines are supported. Axes that are not
+/// specified take on their default value.
+pub fn from_bytes_with_variations(
+    bytes: &[u8],
+    index: u32,
+    variations: &[Variation],
+) -> Result<Font, Error> {
+    let instance = instance::instantiate(bytes, index, variations)?;
+    from_bytes(instance)
+}
+
+/// The font with the given `Id`, if it was loaded via one of the loading functions within this
+/// module and some `Font` sharing its data is still in use.
+///
+/// Loaded fonts are shared between all windows and threads.
+pub fn cached(id: Id) -> Option<Font> {
+    let data = FONTS
+        .lock()
+        .expect("failed to acquire font cache lock")
+        .get(&id)
+        .and_then(Loaded::data)?;
+    font_from_data(&data).ok()
+}
+
+/// Load the default notosans font.
+///
+/// The font is only loaded once, after which the same data is shared between all calls.
+///
+/// This function is only available if the `notosans` feature is enabled, which it is by default.
+#[cfg(feature = "notosans")]
+pub fn default_notosans() -> Font {
+    NOTOSANS.clone()
+}
+
+/// The directory that is searched for default fonts.
+pub fn default_directory(assets: &Path) -> PathBuf {
+    assets.join(DEFAULT_DIRECTORY_NAME)
+}
+
+/// Load the default font.
+///
+/// If the `notosans` feature is enabled, this will return the font loaded from
+/// `notosans::REGULAR_TTF`.
+///
+/// Otherwise this will attempt to locate the `assets/fonts` directory. If the directory exists,
+/// the first font that is found will be loaded. If no fonts are found, an error is returned.
+#[allow(unreachable_code, unused_variables)]
+pub fn default(assets: &Path) -> Result<Font, Error> {
+    #[cfg(feature = "notosans")]
+    {
+        return Ok(default_notosans());
+    }
+
+    // Find a font in `assets/fonts`.
+    let fonts_dir = default_directory(assets);
+    if fonts_dir.exists() && fonts_dir.is_dir() {
+        for res in crate::io::walk_dir(&fonts_dir) {
+            let entry = match res {
+                Ok(e) => e,
+                Err(_) => continue,
+            };
+            match from_file(entry.path()) {
+                Err(_) => continue,
+                Ok(font) => return Ok(font),
+            }
+        }
+    }
+
+    Err(Error::NoFont)
+}
+
+// The `Id` of the given font if it shares the data of a font loaded via `from_bytes`.
+//
+// rusttype does not expose the data of a font, however its name strings borrow from it. The
+// address of the first name string identifies both the data and the font within it, and remains
+// unique while the `Loaded` entry reserves the data's allocation.
+fn loaded_id(font: &Font) -> Option<Id> {
+    let name = first_name_address(font)?;
+    let fonts = FONTS.lock().expect("failed to acquire font cache lock");
+    fonts
+        .iter()
+        .find(|(_, loaded)| loaded.name == Some(name))
+        .map(|(&id, _)| id)
+}
+
+fn first_name_address(font: &Font) -> Option<usize> {
+    font.font_name_strings()
+        .next()
+        .map(|name| name.0.as_ptr() as usize)
+}
+
+// Load the font described by the given data, sharing the data with the font.
+fn font_from_data(data: &Data) -> Result<Font, Error> {
+    let bytes = SharedBytes::ByArc(data.bytes.clone());
+    let collection =
+        FontCollection::from_bytes(bytes).map_err(|err| Error::Io(std::io::Error::from(err)))?;
+    collection
+        .font_at(data.index as usize)
+        .or(Err(Error::NoFont))
+}
+
+impl Iterator for NewIds {
+    type Item = Id;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.index_range.next().map(|i| Id(i))
+    }
+}
+
+impl<'a> Iterator for Ids<'a> {
+    type Item = Id;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.keys.next().map(|&id| id)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl std::error::Error for Error {
+    fn cause(&self) -> Option<&dyn std::error::Error> {
+        match *self {
+            Error::Io(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match *self {
+            Error::Io(ref e) => std::fmt::Display::fmt(e, f),
+            Error::NoFont => write!(f, "No `Font` found in the loaded `FontCollection`."),
+            Error::Parse => write!(f, "The font data could not be parsed."),
+            Error::NotVariable => write!(f, "The font is not a variable TrueType font."),
+            Error::UnknownAxis(tag) => write!(
+                f,
+                "The variable font has no `{}` axis.",
+                String::from_utf8_lossy(&tag)
+            ),
+        }
+    }
+}
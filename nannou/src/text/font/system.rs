@@ -0,0 +1,648 @@
+//! Discovery of the fonts installed on the system and selection of fonts by family name, weight
+//! and style.
+//!
+//! On Linux and other unix-like systems, the font directories are read from the fontconfig
+//! configuration. On macOS and Windows the standard font directories are searched.
+//!
+//! ```no_run
+//! use nannou::text::font::system::{Query, Style, Weight};
+//!
+//! let font = Query::new("DejaVu Sans")
+//!     .weight(Weight::BOLD)
+//!     .style(Style::Italic)
+//!     .load()
+//!     .unwrap();
+//! ```
+
+use super::{Error, Variation};
+use crate::text::Font;
+use lazy_static::lazy_static;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use ttf_parser as ttf;
+
+/// The weight of a font face in the range `1..=1000` as described by CSS and the `OS/2` table.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Weight(pub u16);
+
+/// The width of a font face in the range `1..=9` as described by the `OS/2` table, where `5` is
+/// a normal width.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Width(pub u16);
+
+/// The slant of a font face.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Style {
+    /// An upright face.
+    Normal,
+    /// A face with cursive, slanted forms.
+    Italic,
+    /// A slanted version of the upright face.
+    Oblique,
+}
+
+/// A variation axis of a variable font face.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Axis {
+    /// The four-byte tag identifying the axis, e.g. `b"wght"`.
+    pub tag: [u8; 4],
+    /// The minimum value of the axis.
+    pub min: f32,
+    /// The value of the axis for the default instance.
+    pub default: f32,
+    /// The maximum value of the axis.
+    pub max: f32,
+}
+
+/// Describes a single font face found within a font file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FaceInfo {
+    /// The path to the font file.
+    pub path: PathBuf,
+    /// The index of the face within the file. This is always `0` unless the file is a
+    /// collection (`.ttc`/`.otc`).
+    pub index: u32,
+    /// The family name of the face, e.g. "DejaVu Sans".
+    pub family: String,
+    /// The full name of the face, e.g. "DejaVu Sans Bold Oblique".
+    pub full_name: Option<String>,
+    /// The weight of the default instance of the face.
+    pub weight: Weight,
+    /// The width of the default instance of the face.
+    pub width: Width,
+    /// The slant of the face.
+    pub style: Style,
+    /// Whether or not every glyph within the face has the same advance.
+    pub monospaced: bool,
+    /// The variation axes of the face. Empty unless the face is a variable font.
+    pub axes: Vec<Axis>,
+}
+
+/// A collection of font faces gathered from font files and directories.
+#[derive(Clone, Debug, Default)]
+pub struct Database {
+    faces: Vec<FaceInfo>,
+}
+
+/// Describes the desired family, weight and style of a font face.
+#[derive(Clone, Debug)]
+pub struct Query {
+    families: Vec<String>,
+    weight: Weight,
+    width: Width,
+    style: Style,
+    variations: Vec<Variation>,
+}
+
+// The file extensions of font files that are loaded when scanning directories.
+const EXTENSIONS: &[&str] = &["ttf", "otf", "ttc", "otc"];
+
+// Limits the depth of fontconfig `<include>` recursion.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+lazy_static! {
+    // The database of system fonts, scanned once upon first use.
+    static ref SYSTEM: Database = Database::system();
+}
+
+impl Weight {
+    pub const THIN: Self = Weight(100);
+    pub const EXTRA_LIGHT: Self = Weight(200);
+    pub const LIGHT: Self = Weight(300);
+    pub const NORMAL: Self = Weight(400);
+    pub const MEDIUM: Self = Weight(500);
+    pub const SEMI_BOLD: Self = Weight(600);
+    pub const BOLD: Self = Weight(700);
+    pub const EXTRA_BOLD: Self = Weight(800);
+    pub const BLACK: Self = Weight(900);
+}
+
+impl Width {
+    pub const ULTRA_CONDENSED: Self = Width(1);
+    pub const EXTRA_CONDENSED: Self = Width(2);
+    pub const CONDENSED: Self = Width(3);
+    pub const SEMI_CONDENSED: Self = Width(4);
+    pub const NORMAL: Self = Width(5);
+    pub const SEMI_EXPANDED: Self = Width(6);
+    pub const EXPANDED: Self = Width(7);
+    pub const EXTRA_EXPANDED: Self = Width(8);
+    pub const ULTRA_EXPANDED: Self = Width(9);
+}
+
+impl FaceInfo {
+    /// Describe each face within the given font data.
+    ///
+    /// Faces that cannot be parsed or that have no family name are skipped.
+    pub fn from_bytes(bytes: &[u8], path: &Path) -> Vec<Self> {
+        let count = ttf::fonts_in_collection(bytes).unwrap_or(1);
+        (0..count)
+            .filter_map(|index| {
+                let face = ttf::Face::from_slice(bytes, index).ok()?;
+                Self::from_face(&face, path, index)
+            })
+            .collect()
+    }
+
+    fn from_face(face: &ttf::Face, path: &Path, index: u32) -> Option<Self> {
+        let family = name(face, ttf::name_id::TYPOGRAPHIC_FAMILY)
+            .or_else(|| name(face, ttf::name_id::FAMILY))?;
+        let full_name = name(face, ttf::name_id::FULL_NAME);
+        let style = if face.is_italic() {
+            Style::Italic
+        } else if face.is_oblique() {
+            Style::Oblique
+        } else {
+            Style::Normal
+        };
+        let axes = face
+            .variation_axes()
+            .map(|axis| Axis {
+                tag: axis.tag.0.to_be_bytes(),
+                min: axis.min_value,
+                default: axis.def_value,
+                max: axis.max_value,
+            })
+            .collect();
+        Some(FaceInfo {
+            path: path.to_path_buf(),
+            index,
+            family,
+            full_name,
+            weight: Weight(face.weight().to_number()),
+            width: Width(face.width().to_number()),
+            style,
+            monospaced: face.is_monospaced(),
+            axes,
+        })
+    }
+
+    /// Whether or not the face is a variable font.
+    pub fn is_variable(&self) -> bool {
+        !self.axes.is_empty()
+    }
+
+    /// The variation axis with the given tag, if there is one.
+    pub fn axis(&self, tag: &[u8; 4]) -> Option<&Axis> {
+        self.axes.iter().find(|axis| &axis.tag == tag)
+    }
+
+    /// Load the face.
+    ///
+    /// Fonts are cached by their file path and index, so loading the same face more than once
+    /// (e.g. from multiple windows) returns the same `Font`.
+    pub fn load(&self) -> Result<Font, Error> {
+        super::from_file_at(&self.path, self.index)
+    }
+
+    /// Load an instance of the variable face with the given axis settings.
+    ///
+    /// Axes that are not specified take on their default value.
+    pub fn load_with_variations(&self, variations: &[Variation]) -> Result<Font, Error> {
+        super::from_file_with_variations(&self.path, self.index, variations)
+    }
+}
+
+impl Database {
+    /// An empty database.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A database of all fonts found within the system font `directories`.
+    pub fn system() -> Self {
+        let mut db = Self::new();
+        for dir in directories() {
+            db.load_dir(dir);
+        }
+        db
+    }
+
+    /// Load all faces within the font file at the given path.
+    pub fn load_file<P>(&mut self, path: P) -> Result<(), std::io::Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)?;
+        self.faces.extend(FaceInfo::from_bytes(&bytes, path));
+        Ok(())
+    }
+
+    /// Recursively load all font files within the given directory.
+    ///
+    /// Files that cannot be read or parsed are skipped.
+    pub fn load_dir<P>(&mut self, dir: P)
+    where
+        P: AsRef<Path>,
+    {
+        for entry in crate::io::walk_dir(dir).into_iter().filter_map(Result::ok) {
+            let path = entry.path();
+            let is_font = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| EXTENSIONS.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+                .unwrap_or(false);
+            if is_font && path.is_file() {
+                self.load_file(path).ok();
+            }
+        }
+    }
+
+    /// Add a face to the database.
+    pub fn push(&mut self, face: FaceInfo) {
+        self.faces.push(face);
+    }
+
+    /// All faces within the database.
+    pub fn faces(&self) -> &[FaceInfo] {
+        &self.faces
+    }
+
+    /// The unique family names within the database in alphabetical order.
+    pub fn families(&self) -> Vec<&str> {
+        let mut families: Vec<_> = self.faces.iter().map(|f| &f.family[..]).collect();
+        families.sort();
+        families.dedup();
+        families
+    }
+
+    /// All faces that belong to the given family. Family names are compared case-insensitively.
+    pub fn family<'a>(&'a self, family: &'a str) -> impl Iterator<Item = &'a FaceInfo> {
+        self.faces
+            .iter()
+            .filter(move |face| face.family.eq_ignore_ascii_case(family))
+    }
+
+    /// Select the face that best matches the given query.
+    ///
+    /// Each family of the query is tried in order. Within a family, faces are matched by width,
+    /// then by style and then by weight following the CSS font matching algorithm.
+    pub fn query(&self, query: &Query) -> Option<&FaceInfo> {
+        query.families.iter().find_map(|family| {
+            let faces = self.faces.iter();
+            let faces = faces.filter(|face| face.family.eq_ignore_ascii_case(family));
+            faces.min_by_key(|face| {
+                let width = width_rank(query.width, face.width);
+                let style = style_rank(query.style, face.style);
+                let weight = weight_rank(query.weight, effective_weight(face, query));
+                (width, style, weight)
+            })
+        })
+    }
+}
+
+impl Query {
+    /// Query the given family with a normal weight, width and style.
+    pub fn new<S>(family: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Query {
+            families: vec![family.into()],
+            weight: Weight::NORMAL,
+            width: Width::NORMAL,
+            style: Style::Normal,
+            variations: vec![],
+        }
+    }
+
+    /// A family to try if none of the previous families are installed.
+    pub fn or<S>(mut self, family: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.families.push(family.into());
+        self
+    }
+
+    /// The desired weight.
+    pub fn weight(mut self, weight: Weight) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// The desired width.
+    pub fn width(mut self, width: Width) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// The desired style.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Specify the value of a variation axis, used if the selected face is a variable font.
+    ///
+    /// If the face has a `wght` axis and no value is given for it, the desired weight is used.
+    pub fn variation(mut self, tag: &[u8; 4], value: f32) -> Self {
+        self.variations.push(Variation::new(tag, value));
+        self
+    }
+
+    /// Select the best matching face within the given database.
+    pub fn find<'a>(&self, db: &'a Database) -> Option<&'a FaceInfo> {
+        db.query(self)
+    }
+
+    /// Select and load the best matching face among the system fonts.
+    pub fn load(&self) -> Result<Font, Error> {
+        self.load_from(database())
+    }
+
+    /// Select and load the best matching face within the given database.
+    ///
+    /// If the selected face is a variable font, the instance is loaded with the query's
+    /// variations and weight.
+    pub fn load_from(&self, db: &Database) -> Result<Font, Error> {
+        let face = self.find(db).ok_or(Error::NoFont)?;
+        if !face.is_variable() {
+            return face.load();
+        }
+        let mut variations: Vec<_> = self
+            .variations
+            .iter()
+            .filter(|v| face.axis(&v.tag).is_some())
+            .cloned()
+            .collect();
+        let has_weight = variations.iter().any(|v| &v.tag == b"wght");
+        if !has_weight && face.axis(b"wght").is_some() {
+            variations.push(Variation::new(b"wght", self.weight.0 as f32));
+        }
+        if variations.is_empty() {
+            return face.load();
+        }
+        face.load_with_variations(&variations)
+    }
+}
+
+/// The database of fonts installed on the system.
+///
+/// The system font directories are scanned upon the first call.
+pub fn database() -> &'static Database {
+    &SYSTEM
+}
+
+/// The directories that are searched for system fonts.
+///
+/// On unix-like systems these are the `<dir>` entries of the fontconfig configuration, falling
+/// back to the common defaults if no configuration is found. Only existing directories are
+/// returned.
+pub fn directories() -> Vec<PathBuf> {
+    let mut dirs = platform_directories();
+    let mut seen = HashSet::new();
+    dirs.retain(|dir| dir.is_dir() && seen.insert(dir.clone()));
+    dirs
+}
+
+#[cfg(target_os = "macos")]
+fn platform_directories() -> Vec<PathBuf> {
+    let mut dirs = vec![
+        PathBuf::from("/System/Library/Fonts"),
+        PathBuf::from("/Library/Fonts"),
+        PathBuf::from("/Network/Library/Fonts"),
+    ];
+    if let Some(home) = home_dir() {
+        dirs.push(home.join("Library/Fonts"));
+    }
+    dirs
+}
+
+#[cfg(target_os = "windows")]
+fn platform_directories() -> Vec<PathBuf> {
+    let mut dirs = vec![];
+    if let Some(windir) = std::env::var_os("WINDIR") {
+        dirs.push(PathBuf::from(windir).join("Fonts"));
+    }
+    if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+        dirs.push(PathBuf::from(local).join("Microsoft\\Windows\\Fonts"));
+    }
+    dirs
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn platform_directories() -> Vec<PathBuf> {
+    let config = std::env::var_os("FONTCONFIG_FILE")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("/etc/fonts/fonts.conf"));
+    let mut dirs = vec![];
+    fontconfig_dirs(&config, 0, &mut dirs);
+    if dirs.is_empty() {
+        dirs.push(PathBuf::from("/usr/share/fonts"));
+        dirs.push(PathBuf::from("/usr/local/share/fonts"));
+        dirs.push(xdg_data_home().join("fonts"));
+        if let Some(home) = home_dir() {
+            dirs.push(home.join(".fonts"));
+        }
+    }
+    dirs
+}
+
+// Collect the font directories of the fontconfig file at `path`, following includes.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn fontconfig_dirs(path: &Path, depth: usize, dirs: &mut Vec<PathBuf>) {
+    if depth > MAX_INCLUDE_DEPTH {
+        return;
+    }
+    if path.is_dir() {
+        let mut files: Vec<_> = match std::fs::read_dir(path) {
+            Ok(entries) => entries.filter_map(Result::ok).map(|e| e.path()).collect(),
+            Err(_) => return,
+        };
+        files.retain(|p| p.extension().map(|e| e == "conf").unwrap_or(false));
+        files.sort();
+        for file in files {
+            fontconfig_dirs(&file, depth + 1, dirs);
+        }
+        return;
+    }
+    let conf = match std::fs::read_to_string(path) {
+        Ok(conf) => conf,
+        Err(_) => return,
+    };
+    let parent = path.parent().unwrap_or_else(|| Path::new("/"));
+    for (prefix, dir) in elements(&conf, "dir") {
+        dirs.push(resolve(
+            prefix.as_ref().map(|s| &s[..]),
+            &dir,
+            &xdg_data_home(),
+            parent,
+        ));
+    }
+    for (prefix, include) in elements(&conf, "include") {
+        let include = resolve(
+            prefix.as_ref().map(|s| &s[..]),
+            &include,
+            &xdg_config_home(),
+            parent,
+        );
+        fontconfig_dirs(&include, depth + 1, dirs);
+    }
+}
+
+// Resolve a fontconfig path with the given `prefix` attribute.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn resolve(prefix: Option<&str>, path: &str, xdg: &Path, parent: &Path) -> PathBuf {
+    if prefix == Some("xdg") {
+        return xdg.join(path);
+    }
+    if path == "~" || path.starts_with("~/") {
+        if let Some(home) = home_dir() {
+            return home.join(path.trim_start_matches('~').trim_start_matches('/'));
+        }
+    }
+    let path = Path::new(path);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        parent.join(path)
+    }
+}
+
+// The text and `prefix` attribute of each `<name ...>text</name>` element.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn elements(conf: &str, name: &str) -> Vec<(Option<String>, String)> {
+    let open = format!("<{}", name);
+    let close = format!("</{}>", name);
+    let mut elems = vec![];
+    let mut rest = conf;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        // Ensure this is the element itself and not one sharing the prefix, e.g. `<dirs>`.
+        match rest.chars().next() {
+            Some('>') | Some(' ') | Some('\t') | Some('\n') | Some('\r') => (),
+            _ => continue,
+        }
+        let tag_end = match rest.find('>') {
+            Some(i) => i,
+            None => break,
+        };
+        let attrs = &rest[..tag_end];
+        if attrs.ends_with('/') {
+            continue;
+        }
+        rest = &rest[tag_end + 1..];
+        let text_end = match rest.find(&close) {
+            Some(i) => i,
+            None => break,
+        };
+        let text = rest[..text_end].trim();
+        if !text.is_empty() {
+            elems.push((attribute(attrs, "prefix"), text.to_string()));
+        }
+        rest = &rest[text_end + close.len()..];
+    }
+    elems
+}
+
+// The value of the attribute with the given name.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn attribute(attrs: &str, name: &str) -> Option<String> {
+    let pattern = format!("{}=", name);
+    let start = attrs.find(&pattern)? + pattern.len();
+    let value = &attrs[start..];
+    let quote = value.chars().next().filter(|&c| c == '"' || c == '\'')?;
+    let value = &value[1..];
+    let end = value.find(quote)?;
+    Some(value[..end].to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(PathBuf::from)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn xdg_data_home() -> PathBuf {
+    std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".local/share")))
+        .unwrap_or_default()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn xdg_config_home() -> PathBuf {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".config")))
+        .unwrap_or_default()
+}
+
+// The first unicode name record with the given ID, preferring English.
+fn name(face: &ttf::Face, name_id: u16) -> Option<String> {
+    let names = || face.names().filter(|n| n.name_id() == name_id);
+    names()
+        .filter(|n| n.language_id() == 0x0409)
+        .chain(names())
+        .find_map(|n| n.to_string())
+        .filter(|s| !s.is_empty())
+}
+
+// The weight of the face closest to the desired weight, taking the `wght` axis into account.
+fn effective_weight(face: &FaceInfo, query: &Query) -> Weight {
+    match face.axis(b"wght") {
+        Some(axis) => {
+            let w = query.weight.0 as f32;
+            Weight(w.max(axis.min).min(axis.max).round() as u16)
+        }
+        None => face.weight,
+    }
+}
+
+// Narrower widths are preferred for normal or narrow queries and wider for wide queries. Lower
+// is better.
+fn width_rank(desired: Width, width: Width) -> (u8, u16) {
+    let (d, w) = (desired.0, width.0);
+    let distance = if w > d { w - d } else { d - w };
+    let tier = if (d <= 5 && w <= d) || (d > 5 && w >= d) {
+        0
+    } else {
+        1
+    };
+    (tier, distance)
+}
+
+// Lower is better.
+fn style_rank(desired: Style, style: Style) -> u8 {
+    let order = match desired {
+        Style::Normal => [Style::Normal, Style::Oblique, Style::Italic],
+        Style::Italic => [Style::Italic, Style::Oblique, Style::Normal],
+        Style::Oblique => [Style::Oblique, Style::Italic, Style::Normal],
+    };
+    order
+        .iter()
+        .position(|&s| s == style)
+        .unwrap_or(order.len()) as u8
+}
+
+// Rank the weight as per the CSS font matching algorithm, lower is better.
+//
+// - Between 400 and 500: heavier weights up to 500 first, then lighter, then heavier than 500.
+// - Below 400: lighter weights first, then heavier.
+// - Above 500: heavier weights first, then lighter.
+fn weight_rank(desired: Weight, weight: Weight) -> (u8, u16) {
+    let (d, w) = (desired.0, weight.0);
+    let distance = if w > d { w - d } else { d - w };
+    let tier = if (400..=500).contains(&d) {
+        if (d..=500).contains(&w) {
+            0
+        } else if w < d {
+            1
+        } else {
+            2
+        }
+    } else if d < 400 {
+        if w <= d {
+            0
+        } else {
+            1
+        }
+    } else if w >= d {
+        0
+    } else {
+        1
+    };
+    (tier, distance)
+}
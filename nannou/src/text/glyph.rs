@@ -1,7 +1,8 @@
 //! Logic and types specific to individual glyph layout.
 
-use crate::geom::{Range, Rect};
-use crate::text::{self, FontSize, Scalar, ScaledGlyph};
+use crate::color::LinSrgba;
+use crate::geom::{self, Range, Rect, Vector2};
+use crate::text::{self, FontSize, Point, Scalar, ScaledGlyph};
 
 /// Some position along the X axis (used within `CharXs`).
 pub type X = Scalar;
@@ -48,6 +49,48 @@ pub struct SelectedRectsPerLine<'a, I> {
     end_cursor_idx: text::cursor::Index,
 }
 
+/// A single glyph positioned within a **Text** layout.
+///
+/// Unlike the glyphs yielded by the `Rects` iterator, each glyph may have its own font and font
+/// size as specified by the spans of the **Text**.
+#[derive(Clone)]
+pub struct Info {
+    /// The glyph, scaled to its font size.
+    pub glyph: ScaledGlyph<'static>,
+    /// The unique identifier of the font from which the glyph was produced.
+    pub font_id: text::font::Id,
+    /// The font size of the glyph.
+    pub font_size: FontSize,
+    /// The position of the glyph's origin along its baseline.
+    pub position: Point,
+    /// The bounding rectangle of the glyph.
+    ///
+    /// The bottom of the `Rect` lies on the glyph's baseline.
+    pub rect: Rect,
+    /// The index of the line on which the glyph lies.
+    pub line: usize,
+    /// The byte index of the character that produced the glyph.
+    pub byte: usize,
+    /// The color of the glyph, if one was specified by a span.
+    pub color: Option<LinSrgba>,
+}
+
+/// A transform applied to a single glyph about its origin.
+///
+/// Transforms are produced by `Text::glyph_transforms` and `Text::transforms_on_path` and may be
+/// modified before being used to render the glyph, e.g. via `draw.text(..).glyph_transform(..)`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    /// The position of the glyph's origin along its baseline.
+    pub position: Point,
+    /// The rotation of the glyph about its origin in radians.
+    pub rotation: Scalar,
+    /// The scale of the glyph about its origin.
+    pub scale: Vector2,
+    /// The color of the glyph, if it differs from the color of the text.
+    pub color: Option<LinSrgba>,
+}
+
 struct ContourPathEvents {
     segments: std::vec::IntoIter<rusttype::Segment>,
     first: lyon::math::Point,
@@ -56,6 +99,30 @@ struct ContourPathEvents {
     last: Option<lyon::math::Point>,
 }
 
+impl Info {
+    /// The transform that displays the glyph at its position within the text layout.
+    pub fn transform(&self) -> Transform {
+        Transform {
+            position: self.position,
+            rotation: 0.0,
+            scale: geom::vec2(1.0, 1.0),
+            color: self.color,
+        }
+    }
+}
+
+impl Transform {
+    /// Apply the transform to a point relative to the glyph's original origin within the text
+    /// layout.
+    pub fn transform_point(&self, origin: Point, point: Point) -> Point {
+        let v = point - origin;
+        let (x, y) = (v.x * self.scale.x, v.y * self.scale.y);
+        let (sin, cos) = self.rotation.sin_cos();
+        let rotated = geom::vec2(x * cos - y * sin, x * sin + y * cos);
+        self.position + rotated
+    }
+}
+
 impl<'a, 'b> Iterator for Rects<'a, 'b> {
     type Item = (ScaledGlyph<'a>, Rect);
     fn next(&mut self) -> Option<Self::Item> {
@@ -10,6 +10,7 @@ pub struct Builder {
     pub font_size: Option<FontSize>,
     pub justify: Option<Justify>,
     pub font: Option<Option<Font>>,
+    pub fallback_fonts: Option<Vec<Font>>,
     pub y_align: Option<Align>,
 }
 
@@ -21,6 +22,7 @@ pub struct Layout {
     pub justify: Justify,
     pub font_size: FontSize,
     pub font: Option<Font>,
+    pub fallback_fonts: Vec<Font>,
     pub y_align: Align,
 }
 
@@ -72,6 +74,18 @@ impl Builder {
         self
     }
 
+    /// Specify the chain of fonts that are searched for characters that are missing from the
+    /// `font`, in the order in which they are searched.
+    ///
+    /// See `font::Map::fallback_fonts` for retrieving the fallback chain of a `font::Map`.
+    pub fn fallback_fonts<I>(mut self, fonts: I) -> Self
+    where
+        I: IntoIterator<Item = Font>,
+    {
+        self.fallback_fonts = Some(fonts.into_iter().collect());
+        self
+    }
+
     /// Describe the end along the *x* axis to which the text should be aligned.
     pub fn justify(mut self, justify: Justify) -> Self {
         self.justify = Some(justify);
@@ -93,6 +107,14 @@ impl Builder {
         self.justify(Justify::Right)
     }
 
+    /// Align wrapped lines to both the start and end of the bounding **Rect**'s *x* axis range.
+    ///
+    /// Extra space is added between words in order to achieve this alignment. Lines ending in a
+    /// newline, along with the last line, are aligned to the start.
+    pub fn full_justify(self) -> Self {
+        self.justify(Justify::Full)
+    }
+
     /// Specify how much vertical space should separate each line of text.
     pub fn line_spacing(mut self, spacing: Scalar) -> Self {
         self.line_spacing = Some(spacing);
@@ -125,6 +147,7 @@ impl Builder {
     /// Set all the parameters via an existing `Layout`
     pub fn layout(mut self, layout: &Layout) -> Self {
         self.font = Some(layout.font.clone());
+        self.fallback_fonts = Some(layout.fallback_fonts.clone());
         self.line_spacing(layout.line_spacing)
             .line_wrap(layout.line_wrap)
             .justify(layout.justify)
@@ -140,6 +163,7 @@ impl Builder {
             justify: self.justify.unwrap_or(DEFAULT_JUSTIFY),
             font_size: self.font_size.unwrap_or(DEFAULT_FONT_SIZE),
             font: self.font.unwrap_or(None),
+            fallback_fonts: self.fallback_fonts.unwrap_or_default(),
             y_align: self.y_align.unwrap_or(DEFAULT_Y_ALIGN),
         }
     }
@@ -153,6 +177,7 @@ impl Default for Layout {
             justify: DEFAULT_JUSTIFY,
             font_size: DEFAULT_FONT_SIZE,
             font: None,
+            fallback_fonts: vec![],
             y_align: DEFAULT_Y_ALIGN,
         }
     }
@@ -3,7 +3,7 @@
 //! This module is the core of multi-line text handling.
 
 use crate::geom::{Range, Rect};
-use crate::text::{self, FontSize, Scalar, Wrap};
+use crate::text::{self, span, FontSize, Scalar, Wrap};
 
 /// The two types of **Break** indices returned by the **WrapIndicesBy** iterators.
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -66,6 +66,10 @@ pub struct Info {
     pub width: Scalar,
     /// The greatest height of all characters yielded.
     pub height: Scalar,
+    /// The greatest font size of all characters within the line.
+    ///
+    /// This is used as the height of the line's `Rect`.
+    pub font_size: FontSize,
 }
 
 /// An iterator yielding an `Info` struct for each line in the given `text` wrapped by the
@@ -90,14 +94,37 @@ pub struct Infos<'a, F> {
     last_break: Option<Break>,
 }
 
+/// An iterator yielding an `Info` struct for each line in the given `text`, where the font and
+/// font size of each character may be changed by a list of `text::Span`s.
+///
+/// Construct a `StyledInfos` iterator via the [infos_styled function](./fn.infos_styled.html).
+#[derive(Clone)]
+pub struct StyledInfos<'a> {
+    text: &'a str,
+    font: &'a text::Font,
+    font_size: FontSize,
+    spans: &'a [text::Span],
+    fallback: &'a [text::Font],
+    line_wrap: Option<Wrap>,
+    max_width: Scalar,
+    /// The advance of each byte of the text, if the text is measured by its shaped glyphs.
+    advances: Option<&'a [Option<Scalar>]>,
+    /// The index that indicates the start of the next line to be yielded.
+    start_byte: usize,
+    /// The character index that indicates the start of the next line to be yielded.
+    start_char: usize,
+    /// The break type of the previously yielded line
+    last_break: Option<Break>,
+}
+
 /// An iterator yielding a `Rect` for each line in
 #[derive(Clone)]
 pub struct Rects<I> {
     infos: I,
     x_align: text::Justify,
+    x_bounds: Range,
     line_spacing: Scalar,
-    last_line_top: Scalar,
-    font_size: FontSize,
+    last_line_bottom: Scalar,
     next: Option<Rect>,
 }
 
@@ -194,6 +221,19 @@ impl<'a> Infos<'a, NextBreakFnPtr> {
     }
 }
 
+impl<'a> StyledInfos<'a> {
+    /// Measure the text by the given advances rather than by the glyph of each character, e.g. in
+    /// order to wrap the text by the advances of its shaped glyphs.
+    ///
+    /// `advances` contains an element for each byte of the text. The advance of each cluster of
+    /// characters shaped together, e.g. into a ligature, belongs to the first byte of the cluster.
+    /// All other bytes are `None`. Lines are never wrapped within a cluster.
+    pub fn advances(mut self, advances: &'a [Option<Scalar>]) -> Self {
+        self.advances = Some(advances);
+        self
+    }
+}
+
 /// A function for finding the advance width between the given character that also considers
 /// the kerning for some previous glyph.
 ///
@@ -473,6 +513,183 @@ fn next_break_by_whitespace(
     }
 }
 
+/// The same as `advance_width_and_height`, but for a character whose style has been resolved from
+/// some list of spans.
+///
+/// Kerning is only applied between glyphs of the same font and font size.
+fn styled_advance_width_and_height(
+    ch: char,
+    style: &span::Resolved,
+    last_glyph: &mut Option<(*const text::Font, FontSize, text::GlyphId)>,
+) -> (Scalar, Scalar) {
+    let font = style.font;
+    let scale = text::pt_to_scale(style.font_size);
+    let g = font.glyph(ch).scaled(scale);
+    let kern = match *last_glyph {
+        Some((last_font, last_size, last_id))
+            if std::ptr::eq(last_font, font) && last_size == style.font_size =>
+        {
+            font.pair_kerning(scale, last_id, g.id())
+        }
+        _ => 0.0,
+    };
+    let advance_width = g.h_metrics().advance_width;
+    let height = g
+        .exact_bounding_box()
+        .map(|bb| bb.min.y.abs() as Scalar + style.baseline_shift)
+        .unwrap_or(0.0);
+    *last_glyph = Some((font as *const _, style.font_size, g.id()));
+    let adv_w = (kern + advance_width) as Scalar;
+    (adv_w, height)
+}
+
+/// Returns the next break within the text of the given `infos` beginning at the byte `offset`, in
+/// the same manner as the `next_break`, `next_break_by_character` and `next_break_by_whitespace`
+/// functions, where the style of each character is resolved from the spans of the `infos`.
+///
+/// Characters that are not covered by their resolved font use the first font within the
+/// `fallback` chain that covers them.
+///
+/// Also returns the greatest font size of all characters within the line.
+fn next_break_styled(infos: &StyledInfos, offset: usize) -> (NextBreak, FontSize) {
+    let StyledInfos {
+        font,
+        font_size,
+        spans,
+        fallback,
+        line_wrap,
+        max_width,
+        advances,
+        ..
+    } = *infos;
+    let text = &infos.text[offset..];
+    struct Last {
+        byte: usize,
+        char: usize,
+        width_before: Scalar,
+        font_size_before: FontSize,
+    }
+    let mut last_whitespace_start = None;
+    let mut width = 0.0;
+    let mut height = 0.0;
+    let mut line_font_size = span::resolve(spans, offset, font, font_size).font_size;
+    let mut char_i = 0;
+    let mut char_indices = text.char_indices().peekable();
+    let mut last_glyph = None;
+    while let Some((byte_i, ch)) = char_indices.next() {
+        // Check for a newline.
+        if ch == '\r' {
+            if let Some(&(_, '\n')) = char_indices.peek() {
+                let break_ = Break::Newline {
+                    byte: byte_i,
+                    char: char_i,
+                    len_bytes: 2,
+                };
+                let next = NextBreak {
+                    break_,
+                    width,
+                    height,
+                };
+                return (next, line_font_size);
+            }
+        } else if ch == '\n' {
+            let break_ = Break::Newline {
+                byte: byte_i,
+                char: char_i,
+                len_bytes: 1,
+            };
+            let next = NextBreak {
+                break_,
+                width,
+                height,
+            };
+            return (next, line_font_size);
+        }
+
+        // Add the character's width to the width so far.
+        let mut style = span::resolve(spans, offset + byte_i, font, font_size);
+        style.font = text::font::fallback(style.font, fallback, ch);
+        let (adv_w, h) = styled_advance_width_and_height(ch, &style, &mut last_glyph);
+        let (adv_w, is_cluster_start) = match advances {
+            None => (adv_w, true),
+            Some(advances) => match advances[offset + byte_i] {
+                Some(adv_w) => (adv_w, true),
+                None => (0.0, false),
+            },
+        };
+        let new_width = width + adv_w;
+
+        // Check for a line wrap.
+        let wrap_at_char = is_cluster_start
+            && match line_wrap {
+                None => false,
+                Some(Wrap::Character) => new_width > max_width,
+                Some(Wrap::Whitespace) => width > max_width,
+            };
+        if wrap_at_char {
+            let next = match last_whitespace_start {
+                Some(Last {
+                    byte,
+                    char,
+                    width_before,
+                    font_size_before,
+                }) => {
+                    let break_ = Break::Wrap {
+                        byte,
+                        char,
+                        len_bytes: 1,
+                    };
+                    line_font_size = font_size_before;
+                    NextBreak {
+                        break_,
+                        width: width_before,
+                        height,
+                    }
+                }
+                None => {
+                    let break_ = Break::Wrap {
+                        byte: byte_i,
+                        char: char_i,
+                        len_bytes: 0,
+                    };
+                    NextBreak {
+                        break_,
+                        width,
+                        height,
+                    }
+                }
+            };
+            return (next, line_font_size);
+        }
+
+        // Check for a new whitespace.
+        if line_wrap == Some(Wrap::Whitespace) && is_cluster_start && ch.is_whitespace() {
+            last_whitespace_start = Some(Last {
+                byte: byte_i,
+                char: char_i,
+                width_before: width,
+                font_size_before: line_font_size,
+            });
+        }
+
+        width = new_width;
+        height = height.max(h);
+        line_font_size = line_font_size.max(style.font_size);
+        char_i += 1;
+    }
+
+    let break_ = Break::End {
+        byte: text.len(),
+        char: char_i,
+    };
+    let next = NextBreak {
+        break_,
+        width,
+        height,
+    };
+    (next, line_font_size)
+}
+
 /// Produce the width of the given line of text including spaces (i.e. ' ').
 pub fn width(text: &str, font: &text::Font, font_size: FontSize) -> Scalar {
     let scale = text::Scale::uniform(text::pt_to_px(font_size));
@@ -548,15 +765,46 @@ pub fn infos_maybe_wrapped<'a>(
     }
 }
 
+/// Produce a `StyledInfos` iterator that yields an `Info` for every line in the given text.
+///
+/// The font and font size of each character are resolved from the given `spans`, falling back to
+/// the given `font` and `font_size`. Characters that are missing from their font are measured
+/// with the first font in the `fallback` chain that covers them. Lines are wrapped in the same manner as
+/// `infos_maybe_wrapped`, and the `font_size` of each yielded `Info` is the greatest font size
+/// within the line.
+pub fn infos_styled<'a>(
+    text: &'a str,
+    font: &'a text::Font,
+    font_size: FontSize,
+    spans: &'a [text::Span],
+    fallback: &'a [text::Font],
+    line_wrap: Option<Wrap>,
+    max_width: Scalar,
+) -> StyledInfos<'a> {
+    StyledInfos {
+        text,
+        font,
+        font_size,
+        spans,
+        fallback,
+        line_wrap,
+        max_width,
+        advances: None,
+        start_byte: 0,
+        start_char: 0,
+        last_break: None,
+    }
+}
+
 /// Produce an iterator yielding the bounding `Rect` for each line in the text.
 ///
-/// Yielded `Rect`s will begin with the top-left of the first line at a [0.0, 0.0].
+/// Yielded `Rect`s will begin with the top-left of the first line at a [0.0, 0.0]. The height of
+/// each line is equal to the `font_size` of its `Info`.
 ///
-/// This function assumes that `font_size` and `max_width` are the same as those used to produce
-/// the `Info`s yielded by the `infos` Iterator.
+/// This function assumes that `max_width` is the same as that used to produce the `Info`s yielded
+/// by the `infos` Iterator.
 pub fn rects<I>(
-    mut infos: I,
-    font_size: FontSize,
+    infos: I,
     max_width: Scalar,
     x_align: text::Justify,
     line_spacing: Scalar,
@@ -564,29 +812,16 @@ pub fn rects<I>(
 where
     I: Iterator<Item = Info>,
 {
-    let first_rect = infos.next().map(|first_info| {
-        // Calculate the `x` `Range` of the first line `Rect`.
-        let x_bounds = Range::new(0.0, max_width);
-        let range = Range::new(0.0, first_info.width);
-        let x = match x_align {
-            text::Justify::Left => range.align_start_of(x_bounds),
-            text::Justify::Center => range.align_middle_of(x_bounds),
-            text::Justify::Right => range.align_end_of(x_bounds),
-        };
-        let y_start = -(font_size as Scalar);
-        //let y_end = y_start + first_info.height;
-        let y_end = y_start + font_size as Scalar;
-        let y = Range::new(y_start, y_end);
-        Rect { x: x, y: y }
-    });
-    Rects {
-        infos: infos,
-        next: first_rect,
-        x_align: x_align,
-        last_line_top: 0.0,
-        font_size: font_size,
-        line_spacing: line_spacing,
-    }
+    let mut rects = Rects {
+        infos,
+        next: None,
+        x_align,
+        x_bounds: Range::new(0.0, max_width),
+        last_line_bottom: line_spacing,
+        line_spacing,
+    };
+    rects.next = rects.next_rect();
+    rects
 }
 
 /// Produces an iterator yielding a `Rect` for the selected range in each selected line in a block
@@ -634,138 +869,184 @@ where
         } = *self;
 
         let next = next_break_fn(&text[*start_byte..], font, font_size, max_width);
-        match next.break_ {
-            Break::Newline { .. } | Break::Wrap { .. } => {
-                let next_break = match next.break_ {
-                    Break::Newline {
-                        byte,
-                        char,
-                        len_bytes,
-                    } => Break::Newline {
-                        byte: *start_byte + byte,
-                        char: *start_char + char,
-                        len_bytes: len_bytes,
-                    },
-                    Break::Wrap {
-                        byte,
-                        char,
-                        len_bytes,
-                    } => Break::Wrap {
-                        byte: *start_byte + byte,
-                        char: *start_char + char,
-                        len_bytes: len_bytes,
+        next_info(text, next, font_size, start_byte, start_char, last_break)
+    }
+}
+
+impl<'a> Iterator for StyledInfos<'a> {
+    type Item = Info;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (next, line_font_size) = next_break_styled(self, self.start_byte);
+        next_info(
+            self.text,
+            next,
+            line_font_size,
+            &mut self.start_byte,
+            &mut self.start_char,
+            &mut self.last_break,
+        )
+    }
+}
+
+/// Produce the `Info` for the line ending at the given break, advancing the given indices to the
+/// start of the following line.
+///
+/// Returns `None` if the end of the text has already been reached.
+fn next_info(
+    text: &str,
+    next: NextBreak,
+    font_size: FontSize,
+    start_byte: &mut usize,
+    start_char: &mut usize,
+    last_break: &mut Option<Break>,
+) -> Option<Info> {
+    match next.break_ {
+        Break::Newline { .. } | Break::Wrap { .. } => {
+            let next_break = match next.break_ {
+                Break::Newline {
+                    byte,
+                    char,
+                    len_bytes,
+                } => Break::Newline {
+                    byte: *start_byte + byte,
+                    char: *start_char + char,
+                    len_bytes: len_bytes,
+                },
+                Break::Wrap {
+                    byte,
+                    char,
+                    len_bytes,
+                } => Break::Wrap {
+                    byte: *start_byte + byte,
+                    char: *start_char + char,
+                    len_bytes: len_bytes,
+                },
+                _ => unreachable!(),
+            };
+
+            let info = Info {
+                start_byte: *start_byte,
+                start_char: *start_char,
+                end_break: next_break,
+                width: next.width,
+                height: next.height,
+                font_size,
+            };
+
+            match next.break_ {
+                Break::Newline {
+                    byte,
+                    char,
+                    len_bytes,
+                }
+                | Break::Wrap {
+                    byte,
+                    char,
+                    len_bytes,
+                } => {
+                    *start_byte = info.start_byte + byte + len_bytes;
+                    *start_char = info.start_char + char + 1;
+                }
+                _ => unreachable!(),
+            };
+            *last_break = Some(next_break);
+            Some(info)
+        }
+
+        Break::End { char, .. } => {
+            // if the last line ends in a new line, or the entire text is empty, return an
+            // empty line Info.
+            let empty_line = {
+                match *last_break {
+                    Some(last_break_) => match last_break_ {
+                        Break::Newline { .. } => true,
+                        _ => false,
                     },
-                    _ => unreachable!(),
+                    None => true,
+                }
+            };
+            if *start_byte < text.len() || empty_line {
+                let total_bytes = text.len();
+                let total_chars = *start_char + char;
+                let end_break = Break::End {
+                    byte: total_bytes,
+                    char: total_chars,
                 };
-
                 let info = Info {
                     start_byte: *start_byte,
                     start_char: *start_char,
-                    end_break: next_break,
+                    end_break: end_break,
                     width: next.width,
                     height: next.height,
+                    font_size,
                 };
-
-                match next.break_ {
-                    Break::Newline {
-                        byte,
-                        char,
-                        len_bytes,
-                    }
-                    | Break::Wrap {
-                        byte,
-                        char,
-                        len_bytes,
-                    } => {
-                        *start_byte = info.start_byte + byte + len_bytes;
-                        *start_char = info.start_char + char + 1;
-                    }
-                    _ => unreachable!(),
-                };
-                *last_break = Some(next_break);
+                *start_byte = total_bytes;
+                *start_char = total_chars;
+                *last_break = Some(end_break);
                 Some(info)
-            }
-
-            Break::End { char, .. } => {
-                // if the last line ends in a new line, or the entire text is empty, return an
-                // empty line Info.
-                let empty_line = {
-                    match *last_break {
-                        Some(last_break_) => match last_break_ {
-                            Break::Newline { .. } => true,
-                            _ => false,
-                        },
-                        None => true,
-                    }
-                };
-                if *start_byte < text.len() || empty_line {
-                    let total_bytes = text.len();
-                    let total_chars = *start_char + char;
-                    let end_break = Break::End {
-                        byte: total_bytes,
-                        char: total_chars,
-                    };
-                    let info = Info {
-                        start_byte: *start_byte,
-                        start_char: *start_char,
-                        end_break: end_break,
-                        width: next.width,
-                        height: next.height,
-                    };
-                    *start_byte = total_bytes;
-                    *start_char = total_chars;
-                    *last_break = Some(end_break);
-                    Some(info)
-                } else {
-                    None
-                }
+            } else {
+                None
             }
         }
     }
 }
 
-impl<I> Iterator for Rects<I>
+impl<I> Rects<I>
 where
     I: Iterator<Item = Info>,
 {
-    type Item = Rect;
-    fn next(&mut self) -> Option<Self::Item> {
+    // Produce the `Rect` for the next line, beginning `line_spacing` below the previous line.
+    fn next_rect(&mut self) -> Option<Rect> {
         let Rects {
-            ref mut next,
             ref mut infos,
             x_align,
-            ref mut last_line_top,
-            font_size,
+            x_bounds,
+            ref mut last_line_bottom,
             line_spacing,
+            ..
         } = *self;
-        next.map(|line_rect| {
-            *next = infos.next().map(|info| {
-                let y = {
-                    let line_top = *last_line_top - font_size as Scalar - line_spacing;
-                    *last_line_top = line_top;
-                    let y_start = line_top - font_size as Scalar;
-                    //let y_end = y_start + info.height;
-                    let y_end = y_start + font_size as Scalar;
-                    Range::new(y_start, y_end)
-                };
-
-                let x = {
-                    let range = Range::new(0.0, info.width);
-                    match x_align {
-                        text::Justify::Left => range.align_start_of(line_rect.x),
-                        text::Justify::Center => range.align_middle_of(line_rect.x),
-                        text::Justify::Right => range.align_end_of(line_rect.x),
-                    }
-                };
+        infos.next().map(|info| {
+            let y = {
+                let line_top = *last_line_bottom - line_spacing;
+                let y_start = line_top - info.font_size as Scalar;
+                *last_line_bottom = y_start;
+                //let y_end = y_start + info.height;
+                let y_end = line_top;
+                Range::new(y_start, y_end)
+            };
 
-                Rect { x: x, y: y }
-            });
+            let x = {
+                let range = Range::new(0.0, info.width);
+                match x_align {
+                    text::Justify::Left => range.align_start_of(x_bounds),
+                    text::Justify::Center => range.align_middle_of(x_bounds),
+                    text::Justify::Right => range.align_end_of(x_bounds),
+                    text::Justify::Full => match info.end_break {
+                        Break::Wrap { .. } => x_bounds,
+                        _ => range.align_start_of(x_bounds),
+                    },
+                }
+            };
 
-            line_rect
+            Rect { x: x, y: y }
         })
     }
 }
 
+impl<I> Iterator for Rects<I>
+where
+    I: Iterator<Item = Info>,
+{
+    type Item = Rect;
+    fn next(&mut self) -> Option<Self::Item> {
+        let line_rect = self.next.take();
+        if line_rect.is_some() {
+            self.next = self.next_rect();
+        }
+        line_rect
+    }
+}
+
 impl<'a, I> Iterator for SelectedRects<'a, I>
 where
     I: Iterator<Item = (&'a str, Rect)>,
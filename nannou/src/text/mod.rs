@@ -8,18 +8,25 @@ pub mod font;
 pub mod glyph;
 pub mod layout;
 pub mod line;
+pub mod sdf;
+pub mod shape;
+pub mod span;
 pub mod rt {
     //! Re-exported RustType geometric types.
-    pub use rusttype::{gpu_cache, point, vector, Point, Rect, Vector};
+    pub use rusttype::{
+        gpu_cache, point, vector, Contour, Curve, Line, Point, Rect, Segment, Vector,
+    };
 }
 
 // Re-export all relevant rusttype types here.
 pub use self::layout::Layout;
+pub use self::span::Span;
 pub use rusttype::gpu_cache::Cache as GlyphCache;
 pub use rusttype::{Glyph, GlyphId, GlyphIter, LayoutIter, Scale, ScaledGlyph};
 
 use crate::geom;
 use std::borrow::Cow;
+use std::collections::HashMap;
 
 /// The RustType `FontCollection` type used by nannou.
 pub type FontCollection = rusttype::FontCollection<'static>;
@@ -41,6 +48,7 @@ pub type FontSize = u32;
 pub struct Builder<'a> {
     text: Cow<'a, str>,
     layout_builder: layout::Builder,
+    spans: Vec<Span>,
 }
 
 /// An instance of some multi-line text and its layout.
@@ -49,7 +57,10 @@ pub struct Text<'a> {
     text: Cow<'a, str>,
     font: Font,
     layout: Layout,
+    spans: Vec<Span>,
     line_infos: Vec<line::Info>,
+    glyphs: Vec<glyph::Info>,
+    line_glyphs: Vec<std::ops::Range<usize>>,
     rect: geom::Rect,
 }
 
@@ -61,6 +72,16 @@ pub struct Lines<'a, I> {
     ranges: I,
 }
 
+// A glyph produced by shaping a line of text, prior to positioning.
+struct Shaped {
+    glyph: ScaledGlyph<'static>,
+    font_id: font::Id,
+    font_size: FontSize,
+    advance: Scalar,
+    offset: geom::Vector2,
+    byte: usize,
+}
+
 /// An alias for the line info iterator yielded by `Text::line_infos`.
 pub type TextLineInfos<'a> = line::Infos<'a, line::NextBreakFnPtr>;
 
@@ -83,16 +104,19 @@ pub struct TextLineRects<'a> {
 /// An alias for the iterator yielded by `Text::lines_with_rects`.
 pub type TextLinesWithRects<'a> = std::iter::Zip<TextLines<'a>, TextLineRects<'a>>;
 
-/// An alias for the iterator yielded by `Text::glyphs_per_line`.
-pub type TextGlyphsPerLine<'a> = glyph::RectsPerLine<'a, TextLinesWithRects<'a>>;
-
 /// An alias for the iterator yielded by `Text::glyphs`.
-pub type TextGlyphs<'a> = std::iter::FlatMap<
-    TextGlyphsPerLine<'a>,
-    glyph::Rects<'a, 'a>,
-    fn(glyph::Rects<'a, 'a>) -> glyph::Rects<'a, 'a>,
+pub type TextGlyphs<'a> = std::iter::Map<
+    std::slice::Iter<'a, glyph::Info>,
+    fn(&glyph::Info) -> (ScaledGlyph<'static>, geom::Rect),
 >;
 
+/// The iterator yielded by `Text::glyphs_per_line`.
+#[derive(Clone)]
+pub struct TextGlyphsPerLine<'a> {
+    glyphs: &'a [glyph::Info],
+    line_glyphs: std::slice::Iter<'a, std::ops::Range<usize>>,
+}
+
 /// Alignment along an axis.
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
 pub enum Align {
@@ -110,10 +134,11 @@ pub enum Justify {
     Center,
     /// Align text to the end of the bounding `Rect`'s *x* axis.
     Right,
-    // /// Align wrapped text to both the start and end of the bounding `Rect`s *x* axis.
-    // ///
-    // /// Extra space is added between words in order to achieve this alignment.
-    // TODO: Full,
+    /// Align wrapped text to both the start and end of the bounding `Rect`s *x* axis.
+    ///
+    /// Extra space is added between words in order to achieve this alignment. Lines that end
+    /// with a newline, along with the final line, are aligned to the start.
+    Full,
 }
 
 /// The way in which text should wrap around the width.
@@ -128,9 +153,11 @@ pub enum Wrap {
 impl<'a> From<Cow<'a, str>> for Builder<'a> {
     fn from(text: Cow<'a, str>) -> Self {
         let layout_builder = Default::default();
+        let spans = vec![];
         Builder {
             text,
             layout_builder,
+            spans,
         }
     }
 }
@@ -197,6 +224,14 @@ impl<'a> Builder<'a> {
         self.map_layout(|l| l.font(font))
     }
 
+    /// Specify the chain of fonts that are searched for characters missing from the `font`.
+    pub fn fallback_fonts<I>(self, fonts: I) -> Self
+    where
+        I: IntoIterator<Item = Font>,
+    {
+        self.map_layout(|l| l.fallback_fonts(fonts))
+    }
+
     /// Describe the end along the *x* axis to which the text should be aligned.
     pub fn justify(self, justify: Justify) -> Self {
         self.map_layout(|l| l.justify(justify))
@@ -217,6 +252,11 @@ impl<'a> Builder<'a> {
         self.map_layout(|l| l.right_justify())
     }
 
+    /// Align wrapped lines to both ends of the bounding **Rect**'s *x* axis range.
+    pub fn full_justify(self) -> Self {
+        self.map_layout(|l| l.full_justify())
+    }
+
     /// Specify how much vertical space should separate each line of text.
     pub fn line_spacing(self, spacing: Scalar) -> Self {
         self.map_layout(|l| l.line_spacing(spacing))
@@ -249,10 +289,27 @@ impl<'a> Builder<'a> {
         self.map_layout(|l| l.layout(layout))
     }
 
+    /// Apply the given style to the given byte range of the text.
+    ///
+    /// Where spans overlap, the style of the span that was added last takes precedence.
+    pub fn span(mut self, range: std::ops::Range<usize>, style: span::Style) -> Self {
+        self.spans.push(Span::new(range, style));
+        self
+    }
+
+    /// Apply each of the given spans to the text.
+    pub fn spans<I>(mut self, spans: I) -> Self
+    where
+        I: IntoIterator<Item = Span>,
+    {
+        self.spans.extend(spans);
+        self
+    }
+
     /// Build the text.
     ///
     /// This iterates over the text in order to pre-calculates the text's multi-line information
-    /// using the `line::infos` function.
+    /// using the `line::infos_styled` function.
     ///
     /// The given `rect` will be used for applying the layout including text alignment, positioning
     /// of text, multi-line wrapping, etc,
@@ -269,17 +326,37 @@ impl<'a> Builder<'a> {
                 .expect("failed to detect the assets directory when searching for a default font");
             font::default(&assets).expect("failed to detect a default font")
         });
-        let max_width = rect.w();
-        let line_infos =
-            line::infos_maybe_wrapped(&text, &font, layout.font_size, layout.line_wrap, max_width)
-                .collect();
-        Text {
+        let spans = self.spans;
+        let mut text = Text {
             text,
             font,
             layout,
-            line_infos,
+            spans,
+            line_infos: vec![],
+            glyphs: vec![],
+            line_glyphs: vec![],
             rect,
+        };
+
+        // Shaping may change the width of the text, e.g. due to ligatures, so each paragraph is
+        // shaped in order to wrap it by the advances of its shaped glyphs. The resulting lines are
+        // then shaped again, as bidirectional text is ordered within each line.
+        text.line_infos = text.styled_line_infos(None);
+        if text.layout.line_wrap.is_some() {
+            let advances = text.shaped_advances(&text.shape_lines());
+            text.line_infos = text.styled_line_infos(Some(&advances));
+        }
+
+        // Shaping may change the width of each line, e.g. due to ligatures.
+        let shaped_lines = text.shape_lines();
+        for (info, shaped) in text.line_infos.iter_mut().zip(&shaped_lines) {
+            info.width = shaped.iter().map(|g| g.advance).sum();
         }
+
+        let (glyphs, line_glyphs) = text.layout_glyphs(shaped_lines);
+        text.glyphs = glyphs;
+        text.line_glyphs = line_glyphs;
+        text
     }
 }
 
@@ -300,10 +377,22 @@ impl<'a> Text<'a> {
     }
 
     /// The font used for this text instance.
+    ///
+    /// Spans may specify other fonts for ranges of the text.
     pub fn font(&self) -> &Font {
         &self.font
     }
 
+    /// The spans applied to ranges of the text.
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// Every positioned glyph within the text, along with its font, color and line.
+    pub fn glyph_infos(&self) -> &[glyph::Info] {
+        &self.glyphs
+    }
+
     /// The number of lines in the text.
     pub fn num_lines(&self) -> usize {
         self.line_infos.len()
@@ -323,7 +412,7 @@ impl<'a> Text<'a> {
             None => return geom::Rect::from_w_h(0.0, 0.0),
             Some(info) => info,
         };
-        let line_h = self.layout.font_size as Scalar;
+        let line_h = info.font_size as Scalar;
         r.y.end -= line_h - info.height;
         r
     }
@@ -331,7 +420,7 @@ impl<'a> Text<'a> {
     /// The rectangle that describes the min and max bounds along each axis reached by the text.
     ///
     /// This is similar to `bounding_rect` but assumes that all lines have a height equal to
-    /// `font_size`, rather than using the exact height.
+    /// their `font_size`, rather than using the exact height.
     pub fn bounding_rect_by_lines(&self) -> geom::Rect {
         let mut lrs = self.line_rects();
         let lr = match lrs.next() {
@@ -358,25 +447,22 @@ impl<'a> Text<'a> {
             None => return 0.0,
             Some(info) => info,
         };
-        exact_height(
-            info.height,
-            self.num_lines(),
-            self.layout.font_size,
-            self.layout.line_spacing,
-        )
+        self.height_by_lines() - info.font_size as Scalar + info.height
     }
 
-    /// Determine the total height of a block of text with the given number of lines, font size and
-    /// `line_spacing` (the space that separates each line of text).
+    /// Determine the total height of the text, including the `line_spacing` that separates each
+    /// line of text.
     ///
-    /// The height of all lines of text are assumed to match the `font_size`. If looking for the exact
-    /// height, see the `exact_height` function.
+    /// The height of each line of text is assumed to match its `font_size`. If looking for the
+    /// exact height, see the `height` method.
     pub fn height_by_lines(&self) -> Scalar {
-        height_by_lines(
-            self.num_lines(),
-            self.layout.font_size,
-            self.layout.line_spacing,
-        )
+        let lines_height: Scalar = self
+            .line_infos
+            .iter()
+            .map(|info| info.font_size as Scalar)
+            .sum();
+        let num_spaces = self.num_lines().max(1) - 1;
+        lines_height + num_spaces as Scalar * self.layout.line_spacing
     }
 
     /// Produce an iterator yielding each wrapped line within the **Text**.
@@ -392,7 +478,6 @@ impl<'a> Text<'a> {
         let offset = self.position_offset();
         let line_rects = line::rects(
             self.line_infos.iter().cloned(),
-            self.layout.font_size,
             self.rect.w(),
             self.layout.justify,
             self.layout.line_spacing,
@@ -408,75 +493,109 @@ impl<'a> Text<'a> {
     /// Produce an iterator yielding iterators yielding every glyph alongside its bounding rect for
     /// each line.
     pub fn glyphs_per_line(&self) -> TextGlyphsPerLine {
-        glyph::rects_per_line(self.lines_with_rects(), &self.font, self.layout.font_size)
+        TextGlyphsPerLine {
+            glyphs: &self.glyphs,
+            line_glyphs: self.line_glyphs.iter(),
+        }
     }
 
     /// Produce an iterator yielding every glyph alongside its bounding rect.
     ///
     /// This is the "flattened" version of the `glyphs_per_line` method.
     pub fn glyphs(&self) -> TextGlyphs {
-        self.glyphs_per_line().flat_map(std::convert::identity)
+        glyphs_with_rects(&self.glyphs)
     }
 
     /// Produce an iterator yielding the path events for every glyph in every line.
     pub fn path_events<'b>(&'b self) -> impl 'b + Iterator<Item = lyon::path::PathEvent> {
-        use lyon::path::PathEvent;
-
-        // Translate the given lyon point by the given vector.
-        fn trans_lyon_point(p: &lyon::math::Point, v: geom::Vector2) -> lyon::math::Point {
-            lyon::math::point(p.x + v.x, p.y + v.y)
-        }
-
-        // Translate the given path event in 2D space.
-        fn trans_path_event(e: &PathEvent, v: geom::Vector2) -> PathEvent {
-            match *e {
-                PathEvent::Begin { ref at } => PathEvent::Begin {
-                    at: trans_lyon_point(at, v),
-                },
-                PathEvent::Line { ref from, ref to } => PathEvent::Line {
-                    from: trans_lyon_point(from, v),
-                    to: trans_lyon_point(to, v),
-                },
-                PathEvent::Quadratic {
-                    ref from,
-                    ref ctrl,
-                    ref to,
-                } => PathEvent::Quadratic {
-                    from: trans_lyon_point(from, v),
-                    ctrl: trans_lyon_point(ctrl, v),
-                    to: trans_lyon_point(to, v),
-                },
-                PathEvent::Cubic {
-                    ref from,
-                    ref ctrl1,
-                    ref ctrl2,
-                    ref to,
-                } => PathEvent::Cubic {
-                    from: trans_lyon_point(from, v),
-                    ctrl1: trans_lyon_point(ctrl1, v),
-                    ctrl2: trans_lyon_point(ctrl2, v),
-                    to: trans_lyon_point(to, v),
-                },
-                PathEvent::End {
-                    ref last,
-                    ref first,
-                    ref close,
-                } => PathEvent::End {
-                    last: trans_lyon_point(last, v),
-                    first: trans_lyon_point(first, v),
-                    close: *close,
-                },
-            }
-        }
-
         self.glyphs().flat_map(|(g, r)| {
+            let v = r.bottom_left();
             glyph::path_events(g)
                 .into_iter()
                 .flat_map(|es| es)
-                .map(move |e| trans_path_event(&e, r.bottom_left()))
+                .map(move |e| map_path_event(&e, |p| lyon::math::point(p.x + v.x, p.y + v.y)))
         })
     }
 
+    /// Produce the transform that displays each glyph at its position within the layout.
+    ///
+    /// Transforms are yielded in the same order as `glyph_infos`.
+    pub fn glyph_transforms(&self) -> Vec<glyph::Transform> {
+        self.glyphs.iter().map(glyph::Info::transform).collect()
+    }
+
+    /// Produce a transform for each glyph that lays the text along the given path.
+    ///
+    /// The baseline of the first line follows the path, with following lines offset along the
+    /// path's normal. Each glyph is rotated to follow the tangent of the path at its centre.
+    ///
+    /// `offset` describes the distance along the path at which the text begins, after which the
+    /// text is aligned within the remaining length of the path via `align`. Glyphs beyond the end
+    /// of the path are positioned at its end.
+    ///
+    /// Transforms are yielded in the same order as `glyph_infos`.
+    pub fn transforms_on_path(
+        &self,
+        path: &geom::path::Measure,
+        offset: Scalar,
+        align: Align,
+    ) -> Vec<glyph::Transform> {
+        let bounds = self.bounding_rect_by_lines();
+        let first_baseline = match self.line_rects().next() {
+            None => return vec![],
+            Some(r) => r.bottom(),
+        };
+        let remaining = path.length() - offset;
+        let start = offset
+            + match align {
+                Align::Start => 0.0,
+                Align::Middle => (remaining - bounds.w()) * 0.5,
+                Align::End => remaining - bounds.w(),
+            };
+        self.glyphs
+            .iter()
+            .map(|info| {
+                let half_advance = info.glyph.h_metrics().advance_width * 0.5;
+                let distance = start + info.position.x - bounds.left() + half_advance;
+                let tangent = path.tangent_at(distance);
+                let normal = geom::vec2(-tangent.y, tangent.x);
+                let rise = info.position.y - first_baseline;
+                let position = path.point_at(distance) - tangent * half_advance + normal * rise;
+                glyph::Transform {
+                    position,
+                    rotation: tangent.y.atan2(tangent.x),
+                    scale: geom::vec2(1.0, 1.0),
+                    color: info.color,
+                }
+            })
+            .collect()
+    }
+
+    /// Produce an iterator yielding the path events for every glyph, with each glyph transformed
+    /// by the transform at the same index.
+    ///
+    /// Glyphs without a corresponding transform are skipped.
+    pub fn path_events_transformed<'b>(
+        &'b self,
+        transforms: &'b [glyph::Transform],
+    ) -> impl 'b + Iterator<Item = lyon::path::PathEvent> {
+        self.glyphs
+            .iter()
+            .zip(transforms)
+            .flat_map(|(info, transform)| {
+                let origin = geom::pt2(0.0, 0.0);
+                glyph::path_events(info.glyph.clone())
+                    .into_iter()
+                    .flat_map(|es| es)
+                    .map(move |e| {
+                        map_path_event(&e, |p| {
+                            let p = transform.transform_point(origin, geom::pt2(p.x, p.y));
+                            lyon::math::point(p.x, p.y)
+                        })
+                    })
+            })
+    }
+
     /// Produce an iterator yielding positioned rusttype glyphs ready for caching.
     ///
     /// The window dimensions (in logical space) and scale_factor are required to transform glyph
@@ -487,13 +606,21 @@ impl<'a> Text<'a> {
         window_size: geom::Vector2,
         scale_factor: Scalar,
     ) -> impl 'a + 'b + Iterator<Item = PositionedGlyph> {
-        rt_positioned_glyphs(
-            self.lines_with_rects(),
-            &self.font,
-            self.layout.font_size,
-            window_size,
-            scale_factor,
-        )
+        // Functions for converting nannou coordinates to rusttype pixel coordinates.
+        let trans_x = move |x: Scalar| (x + window_size.x / 2.0) * scale_factor as Scalar;
+        let trans_y = move |y: Scalar| ((-y) + window_size.y / 2.0) * scale_factor as Scalar;
+        self.glyphs.iter().map(move |info| {
+            let scale = f32_pt_to_scale(info.font_size as f32 * scale_factor);
+            let point = rt::point(
+                trans_x(info.position.x) as f32,
+                trans_y(info.position.y) as f32,
+            );
+            info.glyph
+                .unscaled()
+                .clone()
+                .scaled(scale)
+                .positioned(point)
+        })
     }
 
     /// Converts this `Text` instance into an instance that owns the inner text string.
@@ -502,7 +629,10 @@ impl<'a> Text<'a> {
             text,
             font,
             layout,
+            spans,
             line_infos,
+            glyphs,
+            line_glyphs,
             rect,
         } = self;
         let text = Cow::Owned(text.into_owned());
@@ -510,19 +640,218 @@ impl<'a> Text<'a> {
             text,
             font,
             layout,
+            spans,
             line_infos,
+            glyphs,
+            line_glyphs,
             rect,
         }
     }
 
     fn position_offset(&self) -> geom::Vector2 {
-        position_offset(
-            self.num_lines(),
+        let x_offset = self.rect.x.start;
+        let y_offset = y_offset(self.height_by_lines(), self.rect, self.layout.y_align);
+        geom::vec2(x_offset, y_offset)
+    }
+
+    // Resolve the style of the given character, selecting a fallback font if necessary.
+    fn resolve(&self, byte: usize, ch: char) -> span::Resolved {
+        let mut style = span::resolve(&self.spans, byte, &self.font, self.layout.font_size);
+        style.font = font::fallback(style.font, &self.layout.fallback_fonts, ch);
+        style
+    }
+
+    // Break the text into lines. The lines are only wrapped if the `advances` of each byte are
+    // given, as produced by `shaped_advances`.
+    fn styled_line_infos(&self, advances: Option<&[Option<Scalar>]>) -> Vec<line::Info> {
+        let line_wrap = advances.and(self.layout.line_wrap);
+        let infos = line::infos_styled(
+            &self.text,
+            &self.font,
             self.layout.font_size,
-            self.layout.line_spacing,
-            self.rect,
-            self.layout.y_align,
-        )
+            &self.spans,
+            &self.layout.fallback_fonts,
+            line_wrap,
+            self.rect.w(),
+        );
+        match advances {
+            None => infos.collect(),
+            Some(advances) => infos.advances(advances).collect(),
+        }
+    }
+
+    // The advance of each byte of the text given its shaped lines, as expected by
+    // `line::StyledInfos::advances`.
+    fn shaped_advances(&self, shaped_lines: &[Vec<Shaped>]) -> Vec<Option<Scalar>> {
+        let mut advances = vec![None; self.text.len()];
+        for s in shaped_lines.iter().flatten() {
+            *advances[s.byte].get_or_insert(0.0) += s.advance;
+        }
+        advances
+    }
+
+    // Shape each line of text, producing its glyphs in visual order.
+    //
+    // The data of each font is retrieved and parsed once for the whole layout.
+    fn shape_lines(&self) -> Vec<Vec<Shaped>> {
+        let fonts: Vec<&Font> = std::iter::once(&self.font)
+            .chain(
+                self.spans
+                    .iter()
+                    .filter_map(|span| span.style.font.as_ref()),
+            )
+            .chain(self.layout.fallback_fonts.iter())
+            .collect();
+        let data: Vec<_> = fonts.iter().map(|font| font::data(font)).collect();
+        let faces: HashMap<font::Id, shape::Face> = fonts
+            .iter()
+            .zip(&data)
+            .map(|(font, data)| (font::id(font), shape::Face::new(font, data.as_ref())))
+            .collect();
+        self.line_infos
+            .iter()
+            .zip(self.lines())
+            .map(|(info, line)| self.shape_line(info.start_byte, line, &faces))
+            .collect()
+    }
+
+    // Shape a single line beginning at the given byte.
+    //
+    // The line is split into bidirectional runs, each of which is split into items that share the
+    // same font and font size for shaping.
+    fn shape_line(
+        &self,
+        start_byte: usize,
+        line: &str,
+        faces: &HashMap<font::Id, shape::Face>,
+    ) -> Vec<Shaped> {
+        let mut shaped = vec![];
+        for run in shape::visual_runs(line) {
+            let mut items: Vec<(std::ops::Range<usize>, span::Resolved)> = vec![];
+            for (byte_i, ch) in line[run.range.clone()].char_indices() {
+                let byte = run.range.start + byte_i;
+                let end = byte + ch.len_utf8();
+                let style = self.resolve(start_byte + byte, ch);
+                match items.last_mut() {
+                    Some((range, last))
+                        if std::ptr::eq(last.font, style.font)
+                            && last.font_size == style.font_size =>
+                    {
+                        range.end = end;
+                    }
+                    _ => items.push((byte..end, style)),
+                }
+            }
+            if run.direction == shape::Direction::RightToLeft {
+                items.reverse();
+            }
+            for (range, style) in items {
+                let font_id = font::id(style.font);
+                let scale = pt_to_scale(style.font_size);
+                let text = &line[range.clone()];
+                let glyphs = match faces.get(&font_id) {
+                    Some(face) => face.shape(text, style.font_size, run.direction),
+                    None => shape::shape(text, style.font, style.font_size, run.direction),
+                };
+                shaped.extend(glyphs.into_iter().map(|g| Shaped {
+                    glyph: style.font.glyph(g.id).scaled(scale),
+                    font_id,
+                    font_size: style.font_size,
+                    advance: g.advance,
+                    offset: g.offset,
+                    byte: start_byte + range.start + g.byte,
+                }));
+            }
+        }
+        shaped
+    }
+
+    // Position every shaped glyph within the text, returning the glyphs along with the range of
+    // glyphs within each line.
+    fn layout_glyphs(
+        &self,
+        shaped_lines: Vec<Vec<Shaped>>,
+    ) -> (Vec<glyph::Info>, Vec<std::ops::Range<usize>>) {
+        let mut glyphs = vec![];
+        let mut line_glyphs = vec![];
+        let is_whitespace = |g: &Shaped| {
+            self.text[g.byte..]
+                .chars()
+                .next()
+                .map(char::is_whitespace)
+                .unwrap_or(false)
+        };
+        let lines = self
+            .line_infos
+            .iter()
+            .zip(self.line_rects())
+            .zip(shaped_lines);
+        for (line_i, ((info, line_rect), shaped)) in lines.enumerate() {
+            let start = glyphs.len();
+
+            // Determine the extra space to insert for fully justified lines. Space is added after
+            // each whitespace glyph, or between every glyph if there is no whitespace.
+            let mut gap_space = 0.0;
+            let mut gap_after_whitespace = true;
+            if let (Justify::Full, line::Break::Wrap { .. }) = (self.layout.justify, info.end_break)
+            {
+                let whitespace = shaped.iter().filter(|g| is_whitespace(g)).count();
+                let gaps = match whitespace {
+                    0 => {
+                        gap_after_whitespace = false;
+                        shaped.len().max(1) - 1
+                    }
+                    n => n,
+                };
+                if gaps > 0 {
+                    gap_space = (line_rect.w() - info.width).max(0.0) / gaps as Scalar;
+                }
+            }
+
+            let mut x = line_rect.left();
+            let num_shaped = shaped.len();
+            for (i, s) in shaped.into_iter().enumerate() {
+                let style = span::resolve(&self.spans, s.byte, &self.font, self.layout.font_size);
+                let position = geom::pt2(
+                    x + s.offset.x,
+                    line_rect.bottom() + style.baseline_shift + s.offset.y,
+                );
+                let (right, height) = s
+                    .glyph
+                    .clone()
+                    .positioned(rt::point(position.x, 0.0))
+                    .pixel_bounding_box()
+                    .map(|bb| (bb.max.x as Scalar, (bb.max.y - bb.min.y) as Scalar))
+                    .unwrap_or((position.x + s.advance, 0.0));
+                let rect = geom::Rect {
+                    x: geom::Range::new(position.x, right),
+                    y: geom::Range::new(position.y, position.y + height),
+                };
+
+                x += s.advance;
+                let is_gap = if gap_after_whitespace {
+                    is_whitespace(&s)
+                } else {
+                    i + 1 < num_shaped
+                };
+                if is_gap {
+                    x += gap_space;
+                }
+
+                glyphs.push(glyph::Info {
+                    glyph: s.glyph,
+                    font_id: s.font_id,
+                    font_size: s.font_size,
+                    position,
+                    rect,
+                    line: line_i,
+                    byte: s.byte,
+                    color: style.color,
+                });
+            }
+            line_glyphs.push(start..glyphs.len());
+        }
+        (glyphs, line_glyphs)
     }
 }
 
@@ -547,6 +876,19 @@ impl<'a> Iterator for TextLineRects<'a> {
     }
 }
 
+impl<'a> Iterator for TextGlyphsPerLine<'a> {
+    type Item = TextGlyphs<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let TextGlyphsPerLine {
+            glyphs,
+            ref mut line_glyphs,
+        } = *self;
+        line_glyphs
+            .next()
+            .map(|range| glyphs_with_rects(&glyphs[range.clone()]))
+    }
+}
+
 /// Determine the total height of a block of text with the given number of lines, font size and
 /// `line_spacing` (the space that separates each line of text).
 ///
@@ -604,20 +946,66 @@ pub fn position_offset(
     y_align: Align,
 ) -> geom::Vector2 {
     let x_offset = bounding_rect.x.start;
-    let y_offset = {
-        // Calculate the `y` `Range` of the first line `Rect`.
-        let total_text_height = height_by_lines(num_lines, font_size, line_spacing);
-        let total_text_y_range = geom::Range::new(0.0, total_text_height);
-        let total_text_y = match y_align {
-            Align::Start => total_text_y_range.align_start_of(bounding_rect.y),
-            Align::Middle => total_text_y_range.align_middle_of(bounding_rect.y),
-            Align::End => total_text_y_range.align_end_of(bounding_rect.y),
-        };
-        total_text_y.end
-    };
+    let total_text_height = height_by_lines(num_lines, font_size, line_spacing);
+    let y_offset = y_offset(total_text_height, bounding_rect, y_align);
     geom::vec2(x_offset, y_offset)
 }
 
+// The position of the top of the first line of text with the given total height.
+fn y_offset(total_text_height: Scalar, bounding_rect: geom::Rect, y_align: Align) -> Scalar {
+    let total_text_y_range = geom::Range::new(0.0, total_text_height);
+    let total_text_y = match y_align {
+        Align::Start => total_text_y_range.align_start_of(bounding_rect.y),
+        Align::Middle => total_text_y_range.align_middle_of(bounding_rect.y),
+        Align::End => total_text_y_range.align_end_of(bounding_rect.y),
+    };
+    total_text_y.end
+}
+
+// Map each point of the given path event via the given function.
+fn map_path_event<F>(e: &lyon::path::PathEvent, f: F) -> lyon::path::PathEvent
+where
+    F: Fn(lyon::math::Point) -> lyon::math::Point,
+{
+    use lyon::path::PathEvent;
+    match *e {
+        PathEvent::Begin { at } => PathEvent::Begin { at: f(at) },
+        PathEvent::Line { from, to } => PathEvent::Line {
+            from: f(from),
+            to: f(to),
+        },
+        PathEvent::Quadratic { from, ctrl, to } => PathEvent::Quadratic {
+            from: f(from),
+            ctrl: f(ctrl),
+            to: f(to),
+        },
+        PathEvent::Cubic {
+            from,
+            ctrl1,
+            ctrl2,
+            to,
+        } => PathEvent::Cubic {
+            from: f(from),
+            ctrl1: f(ctrl1),
+            ctrl2: f(ctrl2),
+            to: f(to),
+        },
+        PathEvent::End { last, first, close } => PathEvent::End {
+            last: f(last),
+            first: f(first),
+            close,
+        },
+    }
+}
+
+// Pair each of the given glyphs with its bounding rect.
+fn glyphs_with_rects(glyphs: &[glyph::Info]) -> TextGlyphs {
+    fn glyph_with_rect(info: &glyph::Info) -> (ScaledGlyph<'static>, geom::Rect) {
+        (info.glyph.clone(), info.rect)
+    }
+    glyphs.iter().map(glyph_with_rect)
+}
+
 /// Produce the position of each glyph ready for the rusttype glyph cache.
 ///
 /// Window dimensions are expected in logical coordinates.
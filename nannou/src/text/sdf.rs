@@ -0,0 +1,622 @@
+//! Generation and caching of signed distance fields for glyphs.
+//!
+//! Rather than rasterising each glyph at the scale at which it is displayed, a distance field
+//! stores the distance from each texel to the nearest edge of the glyph's outline. Sampling the
+//! field with linear filtering and thresholding the result produces crisp edges at any scale,
+//! rotation or zoom, allowing a single cached glyph to serve every size at which it is drawn.
+//! Distances beyond the edge of the glyph may also be used to produce outline and glow effects.
+//!
+//! Two kinds of field are supported:
+//!
+//! - `Field::Sdf` stores the true distance to the outline within every channel. Sharp corners
+//!   become rounded when the glyph is magnified well beyond the size at which it was generated.
+//! - `Field::Msdf` stores a distance within each of the red, green and blue channels, each
+//!   measured to a different subset of the outline's edges. The median of the three channels
+//!   preserves sharp corners. The true distance is stored within the alpha channel for effects
+//!   that extend far beyond the edges of the glyph.
+//!
+//! Within all channels, a value of `0.5` lies on the outline, with greater values lying inside
+//! the glyph. The distance `range` on either side of the outline maps to the range `0.0..=1.0`.
+
+use crate::geom::{self, Point2};
+use crate::math::InnerSpace;
+use crate::text::{rt, GlyphId, Scalar, Scale, ScaledGlyph};
+use std::collections::HashMap;
+
+/// The kind of distance field generated for each glyph.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Field {
+    /// A single channel signed distance field.
+    Sdf,
+    /// A multi-channel signed distance field that preserves sharp corners.
+    Msdf,
+}
+
+/// The distance field generated for a single glyph.
+#[derive(Clone, Debug)]
+pub struct Bitmap {
+    /// The width of the bitmap in texels.
+    pub width: u32,
+    /// The height of the bitmap in texels.
+    pub height: u32,
+    /// The RGBA texels of the bitmap, row by row from the top.
+    pub data: Vec<u8>,
+    /// The area covered by the bitmap relative to the glyph's origin on the baseline.
+    ///
+    /// Described in pixels at the scale at which the field was generated, with *y* pointing up.
+    pub bounds: geom::Rect,
+}
+
+/// A texture atlas of distance fields for glyphs of any font.
+///
+/// Each glyph is generated once at a fixed scale, regardless of the scale at which it is drawn.
+/// Like the RustType `GlyphCache`, glyphs are first queued via `queue_glyph` and then generated
+/// via `cache_queued`, after which their location may be retrieved via `rect_for`.
+pub struct Cache {
+    width: u32,
+    height: u32,
+    field: Field,
+    scale: Scalar,
+    range: Scalar,
+    pixels: Vec<u8>,
+    // `None` for glyphs that have no outline, e.g. whitespace.
+    entries: HashMap<(usize, GlyphId), Option<Entry>>,
+    queue: Vec<(usize, ScaledGlyph<'static>)>,
+    // Glyphs are packed into rows, or "shelves", from the top of the atlas.
+    shelf_x: u32,
+    shelf_y: u32,
+    shelf_h: u32,
+}
+
+/// The location of a cached glyph within the **Cache**.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Entry {
+    /// The normalised texture coordinates of the glyph, where `0.0, 0.0` is the top-left.
+    pub uv: rt::Rect<f32>,
+    /// The area covered by the glyph's distance field relative to its origin on the baseline.
+    ///
+    /// Described in pixels at the cache's `scale`, with *y* pointing up.
+    pub bounds: geom::Rect,
+}
+
+/// Returned by `Cache::cache_queued` when the glyphs queued for a single frame do not fit within
+/// the cache, even after evicting all other glyphs.
+#[derive(Copy, Clone, Debug)]
+pub struct CacheFull;
+
+// Color channels of an edge within a multi-channel distance field.
+const RED: u8 = 0b001;
+const GREEN: u8 = 0b010;
+const BLUE: u8 = 0b100;
+const WHITE: u8 = RED | GREEN | BLUE;
+const CYAN: u8 = GREEN | BLUE;
+const MAGENTA: u8 = RED | BLUE;
+const YELLOW: u8 = RED | GREEN;
+
+// The sine of the angle beneath which the meeting of two edges is considered a corner.
+const CORNER_THRESHOLD: Scalar = 0.141;
+
+// The maximum length of each line used to approximate a curve.
+const CURVE_TOLERANCE: Scalar = 1.0;
+
+// A single edge of a glyph outline, flattened into lines.
+struct Edge {
+    lines: Vec<[Point2; 2]>,
+    color: u8,
+}
+
+// The distance from some point to a line of an edge.
+#[derive(Copy, Clone)]
+struct Distance {
+    // The absolute distance to the nearest point on the line.
+    dist: Scalar,
+    // How orthogonal the direction to the nearest point is to the line, used to break ties.
+    orthogonality: Scalar,
+    // The signed distance, extended beyond the ends of the edge along its tangents.
+    pseudo: Scalar,
+}
+
+impl Cache {
+    /// The default scale in pixels at which glyphs are generated.
+    pub const DEFAULT_SCALE: Scalar = 48.0;
+    /// The default distance in pixels on either side of each glyph's outline covered by its field.
+    pub const DEFAULT_RANGE: Scalar = 6.0;
+
+    /// Create a new, empty cache with the given dimensions in texels.
+    ///
+    /// Glyphs are generated with a height of `scale` pixels per em. `range` describes the
+    /// distance in pixels at this scale on either side of each glyph's outline that is described
+    /// by its field. Effects such as outlines and glows cannot extend beyond this range.
+    pub fn new(size: [u32; 2], field: Field, scale: Scalar, range: Scalar) -> Self {
+        let [width, height] = size;
+        Cache {
+            width,
+            height,
+            field,
+            scale,
+            range,
+            pixels: vec![0; width as usize * height as usize * 4],
+            entries: Default::default(),
+            queue: vec![],
+            shelf_x: 0,
+            shelf_y: 0,
+            shelf_h: 0,
+        }
+    }
+
+    /// The width and height of the cache in texels.
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// The kind of distance field generated for each glyph.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    /// The scale in pixels per em at which glyphs are generated.
+    pub fn scale(&self) -> Scalar {
+        self.scale
+    }
+
+    /// The distance in pixels on either side of each glyph's outline covered by its field.
+    pub fn range(&self) -> Scalar {
+        self.range
+    }
+
+    /// The RGBA texels of the cache, row by row from the top.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Queue the given glyph of the font with the given index to be cached.
+    ///
+    /// The scale and position of the glyph are ignored.
+    pub fn queue_glyph(&mut self, font_id: usize, glyph: ScaledGlyph<'static>) {
+        self.queue.push((font_id, glyph));
+    }
+
+    /// Generate and cache the distance field of every queued glyph that is not already cached.
+    ///
+    /// If the queued glyphs do not fit within the remaining space, all glyphs are evicted and
+    /// only those that are queued are cached.
+    ///
+    /// Returns whether or not the texels of the cache were updated.
+    pub fn cache_queued(&mut self) -> Result<bool, CacheFull> {
+        let queue = std::mem::replace(&mut self.queue, vec![]);
+        let mut updated = false;
+        for (font_id, glyph) in &queue {
+            let key = (*font_id, glyph.id());
+            if self.entries.contains_key(&key) {
+                continue;
+            }
+            if !self.insert(key, glyph) {
+                self.clear();
+                for (font_id, glyph) in &queue {
+                    let key = (*font_id, glyph.id());
+                    if !self.entries.contains_key(&key) && !self.insert(key, glyph) {
+                        return Err(CacheFull);
+                    }
+                }
+                return Ok(true);
+            }
+            updated = true;
+        }
+        Ok(updated)
+    }
+
+    /// The location of the given glyph within the cache.
+    ///
+    /// Returns `None` if the glyph has not been cached or if it has no outline.
+    pub fn rect_for(&self, font_id: usize, glyph_id: GlyphId) -> Option<Entry> {
+        self.entries.get(&(font_id, glyph_id)).and_then(|&e| e)
+    }
+
+    /// Evict all glyphs from the cache.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.shelf_x = 0;
+        self.shelf_y = 0;
+        self.shelf_h = 0;
+        for p in self.pixels.iter_mut() {
+            *p = 0;
+        }
+    }
+
+    // Generate and insert the given glyph. Returns `false` if there is no room.
+    fn insert(&mut self, key: (usize, GlyphId), glyph: &ScaledGlyph<'static>) -> bool {
+        let glyph = glyph.unscaled().clone().scaled(Scale::uniform(self.scale));
+        let bitmap = match generate(&glyph, self.field, self.range) {
+            None => {
+                self.entries.insert(key, None);
+                return true;
+            }
+            Some(bitmap) => bitmap,
+        };
+        let [x, y] = match self.allocate(bitmap.width, bitmap.height) {
+            None => return false,
+            Some(pos) => pos,
+        };
+        let row_len = bitmap.width as usize * 4;
+        for (row, src) in bitmap.data.chunks(row_len).enumerate() {
+            let start = ((y as usize + row) * self.width as usize + x as usize) * 4;
+            self.pixels[start..start + row_len].copy_from_slice(src);
+        }
+        let (w, h) = (self.width as f32, self.height as f32);
+        let uv = rt::Rect {
+            min: rt::point(x as f32 / w, y as f32 / h),
+            max: rt::point(
+                (x + bitmap.width) as f32 / w,
+                (y + bitmap.height) as f32 / h,
+            ),
+        };
+        let bounds = bitmap.bounds;
+        self.entries.insert(key, Some(Entry { uv, bounds }));
+        true
+    }
+
+    // Find room for a bitmap of the given size, leaving a gap of one texel between neighbours.
+    fn allocate(&mut self, width: u32, height: u32) -> Option<[u32; 2]> {
+        let (w, h) = (width + 1, height + 1);
+        if w > self.width {
+            return None;
+        }
+        if self.shelf_x + w > self.width {
+            self.shelf_y += self.shelf_h;
+            self.shelf_x = 0;
+            self.shelf_h = 0;
+        }
+        if self.shelf_y + h > self.height {
+            return None;
+        }
+        let pos = [self.shelf_x, self.shelf_y];
+        self.shelf_x += w;
+        self.shelf_h = std::cmp::max(self.shelf_h, h);
+        Some(pos)
+    }
+}
+
+/// Generate a distance field for the given glyph at the glyph's scale.
+///
+/// `range` describes the distance in pixels on either side of the outline described by the field.
+/// The bitmap is padded so that the full range is represented around the glyph.
+///
+/// Returns `None` if the glyph has no outline.
+pub fn generate(glyph: &ScaledGlyph, field: Field, range: Scalar) -> Option<Bitmap> {
+    let edges = edges(&glyph.shape()?);
+    let lines = || edges.iter().flat_map(|e| e.lines.iter());
+    let mut min = geom::pt2(std::f32::MAX, std::f32::MAX);
+    let mut max = geom::pt2(std::f32::MIN, std::f32::MIN);
+    for p in lines().flat_map(|l| l.iter()) {
+        min = geom::pt2(min.x.min(p.x), min.y.min(p.y));
+        max = geom::pt2(max.x.max(p.x), max.y.max(p.y));
+    }
+    if min.x > max.x {
+        return None;
+    }
+
+    let pad = range.ceil() + 1.0;
+    let left = min.x.floor() - pad;
+    let bottom = min.y.floor() - pad;
+    let right = max.x.ceil() + pad;
+    let top = max.y.ceil() + pad;
+    let width = (right - left) as u32;
+    let height = (top - bottom) as u32;
+
+    // Outer contours may wind in either direction, so determine which side is the inside.
+    let area: Scalar = lines().map(|&[a, b]| a.x * b.y - b.x * a.y).sum();
+    let orientation = if area < 0.0 { -1.0 } else { 1.0 };
+
+    let mut texels = Vec::with_capacity(width as usize * height as usize);
+    for j in 0..height {
+        for i in 0..width {
+            let p = geom::pt2(left + i as f32 + 0.5, top - j as f32 - 0.5);
+
+            // The true signed distance, positive inside the glyph.
+            let nearest = lines()
+                .map(|&l| distance(l, p).dist)
+                .fold(std::f32::MAX, Scalar::min);
+            let inside = winding(lines(), p) != 0;
+            let sd = if inside { nearest } else { -nearest };
+
+            let rgb = match field {
+                Field::Sdf => [sd; 3],
+                Field::Msdf => {
+                    let mut rgb = [sd; 3];
+                    for (c, &channel) in [RED, GREEN, BLUE].iter().enumerate() {
+                        let closest = edges
+                            .iter()
+                            .filter(|e| e.color & channel != 0)
+                            .flat_map(|e| e.lines.iter().enumerate().map(move |l| (e, l)))
+                            .map(|(e, (ix, &l))| {
+                                let d = distance(l, p);
+                                let first = ix == 0;
+                                let last = ix == e.lines.len() - 1;
+                                (d, pseudo_distance(l, p, d, first, last))
+                            })
+                            .fold(
+                                None,
+                                |closest: Option<(Distance, Scalar)>, (d, pd)| match closest {
+                                    Some((c, _)) if !is_closer(&d, &c) => closest,
+                                    _ => Some((d, pd)),
+                                },
+                            );
+                        if let Some((_, pd)) = closest {
+                            rgb[c] = pd * orientation;
+                        }
+                    }
+                    // Where the median disagrees with the true distance, the channels would
+                    // produce artifacts, so fall back to the true distance.
+                    let m = median(rgb[0], rgb[1], rgb[2]);
+                    if (m > 0.0) != (sd > 0.0) {
+                        rgb = [sd; 3];
+                    }
+                    rgb
+                }
+            };
+            texels.push([rgb[0], rgb[1], rgb[2], sd]);
+        }
+    }
+    if field == Field::Msdf {
+        correct_clashes(&mut texels, width as usize, height as usize);
+    }
+
+    let encode = |d: Scalar| ((0.5 + d / (2.0 * range)).max(0.0).min(1.0) * 255.0).round() as u8;
+    let data = texels
+        .iter()
+        .flat_map(|t| t.iter().map(|&d| encode(d)))
+        .collect();
+
+    let bounds = geom::Rect::from_corners(geom::pt2(left, bottom), geom::pt2(right, top));
+    Some(Bitmap {
+        width,
+        height,
+        data,
+        bounds,
+    })
+}
+
+/// The median of the three given values.
+pub fn median(a: Scalar, b: Scalar, c: Scalar) -> Scalar {
+    a.min(b).max(a.max(b).min(c))
+}
+
+// Equalise the channels of neighbouring texels whose channels differ in such a way that
+// interpolating between them would produce artifacts.
+//
+// Of each clashing pair, only the texel that lies farther from the outline is equalised.
+fn correct_clashes(texels: &mut [[Scalar; 4]], width: usize, height: usize) {
+    let threshold = 1.001;
+    let diagonal = threshold * std::f32::consts::SQRT_2;
+    let neighbours = [(1, 0, threshold), (0, 1, threshold), (1, 1, diagonal)];
+    let mut clashes = vec![false; texels.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let a = y * width + x;
+            for &(dx, dy, threshold) in &neighbours {
+                if x + dx >= width || y + dy >= height {
+                    continue;
+                }
+                let b = (y + dy) * width + x + dx;
+                if is_clash(&texels[a], &texels[b], threshold) {
+                    clashes[a] = true;
+                }
+                if is_clash(&texels[b], &texels[a], threshold) {
+                    clashes[b] = true;
+                }
+            }
+        }
+    }
+    for (t, _) in texels.iter_mut().zip(clashes).filter(|(_, c)| *c) {
+        let m = median(t[0], t[1], t[2]);
+        t[0] = m;
+        t[1] = m;
+        t[2] = m;
+    }
+}
+
+// Whether texel `a` clashes with its neighbour `b`.
+fn is_clash(a: &[Scalar; 4], b: &[Scalar; 4], threshold: Scalar) -> bool {
+    // Order the channels from the greatest to the least difference between the texels.
+    let mut channels = [(a[0], b[0]), (a[1], b[1]), (a[2], b[2])];
+    channels.sort_by(|x, y| {
+        let dx = (x.1 - x.0).abs();
+        let dy = (y.1 - y.0).abs();
+        dy.partial_cmp(&dx).unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let [_, (a1, b1), (a2, b2)] = channels;
+    let equalised = b[0] == b[1] && b[0] == b[2];
+    (b1 - a1).abs() >= threshold && !equalised && a2.abs() >= b2.abs()
+}
+
+// Flatten the given contours into edges and assign each edge a color.
+fn edges(contours: &[rt::Contour]) -> Vec<Edge> {
+    let mut edges = vec![];
+    for contour in contours {
+        let mut contour_edges: Vec<Edge> = contour
+            .segments
+            .iter()
+            .map(|segment| Edge {
+                lines: flatten(segment),
+                color: WHITE,
+            })
+            .filter(|edge| !edge.lines.is_empty())
+            .collect();
+        // Close the contour if necessary.
+        let ends = contour_edges.first().and_then(|f| {
+            contour_edges
+                .last()
+                .map(|l| (f.lines[0][0], l.lines.last().unwrap()[1]))
+        });
+        if let Some((start, end)) = ends {
+            if (start - end).magnitude2() > std::f32::EPSILON {
+                let lines = vec![[end, start]];
+                contour_edges.push(Edge {
+                    lines,
+                    color: WHITE,
+                });
+            }
+        }
+        color_edges(&mut contour_edges);
+        edges.extend(contour_edges);
+    }
+    edges
+}
+
+// Approximate the given segment with lines, skipping those of zero length.
+fn flatten(segment: &rt::Segment) -> Vec<[Point2; 2]> {
+    let pt = |p: rt::Point<f32>| geom::pt2(p.x, p.y);
+    let points = match *segment {
+        rt::Segment::Line(ref l) => vec![pt(l.p[0]), pt(l.p[1])],
+        rt::Segment::Curve(ref c) => {
+            let [a, b, c] = [pt(c.p[0]), pt(c.p[1]), pt(c.p[2])];
+            let len = (b - a).magnitude() + (c - b).magnitude();
+            let n = (len / CURVE_TOLERANCE).ceil().max(1.0).min(16.0) as usize;
+            (0..=n)
+                .map(|i| {
+                    let t = i as f32 / n as f32;
+                    let u = 1.0 - t;
+                    a * (u * u) + b * (2.0 * u * t) + c * (t * t)
+                })
+                .collect()
+        }
+    };
+    points
+        .windows(2)
+        .map(|w| [w[0], w[1]])
+        .filter(|[a, b]| (*b - *a).magnitude2() > std::f32::EPSILON)
+        .collect()
+}
+
+// Assign colors to the edges of a single contour so that the edges on either side of each corner
+// share only one channel.
+fn color_edges(edges: &mut [Edge]) {
+    let n = edges.len();
+    let direction = |l: &[Point2; 2]| (l[1] - l[0]).normalize();
+    let corners: Vec<bool> = (0..n)
+        .map(|i| {
+            let prev = &edges[(i + n - 1) % n];
+            let a = direction(prev.lines.last().unwrap());
+            let b = direction(&edges[i].lines[0]);
+            a.dot(b) <= 0.0 || (a.x * b.y - a.y * b.x).abs() > CORNER_THRESHOLD
+        })
+        .collect();
+    let corner_count = corners.iter().filter(|&&c| c).count();
+    let start = match corners.iter().position(|&c| c) {
+        // Smooth contours are the same in every channel.
+        None => return,
+        Some(start) => start,
+    };
+    if corner_count == 1 {
+        // A "teardrop" with a single corner, split into three edge groups.
+        if n < 3 {
+            return;
+        }
+        for k in 0..n {
+            let group = 3 * k / n;
+            edges[(start + k) % n].color = [MAGENTA, WHITE, YELLOW][group];
+        }
+        return;
+    }
+    // Alternate colors at each corner, ensuring the last and first splines also differ.
+    let mut spline = 0;
+    for k in 0..n {
+        let i = (start + k) % n;
+        if k > 0 && corners[i] {
+            spline += 1;
+        }
+        let last = spline == corner_count - 1;
+        edges[i].color = if last && corner_count % 2 == 1 {
+            YELLOW
+        } else {
+            [CYAN, MAGENTA][spline % 2]
+        };
+    }
+}
+
+// The distance from the given point to the given line.
+fn distance([a, b]: [Point2; 2], p: Point2) -> Distance {
+    let ab = b - a;
+    let ap = p - a;
+    let t = ap.dot(ab) / ab.magnitude2();
+    let nearest = a + ab * t.max(0.0).min(1.0);
+    let dist = (p - nearest).magnitude();
+    let cross = ab.x * ap.y - ab.y * ap.x;
+    let orthogonality = if (0.0..=1.0).contains(&t) || dist == 0.0 {
+        1.0
+    } else {
+        cross.abs() / (ab.magnitude() * dist)
+    };
+    let sign = if cross < 0.0 { -1.0 } else { 1.0 };
+    Distance {
+        dist,
+        orthogonality,
+        pseudo: sign * dist,
+    }
+}
+
+// The signed distance to the line, extending the ends of the edge along their tangents.
+fn pseudo_distance([a, b]: [Point2; 2], p: Point2, d: Distance, first: bool, last: bool) -> Scalar {
+    let ab = b - a;
+    let t = (p - a).dot(ab) / ab.magnitude2();
+    if (t < 0.0 && first) || (t > 1.0 && last) {
+        let ap = p - a;
+        let perpendicular = (ab.x * ap.y - ab.y * ap.x) / ab.magnitude();
+        if perpendicular.abs() <= d.dist {
+            return perpendicular;
+        }
+    }
+    d.pseudo
+}
+
+// Whether `a` is closer than `b`, preferring the more orthogonal of two equal distances.
+fn is_closer(a: &Distance, b: &Distance) -> bool {
+    const EPSILON: Scalar = 1e-4;
+    if (a.dist - b.dist).abs() <= EPSILON {
+        a.orthogonality > b.orthogonality
+    } else {
+        a.dist < b.dist
+    }
+}
+
+// The non-zero winding number of the given lines around the given point.
+fn winding<'a, I>(lines: I, p: Point2) -> i32
+where
+    I: IntoIterator<Item = &'a [Point2; 2]>,
+{
+    let mut winding = 0;
+    for &[a, b] in lines {
+        let cross = (b.x - a.x) * (p.y - a.y) - (p.x - a.x) * (b.y - a.y);
+        if a.y <= p.y {
+            if b.y > p.y && cross > 0.0 {
+                winding += 1;
+            }
+        } else if b.y <= p.y && cross < 0.0 {
+            winding -= 1;
+        }
+    }
+    winding
+}
+
+impl std::error::Error for CacheFull {}
+
+impl std::fmt::Display for CacheFull {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "the glyphs queued for a single frame do not fit within the distance field cache"
+        )
+    }
+}
+
+impl std::fmt::Debug for Cache {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Cache")
+            .field("dimensions", &(self.width, self.height))
+            .field("field", &self.field)
+            .field("scale", &self.scale)
+            .field("range", &self.range)
+            .field("entries", &self.entries.len())
+            .finish()
+    }
+}
@@ -0,0 +1,180 @@
+//! Shaping of text into glyphs along with bidirectional text ordering.
+//!
+//! Shaping converts a run of characters into the glyphs that represent them, applying the
+//! ligatures, contextual forms, mark positioning and kerning described by the font. This is
+//! necessary for correctly displaying scripts such as Arabic and Devanagari.
+//!
+//! Shaping is performed via `rustybuzz` and requires the font's data, which is retained for all
+//! fonts loaded via `font::from_bytes`, `font::from_file` and `font::default_notosans`. For all
+//! other fonts, text is laid out one character at a time with kerning applied between pairs.
+
+use crate::geom;
+use crate::text::{self, font, Font, FontSize, GlyphId, Scalar};
+use std::ops::Range;
+
+/// The direction in which a run of text is read.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Direction {
+    LeftToRight,
+    RightToLeft,
+}
+
+/// A range of text within a line that is read in a single direction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Run {
+    /// The byte range of the run within the line.
+    pub range: Range<usize>,
+    /// The direction in which the run is read.
+    pub direction: Direction,
+}
+
+/// A font prepared for shaping.
+///
+/// Parsing a font's data for shaping is relatively expensive, so a single `Face` should be reused
+/// when shaping many runs of text with the same font.
+pub struct Face<'a> {
+    font: &'a Font,
+    face: Option<rustybuzz::Face<'a>>,
+}
+
+/// A single glyph produced by shaping some text.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Glyph {
+    /// The identifier of the glyph within its font.
+    pub id: GlyphId,
+    /// The byte index of the first character in the cluster from which the glyph was produced.
+    pub byte: usize,
+    /// The distance by which the pen advances along the *x* axis after this glyph.
+    pub advance: Scalar,
+    /// The offset of the glyph from the pen position.
+    pub offset: geom::Vector2,
+}
+
+/// Split a line of text into runs of a single direction using the unicode bidirectional algorithm.
+///
+/// Runs are yielded in visual order from left to right. The text within each right-to-left run
+/// must be reversed for display, which `shape` does when given `Direction::RightToLeft`.
+pub fn visual_runs(line: &str) -> Vec<Run> {
+    if line.is_empty() {
+        return vec![];
+    }
+    let info = unicode_bidi::BidiInfo::new(line, None);
+    if !info.has_rtl() {
+        let range = 0..line.len();
+        let direction = Direction::LeftToRight;
+        return vec![Run { range, direction }];
+    }
+    let mut runs = vec![];
+    for para in &info.paragraphs {
+        let (levels, level_runs) = info.visual_runs(para, para.range.clone());
+        for range in level_runs {
+            let direction = if levels[range.start].is_rtl() {
+                Direction::RightToLeft
+            } else {
+                Direction::LeftToRight
+            };
+            runs.push(Run { range, direction });
+        }
+    }
+    runs
+}
+
+impl<'a> Face<'a> {
+    /// Prepare the given font for shaping with its data, as produced by `font::data`.
+    ///
+    /// If `data` is `None` or cannot be parsed, text is laid out via `shape_by_char`.
+    pub fn new(font: &'a Font, data: Option<&'a font::Data>) -> Self {
+        let face = data.and_then(|data| rustybuzz::Face::from_slice(&data.bytes, data.index));
+        Face { font, face }
+    }
+
+    /// Shape the given run of text with the given font size.
+    ///
+    /// See the `shape` function for details.
+    pub fn shape(&self, text: &str, font_size: FontSize, direction: Direction) -> Vec<Glyph> {
+        match self.face {
+            Some(ref face) => shape_with_face(text, self.font, font_size, direction, face),
+            None => shape_by_char(text, self.font, font_size, direction),
+        }
+    }
+}
+
+/// Shape the given run of text with the given font and font size.
+///
+/// Glyphs are returned in visual order from left to right, with the `byte` of each glyph
+/// indexing into the given `text`.
+///
+/// The font's data is parsed on each call. When shaping many runs of text, prefer reusing a
+/// `Face` for each font.
+pub fn shape(text: &str, font: &Font, font_size: FontSize, direction: Direction) -> Vec<Glyph> {
+    let data = font::data(font);
+    Face::new(font, data.as_ref()).shape(text, font_size, direction)
+}
+
+/// Lay out the given text one character at a time, applying kerning between each pair.
+///
+/// This is used for fonts whose data is unavailable for shaping.
+pub fn shape_by_char(
+    text: &str,
+    font: &Font,
+    font_size: FontSize,
+    direction: Direction,
+) -> Vec<Glyph> {
+    let scale = text::pt_to_scale(font_size);
+    let mut chars: Vec<_> = text.char_indices().collect();
+    if direction == Direction::RightToLeft {
+        chars.reverse();
+    }
+    let mut glyphs: Vec<Glyph> = Vec::with_capacity(chars.len());
+    for (byte, ch) in chars {
+        let g = font.glyph(ch).scaled(scale);
+        if let Some(last) = glyphs.last_mut() {
+            last.advance += font.pair_kerning(scale, last.id, g.id());
+        }
+        glyphs.push(Glyph {
+            id: g.id(),
+            byte,
+            advance: g.h_metrics().advance_width,
+            offset: geom::vec2(0.0, 0.0),
+        });
+    }
+    glyphs
+}
+
+// Shape the text via rustybuzz.
+fn shape_with_face(
+    text: &str,
+    font: &Font,
+    font_size: FontSize,
+    direction: Direction,
+    face: &rustybuzz::Face,
+) -> Vec<Glyph> {
+    let mut buffer = rustybuzz::UnicodeBuffer::new();
+    buffer.push_str(text);
+    buffer.set_direction(match direction {
+        Direction::LeftToRight => rustybuzz::Direction::LeftToRight,
+        Direction::RightToLeft => rustybuzz::Direction::RightToLeft,
+    });
+    buffer.guess_segment_properties();
+    let output = rustybuzz::shape(face, &[], buffer);
+
+    // Font units are scaled in the same manner as rusttype so that glyphs match their outlines.
+    let v_metrics = font.v_metrics_unscaled();
+    let scale = text::pt_to_scale(font_size);
+    let units_to_px = scale.y / (v_metrics.ascent - v_metrics.descent);
+
+    output
+        .glyph_infos()
+        .iter()
+        .zip(output.glyph_positions())
+        .map(|(info, pos)| Glyph {
+            id: GlyphId(info.glyph_id as u16),
+            byte: info.cluster as usize,
+            advance: pos.x_advance as Scalar * units_to_px,
+            offset: geom::vec2(
+                pos.x_offset as Scalar * units_to_px,
+                pos.y_offset as Scalar * units_to_px,
+            ),
+        })
+        .collect()
+}
@@ -0,0 +1,125 @@
+//! Styling applied to ranges of text within a single **Text** layout.
+//!
+//! Spans allow for "rich text" in which the font, font size, color and baseline of each range of
+//! text may differ from the rest of the **Text**. Characters that do not lie within any span use
+//! the font and font size of the **Text**'s **Layout**.
+
+use crate::color::{self, IntoLinSrgba, LinSrgba};
+use crate::text::{Font, FontSize, Scalar};
+use std::ops::Range;
+
+/// Styling that may be applied to a range of text.
+///
+/// Fields that are `None` fall back to the style of the surrounding **Text**.
+#[derive(Clone, Debug, Default)]
+pub struct Style {
+    /// The font used to display the range of text.
+    pub font: Option<Font>,
+    /// The font size used to display the range of text.
+    pub font_size: Option<FontSize>,
+    /// The color of the range of text.
+    pub color: Option<LinSrgba>,
+    /// The distance by which the baseline of the range of text is shifted upwards.
+    pub baseline_shift: Option<Scalar>,
+}
+
+/// A range of text along with the **Style** applied to it.
+#[derive(Clone, Debug)]
+pub struct Span {
+    /// The byte range of the text to which the style is applied.
+    pub range: Range<usize>,
+    /// The style applied to the range.
+    pub style: Style,
+}
+
+/// The style of a single character with all fallbacks applied.
+#[derive(Copy, Clone, Debug)]
+pub struct Resolved<'a> {
+    /// The font of the last span specifying one, otherwise the font of the **Text**.
+    pub font: &'a Font,
+    /// The font size of the last span specifying one, otherwise that of the **Text**.
+    pub font_size: FontSize,
+    /// The color of the last span specifying one, or `None` if the color of the **Text** is used.
+    pub color: Option<LinSrgba>,
+    /// The distance by which the baseline is shifted upwards, `0.0` unless specified by a span.
+    pub baseline_shift: Scalar,
+}
+
+impl Style {
+    /// A style that applies no changes to the surrounding **Text**.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Specify the font used to display the range of text.
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Specify the font size used to display the range of text.
+    pub fn font_size(mut self, size: FontSize) -> Self {
+        self.font_size = Some(size);
+        self
+    }
+
+    /// Specify the color of the range of text.
+    pub fn color<C>(mut self, color: C) -> Self
+    where
+        C: IntoLinSrgba<color::DefaultScalar>,
+    {
+        self.color = Some(color.into_lin_srgba());
+        self
+    }
+
+    /// Shift the baseline of the range of text upwards by the given distance.
+    ///
+    /// Negative values shift the baseline downwards, e.g. for subscript.
+    pub fn baseline_shift(mut self, shift: Scalar) -> Self {
+        self.baseline_shift = Some(shift);
+        self
+    }
+}
+
+impl Span {
+    /// Apply the given style to the given byte range of text.
+    pub fn new(range: Range<usize>, style: Style) -> Self {
+        Span { range, style }
+    }
+}
+
+/// Resolve the style of the character at the given byte index.
+///
+/// Where spans overlap, the fields specified by later spans take precedence.
+pub fn resolve<'a>(
+    spans: &'a [Span],
+    byte: usize,
+    font: &'a Font,
+    font_size: FontSize,
+) -> Resolved<'a> {
+    let mut resolved = Resolved {
+        font,
+        font_size,
+        color: None,
+        baseline_shift: 0.0,
+    };
+    let containing = spans
+        .iter()
+        .filter(|span| span.range.start <= byte && byte < span.range.end);
+    for span in containing {
+        let style = &span.style;
+        if let Some(ref font) = style.font {
+            resolved.font = font;
+        }
+        if let Some(font_size) = style.font_size {
+            resolved.font_size = font_size;
+        }
+        if let Some(color) = style.color {
+            resolved.color = Some(color);
+        }
+        if let Some(shift) = style.baseline_shift {
+            resolved.baseline_shift = shift;
+        }
+    }
+    resolved
+}
@@ -614,6 +614,13 @@ fn default_font(default_font_path: Option<&Path>) -> Result<Font, text::font::Er
         match err {
             font::Error::Io(err) => text::font::Error::IO(err),
             font::Error::NoFont => text::font::Error::NoFont,
+            // Conrod has no equivalent for these, so report them as invalid data.
+            err @ font::Error::Parse
+            | err @ font::Error::NotVariable
+            | err @ font::Error::UnknownAxis(_) => {
+                let err = std::io::Error::new(std::io::ErrorKind::InvalidData, err);
+                text::font::Error::IO(err)
+            }
         }
     }
 
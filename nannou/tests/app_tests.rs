@@ -0,0 +1,52 @@
+use nannou::prelude::*;
+use std::time::Duration;
+
+struct Model {
+    received: Vec<u32>,
+}
+
+fn model(app: &App) -> Model {
+    // The app exits once its last window closes, so keep one open for the duration of the test.
+    app.new_window().view(view).build().unwrap();
+    let proxy = app.create_proxy();
+    std::thread::spawn(move || {
+        for i in 0..3u32 {
+            proxy.send_event(i).unwrap();
+        }
+        proxy.send_event("done").unwrap();
+    });
+    // Fail rather than hang if the events never arrive.
+    std::thread::spawn(|| {
+        std::thread::sleep(Duration::from_secs(10));
+        eprintln!("timed out waiting for user events");
+        std::process::exit(1);
+    });
+    Model { received: vec![] }
+}
+
+fn event(_app: &App, model: &mut Model, event: Event) {
+    let event = match event {
+        Event::User(event) => event,
+        _ => return,
+    };
+    let event = match event.downcast::<u32>() {
+        Ok(i) => return model.received.push(i),
+        Err(event) => event,
+    };
+    assert_eq!(event.downcast_ref::<&str>(), Some(&"done"));
+    assert_eq!(model.received, vec![0, 1, 2]);
+    // The event loop never returns, so exit once the events have been checked.
+    std::process::exit(0);
+}
+
+fn view(_app: &App, _model: &Model, _frame: Frame) {}
+
+// Custom events sent via an `app::Proxy` are delivered in order to the `event` function.
+//
+// The app loop takes over the process, so run this on its own with
+// `cargo test --test app_tests -- --ignored`.
+#[test]
+#[ignore = "requires a display"]
+fn user_events_are_delivered_to_the_event_function() {
+    nannou::app(model).event(event).run();
+}
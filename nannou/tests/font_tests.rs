@@ -0,0 +1,217 @@
+use nannou::text;
+use nannou::text::font::system::{Database, FaceInfo, Query, Style, Weight, Width};
+use nannou::text::font::{self, Variation};
+use std::path::PathBuf;
+
+mod test_font;
+
+fn face(family: &str, weight: Weight, width: Width, style: Style) -> FaceInfo {
+    FaceInfo {
+        path: PathBuf::from(format!("{}-{}-{:?}.ttf", family, weight.0, style)),
+        index: 0,
+        family: family.to_string(),
+        full_name: None,
+        weight,
+        width,
+        style,
+        monospaced: false,
+        axes: vec![],
+    }
+}
+
+// A font with a weight axis from 100 to 900 along which "a" widens, grows and advances further.
+fn variable_font() -> Vec<u8> {
+    let mut a = test_font::rect('a', 600, (100, 500), (0, 500));
+    a.deltas = vec![(0, 0), (200, 0), (200, 100), (0, 100)];
+    a.advance_delta = 200;
+    let axis = test_font::Axis {
+        tag: *b"wght",
+        min: 100.0,
+        default: 400.0,
+        max: 900.0,
+    };
+    test_font::build_variable("Test Variable", &[a], axis)
+}
+
+// Collects the points of a glyph outline.
+#[derive(Default)]
+struct Points(Vec<(f32, f32)>);
+
+impl ttf_parser::OutlineBuilder for Points {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.0.push((x, y));
+    }
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.0.push((x, y));
+    }
+    fn quad_to(&mut self, _: f32, _: f32, x: f32, y: f32) {
+        self.0.push((x, y));
+    }
+    fn curve_to(&mut self, _: f32, _: f32, _: f32, _: f32, x: f32, y: f32) {
+        self.0.push((x, y));
+    }
+    fn close(&mut self) {
+        // Ignore the point closing the contour, if any.
+        if self.0.len() > 1 && self.0.first() == self.0.last() {
+            self.0.pop();
+        }
+    }
+}
+
+// The outline points and advance of the given character.
+fn outline_and_advance(font: &[u8], ch: char) -> (Vec<(f32, f32)>, u16) {
+    let face = ttf_parser::Face::from_slice(font, 0).unwrap();
+    let id = face.glyph_index(ch).unwrap();
+    let mut points = Points::default();
+    face.outline_glyph(id, &mut points).unwrap();
+    (points.0, face.glyph_hor_advance(id).unwrap())
+}
+
+fn database() -> Database {
+    let mut db = Database::new();
+    for &(weight, style) in &[
+        (Weight::LIGHT, Style::Normal),
+        (Weight::NORMAL, Style::Normal),
+        (Weight::BOLD, Style::Normal),
+        (Weight::BOLD, Style::Oblique),
+    ] {
+        db.push(face("Sans", weight, Width::NORMAL, style));
+    }
+    db.push(face("Sans", Weight::BOLD, Width::CONDENSED, Style::Italic));
+    db.push(face("Serif", Weight::MEDIUM, Width::NORMAL, Style::Normal));
+    db
+}
+
+#[test]
+fn query_selects_family_case_insensitively() {
+    let db = database();
+    let face = Query::new("sans").find(&db).unwrap();
+    assert_eq!(face.family, "Sans");
+    assert_eq!(face.weight, Weight::NORMAL);
+    assert!(Query::new("Mono").find(&db).is_none());
+    assert_eq!(
+        Query::new("Mono").or("Serif").find(&db).unwrap().family,
+        "Serif"
+    );
+}
+
+#[test]
+fn query_matches_weight() {
+    let db = database();
+    let select = |w| Query::new("Sans").weight(w).find(&db).unwrap().weight;
+    assert_eq!(select(Weight::BOLD), Weight::BOLD);
+    // Heavier weights are preferred for bold queries.
+    assert_eq!(select(Weight::SEMI_BOLD), Weight::BOLD);
+    // Lighter weights are preferred for light queries.
+    assert_eq!(select(Weight::EXTRA_LIGHT), Weight::LIGHT);
+    // Lighter weights are preferred between 400 and 500 when no heavier weight up to 500 exists.
+    assert_eq!(select(Weight::MEDIUM), Weight::NORMAL);
+    assert_eq!(
+        Query::new("Serif")
+            .weight(Weight::NORMAL)
+            .find(&db)
+            .unwrap()
+            .weight,
+        Weight::MEDIUM
+    );
+}
+
+#[test]
+fn query_matches_width_then_style() {
+    let db = database();
+    // The normal width oblique face is preferred over the condensed italic face.
+    let face = Query::new("Sans").style(Style::Italic).find(&db).unwrap();
+    assert_eq!(face.style, Style::Oblique);
+    let face = Query::new("Sans")
+        .width(Width::CONDENSED)
+        .style(Style::Italic)
+        .find(&db)
+        .unwrap();
+    assert_eq!((face.width, face.style), (Width::CONDENSED, Style::Italic));
+}
+
+#[cfg(feature = "notosans")]
+#[test]
+fn loaded_fonts_are_cached_by_id() {
+    let a = font::from_bytes(notosans::REGULAR_TTF).unwrap();
+    let b = font::from_bytes(notosans::REGULAR_TTF).unwrap();
+    let id = font::id(&a);
+    assert_eq!(id, font::id(&b));
+    assert!(font::cached(id).is_some());
+    assert!(font::data(&a).is_some());
+}
+
+#[cfg(feature = "notosans")]
+#[test]
+fn variations_require_a_variable_font() {
+    let res = font::from_bytes_with_variations(
+        notosans::REGULAR_TTF,
+        0,
+        &[Variation::new(b"wght", 700.0)],
+    );
+    match res {
+        Err(font::Error::NotVariable) => (),
+        other => panic!("unexpected result: {:?}", other.map(|f| font::id(&f))),
+    }
+}
+
+#[test]
+fn variable_fonts_are_instanced() {
+    let bytes = variable_font();
+    let instance = |value| {
+        let variations = [Variation::new(b"wght", value)];
+        let font = font::from_bytes_with_variations(&bytes, 0, &variations).unwrap();
+        let data = font::data(&font).unwrap();
+        (font, data.bytes)
+    };
+    let (default_font, default_bytes) = instance(400.0);
+    let (bold_font, bold_bytes) = instance(900.0);
+    let (_, semi_bold_bytes) = instance(650.0);
+
+    // Each instance is a valid static font with valid checksums.
+    for bytes in &[&default_bytes, &bold_bytes, &semi_bold_bytes] {
+        let face = ttf_parser::Face::from_slice(bytes, 0).unwrap();
+        assert!(!face.is_variable());
+        for (tag, checksum, data) in test_font::tables(bytes) {
+            let mut data = data.to_vec();
+            if &tag == b"head" {
+                data[8..12].copy_from_slice(&[0; 4]);
+            }
+            assert_eq!(test_font::checksum(&data), checksum);
+        }
+        assert_eq!(test_font::checksum(bytes), 0xB1B0_AFBA);
+    }
+
+    // The default instance matches the original outlines and advances.
+    let rect = |x: f32, y: f32| vec![(100.0, 0.0), (x, 0.0), (x, y), (100.0, y)];
+    assert_eq!(outline_and_advance(&bytes, 'a'), (rect(500.0, 500.0), 600));
+    assert_eq!(
+        outline_and_advance(&default_bytes, 'a'),
+        (rect(500.0, 500.0), 600)
+    );
+    assert_eq!(
+        outline_and_advance(&bold_bytes, 'a'),
+        (rect(700.0, 600.0), 800)
+    );
+    assert_eq!(
+        outline_and_advance(&semi_bold_bytes, 'a'),
+        (rect(600.0, 550.0), 700)
+    );
+
+    // The instances are distinct fonts, laid out with their own metrics.
+    assert_ne!(font::id(&default_font), font::id(&bold_font));
+    let scale = text::Scale::uniform(test_font::UNITS_PER_EM as f32);
+    let advance = |font: &text::Font| font.glyph('a').scaled(scale).h_metrics().advance_width;
+    assert_eq!(advance(&default_font), 600.0);
+    assert_eq!(advance(&bold_font), 800.0);
+}
+
+#[test]
+fn variations_require_a_known_axis() {
+    let res =
+        font::from_bytes_with_variations(&variable_font(), 0, &[Variation::new(b"wdth", 50.0)]);
+    match res {
+        Err(font::Error::UnknownAxis(tag)) => assert_eq!(&tag, b"wdth"),
+        other => panic!("unexpected result: {:?}", other.map(|f| font::id(&f))),
+    }
+}
@@ -13,3 +13,123 @@ fn angle_test() {
     let vector = Vector2::new(70.7, -60.8);
     assert_eq!(vector.angle(), -0.7102547457375739);
 }
+
+#[test]
+fn catmull_rom_passes_through_points() {
+    let points = vec![pt2(0.0, 0.0), pt2(1.0, 1.0), pt2(2.0, 0.0), pt2(3.0, 1.0)];
+    let spline = geom::Spline::catmull_rom(points.clone(), 0.5);
+    assert_eq!(spline.segments().len(), points.len() - 1);
+    for (segment, point) in spline.segments().iter().zip(&points) {
+        assert_eq!(segment.from, *point);
+    }
+    assert_eq!(spline.segments().last().unwrap().to, points[3]);
+}
+
+#[test]
+fn arc_length_equidistant_points() {
+    use nannou::geom::Curve;
+    let line = geom::CubicBezier::line(pt2(0.0, 0.0), pt2(10.0, 0.0));
+    let arc_length = line.arc_length(32);
+    assert!((arc_length.length() - 10.0).abs() < 1e-4);
+    let points: Vec<_> = arc_length.equidistant_points(2.0).collect();
+    assert_eq!(points.len(), 6);
+    for (i, p) in points.iter().enumerate() {
+        assert!((p.x - i as f32 * 2.0).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn convex_hull_and_bounding_shapes() {
+    let points = vec![
+        pt2(0.0, 0.0),
+        pt2(2.0, 0.0),
+        pt2(1.0, 1.0),
+        pt2(2.0, 2.0),
+        pt2(0.0, 2.0),
+        pt2(1.0, 0.0),
+    ];
+    let hull = geom::convex_hull(points.clone());
+    assert_eq!(
+        hull,
+        vec![pt2(0.0, 0.0), pt2(2.0, 0.0), pt2(2.0, 2.0), pt2(0.0, 2.0)]
+    );
+    assert_eq!(geom::polygon::area(hull.iter().cloned()), 4.0);
+    assert_eq!(
+        geom::polygon::winding(hull),
+        Some(geom::Winding::CounterClockwise)
+    );
+    let circle = geom::bounding_circle(points.clone()).unwrap();
+    assert!((circle.center - pt2(1.0, 1.0)).magnitude() < 1e-5);
+    assert!((circle.radius - 2.0f32.sqrt()).abs() < 1e-5);
+    let diamond = vec![pt2(1.0, 0.0), pt2(2.0, 1.0), pt2(1.0, 2.0), pt2(0.0, 1.0)];
+    let rect = geom::oriented_bounding_rect(diamond).unwrap();
+    assert!((rect.area() - 2.0).abs() < 1e-5);
+}
+
+#[test]
+fn point_in_concave_polygon_with_holes() {
+    let outline = vec![
+        pt2(0.0, 0.0),
+        pt2(4.0, 0.0),
+        pt2(4.0, 4.0),
+        pt2(2.0, 1.0),
+        pt2(0.0, 4.0),
+    ];
+    let hole = vec![pt2(0.5, 0.2), pt2(1.5, 0.2), pt2(1.5, 0.8), pt2(0.5, 0.8)];
+    let contains =
+        |p| geom::polygon::contains_point_with_holes(outline.clone(), vec![hole.clone()], &p);
+    assert!(!contains(pt2(2.0, 2.0)));
+    assert!(!contains(pt2(1.0, 0.5)));
+    assert!(contains(pt2(3.0, 0.5)));
+}
+
+#[test]
+fn ellipse_contains_matches_its_polygon() {
+    let (x, y) = (1.0, -2.0);
+    let ellipse = geom::Ellipse::new(Rect::from_x_y_w_h(x, y, 6.0, 3.0), 512);
+    let polygon: Vec<_> = ellipse.circumference().collect();
+    for i in 0..=40 {
+        for j in 0..=40 {
+            let p = pt2(x - 4.0 + i as f32 * 0.2, y - 2.0 + j as f32 * 0.1);
+            let dist = ((p.x - x) / 3.0).powi(2) + ((p.y - y) / 1.5).powi(2);
+            // The polygon lies just within the ellipse, so they may only differ near its edge.
+            if (dist - 1.0).abs() > 1e-3 {
+                let in_polygon = geom::polygon::contains_point(polygon.iter().cloned(), &p);
+                assert_eq!(ellipse.contains(p), in_polygon, "{:?}", p);
+            }
+        }
+    }
+    assert!(ellipse.contains(pt2(4.0, -2.0)));
+    assert!(!ellipse.contains(pt2(4.0, -1.9)));
+}
+
+#[test]
+fn graph_transform_map_and_picking() {
+    use nannou::geom::graph::{edge, node, Node};
+    let mut graph = geom::Graph::<f32>::new();
+    let a = graph.add_node(Node::Point);
+    let origin = graph.origin();
+    graph.set_edge(origin, a, edge::Edge::x_position(10.0)).unwrap();
+    let b = graph.add_node(Node::Point);
+    graph.set_edge(a, b, edge::Edge::x_position(5.0)).unwrap();
+    graph.set_edge(a, b, edge::Edge::x_scale(2.0)).unwrap();
+
+    let transforms = graph.transform_map();
+    assert_eq!(transforms[&a].disp, vec3(10.0, 0.0, 0.0));
+    assert_eq!(transforms[&b].disp, vec3(15.0, 0.0, 0.0));
+    assert_eq!(transforms[&b].scale, vec3(2.0, 1.0, 1.0));
+
+    // A unit square centred on each node other than the origin.
+    let square = |n: &node::Index| -> Vec<Point3> {
+        if *n == origin {
+            return vec![];
+        }
+        vec![pt3(-0.5, -0.5, 0.0), pt3(0.5, 0.5, 0.0)]
+    };
+    let mut dfs = node::Dfs::new(&graph);
+    assert_eq!(graph.pick_xy(&mut dfs, square, pt2(15.9, 0.0)), vec![b]);
+    let mut dfs = node::Dfs::new(&graph);
+    assert_eq!(graph.pick_xy(&mut dfs, square, pt2(10.0, 0.25)), vec![a]);
+    let mut dfs = node::Dfs::new(&graph);
+    assert!(graph.pick_xy(&mut dfs, square, pt2(0.0, 0.0)).is_empty());
+}
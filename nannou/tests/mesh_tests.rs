@@ -0,0 +1,123 @@
+use nannou::geom::graph::node::{self, ApplyTransform};
+use nannou::math::{Matrix4, Rad};
+use nannou::mesh::file::{self, Encoding};
+use nannou::mesh::{normals, shape, vertex, TriMesh};
+use nannou::prelude::*;
+
+fn colored_quad() -> TriMesh {
+    let points = vec![
+        pt3(0.0, 0.0, 0.0),
+        pt3(1.0, 0.0, 0.0),
+        pt3(1.0, 1.0, 0.0),
+        pt3(0.0, 1.0, 0.0),
+    ];
+    TriMesh::new(points, vec![0, 1, 2, 0, 2, 3]).with_colors(vec![RED; 4])
+}
+
+#[test]
+fn mesh_file_round_trip() {
+    let mesh = colored_quad();
+
+    let mut obj = vec![];
+    file::obj::write(&mesh, &mut obj).unwrap();
+    let loaded = file::obj::read(&obj[..]).unwrap();
+    assert_eq!(loaded.points(), mesh.points());
+    assert_eq!(loaded.indices(), mesh.indices());
+    assert!(loaded.has_colors());
+
+    for &encoding in &[Encoding::Ascii, Encoding::Binary] {
+        let mut ply = vec![];
+        file::ply::write(&mesh, &mut ply, encoding).unwrap();
+        let loaded = file::ply::read(&ply[..]).unwrap();
+        assert_eq!(loaded.points(), mesh.points());
+        assert_eq!(loaded.indices(), mesh.indices());
+
+        let mut stl = vec![];
+        file::stl::write(&mesh, &mut stl, encoding).unwrap();
+        let loaded = file::stl::read(&stl[..]).unwrap();
+        assert_eq!(loaded.triangle_count(), 2);
+        assert_eq!(loaded.normals()[0], vec3(0.0, 0.0, 1.0));
+    }
+}
+
+// Assert that every triangle is non-degenerate and faces the same way as its vertex normals.
+fn assert_outward_facing(mesh: &TriMesh) {
+    for tri in mesh.indices().chunks(3) {
+        let p = |i: usize| mesh.points()[tri[i] as usize];
+        let face = normals::face(p(0), p(1), p(2));
+        assert!(!face.is_zero());
+        for &i in tri {
+            let normal = mesh.normals()[i as usize];
+            assert!((normal.magnitude() - 1.0).abs() < 1e-4);
+            assert!(face.dot(normal) > 0.0);
+        }
+    }
+}
+
+#[test]
+fn mesh_shapes_and_processing() {
+    let shapes = vec![
+        shape::plane(2.0, 1.0, 4, 3),
+        shape::sphere(1.0, 16, 8),
+        shape::icosphere(1.0, 2),
+        shape::cylinder(1.0, 2.0, 12),
+        shape::torus(1.0, 0.25, 16, 8),
+    ];
+    for mesh in &shapes {
+        assert_outward_facing(mesh);
+    }
+    assert_eq!(shapes[2].points().len(), 162);
+    assert_eq!(shapes[2].triangle_count(), 320);
+
+    let merged = TriMesh::merge(&shapes);
+    let triangle_count: usize = shapes.iter().map(|m| m.triangle_count()).sum();
+    assert_eq!(merged.triangle_count(), triangle_count);
+    assert_outward_facing(&merged);
+
+    // Welding a UV sphere joins the duplicated seam and pole points.
+    let welded = shape::sphere(1.0, 8, 4).weld(1e-5).with_smooth_normals();
+    assert_eq!(welded.points().len(), 8 * 3 + 2);
+    assert_outward_facing(&welded);
+
+    let flat = shape::icosphere(1.0, 1).with_flat_normals();
+    assert_eq!(flat.points().len(), flat.indices().len());
+    assert_outward_facing(&flat);
+
+    // Loop subdivision adds a vertex per edge, Catmull-Clark a vertex per edge and face.
+    let ico = shape::icosphere(1.0, 0);
+    let subdivided = ico.clone().subdivide_loop(1);
+    assert_eq!(subdivided.points().len(), 12 + 30);
+    assert_eq!(subdivided.triangle_count(), 80);
+    assert_outward_facing(&subdivided);
+    let subdivided = ico.subdivide_catmull_clark(1);
+    assert_eq!(subdivided.points().len(), 12 + 30 + 20);
+    assert_eq!(subdivided.triangle_count(), 60 * 2);
+    assert_outward_facing(&subdivided);
+
+    // Boundaries remain in place and colors are interpolated.
+    let plane = shape::plane(1.0, 1.0, 1, 1)
+        .with_color(RED)
+        .subdivide_loop(2);
+    assert!(plane.points().iter().all(|p| p.z == 0.0));
+    assert!(plane.has_colors());
+
+    // Mirrored transforms reverse the winding order.
+    let scale = Matrix4::from_nonuniform_scale(-2.0, 1.0, 0.5);
+    assert_outward_facing(&shape::torus(1.0, 0.3, 8, 6).transform(scale));
+}
+
+#[test]
+fn vertex_with_normal_apply_transform() {
+    let mut transform = node::Transform::default();
+    transform.scale = vec3(2.0, 1.0, 1.0);
+    transform.rot.z = Rad(std::f32::consts::FRAC_PI_2);
+    let v = vertex::WithNormal {
+        vertex: pt3(1.0, 1.0, 0.0),
+        normal: vec3(1.0, 1.0, 0.0).normalize(),
+    };
+    let v = v.apply_transform(&transform.prepare());
+    assert!((v.vertex - pt3(-1.0, 2.0, 0.0)).magnitude() < 1e-5);
+    // The normal is scaled by the inverse scale prior to rotation.
+    let expected = vec3(-1.0, 0.5, 0.0).normalize();
+    assert!((v.normal - expected).magnitude() < 1e-5);
+}
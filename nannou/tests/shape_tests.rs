@@ -0,0 +1,216 @@
+use nannou::prelude::*;
+use nannou::text::font;
+use nannou::text::shape::{self, Direction, Run};
+use nannou::text::{self, GlyphId};
+
+mod test_font;
+
+const HEBREW: &str = "\u{5D0}\u{5D1}\u{5D2}";
+
+// A font covering lowercase "abc" and the space.
+fn latin_font(family: &str) -> Vec<u8> {
+    let space = test_font::Glyph {
+        ch: ' ',
+        advance: 250,
+        ..Default::default()
+    };
+    let glyphs = vec![
+        test_font::rect('a', 500, (50, 450), (0, 500)),
+        test_font::rect('b', 550, (50, 500), (0, 700)),
+        test_font::rect('c', 450, (50, 400), (0, 500)),
+        space,
+    ];
+    test_font::build(family, &glyphs)
+}
+
+// A font covering the first three letters of the Hebrew alphabet.
+fn hebrew_font() -> Vec<u8> {
+    let glyphs: Vec<_> = HEBREW
+        .chars()
+        .enumerate()
+        .map(|(i, ch)| test_font::rect(ch, 600 + i as u16 * 10, (50, 550), (0, 600)))
+        .collect();
+    test_font::build("Test Hebrew", &glyphs)
+}
+
+#[test]
+fn fonts_are_identified_by_content() {
+    let a = font::from_bytes(latin_font("Test Latin")).unwrap();
+    let b = font::from_bytes(latin_font("Test Latin")).unwrap();
+    assert_eq!(font::id(&a), font::id(&b));
+
+    // Fonts with the same names but different data must not share an `Id` or data.
+    let renamed = test_font::build("Test Latin", &[test_font::rect('x', 1, (0, 1), (0, 1))]);
+    let c = font::from_bytes(renamed.clone()).unwrap();
+    assert_ne!(font::id(&a), font::id(&c));
+    assert_eq!(&font::data(&c).unwrap().bytes[..], &renamed[..]);
+    assert_ne!(&font::data(&a).unwrap().bytes[..], &renamed[..]);
+
+    // Reloading identical data produces the same `Id`, even once the original has been dropped.
+    let (id, c_id) = (font::id(&a), font::id(&c));
+    drop((a, b, c));
+    let d = font::from_bytes(latin_font("Test Latin")).unwrap();
+    assert_eq!(font::id(&d), id);
+    let e = font::from_bytes(renamed).unwrap();
+    assert_eq!(font::id(&e), c_id);
+}
+
+#[test]
+fn unused_fonts_are_evicted() {
+    let id = {
+        let font = font::from_bytes(latin_font("Test Evicted")).unwrap();
+        let id = font::id(&font);
+        assert!(font::cached(id).is_some());
+        id
+    };
+    assert!(font::cached(id).is_none());
+}
+
+#[test]
+fn visual_runs_order_mixed_directions() {
+    let runs = |s: &str| -> Vec<(std::ops::Range<usize>, Direction)> {
+        shape::visual_runs(s)
+            .into_iter()
+            .map(|Run { range, direction }| (range, direction))
+            .collect()
+    };
+    assert_eq!(runs("abc"), vec![(0..3, Direction::LeftToRight)]);
+
+    // Right-to-left text within a left-to-right paragraph.
+    let line = format!("abc {} def", HEBREW);
+    assert_eq!(
+        runs(&line),
+        vec![
+            (0..4, Direction::LeftToRight),
+            (4..10, Direction::RightToLeft),
+            (10..14, Direction::LeftToRight),
+        ]
+    );
+
+    // Left-to-right text within a right-to-left paragraph appears first visually.
+    let line = format!("{} abc", HEBREW);
+    assert_eq!(
+        runs(&line),
+        vec![
+            (7..10, Direction::LeftToRight),
+            (0..7, Direction::RightToLeft)
+        ]
+    );
+}
+
+#[test]
+fn right_to_left_runs_are_reversed() {
+    let font = font::from_bytes(hebrew_font()).unwrap();
+    assert!(font::data(&font).is_some());
+    let bytes: Vec<_> = HEBREW.char_indices().map(|(i, _)| i).rev().collect();
+    let ids: Vec<_> = HEBREW.chars().rev().map(|ch| font.glyph(ch).id()).collect();
+    for glyphs in vec![
+        shape::shape(HEBREW, &font, 20, Direction::RightToLeft),
+        shape::shape_by_char(HEBREW, &font, 20, Direction::RightToLeft),
+    ] {
+        assert_eq!(glyphs.iter().map(|g| g.byte).collect::<Vec<_>>(), bytes);
+        assert_eq!(glyphs.iter().map(|g| g.id).collect::<Vec<_>>(), ids);
+    }
+
+    // Laid out within text, the first character appears furthest to the right.
+    let text = text::text(HEBREW)
+        .font(font)
+        .no_line_wrap()
+        .build(Rect::from_w_h(400.0, 100.0));
+    let glyphs = text.glyph_infos();
+    assert_eq!(glyphs.len(), 3);
+    assert!(glyphs[0].byte > glyphs[1].byte && glyphs[1].byte > glyphs[2].byte);
+    assert!(glyphs[0].position.x < glyphs[2].position.x);
+}
+
+#[test]
+fn fonts_without_data_are_shaped_by_char() {
+    let font = text::Font::from_bytes(latin_font("Test Unregistered")).unwrap();
+    assert!(font::data(&font).is_none());
+    let glyphs = shape::shape("abc", &font, 20, Direction::LeftToRight);
+    assert_eq!(
+        glyphs,
+        shape::shape_by_char("abc", &font, 20, Direction::LeftToRight)
+    );
+    assert_eq!(
+        glyphs.iter().map(|g| g.byte).collect::<Vec<_>>(),
+        vec![0, 1, 2]
+    );
+}
+
+#[test]
+fn missing_glyphs_fall_back() {
+    let latin = font::from_bytes(latin_font("Test Latin")).unwrap();
+    let hebrew = font::from_bytes(hebrew_font()).unwrap();
+    let alef = HEBREW.chars().next().unwrap();
+    assert!(!font::covers(&latin, alef));
+    let fallback = vec![hebrew.clone()];
+    assert_eq!(
+        font::id(font::fallback(&latin, &fallback, 'a')),
+        font::id(&latin)
+    );
+    assert_eq!(
+        font::id(font::fallback(&latin, &fallback, alef)),
+        font::id(&hebrew)
+    );
+
+    let s = format!("ab {}", HEBREW);
+    let text = text::text(&s)
+        .font(latin.clone())
+        .fallback_fonts(fallback)
+        .no_line_wrap()
+        .build(Rect::from_w_h(400.0, 100.0));
+    let glyphs = text.glyph_infos();
+    assert_eq!(glyphs.len(), s.chars().count());
+    for glyph in glyphs {
+        let ch = s[glyph.byte..].chars().next().unwrap();
+        let expected = if ch.is_ascii() { &latin } else { &hebrew };
+        assert_eq!(glyph.font_id, font::id(expected));
+        assert_ne!(glyph.glyph.id(), GlyphId(0));
+    }
+}
+
+// The bundled font has no "liga" feature, so the "ccmp" ligatures of its tone letters are used.
+#[cfg(feature = "notosans")]
+#[test]
+fn shaping_applies_ligatures() {
+    let font = font::default_notosans();
+    let s = "\u{2E5}\u{2E6}";
+    let shaped = shape::shape(s, &font, 20, Direction::LeftToRight);
+    let by_char = shape::shape_by_char(s, &font, 20, Direction::LeftToRight);
+    assert_eq!(by_char.len(), 2);
+    assert_eq!(shaped.len(), 1);
+    assert_eq!(shaped[0].byte, 0);
+    assert!(by_char.iter().all(|g| g.id != shaped[0].id));
+
+    // Without a ligature, shaping produces the same glyphs as laying out each character.
+    let shaped = shape::shape("fi", &font, 20, Direction::LeftToRight);
+    let by_char = shape::shape_by_char("fi", &font, 20, Direction::LeftToRight);
+    let ids = |gs: &[shape::Glyph]| gs.iter().map(|g| g.id).collect::<Vec<_>>();
+    assert_eq!(ids(&shaped), ids(&by_char));
+}
+
+// Lines are wrapped by the advances of their shaped glyphs, so a line of exactly the shaped width
+// is not wrapped, while ligatures are never split between lines.
+#[cfg(feature = "notosans")]
+#[test]
+fn lines_wrap_by_shaped_advances() {
+    let s = "AV \u{2E5}\u{2E6}".repeat(3);
+    let build = |w: f32| {
+        text::text(&s)
+            .font_size(24)
+            .wrap_by_character()
+            .build(Rect::from_w_h(w, 400.0))
+    };
+    let unwrapped = text::text(&s)
+        .font_size(24)
+        .no_line_wrap()
+        .build(Rect::from_w_h(1_000.0, 400.0));
+    let width = unwrapped.line_infos()[0].width;
+
+    assert_eq!(build(width + 0.01).num_lines(), 1);
+    let wrapped = build(width - 0.01);
+    assert_eq!(wrapped.num_lines(), 2);
+    assert_eq!(wrapped.lines().last(), Some("\u{2E5}\u{2E6}"));
+    assert_eq!(wrapped.glyph_infos().len(), unwrapped.glyph_infos().len());
+}
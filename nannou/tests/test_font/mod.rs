@@ -0,0 +1,458 @@
+//! A minimal TrueType font writer used to produce fonts with known glyphs and variations.
+//!
+//! Each glyph is made up of closed contours of on-curve points. Glyph `0` is always an empty
+//! `.notdef` glyph, with the given glyphs following in order. When an axis is given, the font
+//! is variable, with the point and advance deltas of each glyph applied in full at the maximum of
+//! the axis via the `gvar` and `HVAR` tables.
+
+// Each test crate only uses some of the writer.
+#![allow(dead_code)]
+
+/// A glyph mapped to a single character.
+#[derive(Clone, Debug, Default)]
+pub struct Glyph {
+    pub ch: char,
+    pub advance: u16,
+    pub contours: Vec<Vec<(i16, i16)>>,
+    /// The offset of each point at the maximum of the axis, in the order that they appear.
+    pub deltas: Vec<(i16, i16)>,
+    /// The offset of the advance at the maximum of the axis.
+    pub advance_delta: i16,
+}
+
+/// The single axis of a variable font.
+#[derive(Copy, Clone, Debug)]
+pub struct Axis {
+    pub tag: [u8; 4],
+    pub min: f32,
+    pub default: f32,
+    pub max: f32,
+}
+
+/// The units per em of all fonts.
+pub const UNITS_PER_EM: u16 = 1000;
+
+/// A glyph for the given character made up of a single rectangle.
+pub fn rect(ch: char, advance: u16, x: (i16, i16), y: (i16, i16)) -> Glyph {
+    let contour = vec![(x.0, y.0), (x.1, y.0), (x.1, y.1), (x.0, y.1)];
+    Glyph {
+        ch,
+        advance,
+        contours: vec![contour],
+        ..Default::default()
+    }
+}
+
+/// Write a font with the given family name and glyphs.
+///
+/// The characters of the glyphs must be within the basic multilingual plane.
+pub fn build(family: &str, glyphs: &[Glyph]) -> Vec<u8> {
+    font(family, glyphs, None)
+}
+
+/// Write a variable font with the given family name, glyphs and axis.
+pub fn build_variable(family: &str, glyphs: &[Glyph], axis: Axis) -> Vec<u8> {
+    font(family, glyphs, Some(axis))
+}
+
+fn font(family: &str, glyphs: &[Glyph], axis: Option<Axis>) -> Vec<u8> {
+    let notdef = Glyph {
+        advance: 500,
+        ..Default::default()
+    };
+    let glyphs: Vec<&Glyph> = std::iter::once(&notdef).chain(glyphs).collect();
+    let (glyf, loca) = glyf_and_loca(&glyphs);
+    let mut tables = vec![
+        (*b"cmap", cmap(&glyphs)),
+        (*b"glyf", glyf),
+        (*b"head", head(&glyphs)),
+        (*b"hhea", hhea(&glyphs)),
+        (*b"hmtx", hmtx(&glyphs)),
+        (*b"loca", loca),
+        (*b"maxp", maxp(&glyphs)),
+        (*b"name", name(family)),
+        (*b"post", post()),
+    ];
+    if let Some(axis) = axis {
+        tables.push((*b"HVAR", hvar(&glyphs)));
+        tables.push((*b"fvar", fvar(axis)));
+        tables.push((*b"gvar", gvar(&glyphs)));
+    }
+    tables.sort_by_key(|&(tag, _)| tag);
+    write_font(&tables)
+}
+
+/// The sum of the given data as big-endian `u32`s, padded with zeros.
+pub fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// The tag, stored checksum and data of each table within the given font.
+pub fn tables(font: &[u8]) -> Vec<([u8; 4], u32, &[u8])> {
+    let num_tables = u16::from_be_bytes([font[4], font[5]]) as usize;
+    (0..num_tables)
+        .map(|i| {
+            let record = &font[12 + i * 16..12 + (i + 1) * 16];
+            let word = |i: usize| {
+                u32::from_be_bytes([record[i], record[i + 1], record[i + 2], record[i + 3]])
+            };
+            let mut tag = [0; 4];
+            tag.copy_from_slice(&record[..4]);
+            let (offset, len) = (word(8) as usize, word(12) as usize);
+            (tag, word(4), &font[offset..offset + len])
+        })
+        .collect()
+}
+
+trait Write {
+    fn u16(&mut self, v: u16);
+    fn i16(&mut self, v: i16);
+    fn u32(&mut self, v: u32);
+}
+
+impl Write for Vec<u8> {
+    fn u16(&mut self, v: u16) {
+        self.extend_from_slice(&v.to_be_bytes());
+    }
+    fn i16(&mut self, v: i16) {
+        self.extend_from_slice(&v.to_be_bytes());
+    }
+    fn u32(&mut self, v: u32) {
+        self.extend_from_slice(&v.to_be_bytes());
+    }
+}
+
+fn points<'a>(glyph: &'a Glyph) -> impl 'a + Iterator<Item = (i16, i16)> {
+    glyph.contours.iter().flat_map(|c| c.iter().cloned())
+}
+
+fn bounds(glyph: &Glyph) -> Option<(i16, i16, i16, i16)> {
+    points(glyph).fold(None, |acc, (x, y)| {
+        let (x0, y0, x1, y1) = acc.unwrap_or((x, y, x, y));
+        Some((x0.min(x), y0.min(y), x1.max(x), y1.max(y)))
+    })
+}
+
+fn fixed(v: f32) -> u32 {
+    (v * 65536.0) as i32 as u32
+}
+
+fn cmap(glyphs: &[&Glyph]) -> Vec<u8> {
+    let mut chars: Vec<(u16, u16)> = glyphs
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(id, g)| (g.ch as u32 as u16, id as u16))
+        .collect();
+    chars.sort();
+    chars.push((0xFFFF, 0));
+    let seg_count = chars.len() as u16;
+    let entry_selector = 15 - seg_count.leading_zeros() as u16;
+    let search_range = 2 << entry_selector;
+
+    let mut t = vec![];
+    t.u16(0);
+    t.u16(1);
+    t.u16(3);
+    t.u16(1);
+    t.u32(12);
+    t.u16(4);
+    t.u16(16 + 8 * seg_count);
+    t.u16(0);
+    t.u16(seg_count * 2);
+    t.u16(search_range);
+    t.u16(entry_selector);
+    t.u16(seg_count * 2 - search_range);
+    for &(ch, _) in &chars {
+        t.u16(ch);
+    }
+    t.u16(0);
+    for &(ch, _) in &chars {
+        t.u16(ch);
+    }
+    for &(ch, id) in &chars {
+        t.u16(if ch == 0xFFFF { 1 } else { id.wrapping_sub(ch) });
+    }
+    for _ in &chars {
+        t.u16(0);
+    }
+    t
+}
+
+fn glyf_and_loca(glyphs: &[&Glyph]) -> (Vec<u8>, Vec<u8>) {
+    let mut glyf = vec![];
+    let mut loca = vec![];
+    for glyph in glyphs {
+        loca.u32(glyf.len() as u32);
+        let (x0, y0, x1, y1) = match bounds(glyph) {
+            None => continue,
+            Some(bounds) => bounds,
+        };
+        glyf.i16(glyph.contours.len() as i16);
+        for &v in &[x0, y0, x1, y1] {
+            glyf.i16(v);
+        }
+        let mut end = 0;
+        for contour in &glyph.contours {
+            end += contour.len();
+            glyf.u16(end as u16 - 1);
+        }
+        // No instructions.
+        glyf.u16(0);
+        // On-curve points with two byte coordinates.
+        glyf.extend(points(glyph).map(|_| 1u8));
+        let mut last = (0, 0);
+        let mut ys = vec![];
+        for (x, y) in points(glyph) {
+            glyf.i16(x - last.0);
+            ys.i16(y - last.1);
+            last = (x, y);
+        }
+        glyf.extend_from_slice(&ys);
+        glyf.resize((glyf.len() + 3) & !3, 0);
+    }
+    loca.u32(glyf.len() as u32);
+    (glyf, loca)
+}
+
+fn head(glyphs: &[&Glyph]) -> Vec<u8> {
+    let (x0, y0, x1, y1) = glyphs
+        .iter()
+        .filter_map(|g| bounds(g))
+        .fold((0, 0, 0, 0), |(a, b, c, d), (x0, y0, x1, y1)| {
+            (a.min(x0), b.min(y0), c.max(x1), d.max(y1))
+        });
+    let mut t = vec![];
+    t.u32(0x0001_0000);
+    t.u32(0x0001_0000);
+    // The `checksumAdjustment` is written along with the font.
+    t.u32(0);
+    t.u32(0x5F0F_3CF5);
+    t.u16(0x000B);
+    t.u16(UNITS_PER_EM);
+    t.extend_from_slice(&[0; 16]);
+    for &v in &[x0, y0, x1, y1] {
+        t.i16(v);
+    }
+    t.u16(0);
+    t.u16(8);
+    t.i16(2);
+    // Long `loca` offsets.
+    t.i16(1);
+    t.i16(0);
+    t
+}
+
+fn hhea(glyphs: &[&Glyph]) -> Vec<u8> {
+    let max_advance = glyphs.iter().map(|g| g.advance).max().unwrap_or(0);
+    let mut t = vec![];
+    t.u32(0x0001_0000);
+    t.i16(800);
+    t.i16(-200);
+    t.i16(0);
+    t.u16(max_advance);
+    t.i16(0);
+    t.i16(0);
+    t.i16(max_advance as i16);
+    t.i16(1);
+    t.extend_from_slice(&[0; 14]);
+    t.i16(0);
+    t.u16(glyphs.len() as u16);
+    t
+}
+
+fn hmtx(glyphs: &[&Glyph]) -> Vec<u8> {
+    let mut t = vec![];
+    for glyph in glyphs {
+        t.u16(glyph.advance);
+        t.i16(bounds(glyph).map(|b| b.0).unwrap_or(0));
+    }
+    t
+}
+
+fn maxp(glyphs: &[&Glyph]) -> Vec<u8> {
+    let max_points = glyphs.iter().map(|g| points(g).count()).max();
+    let max_contours = glyphs.iter().map(|g| g.contours.len()).max();
+    let mut t = vec![];
+    t.u32(0x0001_0000);
+    t.u16(glyphs.len() as u16);
+    t.u16(max_points.unwrap_or(0) as u16);
+    t.u16(max_contours.unwrap_or(0) as u16);
+    t.u16(0);
+    t.u16(0);
+    t.u16(2);
+    t.extend_from_slice(&[0; 16]);
+    t
+}
+
+fn name(family: &str) -> Vec<u8> {
+    let full_name = format!("{} Regular", family);
+    let unique = format!("{};1.0", family);
+    let postscript = format!("{}-Regular", family.replace(' ', ""));
+    let names = [
+        (1, family),
+        (2, "Regular"),
+        (3, &unique[..]),
+        (4, &full_name[..]),
+        (6, &postscript[..]),
+    ];
+    let mut t = vec![];
+    let mut strings = vec![];
+    t.u16(0);
+    t.u16(names.len() as u16);
+    t.u16(6 + 12 * names.len() as u16);
+    for &(id, string) in &names {
+        let encoded: Vec<u8> = string
+            .encode_utf16()
+            .flat_map(|u| u.to_be_bytes().to_vec())
+            .collect();
+        for &v in &[3, 1, 0x0409, id, encoded.len() as u16, strings.len() as u16] {
+            t.u16(v);
+        }
+        strings.extend(encoded);
+    }
+    t.extend(strings);
+    t
+}
+
+fn post() -> Vec<u8> {
+    let mut t = vec![];
+    t.u32(0x0003_0000);
+    t.u32(0);
+    t.i16(-100);
+    t.i16(50);
+    t.extend_from_slice(&[0; 20]);
+    t
+}
+
+fn fvar(axis: Axis) -> Vec<u8> {
+    let mut t = vec![];
+    for &v in &[1, 0, 16, 2, 1, 20, 0, 8] {
+        t.u16(v);
+    }
+    t.extend_from_slice(&axis.tag);
+    t.u32(fixed(axis.min));
+    t.u32(fixed(axis.default));
+    t.u32(fixed(axis.max));
+    t.u16(0);
+    t.u16(256);
+    t
+}
+
+// A single tuple peaking at the maximum of the axis, listing every point of each glyph.
+fn gvar(glyphs: &[&Glyph]) -> Vec<u8> {
+    let mut data = vec![];
+    let mut offsets = vec![];
+    for glyph in glyphs {
+        offsets.push(data.len() as u32);
+        let n = points(glyph).count();
+        if n == 0 {
+            continue;
+        }
+        assert_eq!(glyph.deltas.len(), n, "a delta is required for each point");
+        assert!(n <= 127);
+        let mut serialized = vec![n as u8, n as u8 - 1, 0];
+        serialized.extend(std::iter::repeat(1).take(n - 1));
+        let deltas: Vec<i16> = glyph
+            .deltas
+            .iter()
+            .map(|d| d.0)
+            .chain(glyph.deltas.iter().map(|d| d.1))
+            .collect();
+        for run in deltas.chunks(64) {
+            serialized.push(0x40 | (run.len() as u8 - 1));
+            for &d in run {
+                serialized.i16(d);
+            }
+        }
+        data.u16(1);
+        data.u16(10);
+        data.u16(serialized.len() as u16);
+        // An embedded peak tuple with private point numbers.
+        data.u16(0x8000 | 0x2000);
+        data.u16(0x4000);
+        data.extend(serialized);
+    }
+    offsets.push(data.len() as u32);
+
+    let header_len = 20 + 4 * offsets.len() as u32;
+    let mut t = vec![];
+    t.u16(1);
+    t.u16(0);
+    t.u16(1);
+    t.u16(0);
+    t.u32(header_len);
+    t.u16(glyphs.len() as u16);
+    // Long offsets.
+    t.u16(1);
+    t.u32(header_len);
+    for offset in offsets {
+        t.u32(offset);
+    }
+    t.extend(data);
+    t
+}
+
+// An item variation store with a single region, mapping each glyph to the item at its index.
+fn hvar(glyphs: &[&Glyph]) -> Vec<u8> {
+    let mut t = vec![];
+    t.u16(1);
+    t.u16(0);
+    t.u32(20);
+    t.extend_from_slice(&[0; 12]);
+    // The item variation store.
+    t.u16(1);
+    t.u32(12);
+    t.u16(1);
+    t.u32(22);
+    // The region list.
+    t.u16(1);
+    t.u16(1);
+    t.u16(0);
+    t.u16(0x4000);
+    t.u16(0x4000);
+    // The item variation data.
+    t.u16(glyphs.len() as u16);
+    t.u16(1);
+    t.u16(1);
+    t.u16(0);
+    for glyph in glyphs {
+        t.i16(glyph.advance_delta);
+    }
+    t
+}
+
+fn write_font(tables: &[([u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let num_tables = tables.len() as u16;
+    let entry_selector = 15 - num_tables.leading_zeros() as u16;
+    let search_range = 16 << entry_selector;
+    let mut font = vec![];
+    font.u32(0x0001_0000);
+    font.u16(num_tables);
+    font.u16(search_range);
+    font.u16(entry_selector);
+    font.u16(num_tables * 16 - search_range);
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, data) in tables {
+        font.extend_from_slice(tag);
+        font.u32(checksum(data));
+        font.u32(offset as u32);
+        font.u32(data.len() as u32);
+        offset += (data.len() + 3) & !3;
+    }
+    let mut head = 0;
+    for (tag, data) in tables {
+        if tag == b"head" {
+            head = font.len();
+        }
+        font.extend_from_slice(data);
+        font.resize((font.len() + 3) & !3, 0);
+    }
+    let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&font));
+    font[head + 8..head + 12].copy_from_slice(&adjustment.to_be_bytes());
+    font
+}
@@ -0,0 +1,250 @@
+use nannou::color::IntoLinSrgba;
+use nannou::geom::path;
+use nannou::prelude::*;
+use nannou::text::{self, line, sdf, span, Align, Scale};
+
+const LOREM: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod \
+                     tempor incididunt ut labore et dolore magna aliqua.";
+
+#[cfg(feature = "notosans")]
+#[test]
+fn full_justify_fills_wrapped_lines() {
+    let rect = Rect::from_w_h(300.0, 400.0);
+    let text = text::text(LOREM)
+        .font_size(18)
+        .wrap_by_word()
+        .full_justify()
+        .build(rect);
+    assert!(text.num_lines() > 2);
+
+    let line_rects: Vec<_> = text.line_rects().collect();
+    let (last_info, infos) = text.line_infos().split_last().unwrap();
+    for (info, line_rect) in infos.iter().zip(&line_rects) {
+        match info.end_break {
+            line::Break::Wrap { .. } => (),
+            _ => panic!("expected a wrapped line"),
+        }
+        assert!((line_rect.w() - rect.w()).abs() < 1e-3);
+    }
+
+    // The final line is aligned to the start rather than stretched.
+    let last_rect = line_rects.last().unwrap();
+    assert!(last_info.width < rect.w());
+    assert!((last_rect.w() - last_info.width).abs() < 1e-3);
+    assert!((last_rect.left() - rect.left()).abs() < 1e-3);
+
+    // The last glyph of each wrapped line ends at the right edge of the bounding rect.
+    let line_ends = text.glyphs_per_line().map(|glyphs| {
+        let (glyph, rect) = glyphs.last().unwrap();
+        rect.left() + glyph.h_metrics().advance_width
+    });
+    for (i, end) in line_ends.enumerate() {
+        if i + 1 < text.num_lines() {
+            assert!((end - rect.right()).abs() < 0.5);
+        } else {
+            assert!(end < rect.right() - 1.0);
+        }
+    }
+}
+
+#[cfg(feature = "notosans")]
+#[test]
+fn spans_style_glyph_ranges() {
+    let s = "plain large plain";
+    let large = 6..11;
+    let style = span::Style::new().font_size(40).color(RED);
+    let text = text::text(s)
+        .font_size(20)
+        .no_line_wrap()
+        .span(large.clone(), style)
+        .build(Rect::from_w_h(800.0, 200.0));
+
+    // The line height follows the greatest font size within the line.
+    assert_eq!(text.line_infos()[0].font_size, 40);
+
+    let red = RED.into_lin_srgba();
+    let glyphs = text.glyph_infos();
+    assert_eq!(glyphs.len(), s.chars().count());
+    for glyph in glyphs {
+        if large.contains(&glyph.byte) {
+            assert_eq!(glyph.font_size, 40);
+            assert_eq!(glyph.color, Some(red));
+        } else {
+            assert_eq!(glyph.font_size, 20);
+            assert_eq!(glyph.color, None);
+        }
+    }
+}
+
+#[cfg(feature = "notosans")]
+#[test]
+fn later_spans_take_precedence() {
+    let text = text::text("abc")
+        .font_size(20)
+        .span(0..3, span::Style::new().font_size(30).color(RED))
+        .span(1..2, span::Style::new().font_size(10))
+        .build(Rect::from_w_h(400.0, 200.0));
+    let sizes: Vec<_> = text.glyph_infos().iter().map(|g| g.font_size).collect();
+    assert_eq!(sizes, vec![30, 10, 30]);
+    // Fields not specified by the later span fall back to the earlier span.
+    let red = RED.into_lin_srgba();
+    assert!(text.glyph_infos().iter().all(|g| g.color == Some(red)));
+}
+
+#[cfg(feature = "notosans")]
+#[test]
+fn transforms_on_straight_path_follow_layout() {
+    let text = text::text("abc def")
+        .font_size(24)
+        .no_line_wrap()
+        .build(Rect::from_w_h(400.0, 100.0));
+    let bounds = text.bounding_rect_by_lines();
+    let path = path()
+        .move_to(pt2(0.0, 0.0))
+        .line_to(pt2(1000.0, 0.0))
+        .build();
+    let measure = path.measure(8);
+
+    let offset = 10.0;
+    let transforms = text.transforms_on_path(&measure, offset, Align::Start);
+    assert_eq!(transforms.len(), text.glyph_infos().len());
+    for (info, t) in text.glyph_infos().iter().zip(&transforms) {
+        let x = offset + info.position.x - bounds.left();
+        assert!((t.position.x - x).abs() < 1e-3);
+        assert!(t.position.y.abs() < 1e-3);
+        assert!(t.rotation.abs() < 1e-6);
+    }
+
+    // Aligned to the end, the text ends at the end of the path.
+    let transforms = text.transforms_on_path(&measure, offset, Align::End);
+    let first = transforms[0].position.x;
+    let info = &text.glyph_infos()[0];
+    assert!((first - (1000.0 - bounds.w() + info.position.x - bounds.left())).abs() < 1e-3);
+}
+
+#[cfg(feature = "notosans")]
+#[test]
+fn transforms_on_vertical_path_rotate_glyphs() {
+    let text = text::text("ab\ncd")
+        .font_size(24)
+        .no_line_wrap()
+        .build(Rect::from_w_h(400.0, 100.0));
+    let first_baseline = text.line_rects().next().unwrap().bottom();
+    let path = path()
+        .move_to(pt2(0.0, 0.0))
+        .line_to(pt2(0.0, 1000.0))
+        .build();
+    let transforms = text.transforms_on_path(&path.measure(8), 0.0, Align::Start);
+    for (info, t) in text.glyph_infos().iter().zip(&transforms) {
+        assert!((t.rotation - PI / 2.0).abs() < 1e-6);
+        // Following lines lie beneath the baseline, to the right of an upward path.
+        let rise = info.position.y - first_baseline;
+        assert!((t.position.x + rise).abs() < 1e-3);
+        if info.line == 1 {
+            assert!(t.position.x > 0.0);
+        }
+    }
+}
+
+#[cfg(feature = "notosans")]
+#[test]
+fn transforms_beyond_the_path_are_clamped_to_its_end() {
+    let text = text::text("abcdef")
+        .font_size(24)
+        .no_line_wrap()
+        .build(Rect::from_w_h(400.0, 100.0));
+    let path = path()
+        .move_to(pt2(0.0, 0.0))
+        .line_to(pt2(10.0, 0.0))
+        .build();
+    let transforms = text.transforms_on_path(&path.measure(8), 0.0, Align::Start);
+    let last = transforms.last().unwrap();
+    let info = text.glyph_infos().last().unwrap();
+    let half_advance = info.glyph.h_metrics().advance_width * 0.5;
+    assert!((last.position.x - (10.0 - half_advance)).abs() < 1e-3);
+}
+
+// Sample the texel of the bitmap containing the given point relative to the glyph's origin.
+#[cfg(feature = "notosans")]
+fn sample(bitmap: &sdf::Bitmap, p: Point2) -> [u8; 4] {
+    let i = (p.x - bitmap.bounds.left()).floor() as usize;
+    let j = (bitmap.bounds.top() - p.y).floor() as usize;
+    let start = (j * bitmap.width as usize + i) * 4;
+    let mut texel = [0; 4];
+    texel.copy_from_slice(&bitmap.data[start..start + 4]);
+    texel
+}
+
+#[cfg(feature = "notosans")]
+#[test]
+fn distance_fields_are_positive_inside_glyphs() {
+    let font = text::font::default_notosans();
+    let scale = Scale::uniform(sdf::Cache::DEFAULT_SCALE);
+    let range = sdf::Cache::DEFAULT_RANGE;
+    for &field in &[sdf::Field::Sdf, sdf::Field::Msdf] {
+        let median = |t: [u8; 4]| sdf::median(t[0] as f32, t[1] as f32, t[2] as f32);
+
+        // The centre of the stem of an "I" lies inside the glyph.
+        let bitmap = sdf::generate(&font.glyph('I').scaled(scale), field, range).unwrap();
+        let texel = sample(&bitmap, bitmap.bounds.xy());
+        assert!(median(texel) > 128.0);
+        assert!(texel[3] > 128);
+        // Texels beyond the range of the outline are entirely outside.
+        assert_eq!(&bitmap.data[..4], &[0; 4]);
+
+        // The centre of an "o" lies within its counter, outside the glyph.
+        let bitmap = sdf::generate(&font.glyph('o').scaled(scale), field, range).unwrap();
+        let texel = sample(&bitmap, bitmap.bounds.xy());
+        assert!(median(texel) < 128.0);
+        assert!(texel[3] < 128);
+    }
+    assert!(sdf::generate(&font.glyph(' ').scaled(scale), sdf::Field::Sdf, range).is_none());
+}
+
+#[cfg(feature = "notosans")]
+#[test]
+fn distance_field_cache_packs_and_evicts_glyphs() {
+    let font = text::font::default_notosans();
+    let glyph = |ch| font.glyph(ch).scaled(Scale::uniform(12.0));
+    let (scale, range) = (sdf::Cache::DEFAULT_SCALE, sdf::Cache::DEFAULT_RANGE);
+    let bitmap = |ch| {
+        let glyph = font.glyph(ch).scaled(Scale::uniform(scale));
+        sdf::generate(&glyph, sdf::Field::Sdf, range).unwrap()
+    };
+    let (i, l) = (bitmap('I'), bitmap('l'));
+
+    // Room for exactly "I" and "l" side by side, each followed by a gap of one texel.
+    let size = [i.width + l.width + 2, std::cmp::max(i.height, l.height) + 1];
+    let mut cache = sdf::Cache::new(size, sdf::Field::Sdf, scale, range);
+    cache.queue_glyph(0, glyph('I'));
+    cache.queue_glyph(0, glyph('l'));
+    cache.queue_glyph(0, glyph(' '));
+    assert!(cache.cache_queued().unwrap());
+    let i_entry = cache.rect_for(0, glyph('I').id()).unwrap();
+    let l_entry = cache.rect_for(0, glyph('l').id()).unwrap();
+    assert!(cache.rect_for(0, glyph(' ').id()).is_none());
+    assert_eq!(i_entry.bounds, i.bounds);
+    assert_eq!(l_entry.bounds, l.bounds);
+    assert_eq!(i_entry.uv.min.x, 0.0);
+    assert!(i_entry.uv.max.x <= l_entry.uv.min.x);
+    // The first row of the atlas begins with the first row of the "I".
+    let row_len = i.width as usize * 4;
+    assert_eq!(&cache.pixels()[..row_len], &i.data[..row_len]);
+
+    // Cached glyphs are not generated again.
+    cache.queue_glyph(0, glyph('I'));
+    assert!(!cache.cache_queued().unwrap());
+
+    // The same glyph of another font does not fit, so all other glyphs are evicted.
+    cache.queue_glyph(1, glyph('I'));
+    assert!(cache.cache_queued().unwrap());
+    assert!(cache.rect_for(0, glyph('I').id()).is_none());
+    assert!(cache.rect_for(0, glyph('l').id()).is_none());
+    let entry = cache.rect_for(1, glyph('I').id()).unwrap();
+    assert_eq!((entry.uv.min.x, entry.uv.min.y), (0.0, 0.0));
+
+    // Glyphs that cannot fit within an empty cache are an error.
+    let mut cache = sdf::Cache::new([8, 8], sdf::Field::Sdf, scale, range);
+    cache.queue_glyph(0, glyph('I'));
+    assert!(cache.cache_queued().is_err());
+}
@@ -0,0 +1,112 @@
+/// The frequency scale along which the spectrum is divided into bands.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Scale {
+    /// Bands are evenly spaced in Hz.
+    Linear,
+    /// Bands are evenly spaced in octaves, giving the low end far more resolution.
+    Log,
+    /// Bands are evenly spaced along the mel scale, approximating human pitch perception.
+    Mel,
+}
+
+// A set of overlapping triangular filters, one per band.
+pub(crate) struct FilterBank {
+    filters: Vec<Filter>,
+    centers: Vec<f32>,
+}
+
+struct Filter {
+    // The index of the first bin weighted by the filter.
+    start: usize,
+    // The normalised weight of each bin from `start`.
+    weights: Vec<f32>,
+}
+
+impl Scale {
+    fn to_scale(self, hz: f32) -> f32 {
+        match self {
+            Scale::Linear => hz,
+            Scale::Log => hz.ln(),
+            Scale::Mel => 2595.0 * (1.0 + hz / 700.0).log10(),
+        }
+    }
+
+    fn to_hz(self, value: f32) -> f32 {
+        match self {
+            Scale::Linear => value,
+            Scale::Log => value.exp(),
+            Scale::Mel => 700.0 * (10f32.powf(value / 2595.0) - 1.0),
+        }
+    }
+}
+
+impl FilterBank {
+    /// Create `bands` filters evenly spaced along `scale` between `min_hz` and `max_hz`.
+    ///
+    /// The bank applies to a spectrum of `fft_size / 2 + 1` bins at the given sample rate.
+    pub fn new(
+        scale: Scale,
+        bands: usize,
+        min_hz: f32,
+        max_hz: f32,
+        fft_size: usize,
+        sample_rate: u32,
+    ) -> Self {
+        let n_bins = fft_size / 2 + 1;
+        let bin_hz = sample_rate as f32 / fft_size as f32;
+        let nyquist = sample_rate as f32 / 2.0;
+        // The log scale cannot start at 0Hz.
+        let min_hz = min_hz.max(1.0).min(nyquist);
+        let max_hz = max_hz.max(min_hz).min(nyquist);
+        let (lo, hi) = (scale.to_scale(min_hz), scale.to_scale(max_hz));
+        let edge = |i: usize| scale.to_hz(lo + (hi - lo) * i as f32 / (bands + 1) as f32);
+
+        let mut filters = Vec::with_capacity(bands);
+        let mut centers = Vec::with_capacity(bands);
+        for b in 0..bands {
+            let (lo, center, hi) = (edge(b), edge(b + 1), edge(b + 2));
+            let start = ((lo / bin_hz).ceil() as usize).min(n_bins);
+            let end = ((hi / bin_hz).floor() as usize + 1).min(n_bins).max(start);
+            let mut weights: Vec<f32> = (start..end)
+                .map(|k| {
+                    let f = k as f32 * bin_hz;
+                    if f < center {
+                        (f - lo) / (center - lo)
+                    } else {
+                        (hi - f) / (hi - center)
+                    }
+                })
+                .map(|w| w.max(0.0))
+                .collect();
+            let mut start = start;
+            // Filters narrower than a bin interpolate the spectrum at their centre instead.
+            if weights.iter().sum::<f32>() <= f32::EPSILON {
+                let pos = center / bin_hz;
+                let k = (pos.floor() as usize).min(n_bins.saturating_sub(2));
+                let frac = (pos - k as f32).clamp(0.0, 1.0);
+                start = k;
+                weights = vec![1.0 - frac, frac];
+            }
+            let sum: f32 = weights.iter().sum();
+            for w in &mut weights {
+                *w /= sum;
+            }
+            filters.push(Filter { start, weights });
+            centers.push(center);
+        }
+        FilterBank { filters, centers }
+    }
+
+    /// The centre frequency of each band in Hz.
+    pub fn centers(&self) -> &[f32] {
+        &self.centers
+    }
+
+    /// Write the weighted average magnitude of each band to `bands`.
+    pub fn apply(&self, spectrum: &[f32], bands: &mut [f32]) {
+        for (band, filter) in bands.iter_mut().zip(&self.filters) {
+            let bins = &spectrum[filter.start..];
+            *band = filter.weights.iter().zip(bins).map(|(w, m)| w * m).sum();
+        }
+    }
+}
@@ -0,0 +1,550 @@
+//! Real-time audio analysis for audio-reactive visuals.
+//!
+//! - [**Analyzer**](./struct.Analyzer.html) - lives on the audio thread, typically within the
+//!   model of an input stream, and analyses each `Buffer` passed to it.
+//! - [**Reader**](./struct.Reader.html) - lives on the main thread and provides the most recent
+//!   analysis [**Features**](./struct.Features.html) without blocking the audio thread.
+//!
+//! Each analysis frame covers a window of `fft_size` samples of the mono downmix, advancing by
+//! `hop_size` samples between frames. The features of each frame include the FFT magnitude
+//! spectrum and a set of bands along a linear, log or mel scale, RMS and peak amplitude, the
+//! spectral centroid, onsets and beats along with an estimated tempo, and the fundamental
+//! frequency.
+//!
+//! ```no_run
+//! use nannou_audio::analysis::{self, Analyzer, Scale};
+//! use nannou_audio::Buffer;
+//!
+//! fn capture(analyzer: &mut Analyzer, buffer: &Buffer) {
+//!     analyzer.process(buffer);
+//! }
+//!
+//! let (analyzer, mut reader) = analysis::Builder::new().scale(Scale::Mel).bands(40).build();
+//! let host = nannou_audio::Host::new();
+//! let stream = host
+//!     .new_input_stream(analyzer)
+//!     .capture(capture)
+//!     .build()
+//!     .unwrap();
+//!
+//! // Later, on the main thread.
+//! let features = reader.update();
+//! println!("rms: {}, pitch: {:?}", features.rms, features.pitch);
+//! ```
+//!
+//! The bands of each frame may also be normalised into the columns of an ISF `AudioFft` input via
+//! `Features::columns`.
+
+use crate::Buffer;
+use dasp_sample::{Sample, ToSample};
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use std::sync::Arc;
+
+use self::bands::FilterBank;
+pub use self::bands::Scale;
+use self::onset::{BeatTracker, OnsetDetector};
+use self::pitch::Yin;
+
+mod bands;
+mod onset;
+mod pitch;
+mod triple;
+
+/// Describes the analysis performed by an `Analyzer`.
+#[derive(Clone, Debug)]
+pub struct Builder {
+    fft_size: usize,
+    hop_size: usize,
+    scale: Scale,
+    bands: usize,
+    min_hz: f32,
+    max_hz: f32,
+    smoothing: f32,
+    onset_sensitivity: f32,
+    pitch_min_hz: f32,
+    pitch_max_hz: f32,
+    pitch_threshold: f32,
+    sample_rate: Option<u32>,
+}
+
+/// Analyses audio on the audio thread, publishing the resulting `Features` to the `Reader`.
+///
+/// All analysis state is allocated up front, with the exception of the state that depends on the
+/// sample rate. This is allocated upon processing the first buffer unless the sample rate is
+/// specified via `Builder::sample_rate`.
+pub struct Analyzer {
+    config: Builder,
+    sample_rate: u32,
+    fft: Arc<dyn Fft<f32>>,
+    fft_buffer: Vec<Complex<f32>>,
+    fft_scratch: Vec<Complex<f32>>,
+    window: Vec<f32>,
+    window_sum: f32,
+    // The most recent `fft_size` mono samples, written circularly.
+    ring: Vec<f32>,
+    ring_pos: usize,
+    since_hop: usize,
+    frames_processed: u64,
+    // The current analysis window in chronological order.
+    frame: Vec<f32>,
+    spectrum: Vec<f32>,
+    bands: Vec<f32>,
+    smoothed_bands: Vec<f32>,
+    filter_bank: Option<FilterBank>,
+    onset: Option<OnsetDetector>,
+    beat: Option<BeatTracker>,
+    yin: Yin,
+    index: u64,
+    onset_count: u64,
+    beat_count: u64,
+    writer: triple::Writer<Features>,
+}
+
+/// Provides the latest `Features` published by the `Analyzer` on the main thread.
+pub struct Reader {
+    reader: triple::Reader<Features>,
+    onsets_seen: u64,
+    beats_seen: u64,
+}
+
+/// The features of a single analysis frame.
+#[derive(Clone, Debug, Default)]
+pub struct Features {
+    /// The index of the analysis frame.
+    pub index: u64,
+    /// The time at the end of the analysis window in seconds since the first processed sample.
+    pub time: f64,
+    /// The sample rate of the analysed audio.
+    pub sample_rate: u32,
+    /// The root mean square amplitude of the window.
+    pub rms: f32,
+    /// The peak absolute amplitude of the window.
+    pub peak: f32,
+    /// The magnitude of each FFT bin from 0Hz to the Nyquist frequency, scaled such that a
+    /// full-scale sine wave has a magnitude of roughly `1.0`.
+    pub spectrum: Vec<f32>,
+    /// The smoothed magnitude of each band along the configured scale.
+    pub bands: Vec<f32>,
+    /// The centre frequency of each band in Hz.
+    pub band_frequencies: Vec<f32>,
+    /// The spectral centroid in Hz, a measure of the "brightness" of the sound.
+    pub centroid: f32,
+    /// The spectral flux, a measure of how quickly the spectrum is changing.
+    pub flux: f32,
+    /// Whether or not an onset was detected within this frame.
+    pub onset: bool,
+    /// The total number of onsets detected so far.
+    pub onset_count: u64,
+    /// Whether or not this frame falls on a beat.
+    pub beat: bool,
+    /// The total number of beats so far.
+    pub beat_count: u64,
+    /// The estimated tempo in beats per minute, if a steady pulse has been detected.
+    pub bpm: Option<f32>,
+    /// The estimated fundamental frequency in Hz, if the audio is pitched.
+    pub pitch: Option<f32>,
+    /// The confidence of the pitch estimate in the range `0.0..=1.0`.
+    pub pitch_confidence: f32,
+}
+
+impl Builder {
+    /// The default number of samples per analysis window.
+    pub const DEFAULT_FFT_SIZE: usize = 2_048;
+    /// The default number of samples between the start of consecutive analysis windows.
+    pub const DEFAULT_HOP_SIZE: usize = 512;
+    /// The default number of bands.
+    pub const DEFAULT_BANDS: usize = 32;
+
+    /// Begin building an analyzer with the default configuration.
+    pub fn new() -> Self {
+        Builder {
+            fft_size: Self::DEFAULT_FFT_SIZE,
+            hop_size: Self::DEFAULT_HOP_SIZE,
+            scale: Scale::Log,
+            bands: Self::DEFAULT_BANDS,
+            min_hz: 20.0,
+            max_hz: 20_000.0,
+            smoothing: 0.5,
+            onset_sensitivity: 1.5,
+            pitch_min_hz: 50.0,
+            pitch_max_hz: 2_000.0,
+            pitch_threshold: 0.15,
+            sample_rate: None,
+        }
+    }
+
+    /// The number of samples per analysis window.
+    ///
+    /// Larger windows give finer frequency resolution and lower pitch detection at the cost of
+    /// time resolution.
+    ///
+    /// **Panics** if `fft_size` is not a power of two of at least `64`.
+    pub fn fft_size(mut self, fft_size: usize) -> Self {
+        assert!(fft_size >= 64 && fft_size.is_power_of_two());
+        self.fft_size = fft_size;
+        self
+    }
+
+    /// The number of samples between the start of consecutive analysis windows.
+    ///
+    /// **Panics** if `hop_size` is `0`.
+    pub fn hop_size(mut self, hop_size: usize) -> Self {
+        assert!(hop_size > 0);
+        self.hop_size = hop_size;
+        self
+    }
+
+    /// The scale along which bands are distributed. The default is `Scale::Log`.
+    pub fn scale(mut self, scale: Scale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// The number of bands.
+    pub fn bands(mut self, bands: usize) -> Self {
+        self.bands = bands;
+        self
+    }
+
+    /// The range of frequencies covered by the bands in Hz. The default is 20Hz to 20KHz.
+    pub fn frequency_range(mut self, min_hz: f32, max_hz: f32) -> Self {
+        assert!(min_hz < max_hz);
+        self.min_hz = min_hz;
+        self.max_hz = max_hz;
+        self
+    }
+
+    /// The amount of smoothing applied to the bands between frames in the range `0.0..1.0`.
+    ///
+    /// `0.0` applies no smoothing, while values approaching `1.0` respond increasingly slowly.
+    pub fn smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing.clamp(0.0, 0.999);
+        self
+    }
+
+    /// The factor by which the spectral flux must exceed its recent average to be considered an
+    /// onset. Lower values detect more onsets. The default is `1.5`.
+    pub fn onset_sensitivity(mut self, sensitivity: f32) -> Self {
+        self.onset_sensitivity = sensitivity;
+        self
+    }
+
+    /// The range of fundamental frequencies considered by pitch detection in Hz.
+    ///
+    /// The lowest detectable frequency is also limited by the FFT size, as at least two periods
+    /// must fit within the window.
+    pub fn pitch_range(mut self, min_hz: f32, max_hz: f32) -> Self {
+        assert!(0.0 < min_hz && min_hz < max_hz);
+        self.pitch_min_hz = min_hz;
+        self.pitch_max_hz = max_hz;
+        self
+    }
+
+    /// The YIN threshold used by pitch detection in the range `0.0..1.0`.
+    ///
+    /// Lower values reject all but clearly pitched audio. The default is `0.15`.
+    pub fn pitch_threshold(mut self, threshold: f32) -> Self {
+        self.pitch_threshold = threshold;
+        self
+    }
+
+    /// The sample rate of the audio that will be analysed.
+    ///
+    /// Specifying the sample rate allows all analysis state to be allocated up front rather than
+    /// on the audio thread upon processing the first buffer.
+    pub fn sample_rate(mut self, sample_rate: u32) -> Self {
+        assert!(sample_rate > 0);
+        self.sample_rate = Some(sample_rate);
+        self
+    }
+
+    /// Build the `Analyzer` along with the `Reader` used to access its results.
+    pub fn build(self) -> (Analyzer, Reader) {
+        let n = self.fft_size;
+        let n_bins = n / 2 + 1;
+        let mut planner = FftPlanner::new();
+        let fft = planner.plan_fft_forward(n);
+        let fft_scratch = vec![Complex::default(); fft.get_inplace_scratch_len()];
+        let yin = Yin::new(&mut planner, n);
+
+        // A periodic Hann window.
+        let window: Vec<f32> = (0..n)
+            .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / n as f32).cos())
+            .collect();
+        let window_sum = window.iter().sum();
+
+        let features = Features {
+            spectrum: vec![0.0; n_bins],
+            bands: vec![0.0; self.bands],
+            band_frequencies: vec![0.0; self.bands],
+            ..Default::default()
+        };
+        let (writer, reader) = triple::new(features);
+
+        let mut analyzer = Analyzer {
+            sample_rate: 0,
+            fft,
+            fft_buffer: vec![Complex::default(); n],
+            fft_scratch,
+            window,
+            window_sum,
+            ring: vec![0.0; n],
+            ring_pos: 0,
+            since_hop: 0,
+            frames_processed: 0,
+            frame: vec![0.0; n],
+            spectrum: vec![0.0; n_bins],
+            bands: vec![0.0; self.bands],
+            smoothed_bands: vec![0.0; self.bands],
+            filter_bank: None,
+            onset: None,
+            beat: None,
+            yin,
+            index: 0,
+            onset_count: 0,
+            beat_count: 0,
+            writer,
+            config: self,
+        };
+        if let Some(sample_rate) = analyzer.config.sample_rate {
+            analyzer.prepare(sample_rate);
+        }
+        let reader = Reader {
+            reader,
+            onsets_seen: 0,
+            beats_seen: 0,
+        };
+        (analyzer, reader)
+    }
+}
+
+impl Analyzer {
+    /// Analyse the given buffer.
+    pub fn process<S>(&mut self, buffer: &Buffer<S>)
+    where
+        S: Sample + ToSample<f32>,
+    {
+        self.process_interleaved(buffer, buffer.channels(), buffer.sample_rate());
+    }
+
+    /// Analyse the given interleaved samples.
+    ///
+    /// Multi-channel audio is mixed down to mono. A new frame of `Features` is published each
+    /// time `hop_size` samples have been processed.
+    pub fn process_interleaved<S>(&mut self, samples: &[S], channels: usize, sample_rate: u32)
+    where
+        S: Sample + ToSample<f32>,
+    {
+        if channels == 0 || sample_rate == 0 {
+            return;
+        }
+        if sample_rate != self.sample_rate {
+            self.prepare(sample_rate);
+        }
+        let scale = 1.0 / channels as f32;
+        for frame in samples.chunks_exact(channels) {
+            let mono: f32 = frame.iter().map(|s| s.to_sample::<f32>()).sum::<f32>() * scale;
+            self.ring[self.ring_pos] = mono;
+            self.ring_pos = (self.ring_pos + 1) % self.ring.len();
+            self.frames_processed += 1;
+            self.since_hop += 1;
+            if self.since_hop >= self.config.hop_size {
+                self.since_hop = 0;
+                self.analyze();
+            }
+        }
+    }
+
+    // (Re)allocate all state that depends on the sample rate.
+    fn prepare(&mut self, sample_rate: u32) {
+        let c = &self.config;
+        let frames_per_sec = sample_rate as f32 / c.hop_size as f32;
+        let n_bins = self.spectrum.len();
+        self.filter_bank = Some(FilterBank::new(
+            c.scale,
+            c.bands,
+            c.min_hz,
+            c.max_hz,
+            c.fft_size,
+            sample_rate,
+        ));
+        self.onset = Some(OnsetDetector::new(
+            c.onset_sensitivity,
+            n_bins,
+            frames_per_sec,
+        ));
+        self.beat = Some(BeatTracker::new(frames_per_sec));
+        self.sample_rate = sample_rate;
+    }
+
+    // Analyse the current window and publish the resulting features.
+    fn analyze(&mut self) {
+        let n = self.ring.len();
+        let (old, new) = self.ring.split_at(self.ring_pos);
+        self.frame[..n - self.ring_pos].copy_from_slice(new);
+        self.frame[n - self.ring_pos..].copy_from_slice(old);
+
+        // Amplitude.
+        let mut sum_sq = 0.0;
+        let mut peak = 0.0f32;
+        for &s in &self.frame {
+            sum_sq += s * s;
+            peak = peak.max(s.abs());
+        }
+        let rms = (sum_sq / n as f32).sqrt();
+
+        // Spectrum.
+        for ((c, &s), &w) in self
+            .fft_buffer
+            .iter_mut()
+            .zip(&self.frame)
+            .zip(&self.window)
+        {
+            *c = Complex::new(s * w, 0.0);
+        }
+        self.fft
+            .process_with_scratch(&mut self.fft_buffer, &mut self.fft_scratch);
+        let norm = 2.0 / self.window_sum;
+        for (m, c) in self.spectrum.iter_mut().zip(&self.fft_buffer) {
+            *m = c.norm() * norm;
+        }
+
+        // Spectral centroid.
+        let bin_hz = self.sample_rate as f32 / n as f32;
+        let (mut weighted, mut total) = (0.0, 0.0);
+        for (k, &m) in self.spectrum.iter().enumerate() {
+            weighted += k as f32 * bin_hz * m;
+            total += m;
+        }
+        let centroid = if total > 0.0 { weighted / total } else { 0.0 };
+
+        // Bands.
+        let filter_bank = self.filter_bank.as_ref().expect("analyzer not prepared");
+        filter_bank.apply(&self.spectrum, &mut self.bands);
+        let smoothing = self.config.smoothing;
+        for (s, &b) in self.smoothed_bands.iter_mut().zip(&self.bands) {
+            *s = *s * smoothing + b * (1.0 - smoothing);
+        }
+
+        // Onsets and beats.
+        let (flux, onset) = self.onset.as_mut().unwrap().process(&self.spectrum);
+        let beat_tracker = self.beat.as_mut().unwrap();
+        let beat = beat_tracker.process(flux, onset);
+        let bpm = beat_tracker.bpm();
+        self.onset_count += onset as u64;
+        self.beat_count += beat as u64;
+
+        // Pitch, ignoring near-silence.
+        let c = &self.config;
+        let pitch = if rms > 1e-4 {
+            self.yin.estimate(
+                &self.frame,
+                self.sample_rate,
+                c.pitch_min_hz,
+                c.pitch_max_hz,
+                c.pitch_threshold,
+            )
+        } else {
+            None
+        };
+
+        // Publish.
+        let f = self.writer.get_mut();
+        f.index = self.index;
+        f.time = self.frames_processed as f64 / self.sample_rate as f64;
+        f.sample_rate = self.sample_rate;
+        f.rms = rms;
+        f.peak = peak;
+        f.spectrum.copy_from_slice(&self.spectrum);
+        f.bands.copy_from_slice(&self.smoothed_bands);
+        f.band_frequencies.copy_from_slice(filter_bank.centers());
+        f.centroid = centroid;
+        f.flux = flux;
+        f.onset = onset;
+        f.onset_count = self.onset_count;
+        f.beat = beat;
+        f.beat_count = self.beat_count;
+        f.bpm = bpm;
+        f.pitch = pitch.map(|(hz, _)| hz);
+        f.pitch_confidence = pitch.map(|(_, confidence)| confidence).unwrap_or(0.0);
+        self.writer.publish();
+        self.index += 1;
+    }
+}
+
+impl Reader {
+    /// Acquire and return the most recently published features.
+    pub fn update(&mut self) -> &Features {
+        self.reader.update();
+        self.reader.get()
+    }
+
+    /// The features acquired by the last call to `update`.
+    pub fn features(&self) -> &Features {
+        self.reader.get()
+    }
+
+    /// Whether or not any onsets have occurred since the last call to `take_onset`.
+    ///
+    /// Unlike `Features::onset`, this does not miss onsets that occur in frames that are never
+    /// acquired due to the main thread updating less frequently than the analyzer.
+    pub fn take_onset(&mut self) -> bool {
+        let count = self.update().onset_count;
+        let onset = count > self.onsets_seen;
+        self.onsets_seen = count;
+        onset
+    }
+
+    /// Whether or not any beats have occurred since the last call to `take_beat`.
+    pub fn take_beat(&mut self) -> bool {
+        let count = self.update().beat_count;
+        let beat = count > self.beats_seen;
+        self.beats_seen = count;
+        beat
+    }
+}
+
+impl Features {
+    /// The default decibel floor used by `columns`.
+    pub const DEFAULT_MIN_DB: f32 = -80.0;
+
+    /// Write the bands to `columns` normalised to the range `0.0..=1.0` along a decibel scale,
+    /// resampling them to the number of columns.
+    ///
+    /// This is suitable for the `columns` of an ISF `AudioFft` input.
+    pub fn columns(&self, columns: &mut [f32]) {
+        self.columns_with_floor(columns, Self::DEFAULT_MIN_DB);
+    }
+
+    /// The same as `columns` but with the given decibel floor, which maps to `0.0`.
+    pub fn columns_with_floor(&self, columns: &mut [f32], min_db: f32) {
+        let bands = &self.bands;
+        if bands.is_empty() {
+            columns.iter_mut().for_each(|c| *c = 0.0);
+            return;
+        }
+        let normalise = |m: f32| {
+            let db = 20.0 * m.max(1e-10).log10();
+            (1.0 - db / min_db).clamp(0.0, 1.0)
+        };
+        let last = bands.len() - 1;
+        let step = last as f32 / columns.len().saturating_sub(1).max(1) as f32;
+        for (i, column) in columns.iter_mut().enumerate() {
+            let pos = i as f32 * step;
+            let j = (pos as usize).min(last);
+            let frac = pos - j as f32;
+            let m = match bands.get(j + 1) {
+                Some(&next) => bands[j] + (next - bands[j]) * frac,
+                None => bands[j],
+            };
+            *column = normalise(m);
+        }
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
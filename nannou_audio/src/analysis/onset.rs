@@ -0,0 +1,179 @@
+// Onset detection via spectral flux with an adaptive threshold, along with a simple tempo and
+// beat tracker driven by the autocorrelation of the flux envelope.
+
+// Compression applied to magnitudes before measuring flux, emphasising quieter partials.
+const COMPRESSION: f32 = 100.0;
+// The duration of flux history used for the adaptive threshold.
+const THRESHOLD_SECS: f32 = 0.5;
+// The minimum duration between onsets.
+const REFRACTORY_SECS: f32 = 0.06;
+// The minimum flux required for an onset, avoiding onsets in near-silence.
+const MIN_FLUX: f32 = 1e-3;
+// The duration of flux history used to estimate the tempo.
+const TEMPO_SECS: f32 = 6.0;
+// The range of tempos considered.
+const MIN_BPM: f32 = 60.0;
+const MAX_BPM: f32 = 200.0;
+// The normalised autocorrelation required before a tempo is reported.
+const MIN_TEMPO_CONFIDENCE: f32 = 0.1;
+// The fraction of a beat period within which an onset snaps the beat phase.
+const BEAT_TOLERANCE: f64 = 0.2;
+
+pub(crate) struct OnsetDetector {
+    sensitivity: f32,
+    prev: Vec<f32>,
+    history: Vec<f32>,
+    history_pos: usize,
+    refractory_frames: u64,
+    frames_since_onset: u64,
+}
+
+pub(crate) struct BeatTracker {
+    // The flux envelope in chronological order, oldest first.
+    envelope: Vec<f32>,
+    centered: Vec<f32>,
+    frames_per_sec: f32,
+    frame: u64,
+    period: Option<f64>,
+    last_beat: f64,
+}
+
+impl OnsetDetector {
+    pub fn new(sensitivity: f32, n_bins: usize, frames_per_sec: f32) -> Self {
+        let history_len = ((THRESHOLD_SECS * frames_per_sec) as usize).max(1);
+        OnsetDetector {
+            sensitivity,
+            prev: vec![0.0; n_bins],
+            history: vec![0.0; history_len],
+            history_pos: 0,
+            refractory_frames: (REFRACTORY_SECS * frames_per_sec).ceil() as u64,
+            frames_since_onset: u64::MAX,
+        }
+    }
+
+    /// Measure the flux of the given magnitude spectrum, returning the flux and whether or not it
+    /// constitutes an onset.
+    pub fn process(&mut self, spectrum: &[f32]) -> (f32, bool) {
+        let mut flux = 0.0;
+        for (prev, &m) in self.prev.iter_mut().zip(spectrum) {
+            let m = (1.0 + COMPRESSION * m).ln();
+            flux += (m - *prev).max(0.0);
+            *prev = m;
+        }
+        flux /= spectrum.len().max(1) as f32;
+
+        let mean = self.history.iter().sum::<f32>() / self.history.len() as f32;
+        let threshold = (mean * self.sensitivity).max(MIN_FLUX);
+        self.history[self.history_pos] = flux;
+        self.history_pos = (self.history_pos + 1) % self.history.len();
+
+        self.frames_since_onset = self.frames_since_onset.saturating_add(1);
+        let onset = flux > threshold && self.frames_since_onset > self.refractory_frames;
+        if onset {
+            self.frames_since_onset = 0;
+        }
+        (flux, onset)
+    }
+}
+
+impl BeatTracker {
+    pub fn new(frames_per_sec: f32) -> Self {
+        let len = ((TEMPO_SECS * frames_per_sec) as usize).max(1);
+        BeatTracker {
+            envelope: vec![0.0; len],
+            centered: vec![0.0; len],
+            frames_per_sec,
+            frame: 0,
+            period: None,
+            last_beat: 0.0,
+        }
+    }
+
+    /// The estimated tempo in beats per minute, if any.
+    pub fn bpm(&self) -> Option<f32> {
+        self.period
+            .map(|period| 60.0 * self.frames_per_sec / period as f32)
+    }
+
+    /// Update the tracker with the flux of the latest frame, returning whether or not the frame
+    /// falls on a beat.
+    pub fn process(&mut self, flux: f32, onset: bool) -> bool {
+        self.envelope.rotate_left(1);
+        *self.envelope.last_mut().unwrap() = flux;
+        self.frame += 1;
+        self.estimate_period();
+
+        let period = match self.period {
+            None => return false,
+            Some(period) => period,
+        };
+        let t = self.frame as f64;
+        let next = self.last_beat + period;
+        let tolerance = period * BEAT_TOLERANCE;
+
+        // A slightly early onset is taken as the beat itself.
+        if onset && t <= next && next - t <= tolerance {
+            self.last_beat = t;
+            return true;
+        }
+        // Otherwise beats occur as predicted by the tempo.
+        if t >= next {
+            self.last_beat = next.max(t - tolerance);
+            return true;
+        }
+        // A slightly late onset re-aligns the phase of subsequent beats.
+        if onset && t - self.last_beat <= tolerance {
+            self.last_beat = t;
+        }
+        false
+    }
+
+    // Estimate the beat period in frames via the autocorrelation of the flux envelope.
+    fn estimate_period(&mut self) {
+        let mean = self.envelope.iter().sum::<f32>() / self.envelope.len() as f32;
+        for (c, &e) in self.centered.iter_mut().zip(&self.envelope) {
+            *c = e - mean;
+        }
+        let acf = |lag: usize| -> f32 {
+            let x = &self.centered;
+            x[..x.len() - lag]
+                .iter()
+                .zip(&x[lag..])
+                .map(|(a, b)| a * b)
+                .sum()
+        };
+        let energy = acf(0);
+        if energy <= f32::EPSILON {
+            self.period = None;
+            return;
+        }
+        let min_lag = (60.0 * self.frames_per_sec / MAX_BPM).floor().max(1.0) as usize;
+        let max_lag = (60.0 * self.frames_per_sec / MIN_BPM).ceil() as usize;
+        let max_lag = max_lag.min(self.centered.len() / 2);
+        if min_lag + 1 >= max_lag {
+            self.period = None;
+            return;
+        }
+        let (mut best_lag, mut best) = (0, f32::MIN);
+        for lag in min_lag..=max_lag {
+            let r = acf(lag);
+            if r > best {
+                best_lag = lag;
+                best = r;
+            }
+        }
+        if best / energy < MIN_TEMPO_CONFIDENCE {
+            self.period = None;
+            return;
+        }
+        // Refine the lag via parabolic interpolation.
+        let (l, r) = (acf(best_lag - 1), acf(best_lag + 1));
+        let denom = l - 2.0 * best + r;
+        let offset = if denom.abs() > f32::EPSILON {
+            (0.5 * (l - r) / denom).clamp(-0.5, 0.5)
+        } else {
+            0.0
+        };
+        self.period = Some(best_lag as f64 + offset as f64);
+    }
+}
@@ -0,0 +1,121 @@
+// Fundamental frequency estimation via the YIN algorithm.
+//
+// The difference function is computed from an FFT-based cross-correlation so that the cost per
+// frame is O(n log n) rather than O(n^2).
+
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use std::sync::Arc;
+
+pub(crate) struct Yin {
+    forward: Arc<dyn Fft<f32>>,
+    inverse: Arc<dyn Fft<f32>>,
+    a: Vec<Complex<f32>>,
+    b: Vec<Complex<f32>>,
+    scratch: Vec<Complex<f32>>,
+    // The cumulative energy of the frame, where `energy[i]` is the sum of squares of `x[..i]`.
+    energy: Vec<f32>,
+    // The cumulative mean normalised difference function.
+    cmnd: Vec<f32>,
+}
+
+impl Yin {
+    pub fn new(planner: &mut FftPlanner<f32>, len: usize) -> Self {
+        let forward = planner.plan_fft_forward(len);
+        let inverse = planner.plan_fft_inverse(len);
+        let scratch_len = std::cmp::max(
+            forward.get_inplace_scratch_len(),
+            inverse.get_inplace_scratch_len(),
+        );
+        Yin {
+            forward,
+            inverse,
+            a: vec![Complex::default(); len],
+            b: vec![Complex::default(); len],
+            scratch: vec![Complex::default(); scratch_len],
+            energy: vec![0.0; len + 1],
+            cmnd: vec![0.0; len / 2],
+        }
+    }
+
+    /// Estimate the fundamental frequency of `frame` between `min_hz` and `max_hz`.
+    ///
+    /// Returns the frequency along with a confidence in the range `0.0..=1.0`. No estimate is
+    /// returned if the confidence does not exceed `1.0 - threshold`.
+    pub fn estimate(
+        &mut self,
+        frame: &[f32],
+        sample_rate: u32,
+        min_hz: f32,
+        max_hz: f32,
+        threshold: f32,
+    ) -> Option<(f32, f32)> {
+        let n = frame.len();
+        let w = n / 2;
+        let sr = sample_rate as f32;
+        let tau_min = ((sr / max_hz).floor() as usize).max(2);
+        let tau_max = ((sr / min_hz).ceil() as usize).min(w - 1);
+        if tau_min + 2 >= tau_max {
+            return None;
+        }
+
+        // Cross-correlate the first half of the frame with the whole frame.
+        for (i, &x) in frame.iter().enumerate() {
+            self.a[i] = Complex::new(if i < w { x } else { 0.0 }, 0.0);
+            self.b[i] = Complex::new(x, 0.0);
+        }
+        self.forward
+            .process_with_scratch(&mut self.a, &mut self.scratch);
+        self.forward
+            .process_with_scratch(&mut self.b, &mut self.scratch);
+        for (a, b) in self.a.iter_mut().zip(&self.b) {
+            *a = a.conj() * b;
+        }
+        self.inverse
+            .process_with_scratch(&mut self.a, &mut self.scratch);
+        let scale = 1.0 / n as f32;
+
+        self.energy[0] = 0.0;
+        for (i, &x) in frame.iter().enumerate() {
+            self.energy[i + 1] = self.energy[i] + x * x;
+        }
+        let e0 = self.energy[w];
+
+        // The cumulative mean normalised difference.
+        self.cmnd[0] = 1.0;
+        let mut sum = 0.0;
+        for tau in 1..=tau_max {
+            let e = self.energy[tau + w] - self.energy[tau];
+            let d = (e0 + e - 2.0 * self.a[tau].re * scale).max(0.0);
+            sum += d;
+            self.cmnd[tau] = if sum > 0.0 { d * tau as f32 / sum } else { 1.0 };
+        }
+
+        // Take the first dip below the threshold, as later dips tend to be sub-harmonics.
+        let mut best = None;
+        let mut tau = tau_min;
+        while tau < tau_max {
+            if self.cmnd[tau] < threshold {
+                while tau + 1 < tau_max && self.cmnd[tau + 1] < self.cmnd[tau] {
+                    tau += 1;
+                }
+                best = Some(tau);
+                break;
+            }
+            tau += 1;
+        }
+        let tau = best?;
+
+        // Refine the period via parabolic interpolation.
+        let (l, c, r) = (self.cmnd[tau - 1], self.cmnd[tau], self.cmnd[tau + 1]);
+        let denom = l - 2.0 * c + r;
+        let offset = if denom.abs() > f32::EPSILON {
+            (0.5 * (l - r) / denom).clamp(-0.5, 0.5)
+        } else {
+            0.0
+        };
+        let period = tau as f32 + offset;
+        let confidence = (1.0 - c).clamp(0.0, 1.0);
+        Some((sr / period, confidence))
+    }
+}
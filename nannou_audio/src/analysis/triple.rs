@@ -0,0 +1,87 @@
+//! A lock-free triple buffer for publishing the latest value from one thread to another.
+//!
+//! The writer and reader each own one of three slots. The third slot is exchanged atomically:
+//! the writer publishes by swapping its slot into the middle, while the reader swaps the middle
+//! slot out for its own whenever a newer value is available. Neither side ever blocks.
+
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// Set on the middle index when it holds a value that the reader has not yet seen.
+const FRESH: usize = 0b100;
+const INDEX: usize = 0b011;
+
+struct Shared<T> {
+    slots: [UnsafeCell<T>; 3],
+    middle: AtomicUsize,
+}
+
+pub(crate) struct Writer<T> {
+    shared: Arc<Shared<T>>,
+    index: usize,
+}
+
+pub(crate) struct Reader<T> {
+    shared: Arc<Shared<T>>,
+    index: usize,
+}
+
+// Each slot is only ever accessed by the side that currently owns its index.
+unsafe impl<T: Send> Send for Writer<T> {}
+unsafe impl<T: Send> Send for Reader<T> {}
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+/// Create a triple buffer whose slots are each initialised with a clone of `value`.
+pub(crate) fn new<T: Clone>(value: T) -> (Writer<T>, Reader<T>) {
+    let slots = [
+        UnsafeCell::new(value.clone()),
+        UnsafeCell::new(value.clone()),
+        UnsafeCell::new(value),
+    ];
+    let shared = Arc::new(Shared {
+        slots,
+        middle: AtomicUsize::new(1),
+    });
+    let writer = Writer {
+        shared: shared.clone(),
+        index: 0,
+    };
+    let reader = Reader { shared, index: 2 };
+    (writer, reader)
+}
+
+impl<T> Writer<T> {
+    /// The slot owned by the writer.
+    pub fn get_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.shared.slots[self.index].get() }
+    }
+
+    /// Publish the writer's slot, taking ownership of the previous middle slot.
+    pub fn publish(&mut self) {
+        let prev = self
+            .shared
+            .middle
+            .swap(self.index | FRESH, Ordering::AcqRel);
+        self.index = prev & INDEX;
+    }
+}
+
+impl<T> Reader<T> {
+    /// Acquire the most recently published value, if newer than the current value.
+    ///
+    /// Returns `true` if a newer value was acquired.
+    pub fn update(&mut self) -> bool {
+        if self.shared.middle.load(Ordering::Relaxed) & FRESH == 0 {
+            return false;
+        }
+        let prev = self.shared.middle.swap(self.index, Ordering::AcqRel);
+        self.index = prev & INDEX;
+        true
+    }
+
+    /// The slot owned by the reader.
+    pub fn get(&self) -> &T {
+        unsafe { &*self.shared.slots[self.index].get() }
+    }
+}
@@ -1,8 +1,13 @@
 use crate::{
-    DefaultStreamConfigError, DeviceNameError, SupportedStreamConfig, SupportedStreamConfigsError,
+    DefaultStreamConfigError, DeviceNameError, HostId, SupportedStreamConfig,
+    SupportedStreamConfigsError,
 };
-use cpal::traits::DeviceTrait;
+use cpal::traits::{DeviceTrait, HostTrait};
 use std::ops::Deref;
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
 
 /// A device that can be used to spawn an audio stream.
 pub struct Device {
@@ -14,6 +19,44 @@ pub struct Devices {
     pub(crate) devices: cpal::Devices,
 }
 
+/// Notifies of audio devices being connected or disconnected and of changes to the default
+/// devices.
+///
+/// CPAL does not provide notifications of its own, so devices are enumerated on a background
+/// thread at a regular interval and compared with the previous enumeration. Devices are identified
+/// by name. Dropping the `DeviceWatcher` stops the thread.
+pub struct DeviceWatcher {
+    events: mpsc::Receiver<DeviceEvent>,
+    closed: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+/// A change to the available audio devices, reported by a `DeviceWatcher`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeviceEvent {
+    /// A device supporting input streams became available.
+    InputAdded { name: String },
+    /// A device supporting input streams is no longer available.
+    InputRemoved { name: String },
+    /// A device supporting output streams became available.
+    OutputAdded { name: String },
+    /// A device supporting output streams is no longer available.
+    OutputRemoved { name: String },
+    /// The default input device changed, or there is no longer a default input device.
+    DefaultInputChanged { name: Option<String> },
+    /// The default output device changed, or there is no longer a default output device.
+    DefaultOutputChanged { name: Option<String> },
+}
+
+// The names of the available devices at a single moment.
+#[derive(Default)]
+struct Snapshot {
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    default_input: Option<String>,
+    default_output: Option<String>,
+}
+
 /// An iterator yielding configs that are supported by the backend.
 pub type SupportedInputConfigs = cpal::SupportedInputConfigs;
 
@@ -73,6 +116,81 @@ impl Device {
     }
 }
 
+impl DeviceWatcher {
+    /// The default interval at which devices are enumerated.
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+    // Spawn the thread enumerating the devices of the host with the given ID.
+    pub(crate) fn spawn(host_id: HostId, interval: Duration) -> Self {
+        let (events_tx, events_rx) = mpsc::channel();
+        let closed = Arc::new(AtomicBool::new(false));
+        let thread_closed = closed.clone();
+        let thread = thread::Builder::new()
+            .name("nannou_audio-device-watcher".into())
+            .spawn(move || watch(host_id, interval, thread_closed, events_tx))
+            .expect("failed to spawn device watcher thread");
+        DeviceWatcher {
+            events: events_rx,
+            closed,
+            thread: Some(thread),
+        }
+    }
+
+    /// The changes to the available devices since the last call.
+    pub fn events(&self) -> mpsc::TryIter<'_, DeviceEvent> {
+        self.events.try_iter()
+    }
+}
+
+impl Snapshot {
+    // Enumerate the devices of the given host, or `None` if enumeration fails.
+    fn new(host: &cpal::Host) -> Option<Self> {
+        let snapshot = Snapshot {
+            inputs: names(host.input_devices().ok()?),
+            outputs: names(host.output_devices().ok()?),
+            default_input: host.default_input_device().and_then(|d| d.name().ok()),
+            default_output: host.default_output_device().and_then(|d| d.name().ok()),
+        };
+        Some(snapshot)
+    }
+
+    // The events describing the change from `self` to `next`.
+    fn diff(&self, next: &Snapshot) -> Vec<DeviceEvent> {
+        let mut events = vec![];
+        for name in removed(&self.inputs, &next.inputs) {
+            events.push(DeviceEvent::InputRemoved { name });
+        }
+        for name in removed(&self.outputs, &next.outputs) {
+            events.push(DeviceEvent::OutputRemoved { name });
+        }
+        for name in removed(&next.inputs, &self.inputs) {
+            events.push(DeviceEvent::InputAdded { name });
+        }
+        for name in removed(&next.outputs, &self.outputs) {
+            events.push(DeviceEvent::OutputAdded { name });
+        }
+        if self.default_input != next.default_input {
+            let name = next.default_input.clone();
+            events.push(DeviceEvent::DefaultInputChanged { name });
+        }
+        if self.default_output != next.default_output {
+            let name = next.default_output.clone();
+            events.push(DeviceEvent::DefaultOutputChanged { name });
+        }
+        events
+    }
+}
+
+impl Drop for DeviceWatcher {
+    fn drop(&mut self) {
+        self.closed.store(true, atomic::Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            thread.join().ok();
+        }
+    }
+}
+
 impl Deref for Device {
     type Target = cpal::Device;
     fn deref(&self) -> &Self::Target {
@@ -86,3 +204,58 @@ impl Iterator for Devices {
         self.devices.next().map(|device| Device { device })
     }
 }
+
+// Runs on the watcher thread, reporting changes until the `DeviceWatcher` is dropped.
+fn watch(
+    host_id: HostId,
+    interval: Duration,
+    closed: Arc<AtomicBool>,
+    events: mpsc::Sender<DeviceEvent>,
+) {
+    // CPAL hosts are not necessarily `Send`, so the thread uses a host of its own.
+    let host = match cpal::host_from_id(host_id) {
+        Ok(host) => host,
+        Err(_) => return,
+    };
+    let mut snapshot = Snapshot::new(&host).unwrap_or_default();
+    while !closed.load(atomic::Ordering::Acquire) {
+        thread::park_timeout(interval);
+        if closed.load(atomic::Ordering::Acquire) {
+            break;
+        }
+        // If enumeration fails, e.g. while a device is being reconfigured, try again later.
+        let next = match Snapshot::new(&host) {
+            Some(next) => next,
+            None => continue,
+        };
+        for event in snapshot.diff(&next) {
+            if events.send(event).is_err() {
+                return;
+            }
+        }
+        snapshot = next;
+    }
+}
+
+// The names of the given devices, skipping those whose name is unavailable.
+fn names<I>(devices: I) -> Vec<String>
+where
+    I: Iterator<Item = cpal::Device>,
+{
+    devices.filter_map(|d| d.name().ok()).collect()
+}
+
+// The names within `a` that are missing from `b`, allowing for duplicate names.
+fn removed(a: &[String], b: &[String]) -> Vec<String> {
+    let mut b = b.to_vec();
+    let mut removed = vec![];
+    for name in a {
+        match b.iter().position(|n| n == name) {
+            Some(ix) => {
+                b.swap_remove(ix);
+            }
+            None => removed.push(name.clone()),
+        }
+    }
+    removed
+}
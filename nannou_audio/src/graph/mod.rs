@@ -0,0 +1,838 @@
+//! A graph of audio nodes for synthesis and processing within an output stream.
+//!
+//! A graph is made up of two halves:
+//!
+//! - [**Graph**](./struct.Graph.html) - lives on the main thread and is used to add, remove and
+//!   connect nodes and to send parameter changes to them.
+//! - [**Processor**](./struct.Processor.html) - lives on the audio thread, typically as the model
+//!   of an output stream, and renders the graph to each `Buffer`.
+//!
+//! The provided nodes include oscillators, noise, ADSR envelopes, biquad filters, a delay, a
+//! reverb, gain, pan and mixers. Custom nodes may be added by implementing the
+//! [**Node**](./node/trait.Node.html) trait.
+//!
+//! ```no_run
+//! use nannou_audio::graph::{self, Adsr, Graph, Oscillator, Waveform};
+//!
+//! let (mut graph, processor) = Graph::new(2, 44_100);
+//! let osc = graph.add(Oscillator::new(Waveform::Saw, 220.0)).unwrap();
+//! let env = graph.add(Adsr::new(1)).unwrap();
+//! graph.connect(osc, env).unwrap();
+//! graph.connect(env, graph.output()).unwrap();
+//! graph.commit().unwrap();
+//!
+//! let host = nannou_audio::Host::new();
+//! let stream = host
+//!     .new_output_stream(processor)
+//!     .render(graph::render)
+//!     .sample_rate(44_100)
+//!     .build()
+//!     .unwrap();
+//!
+//! // Later, on the main thread.
+//! graph.set(env, Adsr::GATE, 1.0).unwrap();
+//! ```
+//!
+//! Parameter changes are sent to the audio thread immediately and are smoothed by each node.
+//! Changes to the structure of the graph are collected until `Graph::commit` is called, at which
+//! point they are applied together at the start of the next buffer. New connections fade in and
+//! removed connections fade out over `EDGE_FADE_SECS`, and removed nodes continue to run until
+//! their output has faded. Nodes are allocated and dropped on the main thread.
+//!
+//! Audio is converted between differing numbers of channels along each connection. Mono sources
+//! are copied to every channel, inputs to mono nodes receive the average of all source channels
+//! and otherwise each channel is connected to the channel of the same index.
+
+use crate::Buffer;
+use dasp_sample::{FromSample, Sample};
+use ringbuf::{Consumer, Producer, RingBuffer};
+use std::mem;
+use thiserror::Error;
+
+#[doc(inline)]
+pub use self::node::{
+    Adsr, Biquad, Context, Delay, FilterKind, Gain, Mixer, Node, Noise, NoiseColor, Oscillator,
+    Pan, Param, Reverb, Smoothed, Waveform, BLOCK_FRAMES,
+};
+
+pub mod node;
+
+/// The maximum number of nodes of a graph created via `Graph::new`, including the output node.
+pub const DEFAULT_MAX_NODES: usize = 256;
+
+/// The duration in seconds over which connections fade in and out.
+pub const EDGE_FADE_SECS: f32 = 0.005;
+
+// The capacity of the queues between the `Graph` and `Processor`.
+const COMMAND_CAPACITY: usize = 1024;
+const GARBAGE_CAPACITY: usize = 1024;
+
+// The slot of the graph's output node.
+const OUTPUT: usize = 0;
+
+/// Uniquely identifies a node within a graph.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId {
+    index: u32,
+    generation: u32,
+}
+
+/// Uniquely identifies a connection between two nodes within a graph.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EdgeId(u64);
+
+/// Edits the graph rendered by the associated `Processor`.
+///
+/// Dropping the `Graph` leaves the `Processor` rendering the graph as of the last commit.
+pub struct Graph {
+    commands: Producer<Command>,
+    garbage: Consumer<Garbage>,
+    sample_rate: u32,
+    channels: usize,
+    nodes: Vec<Option<NodeEntry>>,
+    generations: Vec<u32>,
+    free: Vec<usize>,
+    // Slots whose nodes have been dropped by the processor. These may be reused once a schedule
+    // that no longer refers to them has been sent.
+    released: Vec<usize>,
+    edges: Vec<EdgeEntry>,
+    next_edge: u64,
+    // Whether or not the structure has changed since the last commit.
+    dirty: bool,
+}
+
+/// Renders the graph edited by the associated `Graph`.
+///
+/// All state is allocated by the `Graph` on the main thread. The `Processor` only allocates in the
+/// case that the stream's sample rate differs from that of the graph, in which case every node is
+/// prepared again for the new rate.
+pub struct Processor {
+    commands: Consumer<Command>,
+    garbage: Producer<Garbage>,
+    nodes: Vec<Option<Box<Slot>>>,
+    schedule: Box<Schedule>,
+    sample_rate: u32,
+}
+
+/// Errors that might occur while editing a graph.
+#[derive(Debug, Error)]
+pub enum GraphError {
+    #[error("no node exists for the given id")]
+    NodeNotFound,
+    #[error("no connection exists for the given id")]
+    EdgeNotFound,
+    #[error("the destination node has no inputs")]
+    NoInputs,
+    #[error("the source node has no outputs")]
+    NoOutputs,
+    #[error("the connection would form a cycle")]
+    Cycle,
+    #[error("the graph's output node cannot be removed")]
+    RemoveOutput,
+    #[error("the graph has reached its maximum number of nodes")]
+    Full,
+    #[error("the graph's command queue is full")]
+    CommandQueueFull,
+}
+
+// The main thread's view of a node.
+struct NodeEntry {
+    generation: u32,
+    inputs: usize,
+    outputs: usize,
+    removing: bool,
+}
+
+// The main thread's view of a connection.
+struct EdgeEntry {
+    id: EdgeId,
+    source: usize,
+    dest: usize,
+    gain: f32,
+    removing: bool,
+    // Whether or not the connection is part of the last schedule sent to the processor.
+    committed: bool,
+}
+
+// A node along with its input and output buffers.
+struct Slot {
+    node: Box<dyn Node>,
+    // The sample rate at which the node was last prepared.
+    sample_rate: u32,
+    inputs: usize,
+    outputs: usize,
+    input: Vec<f32>,
+    output: Vec<f32>,
+}
+
+// The order in which nodes are processed along with the connections to each.
+struct Schedule {
+    order: Vec<usize>,
+    // The connections to each node, indexed by slot.
+    inputs: Vec<Vec<Input>>,
+    // Nodes that should be dropped once their outgoing connections have faded.
+    removing: Vec<usize>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Input {
+    edge: EdgeId,
+    source: usize,
+    gain: f32,
+    // The current gain of the fade.
+    current: f32,
+    removing: bool,
+}
+
+enum Command {
+    AddNode(usize, Box<Slot>),
+    Schedule(Box<Schedule>),
+    SetParam {
+        index: usize,
+        param: Param,
+        value: f32,
+    },
+    SetGain {
+        dest: usize,
+        edge: EdgeId,
+        gain: f32,
+    },
+}
+
+// Items returned to the main thread to be dropped.
+enum Garbage {
+    Schedule(Box<Schedule>),
+    Node(usize, Box<Slot>),
+    Edge(EdgeId),
+}
+
+// The node through which all audio reaches the stream.
+struct Output {
+    channels: usize,
+}
+
+impl Graph {
+    /// Create a graph producing the given number of channels at the given sample rate.
+    ///
+    /// The sample rate should match that of the stream to which the `Processor` is rendered.
+    pub fn new(channels: usize, sample_rate: u32) -> (Self, Processor) {
+        Self::with_capacity(channels, sample_rate, DEFAULT_MAX_NODES)
+    }
+
+    /// Create a graph with room for the given maximum number of nodes, including the output node.
+    pub fn with_capacity(channels: usize, sample_rate: u32, max_nodes: usize) -> (Self, Processor) {
+        assert!(channels > 0);
+        assert!(sample_rate > 0);
+        let max_nodes = max_nodes.max(1);
+        let (command_tx, command_rx) = RingBuffer::new(COMMAND_CAPACITY).split();
+        let (garbage_tx, garbage_rx) = RingBuffer::new(GARBAGE_CAPACITY).split();
+
+        let mut nodes: Vec<Option<NodeEntry>> = (0..max_nodes).map(|_| None).collect();
+        nodes[OUTPUT] = Some(NodeEntry {
+            generation: 0,
+            inputs: channels,
+            outputs: channels,
+            removing: false,
+        });
+        let mut slots: Vec<Option<Box<Slot>>> = (0..max_nodes).map(|_| None).collect();
+        slots[OUTPUT] = Some(Slot::new(Box::new(Output { channels }), sample_rate));
+        let schedule = Box::new(Schedule {
+            order: vec![],
+            inputs: (0..max_nodes).map(|_| vec![]).collect(),
+            removing: vec![],
+        });
+
+        let graph = Graph {
+            commands: command_tx,
+            garbage: garbage_rx,
+            sample_rate,
+            channels,
+            nodes,
+            generations: vec![0; max_nodes],
+            free: (1..max_nodes).rev().collect(),
+            released: vec![],
+            edges: vec![],
+            next_edge: 0,
+            dirty: true,
+        };
+        let processor = Processor {
+            commands: command_rx,
+            garbage: garbage_tx,
+            nodes: slots,
+            schedule,
+            sample_rate,
+        };
+        (graph, processor)
+    }
+
+    /// The node through which all audio reaches the stream.
+    pub fn output(&self) -> NodeId {
+        NodeId {
+            index: OUTPUT as u32,
+            generation: 0,
+        }
+    }
+
+    /// The number of channels produced by the graph.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// The sample rate with which nodes are prepared.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// The number of nodes within the graph, excluding the output node and removed nodes.
+    pub fn len(&self) -> usize {
+        self.nodes
+            .iter()
+            .skip(1)
+            .flatten()
+            .filter(|entry| !entry.removing)
+            .count()
+    }
+
+    /// Whether or not the graph contains any nodes other than the output node.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether or not the graph contains the given node.
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.index(id).is_ok()
+    }
+
+    /// Add the given node to the graph.
+    ///
+    /// The node is prepared at the graph's sample rate and sent to the audio thread immediately,
+    /// though it is not processed until it is connected and the changes are committed.
+    pub fn add<N>(&mut self, node: N) -> Result<NodeId, GraphError>
+    where
+        N: 'static + Node,
+    {
+        self.collect_garbage();
+        let index = self.free.pop().ok_or(GraphError::Full)?;
+        let slot = Slot::new(Box::new(node), self.sample_rate);
+        let (inputs, outputs) = (slot.inputs, slot.outputs);
+        if self.commands.push(Command::AddNode(index, slot)).is_err() {
+            self.free.push(index);
+            return Err(GraphError::CommandQueueFull);
+        }
+        let generation = self.generations[index];
+        self.nodes[index] = Some(NodeEntry {
+            generation,
+            inputs,
+            outputs,
+            removing: false,
+        });
+        let index = index as u32;
+        Ok(NodeId { index, generation })
+    }
+
+    /// Remove the given node from the graph.
+    ///
+    /// Upon commit, the node's outgoing connections fade out, after which the node is dropped.
+    pub fn remove(&mut self, id: NodeId) -> Result<(), GraphError> {
+        self.collect_garbage();
+        let index = self.index(id)?;
+        if index == OUTPUT {
+            return Err(GraphError::RemoveOutput);
+        }
+        self.nodes[index].as_mut().unwrap().removing = true;
+        // Connections that have not yet been committed may be removed immediately.
+        self.edges
+            .retain(|edge| edge.committed || (edge.source != index && edge.dest != index));
+        for edge in self.edges.iter_mut().filter(|edge| edge.source == index) {
+            edge.removing = true;
+        }
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Connect the output of `source` to the input of `dest` at unity gain.
+    pub fn connect(&mut self, source: NodeId, dest: NodeId) -> Result<EdgeId, GraphError> {
+        self.connect_with_gain(source, dest, 1.0)
+    }
+
+    /// Connect the output of `source` to the input of `dest` at the given gain.
+    ///
+    /// Fails if the connection would form a cycle. Feedback may instead be achieved via nodes
+    /// such as `Delay`.
+    pub fn connect_with_gain(
+        &mut self,
+        source: NodeId,
+        dest: NodeId,
+        gain: f32,
+    ) -> Result<EdgeId, GraphError> {
+        self.collect_garbage();
+        let source = self.index(source)?;
+        let dest = self.index(dest)?;
+        if source == OUTPUT || self.nodes[source].as_ref().unwrap().outputs == 0 {
+            return Err(GraphError::NoOutputs);
+        }
+        if self.nodes[dest].as_ref().unwrap().inputs == 0 {
+            return Err(GraphError::NoInputs);
+        }
+        if source == dest || self.reaches(dest, source) {
+            return Err(GraphError::Cycle);
+        }
+        let id = EdgeId(self.next_edge);
+        self.next_edge += 1;
+        self.edges.push(EdgeEntry {
+            id,
+            source,
+            dest,
+            gain,
+            removing: false,
+            committed: false,
+        });
+        self.dirty = true;
+        Ok(id)
+    }
+
+    /// Remove the given connection. Upon commit, the connection fades out.
+    pub fn disconnect(&mut self, edge: EdgeId) -> Result<(), GraphError> {
+        self.collect_garbage();
+        let ix = self.edge_index(edge)?;
+        if self.edges[ix].committed {
+            self.edges[ix].removing = true;
+        } else {
+            self.edges.remove(ix);
+        }
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Set the gain of the given connection.
+    ///
+    /// The change is sent to the audio thread immediately and applied with a short fade.
+    pub fn set_gain(&mut self, edge: EdgeId, gain: f32) -> Result<(), GraphError> {
+        self.collect_garbage();
+        let ix = self.edge_index(edge)?;
+        let entry = &mut self.edges[ix];
+        entry.gain = gain;
+        if entry.committed {
+            let dest = entry.dest;
+            self.send(Command::SetGain { dest, edge, gain })?;
+        }
+        Ok(())
+    }
+
+    /// Send a change of the given parameter to the node on the audio thread.
+    pub fn set(&mut self, node: NodeId, param: Param, value: f32) -> Result<(), GraphError> {
+        self.collect_garbage();
+        let index = self.index(node)?;
+        self.send(Command::SetParam {
+            index,
+            param,
+            value,
+        })
+    }
+
+    /// Apply all changes to the structure of the graph since the last commit.
+    ///
+    /// The changes take effect together at the start of the next buffer rendered by the
+    /// `Processor`. Calling `commit` also releases any removed nodes that have finished fading.
+    pub fn commit(&mut self) -> Result<(), GraphError> {
+        self.collect_garbage();
+        if !self.dirty {
+            return Ok(());
+        }
+
+        // Only nodes with a path to the output are processed.
+        let max_nodes = self.nodes.len();
+        let mut reachable = vec![false; max_nodes];
+        reachable[OUTPUT] = true;
+        let mut stack = vec![OUTPUT];
+        while let Some(dest) = stack.pop() {
+            for edge in self.edges.iter().filter(|edge| edge.dest == dest) {
+                if !reachable[edge.source] {
+                    reachable[edge.source] = true;
+                    stack.push(edge.source);
+                }
+            }
+        }
+
+        // Removed connections that can no longer be heard need not fade.
+        self.edges
+            .retain(|edge| !edge.removing || reachable[edge.dest]);
+
+        // Sort the reachable nodes so that each is processed after all of its sources.
+        let mut pending = vec![0usize; max_nodes];
+        for edge in self.edges.iter().filter(|edge| reachable[edge.dest]) {
+            pending[edge.dest] += 1;
+        }
+        let mut ready: Vec<usize> = (0..max_nodes)
+            .filter(|&i| reachable[i] && pending[i] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(ready.len());
+        while let Some(index) = ready.pop() {
+            order.push(index);
+            let edges = self.edges.iter().filter(|edge| edge.source == index);
+            for edge in edges.filter(|edge| reachable[edge.dest]) {
+                pending[edge.dest] -= 1;
+                if pending[edge.dest] == 0 {
+                    ready.push(edge.dest);
+                }
+            }
+        }
+
+        let mut inputs: Vec<Vec<Input>> = (0..max_nodes).map(|_| vec![]).collect();
+        for edge in self.edges.iter().filter(|edge| reachable[edge.dest]) {
+            inputs[edge.dest].push(Input {
+                edge: edge.id,
+                source: edge.source,
+                gain: edge.gain,
+                current: 0.0,
+                removing: edge.removing,
+            });
+        }
+        let removing = (0..max_nodes)
+            .filter(|&i| self.nodes[i].as_ref().map(|n| n.removing).unwrap_or(false))
+            .collect();
+        let schedule = Box::new(Schedule {
+            order,
+            inputs,
+            removing,
+        });
+        self.send(Command::Schedule(schedule))?;
+
+        for edge in &mut self.edges {
+            edge.committed = reachable[edge.dest];
+        }
+        self.free.append(&mut self.released);
+        self.dirty = false;
+        Ok(())
+    }
+
+    // The slot of the given node, if it exists and has not been removed.
+    fn index(&self, id: NodeId) -> Result<usize, GraphError> {
+        let index = id.index as usize;
+        match self.nodes.get(index) {
+            Some(Some(entry)) if entry.generation == id.generation && !entry.removing => Ok(index),
+            _ => Err(GraphError::NodeNotFound),
+        }
+    }
+
+    // The index of the given connection, if it exists and has not been removed.
+    fn edge_index(&self, id: EdgeId) -> Result<usize, GraphError> {
+        self.edges
+            .iter()
+            .position(|edge| edge.id == id && !edge.removing)
+            .ok_or(GraphError::EdgeNotFound)
+    }
+
+    // Whether or not there is a path from `source` to `dest`.
+    fn reaches(&self, source: usize, dest: usize) -> bool {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = vec![source];
+        while let Some(index) = stack.pop() {
+            if index == dest {
+                return true;
+            }
+            for edge in self.edges.iter().filter(|edge| edge.source == index) {
+                if !visited[edge.dest] {
+                    visited[edge.dest] = true;
+                    stack.push(edge.dest);
+                }
+            }
+        }
+        false
+    }
+
+    fn send(&mut self, command: Command) -> Result<(), GraphError> {
+        self.commands
+            .push(command)
+            .map_err(|_| GraphError::CommandQueueFull)
+    }
+
+    // Drop everything returned by the processor, updating the graph to match.
+    fn collect_garbage(&mut self) {
+        while let Some(garbage) = self.garbage.pop() {
+            match garbage {
+                Garbage::Schedule(schedule) => drop(schedule),
+                Garbage::Node(index, slot) => {
+                    drop(slot);
+                    self.nodes[index] = None;
+                    self.generations[index] = self.generations[index].wrapping_add(1);
+                    self.edges
+                        .retain(|edge| edge.source != index && edge.dest != index);
+                    self.released.push(index);
+                    self.dirty = true;
+                }
+                Garbage::Edge(id) => {
+                    self.edges.retain(|edge| edge.id != id || !edge.removing);
+                }
+            }
+        }
+    }
+}
+
+impl Processor {
+    /// The sample rate at which the graph is currently processed.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Render the graph to the given buffer.
+    pub fn render<S>(&mut self, buffer: &mut Buffer<S>)
+    where
+        S: Sample + FromSample<f32>,
+    {
+        let sample_rate = buffer.sample_rate();
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            for slot in self.nodes.iter_mut().flatten() {
+                slot.prepare(sample_rate);
+            }
+        }
+        self.process_commands();
+
+        let channels = buffer.channels();
+        let frames = buffer.len_frames();
+        let mut start = 0;
+        while start < frames {
+            let n = std::cmp::min(BLOCK_FRAMES, frames - start);
+            self.process_block(n);
+            let output = self.nodes[OUTPUT].as_ref().unwrap();
+            let samples = &mut buffer[start * channels..(start + n) * channels];
+            for (i, frame) in samples.chunks_exact_mut(channels).enumerate() {
+                for (c, sample) in frame.iter_mut().enumerate() {
+                    let x = read_channel(&output.output, output.outputs, channels, c, n, i);
+                    *sample = x.to_sample();
+                }
+            }
+            start += n;
+        }
+
+        self.release_nodes();
+    }
+
+    // Apply all pending commands from the `Graph`.
+    fn process_commands(&mut self) {
+        while let Some(command) = self.commands.pop() {
+            match command {
+                Command::AddNode(index, mut slot) => {
+                    if slot.sample_rate != self.sample_rate {
+                        slot.prepare(self.sample_rate);
+                    }
+                    if let Some(old) = self.nodes[index].replace(slot) {
+                        self.garbage.push(Garbage::Node(index, old)).ok();
+                    }
+                }
+                Command::Schedule(mut schedule) => {
+                    // Continue the fades of connections that remain.
+                    for &dest in &schedule.order {
+                        let old = &self.schedule.inputs[dest];
+                        for input in &mut schedule.inputs[dest] {
+                            if let Some(prev) = old.iter().find(|prev| prev.edge == input.edge) {
+                                input.current = prev.current;
+                            }
+                        }
+                    }
+                    let old = mem::replace(&mut self.schedule, schedule);
+                    self.garbage.push(Garbage::Schedule(old)).ok();
+                }
+                Command::SetParam {
+                    index,
+                    param,
+                    value,
+                } => {
+                    if let Some(ref mut slot) = self.nodes[index] {
+                        slot.node.set_param(param, value);
+                    }
+                }
+                Command::SetGain { dest, edge, gain } => {
+                    let inputs = &mut self.schedule.inputs[dest];
+                    if let Some(input) = inputs.iter_mut().find(|input| input.edge == edge) {
+                        input.gain = gain;
+                    }
+                }
+            }
+        }
+    }
+
+    // Process every scheduled node for a single block of `frames` frames.
+    fn process_block(&mut self, frames: usize) {
+        let Processor {
+            ref mut nodes,
+            ref mut schedule,
+            ref mut garbage,
+            sample_rate,
+            ..
+        } = *self;
+        let ctx = Context {
+            sample_rate,
+            frames,
+        };
+        let fade_step = frames as f32 / (EDGE_FADE_SECS * sample_rate as f32).max(1.0);
+        let Schedule {
+            ref order,
+            ref mut inputs,
+            ..
+        } = **schedule;
+
+        for &dest in order {
+            let mut slot = match nodes[dest].take() {
+                None => continue,
+                Some(slot) => slot,
+            };
+            let dest_channels = slot.inputs;
+            let input = &mut slot.input[..dest_channels * frames];
+            for sample in input.iter_mut() {
+                *sample = 0.0;
+            }
+
+            // Sum each connection into the input, fading its gain towards the target.
+            let dest_inputs = &mut inputs[dest];
+            for conn in dest_inputs.iter_mut() {
+                let target = if conn.removing { 0.0 } else { conn.gain };
+                let from = conn.current;
+                let to = if (target - from).abs() <= fade_step {
+                    target
+                } else {
+                    from + fade_step * (target - from).signum()
+                };
+                conn.current = to;
+                let source = match nodes[conn.source] {
+                    None => continue,
+                    Some(ref source) => source,
+                };
+                if from == 0.0 && to == 0.0 {
+                    continue;
+                }
+                let step = (to - from) / frames as f32;
+                for (c, channel) in input.chunks_exact_mut(frames).enumerate() {
+                    for (i, sample) in channel.iter_mut().enumerate() {
+                        let gain = from + step * (i + 1) as f32;
+                        let x = read_channel(
+                            &source.output,
+                            source.outputs,
+                            dest_channels,
+                            c,
+                            frames,
+                            i,
+                        );
+                        *sample += x * gain;
+                    }
+                }
+            }
+
+            // Release connections that have finished fading out.
+            let mut i = 0;
+            while i < dest_inputs.len() {
+                let conn = dest_inputs[i];
+                if conn.removing
+                    && conn.current == 0.0
+                    && garbage.push(Garbage::Edge(conn.edge)).is_ok()
+                {
+                    dest_inputs.remove(i);
+                } else {
+                    i += 1;
+                }
+            }
+
+            let outputs = slot.outputs;
+            let Slot {
+                ref mut node,
+                ref input,
+                ref mut output,
+                ..
+            } = *slot;
+            node.process(
+                &ctx,
+                &input[..dest_channels * frames],
+                &mut output[..outputs * frames],
+            );
+            nodes[dest] = Some(slot);
+        }
+    }
+
+    // Return removed nodes to the main thread once nothing is connected to their output.
+    fn release_nodes(&mut self) {
+        let Processor {
+            ref mut nodes,
+            ref schedule,
+            ref mut garbage,
+            ..
+        } = *self;
+        for &index in &schedule.removing {
+            if nodes[index].is_none() || garbage.is_full() {
+                continue;
+            }
+            let connected = schedule.order.iter().any(|&dest| {
+                schedule.inputs[dest]
+                    .iter()
+                    .any(|input| input.source == index)
+            });
+            if !connected {
+                let slot = nodes[index].take().unwrap();
+                garbage.push(Garbage::Node(index, slot)).ok();
+            }
+        }
+    }
+}
+
+impl Slot {
+    fn new(mut node: Box<dyn Node>, sample_rate: u32) -> Box<Self> {
+        node.prepare(sample_rate);
+        let inputs = node.inputs();
+        let outputs = node.outputs();
+        Box::new(Slot {
+            node,
+            sample_rate,
+            inputs,
+            outputs,
+            input: vec![0.0; inputs * BLOCK_FRAMES],
+            output: vec![0.0; outputs * BLOCK_FRAMES],
+        })
+    }
+
+    fn prepare(&mut self, sample_rate: u32) {
+        self.node.prepare(sample_rate);
+        self.sample_rate = sample_rate;
+    }
+}
+
+impl Node for Output {
+    fn inputs(&self) -> usize {
+        self.channels
+    }
+
+    fn outputs(&self) -> usize {
+        self.channels
+    }
+
+    fn process(&mut self, _ctx: &Context, input: &[f32], output: &mut [f32]) {
+        output.copy_from_slice(input);
+    }
+}
+
+/// Render the `Processor` to the given buffer.
+///
+/// This may be passed directly to an output stream builder's `render` method.
+pub fn render<S>(processor: &mut Processor, buffer: &mut Buffer<S>)
+where
+    S: Sample + FromSample<f32>,
+{
+    processor.render(buffer);
+}
+
+// Read frame `i` of channel `c` of a planar block with `src` channels for a destination with
+// `dest` channels.
+#[inline]
+fn read_channel(block: &[f32], src: usize, dest: usize, c: usize, frames: usize, i: usize) -> f32 {
+    if src == dest {
+        block[c * frames + i]
+    } else if src == 1 {
+        block[i]
+    } else if dest == 1 {
+        let sum: f32 = (0..src).map(|c| block[c * frames + i]).sum();
+        sum / src as f32
+    } else if c < src {
+        block[c * frames + i]
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,191 @@
+use super::{channels_mut, Context, Node, Param, BLOCK_FRAMES};
+
+/// An attack, decay, sustain, release envelope applied to the node's input.
+///
+/// Setting the `GATE` parameter to `1.0` begins the attack from the current level, while setting
+/// it to `0.0` begins the release.
+#[derive(Clone, Debug)]
+pub struct Adsr {
+    channels: usize,
+    attack: f32,
+    decay: f32,
+    sustain: f32,
+    release: f32,
+    stage: Stage,
+    level: f32,
+    // The amount by which the level falls per frame during the release.
+    release_step: f32,
+    sample_rate: u32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Stage {
+    Idle,
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+}
+
+// The coefficient with which the level follows changes to the sustain level.
+const SUSTAIN_FOLLOW: f32 = 0.001;
+
+impl Adsr {
+    /// Values greater than `0.5` open the gate, all others close it.
+    pub const GATE: Param = Param(0);
+    /// The attack duration in seconds.
+    pub const ATTACK: Param = Param(1);
+    /// The decay duration in seconds.
+    pub const DECAY: Param = Param(2);
+    /// The sustain level in the range `0.0..=1.0`.
+    pub const SUSTAIN: Param = Param(3);
+    /// The release duration in seconds.
+    pub const RELEASE: Param = Param(4);
+
+    /// An envelope for the given number of channels with a closed gate.
+    pub fn new(channels: usize) -> Self {
+        Adsr {
+            channels,
+            attack: 0.01,
+            decay: 0.1,
+            sustain: 0.7,
+            release: 0.3,
+            stage: Stage::Idle,
+            level: 0.0,
+            release_step: 0.0,
+            sample_rate: crate::stream::DEFAULT_SAMPLE_RATE,
+        }
+    }
+
+    /// Specify the attack duration in seconds. Defaults to `0.01`.
+    pub fn attack(mut self, secs: f32) -> Self {
+        self.attack = secs.max(0.0);
+        self
+    }
+
+    /// Specify the decay duration in seconds. Defaults to `0.1`.
+    pub fn decay(mut self, secs: f32) -> Self {
+        self.decay = secs.max(0.0);
+        self
+    }
+
+    /// Specify the sustain level. Defaults to `0.7`.
+    pub fn sustain(mut self, level: f32) -> Self {
+        self.sustain = level.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Specify the release duration in seconds. Defaults to `0.3`.
+    pub fn release(mut self, secs: f32) -> Self {
+        self.release = secs.max(0.0);
+        self
+    }
+
+    /// Whether or not the envelope has finished releasing.
+    pub fn is_idle(&self) -> bool {
+        self.stage == Stage::Idle
+    }
+
+    // The amount by which the level changes per frame over the given duration.
+    fn step(&self, secs: f32, distance: f32) -> f32 {
+        let frames = secs * self.sample_rate as f32;
+        if frames < 1.0 {
+            distance
+        } else {
+            distance / frames
+        }
+    }
+
+    fn begin_release(&mut self) {
+        self.stage = Stage::Release;
+        self.release_step = self.step(self.release, self.level);
+    }
+
+    // Advance the envelope by a single frame.
+    fn tick(&mut self) -> f32 {
+        match self.stage {
+            Stage::Idle => (),
+            Stage::Attack => {
+                self.level += self.step(self.attack, 1.0);
+                if self.level >= 1.0 {
+                    self.level = 1.0;
+                    self.stage = Stage::Decay;
+                }
+            }
+            Stage::Decay => {
+                self.level -= self.step(self.decay, 1.0 - self.sustain);
+                if self.level <= self.sustain {
+                    self.level = self.sustain;
+                    self.stage = Stage::Sustain;
+                }
+            }
+            Stage::Sustain => {
+                self.level += (self.sustain - self.level) * SUSTAIN_FOLLOW;
+            }
+            Stage::Release => {
+                self.level -= self.release_step;
+                if self.level <= 0.0 {
+                    self.level = 0.0;
+                    self.stage = Stage::Idle;
+                }
+            }
+        }
+        self.level
+    }
+}
+
+impl Node for Adsr {
+    fn inputs(&self) -> usize {
+        self.channels
+    }
+
+    fn outputs(&self) -> usize {
+        self.channels
+    }
+
+    fn prepare(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate;
+    }
+
+    fn set_param(&mut self, param: Param, value: f32) {
+        match param {
+            Self::GATE => {
+                if value > 0.5 {
+                    self.stage = Stage::Attack;
+                } else if self.stage != Stage::Idle {
+                    self.begin_release();
+                }
+            }
+            Self::ATTACK => self.attack = value.max(0.0),
+            Self::DECAY => self.decay = value.max(0.0),
+            Self::SUSTAIN => self.sustain = value.clamp(0.0, 1.0),
+            Self::RELEASE => {
+                self.release = value.max(0.0);
+                if self.stage == Stage::Release {
+                    self.begin_release();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn process(&mut self, ctx: &Context, input: &[f32], output: &mut [f32]) {
+        let frames = ctx.frames;
+        if self.stage == Stage::Idle {
+            for sample in output.iter_mut() {
+                *sample = 0.0;
+            }
+            return;
+        }
+        output.copy_from_slice(input);
+        let mut levels = [0.0; BLOCK_FRAMES];
+        for level in &mut levels[..frames] {
+            *level = self.tick();
+        }
+        for channel in channels_mut(output, frames) {
+            for (sample, level) in channel.iter_mut().zip(&levels) {
+                *sample *= level;
+            }
+        }
+    }
+}
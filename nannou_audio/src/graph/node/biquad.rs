@@ -0,0 +1,202 @@
+use super::{channels, channels_mut, smoothing_frames, Context, Node, Param, Smoothed};
+
+/// The response of a `Biquad` filter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FilterKind {
+    LowPass,
+    HighPass,
+    /// A band-pass with a constant 0dB peak gain.
+    BandPass,
+    Notch,
+    /// A peaking EQ, boosting or cutting by `GAIN` around the frequency.
+    Peak,
+    /// A low shelf, boosting or cutting by `GAIN` below the frequency.
+    LowShelf,
+    /// A high shelf, boosting or cutting by `GAIN` above the frequency.
+    HighShelf,
+    AllPass,
+}
+
+/// A second-order IIR filter based on the filters of the Audio EQ Cookbook.
+///
+/// Changes to the frequency, Q and gain are smoothed and the coefficients are recalculated once
+/// per block.
+#[derive(Clone, Debug)]
+pub struct Biquad {
+    kind: FilterKind,
+    channels: usize,
+    frequency: Smoothed,
+    q: Smoothed,
+    gain: Smoothed,
+    coefficients: Coefficients,
+    // The transposed direct form II state of each channel.
+    state: Vec<[f32; 2]>,
+    // Whether or not the coefficients must be recalculated.
+    dirty: bool,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct Coefficients {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl Biquad {
+    /// The cutoff or centre frequency in Hz.
+    pub const FREQUENCY: Param = Param(0);
+    /// The quality factor, where higher values give a narrower band or a more resonant cutoff.
+    pub const Q: Param = Param(1);
+    /// The gain in decibels of the peak and shelf filters.
+    pub const GAIN: Param = Param(2);
+
+    /// A filter for the given number of channels with the given cutoff or centre frequency.
+    pub fn new(kind: FilterKind, channels: usize, hz: f32) -> Self {
+        Biquad {
+            kind,
+            channels,
+            frequency: Smoothed::new(hz),
+            q: Smoothed::new(std::f32::consts::FRAC_1_SQRT_2),
+            gain: Smoothed::new(0.0),
+            coefficients: Coefficients::default(),
+            state: vec![[0.0; 2]; channels],
+            dirty: true,
+        }
+    }
+
+    /// Specify the initial Q. Defaults to `1/√2`.
+    pub fn q(mut self, q: f32) -> Self {
+        self.q.reset(q);
+        self
+    }
+
+    /// Specify the initial gain in decibels. Defaults to `0.0`.
+    pub fn gain(mut self, db: f32) -> Self {
+        self.gain.reset(db);
+        self
+    }
+
+    fn update_coefficients(&mut self, sample_rate: u32) {
+        let sr = sample_rate as f32;
+        let hz = self.frequency.value().clamp(10.0, sr * 0.49);
+        let q = self.q.value().max(0.01);
+        let a = 10f32.powf(self.gain.value() / 40.0);
+        let w0 = 2.0 * std::f32::consts::PI * hz / sr;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * q);
+        let sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+        let (b0, b1, b2, a0, a1, a2) = match self.kind {
+            FilterKind::LowPass => {
+                let b1 = 1.0 - cos;
+                (b1 / 2.0, b1, b1 / 2.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha)
+            }
+            FilterKind::HighPass => {
+                let b1 = -(1.0 + cos);
+                (
+                    -b1 / 2.0,
+                    b1,
+                    -b1 / 2.0,
+                    1.0 + alpha,
+                    -2.0 * cos,
+                    1.0 - alpha,
+                )
+            }
+            FilterKind::BandPass => (alpha, 0.0, -alpha, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+            FilterKind::Notch => (1.0, -2.0 * cos, 1.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+            FilterKind::AllPass => (
+                1.0 - alpha,
+                -2.0 * cos,
+                1.0 + alpha,
+                1.0 + alpha,
+                -2.0 * cos,
+                1.0 - alpha,
+            ),
+            FilterKind::Peak => (
+                1.0 + alpha * a,
+                -2.0 * cos,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos,
+                1.0 - alpha / a,
+            ),
+            FilterKind::LowShelf => (
+                a * ((a + 1.0) - (a - 1.0) * cos + sqrt_a_alpha),
+                2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                a * ((a + 1.0) - (a - 1.0) * cos - sqrt_a_alpha),
+                (a + 1.0) + (a - 1.0) * cos + sqrt_a_alpha,
+                -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                (a + 1.0) + (a - 1.0) * cos - sqrt_a_alpha,
+            ),
+            FilterKind::HighShelf => (
+                a * ((a + 1.0) + (a - 1.0) * cos + sqrt_a_alpha),
+                -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                a * ((a + 1.0) + (a - 1.0) * cos - sqrt_a_alpha),
+                (a + 1.0) - (a - 1.0) * cos + sqrt_a_alpha,
+                2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                (a + 1.0) - (a - 1.0) * cos - sqrt_a_alpha,
+            ),
+        };
+        self.coefficients = Coefficients {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        };
+    }
+}
+
+impl Node for Biquad {
+    fn inputs(&self) -> usize {
+        self.channels
+    }
+
+    fn outputs(&self) -> usize {
+        self.channels
+    }
+
+    fn prepare(&mut self, sample_rate: u32) {
+        let frames = smoothing_frames(sample_rate);
+        self.frequency.set_frames(frames);
+        self.q.set_frames(frames);
+        self.gain.set_frames(frames);
+        self.dirty = true;
+    }
+
+    fn set_param(&mut self, param: Param, value: f32) {
+        match param {
+            Self::FREQUENCY => self.frequency.set(value),
+            Self::Q => self.q.set(value),
+            Self::GAIN => self.gain.set(value),
+            _ => return,
+        }
+        self.dirty = true;
+    }
+
+    fn process(&mut self, ctx: &Context, input: &[f32], output: &mut [f32]) {
+        let frames = ctx.frames as u32;
+        let smoothing =
+            self.frequency.is_smoothing() || self.q.is_smoothing() || self.gain.is_smoothing();
+        self.frequency.advance(frames);
+        self.q.advance(frames);
+        self.gain.advance(frames);
+        if smoothing || self.dirty {
+            self.update_coefficients(ctx.sample_rate);
+            self.dirty = false;
+        }
+
+        let Coefficients { b0, b1, b2, a1, a2 } = self.coefficients;
+        let ins = channels(input, ctx.frames);
+        let outs = channels_mut(output, ctx.frames);
+        for ((input, output), z) in ins.zip(outs).zip(&mut self.state) {
+            for (&x, y) in input.iter().zip(output.iter_mut()) {
+                let out = b0 * x + z[0];
+                z[0] = b1 * x - a1 * out + z[1];
+                z[1] = b2 * x - a2 * out;
+                *y = out;
+            }
+        }
+    }
+}
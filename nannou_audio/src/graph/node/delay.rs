@@ -0,0 +1,119 @@
+use super::{smoothing_frames, Context, Node, Param, Smoothed};
+
+/// A feedback delay line.
+///
+/// Changes to the delay time are smoothed, producing a brief change in pitch rather than a click.
+#[derive(Clone, Debug)]
+pub struct Delay {
+    channels: usize,
+    max_secs: f32,
+    time: Smoothed,
+    feedback: Smoothed,
+    mix: Smoothed,
+    // The delay line of each channel.
+    lines: Vec<Vec<f32>>,
+    write: usize,
+}
+
+impl Delay {
+    /// The delay time in seconds, limited to the maximum given upon construction.
+    pub const TIME: Param = Param(0);
+    /// The amount of the delayed signal fed back into the delay line.
+    pub const FEEDBACK: Param = Param(1);
+    /// The balance between the dry input (`0.0`) and the delayed signal (`1.0`).
+    pub const MIX: Param = Param(2);
+
+    /// A delay for the given number of channels supporting delay times of up to `max_secs`.
+    ///
+    /// The delay lines are allocated when the node is added to a graph.
+    pub fn new(channels: usize, max_secs: f32) -> Self {
+        let max_secs = max_secs.max(0.0);
+        Delay {
+            channels,
+            max_secs,
+            time: Smoothed::new(max_secs.min(0.25)),
+            feedback: Smoothed::new(0.3),
+            mix: Smoothed::new(0.5),
+            lines: vec![],
+            write: 0,
+        }
+    }
+
+    /// Specify the initial delay time in seconds. Defaults to `0.25`.
+    pub fn time(mut self, secs: f32) -> Self {
+        self.time.reset(secs.clamp(0.0, self.max_secs));
+        self
+    }
+
+    /// Specify the initial feedback. Defaults to `0.3`.
+    pub fn feedback(mut self, feedback: f32) -> Self {
+        self.feedback.reset(feedback);
+        self
+    }
+
+    /// Specify the initial mix. Defaults to `0.5`.
+    pub fn mix(mut self, mix: f32) -> Self {
+        self.mix.reset(mix);
+        self
+    }
+}
+
+impl Node for Delay {
+    fn inputs(&self) -> usize {
+        self.channels
+    }
+
+    fn outputs(&self) -> usize {
+        self.channels
+    }
+
+    fn prepare(&mut self, sample_rate: u32) {
+        // Two additional frames for interpolation and the write head.
+        let len = (self.max_secs * sample_rate as f32).ceil() as usize + 2;
+        self.lines = vec![vec![0.0; len]; self.channels];
+        self.write = 0;
+        // Smooth the delay time more slowly to limit the resulting change in pitch.
+        let frames = smoothing_frames(sample_rate);
+        self.time.set_frames(frames * 4);
+        self.feedback.set_frames(frames);
+        self.mix.set_frames(frames);
+    }
+
+    fn set_param(&mut self, param: Param, value: f32) {
+        match param {
+            Self::TIME => self.time.set(value.clamp(0.0, self.max_secs)),
+            Self::FEEDBACK => self.feedback.set(value),
+            Self::MIX => self.mix.set(value),
+            _ => (),
+        }
+    }
+
+    fn process(&mut self, ctx: &Context, input: &[f32], output: &mut [f32]) {
+        let frames = ctx.frames;
+        let len = match self.lines.first() {
+            Some(line) => line.len(),
+            None => {
+                output.copy_from_slice(input);
+                return;
+            }
+        };
+        let sample_rate = ctx.sample_rate as f32;
+        for i in 0..frames {
+            let delay = (self.time.tick() * sample_rate).clamp(1.0, (len - 2) as f32);
+            let feedback = self.feedback.tick();
+            let mix = self.mix.tick();
+            let read = self.write as f32 + len as f32 - delay;
+            let index = read.floor() as usize;
+            let frac = read - read.floor();
+            for (ch, line) in self.lines.iter_mut().enumerate() {
+                let x = input[ch * frames + i];
+                let a = line[index % len];
+                let b = line[(index + 1) % len];
+                let wet = a + (b - a) * frac;
+                line[self.write] = x + wet * feedback;
+                output[ch * frames + i] = x * (1.0 - mix) + wet * mix;
+            }
+            self.write = (self.write + 1) % len;
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use super::BLOCK_FRAMES;
+use super::{channels, channels_mut, smoothing_frames, Context, Node, Param, Smoothed};
+
+/// Scales its input by a smoothed gain.
+#[derive(Clone, Debug)]
+pub struct Gain {
+    channels: usize,
+    gain: Smoothed,
+}
+
+/// Sums all audio connected to it and applies a master gain.
+///
+/// All nodes sum their inputs, so the mixer is primarily useful as a bus through which many nodes
+/// are routed. The level of each input may be set via `Graph::set_gain`.
+#[derive(Clone, Debug)]
+pub struct Mixer {
+    gain: Gain,
+}
+
+/// Pans a mono input or balances a stereo input across two output channels.
+#[derive(Clone, Debug)]
+pub struct Pan {
+    inputs: usize,
+    pan: Smoothed,
+}
+
+impl Gain {
+    /// The linear gain.
+    pub const GAIN: Param = Param(0);
+
+    /// A unity gain for the given number of channels.
+    pub fn new(channels: usize) -> Self {
+        Gain {
+            channels,
+            gain: Smoothed::new(1.0),
+        }
+    }
+
+    /// Specify the initial gain. Defaults to `1.0`.
+    pub fn gain(mut self, gain: f32) -> Self {
+        self.gain.reset(gain);
+        self
+    }
+}
+
+impl Mixer {
+    /// The linear gain applied to the sum of the inputs.
+    pub const GAIN: Param = Gain::GAIN;
+
+    /// A mixer for the given number of channels.
+    pub fn new(channels: usize) -> Self {
+        Mixer {
+            gain: Gain::new(channels),
+        }
+    }
+
+    /// Specify the initial gain. Defaults to `1.0`.
+    pub fn gain(mut self, gain: f32) -> Self {
+        self.gain = self.gain.gain(gain);
+        self
+    }
+}
+
+impl Pan {
+    /// The position in the range `-1.0` (left) to `1.0` (right).
+    pub const PAN: Param = Param(0);
+
+    /// A panner for the given number of input channels, either `1` or `2`.
+    ///
+    /// Mono input is panned with an equal power law while stereo input is balanced.
+    pub fn new(inputs: usize) -> Self {
+        assert!(inputs == 1 || inputs == 2, "`Pan` supports 1 or 2 inputs");
+        Pan {
+            inputs,
+            pan: Smoothed::new(0.0),
+        }
+    }
+
+    /// Specify the initial position. Defaults to `0.0`.
+    pub fn pan(mut self, pan: f32) -> Self {
+        self.pan.reset(pan.clamp(-1.0, 1.0));
+        self
+    }
+}
+
+impl Node for Gain {
+    fn inputs(&self) -> usize {
+        self.channels
+    }
+
+    fn outputs(&self) -> usize {
+        self.channels
+    }
+
+    fn prepare(&mut self, sample_rate: u32) {
+        self.gain.set_frames(smoothing_frames(sample_rate));
+    }
+
+    fn set_param(&mut self, param: Param, value: f32) {
+        if param == Self::GAIN {
+            self.gain.set(value);
+        }
+    }
+
+    fn process(&mut self, ctx: &Context, input: &[f32], output: &mut [f32]) {
+        let frames = ctx.frames;
+        if !self.gain.is_smoothing() {
+            let gain = self.gain.value();
+            for (out, x) in output.iter_mut().zip(input) {
+                *out = x * gain;
+            }
+            return;
+        }
+        let mut gains = [0.0; BLOCK_FRAMES];
+        for gain in &mut gains[..frames] {
+            *gain = self.gain.tick();
+        }
+        for (input, output) in channels(input, frames).zip(channels_mut(output, frames)) {
+            for ((out, x), gain) in output.iter_mut().zip(input).zip(&gains) {
+                *out = x * gain;
+            }
+        }
+    }
+}
+
+impl Node for Mixer {
+    fn inputs(&self) -> usize {
+        self.gain.inputs()
+    }
+
+    fn outputs(&self) -> usize {
+        self.gain.outputs()
+    }
+
+    fn prepare(&mut self, sample_rate: u32) {
+        self.gain.prepare(sample_rate);
+    }
+
+    fn set_param(&mut self, param: Param, value: f32) {
+        self.gain.set_param(param, value);
+    }
+
+    fn process(&mut self, ctx: &Context, input: &[f32], output: &mut [f32]) {
+        self.gain.process(ctx, input, output);
+    }
+}
+
+impl Node for Pan {
+    fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    fn outputs(&self) -> usize {
+        2
+    }
+
+    fn prepare(&mut self, sample_rate: u32) {
+        self.pan.set_frames(smoothing_frames(sample_rate));
+    }
+
+    fn set_param(&mut self, param: Param, value: f32) {
+        if param == Self::PAN {
+            self.pan.set(value.clamp(-1.0, 1.0));
+        }
+    }
+
+    fn process(&mut self, ctx: &Context, input: &[f32], output: &mut [f32]) {
+        let frames = ctx.frames;
+        let (out_l, out_r) = output.split_at_mut(frames);
+        for i in 0..frames {
+            let pan = self.pan.tick();
+            if self.inputs == 1 {
+                let angle = (pan + 1.0) * std::f32::consts::FRAC_PI_4;
+                let (r, l) = angle.sin_cos();
+                out_l[i] = input[i] * l;
+                out_r[i] = input[i] * r;
+            } else {
+                out_l[i] = input[i] * (1.0 - pan).min(1.0);
+                out_r[i] = input[frames + i] * (1.0 + pan).min(1.0);
+            }
+        }
+    }
+}
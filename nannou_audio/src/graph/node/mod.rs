@@ -0,0 +1,171 @@
+//! The `Node` trait along with the nodes provided for building graphs.
+
+pub use self::adsr::Adsr;
+pub use self::biquad::{Biquad, FilterKind};
+pub use self::delay::Delay;
+pub use self::mix::{Gain, Mixer, Pan};
+pub use self::noise::{Noise, NoiseColor};
+pub use self::osc::{Oscillator, Waveform};
+pub use self::reverb::Reverb;
+
+mod adsr;
+mod biquad;
+mod delay;
+mod mix;
+mod noise;
+mod osc;
+mod reverb;
+
+/// The maximum number of frames processed by a node at once.
+///
+/// The graph divides each buffer delivered by the stream into blocks of at most this many frames.
+pub const BLOCK_FRAMES: usize = 64;
+
+/// The duration in seconds over which the provided nodes smooth parameter changes.
+pub const DEFAULT_SMOOTHING_SECS: f32 = 0.02;
+
+/// Identifies a parameter of a node.
+///
+/// Each of the provided nodes exposes its parameters as associated constants, e.g.
+/// `Oscillator::FREQUENCY`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Param(pub u32);
+
+/// Describes the block of frames being processed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Context {
+    /// The sample rate of the graph.
+    pub sample_rate: u32,
+    /// The number of frames within the block. Never greater than `BLOCK_FRAMES`.
+    pub frames: usize,
+}
+
+/// A unit of audio processing within a graph.
+///
+/// Audio is passed to and from `process` in planar layout, where the samples of channel `c`
+/// occupy the range `c * ctx.frames..(c + 1) * ctx.frames`. The input contains the sum of all
+/// audio connected to the node, converted to the node's number of input channels.
+///
+/// All methods other than `prepare` are called on the audio thread and should avoid allocating,
+/// blocking or performing I/O.
+pub trait Node: Send {
+    /// The number of channels of audio expected at the node's input. Zero for generators.
+    fn inputs(&self) -> usize;
+
+    /// The number of channels of audio produced by the node.
+    fn outputs(&self) -> usize;
+
+    /// Prepare the node for processing at the given sample rate.
+    ///
+    /// This is called on the main thread when the node is added to a graph, so it is a suitable
+    /// place to allocate any sample rate dependent state. It is only called on the audio thread
+    /// in the case that the stream's sample rate differs from that of the graph.
+    fn prepare(&mut self, _sample_rate: u32) {}
+
+    /// Apply a parameter change sent from the main thread.
+    fn set_param(&mut self, _param: Param, _value: f32) {}
+
+    /// Process a single block of audio.
+    fn process(&mut self, ctx: &Context, input: &[f32], output: &mut [f32]);
+}
+
+/// A value that ramps linearly towards its target to avoid clicks upon parameter changes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Smoothed {
+    current: f32,
+    target: f32,
+    step: f32,
+    remaining: u32,
+    frames: u32,
+}
+
+impl Smoothed {
+    /// A value that is initially at rest at the given value.
+    ///
+    /// Changes are applied immediately until a ramp duration is given via `set_frames`.
+    pub fn new(value: f32) -> Self {
+        Smoothed {
+            current: value,
+            target: value,
+            step: 0.0,
+            remaining: 0,
+            frames: 0,
+        }
+    }
+
+    /// Specify the number of frames over which subsequent changes are ramped.
+    pub fn set_frames(&mut self, frames: u32) {
+        self.frames = frames;
+    }
+
+    /// Specify the duration of subsequent ramps in seconds at the given sample rate.
+    pub fn set_secs(&mut self, secs: f32, sample_rate: u32) {
+        self.set_frames((secs * sample_rate as f32) as u32);
+    }
+
+    /// Begin ramping towards the given target.
+    pub fn set(&mut self, target: f32) {
+        self.target = target;
+        if self.frames == 0 {
+            self.reset(target);
+        } else {
+            self.step = (target - self.current) / self.frames as f32;
+            self.remaining = self.frames;
+        }
+    }
+
+    /// Jump to the given value immediately.
+    pub fn reset(&mut self, value: f32) {
+        self.current = value;
+        self.target = value;
+        self.remaining = 0;
+    }
+
+    /// The value towards which the ramp is heading.
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    /// The current value.
+    pub fn value(&self) -> f32 {
+        self.current
+    }
+
+    /// Whether or not the value is still ramping towards its target.
+    pub fn is_smoothing(&self) -> bool {
+        self.remaining > 0
+    }
+
+    /// Advance the ramp by a single frame, returning the new value.
+    #[inline]
+    pub fn tick(&mut self) -> f32 {
+        self.advance(1)
+    }
+
+    /// Advance the ramp by the given number of frames, returning the new value.
+    pub fn advance(&mut self, frames: u32) -> f32 {
+        if self.remaining > frames {
+            self.remaining -= frames;
+            self.current += self.step * frames as f32;
+        } else if self.remaining > 0 {
+            self.remaining = 0;
+            self.current = self.target;
+        }
+        self.current
+    }
+}
+
+// The number of frames over which the provided nodes smooth parameters.
+fn smoothing_frames(sample_rate: u32) -> u32 {
+    (DEFAULT_SMOOTHING_SECS * sample_rate as f32) as u32
+}
+
+// Iterate over the channels of a planar block.
+fn channels(block: &[f32], frames: usize) -> std::slice::ChunksExact<'_, f32> {
+    block.chunks_exact(frames.max(1))
+}
+
+// Mutably iterate over the channels of a planar block.
+fn channels_mut(block: &mut [f32], frames: usize) -> std::slice::ChunksExactMut<'_, f32> {
+    block.chunks_exact_mut(frames.max(1))
+}
@@ -0,0 +1,103 @@
+use super::{smoothing_frames, Context, Node, Param, Smoothed};
+
+/// The spectral colour of the noise produced by a `Noise` node.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NoiseColor {
+    /// Equal energy per Hz.
+    White,
+    /// Equal energy per octave.
+    Pink,
+}
+
+/// A mono noise generator.
+#[derive(Clone, Debug)]
+pub struct Noise {
+    color: NoiseColor,
+    amplitude: Smoothed,
+    // The state of the xorshift generator. Never zero.
+    state: u32,
+    // The state of the pinking filter.
+    pink: [f32; 7],
+}
+
+impl Noise {
+    /// The linear amplitude.
+    pub const AMPLITUDE: Param = Param(0);
+
+    /// A noise generator of the given colour.
+    pub fn new(color: NoiseColor) -> Self {
+        Noise {
+            color,
+            amplitude: Smoothed::new(1.0),
+            state: 0x9E37_79B9,
+            pink: [0.0; 7],
+        }
+    }
+
+    /// Specify the initial amplitude. Defaults to `1.0`.
+    pub fn amplitude(mut self, amplitude: f32) -> Self {
+        self.amplitude.reset(amplitude);
+        self
+    }
+
+    /// Seed the random number generator, allowing for distinct yet deterministic sequences.
+    pub fn seed(mut self, seed: u32) -> Self {
+        self.state = if seed == 0 { 0x9E37_79B9 } else { seed };
+        self
+    }
+
+    // A uniformly distributed value in the range `-1.0..1.0`.
+    fn white(&mut self) -> f32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        (x as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+
+    // Paul Kellet's refined pinking filter.
+    fn pink(&mut self) -> f32 {
+        let white = self.white();
+        let b = &mut self.pink;
+        b[0] = 0.99886 * b[0] + white * 0.055_517_9;
+        b[1] = 0.99332 * b[1] + white * 0.075_075_9;
+        b[2] = 0.96900 * b[2] + white * 0.153_852;
+        b[3] = 0.86650 * b[3] + white * 0.310_485_6;
+        b[4] = 0.55000 * b[4] + white * 0.532_952_2;
+        b[5] = -0.7616 * b[5] - white * 0.016_898;
+        let pink = b[0] + b[1] + b[2] + b[3] + b[4] + b[5] + b[6] + white * 0.5362;
+        b[6] = white * 0.115_926;
+        pink * 0.11
+    }
+}
+
+impl Node for Noise {
+    fn inputs(&self) -> usize {
+        0
+    }
+
+    fn outputs(&self) -> usize {
+        1
+    }
+
+    fn prepare(&mut self, sample_rate: u32) {
+        self.amplitude.set_frames(smoothing_frames(sample_rate));
+    }
+
+    fn set_param(&mut self, param: Param, value: f32) {
+        if param == Self::AMPLITUDE {
+            self.amplitude.set(value);
+        }
+    }
+
+    fn process(&mut self, ctx: &Context, _input: &[f32], output: &mut [f32]) {
+        for out in &mut output[..ctx.frames] {
+            let value = match self.color {
+                NoiseColor::White => self.white(),
+                NoiseColor::Pink => self.pink(),
+            };
+            *out = value * self.amplitude.tick();
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use super::{smoothing_frames, Context, Node, Param, Smoothed};
+
+/// The shape of the waveform produced by an `Oscillator`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Waveform {
+    Sine,
+    /// A band-limited sawtooth.
+    Saw,
+    /// A band-limited square.
+    Square,
+    Triangle,
+}
+
+/// A mono oscillator.
+///
+/// The sawtooth and square waveforms are band-limited via PolyBLEP to reduce aliasing.
+#[derive(Clone, Debug)]
+pub struct Oscillator {
+    waveform: Waveform,
+    frequency: Smoothed,
+    amplitude: Smoothed,
+    // The phase in the range `0.0..1.0`.
+    phase: f64,
+    sample_rate: u32,
+}
+
+impl Oscillator {
+    /// The frequency in Hz.
+    pub const FREQUENCY: Param = Param(0);
+    /// The linear amplitude.
+    pub const AMPLITUDE: Param = Param(1);
+
+    /// An oscillator producing the given waveform at the given frequency in Hz.
+    pub fn new(waveform: Waveform, hz: f32) -> Self {
+        Oscillator {
+            waveform,
+            frequency: Smoothed::new(hz),
+            amplitude: Smoothed::new(1.0),
+            phase: 0.0,
+            sample_rate: crate::stream::DEFAULT_SAMPLE_RATE,
+        }
+    }
+
+    /// Specify the initial amplitude. Defaults to `1.0`.
+    pub fn amplitude(mut self, amplitude: f32) -> Self {
+        self.amplitude.reset(amplitude);
+        self
+    }
+
+    /// Specify the initial phase in the range `0.0..1.0`. Defaults to `0.0`.
+    pub fn phase(mut self, phase: f64) -> Self {
+        self.phase = phase.rem_euclid(1.0);
+        self
+    }
+}
+
+impl Node for Oscillator {
+    fn inputs(&self) -> usize {
+        0
+    }
+
+    fn outputs(&self) -> usize {
+        1
+    }
+
+    fn prepare(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate;
+        self.frequency.set_frames(smoothing_frames(sample_rate));
+        self.amplitude.set_frames(smoothing_frames(sample_rate));
+    }
+
+    fn set_param(&mut self, param: Param, value: f32) {
+        match param {
+            Self::FREQUENCY => self.frequency.set(value),
+            Self::AMPLITUDE => self.amplitude.set(value),
+            _ => (),
+        }
+    }
+
+    fn process(&mut self, ctx: &Context, _input: &[f32], output: &mut [f32]) {
+        let sample_rate = ctx.sample_rate as f64;
+        for out in &mut output[..ctx.frames] {
+            let dt = (self.frequency.tick() as f64 / sample_rate).abs().min(0.5);
+            let t = self.phase;
+            let value = match self.waveform {
+                Waveform::Sine => (t * 2.0 * std::f64::consts::PI).sin(),
+                Waveform::Saw => 2.0 * t - 1.0 - poly_blep(t, dt),
+                Waveform::Square => {
+                    let naive = if t < 0.5 { 1.0 } else { -1.0 };
+                    naive + poly_blep(t, dt) - poly_blep((t + 0.5) % 1.0, dt)
+                }
+                Waveform::Triangle => 1.0 - 4.0 * (t - 0.5).abs(),
+            };
+            *out = value as f32 * self.amplitude.tick();
+            self.phase = (self.phase + dt) % 1.0;
+        }
+    }
+}
+
+// The polynomial band-limited step correction for a discontinuity at phase `0.0`.
+fn poly_blep(t: f64, dt: f64) -> f64 {
+    if dt <= 0.0 {
+        0.0
+    } else if t < dt {
+        let t = t / dt;
+        t + t - t * t - 1.0
+    } else if t > 1.0 - dt {
+        let t = (t - 1.0) / dt;
+        t * t + t + t + 1.0
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,205 @@
+use super::{smoothing_frames, Context, Node, Param, Smoothed};
+
+// The tunings of the Freeverb algorithm in frames at 44.1KHz.
+const COMB_TUNINGS: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+const ALLPASS_TUNINGS: [usize; 4] = [556, 441, 341, 225];
+const STEREO_SPREAD: usize = 23;
+const TUNING_SAMPLE_RATE: f32 = 44_100.0;
+const FIXED_GAIN: f32 = 0.015;
+const SCALE_WET: f32 = 3.0;
+const SCALE_DAMPING: f32 = 0.4;
+const SCALE_ROOM: f32 = 0.28;
+const OFFSET_ROOM: f32 = 0.7;
+const ALLPASS_FEEDBACK: f32 = 0.5;
+
+/// A stereo reverb based on the Freeverb algorithm.
+///
+/// Mono input is spread across both channels.
+#[derive(Clone, Debug)]
+pub struct Reverb {
+    room_size: Smoothed,
+    damping: Smoothed,
+    width: Smoothed,
+    mix: Smoothed,
+    // The parallel comb filters and series allpass filters of the left and right channels.
+    combs: [Vec<Comb>; 2],
+    allpasses: [Vec<Allpass>; 2],
+}
+
+#[derive(Clone, Debug)]
+struct Comb {
+    buffer: Vec<f32>,
+    index: usize,
+    filter_store: f32,
+}
+
+#[derive(Clone, Debug)]
+struct Allpass {
+    buffer: Vec<f32>,
+    index: usize,
+}
+
+impl Reverb {
+    /// The size of the room in the range `0.0..=1.0`, determining the decay time.
+    pub const ROOM_SIZE: Param = Param(0);
+    /// The damping of high frequencies in the range `0.0..=1.0`.
+    pub const DAMPING: Param = Param(1);
+    /// The stereo width of the reverberation in the range `0.0..=1.0`.
+    pub const WIDTH: Param = Param(2);
+    /// The balance between the dry input (`0.0`) and the reverberation (`1.0`).
+    pub const MIX: Param = Param(3);
+
+    /// A reverb with a medium sized room.
+    ///
+    /// The delay lines are allocated when the node is added to a graph.
+    pub fn new() -> Self {
+        Reverb {
+            room_size: Smoothed::new(0.5),
+            damping: Smoothed::new(0.5),
+            width: Smoothed::new(1.0),
+            mix: Smoothed::new(0.3),
+            combs: [vec![], vec![]],
+            allpasses: [vec![], vec![]],
+        }
+    }
+
+    /// Specify the initial room size. Defaults to `0.5`.
+    pub fn room_size(mut self, room_size: f32) -> Self {
+        self.room_size.reset(room_size);
+        self
+    }
+
+    /// Specify the initial damping. Defaults to `0.5`.
+    pub fn damping(mut self, damping: f32) -> Self {
+        self.damping.reset(damping);
+        self
+    }
+
+    /// Specify the initial stereo width. Defaults to `1.0`.
+    pub fn width(mut self, width: f32) -> Self {
+        self.width.reset(width);
+        self
+    }
+
+    /// Specify the initial mix. Defaults to `0.3`.
+    pub fn mix(mut self, mix: f32) -> Self {
+        self.mix.reset(mix);
+        self
+    }
+}
+
+impl Comb {
+    fn new(len: usize) -> Self {
+        Comb {
+            buffer: vec![0.0; len.max(1)],
+            index: 0,
+            filter_store: 0.0,
+        }
+    }
+
+    #[inline]
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let output = self.buffer[self.index];
+        self.filter_store = output * (1.0 - damping) + self.filter_store * damping;
+        self.buffer[self.index] = input + self.filter_store * feedback;
+        self.index = (self.index + 1) % self.buffer.len();
+        output
+    }
+}
+
+impl Allpass {
+    fn new(len: usize) -> Self {
+        Allpass {
+            buffer: vec![0.0; len.max(1)],
+            index: 0,
+        }
+    }
+
+    #[inline]
+    fn process(&mut self, input: f32) -> f32 {
+        let delayed = self.buffer[self.index];
+        self.buffer[self.index] = input + delayed * ALLPASS_FEEDBACK;
+        self.index = (self.index + 1) % self.buffer.len();
+        delayed - input
+    }
+}
+
+impl Default for Reverb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for Reverb {
+    fn inputs(&self) -> usize {
+        2
+    }
+
+    fn outputs(&self) -> usize {
+        2
+    }
+
+    fn prepare(&mut self, sample_rate: u32) {
+        let scale = sample_rate as f32 / TUNING_SAMPLE_RATE;
+        let len = |frames: usize| (frames as f32 * scale).round() as usize;
+        for (ch, spread) in [0, STEREO_SPREAD].iter().enumerate() {
+            self.combs[ch] = COMB_TUNINGS
+                .iter()
+                .map(|&t| Comb::new(len(t + spread)))
+                .collect();
+            self.allpasses[ch] = ALLPASS_TUNINGS
+                .iter()
+                .map(|&t| Allpass::new(len(t + spread)))
+                .collect();
+        }
+        let frames = smoothing_frames(sample_rate);
+        self.room_size.set_frames(frames);
+        self.damping.set_frames(frames);
+        self.width.set_frames(frames);
+        self.mix.set_frames(frames);
+    }
+
+    fn set_param(&mut self, param: Param, value: f32) {
+        let value = value.clamp(0.0, 1.0);
+        match param {
+            Self::ROOM_SIZE => self.room_size.set(value),
+            Self::DAMPING => self.damping.set(value),
+            Self::WIDTH => self.width.set(value),
+            Self::MIX => self.mix.set(value),
+            _ => (),
+        }
+    }
+
+    fn process(&mut self, ctx: &Context, input: &[f32], output: &mut [f32]) {
+        let frames = ctx.frames;
+        let n = frames as u32;
+        let feedback = self.room_size.advance(n) * SCALE_ROOM + OFFSET_ROOM;
+        let damping = self.damping.advance(n) * SCALE_DAMPING;
+        let width = self.width.advance(n);
+        let mix = self.mix.advance(n);
+        let wet = mix * SCALE_WET;
+        let dry = 1.0 - mix;
+        let wet1 = wet * (width / 2.0 + 0.5);
+        let wet2 = wet * ((1.0 - width) / 2.0);
+
+        let (in_l, in_r) = input.split_at(frames);
+        let (out_l, out_r) = output.split_at_mut(frames);
+        for i in 0..frames {
+            let (l, r) = (in_l[i], in_r[i]);
+            let x = (l + r) * FIXED_GAIN;
+            let mut wet_out = [0.0; 2];
+            for (ch, out) in wet_out.iter_mut().enumerate() {
+                let mut sum = 0.0;
+                for comb in &mut self.combs[ch] {
+                    sum += comb.process(x, feedback, damping);
+                }
+                for allpass in &mut self.allpasses[ch] {
+                    sum = allpass.process(sum);
+                }
+                *out = sum;
+            }
+            out_l[i] = wet_out[0] * wet1 + wet_out[1] * wet2 + l * dry;
+            out_r[i] = wet_out[1] * wet1 + wet_out[0] * wet2 + r * dry;
+        }
+    }
+}
@@ -1,23 +1,39 @@
 //! The nannou audio API and implementation.
 //!
 //! - [**Host**](./Host.html) - top-level access to device enumeration and spawning streams.
-//! - [**Stream**](./stream/struct.Stream.html) - for managing an input/output audio stream. This may be
-//!   created via the **App**'s **Audio** API.
+//! - [**Stream**](./stream/struct.Stream.html) - for managing an input, output or duplex audio
+//!   stream. This may be created via the **App**'s **Audio** API.
 //! - [**Buffer**](./buffer/struct.Buffer.html) - contains audio data, either for reading or writing.
 //!   This is passed to the `capture` or `render` function for each stream.
 //! - [**Devices**](./device/struct.Devices.html) - for enumerating all audio devices on the system.
 //! - [**Device**](./device/struct.Device.html) - for querying information about supported stream
 //!   formats or for creating a stream targeted towards a specific audio device.
+//! - [**DeviceWatcher**](./device/struct.DeviceWatcher.html) - for being notified of devices being
+//!   connected or disconnected. Streams may also opt in to
+//!   [**Recovery**](./stream/recovery/struct.Recovery.html) from the loss of their device.
 //! - [**Receiver**](./receiver/struct.Receiver.html) and
 //!   [**Requester**](./requester/struct.Requester.html) for buffering input and output streams that
 //!   may deliver buffers of inconsistent sizes into a stream of consistently sized buffers.
+//! - [**offline::Host**](./offline/struct.Host.html) - for rendering output streams on demand
+//!   without an audio device, into memory or to a file.
+//! - [**Graph**](./graph/struct.Graph.html) - for building synthesis and processing graphs of
+//!   oscillators, envelopes, filters and effects that render within an output stream.
+//! - [**Analyzer**](./analysis/struct.Analyzer.html) - for analysing the spectrum, loudness,
+//!   onsets, tempo and pitch of a stream for audio-reactive visuals.
+//! - [**Player**](./player/struct.Player.html) - for decoding audio files and mixing polyphonic
+//!   sample playback within an output stream.
+//! - [**Recorder**](./recorder/struct.Recorder.html) - for recording the audio of any input or
+//!   output stream to WAV or FLAC files.
+//! - [**Spatializer**](./spatial/struct.Spatializer.html) - for positioning sources in 3D space and
+//!   rendering them to multichannel speaker layouts or binaurally for headphones.
 
 use cpal::traits::HostTrait;
 use std::marker::PhantomData;
 use std::sync::Arc;
+use std::time::Duration;
 
 pub use self::buffer::Buffer;
-pub use self::device::{Device, Devices};
+pub use self::device::{Device, DeviceEvent, DeviceWatcher, Devices};
 pub use self::receiver::Receiver;
 pub use self::requester::Requester;
 pub use self::stream::Stream;
@@ -32,10 +48,16 @@ pub use cpal::{
 };
 pub use dasp_sample;
 
+pub mod analysis;
 pub mod buffer;
 pub mod device;
+pub mod graph;
+pub mod offline;
+pub mod player;
 pub mod receiver;
+pub mod recorder;
 pub mod requester;
+pub mod spatial;
 pub mod stream;
 
 /// The top-level audio API, for enumerating devices and spawning input/output streams.
@@ -102,6 +124,19 @@ impl Host {
             .map(|device| Device { device })
     }
 
+    /// Watch for audio devices being connected or disconnected and for changes to the default
+    /// devices.
+    ///
+    /// Devices are enumerated every `DeviceWatcher::DEFAULT_INTERVAL`.
+    pub fn watch_devices(&self) -> DeviceWatcher {
+        self.watch_devices_with_interval(DeviceWatcher::DEFAULT_INTERVAL)
+    }
+
+    /// Watch for changes to the available audio devices, enumerating them at the given interval.
+    pub fn watch_devices_with_interval(&self, interval: Duration) -> DeviceWatcher {
+        DeviceWatcher::spawn(self.host.id(), interval)
+    }
+
     /// Begin building a new input audio stream.
     ///
     /// If this is the first time a stream has been created, this method will spawn the
@@ -126,7 +161,23 @@ impl Host {
         }
     }
 
-    // Builder initialisation shared between input and output streams.
+    /// Begin building a new duplex audio stream, linking an input stream to an output stream.
+    ///
+    /// Each buffer of captured input is delivered along with the buffer of output to be rendered
+    /// for the same period. The input is resampled as necessary to compensate for any drift
+    /// between the clocks of the input and output devices.
+    pub fn new_duplex_stream<M, S>(&self, model: M) -> stream::duplex::BuilderInit<M, S> {
+        stream::duplex::Builder {
+            process: stream::duplex::default_process_fn,
+            error: stream::default_error_fn,
+            input_device: None,
+            input_channels: None,
+            latency_frames: None,
+            builder: self.new_stream(model),
+        }
+    }
+
+    // Builder initialisation shared between input, output and duplex streams.
     //
     // If this is the first time a stream has been created, this method will spawn the
     // `cpal::EventLoop::run` method on its own thread, ready to run built streams.
@@ -139,6 +190,8 @@ impl Host {
             frames_per_buffer: None,
             device_buffer_size: None,
             device: None,
+            tap: None,
+            recovery: None,
             sample_format: PhantomData,
         }
     }
@@ -0,0 +1,381 @@
+//! Rendering output streams offline, without an audio device.
+//!
+//! The offline **Host** spawns output streams that are not driven by an audio device. Instead,
+//! the stream's render function is called on demand as buffers are requested, either to fill
+//! buffers in memory or to write a WAV or FLAC file. Audio may be rendered far faster than real
+//! time and the result is entirely deterministic, making offline streams useful for testing DSP
+//! code and for rendering audio in sync with captured video frames.
+//!
+//! ```
+//! use nannou_audio::{offline, Buffer};
+//!
+//! struct Audio {
+//!     phase: f32,
+//! }
+//!
+//! fn render(audio: &mut Audio, buffer: &mut Buffer) {
+//!     for frame in buffer.frames_mut() {
+//!         let amp = (audio.phase * 2.0 * std::f32::consts::PI).sin();
+//!         audio.phase = (audio.phase + 440.0 / 44_100.0) % 1.0;
+//!         for sample in frame {
+//!             *sample = amp;
+//!         }
+//!     }
+//! }
+//!
+//! let host = offline::Host::new();
+//! let mut stream = host
+//!     .new_output_stream(Audio { phase: 0.0 })
+//!     .render(render)
+//!     .sample_rate(44_100)
+//!     .channels(1)
+//!     .build();
+//!
+//! // Render one second of audio into memory.
+//! let samples = stream.render(44_100);
+//! assert_eq!(samples.len(), 44_100);
+//! assert_eq!(stream.rendered_frames(), 44_100);
+//! ```
+//!
+//! When rendering alongside video, `render_until_secs` may be called once per video frame with the
+//! time of the frame. As the position is tracked in frames, the audio never drifts from the video
+//! regardless of how the frame duration rounds.
+
+use crate::recorder::{self, FileWriter, RecordError, SampleFormat};
+use crate::{stream, Buffer, Requester};
+use dasp_sample::{Sample, ToSample};
+use std::marker::PhantomData;
+use std::path::Path;
+
+/// The number of channels used by offline streams if unspecified.
+pub const DEFAULT_CHANNELS: usize = 2;
+
+/// Spawns offline output streams.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Host {
+    _private: (),
+}
+
+/// A type used for building an offline output stream.
+pub struct Builder<M, FR, S = f32> {
+    model: M,
+    render: FR,
+    sample_rate: Option<u32>,
+    channels: Option<usize>,
+    frames_per_buffer: Option<usize>,
+    sample_format: PhantomData<S>,
+}
+
+/// The builder when first initialised.
+pub type BuilderInit<M, S = f32> = Builder<M, stream::output::DefaultRenderFn<M, S>, S>;
+
+/// An output stream whose buffers are rendered on demand.
+///
+/// As with a regular output stream, the render function is always called with buffers of
+/// `frames_per_buffer` frames, regardless of the number of frames requested.
+pub struct Stream<M, S = f32> {
+    model: Option<M>,
+    render: Box<dyn stream::output::RenderFn<M, S> + Send>,
+    requester: Requester<S>,
+    sample_rate: u32,
+    channels: usize,
+    frames_per_buffer: usize,
+    rendered_frames: u64,
+    recording: Option<Recording>,
+    sample_format: Option<SampleFormat>,
+    // An error that occurred while writing the recording, returned by `stop_recording`.
+    record_error: Option<RecordError>,
+}
+
+// The file being written by a stream.
+struct Recording {
+    writer: FileWriter,
+    // Intermediary buffer for converting samples to `f32`.
+    samples: Vec<f32>,
+    frames: u64,
+}
+
+impl Host {
+    /// Initialise the offline host.
+    pub fn new() -> Self {
+        Host { _private: () }
+    }
+
+    /// Begin building a new offline output stream.
+    pub fn new_output_stream<M, S>(&self, model: M) -> BuilderInit<M, S> {
+        Builder {
+            model,
+            render: stream::output::default_render_fn,
+            sample_rate: None,
+            channels: None,
+            frames_per_buffer: None,
+            sample_format: PhantomData,
+        }
+    }
+}
+
+impl<M, FR, S> Builder<M, FR, S> {
+    /// Specify the render function to use for rendering the model to the buffer.
+    pub fn render<GR>(self, render: GR) -> Builder<M, GR, S> {
+        let Builder {
+            model,
+            sample_rate,
+            channels,
+            frames_per_buffer,
+            sample_format,
+            ..
+        } = self;
+        Builder {
+            model,
+            render,
+            sample_rate,
+            channels,
+            frames_per_buffer,
+            sample_format,
+        }
+    }
+
+    /// The rate at which the stream is rendered. Defaults to `stream::DEFAULT_SAMPLE_RATE`.
+    pub fn sample_rate(mut self, sample_rate: u32) -> Self {
+        assert!(sample_rate > 0);
+        self.sample_rate = Some(sample_rate);
+        self
+    }
+
+    /// The number of channels of the stream. Defaults to `DEFAULT_CHANNELS`.
+    pub fn channels(mut self, channels: usize) -> Self {
+        assert!(channels > 0);
+        self.channels = Some(channels);
+        self
+    }
+
+    /// The number of frames in each buffer passed to the render function.
+    pub fn frames_per_buffer(mut self, frames_per_buffer: usize) -> Self {
+        assert!(frames_per_buffer > 0);
+        self.frames_per_buffer = Some(frames_per_buffer);
+        self
+    }
+
+    pub fn build(self) -> Stream<M, S>
+    where
+        S: 'static + Sample,
+        M: 'static,
+        FR: 'static + stream::output::RenderFn<M, S> + Send,
+    {
+        let Builder {
+            model,
+            render,
+            sample_rate,
+            channels,
+            frames_per_buffer,
+            ..
+        } = self;
+        let sample_rate = sample_rate.unwrap_or(stream::DEFAULT_SAMPLE_RATE);
+        let channels = channels.unwrap_or(DEFAULT_CHANNELS);
+        let frames_per_buffer = frames_per_buffer.unwrap_or(Buffer::<S>::DEFAULT_LEN_FRAMES);
+        let requester = Requester::new(frames_per_buffer, channels);
+        Stream {
+            model: Some(model),
+            render: Box::new(render),
+            requester,
+            sample_rate,
+            channels,
+            frames_per_buffer,
+            rendered_frames: 0,
+            recording: None,
+            sample_format: None,
+            record_error: None,
+        }
+    }
+}
+
+impl<M, S> Stream<M, S>
+where
+    S: Sample + ToSample<f32>,
+{
+    /// The rate at which the stream is rendered.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// The number of channels of the stream.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// The number of frames in each buffer passed to the render function.
+    pub fn frames_per_buffer(&self) -> usize {
+        self.frames_per_buffer
+    }
+
+    /// The total number of frames rendered since the stream was built.
+    pub fn rendered_frames(&self) -> u64 {
+        self.rendered_frames
+    }
+
+    /// The total duration rendered since the stream was built in seconds.
+    pub fn rendered_secs(&self) -> f64 {
+        self.rendered_frames as f64 / self.sample_rate as f64
+    }
+
+    /// A reference to the stream's model.
+    pub fn model(&self) -> &M {
+        self.model.as_ref().unwrap()
+    }
+
+    /// A mutable reference to the stream's model.
+    pub fn model_mut(&mut self) -> &mut M {
+        self.model.as_mut().unwrap()
+    }
+
+    /// Consume the stream, returning the model.
+    ///
+    /// Any recording in progress is finalised first.
+    pub fn into_model(mut self) -> M {
+        self.stop_recording().ok();
+        self.model.take().unwrap()
+    }
+
+    /// Apply the given update to the model.
+    ///
+    /// As the stream is only rendered on demand, the update is applied immediately. This is
+    /// provided for parity with `stream::Stream::send`.
+    pub fn send<F>(&mut self, update: F)
+    where
+        F: FnOnce(&mut M),
+    {
+        update(self.model_mut());
+    }
+
+    /// Fill the given interleaved buffer with the next frames of the stream.
+    ///
+    /// `Panic!`s if the length of `output` is not a multiple of the number of channels.
+    pub fn fill(&mut self, output: &mut [S]) {
+        assert_eq!(output.len() % self.channels, 0);
+        let model = self.model.take().unwrap();
+        let model = self.requester.fill_buffer(
+            model,
+            &self.render,
+            output,
+            self.channels,
+            self.sample_rate,
+        );
+        self.model = Some(model);
+        self.rendered_frames += (output.len() / self.channels) as u64;
+        self.write_recording(output);
+    }
+
+    /// Render the next `frames` frames of the stream, returning the interleaved samples.
+    pub fn render(&mut self, frames: usize) -> Vec<S> {
+        let mut samples = vec![S::EQUILIBRIUM; frames * self.channels];
+        self.fill(&mut samples);
+        samples
+    }
+
+    /// Render all frames up to the given frame index, returning the interleaved samples.
+    ///
+    /// Returns no samples if the stream has already been rendered beyond `frame`.
+    pub fn render_until(&mut self, frame: u64) -> Vec<S> {
+        let frames = frame.saturating_sub(self.rendered_frames);
+        self.render(frames as usize)
+    }
+
+    /// Render all frames up to the given time in seconds, returning the interleaved samples.
+    ///
+    /// The target frame is rounded to the nearest whole frame, so calling this with the time of
+    /// each video frame yields audio that stays in sync with the video.
+    pub fn render_until_secs(&mut self, secs: f64) -> Vec<S> {
+        let frame = (secs.max(0.0) * self.sample_rate as f64).round() as u64;
+        self.render_until(frame)
+    }
+
+    /// Specify the format of the samples written by subsequent recordings.
+    ///
+    /// By default, WAV files are written with 32-bit float samples and FLAC files with 24-bit
+    /// integer samples.
+    pub fn set_sample_format(&mut self, sample_format: SampleFormat) {
+        self.sample_format = Some(sample_format);
+    }
+
+    /// Whether or not rendered audio is currently being written to a file.
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Begin writing all subsequently rendered audio to the file at the given path.
+    ///
+    /// The format is determined by the file extension, either `wav` or `flac`. Unlike the
+    /// `Recorder`, files are written on the calling thread so no audio is ever dropped.
+    pub fn record<P>(&mut self, path: P) -> Result<(), RecordError>
+    where
+        P: AsRef<Path>,
+    {
+        if self.recording.is_some() {
+            return Err(RecordError::AlreadyRecording);
+        }
+        let spec = recorder::file_spec(path.as_ref(), self.sample_format)?;
+        let writer = FileWriter::create(&spec, self.channels, self.sample_rate)?;
+        self.recording = Some(Recording {
+            writer,
+            samples: Vec::new(),
+            frames: 0,
+        });
+        Ok(())
+    }
+
+    /// Stop recording and finalise the file, returning the number of frames written.
+    ///
+    /// If an error occurred while writing the file during rendering, the recording is stopped at
+    /// that point and the error is returned here.
+    pub fn stop_recording(&mut self) -> Result<u64, RecordError> {
+        if let Some(err) = self.record_error.take() {
+            return Err(err);
+        }
+        let recording = self.recording.take().ok_or(RecordError::NotRecording)?;
+        recording.writer.finalize()?;
+        Ok(recording.frames)
+    }
+
+    /// Render the next `frames` frames of the stream to the file at the given path.
+    pub fn render_to_file<P>(&mut self, path: P, frames: usize) -> Result<(), RecordError>
+    where
+        P: AsRef<Path>,
+    {
+        self.record(path)?;
+        let mut buffer = vec![S::EQUILIBRIUM; self.frames_per_buffer * self.channels];
+        let mut remaining = frames;
+        while remaining > 0 {
+            let n = std::cmp::min(remaining, self.frames_per_buffer);
+            self.fill(&mut buffer[..n * self.channels]);
+            remaining -= n;
+        }
+        self.stop_recording().map(|_| ())
+    }
+
+    // Write the given samples to the current recording, if any.
+    fn write_recording(&mut self, output: &[S]) {
+        let recording = match self.recording {
+            None => return,
+            Some(ref mut recording) => recording,
+        };
+        recording.samples.clear();
+        recording
+            .samples
+            .extend(output.iter().map(|s| s.to_sample::<f32>()));
+        match recording.writer.write_samples(&recording.samples) {
+            Ok(()) => recording.frames += (output.len() / self.channels) as u64,
+            Err(err) => {
+                self.recording = None;
+                self.record_error = Some(err);
+            }
+        }
+    }
+}
+
+impl<M, S> Drop for Stream<M, S> {
+    fn drop(&mut self) {
+        if let Some(recording) = self.recording.take() {
+            recording.writer.finalize().ok();
+        }
+    }
+}
@@ -0,0 +1,442 @@
+//! Decoding of WAV, FLAC, OGG Vorbis and MP3 audio files to interleaved `f32` samples.
+//!
+//! Each format may be disabled via its respective cargo feature (`wav`, `flac`, `ogg` and `mp3`).
+
+use std::fs::File;
+use std::io::{self, BufReader, Cursor, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// The audio file formats supported by the `Decoder`.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Format {
+    Wav,
+    Flac,
+    Ogg,
+    Mp3,
+}
+
+/// Decodes an audio file to interleaved `f32` samples in the range `-1.0..=1.0`.
+pub struct Decoder {
+    source: Source,
+    format: Format,
+    channels: usize,
+    sample_rate: u32,
+    len_frames: Option<u64>,
+    position: u64,
+    inner: Inner,
+    // Samples that have been decoded but not yet read.
+    pending: Vec<f32>,
+    pending_start: usize,
+}
+
+/// Errors that might occur while decoding an audio file.
+#[derive(Debug, Error)]
+pub enum DecodeError {
+    #[error("failed to read audio data: {err}")]
+    Io {
+        #[from]
+        err: io::Error,
+    },
+    #[error("unsupported or unrecognised audio format")]
+    UnsupportedFormat,
+    #[error("the audio data has no channels")]
+    NoChannels,
+    #[cfg(feature = "wav")]
+    #[error("failed to decode WAV data: {err}")]
+    Wav {
+        #[from]
+        err: hound::Error,
+    },
+    #[cfg(feature = "flac")]
+    #[error("failed to decode FLAC data: {err}")]
+    Flac {
+        #[from]
+        err: claxon::Error,
+    },
+    #[cfg(feature = "ogg")]
+    #[error("failed to decode OGG Vorbis data: {err}")]
+    Ogg {
+        #[from]
+        err: lewton::VorbisError,
+    },
+    #[cfg(feature = "mp3")]
+    #[error("failed to decode MP3 data: {err}")]
+    Mp3 {
+        #[from]
+        err: minimp3::Error,
+    },
+}
+
+// Where the encoded data is read from, retained so that the decoder may be re-opened for seeking.
+#[derive(Clone)]
+enum Source {
+    Path(PathBuf),
+    Bytes(Arc<[u8]>),
+}
+
+trait ReadSeek: Read + Seek + Send {}
+
+type Reader = BufReader<Box<dyn ReadSeek>>;
+
+enum Inner {
+    #[cfg(feature = "wav")]
+    Wav(hound::WavReader<Reader>),
+    #[cfg(feature = "flac")]
+    Flac {
+        reader: claxon::FlacReader<Reader>,
+        buffer: Vec<i32>,
+    },
+    #[cfg(feature = "ogg")]
+    Ogg(Box<lewton::inside_ogg::OggStreamReader<Reader>>),
+    #[cfg(feature = "mp3")]
+    Mp3(minimp3::Decoder<Reader>),
+}
+
+impl Format {
+    /// Determine the format from the first bytes of the encoded data.
+    pub fn from_magic(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => Some(Format::Wav),
+            [b'f', b'L', b'a', b'C', ..] => Some(Format::Flac),
+            [b'O', b'g', b'g', b'S', ..] => Some(Format::Ogg),
+            [b'I', b'D', b'3', ..] => Some(Format::Mp3),
+            [0xFF, b, ..] if b & 0xE0 == 0xE0 => Some(Format::Mp3),
+            _ => None,
+        }
+    }
+
+    /// Determine the format from the extension of the given path.
+    pub fn from_path<P>(path: P) -> Option<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let ext = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match &ext[..] {
+            "wav" | "wave" => Some(Format::Wav),
+            "flac" => Some(Format::Flac),
+            "ogg" | "oga" => Some(Format::Ogg),
+            "mp3" => Some(Format::Mp3),
+            _ => None,
+        }
+    }
+}
+
+impl Decoder {
+    /// Open the audio file at the given path.
+    ///
+    /// The format is determined from the contents of the file, falling back to the file
+    /// extension.
+    pub fn open<P>(path: P) -> Result<Self, DecodeError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let mut magic = [0u8; 12];
+        let mut file = File::open(path)?;
+        let n = read_up_to(&mut file, &mut magic)?;
+        let format = Format::from_magic(&magic[..n])
+            .or_else(|| Format::from_path(path))
+            .ok_or(DecodeError::UnsupportedFormat)?;
+        Self::new(Source::Path(path.to_path_buf()), format)
+    }
+
+    /// Decode the given encoded bytes, e.g. the contents of an audio file included in the binary.
+    pub fn from_bytes<B>(bytes: B) -> Result<Self, DecodeError>
+    where
+        B: Into<Arc<[u8]>>,
+    {
+        let bytes = bytes.into();
+        let format = Format::from_magic(&bytes).ok_or(DecodeError::UnsupportedFormat)?;
+        Self::new(Source::Bytes(bytes), format)
+    }
+
+    fn new(source: Source, format: Format) -> Result<Self, DecodeError> {
+        let mut pending = vec![];
+        let (inner, channels, sample_rate, len_frames) = open(&source, format, &mut pending)?;
+        if channels == 0 {
+            return Err(DecodeError::NoChannels);
+        }
+        Ok(Decoder {
+            source,
+            format,
+            channels,
+            sample_rate,
+            len_frames,
+            position: 0,
+            inner,
+            pending,
+            pending_start: 0,
+        })
+    }
+
+    /// The format of the encoded data.
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// The number of channels per frame.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// The sample rate of the audio in Hz.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// The total length of the audio in frames, if known ahead of decoding.
+    ///
+    /// This is always known for WAV and usually known for FLAC. For OGG and MP3 this is `None`.
+    pub fn len_frames(&self) -> Option<u64> {
+        self.len_frames
+    }
+
+    /// The index of the next frame that will be read.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Decode up to `max_frames` frames, appending the interleaved samples to `out`.
+    ///
+    /// Returns the number of frames that were appended. `0` indicates the end of the audio.
+    pub fn read(&mut self, out: &mut Vec<f32>, max_frames: usize) -> Result<usize, DecodeError> {
+        let max_samples = max_frames * self.channels;
+        let start_len = out.len();
+        while out.len() - start_len < max_samples {
+            if self.pending_start >= self.pending.len() {
+                self.pending.clear();
+                self.pending_start = 0;
+                if !self.decode_next()? {
+                    break;
+                }
+                continue;
+            }
+            let wanted = max_samples - (out.len() - start_len);
+            let available = &self.pending[self.pending_start..];
+            let n = std::cmp::min(wanted, available.len());
+            out.extend_from_slice(&available[..n]);
+            self.pending_start += n;
+        }
+        let frames = (out.len() - start_len) / self.channels;
+        self.position += frames as u64;
+        Ok(frames)
+    }
+
+    /// Decode the remainder of the audio to a buffer of interleaved samples.
+    pub fn read_to_end(&mut self) -> Result<Vec<f32>, DecodeError> {
+        let capacity = self
+            .len_frames
+            .map(|len| len.saturating_sub(self.position) as usize * self.channels)
+            .unwrap_or(0);
+        let mut samples = Vec::with_capacity(capacity);
+        while self.read(&mut samples, 4096)? > 0 {}
+        Ok(samples)
+    }
+
+    /// Seek to the given frame.
+    ///
+    /// WAV data is seeked directly. Other formats are decoded up to the target frame, re-opening
+    /// the source first if the target lies behind the current position.
+    pub fn seek(&mut self, frame: u64) -> Result<(), DecodeError> {
+        self.pending.clear();
+        self.pending_start = 0;
+        #[cfg(feature = "wav")]
+        #[allow(irrefutable_let_patterns)]
+        {
+            if let Inner::Wav(ref mut reader) = self.inner {
+                let frame = std::cmp::min(frame, reader.duration() as u64);
+                reader.seek(frame as u32)?;
+                self.position = frame;
+                return Ok(());
+            }
+        }
+        if frame < self.position {
+            self.reopen()?;
+        }
+        // Decode and discard frames until reaching the target.
+        let mut discard = Vec::with_capacity(4096 * self.channels);
+        while self.position < frame {
+            discard.clear();
+            let n = std::cmp::min(frame - self.position, 4096) as usize;
+            if self.read(&mut discard, n)? == 0 {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    // Re-open the decoder from the beginning of the source.
+    fn reopen(&mut self) -> Result<(), DecodeError> {
+        self.pending.clear();
+        self.pending_start = 0;
+        let (inner, _, _, _) = open(&self.source, self.format, &mut self.pending)?;
+        self.inner = inner;
+        self.position = 0;
+        Ok(())
+    }
+
+    // Decode the next chunk of samples into `pending`. Returns `false` at the end of the audio.
+    #[cfg_attr(
+        not(any(feature = "wav", feature = "flac", feature = "ogg", feature = "mp3")),
+        allow(unreachable_code, unused_variables)
+    )]
+    fn decode_next(&mut self) -> Result<bool, DecodeError> {
+        let pending = &mut self.pending;
+        match self.inner {
+            #[cfg(feature = "wav")]
+            Inner::Wav(ref mut reader) => {
+                let spec = reader.spec();
+                let n = 1024 * spec.channels as usize;
+                match spec.sample_format {
+                    hound::SampleFormat::Float => {
+                        for s in reader.samples::<f32>().take(n) {
+                            pending.push(s?);
+                        }
+                    }
+                    hound::SampleFormat::Int => {
+                        let scale = int_scale(spec.bits_per_sample as u32);
+                        for s in reader.samples::<i32>().take(n) {
+                            pending.push(s? as f32 * scale);
+                        }
+                    }
+                }
+            }
+            #[cfg(feature = "flac")]
+            Inner::Flac {
+                ref mut reader,
+                ref mut buffer,
+            } => {
+                let scale = int_scale(reader.streaminfo().bits_per_sample);
+                let block = reader.blocks().read_next_or_eof(std::mem::take(buffer))?;
+                if let Some(block) = block {
+                    for i in 0..block.duration() {
+                        for ch in 0..block.channels() {
+                            pending.push(block.sample(ch, i) as f32 * scale);
+                        }
+                    }
+                    *buffer = block.into_buffer();
+                }
+            }
+            #[cfg(feature = "ogg")]
+            Inner::Ogg(ref mut reader) => {
+                // Skip packets that contain no audio, e.g. the first packet.
+                while let Some(packet) = reader.read_dec_packet_itl()? {
+                    if !packet.is_empty() {
+                        pending.extend(packet.into_iter().map(|s| s as f32 / 32_768.0));
+                        break;
+                    }
+                }
+            }
+            #[cfg(feature = "mp3")]
+            Inner::Mp3(ref mut decoder) => loop {
+                match decoder.next_frame() {
+                    Ok(frame) => {
+                        pending.extend(frame.data.iter().map(|&s| s as f32 / 32_768.0));
+                        if !frame.data.is_empty() {
+                            break;
+                        }
+                    }
+                    Err(minimp3::Error::Eof) => break,
+                    Err(minimp3::Error::SkippedData) => continue,
+                    Err(err) => return Err(err.into()),
+                }
+            },
+        }
+        // Ensure only whole frames are yielded.
+        let whole = pending.len() - pending.len() % self.channels;
+        pending.truncate(whole);
+        Ok(!pending.is_empty())
+    }
+}
+
+impl Source {
+    fn reader(&self) -> io::Result<Reader> {
+        let reader: Box<dyn ReadSeek> = match *self {
+            Source::Path(ref path) => Box::new(File::open(path)?),
+            Source::Bytes(ref bytes) => Box::new(Cursor::new(bytes.clone())),
+        };
+        Ok(BufReader::new(reader))
+    }
+}
+
+impl<T> ReadSeek for T where T: Read + Seek + Send {}
+
+// Open the decoder for the given format. Any samples decoded while reading the stream's
+// properties are written to `pending`.
+#[allow(unused_variables)]
+fn open(
+    source: &Source,
+    format: Format,
+    pending: &mut Vec<f32>,
+) -> Result<(Inner, usize, u32, Option<u64>), DecodeError> {
+    let mut reader = source.reader()?;
+    reader.seek(SeekFrom::Start(0))?;
+    match format {
+        #[cfg(feature = "wav")]
+        Format::Wav => {
+            let reader = hound::WavReader::new(reader)?;
+            let spec = reader.spec();
+            let len = reader.duration() as u64;
+            let inner = Inner::Wav(reader);
+            Ok((inner, spec.channels as usize, spec.sample_rate, Some(len)))
+        }
+        #[cfg(feature = "flac")]
+        Format::Flac => {
+            let reader = claxon::FlacReader::new(reader)?;
+            let info = reader.streaminfo();
+            let buffer = vec![];
+            let inner = Inner::Flac { reader, buffer };
+            Ok((
+                inner,
+                info.channels as usize,
+                info.sample_rate,
+                info.samples,
+            ))
+        }
+        #[cfg(feature = "ogg")]
+        Format::Ogg => {
+            let reader = lewton::inside_ogg::OggStreamReader::new(reader)?;
+            let channels = reader.ident_hdr.audio_channels as usize;
+            let sample_rate = reader.ident_hdr.audio_sample_rate;
+            Ok((Inner::Ogg(Box::new(reader)), channels, sample_rate, None))
+        }
+        #[cfg(feature = "mp3")]
+        Format::Mp3 => {
+            // The stream properties are only known after decoding the first frame.
+            let mut decoder = minimp3::Decoder::new(reader);
+            let frame = loop {
+                match decoder.next_frame() {
+                    Ok(frame) => break frame,
+                    Err(minimp3::Error::SkippedData) => continue,
+                    Err(minimp3::Error::Eof) => return Err(DecodeError::UnsupportedFormat),
+                    Err(err) => return Err(err.into()),
+                }
+            };
+            pending.extend(frame.data.iter().map(|&s| s as f32 / 32_768.0));
+            let inner = Inner::Mp3(decoder);
+            Ok((inner, frame.channels, frame.sample_rate as u32, None))
+        }
+        #[allow(unreachable_patterns)]
+        _ => Err(DecodeError::UnsupportedFormat),
+    }
+}
+
+// The factor for converting a signed integer sample with the given bit depth to `f32`.
+#[allow(dead_code)]
+fn int_scale(bits_per_sample: u32) -> f32 {
+    1.0 / (1u64 << (bits_per_sample.max(1) - 1)) as f32
+}
+
+// Read as many bytes as are available up to the length of `buf`.
+fn read_up_to<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..])? {
+            0 => break,
+            n => read += n,
+        }
+    }
+    Ok(read)
+}
@@ -0,0 +1,313 @@
+use super::decode::{DecodeError, Decoder};
+use ringbuf::{Consumer, Producer, RingBuffer};
+use std::path::Path;
+use std::sync::atomic::{self, AtomicBool, AtomicU64};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+/// An audio file streamed from disk.
+///
+/// The file is decoded ahead of playback on a background thread into a fixed number of
+/// pre-allocated blocks that are handed to the audio thread via lock-free queues. This allows
+/// playing files that would be impractical to decode into memory in full.
+///
+/// The decoding thread is stopped when the `DiskStream` is dropped. If decoding fails, the stream
+/// ends and the error may be retrieved via `errors`.
+pub struct DiskStream {
+    channels: usize,
+    sample_rate: u32,
+    len_frames: Option<u64>,
+    shared: Arc<Shared>,
+    errors: mpsc::Receiver<DecodeError>,
+    // Blocks filled by the decoding thread.
+    full: Consumer<Box<Block>>,
+    // Blocks returned to the decoding thread.
+    free: Producer<Box<Block>>,
+    // The block that is currently being read along with the index of the next frame.
+    current: Option<(Box<Block>, usize)>,
+    // Blocks from a generation prior to this one are discarded.
+    generation: u64,
+    ended: bool,
+}
+
+/// The result of reading a frame from a `DiskStream`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Read {
+    /// The frame was read.
+    Frame,
+    /// The decoding thread has not yet caught up. The frame was filled with silence.
+    Underrun,
+    /// The end of the stream was reached. The frame was filled with silence.
+    End,
+}
+
+// State shared between the stream and the decoding thread.
+struct Shared {
+    seek_to: AtomicU64,
+    generation: AtomicU64,
+    looping: AtomicBool,
+    closed: AtomicBool,
+}
+
+struct Block {
+    generation: u64,
+    start: u64,
+    samples: Vec<f32>,
+    end: bool,
+}
+
+impl DiskStream {
+    /// The default number of frames decoded into each block.
+    pub const DEFAULT_BLOCK_FRAMES: usize = 4_096;
+    /// The default number of blocks decoded ahead of playback.
+    pub const DEFAULT_BLOCKS: usize = 8;
+
+    /// Begin streaming the audio file at the given path.
+    pub fn open<P>(path: P) -> Result<Self, DecodeError>
+    where
+        P: AsRef<Path>,
+    {
+        Self::open_with_buffer(path, Self::DEFAULT_BLOCK_FRAMES, Self::DEFAULT_BLOCKS)
+    }
+
+    /// Begin streaming the audio file at the given path, decoding up to `blocks` blocks of
+    /// `block_frames` frames ahead of playback.
+    ///
+    /// **Panics** if either `block_frames` or `blocks` is `0`.
+    pub fn open_with_buffer<P>(
+        path: P,
+        block_frames: usize,
+        blocks: usize,
+    ) -> Result<Self, DecodeError>
+    where
+        P: AsRef<Path>,
+    {
+        assert!(block_frames > 0 && blocks > 0);
+        let decoder = Decoder::open(path)?;
+        let channels = decoder.channels();
+        let sample_rate = decoder.sample_rate();
+        let len_frames = decoder.len_frames();
+
+        let (full_tx, full_rx) = RingBuffer::new(blocks).split();
+        let (mut free_tx, free_rx) = RingBuffer::new(blocks).split();
+        for _ in 0..blocks {
+            let block = Box::new(Block {
+                generation: 0,
+                start: 0,
+                samples: Vec::with_capacity(block_frames * channels),
+                end: false,
+            });
+            free_tx.push(block).ok();
+        }
+
+        let shared = Arc::new(Shared {
+            seek_to: AtomicU64::new(0),
+            generation: AtomicU64::new(0),
+            looping: AtomicBool::new(false),
+            closed: AtomicBool::new(false),
+        });
+        let (errors_tx, errors_rx) = mpsc::channel();
+        let thread_shared = shared.clone();
+        thread::Builder::new()
+            .name("nannou_audio-disk-stream".into())
+            .spawn(move || {
+                run(
+                    decoder,
+                    block_frames,
+                    thread_shared,
+                    full_tx,
+                    free_rx,
+                    errors_tx,
+                )
+            })?;
+
+        Ok(DiskStream {
+            channels,
+            sample_rate,
+            len_frames,
+            shared,
+            errors: errors_rx,
+            full: full_rx,
+            free: free_tx,
+            current: None,
+            generation: 0,
+            ended: false,
+        })
+    }
+
+    /// The number of channels per frame.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// The sample rate of the audio in Hz.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// The total length of the audio in frames, if known.
+    pub fn len_frames(&self) -> Option<u64> {
+        self.len_frames
+    }
+
+    /// The index of the next frame that will be read, if known.
+    ///
+    /// This is `None` while waiting for the decoding thread after a seek.
+    pub fn position(&self) -> Option<u64> {
+        self.current
+            .as_ref()
+            .map(|(block, i)| block.start + *i as u64)
+    }
+
+    /// Whether or not the end of the stream has been reached.
+    pub fn is_ended(&self) -> bool {
+        self.ended
+    }
+
+    /// Errors that have occurred on the decoding thread since last checked.
+    ///
+    /// Decoding stops upon any error until the next seek.
+    pub fn errors(&self) -> mpsc::TryIter<'_, DecodeError> {
+        self.errors.try_iter()
+    }
+
+    /// Whether or not the stream restarts from the beginning upon reaching the end.
+    pub fn is_looping(&self) -> bool {
+        self.shared.looping.load(atomic::Ordering::Relaxed)
+    }
+
+    /// Specify whether or not the stream should restart from the beginning upon reaching the end.
+    pub fn set_looping(&mut self, looping: bool) {
+        self.shared
+            .looping
+            .store(looping, atomic::Ordering::Relaxed);
+    }
+
+    /// Request that the decoding thread continue decoding from the given frame.
+    ///
+    /// Frames decoded prior to the seek are discarded. Until the decoding thread catches up,
+    /// reading yields `Read::Underrun`.
+    pub fn seek(&mut self, frame: u64) {
+        self.generation += 1;
+        self.shared.seek_to.store(frame, atomic::Ordering::Relaxed);
+        self.shared
+            .generation
+            .store(self.generation, atomic::Ordering::Release);
+        if let Some((block, _)) = self.current.take() {
+            self.free.push(block).ok();
+        }
+        self.ended = false;
+    }
+
+    /// Read the next frame into `frame`, whose length must match the number of channels.
+    ///
+    /// This never blocks and never allocates, so it is suitable for calling on the audio thread.
+    pub fn read_frame(&mut self, frame: &mut [f32]) -> Read {
+        loop {
+            if let Some((ref block, ref mut i)) = self.current {
+                let start = *i * self.channels;
+                if start + self.channels <= block.samples.len() {
+                    frame.copy_from_slice(&block.samples[start..start + self.channels]);
+                    *i += 1;
+                    return Read::Frame;
+                }
+                if block.end {
+                    self.ended = true;
+                }
+            }
+            if let Some((block, _)) = self.current.take() {
+                self.free.push(block).ok();
+            }
+            if self.ended {
+                silence(frame);
+                return Read::End;
+            }
+            match self.full.pop() {
+                Some(block) => {
+                    if block.generation == self.generation {
+                        self.current = Some((block, 0));
+                    } else {
+                        self.free.push(block).ok();
+                    }
+                }
+                None => {
+                    silence(frame);
+                    return Read::Underrun;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for DiskStream {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, atomic::Ordering::Relaxed);
+    }
+}
+
+fn silence(frame: &mut [f32]) {
+    for sample in frame {
+        *sample = 0.0;
+    }
+}
+
+// The decoding thread.
+fn run(
+    mut decoder: Decoder,
+    block_frames: usize,
+    shared: Arc<Shared>,
+    mut full: Producer<Box<Block>>,
+    mut free: Consumer<Box<Block>>,
+    errors: mpsc::Sender<DecodeError>,
+) {
+    let mut generation = 0;
+    let mut ended = false;
+    // Report the error, if any, returning whether or not one occurred.
+    let report = |result: Result<(), DecodeError>| match result {
+        Ok(()) => false,
+        Err(err) => {
+            errors.send(err).ok();
+            true
+        }
+    };
+    while !shared.closed.load(atomic::Ordering::Relaxed) {
+        let latest = shared.generation.load(atomic::Ordering::Acquire);
+        if latest != generation {
+            generation = latest;
+            let frame = shared.seek_to.load(atomic::Ordering::Relaxed);
+            ended = report(decoder.seek(frame));
+        }
+
+        // Wait for a seek or for the stream to be closed.
+        if ended {
+            thread::sleep(Duration::from_millis(5));
+            continue;
+        }
+
+        let mut block = match free.pop() {
+            Some(block) => block,
+            None => {
+                thread::sleep(Duration::from_millis(1));
+                continue;
+            }
+        };
+
+        block.generation = generation;
+        block.start = decoder.position();
+        block.samples.clear();
+        block.end = false;
+        let looping = shared.looping.load(atomic::Ordering::Relaxed);
+        match decoder.read(&mut block.samples, block_frames) {
+            Ok(0) if looping && decoder.position() > 0 => {
+                block.start = 0;
+                ended = report(decoder.seek(0))
+                    || report(decoder.read(&mut block.samples, block_frames).map(|_| ()));
+            }
+            Ok(n) => ended = n < block_frames && !(looping && n > 0),
+            Err(err) => ended = report(Err(err)),
+        }
+        block.end = ended;
+        full.push(block).ok();
+    }
+}
@@ -0,0 +1,180 @@
+//! Audio file decoding and playback.
+//!
+//! - [**Sample**](./struct.Sample.html) - an audio file decoded into memory, cheap to clone.
+//! - [**DiskStream**](./struct.DiskStream.html) - an audio file decoded on a background thread
+//!   during playback, for files too long to hold in memory.
+//! - [**Voice**](./struct.Voice.html) - a single playing instance of a sample or stream with its
+//!   own gain, pan, playback rate, looping and position.
+//! - [**Player**](./struct.Player.html) - a polyphonic mixer of voices for use within an output
+//!   stream's render function.
+//!
+//! Decoding is provided for WAV, FLAC, Ogg Vorbis and MP3 via the `wav`, `flac`, `ogg` and `mp3`
+//! features respectively, all of which are enabled by default.
+//!
+//! A `Player` is typically stored within the audio model. Voices are created on the main thread
+//! and then sent to the audio thread, where they are mixed into the output buffer:
+//!
+//! ```no_run
+//! use nannou_audio::player::{Player, Sample, Voice};
+//! use nannou_audio::Buffer;
+//!
+//! struct Audio {
+//!     player: Player,
+//! }
+//!
+//! fn render(audio: &mut Audio, buffer: &mut Buffer) {
+//!     audio.player.render(buffer);
+//! }
+//!
+//! let host = nannou_audio::Host::new();
+//! let audio = Audio { player: Player::new() };
+//! let stream = host.new_output_stream(audio).render(render).build().unwrap();
+//!
+//! let sample = Sample::from_file("kick.wav").unwrap();
+//! let voice = Voice::new(&sample).gain(0.5).pitch(-12.0);
+//! stream
+//!     .send(move |audio| {
+//!         audio.player.play(voice);
+//!     })
+//!     .unwrap();
+//! ```
+
+use crate::Buffer;
+
+pub use self::decode::{DecodeError, Decoder, Format};
+pub use self::disk::{DiskStream, Read};
+pub use self::sample::Sample;
+pub use self::voice::{Interpolation, Source, State, Voice, VoiceId};
+
+pub mod decode;
+mod disk;
+mod sample;
+mod voice;
+
+/// Mixes any number of playing voices up to a fixed polyphony.
+///
+/// Storage for the voices is allocated up front so that playing a voice on the audio thread does
+/// not allocate. Note that dropping a finished or stolen voice may deallocate, e.g. if it holds
+/// the last clone of a `Sample` or owns a `DiskStream`.
+pub struct Player {
+    voices: Vec<Voice>,
+    max_voices: usize,
+    gain: f32,
+}
+
+impl Player {
+    /// The default maximum number of voices that may play at once.
+    pub const DEFAULT_MAX_VOICES: usize = 32;
+
+    /// Create a new player with the default maximum number of voices.
+    pub fn new() -> Self {
+        Self::with_max_voices(Self::DEFAULT_MAX_VOICES)
+    }
+
+    /// Create a new player that may play up to `max_voices` voices at once.
+    ///
+    /// **Panics** if `max_voices` is `0`.
+    pub fn with_max_voices(max_voices: usize) -> Self {
+        assert!(max_voices > 0, "a `Player` must allow at least one voice");
+        Player {
+            voices: Vec::with_capacity(max_voices),
+            max_voices,
+            gain: 1.0,
+        }
+    }
+
+    /// Begin playing the given voice, returning its ID.
+    ///
+    /// If the maximum number of voices are already playing, the oldest voice is stopped
+    /// immediately to make room.
+    pub fn play(&mut self, voice: Voice) -> VoiceId {
+        if self.voices.len() >= self.max_voices {
+            self.voices.remove(0);
+        }
+        let id = voice.id();
+        self.voices.push(voice);
+        id
+    }
+
+    /// The voice with the given ID, if it is still playing.
+    pub fn voice(&self, id: VoiceId) -> Option<&Voice> {
+        self.voices.iter().find(|v| v.id() == id)
+    }
+
+    /// The voice with the given ID, if it is still playing.
+    pub fn voice_mut(&mut self, id: VoiceId) -> Option<&mut Voice> {
+        self.voices.iter_mut().find(|v| v.id() == id)
+    }
+
+    /// Fade out and stop the voice with the given ID.
+    ///
+    /// Returns `false` if there is no voice with the given ID.
+    pub fn stop(&mut self, id: VoiceId) -> bool {
+        match self.voice_mut(id) {
+            Some(voice) => {
+                voice.stop();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Fade out and stop all voices.
+    pub fn stop_all(&mut self) {
+        for voice in &mut self.voices {
+            voice.stop();
+        }
+    }
+
+    /// An iterator yielding all voices, from oldest to newest.
+    pub fn voices(&self) -> std::slice::Iter<'_, Voice> {
+        self.voices.iter()
+    }
+
+    /// An iterator yielding mutable access to all voices, from oldest to newest.
+    pub fn voices_mut(&mut self) -> std::slice::IterMut<'_, Voice> {
+        self.voices.iter_mut()
+    }
+
+    /// The number of voices.
+    pub fn len(&self) -> usize {
+        self.voices.len()
+    }
+
+    /// Whether or not there are no voices.
+    pub fn is_empty(&self) -> bool {
+        self.voices.is_empty()
+    }
+
+    /// The maximum number of voices that may play at once.
+    pub fn max_voices(&self) -> usize {
+        self.max_voices
+    }
+
+    /// The linear amplitude applied to the mix of all voices.
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Set the linear amplitude applied to the mix of all voices.
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain.max(0.0);
+    }
+
+    /// Mix all voices into the given buffer, removing those that have finished.
+    ///
+    /// The voices are added to the existing contents of the buffer.
+    pub fn render(&mut self, buffer: &mut Buffer) {
+        let gain = self.gain;
+        for voice in &mut self.voices {
+            voice.render_with_gain(buffer, gain);
+        }
+        self.voices.retain(|v| !v.is_finished());
+    }
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self::new()
+    }
+}
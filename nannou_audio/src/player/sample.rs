@@ -0,0 +1,98 @@
+use super::decode::{DecodeError, Decoder};
+use std::path::Path;
+use std::sync::Arc;
+
+/// A fully decoded audio file held in memory.
+///
+/// `Sample`s are cheap to clone as the underlying samples are shared. The samples are freed when
+/// the last clone is dropped, so it can be useful to retain a clone on the main thread to avoid
+/// deallocating on the audio thread when a voice finishes.
+#[derive(Clone, Debug)]
+pub struct Sample {
+    data: Arc<Data>,
+}
+
+#[derive(Debug)]
+struct Data {
+    samples: Box<[f32]>,
+    channels: usize,
+    sample_rate: u32,
+}
+
+impl Sample {
+    /// Create a sample from interleaved samples with the given channel count and sample rate.
+    ///
+    /// **Panics** if `channels` is `0`.
+    pub fn from_interleaved<S>(samples: S, channels: usize, sample_rate: u32) -> Self
+    where
+        S: Into<Box<[f32]>>,
+    {
+        assert!(channels > 0, "a `Sample` must have at least one channel");
+        let mut samples = samples.into().into_vec();
+        samples.truncate(samples.len() - samples.len() % channels);
+        let data = Arc::new(Data {
+            samples: samples.into_boxed_slice(),
+            channels,
+            sample_rate,
+        });
+        Sample { data }
+    }
+
+    /// Decode the entire audio file at the given path.
+    pub fn from_file<P>(path: P) -> Result<Self, DecodeError>
+    where
+        P: AsRef<Path>,
+    {
+        Self::from_decoder(Decoder::open(path)?)
+    }
+
+    /// Decode the given encoded audio file bytes.
+    pub fn from_bytes<B>(bytes: B) -> Result<Self, DecodeError>
+    where
+        B: Into<Arc<[u8]>>,
+    {
+        Self::from_decoder(Decoder::from_bytes(bytes)?)
+    }
+
+    /// Decode the remaining audio of the given decoder.
+    pub fn from_decoder(mut decoder: Decoder) -> Result<Self, DecodeError> {
+        let samples = decoder.read_to_end()?;
+        Ok(Self::from_interleaved(
+            samples,
+            decoder.channels(),
+            decoder.sample_rate(),
+        ))
+    }
+
+    /// The interleaved samples.
+    pub fn samples(&self) -> &[f32] {
+        &self.data.samples
+    }
+
+    /// The number of channels per frame.
+    pub fn channels(&self) -> usize {
+        self.data.channels
+    }
+
+    /// The sample rate of the audio in Hz.
+    pub fn sample_rate(&self) -> u32 {
+        self.data.sample_rate
+    }
+
+    /// The length of the sample in frames.
+    pub fn len_frames(&self) -> usize {
+        self.data.samples.len() / self.data.channels
+    }
+
+    /// The duration of the sample in seconds.
+    pub fn duration_secs(&self) -> f64 {
+        self.len_frames() as f64 / self.data.sample_rate as f64
+    }
+
+    /// The frame at the given index.
+    pub fn frame(&self, index: usize) -> Option<&[f32]> {
+        let channels = self.data.channels;
+        let start = index * channels;
+        self.data.samples.get(start..start + channels)
+    }
+}
@@ -0,0 +1,519 @@
+use super::disk::{self, DiskStream};
+use super::sample::Sample;
+use crate::Buffer;
+use std::sync::atomic::{self, AtomicU64};
+
+/// A unique identifier for a `Voice`, used to control the voice once it is playing.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct VoiceId(u64);
+
+/// The audio played by a `Voice`.
+pub enum Source {
+    /// A sample decoded into memory.
+    Sample(Sample),
+    /// An audio file streamed from disk.
+    Stream(DiskStream),
+}
+
+/// The interpolation used when the playback rate differs from the output sample rate.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Linear interpolation between neighbouring frames. Cheap, but dulls high frequencies.
+    Linear,
+    /// Four-point cubic Hermite interpolation.
+    Cubic,
+}
+
+/// The playback state of a `Voice`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum State {
+    /// The voice is playing.
+    Playing,
+    /// The voice is paused and retains its position.
+    Paused,
+    /// The voice is fading out, after which it will be finished.
+    Stopping,
+    /// The voice has finished and will be removed from the `Player`.
+    Finished,
+}
+
+/// A single instance of a playing `Source`.
+///
+/// Voices are created on the main thread, configured via the builder methods and then sent to
+/// the `Player` on the audio thread via `Stream::send`.
+pub struct Voice {
+    id: VoiceId,
+    source: Source,
+    state: State,
+    interpolation: Interpolation,
+    // The target parameters.
+    gain: f32,
+    pan: f32,
+    rate: f64,
+    looping: bool,
+    // The parameters at the end of the previous buffer, used to ramp toward the targets.
+    applied_gain: f32,
+    applied_pan: f32,
+    // Four frames surrounding the playhead. The playhead lies `frac` between frames 1 and 2,
+    // which is three frames behind the next frame pulled from the source.
+    window: Box<[f32]>,
+    frac: f64,
+    // The index of the next frame to be pulled from a `Source::Sample`.
+    next_index: u64,
+    // The number of frames pulled into the window since the source ended.
+    frames_since_end: usize,
+    // The interpolated frame.
+    frame: Box<[f32]>,
+}
+
+// The number of frames held by the interpolation window.
+const WINDOW_FRAMES: usize = 4;
+
+impl VoiceId {
+    // Produce a new unique identifier.
+    fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        VoiceId(NEXT.fetch_add(1, atomic::Ordering::Relaxed))
+    }
+}
+
+impl Voice {
+    /// Create a new voice for the given source.
+    ///
+    /// By default the voice plays from the start at unity gain, centred and at the source's
+    /// original speed.
+    pub fn new<S>(source: S) -> Self
+    where
+        S: Into<Source>,
+    {
+        let source = source.into();
+        let channels = source.channels();
+        let mut voice = Voice {
+            id: VoiceId::next(),
+            source,
+            state: State::Playing,
+            interpolation: Interpolation::Cubic,
+            gain: 1.0,
+            pan: 0.0,
+            rate: 1.0,
+            looping: false,
+            applied_gain: 1.0,
+            applied_pan: 0.0,
+            window: vec![0.0; channels * WINDOW_FRAMES].into_boxed_slice(),
+            frac: 0.0,
+            next_index: 0,
+            frames_since_end: 0,
+            frame: vec![0.0; channels].into_boxed_slice(),
+        };
+        voice.fill_window();
+        voice
+    }
+
+    /// Builder method for specifying the initial gain.
+    pub fn gain(mut self, gain: f32) -> Self {
+        self.set_gain(gain);
+        self.applied_gain = self.gain;
+        self
+    }
+
+    /// Builder method for specifying the initial pan.
+    pub fn pan(mut self, pan: f32) -> Self {
+        self.set_pan(pan);
+        self.applied_pan = self.pan;
+        self
+    }
+
+    /// Builder method for specifying the initial playback rate.
+    pub fn rate(mut self, rate: f64) -> Self {
+        self.set_rate(rate);
+        self
+    }
+
+    /// Builder method for specifying the initial pitch shift in semitones.
+    pub fn pitch(mut self, semitones: f64) -> Self {
+        self.set_pitch(semitones);
+        self
+    }
+
+    /// Builder method for specifying whether or not the voice loops.
+    pub fn looping(mut self, looping: bool) -> Self {
+        self.set_looping(looping);
+        self
+    }
+
+    /// Builder method for specifying the frame at which playback starts.
+    pub fn start_at(mut self, frame: u64) -> Self {
+        self.seek(frame);
+        self
+    }
+
+    /// Builder method for specifying the interpolation used for resampling.
+    pub fn interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Builder method for creating the voice in a paused state.
+    pub fn paused(mut self) -> Self {
+        self.pause();
+        self
+    }
+
+    /// The unique identifier of the voice.
+    pub fn id(&self) -> VoiceId {
+        self.id
+    }
+
+    /// The source played by the voice.
+    pub fn source(&self) -> &Source {
+        &self.source
+    }
+
+    /// The current playback state.
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// Whether or not the voice has finished playing.
+    pub fn is_finished(&self) -> bool {
+        self.state == State::Finished
+    }
+
+    /// The linear amplitude applied to the voice.
+    pub fn get_gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// The pan of the voice in the range `-1.0` (left) to `1.0` (right).
+    pub fn get_pan(&self) -> f32 {
+        self.pan
+    }
+
+    /// The playback rate, where `1.0` is the original speed.
+    pub fn get_rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Whether or not the voice restarts from the beginning upon reaching the end.
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    /// Set the linear amplitude applied to the voice. Changes are ramped over the next buffer.
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain.max(0.0);
+    }
+
+    /// Set the pan in the range `-1.0` (left) to `1.0` (right). Changes are ramped over the next
+    /// buffer.
+    ///
+    /// Mono sources are panned using an equal-power law. Sources with more channels have the
+    /// balance of their first two channels adjusted.
+    pub fn set_pan(&mut self, pan: f32) {
+        self.pan = pan.clamp(-1.0, 1.0);
+    }
+
+    /// Set the playback rate, where `1.0` is the original speed and `2.0` is twice as fast and an
+    /// octave higher. Negative rates are clamped to `0.0`.
+    pub fn set_rate(&mut self, rate: f64) {
+        self.rate = rate.max(0.0);
+    }
+
+    /// Set the playback rate from a pitch shift in semitones.
+    pub fn set_pitch(&mut self, semitones: f64) {
+        self.set_rate(2.0f64.powf(semitones / 12.0));
+    }
+
+    /// Specify whether or not the voice restarts from the beginning upon reaching the end.
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+        if let Source::Stream(ref mut stream) = self.source {
+            stream.set_looping(looping);
+        }
+    }
+
+    /// Pause playback. The voice retains its position.
+    pub fn pause(&mut self) {
+        if self.state == State::Playing {
+            self.state = State::Paused;
+        }
+    }
+
+    /// Resume playback of a paused voice.
+    pub fn resume(&mut self) {
+        if self.state == State::Paused {
+            self.state = State::Playing;
+        }
+    }
+
+    /// Fade out over the next buffer and then finish.
+    pub fn stop(&mut self) {
+        match self.state {
+            State::Playing => self.state = State::Stopping,
+            State::Paused => self.state = State::Finished,
+            State::Stopping | State::Finished => (),
+        }
+    }
+
+    /// The frame index of the playhead within the source.
+    pub fn position(&self) -> u64 {
+        match self.source {
+            Source::Sample(ref sample) => {
+                let len = sample.len_frames() as u64;
+                let behind = (WINDOW_FRAMES - 1) as u64;
+                match self.next_index.checked_sub(behind) {
+                    Some(index) => index,
+                    None if self.looping && len > 0 => (self.next_index + len - behind) % len,
+                    None => 0,
+                }
+            }
+            Source::Stream(ref stream) => stream
+                .position()
+                .map(|p| p.saturating_sub((WINDOW_FRAMES - 1) as u64))
+                .unwrap_or(0),
+        }
+    }
+
+    /// The position of the playhead in seconds.
+    pub fn position_secs(&self) -> f64 {
+        self.position() as f64 / self.source.sample_rate() as f64
+    }
+
+    /// Move the playhead to the given frame within the source.
+    pub fn seek(&mut self, frame: u64) {
+        match self.source {
+            Source::Sample(ref sample) => {
+                self.next_index = std::cmp::min(frame, sample.len_frames() as u64);
+            }
+            Source::Stream(ref mut stream) => stream.seek(frame),
+        }
+        if self.state == State::Finished {
+            self.state = State::Playing;
+        }
+        self.fill_window();
+    }
+
+    /// Move the playhead to the given position in seconds.
+    pub fn seek_secs(&mut self, secs: f64) {
+        let frame = (secs.max(0.0) * self.source.sample_rate() as f64) as u64;
+        self.seek(frame);
+    }
+
+    /// Add the voice's audio to the given buffer, advancing the playhead.
+    ///
+    /// Each channel of the source is mixed onto the output channel of the same index, with the
+    /// exception of mono output to which all channels are mixed and mono sources which are panned
+    /// between the first two output channels.
+    pub fn render(&mut self, buffer: &mut Buffer) {
+        self.render_with_gain(buffer, 1.0);
+    }
+
+    // Render the voice with an additional gain applied, e.g. the `Player`'s master gain.
+    pub(crate) fn render_with_gain(&mut self, buffer: &mut Buffer, master: f32) {
+        match self.state {
+            State::Playing | State::Stopping => (),
+            State::Paused | State::Finished => return,
+        }
+
+        let len_frames = buffer.len_frames();
+        let out_channels = buffer.channels();
+        let step = self.rate * self.source.sample_rate() as f64 / buffer.sample_rate() as f64;
+        let target_gain = match self.state {
+            State::Stopping => 0.0,
+            _ => self.gain,
+        };
+        let channels = self.frame.len();
+        let (start_l, start_r) = pan_gains(self.applied_pan, channels);
+        let (end_l, end_r) = pan_gains(self.pan, channels);
+
+        for (i, out) in buffer.frames_mut().enumerate() {
+            let t = (i + 1) as f32 / len_frames as f32;
+            let gain = lerp(self.applied_gain, target_gain, t) * master;
+            let left = gain * lerp(start_l, end_l, t);
+            let right = gain * lerp(start_r, end_r, t);
+
+            self.interpolate();
+            mix(&self.frame, out, out_channels, gain, left, right);
+
+            self.frac += step;
+            while self.frac >= 1.0 {
+                self.frac -= 1.0;
+                self.advance();
+            }
+            if self.frames_since_end >= WINDOW_FRAMES - 1 {
+                self.state = State::Finished;
+                break;
+            }
+        }
+
+        self.applied_gain = target_gain;
+        self.applied_pan = self.pan;
+        if self.state == State::Stopping {
+            self.state = State::Finished;
+        }
+    }
+
+    // Interpolate the frame at the playhead.
+    fn interpolate(&mut self) {
+        let channels = self.frame.len();
+        let w = &self.window;
+        let t = self.frac as f32;
+        for ch in 0..channels {
+            let x0 = w[ch];
+            let x1 = w[channels + ch];
+            let x2 = w[2 * channels + ch];
+            let x3 = w[3 * channels + ch];
+            self.frame[ch] = match self.interpolation {
+                Interpolation::Linear => x1 + (x2 - x1) * t,
+                Interpolation::Cubic => {
+                    let c1 = 0.5 * (x2 - x0);
+                    let c2 = x0 - 2.5 * x1 + 2.0 * x2 - 0.5 * x3;
+                    let c3 = 0.5 * (x3 - x0) + 1.5 * (x1 - x2);
+                    ((c3 * t + c2) * t + c1) * t + x1
+                }
+            };
+        }
+    }
+
+    // Shift the window by one frame, pulling the next frame from the source.
+    fn advance(&mut self) {
+        let channels = self.frame.len();
+        self.window.copy_within(channels.., 0);
+        let start = (WINDOW_FRAMES - 1) * channels;
+        let ended = !self.pull(start);
+        if ended {
+            self.frames_since_end += 1;
+        } else {
+            self.frames_since_end = 0;
+        }
+    }
+
+    // Pull the next frame from the source into the window at the given sample index.
+    //
+    // Returns `false` if the source has ended, in which case silence is written.
+    fn pull(&mut self, start: usize) -> bool {
+        let channels = self.frame.len();
+        let dst = &mut self.window[start..start + channels];
+        match self.source {
+            Source::Sample(ref sample) => {
+                let len = sample.len_frames() as u64;
+                if self.next_index >= len && self.looping && len > 0 {
+                    self.next_index = 0;
+                }
+                match sample.frame(self.next_index as usize) {
+                    Some(frame) => {
+                        dst.copy_from_slice(frame);
+                        self.next_index += 1;
+                        true
+                    }
+                    None => {
+                        dst.iter_mut().for_each(|s| *s = 0.0);
+                        false
+                    }
+                }
+            }
+            // Underruns are treated as silence without ending the voice.
+            Source::Stream(ref mut stream) => stream.read_frame(dst) != disk::Read::End,
+        }
+    }
+
+    // Refill the window from the current source position, e.g. after a seek.
+    fn fill_window(&mut self) {
+        let channels = self.frame.len();
+        self.window.iter_mut().for_each(|s| *s = 0.0);
+        self.frac = 0.0;
+        self.frames_since_end = 0;
+        for i in 1..WINDOW_FRAMES {
+            if !self.pull(i * channels) {
+                self.frames_since_end += 1;
+            }
+        }
+    }
+}
+
+impl Source {
+    /// The number of channels per frame.
+    pub fn channels(&self) -> usize {
+        match *self {
+            Source::Sample(ref sample) => sample.channels(),
+            Source::Stream(ref stream) => stream.channels(),
+        }
+    }
+
+    /// The sample rate of the source in Hz.
+    pub fn sample_rate(&self) -> u32 {
+        match *self {
+            Source::Sample(ref sample) => sample.sample_rate(),
+            Source::Stream(ref stream) => stream.sample_rate(),
+        }
+    }
+
+    /// The length of the source in frames, if known.
+    pub fn len_frames(&self) -> Option<u64> {
+        match *self {
+            Source::Sample(ref sample) => Some(sample.len_frames() as u64),
+            Source::Stream(ref stream) => stream.len_frames(),
+        }
+    }
+}
+
+impl From<Sample> for Source {
+    fn from(sample: Sample) -> Self {
+        Source::Sample(sample)
+    }
+}
+
+impl<'a> From<&'a Sample> for Source {
+    fn from(sample: &'a Sample) -> Self {
+        Source::Sample(sample.clone())
+    }
+}
+
+impl From<DiskStream> for Source {
+    fn from(stream: DiskStream) -> Self {
+        Source::Stream(stream)
+    }
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+// The gains applied to the first two channels for the given pan.
+//
+// Mono sources use an equal-power pan, normalised so that a centred source is at unity gain on
+// both channels. Other sources are balanced, attenuating only the opposing channel.
+fn pan_gains(pan: f32, channels: usize) -> (f32, f32) {
+    if channels == 1 {
+        let angle = (pan + 1.0) * std::f32::consts::FRAC_PI_4;
+        let norm = std::f32::consts::SQRT_2;
+        (angle.cos() * norm, angle.sin() * norm)
+    } else {
+        ((1.0 - pan).min(1.0), (1.0 + pan).min(1.0))
+    }
+}
+
+// Mix the source frame onto the output frame.
+fn mix(frame: &[f32], out: &mut [f32], out_channels: usize, gain: f32, left: f32, right: f32) {
+    match (frame.len(), out_channels) {
+        (_, 1) => {
+            let sum: f32 = frame.iter().sum();
+            let avg = sum / frame.len() as f32;
+            out[0] += avg * gain;
+        }
+        (1, _) => {
+            out[0] += frame[0] * left;
+            out[1] += frame[0] * right;
+        }
+        _ => {
+            for (ch, (o, s)) in out.iter_mut().zip(frame).enumerate() {
+                *o += *s
+                    * match ch {
+                        0 => left,
+                        1 => right,
+                        _ => gain,
+                    };
+            }
+        }
+    }
+}
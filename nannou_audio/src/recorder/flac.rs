@@ -0,0 +1,440 @@
+//! A minimal FLAC encoder.
+//!
+//! Each channel is encoded independently using whichever of the constant, verbatim or fixed
+//! polynomial predictor subframes is smallest, with Rice-coded residuals. This is not as compact
+//! as the reference encoder's LPC analysis but is fast and simple enough to keep up with real-time
+//! recording on the writer thread.
+
+use std::io::{self, Seek, SeekFrom, Write};
+
+/// The number of frames per FLAC block.
+pub(crate) const BLOCK_SIZE: usize = 4_096;
+
+// The largest Rice parameter that may be specified with the 4-bit parameter encoding.
+const MAX_RICE_PARAM: u32 = 14;
+// The largest partition order considered for residual coding.
+const MAX_PARTITION_ORDER: u32 = 8;
+// The byte offset of the STREAMINFO block data, following the marker and block header.
+const STREAMINFO_OFFSET: u64 = 8;
+
+/// Writes interleaved `f32` samples to a FLAC stream.
+pub(crate) struct Writer<W: Write + Seek> {
+    writer: W,
+    channels: usize,
+    sample_rate: u32,
+    bits_per_sample: u32,
+    // The samples of the pending block, one `Vec` per channel.
+    block: Vec<Vec<i32>>,
+    frame_number: u64,
+    total_frames: u64,
+    min_frame_bytes: u32,
+    max_frame_bytes: u32,
+    // Scratch buffers reused between frames.
+    bits: BitWriter,
+    residual: Vec<i32>,
+    best_residual: Vec<i32>,
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    len: u32,
+}
+
+// The encoding chosen for a single channel of a block.
+enum Subframe {
+    Constant,
+    Verbatim,
+    Fixed { order: usize, partition_order: u32 },
+}
+
+impl<W: Write + Seek> Writer<W> {
+    /// Begin a new FLAC stream, writing the stream header.
+    ///
+    /// Supports 1 to 8 channels at a bit depth of 16 or 24.
+    pub fn new(
+        mut writer: W,
+        channels: usize,
+        sample_rate: u32,
+        bits_per_sample: u32,
+    ) -> io::Result<Self> {
+        if channels == 0 || channels > 8 {
+            return Err(invalid_input("FLAC supports between 1 and 8 channels"));
+        }
+        if sample_rate == 0 || sample_rate >= 1 << 20 {
+            return Err(invalid_input("unsupported FLAC sample rate"));
+        }
+        if bits_per_sample != 16 && bits_per_sample != 24 {
+            return Err(invalid_input("FLAC bit depth must be 16 or 24"));
+        }
+        writer.write_all(b"fLaC")?;
+        // The final metadata block is STREAMINFO (type 0) with a length of 34 bytes.
+        writer.write_all(&[0x80, 0, 0, 34])?;
+        let mut flac = Writer {
+            writer,
+            channels,
+            sample_rate,
+            bits_per_sample,
+            block: vec![Vec::with_capacity(BLOCK_SIZE); channels],
+            frame_number: 0,
+            total_frames: 0,
+            min_frame_bytes: 0,
+            max_frame_bytes: 0,
+            bits: BitWriter::new(),
+            residual: Vec::with_capacity(BLOCK_SIZE),
+            best_residual: Vec::with_capacity(BLOCK_SIZE),
+        };
+        flac.write_streaminfo()?;
+        Ok(flac)
+    }
+
+    /// Write the given interleaved samples. Trailing samples that do not form a whole frame are
+    /// ignored.
+    pub fn write_samples(&mut self, samples: &[f32]) -> io::Result<()> {
+        let max = (1i64 << (self.bits_per_sample - 1)) as f32;
+        for frame in samples.chunks_exact(self.channels) {
+            for (ch, &s) in frame.iter().enumerate() {
+                let v = (s * max).round().clamp(-max, max - 1.0);
+                self.block[ch].push(v as i32);
+            }
+            if self.block[0].len() == BLOCK_SIZE {
+                self.write_frame()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encode any pending samples and update the stream header with the final length.
+    pub fn finalize(mut self) -> io::Result<()> {
+        if !self.block[0].is_empty() {
+            self.write_frame()?;
+        }
+        self.writer.seek(SeekFrom::Start(STREAMINFO_OFFSET))?;
+        self.write_streaminfo()?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()
+    }
+
+    fn write_streaminfo(&mut self) -> io::Result<()> {
+        let mut b = BitWriter::new();
+        b.write(BLOCK_SIZE as u64, 16);
+        b.write(BLOCK_SIZE as u64, 16);
+        b.write(self.min_frame_bytes as u64, 24);
+        b.write(self.max_frame_bytes as u64, 24);
+        b.write(self.sample_rate as u64, 20);
+        b.write(self.channels as u64 - 1, 3);
+        b.write(self.bits_per_sample as u64 - 1, 5);
+        b.write(self.total_frames, 36);
+        // An MD5 signature of zero indicates that it was not computed.
+        b.write(0, 64);
+        b.write(0, 64);
+        self.writer.write_all(&b.bytes)
+    }
+
+    // Encode the pending block as a single FLAC frame.
+    fn write_frame(&mut self) -> io::Result<()> {
+        let len = self.block[0].len();
+        let bps = self.bits_per_sample;
+        let mut bits = std::mem::replace(&mut self.bits, BitWriter::new());
+        bits.clear();
+
+        // Frame header.
+        bits.write(0b11_1111_1111_1110, 14);
+        bits.write(0, 1);
+        // Fixed block size strategy.
+        bits.write(0, 1);
+        // The block size is stored as a 16-bit value at the end of the header.
+        bits.write(0b0111, 4);
+        // The sample rate is taken from STREAMINFO.
+        bits.write(0b0000, 4);
+        // Independent channels.
+        bits.write(self.channels as u64 - 1, 4);
+        bits.write(if bps == 16 { 0b100 } else { 0b110 }, 3);
+        bits.write(0, 1);
+        write_utf8(&mut bits, self.frame_number);
+        bits.write(len as u64 - 1, 16);
+        let crc = crc8(&bits.bytes);
+        bits.write(crc as u64, 8);
+
+        // Subframes.
+        for ch in 0..self.channels {
+            let samples = &self.block[ch];
+            let subframe =
+                choose_subframe(samples, bps, &mut self.residual, &mut self.best_residual);
+            write_subframe(&mut bits, samples, bps, &subframe, &self.best_residual);
+        }
+
+        // Frame footer.
+        bits.align();
+        let crc = crc16(&bits.bytes);
+        bits.write(crc as u64, 16);
+
+        self.writer.write_all(&bits.bytes)?;
+        let frame_bytes = bits.bytes.len() as u32;
+        if self.frame_number == 0 || frame_bytes < self.min_frame_bytes {
+            self.min_frame_bytes = frame_bytes;
+        }
+        self.max_frame_bytes = self.max_frame_bytes.max(frame_bytes);
+        self.frame_number += 1;
+        self.total_frames += len as u64;
+        for channel in &mut self.block {
+            channel.clear();
+        }
+        self.bits = bits;
+        Ok(())
+    }
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        BitWriter {
+            bytes: vec![],
+            acc: 0,
+            len: 0,
+        }
+    }
+
+    fn clear(&mut self) {
+        self.bytes.clear();
+        self.acc = 0;
+        self.len = 0;
+    }
+
+    // Write the lowest `bits` bits of `value`, most significant first.
+    fn write(&mut self, value: u64, bits: u32) {
+        if bits > 32 {
+            self.write(value >> 32, bits - 32);
+            self.write(value & 0xFFFF_FFFF, 32);
+            return;
+        }
+        let mask = (1u64 << bits) - 1;
+        self.acc = (self.acc << bits) | (value & mask);
+        self.len += bits;
+        while self.len >= 8 {
+            self.len -= 8;
+            self.bytes.push((self.acc >> self.len) as u8);
+        }
+    }
+
+    fn write_signed(&mut self, value: i32, bits: u32) {
+        self.write(value as u32 as u64, bits);
+    }
+
+    // Write `q` zero bits followed by a one bit.
+    fn write_unary(&mut self, mut q: u64) {
+        while q >= 32 {
+            self.write(0, 32);
+            q -= 32;
+        }
+        self.write(1, q as u32 + 1);
+    }
+
+    // Pad with zero bits up to the next byte boundary.
+    fn align(&mut self) {
+        if self.len > 0 {
+            self.write(0, 8 - self.len);
+        }
+    }
+}
+
+// Write a frame number using FLAC's extended UTF-8 style coding.
+fn write_utf8(bits: &mut BitWriter, n: u64) {
+    if n < 0x80 {
+        bits.write(n, 8);
+        return;
+    }
+    let mut len = 2;
+    while len < 7 && n >= 1 << (5 * len + 1) {
+        len += 1;
+    }
+    let lead = (0xFF00u64 >> len) & 0xFF;
+    bits.write(lead | (n >> (6 * (len - 1))), 8);
+    for i in (0..len - 1).rev() {
+        bits.write(0x80 | ((n >> (6 * i)) & 0x3F), 8);
+    }
+}
+
+// Choose the smallest encoding for a channel, leaving the residual of the chosen fixed predictor
+// in `best_residual`.
+fn choose_subframe(
+    samples: &[i32],
+    bps: u32,
+    residual: &mut Vec<i32>,
+    best_residual: &mut Vec<i32>,
+) -> Subframe {
+    if samples.iter().all(|&s| s == samples[0]) {
+        return Subframe::Constant;
+    }
+    let mut best = Subframe::Verbatim;
+    let mut best_bits = samples.len() as u64 * bps as u64;
+    for order in 0..=std::cmp::min(4, samples.len() - 1) {
+        fixed_residual(samples, order, residual);
+        let (partition_order, residual_bits) = match best_partitioning(residual, order) {
+            Some(p) => p,
+            None => continue,
+        };
+        let bits = order as u64 * bps as u64 + 6 + residual_bits;
+        if bits < best_bits {
+            best_bits = bits;
+            best = Subframe::Fixed {
+                order,
+                partition_order,
+            };
+            std::mem::swap(residual, best_residual);
+        }
+    }
+    best
+}
+
+// The residual of the fixed polynomial predictor of the given order.
+fn fixed_residual(samples: &[i32], order: usize, residual: &mut Vec<i32>) {
+    residual.clear();
+    let s = |i: usize| samples[i] as i64;
+    for i in order..samples.len() {
+        let r = match order {
+            0 => s(i),
+            1 => s(i) - s(i - 1),
+            2 => s(i) - 2 * s(i - 1) + s(i - 2),
+            3 => s(i) - 3 * s(i - 1) + 3 * s(i - 2) - s(i - 3),
+            _ => s(i) - 4 * s(i - 1) + 6 * s(i - 2) - 4 * s(i - 3) + s(i - 4),
+        };
+        // Residuals must fit within 32 bits. In practice this only fails for full-scale noise, in
+        // which case a verbatim subframe is smaller regardless.
+        residual.push(r.max(i32::MIN as i64 + 1).min(i32::MAX as i64) as i32);
+    }
+}
+
+// The partition order and total size in bits of the smallest Rice coding of the residual.
+fn best_partitioning(residual: &[i32], order: usize) -> Option<(u32, u64)> {
+    let block_size = residual.len() + order;
+    let mut best: Option<(u32, u64)> = None;
+    for partition_order in 0..=MAX_PARTITION_ORDER {
+        let partitions = 1usize << partition_order;
+        if block_size % partitions != 0 || block_size / partitions <= order {
+            break;
+        }
+        let mut bits = 6u64;
+        for p in 0..partitions {
+            let (_, partition_bits) = rice_param(partition(residual, order, partition_order, p));
+            bits += 4 + partition_bits;
+        }
+        if best.map(|(_, b)| bits < b).unwrap_or(true) {
+            best = Some((partition_order, bits));
+        }
+    }
+    best
+}
+
+// The residuals of the given partition. The first partition excludes the warm-up samples.
+fn partition(residual: &[i32], order: usize, partition_order: u32, p: usize) -> &[i32] {
+    let len = (residual.len() + order) >> partition_order;
+    let start = if p == 0 { 0 } else { p * len - order };
+    let end = (p + 1) * len - order;
+    &residual[start..end]
+}
+
+fn fold(r: i32) -> u64 {
+    ((r << 1) ^ (r >> 31)) as u32 as u64
+}
+
+// The optimal Rice parameter for the given residuals along with the size of their encoding.
+fn rice_param(residual: &[i32]) -> (u32, u64) {
+    let sum: u64 = residual.iter().map(|&r| fold(r)).sum();
+    let n = residual.len().max(1) as u64;
+    let mean = sum / n;
+    let estimate = if mean == 0 {
+        0
+    } else {
+        63 - mean.leading_zeros()
+    };
+    let cost = |k: u32| -> u64 {
+        residual
+            .iter()
+            .map(|&r| (fold(r) >> k) + 1 + k as u64)
+            .sum()
+    };
+    let lo = estimate.saturating_sub(1).min(MAX_RICE_PARAM);
+    let hi = (estimate + 1).min(MAX_RICE_PARAM);
+    (lo..=hi)
+        .map(|k| (k, cost(k)))
+        .min_by_key(|&(_, bits)| bits)
+        .unwrap_or((0, 0))
+}
+
+fn write_subframe(
+    bits: &mut BitWriter,
+    samples: &[i32],
+    bps: u32,
+    subframe: &Subframe,
+    residual: &[i32],
+) {
+    match *subframe {
+        Subframe::Constant => {
+            // Zero padding bit, type `000000` and no wasted bits.
+            bits.write(0b0000_0000, 8);
+            bits.write_signed(samples[0], bps);
+        }
+        Subframe::Verbatim => {
+            bits.write(0b0000_0010, 8);
+            for &s in samples {
+                bits.write_signed(s, bps);
+            }
+        }
+        Subframe::Fixed {
+            order,
+            partition_order,
+        } => {
+            bits.write(0b0001_0000 | ((order as u64) << 1), 8);
+            for &s in &samples[..order] {
+                bits.write_signed(s, bps);
+            }
+            // Rice coding with 4-bit parameters.
+            bits.write(0b00, 2);
+            bits.write(partition_order as u64, 4);
+            for p in 0..1 << partition_order {
+                let residual = partition(residual, order, partition_order, p);
+                let (k, _) = rice_param(residual);
+                bits.write(k as u64, 4);
+                for &r in residual {
+                    let u = fold(r);
+                    bits.write_unary(u >> k);
+                    bits.write(u, k);
+                }
+            }
+        }
+    }
+}
+
+fn crc8(bytes: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for &byte in bytes {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn crc16(bytes: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for &byte in bytes {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn invalid_input(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
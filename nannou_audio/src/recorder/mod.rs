@@ -0,0 +1,550 @@
+//! Recording the audio of input and output streams to WAV or FLAC files.
+//!
+//! A recorder is made up of two halves:
+//!
+//! - [**Recorder**](./struct.Recorder.html) - lives on the main thread and is used to start, stop
+//!   and split recordings. Encoding and file I/O occur on a dedicated writer thread owned by the
+//!   `Recorder`.
+//! - [**Tap**](./struct.Tap.html) - lives on the audio thread, forwarding the samples of each
+//!   buffer to the writer thread via a lock-free ring buffer. A `Tap` may be attached to any input
+//!   or output stream via the stream builder's `tap` method, or written to manually.
+//!
+//! ```no_run
+//! use nannou_audio::recorder::Recorder;
+//! # fn render(_: &mut (), _: &mut nannou_audio::Buffer) {}
+//!
+//! let host = nannou_audio::Host::new();
+//! let (mut recorder, tap) = Recorder::new();
+//! let stream = host
+//!     .new_output_stream(())
+//!     .render(render)
+//!     .tap(tap)
+//!     .build()
+//!     .unwrap();
+//!
+//! recorder.start("take_1.wav").unwrap();
+//! // ...
+//! recorder.split("take_2.flac").unwrap();
+//! // ...
+//! recorder.stop().unwrap();
+//! ```
+//!
+//! Starts, stops and splits take effect on the boundary of the next buffer delivered to the
+//! `Tap`, so consecutive files produced via `split` are sample-contiguous.
+
+use crate::Buffer;
+use dasp_sample::{Sample, ToSample};
+use ringbuf::{Consumer, Producer, RingBuffer};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{self, AtomicBool, AtomicU64};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+use thiserror::Error;
+
+mod flac;
+
+/// Controls recording of the audio delivered to the associated `Tap`.
+///
+/// Dropping the `Recorder` finishes writing any pending audio, finalises the current file and
+/// then joins the writer thread.
+pub struct Recorder {
+    commands: Producer<Command>,
+    shared: Arc<Shared>,
+    errors: mpsc::Receiver<RecordError>,
+    thread: Option<thread::JoinHandle<()>>,
+    sample_format: Option<SampleFormat>,
+    is_recording: bool,
+}
+
+/// Forwards audio from the audio thread to the `Recorder`'s writer thread.
+///
+/// Writing to the `Tap` never blocks or allocates. If the writer thread falls behind and the ring
+/// buffer fills, whole buffers are dropped and counted via `Recorder::dropped_frames`.
+pub struct Tap {
+    samples: Producer<f32>,
+    commands: Consumer<Command>,
+    events: Producer<Event>,
+    shared: Arc<Shared>,
+    is_recording: bool,
+    // The total number of samples pushed to the ring buffer.
+    pushed: u64,
+}
+
+/// The file formats supported by the `Recorder`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Uncompressed WAV, requiring the `wav` feature.
+    Wav,
+    /// Losslessly compressed FLAC.
+    Flac,
+}
+
+/// The format of the samples written to a file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SampleFormat {
+    /// 16-bit signed integer samples.
+    I16,
+    /// 24-bit signed integer samples.
+    I24,
+    /// 32-bit floating point samples. Only supported by WAV.
+    F32,
+}
+
+/// Errors that might occur while recording.
+#[derive(Debug, Error)]
+pub enum RecordError {
+    #[error("unsupported recording format for path {path:?}")]
+    UnsupportedFormat { path: PathBuf },
+    #[error("{format:?} does not support {sample_format:?} samples")]
+    UnsupportedSampleFormat {
+        format: Format,
+        sample_format: SampleFormat,
+    },
+    #[error("the recorder is already recording")]
+    AlreadyRecording,
+    #[error("the recorder is not recording")]
+    NotRecording,
+    #[error("the recorder's command queue is full")]
+    CommandQueueFull,
+    #[error("an IO error occurred: {err}")]
+    Io {
+        #[from]
+        err: std::io::Error,
+    },
+    #[cfg(feature = "wav")]
+    #[error("failed to write WAV: {err}")]
+    Wav {
+        #[from]
+        err: hound::Error,
+    },
+}
+
+// State shared between the `Recorder`, `Tap` and writer thread.
+struct Shared {
+    closed: AtomicBool,
+    dropped_frames: AtomicU64,
+    written_frames: AtomicU64,
+}
+
+// A request from the `Recorder`, forwarded by the `Tap` so that it takes effect on a buffer
+// boundary.
+enum Command {
+    Start(FileSpec),
+    Split(FileSpec),
+    Stop,
+}
+
+pub(crate) struct FileSpec {
+    path: PathBuf,
+    format: Format,
+    sample_format: SampleFormat,
+}
+
+struct Event {
+    command: Command,
+    // The sample index within the ring buffer stream at which the command takes effect.
+    at: u64,
+    channels: usize,
+    sample_rate: u32,
+}
+
+// The file currently being written by the writer thread.
+pub(crate) enum FileWriter {
+    #[cfg(feature = "wav")]
+    Wav(hound::WavWriter<BufWriter<File>>, SampleFormat),
+    Flac(flac::Writer<BufWriter<File>>),
+}
+
+impl Recorder {
+    /// The default capacity of the ring buffer in samples.
+    ///
+    /// This is roughly three seconds of stereo audio at 44.1KHz.
+    pub const DEFAULT_CAPACITY: usize = 1 << 18;
+
+    /// The default capacity of the command queue.
+    const COMMAND_CAPACITY: usize = 64;
+
+    /// Create a new recorder along with the tap used to feed it audio.
+    pub fn new() -> (Self, Tap) {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+
+    /// Create a new recorder whose ring buffer holds up to `capacity` samples.
+    ///
+    /// A larger capacity gives the writer thread more slack before audio is dropped.
+    ///
+    /// **Panics** if `capacity` is `0`.
+    pub fn with_capacity(capacity: usize) -> (Self, Tap) {
+        assert!(
+            capacity > 0,
+            "the recorder capacity must be greater than zero"
+        );
+        let (samples_tx, samples_rx) = RingBuffer::new(capacity).split();
+        let (commands_tx, commands_rx) = RingBuffer::new(Self::COMMAND_CAPACITY).split();
+        let (events_tx, events_rx) = RingBuffer::new(Self::COMMAND_CAPACITY).split();
+        let (errors_tx, errors_rx) = mpsc::channel();
+        let shared = Arc::new(Shared {
+            closed: AtomicBool::new(false),
+            dropped_frames: AtomicU64::new(0),
+            written_frames: AtomicU64::new(0),
+        });
+        let thread_shared = shared.clone();
+        let thread = thread::Builder::new()
+            .name("nannou_audio-recorder".into())
+            .spawn(move || run(samples_rx, events_rx, thread_shared, errors_tx))
+            .expect("failed to spawn recorder thread");
+        let recorder = Recorder {
+            commands: commands_tx,
+            shared: shared.clone(),
+            errors: errors_rx,
+            thread: Some(thread),
+            sample_format: None,
+            is_recording: false,
+        };
+        let tap = Tap {
+            samples: samples_tx,
+            commands: commands_rx,
+            events: events_tx,
+            shared,
+            is_recording: false,
+            pushed: 0,
+        };
+        (recorder, tap)
+    }
+
+    /// Specify the sample format used for subsequent recordings.
+    ///
+    /// By default, WAV files are written with `F32` samples and FLAC files with `I24` samples.
+    pub fn set_sample_format(&mut self, sample_format: SampleFormat) {
+        self.sample_format = Some(sample_format);
+    }
+
+    /// Whether or not a recording has been started and not yet stopped.
+    pub fn is_recording(&self) -> bool {
+        self.is_recording
+    }
+
+    /// Begin recording to the file at the given path.
+    ///
+    /// The format is determined by the file extension, either `wav` or `flac`.
+    pub fn start<P>(&mut self, path: P) -> Result<(), RecordError>
+    where
+        P: AsRef<Path>,
+    {
+        if self.is_recording {
+            return Err(RecordError::AlreadyRecording);
+        }
+        let spec = file_spec(path.as_ref(), self.sample_format)?;
+        self.send(Command::Start(spec))?;
+        self.is_recording = true;
+        Ok(())
+    }
+
+    /// Finish the current file and continue recording to the file at the given path without
+    /// dropping any samples in between.
+    pub fn split<P>(&mut self, path: P) -> Result<(), RecordError>
+    where
+        P: AsRef<Path>,
+    {
+        if !self.is_recording {
+            return Err(RecordError::NotRecording);
+        }
+        let spec = file_spec(path.as_ref(), self.sample_format)?;
+        self.send(Command::Split(spec))
+    }
+
+    /// Stop recording and finalise the current file.
+    pub fn stop(&mut self) -> Result<(), RecordError> {
+        if !self.is_recording {
+            return Err(RecordError::NotRecording);
+        }
+        self.send(Command::Stop)?;
+        self.is_recording = false;
+        Ok(())
+    }
+
+    /// The number of frames that have been written to files by the writer thread.
+    pub fn written_frames(&self) -> u64 {
+        self.shared.written_frames.load(atomic::Ordering::Relaxed)
+    }
+
+    /// The number of frames dropped due to the writer thread falling behind the audio thread.
+    pub fn dropped_frames(&self) -> u64 {
+        self.shared.dropped_frames.load(atomic::Ordering::Relaxed)
+    }
+
+    /// Errors that have occurred on the writer thread since last checked.
+    pub fn errors(&self) -> mpsc::TryIter<'_, RecordError> {
+        self.errors.try_iter()
+    }
+
+    fn send(&mut self, command: Command) -> Result<(), RecordError> {
+        self.commands
+            .push(command)
+            .map_err(|_| RecordError::CommandQueueFull)
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, atomic::Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+impl Tap {
+    /// Forward the given buffer to the recorder.
+    pub fn write<S>(&mut self, buffer: &Buffer<S>)
+    where
+        S: Sample + ToSample<f32>,
+    {
+        self.write_interleaved(buffer, buffer.channels(), buffer.sample_rate());
+    }
+
+    /// Forward the given interleaved samples to the recorder.
+    ///
+    /// This is useful for recording audio that is not delivered via a `Buffer`.
+    pub fn write_interleaved<S>(&mut self, samples: &[S], channels: usize, sample_rate: u32)
+    where
+        S: Sample + ToSample<f32>,
+    {
+        if self.shared.closed.load(atomic::Ordering::Relaxed) {
+            return;
+        }
+
+        // Forward any pending commands so that they take effect at the start of this buffer.
+        while !self.events.is_full() {
+            let command = match self.commands.pop() {
+                None => break,
+                Some(command) => command,
+            };
+            match command {
+                Command::Start(_) | Command::Split(_) => self.is_recording = true,
+                Command::Stop => self.is_recording = false,
+            }
+            let event = Event {
+                command,
+                at: self.pushed,
+                channels,
+                sample_rate,
+            };
+            self.events.push(event).ok();
+        }
+
+        if !self.is_recording || channels == 0 {
+            return;
+        }
+
+        // Only push whole buffers so that files always contain whole frames.
+        if self.samples.remaining() < samples.len() {
+            let frames = (samples.len() / channels) as u64;
+            self.shared
+                .dropped_frames
+                .fetch_add(frames, atomic::Ordering::Relaxed);
+            return;
+        }
+        let mut iter = samples.iter().map(|s| s.to_sample::<f32>());
+        self.pushed += self.samples.push_iter(&mut iter) as u64;
+    }
+}
+
+// Determine the format of the file at the given path along with the format of its samples.
+pub(crate) fn file_spec(
+    path: &Path,
+    sample_format: Option<SampleFormat>,
+) -> Result<FileSpec, RecordError> {
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+    let format = match ext.as_ref().map(|ext| &ext[..]) {
+        #[cfg(feature = "wav")]
+        Some("wav") | Some("wave") => Format::Wav,
+        Some("flac") => Format::Flac,
+        _ => {
+            let path = path.to_path_buf();
+            return Err(RecordError::UnsupportedFormat { path });
+        }
+    };
+    let sample_format = match (format, sample_format) {
+        (Format::Flac, Some(SampleFormat::F32)) => {
+            let sample_format = SampleFormat::F32;
+            return Err(RecordError::UnsupportedSampleFormat {
+                format,
+                sample_format,
+            });
+        }
+        (_, Some(sample_format)) => sample_format,
+        (Format::Wav, None) => SampleFormat::F32,
+        (Format::Flac, None) => SampleFormat::I24,
+    };
+    let path = path.to_path_buf();
+    Ok(FileSpec {
+        path,
+        format,
+        sample_format,
+    })
+}
+
+impl FileWriter {
+    pub(crate) fn create(
+        spec: &FileSpec,
+        channels: usize,
+        sample_rate: u32,
+    ) -> Result<Self, RecordError> {
+        let file = BufWriter::new(File::create(&spec.path)?);
+        let writer = match spec.format {
+            #[cfg(feature = "wav")]
+            Format::Wav => {
+                let (bits_per_sample, sample_format) = match spec.sample_format {
+                    SampleFormat::I16 => (16, hound::SampleFormat::Int),
+                    SampleFormat::I24 => (24, hound::SampleFormat::Int),
+                    SampleFormat::F32 => (32, hound::SampleFormat::Float),
+                };
+                let wav_spec = hound::WavSpec {
+                    channels: channels as u16,
+                    sample_rate,
+                    bits_per_sample,
+                    sample_format,
+                };
+                let writer = hound::WavWriter::new(file, wav_spec)?;
+                FileWriter::Wav(writer, spec.sample_format)
+            }
+            #[cfg(not(feature = "wav"))]
+            Format::Wav => {
+                let path = spec.path.clone();
+                return Err(RecordError::UnsupportedFormat { path });
+            }
+            Format::Flac => {
+                let bits_per_sample = match spec.sample_format {
+                    SampleFormat::I16 => 16,
+                    _ => 24,
+                };
+                FileWriter::Flac(flac::Writer::new(
+                    file,
+                    channels,
+                    sample_rate,
+                    bits_per_sample,
+                )?)
+            }
+        };
+        Ok(writer)
+    }
+
+    pub(crate) fn write_samples(&mut self, samples: &[f32]) -> Result<(), RecordError> {
+        match *self {
+            #[cfg(feature = "wav")]
+            FileWriter::Wav(ref mut writer, sample_format) => match sample_format {
+                SampleFormat::I16 => {
+                    for &s in samples {
+                        writer.write_sample(s.to_sample::<i16>())?;
+                    }
+                }
+                SampleFormat::I24 => {
+                    for &s in samples {
+                        let s = (s * 8_388_608.0).round().clamp(-8_388_608.0, 8_388_607.0);
+                        writer.write_sample(s as i32)?;
+                    }
+                }
+                SampleFormat::F32 => {
+                    for &s in samples {
+                        writer.write_sample(s)?;
+                    }
+                }
+            },
+            FileWriter::Flac(ref mut writer) => writer.write_samples(samples)?,
+        }
+        Ok(())
+    }
+
+    pub(crate) fn finalize(self) -> Result<(), RecordError> {
+        match self {
+            #[cfg(feature = "wav")]
+            FileWriter::Wav(writer, _) => writer.finalize()?,
+            FileWriter::Flac(writer) => writer.finalize()?,
+        }
+        Ok(())
+    }
+}
+
+// The writer thread.
+fn run(
+    mut samples: Consumer<f32>,
+    mut events: Consumer<Event>,
+    shared: Arc<Shared>,
+    errors: mpsc::Sender<RecordError>,
+) {
+    let mut buffer = vec![0.0; 4_096];
+    let mut file: Option<(FileWriter, usize)> = None;
+    let mut next_event: Option<Event> = None;
+    // The total number of samples popped from the ring buffer.
+    let mut popped: u64 = 0;
+    let report = |result: Result<(), RecordError>| {
+        if let Err(err) = result {
+            errors.send(err).ok();
+        }
+    };
+
+    loop {
+        // Check whether the recorder was closed before draining so that no audio that was pushed
+        // prior to closing is missed.
+        let closed = shared.closed.load(atomic::Ordering::Acquire);
+
+        if next_event.is_none() {
+            next_event = events.pop();
+        }
+
+        // Write samples up to the next event.
+        let limit = next_event
+            .as_ref()
+            .map(|event| (event.at - popped) as usize)
+            .unwrap_or(usize::MAX);
+        let len = std::cmp::min(limit, buffer.len());
+        let n = samples.pop_slice(&mut buffer[..len]);
+        popped += n as u64;
+        if let Some((ref mut writer, channels)) = file {
+            report(writer.write_samples(&buffer[..n]));
+            shared
+                .written_frames
+                .fetch_add((n / channels) as u64, atomic::Ordering::Relaxed);
+        }
+
+        // Apply the next event once all samples preceding it have been written.
+        let event_ready = next_event
+            .as_ref()
+            .map(|event| event.at == popped)
+            .unwrap_or(false);
+        if event_ready {
+            let event = next_event.take().unwrap();
+            if let Some((writer, _)) = file.take() {
+                report(writer.finalize());
+            }
+            match event.command {
+                Command::Start(spec) | Command::Split(spec) => {
+                    match FileWriter::create(&spec, event.channels, event.sample_rate) {
+                        Ok(writer) => file = Some((writer, event.channels)),
+                        Err(err) => report(Err(err)),
+                    }
+                }
+                Command::Stop => (),
+            }
+            continue;
+        }
+
+        if n == 0 {
+            if closed && next_event.is_none() && events.is_empty() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(2));
+        }
+    }
+
+    if let Some((writer, _)) = file.take() {
+        report(writer.finalize());
+    }
+}
@@ -0,0 +1,50 @@
+use super::{dot, sub, Speaker};
+
+/// Distance based amplitude panning, where each speaker's gain falls off with its distance from
+/// the source regardless of the position of the listener.
+#[derive(Clone, Debug)]
+pub(crate) struct Dbap {
+    positions: Vec<[f32; 3]>,
+    // The exponent applied to the distance, derived from the rolloff in decibels.
+    exponent: f32,
+    // The squared spatial blur, added to every squared distance.
+    blur_squared: f32,
+}
+
+impl Dbap {
+    pub(crate) fn new(speakers: &[Speaker]) -> Self {
+        let mut dbap = Dbap {
+            positions: speakers.iter().map(|s| s.position).collect(),
+            exponent: 0.0,
+            blur_squared: 0.0,
+        };
+        dbap.set_rolloff_db(super::DEFAULT_ROLLOFF_DB);
+        dbap.set_blur(super::DEFAULT_BLUR);
+        dbap
+    }
+
+    pub(crate) fn set_rolloff_db(&mut self, db: f32) {
+        self.exponent = db / (20.0 * 2f32.log10());
+    }
+
+    pub(crate) fn set_blur(&mut self, blur: f32) {
+        self.blur_squared = blur * blur;
+    }
+
+    // Write the power normalised gain of each speaker for a source at the given position.
+    pub(crate) fn gains(&self, position: [f32; 3], gains: &mut [f32]) {
+        // Gains are proportional to `1 / d^a`, computed from the squared distance.
+        let half_exponent = self.exponent / 2.0;
+        let mut power = 0.0;
+        for (g, &p) in gains.iter_mut().zip(&self.positions) {
+            let d = sub(position, p);
+            let distance_squared = dot(d, d) + self.blur_squared;
+            *g = distance_squared.max(f32::EPSILON).powf(-half_exponent);
+            power += *g * *g;
+        }
+        let scale = 1.0 / power.sqrt();
+        for g in gains {
+            *g *= scale;
+        }
+    }
+}
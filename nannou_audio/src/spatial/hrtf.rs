@@ -0,0 +1,356 @@
+use super::vbap::{hull, Triangulation};
+use super::{dot, normalize};
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use thiserror::Error;
+
+/// A pair of head-related impulse responses measured from a single direction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hrir {
+    /// The direction of the measurement relative to the listener, using the same coordinates as
+    /// sources.
+    pub direction: [f32; 3],
+    /// The impulse response of the left ear.
+    pub left: Vec<f32>,
+    /// The impulse response of the right ear.
+    pub right: Vec<f32>,
+}
+
+/// A set of head-related impulse responses measured from points around the listener.
+///
+/// The response for any direction is interpolated from the three measured points surrounding it.
+#[derive(Clone, Debug)]
+pub struct HrirSphere {
+    sample_rate: u32,
+    len: usize,
+    directions: Vec<[f32; 3]>,
+    // The time-reversed left and right responses of each point, ready for convolution.
+    responses: Vec<[Vec<f32>; 2]>,
+    triangulation: Triangulation,
+}
+
+/// Errors that might occur while loading an `HrirSphere`.
+#[derive(Debug, Error)]
+pub enum HrirError {
+    #[error("an IO error occurred: {err}")]
+    Io {
+        #[from]
+        err: io::Error,
+    },
+    #[error("the data is not an HRIR sphere")]
+    InvalidFormat,
+    #[error("the sphere contains no impulse responses")]
+    Empty,
+    #[error("the impulse responses are not all of the same non-zero length")]
+    InvalidLength,
+    #[error("a face refers to a point that does not exist")]
+    InvalidFace,
+}
+
+// Convolves a mono source with the interpolated response for its direction.
+#[derive(Clone, Debug)]
+pub(crate) struct Convolver {
+    // The most recent input samples, stored twice over so that the last `len` samples are always
+    // contiguous.
+    history: Vec<f32>,
+    position: usize,
+    responses: [Vec<f32>; 2],
+    next: [Vec<f32>; 2],
+    direction: Option<[f32; 3]>,
+}
+
+impl HrirSphere {
+    /// A sphere from the given impulse responses, all of which must be the same length and
+    /// measured at the given sample rate.
+    ///
+    /// `faces` describes the triangles between the points, each as the indices of three responses.
+    /// If `faces` is empty, the triangles are found by taking the convex hull of the directions,
+    /// which may take a moment for sets of more than a few hundred points.
+    pub fn new(
+        sample_rate: u32,
+        hrirs: Vec<Hrir>,
+        faces: Vec<[usize; 3]>,
+    ) -> Result<Self, HrirError> {
+        let len = match hrirs.first() {
+            None => return Err(HrirError::Empty),
+            Some(hrir) => hrir.left.len(),
+        };
+        let valid_len = |h: &Hrir| h.left.len() == len && h.right.len() == len;
+        if len == 0 || !hrirs.iter().all(valid_len) {
+            return Err(HrirError::InvalidLength);
+        }
+        if faces.iter().flatten().any(|&i| i >= hrirs.len()) {
+            return Err(HrirError::InvalidFace);
+        }
+
+        let mut directions = Vec::with_capacity(hrirs.len());
+        let mut responses = Vec::with_capacity(hrirs.len());
+        for hrir in hrirs {
+            directions.push(normalize(hrir.direction).unwrap_or([0.0, 1.0, 0.0]));
+            let Hrir {
+                mut left,
+                mut right,
+                ..
+            } = hrir;
+            left.reverse();
+            right.reverse();
+            responses.push([left, right]);
+        }
+        let faces = if faces.is_empty() {
+            hull(&directions)
+        } else {
+            faces
+        };
+        let triangulation = Triangulation::new(&directions, &faces);
+        Ok(HrirSphere {
+            sample_rate,
+            len,
+            directions,
+            responses,
+            triangulation,
+        })
+    }
+
+    /// Load a sphere from a file in the format produced by the
+    /// [hrir_sphere_builder](https://github.com/mrDIMAS/hrir_sphere_builder) tool, as used by the
+    /// `hrtf` crate.
+    ///
+    /// The impulse responses are resampled to the given sample rate if necessary.
+    pub fn from_file<P>(path: P, sample_rate: u32) -> Result<Self, HrirError>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::open(path)?;
+        Self::from_reader(BufReader::new(file), sample_rate)
+    }
+
+    /// Read a sphere in the same format as `from_file`.
+    pub fn from_reader<R>(mut reader: R, sample_rate: u32) -> Result<Self, HrirError>
+    where
+        R: Read,
+    {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != b"HRIR" {
+            return Err(HrirError::InvalidFormat);
+        }
+        let file_sample_rate = read_u32(&mut reader)?;
+        let len = read_u32(&mut reader)? as usize;
+        let points = read_u32(&mut reader)? as usize;
+        let indices = read_u32(&mut reader)? as usize;
+        if indices / 3 * 3 != indices {
+            return Err(HrirError::InvalidFormat);
+        }
+        let mut faces = Vec::with_capacity(indices / 3);
+        for _ in 0..indices / 3 {
+            let mut face = [0; 3];
+            for i in &mut face {
+                *i = read_u32(&mut reader)? as usize;
+            }
+            faces.push(face);
+        }
+        let mut hrirs = Vec::with_capacity(points);
+        for _ in 0..points {
+            // The format places `x` to the left, `y` up and `z` behind the listener.
+            let (x, y, z) = (
+                read_f32(&mut reader)?,
+                read_f32(&mut reader)?,
+                read_f32(&mut reader)?,
+            );
+            let mut left = vec![0.0; len];
+            let mut right = vec![0.0; len];
+            for sample in left.iter_mut().chain(&mut right) {
+                *sample = read_f32(&mut reader)?;
+            }
+            hrirs.push(Hrir {
+                direction: [-x, -z, y],
+                left,
+                right,
+            });
+        }
+        let sphere = Self::new(file_sample_rate, hrirs, faces)?;
+        Ok(sphere.resample(sample_rate))
+    }
+
+    /// Resample the impulse responses to the given sample rate via linear interpolation.
+    pub fn resample(mut self, sample_rate: u32) -> Self {
+        if sample_rate == self.sample_rate {
+            return self;
+        }
+        let ratio = self.sample_rate as f64 / sample_rate as f64;
+        let len = ((self.len as f64 / ratio).ceil() as usize).max(1);
+        for response in self.responses.iter_mut().flatten() {
+            // Responses are stored reversed, so restore the original order while resampling.
+            response.reverse();
+            let resampled: Vec<f32> = (0..len)
+                .rev()
+                .map(|i| {
+                    let pos = i as f64 * ratio;
+                    let index = pos as usize;
+                    let fract = (pos - index as f64) as f32;
+                    let a = response.get(index).cloned().unwrap_or(0.0);
+                    let b = response.get(index + 1).cloned().unwrap_or(0.0);
+                    // Scale to preserve the gain of the response at the new rate.
+                    (a + (b - a) * fract) * ratio as f32
+                })
+                .collect();
+            *response = resampled;
+        }
+        self.sample_rate = sample_rate;
+        self.len = len;
+        self
+    }
+
+    /// The sample rate of the impulse responses.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// The number of measured points.
+    pub fn points(&self) -> usize {
+        self.directions.len()
+    }
+
+    /// The length of each impulse response in frames.
+    pub fn hrir_len(&self) -> usize {
+        self.len
+    }
+
+    // Write the time-reversed responses interpolated for the given direction.
+    fn interpolate(&self, direction: [f32; 3], output: &mut [Vec<f32>; 2]) {
+        let (points, weights) = match self.triangulation.weights(direction) {
+            Some(pw) => pw,
+            None => {
+                let nearest = self.nearest(direction);
+                ([nearest; 3], [1.0, 0.0, 0.0])
+            }
+        };
+        let total: f32 = weights.iter().sum();
+        let scale = if total > 0.0 { 1.0 / total } else { 0.0 };
+        for (ear, out) in output.iter_mut().enumerate() {
+            for s in out.iter_mut() {
+                *s = 0.0;
+            }
+            for (&p, &w) in points.iter().zip(&weights) {
+                let w = w * scale;
+                if w == 0.0 {
+                    continue;
+                }
+                for (s, r) in out.iter_mut().zip(&self.responses[p][ear]) {
+                    *s += r * w;
+                }
+            }
+        }
+    }
+
+    // The index of the point closest to the given direction.
+    fn nearest(&self, direction: [f32; 3]) -> usize {
+        let mut best = (0, f32::MIN);
+        for (i, &d) in self.directions.iter().enumerate() {
+            let similarity = dot(d, direction);
+            if similarity > best.1 {
+                best = (i, similarity);
+            }
+        }
+        best.0
+    }
+}
+
+impl Convolver {
+    pub(crate) fn new(sphere: &HrirSphere) -> Self {
+        let len = sphere.len;
+        Convolver {
+            history: vec![0.0; len * 2],
+            position: 0,
+            responses: [vec![0.0; len], vec![0.0; len]],
+            next: [vec![0.0; len], vec![0.0; len]],
+            direction: None,
+        }
+    }
+
+    /// Convolve a block of the source, adding the result to the first two channels of the given
+    /// interleaved output.
+    ///
+    /// The response crossfades across the block from the previous direction to the given
+    /// direction while the gain ramps from `gain.0` to `gain.1`. Sources without a direction
+    /// retain their previous direction.
+    pub(crate) fn process(
+        &mut self,
+        sphere: &HrirSphere,
+        direction: Option<[f32; 3]>,
+        gain: (f32, f32),
+        input: &[f32],
+        output: &mut [f32],
+        channels: usize,
+    ) {
+        let direction = direction.or(self.direction).unwrap_or([0.0, 1.0, 0.0]);
+        let crossfade = match self.direction {
+            Some(prev) if dot(prev, direction) > 0.99999 => false,
+            Some(_) => {
+                sphere.interpolate(direction, &mut self.next);
+                true
+            }
+            None => {
+                sphere.interpolate(direction, &mut self.responses);
+                false
+            }
+        };
+        self.direction = Some(direction);
+
+        let len = self.responses[0].len();
+        let frames = input.len();
+        let step = (gain.1 - gain.0) / frames as f32;
+        for (i, (&x, frame)) in input.iter().zip(output.chunks_mut(channels)).enumerate() {
+            self.history[self.position] = x;
+            self.history[self.position + len] = x;
+            self.position = (self.position + 1) % len;
+            let window = &self.history[self.position..self.position + len];
+            let mut left = convolve(window, &self.responses[0]);
+            let mut right = convolve(window, &self.responses[1]);
+            if crossfade {
+                let t = (i + 1) as f32 / frames as f32;
+                left += (convolve(window, &self.next[0]) - left) * t;
+                right += (convolve(window, &self.next[1]) - right) * t;
+            }
+            let g = gain.0 + step * (i + 1) as f32;
+            if channels == 1 {
+                frame[0] += (left + right) * 0.5 * g;
+            } else {
+                frame[0] += left * g;
+                frame[1] += right * g;
+            }
+        }
+        if crossfade {
+            std::mem::swap(&mut self.responses, &mut self.next);
+        }
+    }
+}
+
+// The dot product of the input window and a time-reversed response, accumulated across several
+// lanes so that the compiler may vectorise it.
+fn convolve(window: &[f32], response: &[f32]) -> f32 {
+    let mut acc = [0.0f32; 8];
+    let chunks = window.chunks_exact(8).zip(response.chunks_exact(8));
+    for (w, r) in chunks {
+        for i in 0..8 {
+            acc[i] += w[i] * r[i];
+        }
+    }
+    let tail = window.len() - window.len() % 8;
+    let mut sum: f32 = acc.iter().sum();
+    for (w, r) in window[tail..].iter().zip(&response[tail..]) {
+        sum += w * r;
+    }
+    sum
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_f32<R: Read>(reader: &mut R) -> io::Result<f32> {
+    read_u32(reader).map(f32::from_bits)
+}
@@ -0,0 +1,145 @@
+use std::f32::consts::PI;
+
+/// A single loudspeaker within a `Layout`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Speaker {
+    /// The position of the speaker relative to the listener.
+    pub position: [f32; 3],
+    /// The index of the output channel that feeds the speaker.
+    pub channel: usize,
+}
+
+/// An arrangement of loudspeakers around the listener.
+///
+/// Positions use the same coordinates as sources, where the listener sits at the origin facing
+/// along the *y* axis with *x* to the right and *z* pointing up.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layout {
+    speakers: Vec<Speaker>,
+}
+
+impl Speaker {
+    /// A speaker at the given azimuth and elevation in radians and distance from the listener.
+    ///
+    /// The azimuth is measured clockwise from the front, so that `PI / 2.0` is directly to the
+    /// right.
+    pub fn from_angles(azimuth: f32, elevation: f32, distance: f32, channel: usize) -> Self {
+        let (sin_az, cos_az) = azimuth.sin_cos();
+        let (sin_el, cos_el) = elevation.sin_cos();
+        let position = [
+            sin_az * cos_el * distance,
+            cos_az * cos_el * distance,
+            sin_el * distance,
+        ];
+        Speaker { position, channel }
+    }
+}
+
+impl Layout {
+    /// A layout with a speaker at each of the given positions.
+    ///
+    /// The speakers are assigned to channels in order, starting from `0`.
+    pub fn new<I>(positions: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<[f32; 3]>,
+    {
+        let speakers = positions
+            .into_iter()
+            .enumerate()
+            .map(|(channel, p)| Speaker {
+                position: p.into(),
+                channel,
+            })
+            .collect();
+        Layout { speakers }
+    }
+
+    /// A layout from the given speakers, each of which specifies its own channel.
+    pub fn from_speakers(speakers: Vec<Speaker>) -> Self {
+        Layout { speakers }
+    }
+
+    /// A stereo pair at 30 degrees either side of the front.
+    pub fn stereo() -> Self {
+        let angle = PI / 6.0;
+        Layout::from_speakers(vec![
+            Speaker::from_angles(-angle, 0.0, 1.0, 0),
+            Speaker::from_angles(angle, 0.0, 1.0, 1),
+        ])
+    }
+
+    /// Four speakers at the corners of a square, ordered front left, front right, rear left and
+    /// rear right.
+    pub fn quad() -> Self {
+        let (front, rear) = (PI / 4.0, PI * 3.0 / 4.0);
+        Layout::from_speakers(vec![
+            Speaker::from_angles(-front, 0.0, 1.0, 0),
+            Speaker::from_angles(front, 0.0, 1.0, 1),
+            Speaker::from_angles(-rear, 0.0, 1.0, 2),
+            Speaker::from_angles(rear, 0.0, 1.0, 3),
+        ])
+    }
+
+    /// The five full-range speakers of an ITU-R BS.775 5.1 layout.
+    ///
+    /// Channels follow the common L, R, C, LFE, Ls, Rs order, leaving channel `3` for the LFE.
+    pub fn surround_5_1() -> Self {
+        let deg = PI / 180.0;
+        Layout::from_speakers(vec![
+            Speaker::from_angles(-30.0 * deg, 0.0, 1.0, 0),
+            Speaker::from_angles(30.0 * deg, 0.0, 1.0, 1),
+            Speaker::from_angles(0.0, 0.0, 1.0, 2),
+            Speaker::from_angles(-110.0 * deg, 0.0, 1.0, 4),
+            Speaker::from_angles(110.0 * deg, 0.0, 1.0, 5),
+        ])
+    }
+
+    /// A ring of evenly spaced speakers at the given distance from the listener.
+    ///
+    /// The first speaker is directly in front, with the rest following clockwise.
+    pub fn ring(count: usize, radius: f32) -> Self {
+        Layout::dome(&[(count, 0.0)], radius)
+    }
+
+    /// Rings of evenly spaced speakers stacked at the given elevations in radians.
+    ///
+    /// Each ring is given as a speaker count and elevation, and the first speaker of each ring is
+    /// directly in front. Channels are assigned ring by ring. A ring of a single speaker at an
+    /// elevation of `PI / 2.0` places a speaker directly overhead.
+    pub fn dome(rings: &[(usize, f32)], radius: f32) -> Self {
+        let mut speakers = vec![];
+        for &(count, elevation) in rings {
+            for i in 0..count {
+                let azimuth = i as f32 * 2.0 * PI / count as f32;
+                let channel = speakers.len();
+                speakers.push(Speaker::from_angles(azimuth, elevation, radius, channel));
+            }
+        }
+        Layout { speakers }
+    }
+
+    /// The speakers within the layout.
+    pub fn speakers(&self) -> &[Speaker] {
+        &self.speakers
+    }
+
+    /// The number of speakers within the layout.
+    pub fn len(&self) -> usize {
+        self.speakers.len()
+    }
+
+    /// Whether or not the layout contains no speakers.
+    pub fn is_empty(&self) -> bool {
+        self.speakers.is_empty()
+    }
+
+    /// The number of output channels required to feed every speaker.
+    pub fn channels(&self) -> usize {
+        self.speakers
+            .iter()
+            .map(|s| s.channel + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
@@ -0,0 +1,480 @@
+//! Positioning sources of audio in space around the listener.
+//!
+//! - [**Spatializer**](./struct.Spatializer.html) - describes how sources are rendered, either to
+//!   a [**Layout**](./struct.Layout.html) of loudspeakers via vector base amplitude panning (VBAP)
+//!   or distance based amplitude panning (DBAP), or binaurally for headphones via an
+//!   [**HrirSphere**](./struct.HrirSphere.html).
+//! - [**Source**](./struct.Source.html) - a single positioned source that mixes mono audio into an
+//!   output stream's buffer.
+//!
+//! The listener sits at the origin facing along the *y* axis, with *x* to the right and *z*
+//! pointing up. This matches the default orientation of a nannou window when viewing the scene
+//! from above, so that a source may be placed beneath the mouse via `pt3(x, y, 0.0)`.
+//!
+//! Sources are typically stored within the audio model and moved by sending their new position
+//! from the main thread. Movement is smoothed over `DEFAULT_SMOOTHING_SECS` and sources are
+//! attenuated according to their distance from the listener.
+//!
+//! ```no_run
+//! use nannou_audio::spatial::{Layout, Source, Spatializer};
+//! use nannou_audio::Buffer;
+//!
+//! struct Audio {
+//!     phase: f32,
+//!     source: Source,
+//!     scratch: Vec<f32>,
+//! }
+//!
+//! fn render(audio: &mut Audio, buffer: &mut Buffer) {
+//!     audio.scratch.clear();
+//!     for _ in 0..buffer.len_frames() {
+//!         audio.phase = (audio.phase + 440.0 / 44_100.0) % 1.0;
+//!         audio.scratch.push((audio.phase * std::f32::consts::PI * 2.0).sin() * 0.2);
+//!     }
+//!     audio.source.render(&audio.scratch, buffer);
+//! }
+//!
+//! let spatializer = Spatializer::vbap(Layout::ring(8, 2.0)).unwrap();
+//! let audio = Audio {
+//!     phase: 0.0,
+//!     source: spatializer.source([0.0, 1.0, 0.0]),
+//!     scratch: Vec::with_capacity(1024),
+//! };
+//! let host = nannou_audio::Host::new();
+//! let stream = host
+//!     .new_output_stream(audio)
+//!     .render(render)
+//!     .channels(spatializer.channels())
+//!     .build()
+//!     .unwrap();
+//!
+//! // Later, on the main thread.
+//! stream
+//!     .send(|audio| audio.source.set_position([2.0, 0.0, 0.0]))
+//!     .unwrap();
+//! ```
+
+use self::dbap::Dbap;
+use self::hrtf::Convolver;
+use self::vbap::Vbap;
+use crate::graph::Smoothed;
+use crate::Buffer;
+use std::sync::Arc;
+use thiserror::Error;
+
+pub use self::hrtf::{Hrir, HrirError, HrirSphere};
+pub use self::layout::{Layout, Speaker};
+
+mod dbap;
+mod hrtf;
+mod layout;
+mod vbap;
+
+/// The duration in seconds over which a change in a source's position is smoothed.
+pub const DEFAULT_SMOOTHING_SECS: f32 = 0.05;
+
+/// The default decrease in decibels of a speaker's gain per doubling of its distance from a
+/// source when using DBAP.
+pub const DEFAULT_ROLLOFF_DB: f32 = 6.0;
+
+/// The default spatial blur of DBAP, in the units of the layout's positions.
+pub const DEFAULT_BLUR: f32 = 0.2;
+
+// The interval in frames at which smoothed positions are sampled and the gains are updated.
+const UPDATE_FRAMES: usize = 64;
+
+/// Describes how sources are rendered to the channels of an output stream.
+///
+/// A `Spatializer` is cheap to clone, with the layout or HRIR sphere shared between clones and all
+/// sources created from it.
+#[derive(Clone, Debug)]
+pub struct Spatializer {
+    method: Arc<Method>,
+    attenuation: Attenuation,
+    smoothing_secs: f32,
+}
+
+/// A single source of audio positioned relative to the listener.
+#[derive(Clone, Debug)]
+pub struct Source {
+    spatializer: Spatializer,
+    position: [Smoothed; 3],
+    // The sample rate at which the smoothing was last configured.
+    sample_rate: u32,
+    // The current and next gain of each speaker, including attenuation.
+    gains: Vec<f32>,
+    next: Vec<f32>,
+    // The current attenuation of binaural output.
+    gain: f32,
+    convolver: Option<Convolver>,
+}
+
+/// Describes how the gain of a source falls with its distance from the listener.
+///
+/// Follows the distance models of the Web Audio API and OpenAL. Sources within the
+/// `reference_distance` of the listener are not attenuated.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Attenuation {
+    /// The curve with which the gain falls.
+    pub model: DistanceModel,
+    /// The distance at which attenuation begins.
+    pub reference_distance: f32,
+    /// The distance beyond which the gain stops decreasing.
+    pub max_distance: f32,
+    /// How quickly the gain falls with distance.
+    pub rolloff: f32,
+}
+
+/// The curve with which the gain of a source falls with distance.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DistanceModel {
+    /// Sources are not attenuated.
+    None,
+    /// The gain falls linearly to `1.0 - rolloff` at `max_distance`.
+    Linear,
+    /// The gain is inversely proportional to the distance.
+    Inverse,
+    /// The gain falls exponentially with the distance.
+    Exponential,
+}
+
+/// Errors that might occur while creating a `Spatializer`.
+#[derive(Debug, Error)]
+pub enum SpatialError {
+    #[error("the layout contains no speakers")]
+    EmptyLayout,
+    #[error("speaker {index} is positioned at the listener and so has no direction")]
+    SpeakerAtListener { index: usize },
+}
+
+#[derive(Clone, Debug)]
+enum Method {
+    Vbap(Vbap, Vec<Speaker>),
+    Dbap(Dbap, Vec<Speaker>),
+    Hrtf(HrirSphere),
+}
+
+impl Spatializer {
+    /// Render sources to the given layout via vector base amplitude panning.
+    ///
+    /// Each source is panned between the two or three speakers surrounding its direction, so that
+    /// sources sound from the direction of the listener regardless of their distance. Layouts
+    /// with all speakers at zero elevation pan horizontally between adjacent pairs. Otherwise the
+    /// source is panned across the triangles formed by the convex hull of the speakers, with
+    /// directions outside of the hull, e.g. below a dome, assigned to the nearest triangle.
+    pub fn vbap(layout: Layout) -> Result<Self, SpatialError> {
+        let speakers = non_empty(layout)?;
+        let vbap = Vbap::new(&speakers)?;
+        Ok(Self::with_method(Method::Vbap(vbap, speakers)))
+    }
+
+    /// Render sources to the given layout via distance based amplitude panning.
+    ///
+    /// The gain of each speaker depends only on its distance from the source, making DBAP suited
+    /// to irregular layouts and installations where the audience moves among the speakers.
+    pub fn dbap(layout: Layout) -> Result<Self, SpatialError> {
+        let speakers = non_empty(layout)?;
+        let dbap = Dbap::new(&speakers);
+        Ok(Self::with_method(Method::Dbap(dbap, speakers)))
+    }
+
+    /// Render sources binaurally to the first two channels via the given HRIR sphere.
+    ///
+    /// The sphere should be loaded at the sample rate of the stream. This is best experienced with
+    /// headphones.
+    pub fn hrtf(sphere: HrirSphere) -> Self {
+        Self::with_method(Method::Hrtf(sphere))
+    }
+
+    fn with_method(method: Method) -> Self {
+        Spatializer {
+            method: Arc::new(method),
+            attenuation: Attenuation::default(),
+            smoothing_secs: DEFAULT_SMOOTHING_SECS,
+        }
+    }
+
+    /// Specify how sources are attenuated with distance.
+    pub fn attenuation(mut self, attenuation: Attenuation) -> Self {
+        self.attenuation = attenuation;
+        self
+    }
+
+    /// Specify the duration over which changes in position are smoothed.
+    ///
+    /// Defaults to `DEFAULT_SMOOTHING_SECS`.
+    pub fn smoothing_secs(mut self, secs: f32) -> Self {
+        self.smoothing_secs = secs.max(0.0);
+        self
+    }
+
+    /// Specify the decrease in decibels of each speaker's gain per doubling of distance.
+    ///
+    /// Only applies to DBAP. Defaults to `DEFAULT_ROLLOFF_DB`.
+    pub fn rolloff_db(mut self, db: f32) -> Self {
+        if let Method::Dbap(ref mut dbap, _) = *Arc::make_mut(&mut self.method) {
+            dbap.set_rolloff_db(db);
+        }
+        self
+    }
+
+    /// Specify the spatial blur, which limits how strongly a source is focused on the speaker
+    /// nearest to it.
+    ///
+    /// Only applies to DBAP. Defaults to `DEFAULT_BLUR`.
+    pub fn blur(mut self, blur: f32) -> Self {
+        if let Method::Dbap(ref mut dbap, _) = *Arc::make_mut(&mut self.method) {
+            dbap.set_blur(blur);
+        }
+        self
+    }
+
+    /// Create a source at the given position.
+    ///
+    /// This allocates, so sources should be created on the main thread and sent to the audio
+    /// thread where possible.
+    pub fn source<P>(&self, position: P) -> Source
+    where
+        P: Into<[f32; 3]>,
+    {
+        Source::new(self, position)
+    }
+
+    /// The number of output channels required to render every speaker.
+    pub fn channels(&self) -> usize {
+        match *self.method {
+            Method::Vbap(_, ref speakers) | Method::Dbap(_, ref speakers) => {
+                speakers.iter().map(|s| s.channel + 1).max().unwrap_or(0)
+            }
+            Method::Hrtf(_) => 2,
+        }
+    }
+
+    /// The speakers to which sources are rendered, or `None` for binaural rendering.
+    pub fn speakers(&self) -> Option<&[Speaker]> {
+        match *self.method {
+            Method::Vbap(_, ref speakers) | Method::Dbap(_, ref speakers) => Some(speakers),
+            Method::Hrtf(_) => None,
+        }
+    }
+
+    // Write the gain of each speaker for a source at the given position.
+    fn gains(&self, position: [f32; 3], gains: &mut [f32]) {
+        match *self.method {
+            Method::Vbap(ref vbap, _) => vbap.gains(position, gains),
+            Method::Dbap(ref dbap, _) => dbap.gains(position, gains),
+            Method::Hrtf(_) => return,
+        }
+        let gain = self.attenuation.gain(length(position));
+        for g in gains {
+            *g *= gain;
+        }
+    }
+}
+
+impl Source {
+    /// Create a source at the given position, rendered via the given spatializer.
+    pub fn new<P>(spatializer: &Spatializer, position: P) -> Self
+    where
+        P: Into<[f32; 3]>,
+    {
+        let [x, y, z] = position.into();
+        let speakers = spatializer.speakers().map(|s| s.len()).unwrap_or(0);
+        let convolver = match *spatializer.method {
+            Method::Hrtf(ref sphere) => Some(Convolver::new(sphere)),
+            _ => None,
+        };
+        let mut gains = vec![0.0; speakers];
+        spatializer.gains([x, y, z], &mut gains);
+        Source {
+            spatializer: spatializer.clone(),
+            position: [Smoothed::new(x), Smoothed::new(y), Smoothed::new(z)],
+            sample_rate: 0,
+            gains,
+            next: vec![0.0; speakers],
+            gain: spatializer.attenuation.gain(length([x, y, z])),
+            convolver,
+        }
+    }
+
+    /// The spatializer used to render the source.
+    pub fn spatializer(&self) -> &Spatializer {
+        &self.spatializer
+    }
+
+    /// The position towards which the source is moving.
+    pub fn position(&self) -> [f32; 3] {
+        let [x, y, z] = &self.position;
+        [x.target(), y.target(), z.target()]
+    }
+
+    /// Move the source to the given position, smoothing the movement.
+    pub fn set_position<P>(&mut self, position: P)
+    where
+        P: Into<[f32; 3]>,
+    {
+        for (p, v) in self.position.iter_mut().zip(&position.into()) {
+            p.set(*v);
+        }
+    }
+
+    /// Move the source to the given position immediately.
+    pub fn reset_position<P>(&mut self, position: P)
+    where
+        P: Into<[f32; 3]>,
+    {
+        for (p, v) in self.position.iter_mut().zip(&position.into()) {
+            p.reset(*v);
+        }
+    }
+
+    /// Render the given mono audio from the source's position, adding the result to the output.
+    ///
+    /// Each frame of `input` is rendered to the frame of the same index within `output`. Speakers
+    /// assigned to channels beyond those of the output are skipped.
+    pub fn render(&mut self, input: &[f32], output: &mut Buffer) {
+        let sample_rate = output.sample_rate();
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            for p in &mut self.position {
+                p.set_secs(self.spatializer.smoothing_secs, sample_rate);
+            }
+        }
+
+        let channels = output.channels();
+        let frames = std::cmp::min(input.len(), output.len_frames());
+        let mut start = 0;
+        while start < frames {
+            let n = std::cmp::min(UPDATE_FRAMES, frames - start);
+            let [x, y, z] = &mut self.position;
+            let position = [
+                x.advance(n as u32),
+                y.advance(n as u32),
+                z.advance(n as u32),
+            ];
+            let input = &input[start..start + n];
+            let output = &mut output[start * channels..(start + n) * channels];
+            match *self.spatializer.method {
+                Method::Vbap(_, ref speakers) | Method::Dbap(_, ref speakers) => {
+                    self.spatializer.gains(position, &mut self.next);
+                    pan(speakers, &self.gains, &self.next, input, output, channels);
+                    std::mem::swap(&mut self.gains, &mut self.next);
+                }
+                Method::Hrtf(ref sphere) => {
+                    let gain = self.spatializer.attenuation.gain(length(position));
+                    let convolver = self.convolver.as_mut().expect("no convolver for HRTF");
+                    let direction = normalize(position);
+                    let gains = (self.gain, gain);
+                    convolver.process(sphere, direction, gains, input, output, channels);
+                    self.gain = gain;
+                }
+            }
+            start += n;
+        }
+    }
+}
+
+impl Attenuation {
+    /// The gain of a source at the given distance from the listener.
+    pub fn gain(&self, distance: f32) -> f32 {
+        let reference = self.reference_distance.max(0.0);
+        let max = self.max_distance.max(reference);
+        let distance = distance.max(reference).min(max);
+        let gain = match self.model {
+            DistanceModel::None => 1.0,
+            DistanceModel::Linear => {
+                if max > reference {
+                    1.0 - self.rolloff * (distance - reference) / (max - reference)
+                } else {
+                    1.0
+                }
+            }
+            DistanceModel::Inverse => {
+                let d = reference + self.rolloff * (distance - reference);
+                if d > 0.0 {
+                    reference / d
+                } else {
+                    1.0
+                }
+            }
+            DistanceModel::Exponential => {
+                if reference > 0.0 {
+                    (distance / reference).powf(-self.rolloff)
+                } else {
+                    1.0
+                }
+            }
+        };
+        gain.clamp(0.0, 1.0)
+    }
+}
+
+impl Default for Attenuation {
+    fn default() -> Self {
+        Attenuation {
+            model: DistanceModel::Inverse,
+            reference_distance: 1.0,
+            max_distance: 10_000.0,
+            rolloff: 1.0,
+        }
+    }
+}
+
+// Add the input to each speaker's channel, ramping each gain across the block.
+fn pan(
+    speakers: &[Speaker],
+    gains: &[f32],
+    next: &[f32],
+    input: &[f32],
+    output: &mut [f32],
+    channels: usize,
+) {
+    let step = 1.0 / input.len() as f32;
+    for ((speaker, &gain), &next) in speakers.iter().zip(gains).zip(next) {
+        let c = speaker.channel;
+        if c >= channels || (gain == 0.0 && next == 0.0) {
+            continue;
+        }
+        let delta = (next - gain) * step;
+        for (i, (&x, frame)) in input.iter().zip(output.chunks_mut(channels)).enumerate() {
+            frame[c] += x * (gain + delta * (i + 1) as f32);
+        }
+    }
+}
+
+fn non_empty(layout: Layout) -> Result<Vec<Speaker>, SpatialError> {
+    if layout.is_empty() {
+        return Err(SpatialError::EmptyLayout);
+    }
+    Ok(layout.speakers().to_vec())
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn length(a: [f32; 3]) -> f32 {
+    dot(a, a).sqrt()
+}
+
+// The given vector scaled to unit length, or `None` if its length is zero.
+fn normalize(a: [f32; 3]) -> Option<[f32; 3]> {
+    let len = length(a);
+    if len > f32::EPSILON {
+        Some([a[0] / len, a[1] / len, a[2] / len])
+    } else {
+        None
+    }
+}
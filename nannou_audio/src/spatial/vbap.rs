@@ -0,0 +1,263 @@
+use super::{cross, dot, normalize, sub, SpatialError, Speaker};
+use std::f32::consts::PI;
+
+// Tolerance used when testing for coplanar speakers and whether a direction lies within a face.
+const EPSILON: f32 = 1e-4;
+
+/// Vector base amplitude panning across pairs of speakers for horizontal layouts, or across
+/// triangles of speakers for layouts with elevation.
+#[derive(Clone, Debug)]
+pub(crate) enum Vbap {
+    Single,
+    Pairs(Vec<Pair>),
+    Triangles(Triangulation),
+}
+
+// Two speakers that are adjacent in azimuth.
+#[derive(Clone, Debug)]
+pub(crate) struct Pair {
+    speakers: [usize; 2],
+    azimuth: f32,
+    // The angle swept clockwise from the first speaker to the second.
+    arc: f32,
+    // The inverse of the matrix of the speakers' directions, or `None` for arcs too wide to be
+    // panned across via vectors, in which case the gains are interpolated by angle.
+    inverse: Option<[[f32; 2]; 2]>,
+}
+
+/// A set of triangles over points on the unit sphere, used to find the weights with which a
+/// direction is formed from the three points surrounding it.
+#[derive(Clone, Debug)]
+pub(crate) struct Triangulation {
+    faces: Vec<Face>,
+}
+
+#[derive(Clone, Debug)]
+struct Face {
+    vertices: [usize; 3],
+    inverse: [[f32; 3]; 3],
+}
+
+impl Vbap {
+    pub(crate) fn new(speakers: &[Speaker]) -> Result<Self, SpatialError> {
+        let mut directions = Vec::with_capacity(speakers.len());
+        for (index, speaker) in speakers.iter().enumerate() {
+            match normalize(speaker.position) {
+                Some(dir) => directions.push(dir),
+                None => return Err(SpatialError::SpeakerAtListener { index }),
+            }
+        }
+        if directions.len() == 1 {
+            return Ok(Vbap::Single);
+        }
+        if directions.iter().all(|d| d[2].abs() < EPSILON) {
+            return Ok(Vbap::Pairs(pairs(&directions)));
+        }
+        let faces = hull(&directions);
+        Ok(Vbap::Triangles(Triangulation::new(&directions, &faces)))
+    }
+
+    // Write the power normalised gain of each speaker for the given direction.
+    pub(crate) fn gains(&self, dir: [f32; 3], gains: &mut [f32]) {
+        for g in gains.iter_mut() {
+            *g = 0.0;
+        }
+        match *self {
+            Vbap::Single => gains[0] = 1.0,
+            Vbap::Pairs(ref pairs) => {
+                let len = (dir[0] * dir[0] + dir[1] * dir[1]).sqrt();
+                if len < EPSILON {
+                    return uniform(gains);
+                }
+                let (x, y) = (dir[0] / len, dir[1] / len);
+                let azimuth = x.atan2(y);
+                let pair = pairs
+                    .iter()
+                    .find(|p| wrap(azimuth - p.azimuth) <= p.arc)
+                    .unwrap_or(&pairs[0]);
+                let [a, b] = pair.speakers;
+                let (ga, gb) = match pair.inverse {
+                    Some(m) => (m[0][0] * x + m[0][1] * y, m[1][0] * x + m[1][1] * y),
+                    None => {
+                        let t = (wrap(azimuth - pair.azimuth) / pair.arc).min(1.0);
+                        let (sin, cos) = (t * PI / 2.0).sin_cos();
+                        (cos, sin)
+                    }
+                };
+                gains[a] += ga.max(0.0);
+                gains[b] += gb.max(0.0);
+                normalize_power(gains);
+            }
+            Vbap::Triangles(ref triangulation) => match triangulation.weights(dir) {
+                Some((vertices, weights)) => {
+                    for (&v, &w) in vertices.iter().zip(&weights) {
+                        gains[v] = w;
+                    }
+                    normalize_power(gains);
+                }
+                None => uniform(gains),
+            },
+        }
+    }
+}
+
+impl Triangulation {
+    /// Prepare the given faces over the given unit directions, skipping any that are degenerate.
+    pub(crate) fn new(directions: &[[f32; 3]], faces: &[[usize; 3]]) -> Self {
+        let faces = faces
+            .iter()
+            .filter_map(|&vertices| {
+                let [a, b, c] = vertices;
+                let inverse = invert3([directions[a], directions[b], directions[c]])?;
+                Some(Face { vertices, inverse })
+            })
+            .collect();
+        Triangulation { faces }
+    }
+
+    /// The vertices of the face through which the given direction passes along with the
+    /// non-negative weight of each.
+    ///
+    /// Directions that pass through no face, e.g. below a dome of speakers, are assigned to the
+    /// nearest face. Returns `None` if the direction is zero or there are no faces.
+    pub(crate) fn weights(&self, dir: [f32; 3]) -> Option<([usize; 3], [f32; 3])> {
+        let dir = normalize(dir)?;
+        let mut best: Option<(f32, &Face, [f32; 3])> = None;
+        for face in &self.faces {
+            let m = &face.inverse;
+            let w = [dot(m[0], dir), dot(m[1], dir), dot(m[2], dir)];
+            let min = w[0].min(w[1]).min(w[2]);
+            if min >= -EPSILON && w.iter().sum::<f32>() > 0.0 {
+                best = Some((min, face, w));
+                break;
+            }
+            if best.as_ref().map(|b| min > b.0).unwrap_or(true) {
+                best = Some((min, face, w));
+            }
+        }
+        let (_, face, w) = best?;
+        let w = [w[0].max(0.0), w[1].max(0.0), w[2].max(0.0)];
+        Some((face.vertices, w))
+    }
+}
+
+/// The faces of the convex hull of the given unit directions.
+///
+/// This tests every combination of three points and so is only suitable for sets of up to a few
+/// hundred points.
+pub(crate) fn hull(directions: &[[f32; 3]]) -> Vec<[usize; 3]> {
+    let n = directions.len();
+    let mut faces = vec![];
+    for i in 0..n {
+        for j in i + 1..n {
+            for k in j + 1..n {
+                let (a, b, c) = (directions[i], directions[j], directions[k]);
+                let normal = cross(sub(b, a), sub(c, a));
+                if dot(normal, normal) < EPSILON * EPSILON {
+                    continue;
+                }
+                let (mut above, mut below) = (false, false);
+                for (m, &p) in directions.iter().enumerate() {
+                    if m == i || m == j || m == k {
+                        continue;
+                    }
+                    let d = dot(normal, sub(p, a));
+                    above |= d > EPSILON;
+                    below |= d < -EPSILON;
+                    if above && below {
+                        break;
+                    }
+                }
+                if !(above && below) {
+                    faces.push([i, j, k]);
+                }
+            }
+        }
+    }
+    faces
+}
+
+// Adjacent pairs of speakers sorted by azimuth.
+fn pairs(directions: &[[f32; 3]]) -> Vec<Pair> {
+    let mut order: Vec<(usize, f32)> = directions
+        .iter()
+        .enumerate()
+        .map(|(i, d)| (i, d[0].atan2(d[1])))
+        .collect();
+    order.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+    (0..order.len())
+        .map(|i| {
+            let (a, azimuth) = order[i];
+            let (b, next) = order[(i + 1) % order.len()];
+            let arc = wrap(next - azimuth);
+            let inverse = if arc < PI - EPSILON {
+                let (da, db) = (directions[a], directions[b]);
+                invert2([[da[0], db[0]], [da[1], db[1]]])
+            } else {
+                None
+            };
+            Pair {
+                speakers: [a, b],
+                azimuth,
+                arc,
+                inverse,
+            }
+        })
+        .collect()
+}
+
+// Wrap the given angle to the range `0..2PI`.
+fn wrap(angle: f32) -> f32 {
+    let a = angle % (2.0 * PI);
+    if a < 0.0 {
+        a + 2.0 * PI
+    } else {
+        a
+    }
+}
+
+fn uniform(gains: &mut [f32]) {
+    let g = 1.0 / (gains.len() as f32).sqrt();
+    for gain in gains {
+        *gain = g;
+    }
+}
+
+fn normalize_power(gains: &mut [f32]) {
+    let power: f32 = gains.iter().map(|g| g * g).sum();
+    if power > 0.0 {
+        let scale = 1.0 / power.sqrt();
+        for g in gains {
+            *g *= scale;
+        }
+    }
+}
+
+// Invert the matrix whose columns are the given vectors.
+fn invert2(m: [[f32; 2]; 2]) -> Option<[[f32; 2]; 2]> {
+    let det = m[0][0] * m[1][1] - m[0][1] * m[1][0];
+    if det.abs() < EPSILON {
+        return None;
+    }
+    let inv = 1.0 / det;
+    Some([
+        [m[1][1] * inv, -m[0][1] * inv],
+        [-m[1][0] * inv, m[0][0] * inv],
+    ])
+}
+
+// Invert the matrix whose columns are the given vectors, returning its rows.
+fn invert3(cols: [[f32; 3]; 3]) -> Option<[[f32; 3]; 3]> {
+    let [a, b, c] = cols;
+    let det = dot(a, cross(b, c));
+    if det.abs() < EPSILON {
+        return None;
+    }
+    let inv = 1.0 / det;
+    let rows = [cross(b, c), cross(c, a), cross(a, b)];
+    Some([
+        [rows[0][0] * inv, rows[0][1] * inv, rows[0][2] * inv],
+        [rows[1][0] * inv, rows[1][1] * inv, rows[1][2] * inv],
+        [rows[2][0] * inv, rows[2][1] * inv, rows[2][2] * inv],
+    ])
+}
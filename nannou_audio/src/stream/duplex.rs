@@ -0,0 +1,700 @@
+use crate::{
+    recorder::Tap,
+    stream::{
+        self,
+        recovery::{self, Recovery},
+        DefaultErrorFn, ErrorFn,
+    },
+    Buffer, Device, Requester, Stream,
+};
+use cpal::traits::{DeviceTrait, HostTrait};
+use dasp_sample::{FromSample, Sample, ToSample};
+use ringbuf::{Consumer, Producer, RingBuffer};
+use std::sync::atomic::{self, AtomicBool, AtomicUsize};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+
+/// The function that will be called with each captured input `Buffer` along with the output
+/// `Buffer` that is to be rendered for the same period.
+pub trait ProcessFn<M, S>: Fn(&mut M, &Buffer<S>, &mut Buffer<S>) {}
+
+/// The default process function type used when unspecified.
+pub type DefaultProcessFn<M, S> = fn(&mut M, &Buffer<S>, &mut Buffer<S>);
+
+// The default process function used when unspecified.
+pub(crate) fn default_process_fn<M, S>(_: &mut M, _: &Buffer<S>, _: &mut Buffer<S>) {}
+
+/// A type used for building a duplex stream.
+///
+/// The sample rate, channels, device and buffer size of the inner `builder` apply to the output.
+pub struct Builder<M, FP, FE, S = f32> {
+    pub builder: super::Builder<M, S>,
+    pub input_device: Option<Device>,
+    pub input_channels: Option<usize>,
+    pub latency_frames: Option<usize>,
+    pub process: FP,
+    pub error: FE,
+}
+
+/// The builder when first initialised.
+pub type BuilderInit<M, S = f32> = Builder<M, DefaultProcessFn<M, S>, DefaultErrorFn<M>, S>;
+
+/// The greatest fraction by which the rate of the input may be adjusted to compensate for drift
+/// between the input and output clocks.
+///
+/// A correction of `0.005` is roughly 9 cents, well beyond the drift between typical devices.
+pub const MAX_DRIFT_CORRECTION: f64 = 0.005;
+
+// The duration over which the number of queued input frames is averaged.
+const FILL_SMOOTHING_SECS: f64 = 0.5;
+
+// The duration over which a difference between the queued and target latency is corrected.
+const CORRECTION_SECS: f64 = 4.0;
+
+// The duration over which persistent differences accumulate into an estimate of the drift.
+const INTEGRAL_SECS: f64 = 16.0;
+
+// The capacity of the queue of captured input in frames.
+const QUEUE_FRAMES: usize = 16_384;
+
+// The user's model along with the state required to read from the input.
+struct Duplex<M, S> {
+    model: M,
+    link: Link<S>,
+}
+
+// Reads captured input at the rate of the output, compensating for drift.
+struct Link<S> {
+    consumer: Consumer<f32>,
+    channels: usize,
+    sample_rate: u32,
+    // The buffer passed to the process function.
+    input: Buffer<S>,
+    // Interleaved input frames popped from the queue but not yet fully consumed.
+    window: Vec<f32>,
+    window_frames: usize,
+    // The position of the next output frame within the window.
+    position: f64,
+    // The number of input frames per output frame.
+    nominal_ratio: f64,
+    // The average number of queued input frames.
+    fill: f64,
+    // The estimated drift of the input clock relative to the output clock.
+    drift: f64,
+    // Whether or not input is being withheld until the target latency is reached.
+    priming: bool,
+    min_latency_frames: usize,
+    // The most frames delivered by a single input callback.
+    burst_frames: Arc<AtomicUsize>,
+}
+
+impl<M, S, F> ProcessFn<M, S> for F where F: Fn(&mut M, &Buffer<S>, &mut Buffer<S>) {}
+
+impl<M, FP, FE, S> Builder<M, FP, FE, S> {
+    /// Specify the function used to process each input buffer into an output buffer.
+    pub fn process<GP>(self, process: GP) -> Builder<M, GP, FE, S> {
+        let Builder {
+            builder,
+            input_device,
+            input_channels,
+            latency_frames,
+            error,
+            ..
+        } = self;
+        Builder {
+            builder,
+            input_device,
+            input_channels,
+            latency_frames,
+            process,
+            error,
+        }
+    }
+
+    /// Specify a function for handling errors on either the input or output stream.
+    pub fn error<GE>(self, error: GE) -> Builder<M, FP, GE, S> {
+        let Builder {
+            builder,
+            input_device,
+            input_channels,
+            latency_frames,
+            process,
+            ..
+        } = self;
+        Builder {
+            builder,
+            input_device,
+            input_channels,
+            latency_frames,
+            process,
+            error,
+        }
+    }
+
+    pub fn sample_rate(mut self, sample_rate: u32) -> Self {
+        assert!(sample_rate > 0);
+        self.builder.sample_rate = Some(sample_rate);
+        self
+    }
+
+    /// The number of output channels.
+    pub fn channels(mut self, channels: usize) -> Self {
+        assert!(channels > 0);
+        self.builder.channels = Some(channels);
+        self
+    }
+
+    /// The number of input channels.
+    pub fn input_channels(mut self, channels: usize) -> Self {
+        assert!(channels > 0);
+        self.input_channels = Some(channels);
+        self
+    }
+
+    /// The output device.
+    pub fn device(mut self, device: Device) -> Self {
+        self.builder.device = Some(device);
+        self
+    }
+
+    /// The input device. Defaults to the host's default input device.
+    pub fn input_device(mut self, device: Device) -> Self {
+        self.input_device = Some(device);
+        self
+    }
+
+    pub fn frames_per_buffer(mut self, frames_per_buffer: usize) -> Self {
+        assert!(frames_per_buffer > 0);
+        self.builder.frames_per_buffer = Some(frames_per_buffer);
+        self
+    }
+
+    pub fn device_buffer_size(mut self, buffer_size: cpal::BufferSize) -> Self {
+        self.builder.device_buffer_size = Some(buffer_size);
+        self
+    }
+
+    /// The minimum number of input frames to hold in reserve to absorb differences in the timing
+    /// of the input and output callbacks.
+    ///
+    /// By default this is the largest number of frames delivered by a single input callback plus
+    /// twice the `frames_per_buffer`. Smaller values are ignored.
+    pub fn latency_frames(mut self, frames: usize) -> Self {
+        self.latency_frames = Some(frames);
+        self
+    }
+
+    /// Forward the rendered audio of the stream to a `Recorder` via the given `Tap`.
+    pub fn tap(mut self, tap: Tap) -> Self {
+        self.builder.tap = Some(tap);
+        self
+    }
+
+    /// Rebuild the stream on the same or fallback devices if either of its devices becomes
+    /// unavailable.
+    ///
+    /// See `Stream::poll_recovery`.
+    pub fn recovery(mut self, recovery: Recovery) -> Self {
+        self.builder.recovery = Some(recovery);
+        self
+    }
+
+    pub fn build(self) -> std::result::Result<Stream<M>, super::BuildError>
+    where
+        S: 'static
+            + Send
+            + Sample
+            + ToSample<u16>
+            + ToSample<i16>
+            + ToSample<f32>
+            + FromSample<f32>,
+        M: 'static + Send,
+        FP: 'static + ProcessFn<M, S> + Send,
+        FE: 'static + ErrorFn<M> + Send,
+    {
+        let Builder {
+            process,
+            error,
+            input_device,
+            input_channels,
+            latency_frames,
+            builder:
+                stream::Builder {
+                    host,
+                    model,
+                    sample_rate,
+                    channels,
+                    frames_per_buffer,
+                    device_buffer_size,
+                    device,
+                    mut tap,
+                    recovery,
+                    ..
+                },
+        } = self;
+
+        let output_device = match device {
+            None => host
+                .default_output_device()
+                .ok_or(super::BuildError::DefaultDevice)?,
+            Some(Device { device }) => device,
+        };
+        let input_device = match input_device {
+            None => host
+                .default_input_device()
+                .ok_or(super::BuildError::DefaultDevice)?,
+            Some(Device { device }) => device,
+        };
+
+        // Find the best matching output config.
+        let desired = super::DesiredStreamConfig {
+            sample_format: super::cpal_sample_format::<S>(),
+            channels,
+            sample_rate: sample_rate.map(cpal::SampleRate),
+            device_buffer_size: device_buffer_size.clone(),
+        };
+        let matching = super::find_best_matching_config(
+            &output_device,
+            desired,
+            output_device.default_output_config().ok(),
+            |device| device.supported_output_configs().map(|fs| fs.collect()),
+        )?
+        .expect("no matching supported audio output formats for the target device");
+        let num_channels = matching.config.channels as usize;
+        let sample_rate = matching.config.sample_rate.0;
+        let sample_format = matching.sample_format;
+        let stream_config: cpal::StreamConfig = matching.config;
+
+        // Prefer an input config at the output's sample rate, falling back to any other rate.
+        let input_configs =
+            |device: &cpal::Device| device.supported_input_configs().map(|fs| fs.collect());
+        let desired_input = |sample_rate| super::DesiredStreamConfig {
+            sample_format: super::cpal_sample_format::<S>(),
+            channels: input_channels,
+            sample_rate,
+            device_buffer_size: device_buffer_size.clone(),
+        };
+        let default_input_config = input_device.default_input_config().ok();
+        let input_matching = match super::find_best_matching_config(
+            &input_device,
+            desired_input(Some(cpal::SampleRate(sample_rate))),
+            default_input_config.clone(),
+            input_configs,
+        )? {
+            Some(matching) => matching,
+            None => super::find_best_matching_config(
+                &input_device,
+                desired_input(None),
+                default_input_config,
+                input_configs,
+            )?
+            .expect("no matching supported audio input formats for the target device"),
+        };
+        let input_num_channels = input_matching.config.channels as usize;
+        let input_sample_rate = input_matching.config.sample_rate.0;
+        let input_sample_format = input_matching.sample_format;
+        let input_stream_config: cpal::StreamConfig = input_matching.config;
+
+        let (update_tx, update_rx) = mpsc::channel();
+        let model = Arc::new(Mutex::new(Some(model)));
+        let model_render = model.clone();
+        let model_error = model.clone();
+
+        // A buffer for collecting model updates.
+        let mut pending_updates: Vec<Box<dyn FnMut(&mut M) + 'static + Send>> = Vec::new();
+
+        // Get the specified frames_per_buffer or fall back to a default.
+        let frames_per_buffer = frames_per_buffer.unwrap_or(Buffer::<S>::DEFAULT_LEN_FRAMES);
+
+        // The queue through which captured input is delivered to the output callback.
+        let queue = RingBuffer::<f32>::new(QUEUE_FRAMES * input_num_channels);
+        let (mut producer, consumer): (Producer<f32>, _) = queue.split();
+        let burst_frames = Arc::new(AtomicUsize::new(0));
+        let mut link = Some(Link::new(
+            consumer,
+            input_num_channels,
+            input_sample_rate,
+            sample_rate,
+            frames_per_buffer,
+            latency_frames.unwrap_or(0),
+            burst_frames.clone(),
+        ));
+
+        // The function used to queue each buffer of captured input.
+        let capture_fn = move |data: &cpal::Data, _info: &cpal::InputCallbackInfo| {
+            fn push_input<I>(producer: &mut Producer<f32>, input: &[I], channels: usize)
+            where
+                I: Sample + ToSample<f32>,
+            {
+                // If the output has stalled, drop whole frames of input rather than block.
+                let len = producer.remaining() / channels * channels;
+                for &sample in input.iter().take(len) {
+                    producer.push(sample.to_sample()).ok();
+                }
+            }
+
+            match input_sample_format {
+                cpal::SampleFormat::U16 => {
+                    let input = data.as_slice::<u16>().expect("expected u16 data");
+                    push_input(&mut producer, input, input_num_channels)
+                }
+                cpal::SampleFormat::I16 => {
+                    let input = data.as_slice::<i16>().expect("expected i16 data");
+                    push_input(&mut producer, input, input_num_channels)
+                }
+                cpal::SampleFormat::F32 => {
+                    let input = data.as_slice::<f32>().expect("expected f32 data");
+                    push_input(&mut producer, input, input_num_channels)
+                }
+            }
+            let frames = data.len() / input_num_channels;
+            burst_frames.fetch_max(frames, atomic::Ordering::Relaxed);
+        };
+
+        // An audio requester which requests frames from the model+process pair with a specific
+        // buffer size, regardless of the buffer size requested by the OS.
+        let mut requester = Requester::new(frames_per_buffer, num_channels);
+
+        // An intermediary buffer for converting cpal samples to the target sample format.
+        let mut samples = vec![S::EQUILIBRIUM; frames_per_buffer * num_channels];
+
+        // Read the input for each buffer before passing both to the user's function.
+        let render = move |duplex: &mut Duplex<M, S>, output: &mut Buffer<S>| {
+            duplex.link.read(output.len_frames());
+            process(&mut duplex.model, &duplex.link.input, output);
+        };
+
+        // The function used to process a buffer of samples.
+        let render_fn = move |data: &mut cpal::Data, _info: &cpal::OutputCallbackInfo| {
+            // Collect and process any pending updates.
+            macro_rules! process_pending_updates {
+                () => {
+                    // Collect any pending updates.
+                    pending_updates.extend(update_rx.try_iter());
+
+                    // If there are some updates available, take the lock and apply them.
+                    if !pending_updates.is_empty() {
+                        if let Ok(mut guard) = model_render.lock() {
+                            let mut model = guard.take().unwrap();
+                            for mut update in pending_updates.drain(..) {
+                                update(&mut model);
+                            }
+                            *guard = Some(model);
+                        }
+                    }
+                };
+            }
+
+            process_pending_updates!();
+
+            samples.clear();
+            samples.resize(data.len(), S::EQUILIBRIUM);
+
+            if let Ok(mut guard) = model_render.lock() {
+                let duplex = Duplex {
+                    model: guard.take().unwrap(),
+                    link: link.take().unwrap(),
+                };
+                let duplex =
+                    requester.fill_buffer(duplex, &render, &mut samples, num_channels, sample_rate);
+                *guard = Some(duplex.model);
+                link = Some(duplex.link);
+            }
+
+            // Forward the rendered audio to the recorder, if any.
+            if let Some(ref mut tap) = tap {
+                tap.write_interleaved(&samples, num_channels, sample_rate);
+            }
+
+            // A function to simplify filling the unknown buffer type.
+            fn fill_output<O, S>(output: &mut [O], buffer: &[S])
+            where
+                O: Sample,
+                S: Sample + ToSample<O>,
+            {
+                for (out_sample, sample) in output.iter_mut().zip(buffer) {
+                    *out_sample = sample.to_sample();
+                }
+            }
+
+            // Process the given buffer.
+            match sample_format {
+                cpal::SampleFormat::U16 => {
+                    let output = data.as_slice_mut::<u16>().expect("expected u16 data");
+                    fill_output(output, &samples);
+                }
+                cpal::SampleFormat::I16 => {
+                    let output = data.as_slice_mut::<i16>().expect("expected i16 data");
+                    fill_output(output, &samples);
+                }
+                cpal::SampleFormat::F32 => {
+                    let output = data.as_slice_mut::<f32>().expect("expected f32 data");
+                    fill_output(output, &samples);
+                }
+            }
+        };
+
+        // Wrap the user's error function, shared between the input and output streams.
+        let err_fn = move |err: cpal::StreamError| {
+            if let Ok(mut guard) = model_error.lock() {
+                if let Some(ref mut model) = *guard {
+                    error(model, err);
+                }
+            }
+        };
+
+        // Share the callbacks between any streams rebuilt during recovery, flagging the loss of
+        // either device.
+        let (output_slot, output_lost) =
+            recovery::Slot::new(recovery::Direction::Output, &output_device);
+        let (input_slot, input_lost) =
+            recovery::Slot::new(recovery::Direction::Input, &input_device);
+        let render_fn = Arc::new(Mutex::new(render_fn));
+        let capture_fn = Arc::new(Mutex::new(capture_fn));
+        let err_fn = Arc::new(Mutex::new(err_fn));
+        let config = stream_config.clone();
+        let build_streams = move |output_device: &cpal::Device,
+                                  input_device: &cpal::Device|
+              -> Result<_, cpal::BuildStreamError> {
+            let report = |lost: &Arc<AtomicBool>| {
+                let lost = lost.clone();
+                let err_fn = err_fn.clone();
+                move |err: cpal::StreamError| {
+                    if let cpal::StreamError::DeviceNotAvailable = err {
+                        lost.store(true, atomic::Ordering::Relaxed);
+                    }
+                    if let Ok(mut err_fn) = err_fn.lock() {
+                        (*err_fn)(err);
+                    }
+                }
+            };
+            let capture_fn = capture_fn.clone();
+            let input_stream = input_device.build_input_stream_raw(
+                &input_stream_config,
+                input_sample_format,
+                move |data: &cpal::Data, info: &cpal::InputCallbackInfo| {
+                    if let Ok(mut capture_fn) = capture_fn.lock() {
+                        (*capture_fn)(data, info);
+                    }
+                },
+                report(&input_lost),
+            )?;
+            let render_fn = render_fn.clone();
+            let stream = output_device.build_output_stream_raw(
+                &config,
+                sample_format,
+                move |data: &mut cpal::Data, info: &cpal::OutputCallbackInfo| {
+                    if let Ok(mut render_fn) = render_fn.lock() {
+                        (*render_fn)(data, info);
+                    }
+                },
+                report(&output_lost),
+            )?;
+            Ok(super::Streams {
+                stream,
+                input_stream: Some(input_stream),
+            })
+        };
+
+        let streams = build_streams(&output_device, &input_device)?;
+        let recovery = recovery.map(|recovery| {
+            let slots = vec![output_slot, input_slot];
+            let rebuild = move |devices: &[cpal::Device]| build_streams(&devices[0], &devices[1]);
+            recovery::State::new(recovery, host, slots, Box::new(rebuild))
+        });
+        let shared = Arc::new(super::Shared::new(streams, model, recovery));
+
+        let stream = Stream {
+            shared,
+            update_tx,
+            cpal_config: stream_config,
+        };
+        Ok(stream)
+    }
+}
+
+impl<S> Link<S>
+where
+    S: Sample + FromSample<f32>,
+{
+    fn new(
+        consumer: Consumer<f32>,
+        channels: usize,
+        input_sample_rate: u32,
+        sample_rate: u32,
+        frames_per_buffer: usize,
+        min_latency_frames: usize,
+        burst_frames: Arc<AtomicUsize>,
+    ) -> Self {
+        let nominal_ratio = input_sample_rate as f64 / sample_rate as f64;
+        let max_ratio = nominal_ratio * (1.0 + MAX_DRIFT_CORRECTION);
+        let window_frames = (frames_per_buffer as f64 * max_ratio).ceil() as usize + 2;
+        let input = Buffer {
+            interleaved_samples: vec![S::EQUILIBRIUM; frames_per_buffer * channels].into(),
+            channels,
+            sample_rate,
+        };
+        Link {
+            consumer,
+            channels,
+            sample_rate,
+            input,
+            window: vec![0.0; window_frames * channels],
+            window_frames: 0,
+            position: 0.0,
+            nominal_ratio,
+            fill: 0.0,
+            drift: 0.0,
+            priming: true,
+            min_latency_frames,
+            burst_frames,
+        }
+    }
+
+    // Fill the input buffer with the next `frames` frames of input.
+    fn read(&mut self, frames: usize) {
+        let channels = self.channels;
+        let burst = self.burst_frames.load(atomic::Ordering::Relaxed);
+        // Allow for the input arriving just after the output is requested, plus a buffer of margin.
+        let target = std::cmp::max(self.min_latency_frames, burst + frames * 2);
+        let mut available = self.consumer.len() / channels;
+
+        // If the output has fallen far behind, e.g. after the stream was paused, skip ahead.
+        if available > target * 2 + burst {
+            let skip = available - target;
+            self.consumer.discard(skip * channels);
+            available = target;
+            self.fill = target as f64;
+        }
+
+        // Withhold input until enough has been queued to absorb the timing of the callbacks.
+        if self.priming {
+            if available < target {
+                return self.silence();
+            }
+            self.priming = false;
+            self.fill = available as f64;
+        }
+
+        // Nudge the rate at which the input is read towards maintaining the target latency.
+        let secs = frames as f64 / self.sample_rate as f64;
+        let input_rate = self.sample_rate as f64 * self.nominal_ratio;
+        self.fill += (available as f64 - self.fill) * (secs / FILL_SMOOTHING_SECS).min(1.0);
+        let error_secs = (self.fill - target as f64) / input_rate;
+        let max = MAX_DRIFT_CORRECTION;
+        self.drift += error_secs * secs / (CORRECTION_SECS * INTEGRAL_SECS);
+        self.drift = self.drift.clamp(-max, max);
+        let correction = (error_secs / CORRECTION_SECS + self.drift).clamp(-max, max);
+        let ratio = self.nominal_ratio * (1.0 + correction);
+
+        // Pop the frames required to interpolate the whole buffer.
+        let last = self.position + (frames - 1) as f64 * ratio;
+        let needed = last as usize + 2;
+        if needed > self.window_frames {
+            let missing = needed - self.window_frames;
+            if available < missing {
+                self.priming = true;
+                return self.silence();
+            }
+            let start = self.window_frames * channels;
+            let end = needed * channels;
+            self.consumer.pop_slice(&mut self.window[start..end]);
+            self.window_frames = needed;
+        }
+
+        // Linearly interpolate each output frame.
+        let window = &self.window;
+        let frames_out = self.input.chunks_exact_mut(channels).take(frames);
+        for (i, frame) in frames_out.enumerate() {
+            let pos = self.position + i as f64 * ratio;
+            let index = pos as usize;
+            let fract = (pos - index as f64) as f32;
+            let a = &window[index * channels..(index + 1) * channels];
+            let b = &window[(index + 1) * channels..(index + 2) * channels];
+            for ((sample, &a), &b) in frame.iter_mut().zip(a).zip(b) {
+                *sample = (a + (b - a) * fract).to_sample();
+            }
+        }
+
+        // Drop the frames that have been passed.
+        let end = self.position + frames as f64 * ratio;
+        let consumed = std::cmp::min(end as usize, self.window_frames);
+        self.window
+            .copy_within(consumed * channels..self.window_frames * channels, 0);
+        self.window_frames -= consumed;
+        self.position = end - consumed as f64;
+    }
+
+    fn silence(&mut self) {
+        for sample in self.input.iter_mut() {
+            *sample = S::EQUILIBRIUM;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 44_100;
+    const FRAMES: usize = 64;
+
+    // A mono link whose input callbacks deliver `FRAMES` frames at a time.
+    fn link(min_latency_frames: usize) -> (Producer<f32>, Link<f32>) {
+        let (producer, consumer) = RingBuffer::<f32>::new(QUEUE_FRAMES).split();
+        let burst_frames = Arc::new(AtomicUsize::new(FRAMES));
+        let link = Link::new(
+            consumer,
+            1,
+            RATE,
+            RATE,
+            FRAMES,
+            min_latency_frames,
+            burst_frames,
+        );
+        (producer, link)
+    }
+
+    // Push the given frames of a ramp, where each frame's value is its index.
+    fn push_ramp(producer: &mut Producer<f32>, frames: std::ops::Range<usize>) {
+        for i in frames {
+            producer.push(i as f32).unwrap();
+        }
+    }
+
+    #[test]
+    fn input_is_withheld_until_the_target_latency_is_queued() {
+        let (mut producer, mut link) = link(512);
+        push_ramp(&mut producer, 0..511);
+        link.read(FRAMES);
+        assert!(link.input.iter().all(|&s| s == 0.0));
+
+        push_ramp(&mut producer, 511..512);
+        link.read(FRAMES);
+        let expected: Vec<f32> = (0..FRAMES).map(|i| i as f32).collect();
+        assert_eq!(&link.input[..], &expected[..]);
+    }
+
+    #[test]
+    fn latency_is_maintained_despite_drift() {
+        let target = 1_024;
+        // Input clocks running fast and slow by 0.1% relative to the output.
+        for &rate in &[1.001, 1.0, 0.999] {
+            let (mut producer, mut link) = link(target);
+            let (mut pushed, mut due) = (0, 0.0);
+            // Simulate 90 seconds of callbacks, by which point the drift has been estimated.
+            for _ in 0..RATE as usize * 90 / FRAMES {
+                due += FRAMES as f64 * rate;
+                let end = due as usize;
+                push_ramp(&mut producer, pushed..end);
+                pushed = end;
+                link.read(FRAMES);
+            }
+            assert!((link.drift - (rate - 1.0)).abs() < 1e-4);
+            // The latency between the newest input and the end of the output buffer.
+            let latency = (pushed - 1) as f32 - link.input[FRAMES - 1];
+            let range = (target - 2 * FRAMES) as f32..=target as f32;
+            assert!(range.contains(&latency), "{}", latency);
+        }
+    }
+}
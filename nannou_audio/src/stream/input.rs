@@ -1,10 +1,15 @@
 use crate::{
-    stream::{self, DefaultErrorFn, ErrorFn},
+    recorder::Tap,
+    stream::{
+        self,
+        recovery::{self, Recovery},
+        DefaultErrorFn, ErrorFn,
+    },
     Buffer, Device, Receiver, Stream,
 };
 use cpal::traits::{DeviceTrait, HostTrait};
 use dasp_sample::{FromSample, Sample, ToSample};
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic;
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 
@@ -88,6 +93,20 @@ impl<M, FC, FE, S> Builder<M, FC, FE, S> {
         self
     }
 
+    /// Forward the captured audio of the stream to a `Recorder` via the given `Tap`.
+    pub fn tap(mut self, tap: Tap) -> Self {
+        self.builder.tap = Some(tap);
+        self
+    }
+
+    /// Rebuild the stream on the same or a fallback device if its device becomes unavailable.
+    ///
+    /// See `Stream::poll_recovery`.
+    pub fn recovery(mut self, recovery: Recovery) -> Self {
+        self.builder.recovery = Some(recovery);
+        self
+    }
+
     pub fn build(self) -> std::result::Result<Stream<M>, super::BuildError>
     where
         S: 'static + Send + Sample + FromSample<u16> + FromSample<i16> + FromSample<f32>,
@@ -107,6 +126,8 @@ impl<M, FC, FE, S> Builder<M, FC, FE, S> {
                     frames_per_buffer,
                     device_buffer_size,
                     device,
+                    mut tap,
+                    recovery,
                     ..
                 },
         } = self;
@@ -140,7 +161,7 @@ impl<M, FC, FE, S> Builder<M, FC, FE, S> {
         let num_channels = matching.config.channels as usize;
         let sample_rate = matching.config.sample_rate.0;
         let sample_format = matching.sample_format;
-        let stream_config = matching.config.into();
+        let stream_config: cpal::StreamConfig = matching.config;
 
         // A buffer for collecting model updates.
         let mut pending_updates: Vec<Box<dyn FnMut(&mut M) + 'static + Send>> = Vec::new();
@@ -193,18 +214,31 @@ impl<M, FC, FE, S> Builder<M, FC, FE, S> {
                 }
             }
 
+            // Forward the captured audio to the recorder, if any.
+            fn forward_input<I>(tap: &mut Option<Tap>, input: &[I], channels: usize, rate: u32)
+            where
+                I: Sample + ToSample<f32>,
+            {
+                if let Some(ref mut tap) = *tap {
+                    tap.write_interleaved(input, channels, rate);
+                }
+            }
+
             match sample_format {
                 cpal::SampleFormat::U16 => {
                     let input = data.as_slice::<u16>().expect("expected u16 data");
                     fill_input(&mut samples, &input);
+                    forward_input(&mut tap, input, num_channels, sample_rate);
                 }
                 cpal::SampleFormat::I16 => {
                     let input = data.as_slice::<i16>().expect("expected i16 data");
                     fill_input(&mut samples, &input);
+                    forward_input(&mut tap, input, num_channels, sample_rate);
                 }
                 cpal::SampleFormat::F32 => {
                     let input = data.as_slice::<f32>().expect("expected f32 data");
                     fill_input(&mut samples, &input);
+                    forward_input(&mut tap, input, num_channels, sample_rate);
                 }
             }
 
@@ -217,8 +251,12 @@ impl<M, FC, FE, S> Builder<M, FC, FE, S> {
             process_pending_updates!();
         };
 
-        // Wrap the user's error function.
-        let err_fn = move |err| {
+        // Wrap the user's error function, flagging the loss of the device for recovery.
+        let (slot, lost) = recovery::Slot::new(recovery::Direction::Input, &device);
+        let err_fn = move |err: cpal::StreamError| {
+            if let cpal::StreamError::DeviceNotAvailable = err {
+                lost.store(true, atomic::Ordering::Relaxed);
+            }
             if let Ok(mut guard) = model_error.lock() {
                 if let Some(ref mut model) = *guard {
                     error(model, err);
@@ -226,14 +264,39 @@ impl<M, FC, FE, S> Builder<M, FC, FE, S> {
             }
         };
 
-        let stream =
-            device.build_input_stream_raw(&stream_config, sample_format, capture_fn, err_fn)?;
+        // Share the callbacks between any streams rebuilt during recovery.
+        let capture_fn = Arc::new(Mutex::new(capture_fn));
+        let err_fn = Arc::new(Mutex::new(err_fn));
+        let config = stream_config.clone();
+        let build_stream = move |device: &cpal::Device| -> Result<_, cpal::BuildStreamError> {
+            let capture_fn = capture_fn.clone();
+            let err_fn = err_fn.clone();
+            let stream = device.build_input_stream_raw(
+                &config,
+                sample_format,
+                move |data: &cpal::Data, info: &cpal::InputCallbackInfo| {
+                    if let Ok(mut capture_fn) = capture_fn.lock() {
+                        (*capture_fn)(data, info);
+                    }
+                },
+                move |err| {
+                    if let Ok(mut err_fn) = err_fn.lock() {
+                        (*err_fn)(err);
+                    }
+                },
+            )?;
+            Ok(super::Streams {
+                stream,
+                input_stream: None,
+            })
+        };
 
-        let shared = Arc::new(super::Shared {
-            stream,
-            model,
-            is_paused: AtomicBool::new(false),
+        let streams = build_stream(&device)?;
+        let recovery = recovery.map(|recovery| {
+            let rebuild = move |devices: &[cpal::Device]| build_stream(&devices[0]);
+            recovery::State::new(recovery, host, vec![slot], Box::new(rebuild))
         });
+        let shared = Arc::new(super::Shared::new(streams, model, recovery));
 
         let stream = Stream {
             shared,
@@ -1,3 +1,5 @@
+use crate::recorder::Tap;
+use crate::stream::recovery::Recovery;
 use crate::Device;
 use cpal::traits::StreamTrait;
 use std;
@@ -7,14 +9,14 @@ use std::sync::atomic::{self, AtomicBool};
 use std::sync::{mpsc, Arc, Mutex};
 use thiserror::Error;
 
+/// Items related to duplex (synchronised input/output) audio streams.
+pub mod duplex;
 /// Items related to input audio streams.
 pub mod input;
 /// Items related to output audio streams.
 pub mod output;
-/// Items related to duplex (synchronised input/output) audio streams.
-///
-/// *Progress is currently pending implementation of duplex audio streams in CPAL.*
-pub mod duplex {}
+/// Items related to recovering streams from the loss of their device.
+pub mod recovery;
 
 /// Called by the audio host in the case that an error occurs on an audio stream thread.
 pub trait ErrorFn<M>: Fn(&mut M, cpal::StreamError) {}
@@ -36,12 +38,22 @@ pub struct Stream<M> {
 
 // Data shared between each `Stream` handle to a single stream.
 struct Shared<M> {
-    // The CPAL stream handle.
-    stream: cpal::Stream,
+    // The CPAL stream handles, or `None` while a lost stream awaits recovery.
+    streams: Mutex<Option<Streams>>,
     // The user's audio model
     model: Arc<Mutex<Option<M>>>,
     // Whether or not the stream is currently paused.
     is_paused: AtomicBool,
+    // The state used to rebuild the stream on the loss of its device, if enabled.
+    recovery: Option<Mutex<recovery::State>>,
+}
+
+// The CPAL streams driving a single `Stream`.
+pub(crate) struct Streams {
+    // The CPAL stream handle.
+    stream: cpal::Stream,
+    // The input stream feeding a duplex stream.
+    input_stream: Option<cpal::Stream>,
 }
 
 /// Stream building parameters that are common between input and output streams.
@@ -53,6 +65,8 @@ pub struct Builder<M, S = f32> {
     pub frames_per_buffer: Option<usize>,
     pub device_buffer_size: Option<cpal::BufferSize>,
     pub device: Option<Device>,
+    pub tap: Option<Tap>,
+    pub recovery: Option<Recovery>,
     pub(crate) sample_format: PhantomData<S>,
 }
 
@@ -164,6 +178,34 @@ impl<M> Stream<M> {
         Ok(())
     }
 
+    /// Check for the loss of the stream's device and attempt to rebuild the stream if due.
+    ///
+    /// Returns the transitions that occurred since the last call. This should be called regularly
+    /// from the thread that owns the stream, e.g. within the app's `update` function. Always
+    /// returns an empty list for streams built without `recovery`.
+    ///
+    /// While the stream is lost, model updates sent via `send` are queued until the stream
+    /// resumes.
+    pub fn poll_recovery(&self) -> Vec<recovery::Event> {
+        let recovery = match self.shared.recovery {
+            None => return vec![],
+            Some(ref recovery) => recovery,
+        };
+        let play = !self.shared.is_paused();
+        match recovery.lock() {
+            Ok(mut state) => state.poll(&self.shared.streams, play),
+            Err(_) => vec![],
+        }
+    }
+
+    /// Whether or not the stream has lost its device and is awaiting recovery.
+    pub fn is_lost(&self) -> bool {
+        match self.shared.streams.lock() {
+            Ok(guard) => guard.is_none(),
+            Err(_) => false,
+        }
+    }
+
     /// The config with which the inner CPAL stream was created.
     ///
     /// This **should** match the actual stream config that is running. If not, there may be a bug
@@ -176,14 +218,35 @@ impl<M> Stream<M> {
 }
 
 impl<M> Shared<M> {
+    fn new(
+        streams: Streams,
+        model: Arc<Mutex<Option<M>>>,
+        recovery: Option<recovery::State>,
+    ) -> Self {
+        Shared {
+            streams: Mutex::new(Some(streams)),
+            model,
+            is_paused: AtomicBool::new(false),
+            recovery: recovery.map(Mutex::new),
+        }
+    }
+
     fn play(&self) -> Result<(), cpal::PlayStreamError> {
-        self.stream.play()?;
+        if let Ok(guard) = self.streams.lock() {
+            if let Some(ref streams) = *guard {
+                streams.play()?;
+            }
+        }
         self.is_paused.store(false, atomic::Ordering::Relaxed);
         Ok(())
     }
 
     fn pause(&self) -> Result<(), cpal::PauseStreamError> {
-        self.stream.pause()?;
+        if let Ok(guard) = self.streams.lock() {
+            if let Some(ref streams) = *guard {
+                streams.pause()?;
+            }
+        }
         self.is_paused.store(true, atomic::Ordering::Relaxed);
         Ok(())
     }
@@ -197,6 +260,23 @@ impl<M> Shared<M> {
     }
 }
 
+impl Streams {
+    fn play(&self) -> Result<(), cpal::PlayStreamError> {
+        if let Some(ref input_stream) = self.input_stream {
+            input_stream.play()?;
+        }
+        self.stream.play()
+    }
+
+    fn pause(&self) -> Result<(), cpal::PauseStreamError> {
+        self.stream.pause()?;
+        if let Some(ref input_stream) = self.input_stream {
+            input_stream.pause()?;
+        }
+        Ok(())
+    }
+}
+
 impl<M, F> ErrorFn<M> for F where F: Fn(&mut M, cpal::StreamError) {}
 
 impl<M> Clone for Stream<M> {
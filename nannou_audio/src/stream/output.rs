@@ -1,10 +1,15 @@
 use crate::{
-    stream::{self, DefaultErrorFn, ErrorFn},
+    recorder::Tap,
+    stream::{
+        self,
+        recovery::{self, Recovery},
+        DefaultErrorFn, ErrorFn,
+    },
     Buffer, Device, Requester, Stream,
 };
 use cpal::traits::{DeviceTrait, HostTrait};
 use dasp_sample::{Sample, ToSample};
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic;
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 
@@ -87,6 +92,20 @@ impl<M, FR, FE, S> Builder<M, FR, FE, S> {
         self
     }
 
+    /// Forward the rendered audio of the stream to a `Recorder` via the given `Tap`.
+    pub fn tap(mut self, tap: Tap) -> Self {
+        self.builder.tap = Some(tap);
+        self
+    }
+
+    /// Rebuild the stream on the same or a fallback device if its device becomes unavailable.
+    ///
+    /// See `Stream::poll_recovery`.
+    pub fn recovery(mut self, recovery: Recovery) -> Self {
+        self.builder.recovery = Some(recovery);
+        self
+    }
+
     pub fn build(self) -> std::result::Result<Stream<M>, super::BuildError>
     where
         S: 'static + Send + Sample + ToSample<u16> + ToSample<i16> + ToSample<f32>,
@@ -106,6 +125,8 @@ impl<M, FR, FE, S> Builder<M, FR, FE, S> {
                     frames_per_buffer,
                     device_buffer_size,
                     device,
+                    mut tap,
+                    recovery,
                     ..
                 },
         } = self;
@@ -139,7 +160,7 @@ impl<M, FR, FE, S> Builder<M, FR, FE, S> {
         let num_channels = matching.config.channels as usize;
         let sample_rate = matching.config.sample_rate.0;
         let sample_format = matching.sample_format;
-        let stream_config = matching.config.into();
+        let stream_config: cpal::StreamConfig = matching.config;
 
         // A buffer for collecting model updates.
         let mut pending_updates: Vec<Box<dyn FnMut(&mut M) + 'static + Send>> = Vec::new();
@@ -188,6 +209,11 @@ impl<M, FR, FE, S> Builder<M, FR, FE, S> {
                 *guard = Some(m);
             }
 
+            // Forward the rendered audio to the recorder, if any.
+            if let Some(ref mut tap) = tap {
+                tap.write_interleaved(&samples, num_channels, sample_rate);
+            }
+
             // A function to simplify filling the unknown buffer type.
             fn fill_output<O, S>(output: &mut [O], buffer: &[S])
             where
@@ -216,8 +242,12 @@ impl<M, FR, FE, S> Builder<M, FR, FE, S> {
             }
         };
 
-        // Wrap the user's error function.
-        let err_fn = move |err| {
+        // Wrap the user's error function, flagging the loss of the device for recovery.
+        let (slot, lost) = recovery::Slot::new(recovery::Direction::Output, &device);
+        let err_fn = move |err: cpal::StreamError| {
+            if let cpal::StreamError::DeviceNotAvailable = err {
+                lost.store(true, atomic::Ordering::Relaxed);
+            }
             if let Ok(mut guard) = model_error.lock() {
                 if let Some(ref mut model) = *guard {
                     error(model, err);
@@ -225,14 +255,39 @@ impl<M, FR, FE, S> Builder<M, FR, FE, S> {
             }
         };
 
-        let stream =
-            device.build_output_stream_raw(&stream_config, sample_format, render_fn, err_fn)?;
+        // Share the callbacks between any streams rebuilt during recovery.
+        let render_fn = Arc::new(Mutex::new(render_fn));
+        let err_fn = Arc::new(Mutex::new(err_fn));
+        let config = stream_config.clone();
+        let build_stream = move |device: &cpal::Device| -> Result<_, cpal::BuildStreamError> {
+            let render_fn = render_fn.clone();
+            let err_fn = err_fn.clone();
+            let stream = device.build_output_stream_raw(
+                &config,
+                sample_format,
+                move |data: &mut cpal::Data, info: &cpal::OutputCallbackInfo| {
+                    if let Ok(mut render_fn) = render_fn.lock() {
+                        (*render_fn)(data, info);
+                    }
+                },
+                move |err| {
+                    if let Ok(mut err_fn) = err_fn.lock() {
+                        (*err_fn)(err);
+                    }
+                },
+            )?;
+            Ok(super::Streams {
+                stream,
+                input_stream: None,
+            })
+        };
 
-        let shared = Arc::new(super::Shared {
-            stream,
-            model,
-            is_paused: AtomicBool::new(false),
+        let streams = build_stream(&device)?;
+        let recovery = recovery.map(|recovery| {
+            let rebuild = move |devices: &[cpal::Device]| build_stream(&devices[0]);
+            recovery::State::new(recovery, host, vec![slot], Box::new(rebuild))
         });
+        let shared = Arc::new(super::Shared::new(streams, model, recovery));
 
         let stream = Stream {
             shared,
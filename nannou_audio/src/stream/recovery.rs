@@ -0,0 +1,348 @@
+use super::Streams;
+use cpal::traits::{DeviceTrait, HostTrait};
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// How a stream responds to the loss of one of its devices, e.g. when a USB interface is
+/// disconnected mid-performance.
+///
+/// When enabled via the stream builder's `recovery` method, a stream whose device becomes
+/// unavailable is rebuilt on the same device once it reappears, or on a fallback device, keeping
+/// the user's model. Recovery is driven by `Stream::poll_recovery`, which should be called
+/// regularly from the thread that owns the stream, e.g. within the app's `update` function.
+///
+/// The stream is rebuilt with the same config, so a fallback device must support the sample rate,
+/// channel count and sample format with which the stream was originally created.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recovery {
+    retry_interval: Duration,
+    fallback_devices: Vec<String>,
+    fallback_to_default: bool,
+}
+
+/// A transition in the state of a recoverable stream, reported by `Stream::poll_recovery`.
+#[derive(Debug)]
+pub enum Event {
+    /// The device with the given name became unavailable and the stream has stopped.
+    DeviceLost { device: String },
+    /// The stream was rebuilt on the given devices and has resumed.
+    ///
+    /// Duplex streams list the output device followed by the input device.
+    Recovered { devices: Vec<String> },
+    /// An attempt to rebuild the stream failed. Another attempt will be made after the retry
+    /// interval.
+    RetryFailed { err: RecoveryError },
+}
+
+/// Errors that might occur while attempting to rebuild a lost stream.
+#[derive(Debug, Error)]
+pub enum RecoveryError {
+    #[error("none of the candidate {direction} devices are available")]
+    NoDevice { direction: Direction },
+    #[error("failed to enumerate devices: {err}")]
+    Devices {
+        #[from]
+        err: cpal::DevicesError,
+    },
+    #[error("failed to rebuild stream: {err}")]
+    BuildStream {
+        #[from]
+        err: cpal::BuildStreamError,
+    },
+    #[error("failed to play rebuilt stream: {err}")]
+    PlayStream {
+        #[from]
+        err: cpal::PlayStreamError,
+    },
+}
+
+/// Whether a device is used for input or output.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Input,
+    Output,
+}
+
+// A function for building the CPAL streams on the given devices, one for each `Slot`.
+pub(crate) type RebuildFn = dyn FnMut(&[cpal::Device]) -> Result<Streams, cpal::BuildStreamError>;
+
+// A device in use by a recoverable stream.
+pub(crate) struct Slot {
+    direction: Direction,
+    // The name of the device with which the stream was built.
+    preferred: String,
+    // The name of the device currently in use.
+    current: String,
+    // Set by the stream's error function when the device becomes unavailable.
+    lost: Arc<AtomicBool>,
+}
+
+// The recovery state of a stream, owned by its `Shared` data.
+pub(crate) struct State {
+    recovery: Recovery,
+    host: Arc<cpal::Host>,
+    slots: Vec<Slot>,
+    rebuild: Box<RebuildFn>,
+    // While the stream is lost, the time at which to next attempt to rebuild it.
+    next_attempt: Option<Instant>,
+}
+
+impl Recovery {
+    /// The default interval between attempts to rebuild a lost stream.
+    pub const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Recover on the same device once it reappears, falling back to the default device.
+    pub fn new() -> Self {
+        Recovery {
+            retry_interval: Self::DEFAULT_RETRY_INTERVAL,
+            fallback_devices: vec![],
+            fallback_to_default: true,
+        }
+    }
+
+    /// The interval between attempts to rebuild a lost stream.
+    pub fn retry_interval(mut self, interval: Duration) -> Self {
+        self.retry_interval = interval;
+        self
+    }
+
+    /// Add a device to try, by name, if the original device is unavailable.
+    ///
+    /// Fallback devices are tried in the order in which they were added.
+    pub fn fallback_device<N>(mut self, name: N) -> Self
+    where
+        N: Into<String>,
+    {
+        self.fallback_devices.push(name.into());
+        self
+    }
+
+    /// Whether or not to try the host's current default device after all other candidates.
+    ///
+    /// Enabled by default. When disabled, a stream only recovers on its original device or one
+    /// of the named fallback devices.
+    pub fn fallback_to_default(mut self, fallback: bool) -> Self {
+        self.fallback_to_default = fallback;
+        self
+    }
+}
+
+impl Slot {
+    // Returns the slot along with the flag that the stream's error function should set.
+    pub(crate) fn new(direction: Direction, device: &cpal::Device) -> (Self, Arc<AtomicBool>) {
+        let name = device.name().unwrap_or_default();
+        let lost = Arc::new(AtomicBool::new(false));
+        let slot = Slot {
+            direction,
+            preferred: name.clone(),
+            current: name,
+            lost: lost.clone(),
+        };
+        (slot, lost)
+    }
+}
+
+impl State {
+    pub(crate) fn new(
+        recovery: Recovery,
+        host: Arc<cpal::Host>,
+        slots: Vec<Slot>,
+        rebuild: Box<RebuildFn>,
+    ) -> Self {
+        State {
+            recovery,
+            host,
+            slots,
+            rebuild,
+            next_attempt: None,
+        }
+    }
+
+    // Check for the loss of any devices and attempt to rebuild the stream if it is due.
+    pub(crate) fn poll(&mut self, streams: &Mutex<Option<Streams>>, play: bool) -> Vec<Event> {
+        let mut events = vec![];
+        let now = Instant::now();
+
+        for slot in &self.slots {
+            if slot.lost.swap(false, atomic::Ordering::Relaxed) && self.next_attempt.is_none() {
+                let device = slot.current.clone();
+                events.push(Event::DeviceLost { device });
+                self.next_attempt = Some(now);
+                // Drop the dead streams so that their devices are released before rebuilding.
+                if let Ok(mut guard) = streams.lock() {
+                    guard.take();
+                }
+            }
+        }
+
+        match self.next_attempt {
+            Some(next_attempt) if next_attempt <= now => (),
+            _ => return events,
+        }
+
+        match self.rebuild(streams, play) {
+            Ok(devices) => {
+                self.next_attempt = None;
+                events.push(Event::Recovered { devices });
+            }
+            Err(err) => {
+                self.next_attempt = Some(now + self.recovery.retry_interval);
+                events.push(Event::RetryFailed { err });
+            }
+        }
+        events
+    }
+
+    // Find the devices for each slot and rebuild the streams on them.
+    fn rebuild(
+        &mut self,
+        streams: &Mutex<Option<Streams>>,
+        play: bool,
+    ) -> Result<Vec<String>, RecoveryError> {
+        let mut devices = Vec::with_capacity(self.slots.len());
+        let mut names = Vec::with_capacity(self.slots.len());
+        for slot in &self.slots {
+            let (device, name) = self.find_device(slot)?;
+            devices.push(device);
+            names.push(name);
+        }
+
+        let new_streams = (self.rebuild)(&devices)?;
+        if play {
+            new_streams.play()?;
+        }
+        if let Ok(mut guard) = streams.lock() {
+            *guard = Some(new_streams);
+        }
+
+        for (slot, name) in self.slots.iter_mut().zip(&names) {
+            slot.current = name.clone();
+            // Ignore any errors reported by the streams that were replaced.
+            slot.lost.store(false, atomic::Ordering::Relaxed);
+        }
+        Ok(names)
+    }
+
+    // The first available candidate device for the given slot along with its name.
+    fn find_device(&self, slot: &Slot) -> Result<(cpal::Device, String), RecoveryError> {
+        let candidates = std::iter::once(&slot.preferred).chain(&self.recovery.fallback_devices);
+        for candidate in candidates {
+            let mut devices: Box<dyn Iterator<Item = cpal::Device>> = match slot.direction {
+                Direction::Input => Box::new(self.host.input_devices()?),
+                Direction::Output => Box::new(self.host.output_devices()?),
+            };
+            let found = devices.find(|d| d.name().map(|n| &n == candidate).unwrap_or(false));
+            if let Some(device) = found {
+                return Ok((device, candidate.clone()));
+            }
+        }
+
+        if self.recovery.fallback_to_default {
+            let device = match slot.direction {
+                Direction::Input => self.host.default_input_device(),
+                Direction::Output => self.host.default_output_device(),
+            };
+            if let Some(device) = device {
+                let name = device.name().unwrap_or_default();
+                return Ok((device, name));
+            }
+        }
+
+        Err(RecoveryError::NoDevice {
+            direction: slot.direction,
+        })
+    }
+}
+
+impl Default for Recovery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Direction::Input => write!(f, "input"),
+            Direction::Output => write!(f, "output"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MISSING: &str = "nannou_audio missing device";
+
+    // The recovery state of an output stream on a device that does not exist, along with the flag
+    // set by the stream's error function.
+    fn state(recovery: Recovery) -> (State, Arc<AtomicBool>) {
+        let lost = Arc::new(AtomicBool::new(false));
+        let slot = Slot {
+            direction: Direction::Output,
+            preferred: MISSING.to_string(),
+            current: MISSING.to_string(),
+            lost: lost.clone(),
+        };
+        let host = Arc::new(cpal::default_host());
+        let rebuild = |_: &[cpal::Device]| -> Result<Streams, cpal::BuildStreamError> {
+            Err(cpal::BuildStreamError::DeviceNotAvailable)
+        };
+        let recovery = recovery.fallback_to_default(false);
+        (
+            State::new(recovery, host, vec![slot], Box::new(rebuild)),
+            lost,
+        )
+    }
+
+    fn assert_retry_failed(event: &Event) {
+        match *event {
+            Event::RetryFailed { .. } => (),
+            ref event => panic!("unexpected event: {:?}", event),
+        }
+    }
+
+    #[test]
+    fn lost_devices_are_reported_once() {
+        let streams = Mutex::new(None);
+        let interval = Duration::from_secs(3_600);
+        let (mut state, lost) = state(Recovery::new().retry_interval(interval));
+        assert!(state.poll(&streams, true).is_empty());
+
+        // The first attempt to rebuild the stream is made as soon as the loss is reported.
+        lost.store(true, atomic::Ordering::Relaxed);
+        let events = state.poll(&streams, true);
+        assert_eq!(events.len(), 2);
+        match events[0] {
+            Event::DeviceLost { ref device } => assert_eq!(device, MISSING),
+            ref event => panic!("unexpected event: {:?}", event),
+        }
+        assert_retry_failed(&events[1]);
+
+        // Further errors from the lost stream are ignored until the stream recovers.
+        lost.store(true, atomic::Ordering::Relaxed);
+        assert!(state.poll(&streams, true).is_empty());
+        assert!(state.next_attempt.unwrap() > Instant::now());
+    }
+
+    #[test]
+    fn lost_devices_are_retried_at_the_retry_interval() {
+        let streams = Mutex::new(None);
+        let recovery = Recovery::new()
+            .retry_interval(Duration::from_secs(0))
+            .fallback_device("nannou_audio missing fallback");
+        let (mut state, lost) = state(recovery);
+        lost.store(true, atomic::Ordering::Relaxed);
+        assert_eq!(state.poll(&streams, true).len(), 2);
+        for _ in 0..3 {
+            let events = state.poll(&streams, true);
+            assert_eq!(events.len(), 1);
+            assert_retry_failed(&events[0]);
+        }
+        assert!(state.next_attempt.is_some());
+        assert_eq!(state.slots[0].current, MISSING);
+    }
+}
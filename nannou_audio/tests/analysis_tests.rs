@@ -0,0 +1,83 @@
+use nannou_audio::analysis::{self, Analyzer, Reader};
+
+const SAMPLE_RATE: u32 = 48_000;
+const FFT_SIZE: usize = 1_024;
+
+// An analyzer publishing a single frame for each window of `FFT_SIZE` samples.
+fn analyzer() -> (Analyzer, Reader) {
+    analysis::Builder::new()
+        .fft_size(FFT_SIZE)
+        .hop_size(FFT_SIZE)
+        .sample_rate(SAMPLE_RATE)
+        .build()
+}
+
+fn sine(hz: f32, amp: f32, len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            (t * hz * 2.0 * std::f32::consts::PI).sin() * amp
+        })
+        .collect()
+}
+
+#[test]
+fn spectrum_peaks_at_the_bin_of_a_sine() {
+    let (mut analyzer, mut reader) = analyzer();
+    let bin_hz = SAMPLE_RATE as f32 / FFT_SIZE as f32;
+    let bin = 20;
+    analyzer.process_interleaved(&sine(bin as f32 * bin_hz, 0.5, FFT_SIZE), 1, SAMPLE_RATE);
+
+    let features = reader.update();
+    let spectrum = &features.spectrum;
+    assert_eq!(spectrum.len(), FFT_SIZE / 2 + 1);
+    let max = (0..spectrum.len())
+        .max_by(|&a, &b| spectrum[a].partial_cmp(&spectrum[b]).unwrap())
+        .unwrap();
+    assert_eq!(max, bin);
+    // A full-scale sine has a magnitude of roughly its amplitude.
+    assert!((spectrum[bin] - 0.5).abs() < 0.01);
+    // The Hann window spreads half of the magnitude into each neighbouring bin.
+    assert!((spectrum[bin - 1] - 0.25).abs() < 0.01);
+    assert!((spectrum[bin + 1] - 0.25).abs() < 0.01);
+    assert!(spectrum[bin * 2] < 1e-3);
+    assert!((features.centroid - bin as f32 * bin_hz).abs() < bin_hz);
+}
+
+#[test]
+fn rms_and_peak_amplitude() {
+    let (mut analyzer, mut reader) = analyzer();
+    let hz = 20.0 * SAMPLE_RATE as f32 / FFT_SIZE as f32;
+    analyzer.process_interleaved(&sine(hz, 0.5, FFT_SIZE), 1, SAMPLE_RATE);
+    let features = reader.update();
+    assert!((features.rms - 0.5 / 2f32.sqrt()).abs() < 1e-3);
+    assert!((features.peak - 0.5).abs() < 2e-3);
+
+    // Multi-channel audio is mixed down to mono.
+    let stereo: Vec<f32> = (0..FFT_SIZE).flat_map(|_| vec![0.25, -0.75]).collect();
+    analyzer.process_interleaved(&stereo, 2, SAMPLE_RATE);
+    let features = reader.update();
+    assert!((features.rms - 0.25).abs() < 1e-6);
+    assert!((features.peak - 0.25).abs() < 1e-6);
+
+    analyzer.process_interleaved(&vec![0.0f32; FFT_SIZE], 1, SAMPLE_RATE);
+    let features = reader.update();
+    assert_eq!((features.rms, features.peak), (0.0, 0.0));
+    assert!(features.spectrum.iter().all(|&m| m == 0.0));
+    assert_eq!(features.pitch, None);
+}
+
+#[test]
+fn frames_are_published_each_hop() {
+    let (mut analyzer, mut reader) = analysis::Builder::new()
+        .fft_size(FFT_SIZE)
+        .hop_size(FFT_SIZE / 4)
+        .build();
+    analyzer.process_interleaved(&vec![0.0f32; FFT_SIZE / 4 - 1], 1, SAMPLE_RATE);
+    assert_eq!(reader.update().sample_rate, 0);
+    analyzer.process_interleaved(&vec![0.0f32; FFT_SIZE * 3 / 4 + 1], 1, SAMPLE_RATE);
+    let features = reader.update();
+    assert_eq!(features.index, 3);
+    assert_eq!(features.sample_rate, SAMPLE_RATE);
+    assert_eq!(features.time, FFT_SIZE as f64 / SAMPLE_RATE as f64);
+}
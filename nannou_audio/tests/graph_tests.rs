@@ -0,0 +1,136 @@
+use nannou_audio::graph::{self, Context, Graph, GraphError, Node, NodeId, Processor};
+use nannou_audio::offline;
+use std::sync::{Arc, Mutex};
+
+type Log = Arc<Mutex<Vec<&'static str>>>;
+
+// A mono node that adds `value` to its input and logs its name each time it is processed.
+struct Probe {
+    name: &'static str,
+    inputs: usize,
+    value: f32,
+    log: Log,
+}
+
+impl Node for Probe {
+    fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    fn outputs(&self) -> usize {
+        1
+    }
+
+    fn process(&mut self, ctx: &Context, input: &[f32], output: &mut [f32]) {
+        self.log.lock().unwrap().push(self.name);
+        for (i, sample) in output[..ctx.frames].iter_mut().enumerate() {
+            *sample = input.get(i).cloned().unwrap_or(0.0) + self.value;
+        }
+    }
+}
+
+fn probe(graph: &mut Graph, log: &Log, name: &'static str, inputs: usize, value: f32) -> NodeId {
+    let log = log.clone();
+    let node = Probe {
+        name,
+        inputs,
+        value,
+        log,
+    };
+    graph.add(node).unwrap()
+}
+
+// Render the given number of frames of the processor as a single mono buffer.
+fn render(processor: Processor, frames: usize) -> (Processor, Vec<f32>) {
+    let mut stream = offline::Host::new()
+        .new_output_stream(processor)
+        .render(graph::render)
+        .channels(1)
+        .sample_rate(44_100)
+        .frames_per_buffer(frames)
+        .build();
+    let samples = stream.render(frames);
+    (stream.into_model(), samples)
+}
+
+fn position(log: &Log, name: &str) -> usize {
+    let log = log.lock().unwrap();
+    log.iter().position(|&n| n == name).unwrap()
+}
+
+#[test]
+fn nodes_are_processed_after_their_sources() {
+    let log = Log::default();
+    let (mut graph, processor) = Graph::new(1, 44_100);
+    // Add the nodes in the reverse of the order in which they must be processed.
+    let c = probe(&mut graph, &log, "c", 1, 8.0);
+    let b = probe(&mut graph, &log, "b", 1, 2.0);
+    let a = probe(&mut graph, &log, "a", 0, 1.0);
+    let d = probe(&mut graph, &log, "d", 0, 4.0);
+    probe(&mut graph, &log, "unheard", 0, 16.0);
+    graph.connect(a, b).unwrap();
+    graph.connect(b, c).unwrap();
+    graph.connect(d, c).unwrap();
+    graph.connect(c, graph.output()).unwrap();
+    graph.commit().unwrap();
+
+    let (processor, _) = render(processor, graph::BLOCK_FRAMES);
+    assert_eq!(log.lock().unwrap().len(), 4);
+    assert!(position(&log, "a") < position(&log, "b"));
+    assert!(position(&log, "b") < position(&log, "c"));
+    assert!(position(&log, "d") < position(&log, "c"));
+
+    // Once the connections have faded in, the output is the sum along every path.
+    let (_, samples) = render(processor, 1_024);
+    assert!((samples[1_023] - 15.0).abs() < 1e-6);
+}
+
+#[test]
+fn nodes_without_a_path_to_the_output_are_not_processed() {
+    let log = Log::default();
+    let (mut graph, processor) = Graph::new(1, 44_100);
+    let a = probe(&mut graph, &log, "a", 0, 1.0);
+    let b = probe(&mut graph, &log, "b", 1, 1.0);
+    graph.connect(a, b).unwrap();
+    graph.commit().unwrap();
+    let (processor, samples) = render(processor, graph::BLOCK_FRAMES);
+    assert!(log.lock().unwrap().is_empty());
+    assert!(samples.iter().all(|&s| s == 0.0));
+
+    graph.connect(b, graph.output()).unwrap();
+    graph.commit().unwrap();
+    render(processor, graph::BLOCK_FRAMES);
+    assert_eq!(*log.lock().unwrap(), vec!["a", "b"]);
+}
+
+#[test]
+fn cycles_are_rejected() {
+    let log = Log::default();
+    let (mut graph, _processor) = Graph::new(1, 44_100);
+    let a = probe(&mut graph, &log, "a", 1, 0.0);
+    let b = probe(&mut graph, &log, "b", 1, 0.0);
+    let c = probe(&mut graph, &log, "c", 1, 0.0);
+    graph.connect(a, b).unwrap();
+    let bc = graph.connect(b, c).unwrap();
+    for &(source, dest) in &[(c, a), (c, b), (a, a)] {
+        match graph.connect(source, dest) {
+            Err(GraphError::Cycle) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    // Once the path is broken, the reverse connection is allowed.
+    graph.disconnect(bc).unwrap();
+    graph.connect(c, b).unwrap();
+    graph.connect(b, graph.output()).unwrap();
+    graph.commit().unwrap();
+    match graph.connect(graph.output(), a) {
+        Err(GraphError::NoOutputs) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    let generator = probe(&mut graph, &log, "generator", 0, 0.0);
+    match graph.connect(a, generator) {
+        Err(GraphError::NoInputs) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
@@ -0,0 +1,131 @@
+use nannou_audio::{offline, Buffer};
+
+// Counts the frames rendered so far.
+struct Counter {
+    frames: u64,
+}
+
+struct Sine {
+    phase: f32,
+    hz: f32,
+}
+
+// Each sample is `frame * 10 + channel`, exposing both the frame order and the channel layout.
+fn render_counter(counter: &mut Counter, buffer: &mut Buffer) {
+    for frame in buffer.frames_mut() {
+        for (channel, sample) in frame.iter_mut().enumerate() {
+            *sample = (counter.frames * 10 + channel as u64) as f32;
+        }
+        counter.frames += 1;
+    }
+}
+
+fn render_sine(sine: &mut Sine, buffer: &mut Buffer) {
+    let step = sine.hz / buffer.sample_rate() as f32;
+    for frame in buffer.frames_mut() {
+        let amp = (sine.phase * 2.0 * std::f32::consts::PI).sin();
+        sine.phase = (sine.phase + step) % 1.0;
+        for sample in frame {
+            *sample = amp;
+        }
+    }
+}
+
+fn counter_stream(channels: usize, sample_rate: u32) -> offline::Stream<Counter> {
+    offline::Host::new()
+        .new_output_stream(Counter { frames: 0 })
+        .render(render_counter)
+        .channels(channels)
+        .sample_rate(sample_rate)
+        .frames_per_buffer(64)
+        .build()
+}
+
+fn sine_stream() -> offline::Stream<Sine> {
+    offline::Host::new()
+        .new_output_stream(Sine {
+            phase: 0.0,
+            hz: 440.0,
+        })
+        .render(render_sine)
+        .build()
+}
+
+#[test]
+fn frames_are_interleaved() {
+    let mut stream = counter_stream(3, 44_100);
+    assert_eq!(stream.channels(), 3);
+    assert_eq!(stream.frames_per_buffer(), 64);
+    // Render a count that is not a multiple of the buffer size, so that the next render begins
+    // with the frames left over from the last buffer.
+    for &frames in &[100, 1, 27] {
+        let start = stream.rendered_frames();
+        let samples = stream.render(frames);
+        assert_eq!(samples.len(), frames * 3);
+        for (i, frame) in samples.chunks(3).enumerate() {
+            let index = start + i as u64;
+            let expected: Vec<f32> = (0..3).map(|c| (index * 10 + c) as f32).collect();
+            assert_eq!(frame, &expected[..]);
+        }
+    }
+    assert_eq!(stream.rendered_frames(), 128);
+    assert_eq!(stream.model().frames, 128);
+}
+
+#[test]
+fn rendering_is_deterministic() {
+    let mut a = sine_stream();
+    let mut b = sine_stream();
+    let samples = a.render(4_000);
+    // The result does not depend on how rendering is split into calls.
+    let mut split = b.render(1_000);
+    split.extend(b.render(3));
+    split.extend(b.render(2_997));
+    assert_eq!(samples, split);
+    assert_eq!(samples.len(), 4_000 * offline::DEFAULT_CHANNELS);
+}
+
+#[test]
+fn render_until_secs_rounds_to_the_nearest_frame() {
+    let mut stream = counter_stream(1, 1_000);
+    assert_eq!(stream.render_until_secs(0.0014).len(), 1);
+    assert_eq!(stream.render_until_secs(0.0016).len(), 1);
+    assert_eq!(stream.rendered_frames(), 2);
+    // Times before the current position render nothing.
+    assert!(stream.render_until_secs(0.001).is_empty());
+    assert!(stream.render_until_secs(-1.0).is_empty());
+
+    // Rendering once per video frame never drifts from the video.
+    let mut stream = counter_stream(2, 44_100);
+    for i in 1..=30 {
+        let samples = stream.render_until_secs(i as f64 / 30.0);
+        assert_eq!(samples.len(), 1_470 * 2);
+    }
+    assert_eq!(stream.rendered_frames(), 44_100);
+    assert_eq!(stream.rendered_secs(), 1.0);
+    stream.render_until_secs(1.0 + 1.0 / 3.0);
+    assert_eq!(stream.rendered_frames(), 44_100 + 14_700);
+}
+
+#[cfg(feature = "wav")]
+#[test]
+fn render_to_file_writes_wav() {
+    let name = format!("nannou_audio-{}-offline.wav", std::process::id());
+    let path = std::env::temp_dir().join(name);
+    let frames = 1_000;
+    let mut stream = counter_stream(2, 48_000);
+    stream.render_to_file(&path, frames).unwrap();
+    assert!(!stream.is_recording());
+    assert_eq!(stream.rendered_frames(), frames as u64);
+
+    let mut reader = hound::WavReader::open(&path).unwrap();
+    let spec = reader.spec();
+    assert_eq!(spec.channels, 2);
+    assert_eq!(spec.sample_rate, 48_000);
+    assert_eq!(spec.bits_per_sample, 32);
+    assert_eq!(spec.sample_format, hound::SampleFormat::Float);
+    assert_eq!(reader.duration(), frames as u32);
+    let read: Vec<f32> = reader.samples::<f32>().map(|s| s.unwrap()).collect();
+    assert_eq!(read, counter_stream(2, 48_000).render(frames));
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,152 @@
+use nannou_audio::offline;
+use nannou_audio::player::{Player, Sample, State, Voice};
+use nannou_audio::Buffer;
+
+fn render(player: &mut Player, buffer: &mut Buffer) {
+    player.render(buffer);
+}
+
+// Render the given number of frames of the player at the given channel count and rate.
+//
+// The frames are rendered as a single buffer so that the player advances by exactly `frames`.
+fn render_player(player: &mut Player, channels: usize, rate: u32, frames: usize) -> Vec<f32> {
+    let owned = std::mem::replace(player, Player::new());
+    let mut stream = offline::Host::new()
+        .new_output_stream(owned)
+        .render(render)
+        .channels(channels)
+        .sample_rate(rate)
+        .frames_per_buffer(frames)
+        .build();
+    let samples = stream.render(frames);
+    *player = stream.into_model();
+    samples
+}
+
+fn constant(value: f32, frames: usize) -> Sample {
+    Sample::from_interleaved(vec![value; frames], 1, 44_100)
+}
+
+// A mono sample whose frame at each index is `index / len`.
+fn ramp(len: usize, rate: u32) -> Sample {
+    let samples: Vec<f32> = (0..len).map(|i| i as f32 / len as f32).collect();
+    Sample::from_interleaved(samples, 1, rate)
+}
+
+#[test]
+fn voices_are_mixed() {
+    let mut player = Player::new();
+    player.play(Voice::new(constant(0.25, 1_000)));
+    player.play(Voice::new(constant(0.5, 1_000)).gain(0.5));
+    assert_eq!(player.len(), 2);
+    let samples = render_player(&mut player, 1, 44_100, 256);
+    assert!(samples.iter().all(|&s| s == 0.5));
+
+    // The player's gain is applied to the mix of all voices.
+    player.set_gain(0.5);
+    let samples = render_player(&mut player, 1, 44_100, 256);
+    assert!(samples.iter().all(|&s| s == 0.25));
+}
+
+#[test]
+fn mono_voices_are_panned() {
+    let mut player = Player::new();
+    player.play(Voice::new(constant(0.5, 1_000)));
+    let samples = render_player(&mut player, 2, 44_100, 64);
+    // Centred voices are at unity gain on both channels.
+    assert!(samples.iter().all(|s| (s - 0.5).abs() < 1e-6));
+
+    let mut player = Player::new();
+    player.play(Voice::new(constant(0.5, 1_000)).pan(-1.0));
+    let samples = render_player(&mut player, 2, 44_100, 64);
+    for frame in samples.chunks(2) {
+        assert!(frame[0] > 0.5);
+        assert!(frame[1].abs() < 1e-6);
+    }
+}
+
+#[test]
+fn finished_voices_are_removed() {
+    let mut player = Player::new();
+    let id = player.play(Voice::new(ramp(10, 44_100)));
+    let samples = render_player(&mut player, 1, 44_100, 64);
+    let expected: Vec<f32> = (0..10).map(|i| i as f32 / 10.0).collect();
+    assert_eq!(&samples[..10], &expected[..]);
+    assert!(samples[10..].iter().all(|&s| s == 0.0));
+    assert!(player.voice(id).is_none());
+    assert!(player.is_empty());
+}
+
+#[test]
+fn oldest_voices_are_stolen() {
+    let mut player = Player::with_max_voices(2);
+    let a = player.play(Voice::new(constant(0.1, 100)));
+    let b = player.play(Voice::new(constant(0.1, 100)));
+    let c = player.play(Voice::new(constant(0.1, 100)));
+    assert_eq!(player.len(), 2);
+    assert!(player.voice(a).is_none());
+    assert!(player.voice(b).is_some() && player.voice(c).is_some());
+    let ids: Vec<_> = player.voices().map(|v| v.id()).collect();
+    assert_eq!(ids, vec![b, c]);
+}
+
+#[test]
+fn looping_voices_restart() {
+    let mut player = Player::new();
+    let id = player.play(Voice::new(ramp(4, 44_100)).looping(true));
+    let samples = render_player(&mut player, 1, 44_100, 10);
+    let expected: Vec<f32> = (0..10).map(|i| (i % 4) as f32 / 4.0).collect();
+    assert_eq!(samples, expected);
+    let voice = player.voice(id).unwrap();
+    assert_eq!(voice.state(), State::Playing);
+    assert_eq!(voice.position(), 10 % 4);
+
+    // Once looping is disabled, the voice finishes at the end of the sample.
+    player.voice_mut(id).unwrap().set_looping(false);
+    render_player(&mut player, 1, 44_100, 64);
+    assert!(player.voice(id).is_none());
+}
+
+#[test]
+fn voices_seek() {
+    let mut player = Player::new();
+    let id = player.play(Voice::new(ramp(100, 100)).start_at(50));
+    assert_eq!(player.voice(id).unwrap().position(), 50);
+    let samples = render_player(&mut player, 1, 100, 5);
+    assert_eq!(samples[0], 0.5);
+    assert_eq!(player.voice(id).unwrap().position(), 55);
+
+    player.voice_mut(id).unwrap().seek(20);
+    let samples = render_player(&mut player, 1, 100, 1);
+    assert_eq!(samples[0], 0.2);
+
+    player.voice_mut(id).unwrap().seek_secs(0.3);
+    assert_eq!(player.voice(id).unwrap().position(), 30);
+    assert_eq!(player.voice(id).unwrap().position_secs(), 0.3);
+
+    // Seeking beyond the end finishes the voice.
+    player.voice_mut(id).unwrap().seek(1_000);
+    render_player(&mut player, 1, 100, 8);
+    assert!(player.voice(id).is_none());
+}
+
+#[test]
+fn paused_and_stopped_voices() {
+    let mut player = Player::new();
+    let id = player.play(Voice::new(constant(0.5, 1_000)).paused());
+    let samples = render_player(&mut player, 1, 44_100, 16);
+    assert!(samples.iter().all(|&s| s == 0.0));
+    assert_eq!(player.voice(id).unwrap().position(), 0);
+
+    player.voice_mut(id).unwrap().resume();
+    let samples = render_player(&mut player, 1, 44_100, 16);
+    assert!(samples.iter().all(|&s| s == 0.5));
+
+    // Stopping fades the voice out over the next buffer, after which it is removed.
+    assert!(player.stop(id));
+    let samples = render_player(&mut player, 1, 44_100, 64);
+    assert!(samples[0] < 0.5 && samples[0] > 0.0);
+    assert!(samples.windows(2).all(|w| w[1] <= w[0]));
+    assert!(player.is_empty());
+    assert!(!player.stop(id));
+}
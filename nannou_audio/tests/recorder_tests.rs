@@ -0,0 +1,148 @@
+use nannou_audio::recorder::{Format, RecordError, Recorder, SampleFormat};
+use std::path::{Path, PathBuf};
+
+const SAMPLE_RATE: u32 = 44_100;
+
+fn temp_path(name: &str) -> PathBuf {
+    let name = format!("nannou_audio-{}-{}", std::process::id(), name);
+    std::env::temp_dir().join(name)
+}
+
+// A second of a stereo sine wave, with the right channel inverted.
+#[cfg(any(feature = "wav", feature = "flac"))]
+fn stereo_sine() -> Vec<f32> {
+    (0..SAMPLE_RATE)
+        .flat_map(|i| {
+            let phase = i as f32 * 440.0 / SAMPLE_RATE as f32;
+            let s = (phase * 2.0 * std::f32::consts::PI).sin() * 0.5;
+            vec![s, -s]
+        })
+        .collect()
+}
+
+// Record the given stereo samples to the file at the given path.
+//
+// Dropping the recorder finalises the file and joins the writer thread.
+#[cfg(any(feature = "wav", feature = "flac"))]
+fn record(path: &Path, sample_format: Option<SampleFormat>, samples: &[f32]) {
+    let (mut recorder, mut tap) = Recorder::new();
+    if let Some(sample_format) = sample_format {
+        recorder.set_sample_format(sample_format);
+    }
+    recorder.start(path).unwrap();
+    for chunk in samples.chunks(512) {
+        tap.write_interleaved(chunk, 2, SAMPLE_RATE);
+    }
+    drop(recorder);
+    assert!(path.exists());
+}
+
+#[test]
+fn format_is_selected_by_extension() {
+    let (mut recorder, mut tap) = Recorder::new();
+    for name in &["take.mp3", "take"] {
+        let path = temp_path(name);
+        match recorder.start(&path) {
+            Err(RecordError::UnsupportedFormat { path: p }) => assert_eq!(p, path),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(!recorder.is_recording());
+    }
+
+    // Extensions are case insensitive.
+    let path = temp_path("take.FLAC");
+    recorder.start(&path).unwrap();
+    assert!(recorder.is_recording());
+    match recorder.start(temp_path("take.flac")) {
+        Err(RecordError::AlreadyRecording) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match recorder.split(temp_path("take.ogg")) {
+        Err(RecordError::UnsupportedFormat { .. }) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    recorder.stop().unwrap();
+    match recorder.stop() {
+        Err(RecordError::NotRecording) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    // The file is created once the tap forwards the commands to the writer thread.
+    tap.write_interleaved(&[0.0f32; 2], 2, SAMPLE_RATE);
+    drop(recorder);
+    assert!(path.exists());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn flac_does_not_support_float_samples() {
+    let (mut recorder, _tap) = Recorder::new();
+    recorder.set_sample_format(SampleFormat::F32);
+    match recorder.start(temp_path("float.flac")) {
+        Err(RecordError::UnsupportedSampleFormat {
+            format,
+            sample_format,
+        }) => {
+            assert_eq!(format, Format::Flac);
+            assert_eq!(sample_format, SampleFormat::F32);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(!recorder.is_recording());
+}
+
+#[cfg(feature = "wav")]
+#[test]
+fn wav_sample_formats() {
+    let samples = stereo_sine();
+    let formats = [
+        (None, 32, hound::SampleFormat::Float),
+        (Some(SampleFormat::F32), 32, hound::SampleFormat::Float),
+        (Some(SampleFormat::I24), 24, hound::SampleFormat::Int),
+        (Some(SampleFormat::I16), 16, hound::SampleFormat::Int),
+    ];
+    for &(sample_format, bits, hound_format) in &formats {
+        let path = temp_path(&format!("{}.wav", bits));
+        record(&path, sample_format, &samples);
+        let mut reader = hound::WavReader::open(&path).unwrap();
+        let spec = reader.spec();
+        assert_eq!(spec.channels, 2);
+        assert_eq!(spec.sample_rate, SAMPLE_RATE);
+        assert_eq!(spec.bits_per_sample, bits);
+        assert_eq!(spec.sample_format, hound_format);
+        assert_eq!(reader.duration(), SAMPLE_RATE);
+        let read: Vec<f32> = match hound_format {
+            hound::SampleFormat::Float => reader.samples::<f32>().map(|s| s.unwrap()).collect(),
+            hound::SampleFormat::Int => {
+                let max = (1i32 << (bits - 1)) as f32;
+                let samples = reader.samples::<i32>();
+                samples.map(|s| s.unwrap() as f32 / max).collect()
+            }
+        };
+        assert_eq!(read.len(), samples.len());
+        for (a, b) in read.iter().zip(&samples) {
+            assert!((a - b).abs() < 1e-4);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
+
+#[cfg(feature = "flac")]
+#[test]
+fn flac_defaults_to_24_bit_samples() {
+    let samples = stereo_sine();
+    let path = temp_path("default.flac");
+    record(&path, None, &samples);
+    let mut reader = claxon::FlacReader::open(&path).unwrap();
+    let info = reader.streaminfo();
+    assert_eq!(info.channels, 2);
+    assert_eq!(info.sample_rate, SAMPLE_RATE);
+    assert_eq!(info.bits_per_sample, 24);
+    let max = (1 << 23) as f32;
+    let read: Vec<f32> = reader.samples().map(|s| s.unwrap() as f32 / max).collect();
+    assert_eq!(read.len(), samples.len());
+    for (a, b) in read.iter().zip(&samples) {
+        assert!((a - b).abs() < 1e-4);
+    }
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,134 @@
+use nannou_audio::offline;
+use nannou_audio::spatial::{Attenuation, DistanceModel, Layout, Source, Spatializer};
+use nannou_audio::Buffer;
+use std::f32::consts::PI;
+
+const FRAMES: usize = 64;
+
+struct Audio {
+    source: Source,
+    input: Vec<f32>,
+}
+
+fn render(audio: &mut Audio, buffer: &mut Buffer) {
+    audio.source.render(&audio.input, buffer);
+}
+
+// Spatializers whose sources are not attenuated with distance.
+fn vbap(layout: Layout) -> Spatializer {
+    let attenuation = Attenuation {
+        model: DistanceModel::None,
+        ..Default::default()
+    };
+    Spatializer::vbap(layout).unwrap().attenuation(attenuation)
+}
+
+fn dbap(layout: Layout) -> Spatializer {
+    let attenuation = Attenuation {
+        model: DistanceModel::None,
+        ..Default::default()
+    };
+    Spatializer::dbap(layout).unwrap().attenuation(attenuation)
+}
+
+// The gain of each channel for a stationary source at the given position, found by rendering a
+// buffer of a constant unit signal.
+fn channel_gains(spatializer: &Spatializer, position: [f32; 3]) -> Vec<f32> {
+    let audio = Audio {
+        source: spatializer.source(position),
+        input: vec![1.0; FRAMES],
+    };
+    let channels = spatializer.channels();
+    let mut stream = offline::Host::new()
+        .new_output_stream(audio)
+        .render(render)
+        .channels(channels)
+        .frames_per_buffer(FRAMES)
+        .build();
+    let samples = stream.render(FRAMES);
+    let last = &samples[(FRAMES - 1) * channels..];
+    for frame in samples.chunks(channels) {
+        assert_eq!(frame, last);
+    }
+    last.to_vec()
+}
+
+fn power(gains: &[f32]) -> f32 {
+    gains.iter().map(|g| g * g).sum()
+}
+
+// Points around the listener at the given elevation in radians.
+fn circle(count: usize, elevation: f32, radius: f32) -> Vec<[f32; 3]> {
+    (0..count)
+        .map(|i| {
+            let azimuth = i as f32 * 2.0 * PI / count as f32 + 0.1;
+            let (sin_az, cos_az) = azimuth.sin_cos();
+            let (sin_el, cos_el) = elevation.sin_cos();
+            let r = radius;
+            [sin_az * cos_el * r, cos_az * cos_el * r, sin_el * r]
+        })
+        .collect()
+}
+
+#[test]
+fn vbap_pair_gains_have_unit_power() {
+    for layout in &[Layout::stereo(), Layout::quad(), Layout::ring(8, 2.0)] {
+        let spatializer = vbap(layout.clone());
+        for position in circle(64, 0.0, 3.0) {
+            let gains = channel_gains(&spatializer, position);
+            assert!((power(&gains) - 1.0).abs() < 1e-4, "{:?}", gains);
+            assert!(gains.iter().filter(|&&g| g > 0.0).count() <= 2);
+        }
+    }
+}
+
+#[test]
+fn vbap_pans_between_adjacent_speakers() {
+    let spatializer = vbap(Layout::ring(4, 1.0));
+    // A source in the direction of a speaker plays from that speaker alone.
+    let gains = channel_gains(&spatializer, [5.0, 0.0, 0.0]);
+    assert!((gains[1] - 1.0).abs() < 1e-5);
+    assert!(gains[0].abs() < 1e-5 && gains[2].abs() < 1e-5 && gains[3].abs() < 1e-5);
+    // Halfway between the front and right speakers, both are at equal power.
+    let gains = channel_gains(&spatializer, [1.0, 1.0, 0.0]);
+    let half = 0.5f32.sqrt();
+    assert!((gains[0] - half).abs() < 1e-5 && (gains[1] - half).abs() < 1e-5);
+}
+
+#[test]
+fn vbap_triangle_gains_have_unit_power() {
+    let layout = Layout::dome(&[(6, 0.0), (4, PI / 4.0), (1, PI / 2.0)], 2.0);
+    let spatializer = vbap(layout);
+    for &elevation in &[0.0, 0.3, PI / 4.0, 1.2] {
+        for position in circle(16, elevation, 1.0) {
+            let gains = channel_gains(&spatializer, position);
+            assert!((power(&gains) - 1.0).abs() < 1e-4, "{:?}", gains);
+            assert!(gains.iter().filter(|&&g| g > 0.0).count() <= 3);
+        }
+    }
+}
+
+#[test]
+fn dbap_gains_have_unit_power() {
+    let layout = Layout::new(vec![
+        [-2.0, 3.0, 0.0],
+        [2.5, 2.0, 0.0],
+        [0.0, -1.0, 1.0],
+        [-3.0, -2.0, 0.0],
+    ]);
+    let spatializer = dbap(layout.clone());
+    for &radius in &[0.0, 0.5, 2.0, 10.0] {
+        for position in circle(8, 0.2, radius) {
+            let gains = channel_gains(&spatializer, position);
+            assert!((power(&gains) - 1.0).abs() < 1e-4, "{:?}", gains);
+        }
+    }
+
+    // The speaker nearest the source is the loudest, increasingly so with less blur.
+    let near = layout.speakers()[1].position;
+    let gains = channel_gains(&spatializer, near);
+    assert!(gains.iter().all(|&g| g <= gains[1]));
+    let focused = channel_gains(&spatializer.clone().blur(0.01), near);
+    assert!(focused[1] > gains[1]);
+    assert!((power(&focused) - 1.0).abs() < 1e-4);
+}
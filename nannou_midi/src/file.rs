@@ -0,0 +1,440 @@
+//! Items related to reading standard MIDI files (SMF).
+
+use crate::msg::{self, Message};
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+use thiserror::Error;
+
+/// The tempo assumed until a file specifies otherwise, in microseconds per quarter note (120 BPM).
+pub const DEFAULT_TEMPO: u32 = 500_000;
+
+/// A standard MIDI file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct File {
+    /// How the tracks within the file relate to one another.
+    pub format: Format,
+    /// The unit of the `delta` of each event.
+    pub timing: Timing,
+    /// The tracks in the order in which they appear in the file.
+    pub tracks: Vec<Track>,
+}
+
+/// How the tracks within a MIDI file relate to one another.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Format 0 - the file contains a single track.
+    SingleTrack,
+    /// Format 1 - the tracks are played simultaneously. The first track usually holds the tempo
+    /// map.
+    Parallel,
+    /// Format 2 - each track is an independent sequence, e.g. a pattern.
+    Sequential,
+}
+
+/// The unit in which the time between events is measured.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Timing {
+    /// Ticks per quarter note. The duration of a tick depends on the current tempo.
+    TicksPerQuarter(u16),
+    /// Ticks per SMPTE frame. The duration of a tick is independent of tempo.
+    ///
+    /// An `fps` of `29` indicates 29.97 frames per second drop-frame timecode.
+    Timecode { fps: u8, ticks_per_frame: u8 },
+}
+
+/// A single track within a MIDI file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Track {
+    pub events: Vec<TrackEvent>,
+}
+
+/// An event along with the time since the previous event within the same track.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackEvent {
+    /// The number of ticks since the previous event. See `Timing`.
+    pub delta: u32,
+    pub kind: EventKind,
+}
+
+/// The kinds of events that may occur within a track.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EventKind {
+    /// A channel or system exclusive message.
+    Midi(Message),
+    /// Bytes to be sent as-is, e.g. a system exclusive message split into multiple packets.
+    Escape(Vec<u8>),
+    /// Information about the sequence that is not sent to devices.
+    Meta(Meta),
+}
+
+/// Meta events describe a sequence rather than instructing devices.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Meta {
+    SequenceNumber(u16),
+    Text(String),
+    Copyright(String),
+    TrackName(String),
+    InstrumentName(String),
+    Lyric(String),
+    Marker(String),
+    CuePoint(String),
+    /// The channel to which subsequent meta and system exclusive events relate.
+    ChannelPrefix(u8),
+    /// The port to which subsequent events are sent.
+    Port(u8),
+    EndOfTrack,
+    /// The tempo in microseconds per quarter note.
+    Tempo(u32),
+    SmpteOffset {
+        hours: u8,
+        minutes: u8,
+        seconds: u8,
+        frames: u8,
+        subframes: u8,
+    },
+    /// The time signature. The `denominator` is a power of two, e.g. `3` indicates eighth notes.
+    TimeSignature {
+        numerator: u8,
+        denominator: u8,
+        clocks_per_click: u8,
+        thirty_seconds_per_quarter: u8,
+    },
+    /// The key signature as a number of sharps (positive) or flats (negative).
+    KeySignature {
+        sharps: i8,
+        minor: bool,
+    },
+    SequencerSpecific(Vec<u8>),
+    /// A meta event of a kind not covered above.
+    Unknown {
+        kind: u8,
+        data: Vec<u8>,
+    },
+}
+
+/// A MIDI message along with the time at which it occurs, as produced by `File::timed_events`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimedEvent {
+    /// The time since the start of the sequence.
+    pub time: Duration,
+    /// The index of the track containing the message.
+    pub track: usize,
+    pub message: Message,
+}
+
+/// Errors that might occur while reading a MIDI file.
+#[derive(Debug, Error)]
+pub enum ReadError {
+    #[error("failed to read the file: {err}")]
+    Io {
+        #[from]
+        err: std::io::Error,
+    },
+    #[error("the file does not begin with an `MThd` header chunk")]
+    MissingHeader,
+    #[error("the header specifies the unknown format {0}")]
+    UnknownFormat(u16),
+    #[error("the file ended unexpectedly")]
+    UnexpectedEnd,
+    #[error("invalid event at byte {offset}: {err}")]
+    InvalidEvent {
+        offset: usize,
+        err: msg::DecodeError,
+    },
+    #[error("a variable length quantity at byte {offset} exceeds four bytes")]
+    InvalidLength { offset: usize },
+}
+
+// A cursor over the bytes of a file.
+struct Bytes<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl File {
+    /// Read the MIDI file at the given path.
+    pub fn open<P>(path: P) -> Result<Self, ReadError>
+    where
+        P: AsRef<Path>,
+    {
+        let mut bytes = vec![];
+        std::fs::File::open(path)?.read_to_end(&mut bytes)?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Parse a MIDI file from the given bytes.
+    ///
+    /// Chunks other than the header and tracks are ignored.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ReadError> {
+        let mut bytes = Bytes { bytes, offset: 0 };
+        let (kind, mut header) = bytes.chunk().map_err(|_| ReadError::MissingHeader)?;
+        if kind != *b"MThd" {
+            return Err(ReadError::MissingHeader);
+        }
+        let format = match header.u16()? {
+            0 => Format::SingleTrack,
+            1 => Format::Parallel,
+            2 => Format::Sequential,
+            n => return Err(ReadError::UnknownFormat(n)),
+        };
+        let num_tracks = header.u16()? as usize;
+        let division = header.u16()?;
+        let timing = if division & 0x8000 == 0 {
+            Timing::TicksPerQuarter(division)
+        } else {
+            let fps = ((division >> 8) as i8).wrapping_neg() as u8;
+            let ticks_per_frame = (division & 0xFF) as u8;
+            Timing::Timecode {
+                fps,
+                ticks_per_frame,
+            }
+        };
+
+        let mut tracks = Vec::with_capacity(num_tracks);
+        while tracks.len() < num_tracks && !bytes.is_empty() {
+            let (kind, mut chunk) = bytes.chunk()?;
+            if kind == *b"MTrk" {
+                tracks.push(chunk.track()?);
+            }
+        }
+
+        Ok(File {
+            format,
+            timing,
+            tracks,
+        })
+    }
+
+    /// All MIDI messages within the file along with the time at which they occur.
+    ///
+    /// Ticks are converted to time using the file's `Timing` along with any tempo changes. The
+    /// tracks of `SingleTrack` and `Parallel` files are merged into a single timeline ordered by
+    /// time. Each track of a `Sequential` file begins at zero with a tempo map of its own and its
+    /// events follow those of the previous track.
+    pub fn timed_events(&self) -> Vec<TimedEvent> {
+        match self.format {
+            Format::SingleTrack | Format::Parallel => {
+                let tracks: Vec<_> = self.tracks.iter().enumerate().collect();
+                self.timeline(&tracks)
+            }
+            Format::Sequential => self
+                .tracks
+                .iter()
+                .enumerate()
+                .flat_map(|track| self.timeline(&[track]))
+                .collect(),
+        }
+    }
+
+    // Merge the given tracks into a single timeline.
+    fn timeline(&self, tracks: &[(usize, &Track)]) -> Vec<TimedEvent> {
+        // Find the tick of every event, ordering simultaneous events by track.
+        let mut ticked = vec![];
+        for &(track_ix, track) in tracks {
+            let mut tick = 0u64;
+            for event in &track.events {
+                tick += event.delta as u64;
+                ticked.push((tick, track_ix, &event.kind));
+            }
+        }
+        ticked.sort_by_key(|&(tick, track_ix, _)| (tick, track_ix));
+
+        let mut events = vec![];
+        let mut tempo = DEFAULT_TEMPO;
+        let mut last_tick = 0;
+        let mut micros = 0.0;
+        for (tick, track, kind) in ticked {
+            micros += (tick - last_tick) as f64 * self.tick_micros(tempo);
+            last_tick = tick;
+            match *kind {
+                EventKind::Meta(Meta::Tempo(t)) => tempo = t,
+                EventKind::Midi(ref message) => {
+                    let time = Duration::from_micros(micros.round() as u64);
+                    let message = message.clone();
+                    events.push(TimedEvent {
+                        time,
+                        track,
+                        message,
+                    });
+                }
+                _ => (),
+            }
+        }
+        events
+    }
+
+    // The duration of a single tick in microseconds at the given tempo.
+    fn tick_micros(&self, tempo: u32) -> f64 {
+        match self.timing {
+            Timing::TicksPerQuarter(ticks) => tempo as f64 / ticks.max(1) as f64,
+            Timing::Timecode {
+                fps,
+                ticks_per_frame,
+            } => {
+                let fps = if fps == 29 { 29.97 } else { fps as f64 };
+                1_000_000.0 / (fps * ticks_per_frame.max(1) as f64)
+            }
+        }
+    }
+}
+
+impl<'a> Bytes<'a> {
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ReadError> {
+        if self.bytes.len() < len {
+            return Err(ReadError::UnexpectedEnd);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        self.offset += len;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, ReadError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn peek(&self) -> Result<u8, ReadError> {
+        self.bytes.first().cloned().ok_or(ReadError::UnexpectedEnd)
+    }
+
+    fn u16(&mut self) -> Result<u16, ReadError> {
+        let b = self.take(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, ReadError> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    // A variable length quantity, made up of 7-bit bytes with the top bit set on all but the last.
+    fn vlq(&mut self) -> Result<u32, ReadError> {
+        let offset = self.offset;
+        let mut value = 0u32;
+        for _ in 0..4 {
+            let byte = self.u8()?;
+            value = (value << 7) | (byte & 0x7F) as u32;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ReadError::InvalidLength { offset })
+    }
+
+    // The kind and contents of the next chunk.
+    fn chunk(&mut self) -> Result<([u8; 4], Bytes<'a>), ReadError> {
+        let kind = self.take(4)?;
+        let kind = [kind[0], kind[1], kind[2], kind[3]];
+        let len = self.u32()? as usize;
+        let offset = self.offset;
+        // Some files in the wild declare a final track longer than the remaining bytes.
+        let len = len.min(self.bytes.len());
+        let bytes = self.take(len)?;
+        Ok((kind, Bytes { bytes, offset }))
+    }
+
+    // Parse the contents of an `MTrk` chunk.
+    fn track(&mut self) -> Result<Track, ReadError> {
+        let mut events = vec![];
+        let mut running_status = None;
+        while !self.is_empty() {
+            let delta = self.vlq()?;
+            let offset = self.offset;
+            let kind = match self.peek()? {
+                0xFF => {
+                    self.u8()?;
+                    let kind = self.u8()?;
+                    let len = self.vlq()? as usize;
+                    let data = self.take(len)?;
+                    EventKind::Meta(Meta::new(kind, data))
+                }
+                0xF0 => {
+                    self.u8()?;
+                    let len = self.vlq()? as usize;
+                    let data = self.take(len)?;
+                    let data = match data.split_last() {
+                        Some((&0xF7, data)) => data,
+                        _ => data,
+                    };
+                    EventKind::Midi(Message::SysEx(data.to_vec()))
+                }
+                0xF7 => {
+                    self.u8()?;
+                    let len = self.vlq()? as usize;
+                    EventKind::Escape(self.take(len)?.to_vec())
+                }
+                byte => {
+                    // Channel messages may omit the status byte if it matches the previous one.
+                    let status = if byte & 0x80 != 0 {
+                        self.u8()?
+                    } else {
+                        running_status.ok_or(ReadError::InvalidEvent {
+                            offset,
+                            err: msg::DecodeError::MissingStatus(byte),
+                        })?
+                    };
+                    let len = msg::data_len(status)
+                        .map_err(|err| ReadError::InvalidEvent { offset, err })?;
+                    if status < 0xF0 {
+                        running_status = Some(status);
+                    }
+                    let data = self.take(len)?;
+                    EventKind::Midi(msg::from_parts(status, data))
+                }
+            };
+            let end = matches!(kind, EventKind::Meta(Meta::EndOfTrack));
+            events.push(TrackEvent { delta, kind });
+            if end {
+                break;
+            }
+        }
+        Ok(Track { events })
+    }
+}
+
+impl Meta {
+    fn new(kind: u8, data: &[u8]) -> Self {
+        let text = || String::from_utf8_lossy(data).into_owned();
+        match (kind, data.len()) {
+            (0x00, 2) => Meta::SequenceNumber(u16::from_be_bytes([data[0], data[1]])),
+            (0x01, _) => Meta::Text(text()),
+            (0x02, _) => Meta::Copyright(text()),
+            (0x03, _) => Meta::TrackName(text()),
+            (0x04, _) => Meta::InstrumentName(text()),
+            (0x05, _) => Meta::Lyric(text()),
+            (0x06, _) => Meta::Marker(text()),
+            (0x07, _) => Meta::CuePoint(text()),
+            (0x20, 1) => Meta::ChannelPrefix(data[0]),
+            (0x21, 1) => Meta::Port(data[0]),
+            (0x2F, _) => Meta::EndOfTrack,
+            (0x51, 3) => Meta::Tempo(u32::from_be_bytes([0, data[0], data[1], data[2]])),
+            (0x54, 5) => Meta::SmpteOffset {
+                hours: data[0],
+                minutes: data[1],
+                seconds: data[2],
+                frames: data[3],
+                subframes: data[4],
+            },
+            (0x58, 4) => Meta::TimeSignature {
+                numerator: data[0],
+                denominator: data[1],
+                clocks_per_click: data[2],
+                thirty_seconds_per_quarter: data[3],
+            },
+            (0x59, 2) => Meta::KeySignature {
+                sharps: data[0] as i8,
+                minor: data[1] != 0,
+            },
+            (0x7F, _) => Meta::SequencerSpecific(data.to_vec()),
+            _ => Meta::Unknown {
+                kind,
+                data: data.to_vec(),
+            },
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Tools for working with MIDI. [**receiver(port)**](./fn.receiver.html) creates a MIDI receiver,
+//! [**sender(port)**](./fn.sender.html) creates a MIDI sender and
+//! [**input_ports()**](./fn.input_ports.html) and [**output_ports()**](./fn.output_ports.html)
+//! list the names of the available ports.
+
+pub use midir;
+
+pub use self::file::File;
+pub use self::msg::{DecodeError, Message};
+pub use self::recv::{Receiver, Timestamp};
+pub use self::send::Sender;
+
+use midir::{MidiInput, MidiOutput, PortInfoError};
+use thiserror::Error;
+
+pub mod file;
+pub mod msg;
+pub mod recv;
+pub mod send;
+
+/// The name with which nannou registers itself as a MIDI client.
+pub const CLIENT_NAME: &str = "nannou";
+
+/// Errors that might occur while enumerating or opening MIDI ports.
+#[derive(Debug, Error)]
+pub enum PortError {
+    #[error("failed to initialise the MIDI client: {0}")]
+    Init(#[from] midir::InitError),
+    #[error("failed to retrieve port info: {0}")]
+    PortInfo(#[from] PortInfoError),
+    #[error("no MIDI port matching \"{name}\" is available")]
+    NoPort { name: String },
+    #[error("failed to connect to the MIDI port: {0}")]
+    Connect(midir::ConnectErrorKind),
+}
+
+/// Errors that might occur whilst attempting to send or receive a MIDI message.
+#[derive(Debug, Error)]
+pub enum CommunicationError {
+    #[error("failed to send the MIDI message: {0}")]
+    Send(#[from] midir::SendError),
+    #[error("failed to decode the MIDI message: {0}")]
+    Decode(#[from] DecodeError),
+    #[error("the MIDI port was closed")]
+    Disconnected,
+}
+
+impl<T> From<midir::ConnectError<T>> for PortError {
+    fn from(err: midir::ConnectError<T>) -> Self {
+        PortError::Connect(err.kind())
+    }
+}
+
+/// The names of the available MIDI input ports.
+pub fn input_ports() -> Result<Vec<String>, PortError> {
+    let input = MidiInput::new(CLIENT_NAME)?;
+    let names = input
+        .ports()
+        .iter()
+        .map(|port| input.port_name(port))
+        .collect::<Result<_, _>>()?;
+    Ok(names)
+}
+
+/// The names of the available MIDI output ports.
+pub fn output_ports() -> Result<Vec<String>, PortError> {
+    let output = MidiOutput::new(CLIENT_NAME)?;
+    let names = output
+        .ports()
+        .iter()
+        .map(|port| output.port_name(port))
+        .collect::<Result<_, _>>()?;
+    Ok(names)
+}
+
+/// A simple wrapper around the most commonly used `Receiver` constructor.
+pub fn receiver(port: &str) -> Result<Receiver, PortError> {
+    Receiver::connect(port)
+}
+
+/// A simple wrapper around the most commonly used `Sender` constructor.
+pub fn sender(port: &str) -> Result<Sender, PortError> {
+    Sender::connect(port)
+}
+
+/// Decodes a single MIDI message from the given bytes.
+///
+/// Returns a `DecodeError` if the slice does not contain exactly one valid MIDI message.
+pub fn decode(bytes: &[u8]) -> Result<Message, DecodeError> {
+    Message::decode(bytes)
+}
+
+/// Encodes the given `Message` into a `Vec` of bytes.
+pub fn encode(msg: &Message) -> Vec<u8> {
+    msg.encode()
+}
+
+// Find the port with the given name, or the first whose name contains the given name.
+fn find_port<P, F>(ports: Vec<P>, name: &str, name_of: F) -> Result<(P, String), PortError>
+where
+    F: Fn(&P) -> Result<String, PortInfoError>,
+{
+    let mut named = Vec::with_capacity(ports.len());
+    for port in ports {
+        let port_name = name_of(&port)?;
+        named.push((port, port_name));
+    }
+    let exact = named.iter().position(|(_, n)| n == name);
+    let ix = exact.or_else(|| named.iter().position(|(_, n)| n.contains(name)));
+    match ix {
+        Some(ix) => Ok(named.swap_remove(ix)),
+        None => Err(PortError::NoPort {
+            name: name.to_string(),
+        }),
+    }
+}
@@ -0,0 +1,341 @@
+//! Items related to typed MIDI messages and their encoding and decoding.
+
+use thiserror::Error;
+
+/// The value of a centred pitch bend.
+pub const PITCH_BEND_CENTER: i16 = 0;
+/// The minimum value of a pitch bend.
+pub const PITCH_BEND_MIN: i16 = -8192;
+/// The maximum value of a pitch bend.
+pub const PITCH_BEND_MAX: i16 = 8191;
+
+/// A single MIDI message.
+///
+/// Channels are in the range `0..16`. All other data values (keys, velocities, controller numbers,
+/// etc) are 7-bit and in the range `0..128`. Values outside of these ranges are masked when the
+/// message is encoded.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Message {
+    /// A key was released.
+    NoteOff { channel: u8, key: u8, velocity: u8 },
+    /// A key was pressed.
+    ///
+    /// Many devices send a `NoteOn` with a velocity of `0` in place of a `NoteOff`. See
+    /// `Message::is_note_off`.
+    NoteOn { channel: u8, key: u8, velocity: u8 },
+    /// The pressure applied to a single held key changed.
+    PolyPressure { channel: u8, key: u8, pressure: u8 },
+    /// A control change (CC), e.g. a knob, fader or pedal moved.
+    ControlChange {
+        channel: u8,
+        controller: u8,
+        value: u8,
+    },
+    /// The instrument program changed.
+    ProgramChange { channel: u8, program: u8 },
+    /// The pressure applied to all held keys changed.
+    ChannelPressure { channel: u8, pressure: u8 },
+    /// The pitch bend wheel moved.
+    ///
+    /// The value is in the range `PITCH_BEND_MIN..=PITCH_BEND_MAX`, centred at `0`.
+    PitchBend { channel: u8, value: i16 },
+    /// A system exclusive message.
+    ///
+    /// Contains the data between the leading `0xF0` and trailing `0xF7` status bytes.
+    SysEx(Vec<u8>),
+    /// An MTC quarter frame, containing the message type and value nibbles.
+    TimeCodeQuarterFrame(u8),
+    /// The song position as a number of MIDI beats (sixteenth notes) since the start.
+    SongPosition(u16),
+    /// The song or sequence to play.
+    SongSelect(u8),
+    /// A request for analog synthesizers to tune their oscillators.
+    TuneRequest,
+    /// A clock tick, sent 24 times per quarter note.
+    Clock,
+    /// Start playback from the beginning of the song.
+    Start,
+    /// Continue playback from the current song position.
+    Continue,
+    /// Stop playback.
+    Stop,
+    /// Sent regularly by some devices to indicate the connection is alive.
+    ActiveSensing,
+    /// Reset all receivers to their power-up state.
+    Reset,
+}
+
+/// Errors that might occur while decoding a MIDI message.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum DecodeError {
+    #[error("the message was empty")]
+    Empty,
+    #[error("expected a status byte, found the data byte {0:#04x}")]
+    MissingStatus(u8),
+    #[error("the status byte {0:#04x} is undefined")]
+    UndefinedStatus(u8),
+    #[error("expected {expected} data bytes for status {status:#04x}, found {found}")]
+    Length {
+        status: u8,
+        expected: usize,
+        found: usize,
+    },
+    #[error("the system exclusive message was not terminated by 0xf7")]
+    UnterminatedSysEx,
+}
+
+impl Message {
+    /// The channel of a channel message, or `None` for system messages.
+    pub fn channel(&self) -> Option<u8> {
+        match *self {
+            Message::NoteOff { channel, .. }
+            | Message::NoteOn { channel, .. }
+            | Message::PolyPressure { channel, .. }
+            | Message::ControlChange { channel, .. }
+            | Message::ProgramChange { channel, .. }
+            | Message::ChannelPressure { channel, .. }
+            | Message::PitchBend { channel, .. } => Some(channel),
+            _ => None,
+        }
+    }
+
+    /// Whether or not the message releases a key.
+    ///
+    /// This is `true` for `NoteOff` messages along with `NoteOn` messages with a velocity of `0`.
+    pub fn is_note_off(&self) -> bool {
+        match *self {
+            Message::NoteOff { .. } => true,
+            Message::NoteOn { velocity, .. } => velocity == 0,
+            _ => false,
+        }
+    }
+
+    /// Whether or not the message is a system real-time message, e.g. `Clock` or `Start`.
+    ///
+    /// Real-time messages may be interleaved with the bytes of other messages.
+    pub fn is_real_time(&self) -> bool {
+        matches!(
+            *self,
+            Message::Clock
+                | Message::Start
+                | Message::Continue
+                | Message::Stop
+                | Message::ActiveSensing
+                | Message::Reset
+        )
+    }
+
+    /// Decodes a single MIDI message from the given bytes.
+    ///
+    /// The slice must contain exactly one message, beginning with its status byte.
+    pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let (&status, data) = bytes.split_first().ok_or(DecodeError::Empty)?;
+        if status == 0xF0 {
+            return match data.split_last() {
+                Some((&0xF7, data)) => Ok(Message::SysEx(data.to_vec())),
+                _ => Err(DecodeError::UnterminatedSysEx),
+            };
+        }
+        let expected = data_len(status)?;
+        if data.len() != expected {
+            let found = data.len();
+            return Err(DecodeError::Length {
+                status,
+                expected,
+                found,
+            });
+        }
+        Ok(from_parts(status, data))
+    }
+
+    /// Encodes the message into the end of the given buffer of bytes.
+    pub fn encode_into(&self, bytes: &mut Vec<u8>) {
+        let channel_status = |kind: u8, channel: u8| kind | (channel & 0x0F);
+        match *self {
+            Message::NoteOff {
+                channel,
+                key,
+                velocity,
+            } => bytes.extend(&[channel_status(0x80, channel), key & 0x7F, velocity & 0x7F]),
+            Message::NoteOn {
+                channel,
+                key,
+                velocity,
+            } => bytes.extend(&[channel_status(0x90, channel), key & 0x7F, velocity & 0x7F]),
+            Message::PolyPressure {
+                channel,
+                key,
+                pressure,
+            } => bytes.extend(&[channel_status(0xA0, channel), key & 0x7F, pressure & 0x7F]),
+            Message::ControlChange {
+                channel,
+                controller,
+                value,
+            } => bytes.extend(&[
+                channel_status(0xB0, channel),
+                controller & 0x7F,
+                value & 0x7F,
+            ]),
+            Message::ProgramChange { channel, program } => {
+                bytes.extend(&[channel_status(0xC0, channel), program & 0x7F])
+            }
+            Message::ChannelPressure { channel, pressure } => {
+                bytes.extend(&[channel_status(0xD0, channel), pressure & 0x7F])
+            }
+            Message::PitchBend { channel, value } => {
+                let value = (value.clamp(PITCH_BEND_MIN, PITCH_BEND_MAX) + 8192) as u16;
+                let (lsb, msb) = split_u14(value);
+                bytes.extend(&[channel_status(0xE0, channel), lsb, msb]);
+            }
+            Message::SysEx(ref data) => {
+                bytes.push(0xF0);
+                bytes.extend(data.iter().map(|b| b & 0x7F));
+                bytes.push(0xF7);
+            }
+            Message::TimeCodeQuarterFrame(value) => bytes.extend(&[0xF1, value & 0x7F]),
+            Message::SongPosition(beats) => {
+                let (lsb, msb) = split_u14(beats);
+                bytes.extend(&[0xF2, lsb, msb]);
+            }
+            Message::SongSelect(song) => bytes.extend(&[0xF3, song & 0x7F]),
+            Message::TuneRequest => bytes.push(0xF6),
+            Message::Clock => bytes.push(0xF8),
+            Message::Start => bytes.push(0xFA),
+            Message::Continue => bytes.push(0xFB),
+            Message::Stop => bytes.push(0xFC),
+            Message::ActiveSensing => bytes.push(0xFE),
+            Message::Reset => bytes.push(0xFF),
+        }
+    }
+
+    /// Encodes the message into a `Vec` of bytes.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(3);
+        self.encode_into(&mut bytes);
+        bytes
+    }
+}
+
+impl std::fmt::Display for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Message::NoteOff {
+                channel,
+                key,
+                velocity,
+            } => write!(f, "ch{} note off {} vel {}", channel + 1, key, velocity),
+            Message::NoteOn {
+                channel,
+                key,
+                velocity,
+            } => write!(f, "ch{} note on {} vel {}", channel + 1, key, velocity),
+            Message::PolyPressure {
+                channel,
+                key,
+                pressure,
+            } => write!(f, "ch{} poly pressure {} {}", channel + 1, key, pressure),
+            Message::ControlChange {
+                channel,
+                controller,
+                value,
+            } => write!(f, "ch{} cc {} {}", channel + 1, controller, value),
+            Message::ProgramChange { channel, program } => {
+                write!(f, "ch{} program {}", channel + 1, program)
+            }
+            Message::ChannelPressure { channel, pressure } => {
+                write!(f, "ch{} channel pressure {}", channel + 1, pressure)
+            }
+            Message::PitchBend { channel, value } => {
+                write!(f, "ch{} pitch bend {}", channel + 1, value)
+            }
+            Message::SysEx(ref data) => write!(f, "sysex ({} bytes)", data.len()),
+            Message::TimeCodeQuarterFrame(value) => write!(f, "mtc quarter frame {:#04x}", value),
+            Message::SongPosition(beats) => write!(f, "song position {}", beats),
+            Message::SongSelect(song) => write!(f, "song select {}", song),
+            Message::TuneRequest => write!(f, "tune request"),
+            Message::Clock => write!(f, "clock"),
+            Message::Start => write!(f, "start"),
+            Message::Continue => write!(f, "continue"),
+            Message::Stop => write!(f, "stop"),
+            Message::ActiveSensing => write!(f, "active sensing"),
+            Message::Reset => write!(f, "reset"),
+        }
+    }
+}
+
+/// The number of data bytes following the given status byte, excluding system exclusive messages.
+pub(crate) fn data_len(status: u8) -> Result<usize, DecodeError> {
+    let len = match status {
+        0x00..=0x7F => return Err(DecodeError::MissingStatus(status)),
+        0x80..=0xBF | 0xE0..=0xEF | 0xF2 => 2,
+        0xC0..=0xDF | 0xF1 | 0xF3 => 1,
+        0xF6 | 0xF8 | 0xFA..=0xFC | 0xFE | 0xFF => 0,
+        _ => return Err(DecodeError::UndefinedStatus(status)),
+    };
+    Ok(len)
+}
+
+/// Produce a message from a status byte and the matching number of data bytes.
+///
+/// The status must have been validated via `data_len`.
+pub(crate) fn from_parts(status: u8, data: &[u8]) -> Message {
+    let channel = status & 0x0F;
+    let d0 = data.first().map(|b| b & 0x7F).unwrap_or(0);
+    let d1 = data.get(1).map(|b| b & 0x7F).unwrap_or(0);
+    match status & 0xF0 {
+        0x80 => Message::NoteOff {
+            channel,
+            key: d0,
+            velocity: d1,
+        },
+        0x90 => Message::NoteOn {
+            channel,
+            key: d0,
+            velocity: d1,
+        },
+        0xA0 => Message::PolyPressure {
+            channel,
+            key: d0,
+            pressure: d1,
+        },
+        0xB0 => Message::ControlChange {
+            channel,
+            controller: d0,
+            value: d1,
+        },
+        0xC0 => Message::ProgramChange {
+            channel,
+            program: d0,
+        },
+        0xD0 => Message::ChannelPressure {
+            channel,
+            pressure: d0,
+        },
+        0xE0 => Message::PitchBend {
+            channel,
+            value: join_u14(d0, d1) as i16 - 8192,
+        },
+        _ => match status {
+            0xF1 => Message::TimeCodeQuarterFrame(d0),
+            0xF2 => Message::SongPosition(join_u14(d0, d1)),
+            0xF3 => Message::SongSelect(d0),
+            0xF6 => Message::TuneRequest,
+            0xF8 => Message::Clock,
+            0xFA => Message::Start,
+            0xFB => Message::Continue,
+            0xFC => Message::Stop,
+            0xFE => Message::ActiveSensing,
+            _ => Message::Reset,
+        },
+    }
+}
+
+// Split a 14-bit value into its least and most significant 7-bit bytes.
+fn split_u14(value: u16) -> (u8, u8) {
+    ((value & 0x7F) as u8, ((value >> 7) & 0x7F) as u8)
+}
+
+// Join the least and most significant 7-bit bytes of a 14-bit value.
+fn join_u14(lsb: u8, msb: u8) -> u16 {
+    (lsb as u16) | ((msb as u16) << 7)
+}
@@ -0,0 +1,206 @@
+//! Items related to the `midi::Receiver` implementation.
+
+use super::{find_port, CommunicationError, DecodeError, Message, PortError, CLIENT_NAME};
+use midir::{Ignore, MidiInput, MidiInputConnection};
+use std::sync::mpsc;
+use std::time::Instant;
+
+/// A type used for receiving MIDI messages from an input port.
+///
+/// Messages are received on a backend thread and queued until they are retrieved via the `recv`
+/// and `try_recv` methods or their iterators. The port is closed when the `Receiver` is dropped.
+pub struct Receiver {
+    // Kept alive to keep the port open.
+    _connection: MidiInputConnection<()>,
+    messages: mpsc::Receiver<Result<(Message, Timestamp), DecodeError>>,
+    port_name: String,
+}
+
+/// The time at which a MIDI message was received.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp {
+    /// The moment the message was delivered to the receiver by the backend.
+    ///
+    /// This is comparable with `Instant`s produced elsewhere in the app, e.g. the time of a frame.
+    pub instant: Instant,
+    /// The timestamp provided by the backend in microseconds.
+    ///
+    /// This is more precise than `instant` for measuring the time between messages, but the point
+    /// from which it is measured depends on the backend.
+    pub micros: u64,
+}
+
+/// An iterator that calls `recv` on the inner `Receiver` and yields the results.
+///
+/// Each call to `next` will block until the next message is received or until some error occurs.
+pub struct Iter<'a> {
+    receiver: &'a Receiver,
+}
+
+/// An iterator that calls `try_recv` on the inner `Receiver` and yields the results.
+///
+/// Each call to `next` will only return `Some` while there are pending messages and will return
+/// `None` otherwise.
+pub struct TryIter<'a> {
+    receiver: &'a Receiver,
+}
+
+impl Receiver {
+    /// Connect to the first MIDI input port whose name matches the given name.
+    ///
+    /// A port whose name is exactly `name` is preferred, otherwise the first port whose name
+    /// contains `name` is used. See `input_ports` for the names of the available ports.
+    ///
+    /// ```no_run
+    /// use nannou_midi::Receiver;
+    ///
+    /// fn main() {
+    ///     let rx = Receiver::connect("nanoKONTROL2").expect("Couldn't connect to port");
+    /// }
+    /// ```
+    pub fn connect(name: &str) -> Result<Self, PortError> {
+        let input = new_input()?;
+        let (port, port_name) = find_port(input.ports(), name, |p| input.port_name(p))?;
+        let (tx, messages) = mpsc::channel();
+        let connection = input.connect(&port, CLIENT_NAME, callback(tx), ())?;
+        Ok(Receiver {
+            _connection: connection,
+            messages,
+            port_name,
+        })
+    }
+
+    /// Connect to the MIDI input port at the given index within `input_ports`.
+    ///
+    /// Returns `PortError::NoPort` if there is no port at the given index.
+    pub fn connect_index(index: usize) -> Result<Self, PortError> {
+        let input = new_input()?;
+        let port = input
+            .ports()
+            .into_iter()
+            .nth(index)
+            .ok_or_else(|| PortError::NoPort {
+                name: format!("#{}", index),
+            })?;
+        let port_name = input.port_name(&port)?;
+        let (tx, messages) = mpsc::channel();
+        let connection = input.connect(&port, CLIENT_NAME, callback(tx), ())?;
+        Ok(Receiver {
+            _connection: connection,
+            messages,
+            port_name,
+        })
+    }
+
+    /// Create a virtual input port with the given name to which other applications may connect.
+    ///
+    /// Virtual ports are supported by the ALSA, JACK and CoreMIDI backends.
+    ///
+    /// ```no_run
+    /// use nannou_midi::Receiver;
+    ///
+    /// fn main() {
+    ///     let rx = Receiver::create_virtual("nannou in").expect("Couldn't create virtual port");
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn create_virtual(name: &str) -> Result<Self, PortError> {
+        use midir::os::unix::VirtualInput;
+        let input = new_input()?;
+        let (tx, messages) = mpsc::channel();
+        let connection = input.create_virtual(name, callback(tx), ())?;
+        Ok(Receiver {
+            _connection: connection,
+            messages,
+            port_name: name.to_string(),
+        })
+    }
+
+    /// The name of the port from which messages are received.
+    pub fn port_name(&self) -> &str {
+        &self.port_name
+    }
+
+    /// Waits for the next MIDI message to be received and returns it along with the time at which
+    /// it was received.
+    ///
+    /// This will return a `CommunicationError` if the bytes received could not be decoded into a
+    /// `Message` or if the backend closed the port.
+    pub fn recv(&self) -> Result<(Message, Timestamp), CommunicationError> {
+        match self.messages.recv() {
+            Ok(result) => Ok(result?),
+            Err(_) => Err(CommunicationError::Disconnected),
+        }
+    }
+
+    /// Checks for a pending MIDI message and returns `Ok(Some)` if there is one waiting along
+    /// with the time at which it was received.
+    ///
+    /// If there are no messages waiting this will immediately return with `Ok(None)`.
+    ///
+    /// This will return a `CommunicationError` if the bytes received could not be decoded into a
+    /// `Message` or if the backend closed the port.
+    pub fn try_recv(&self) -> Result<Option<(Message, Timestamp)>, CommunicationError> {
+        match self.messages.try_recv() {
+            Ok(result) => Ok(Some(result?)),
+            Err(mpsc::TryRecvError::Empty) => Ok(None),
+            Err(mpsc::TryRecvError::Disconnected) => Err(CommunicationError::Disconnected),
+        }
+    }
+
+    /// An iterator yielding MIDI `Message`s along with the time at which they were received.
+    ///
+    /// Each call to `next` will block until the next message is received or until some error
+    /// occurs.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { receiver: self }
+    }
+
+    /// An iterator yielding MIDI `Message`s along with the time at which they were received.
+    ///
+    /// Each call to `next` will only return `Some` while there are pending messages and will
+    /// return `None` otherwise. Messages that could not be decoded are skipped.
+    pub fn try_iter(&self) -> TryIter<'_> {
+        TryIter { receiver: self }
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (Message, Timestamp);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.recv().ok()
+    }
+}
+
+impl<'a> Iterator for TryIter<'a> {
+    type Item = (Message, Timestamp);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(msg) => return msg,
+                Err(CommunicationError::Decode(_)) => continue,
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+// A MIDI input client that receives all message types, including SysEx and timing messages.
+fn new_input() -> Result<MidiInput, PortError> {
+    let mut input = MidiInput::new(CLIENT_NAME)?;
+    input.ignore(Ignore::None);
+    Ok(input)
+}
+
+// The function called by the backend for each message received.
+fn callback(
+    tx: mpsc::Sender<Result<(Message, Timestamp), DecodeError>>,
+) -> impl FnMut(u64, &[u8], &mut ()) + Send + 'static {
+    move |micros, bytes, _| {
+        let instant = Instant::now();
+        let timestamp = Timestamp { instant, micros };
+        let result = Message::decode(bytes).map(|msg| (msg, timestamp));
+        // The `Receiver` may be in the middle of being dropped.
+        tx.send(result).ok();
+    }
+}
@@ -0,0 +1,109 @@
+//! Items related to the `midi::Sender` implementation.
+
+use super::{find_port, CommunicationError, Message, PortError, CLIENT_NAME};
+use midir::{MidiOutput, MidiOutputConnection};
+
+/// A type used for sending MIDI messages to an output port.
+///
+/// The port is closed when the `Sender` is dropped.
+pub struct Sender {
+    connection: MidiOutputConnection,
+    buffer: Vec<u8>,
+    port_name: String,
+}
+
+impl Sender {
+    /// Connect to the first MIDI output port whose name matches the given name.
+    ///
+    /// A port whose name is exactly `name` is preferred, otherwise the first port whose name
+    /// contains `name` is used. See `output_ports` for the names of the available ports.
+    ///
+    /// ```no_run
+    /// use nannou_midi::Sender;
+    ///
+    /// fn main() {
+    ///     let tx = Sender::connect("Midi Through").expect("Couldn't connect to port");
+    /// }
+    /// ```
+    pub fn connect(name: &str) -> Result<Self, PortError> {
+        let output = MidiOutput::new(CLIENT_NAME)?;
+        let (port, port_name) = find_port(output.ports(), name, |p| output.port_name(p))?;
+        let connection = output.connect(&port, CLIENT_NAME)?;
+        Ok(Sender::new(connection, port_name))
+    }
+
+    /// Connect to the MIDI output port at the given index within `output_ports`.
+    ///
+    /// Returns `PortError::NoPort` if there is no port at the given index.
+    pub fn connect_index(index: usize) -> Result<Self, PortError> {
+        let output = MidiOutput::new(CLIENT_NAME)?;
+        let port = output
+            .ports()
+            .into_iter()
+            .nth(index)
+            .ok_or_else(|| PortError::NoPort {
+                name: format!("#{}", index),
+            })?;
+        let port_name = output.port_name(&port)?;
+        let connection = output.connect(&port, CLIENT_NAME)?;
+        Ok(Sender::new(connection, port_name))
+    }
+
+    /// Create a virtual output port with the given name to which other applications may connect.
+    ///
+    /// Virtual ports are supported by the ALSA, JACK and CoreMIDI backends.
+    ///
+    /// ```no_run
+    /// use nannou_midi::Sender;
+    ///
+    /// fn main() {
+    ///     let tx = Sender::create_virtual("nannou out").expect("Couldn't create virtual port");
+    /// }
+    /// ```
+    #[cfg(unix)]
+    pub fn create_virtual(name: &str) -> Result<Self, PortError> {
+        use midir::os::unix::VirtualOutput;
+        let output = MidiOutput::new(CLIENT_NAME)?;
+        let connection = output.create_virtual(name)?;
+        Ok(Sender::new(connection, name.to_string()))
+    }
+
+    fn new(connection: MidiOutputConnection, port_name: String) -> Self {
+        Sender {
+            connection,
+            buffer: Vec::with_capacity(3),
+            port_name,
+        }
+    }
+
+    /// The name of the port to which messages are sent.
+    pub fn port_name(&self) -> &str {
+        &self.port_name
+    }
+
+    /// Send the given message.
+    ///
+    /// ```no_run
+    /// use nannou_midi::{Message, Sender};
+    ///
+    /// fn main() {
+    ///     let mut tx = Sender::connect("Midi Through").unwrap();
+    ///     let msg = Message::NoteOn { channel: 0, key: 60, velocity: 100 };
+    ///     tx.send(&msg).expect("Couldn't send message");
+    /// }
+    /// ```
+    pub fn send(&mut self, msg: &Message) -> Result<(), CommunicationError> {
+        self.buffer.clear();
+        msg.encode_into(&mut self.buffer);
+        self.connection.send(&self.buffer)?;
+        Ok(())
+    }
+
+    /// Send the given bytes as-is.
+    ///
+    /// The bytes must contain one or more complete MIDI messages.
+    pub fn send_raw(&mut self, bytes: &[u8]) -> Result<(), CommunicationError> {
+        self.connection.send(bytes)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+use nannou_midi as midi;
+use nannou_midi::file::{EventKind, Format, Meta, Timing};
+use nannou_midi::{DecodeError, Message};
+use std::time::Duration;
+
+#[test]
+fn encode_decode_round_trip() {
+    let msgs = vec![
+        Message::NoteOn {
+            channel: 9,
+            key: 36,
+            velocity: 127,
+        },
+        Message::NoteOff {
+            channel: 0,
+            key: 60,
+            velocity: 0,
+        },
+        Message::ControlChange {
+            channel: 15,
+            controller: 74,
+            value: 64,
+        },
+        Message::ProgramChange {
+            channel: 3,
+            program: 12,
+        },
+        Message::PitchBend {
+            channel: 1,
+            value: midi::msg::PITCH_BEND_MIN,
+        },
+        Message::PitchBend {
+            channel: 1,
+            value: midi::msg::PITCH_BEND_MAX,
+        },
+        Message::SysEx(vec![0x7E, 0x7F, 0x06, 0x01]),
+        Message::SongPosition(1000),
+        Message::Clock,
+        Message::Start,
+        Message::Stop,
+    ];
+    for msg in msgs {
+        assert_eq!(midi::decode(&midi::encode(&msg)), Ok(msg));
+    }
+}
+
+#[test]
+fn decode_pitch_bend() {
+    let center = midi::decode(&[0xE2, 0x00, 0x40]).unwrap();
+    assert_eq!(
+        center,
+        Message::PitchBend {
+            channel: 2,
+            value: midi::msg::PITCH_BEND_CENTER,
+        }
+    );
+    assert_eq!(midi::encode(&center), vec![0xE2, 0x00, 0x40]);
+}
+
+#[test]
+fn decode_errors() {
+    assert_eq!(midi::decode(&[]), Err(DecodeError::Empty));
+    assert_eq!(
+        midi::decode(&[0x40, 0x40]),
+        Err(DecodeError::MissingStatus(0x40))
+    );
+    assert_eq!(
+        midi::decode(&[0xF4]),
+        Err(DecodeError::UndefinedStatus(0xF4))
+    );
+    assert_eq!(
+        midi::decode(&[0x90, 60]),
+        Err(DecodeError::Length {
+            status: 0x90,
+            expected: 2,
+            found: 1,
+        })
+    );
+    assert_eq!(
+        midi::decode(&[0xF0, 0x01, 0x02]),
+        Err(DecodeError::UnterminatedSysEx)
+    );
+}
+
+#[test]
+fn note_on_zero_velocity_is_note_off() {
+    let msg = midi::decode(&[0x90, 60, 0]).unwrap();
+    assert!(msg.is_note_off());
+    assert_eq!(msg.channel(), Some(0));
+    assert!(!Message::Clock.is_note_off());
+    assert!(Message::Clock.is_real_time());
+}
+
+// A format 1 file with a tempo track and a note track using running status.
+fn two_track_file() -> Vec<u8> {
+    let mut bytes = vec![];
+    bytes.extend(b"MThd");
+    bytes.extend(&6u32.to_be_bytes());
+    bytes.extend(&[0, 1, 0, 2, 0, 96]);
+
+    let tempo_track = [
+        0x00, 0xFF, 0x03, 0x05, b't', b'e', b'm', b'p', b'o', // Track name.
+        0x00, 0xFF, 0x51, 0x03, 0x0F, 0x42, 0x40, // 1_000_000us per quarter.
+        0x00, 0xFF, 0x2F, 0x00,
+    ];
+    bytes.extend(b"MTrk");
+    bytes.extend(&(tempo_track.len() as u32).to_be_bytes());
+    bytes.extend(&tempo_track);
+
+    let note_track = [
+        0x00, 0x90, 60, 100, // Note on.
+        0x60, 60, 0, // Note off via running status, one quarter later.
+        0x81, 0x40, 0xB0, 7, 100, // Control change, two quarters later.
+        0x00, 0xFF, 0x2F, 0x00,
+    ];
+    bytes.extend(b"MTrk");
+    bytes.extend(&(note_track.len() as u32).to_be_bytes());
+    bytes.extend(&note_track);
+    bytes
+}
+
+#[test]
+fn read_file() {
+    let file = midi::File::from_bytes(&two_track_file()).unwrap();
+    assert_eq!(file.format, Format::Parallel);
+    assert_eq!(file.timing, Timing::TicksPerQuarter(96));
+    assert_eq!(file.tracks.len(), 2);
+    assert_eq!(
+        file.tracks[0].events[0].kind,
+        EventKind::Meta(Meta::TrackName("tempo".into()))
+    );
+    assert_eq!(
+        file.tracks[0].events[1].kind,
+        EventKind::Meta(Meta::Tempo(1_000_000))
+    );
+    let notes = &file.tracks[1].events;
+    assert_eq!(notes.len(), 4);
+    assert_eq!(notes[1].delta, 96);
+    assert_eq!(
+        notes[1].kind,
+        EventKind::Midi(Message::NoteOn {
+            channel: 0,
+            key: 60,
+            velocity: 0,
+        })
+    );
+    assert_eq!(notes[2].delta, 192);
+}
+
+#[test]
+fn file_timed_events() {
+    let file = midi::File::from_bytes(&two_track_file()).unwrap();
+    let events = file.timed_events();
+    let times: Vec<_> = events.iter().map(|e| e.time).collect();
+    assert_eq!(
+        times,
+        vec![
+            Duration::from_secs(0),
+            Duration::from_secs(1),
+            Duration::from_secs(3),
+        ]
+    );
+    assert!(events.iter().all(|e| e.track == 1));
+}
+
+#[test]
+fn read_file_errors() {
+    assert!(matches!(
+        midi::File::from_bytes(b"RIFF"),
+        Err(midi::file::ReadError::MissingHeader)
+    ));
+    let mut truncated = two_track_file();
+    truncated.truncate(42);
+    assert!(midi::File::from_bytes(&truncated).is_ok());
+    truncated.truncate(16);
+    assert!(midi::File::from_bytes(&truncated).is_err());
+}
+
+// Sends messages through a pair of virtual ports. Run via `cargo test -- --ignored` on machines
+// with the ALSA `snd-seq` module loaded.
+#[cfg(target_os = "linux")]
+#[test]
+#[ignore = "requires an ALSA MIDI sequencer"]
+fn virtual_port_loopback() {
+    let mut tx = midi::Sender::create_virtual("nannou_midi loopback").unwrap();
+    let rx = midi::Receiver::connect("nannou_midi loopback").unwrap();
+    let sent = vec![
+        Message::NoteOn {
+            channel: 0,
+            key: 64,
+            velocity: 90,
+        },
+        Message::ControlChange {
+            channel: 1,
+            controller: 1,
+            value: 42,
+        },
+        Message::SysEx(vec![0x7D, 0x01, 0x02]),
+    ];
+    for msg in &sent {
+        tx.send(msg).unwrap();
+    }
+    let received: Vec<_> = rx.iter().take(sent.len()).map(|(msg, _)| msg).collect();
+    assert_eq!(received, sent);
+    assert!(rx.try_iter().next().is_none());
+}
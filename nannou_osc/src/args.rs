@@ -0,0 +1,248 @@
+//! Items related to extracting typed values from the arguments of an OSC message.
+
+use super::{Color, MidiMessage, Type};
+
+/// Types that may be extracted from a single OSC argument.
+pub trait FromArg: Sized {
+    /// A name for the expected OSC type, used when describing errors.
+    const NAME: &'static str;
+    /// Extract the value from the argument, or `None` if the argument is of the wrong type.
+    fn from_arg(arg: &Type) -> Option<Self>;
+}
+
+/// Types that may be extracted from the full list of arguments of an OSC message.
+///
+/// This is implemented for tuples of up to eight `FromArg` types, each of which must match the
+/// argument at the same position, e.g. `(String, f32)`. The number of arguments must match the
+/// length of the tuple. It is also implemented for `Vec<T>` where every argument must be a `T`.
+pub trait FromArgs: Sized {
+    /// Extract the value from the full list of arguments.
+    fn from_args(args: &[Type]) -> Result<Self, ArgsError>;
+}
+
+/// Errors that might occur while extracting typed values from the arguments of an OSC message.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArgsError {
+    /// The message contained the wrong number of arguments.
+    Count { expected: usize, found: usize },
+    /// The argument at the given index was not of the expected type.
+    Type {
+        index: usize,
+        expected: &'static str,
+        found: Type,
+    },
+}
+
+/// Extract the typed arguments from the given list of OSC arguments.
+///
+/// ```
+/// use nannou_osc as osc;
+///
+/// fn main() {
+///     let args = vec![osc::Type::String("kick".into()), osc::Type::Float(0.5)];
+///     let (name, gain): (String, f32) = osc::args::extract(&args).unwrap();
+///     assert_eq!(name, "kick");
+///     assert_eq!(gain, 0.5);
+/// }
+/// ```
+pub fn extract<A>(args: &[Type]) -> Result<A, ArgsError>
+where
+    A: FromArgs,
+{
+    A::from_args(args)
+}
+
+// Extract the argument at the given index.
+fn arg<T>(args: &[Type], index: usize) -> Result<T, ArgsError>
+where
+    T: FromArg,
+{
+    let arg = &args[index];
+    T::from_arg(arg).ok_or_else(|| ArgsError::Type {
+        index,
+        expected: T::NAME,
+        found: arg.clone(),
+    })
+}
+
+impl FromArg for Type {
+    const NAME: &'static str = "any";
+    fn from_arg(arg: &Type) -> Option<Self> {
+        Some(arg.clone())
+    }
+}
+
+impl FromArg for i32 {
+    const NAME: &'static str = "int";
+    fn from_arg(arg: &Type) -> Option<Self> {
+        match *arg {
+            Type::Int(i) => Some(i),
+            _ => None,
+        }
+    }
+}
+
+impl FromArg for i64 {
+    const NAME: &'static str = "long or int";
+    fn from_arg(arg: &Type) -> Option<Self> {
+        match *arg {
+            Type::Long(l) => Some(l),
+            Type::Int(i) => Some(i as i64),
+            _ => None,
+        }
+    }
+}
+
+impl FromArg for f32 {
+    const NAME: &'static str = "float";
+    fn from_arg(arg: &Type) -> Option<Self> {
+        match *arg {
+            Type::Float(f) => Some(f),
+            _ => None,
+        }
+    }
+}
+
+impl FromArg for f64 {
+    const NAME: &'static str = "double or float";
+    fn from_arg(arg: &Type) -> Option<Self> {
+        match *arg {
+            Type::Double(d) => Some(d),
+            Type::Float(f) => Some(f as f64),
+            _ => None,
+        }
+    }
+}
+
+impl FromArg for bool {
+    const NAME: &'static str = "bool";
+    fn from_arg(arg: &Type) -> Option<Self> {
+        match *arg {
+            Type::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+}
+
+impl FromArg for char {
+    const NAME: &'static str = "char";
+    fn from_arg(arg: &Type) -> Option<Self> {
+        match *arg {
+            Type::Char(c) => Some(c),
+            _ => None,
+        }
+    }
+}
+
+impl FromArg for String {
+    const NAME: &'static str = "string";
+    fn from_arg(arg: &Type) -> Option<Self> {
+        match *arg {
+            Type::String(ref s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl FromArg for Vec<u8> {
+    const NAME: &'static str = "blob";
+    fn from_arg(arg: &Type) -> Option<Self> {
+        match *arg {
+            Type::Blob(ref b) => Some(b.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl FromArg for Color {
+    const NAME: &'static str = "color";
+    fn from_arg(arg: &Type) -> Option<Self> {
+        match *arg {
+            Type::Color(ref c) => Some(c.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl FromArg for MidiMessage {
+    const NAME: &'static str = "midi";
+    fn from_arg(arg: &Type) -> Option<Self> {
+        match *arg {
+            Type::Midi(ref m) => Some(m.clone()),
+            _ => None,
+        }
+    }
+}
+
+// `Nil` arguments produce `None`.
+impl<T> FromArg for Option<T>
+where
+    T: FromArg,
+{
+    const NAME: &'static str = T::NAME;
+    fn from_arg(arg: &Type) -> Option<Self> {
+        match *arg {
+            Type::Nil => Some(None),
+            ref arg => T::from_arg(arg).map(Some),
+        }
+    }
+}
+
+impl<T> FromArgs for Vec<T>
+where
+    T: FromArg,
+{
+    fn from_args(args: &[Type]) -> Result<Self, ArgsError> {
+        (0..args.len()).map(|ix| arg(args, ix)).collect()
+    }
+}
+
+macro_rules! impl_from_args_for_tuple {
+    ($len:expr => $($T:ident $ix:tt),*) => {
+        impl<$($T),*> FromArgs for ($($T,)*)
+        where
+            $($T: FromArg,)*
+        {
+            #[allow(unused_variables)]
+            fn from_args(args: &[Type]) -> Result<Self, ArgsError> {
+                if args.len() != $len {
+                    let expected = $len;
+                    let found = args.len();
+                    return Err(ArgsError::Count { expected, found });
+                }
+                Ok(($(arg::<$T>(args, $ix)?,)*))
+            }
+        }
+    };
+}
+
+impl_from_args_for_tuple!(0 =>);
+impl_from_args_for_tuple!(1 => A 0);
+impl_from_args_for_tuple!(2 => A 0, B 1);
+impl_from_args_for_tuple!(3 => A 0, B 1, C 2);
+impl_from_args_for_tuple!(4 => A 0, B 1, C 2, D 3);
+impl_from_args_for_tuple!(5 => A 0, B 1, C 2, D 3, E 4);
+impl_from_args_for_tuple!(6 => A 0, B 1, C 2, D 3, E 4, F 5);
+impl_from_args_for_tuple!(7 => A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_from_args_for_tuple!(8 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+impl std::error::Error for ArgsError {}
+
+impl std::fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ArgsError::Count { expected, found } => {
+                write!(f, "expected {} arguments, found {}", expected, found)
+            }
+            ArgsError::Type {
+                index,
+                expected,
+                ref found,
+            } => write!(
+                f,
+                "expected argument {} to be a {}, found {:?}",
+                index, expected, found
+            ),
+        }
+    }
+}
@@ -0,0 +1,464 @@
+//! Items related to decoding OSC packets without allocating.
+
+use super::{Arg, Array, Bundle, Message, Packet};
+use crate::{Color, MidiMessage};
+
+// The string that begins every OSC bundle, including its nul terminator.
+const BUNDLE_TAG: &[u8] = b"#bundle\0";
+
+/// The maximum depth to which arrays may be nested within the arguments of a message.
+///
+/// Each level of nesting is decoded recursively, so deeper arrays are rejected to avoid
+/// overflowing the stack.
+pub const MAX_ARRAY_DEPTH: usize = 64;
+
+/// How strictly packets are checked against the OSC specification while decoding.
+///
+/// Some OSC implementations produce packets that do not quite follow the specification, e.g. by
+/// omitting the type tag string or leaving garbage within padding bytes. `Lenient` decoding
+/// accepts these packets as long as their arguments can still be read.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Reject any packet that does not follow the OSC 1.0 and 1.1 specifications.
+    Strict,
+    /// Accept packets with the following deviations from the specification:
+    ///
+    /// - messages without a type tag string, which are treated as having no arguments,
+    /// - addresses that do not begin with a `/`,
+    /// - non-zero padding bytes and missing padding at the end of the packet,
+    /// - an unterminated string at the end of the packet,
+    /// - packets and bundle elements whose size is not a multiple of four bytes,
+    /// - bytes following the last argument of a message and
+    /// - `char` arguments that are not valid unicode, which become `U+FFFD`.
+    Lenient,
+}
+
+/// An error that occurred while decoding a packet, along with the position at which it occurred.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    /// The offset from the start of the packet in bytes.
+    pub offset: usize,
+    pub kind: ErrorKind,
+}
+
+/// The kinds of errors that might occur while decoding a packet.
+///
+/// Errors marked *strict* only occur when decoding with `Mode::Strict`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The packet ended where the `expected` item should have been.
+    UnexpectedEnd { expected: &'static str },
+    /// A string was not terminated by a nul byte. *Strict*.
+    UnterminatedString,
+    /// A string was not valid UTF-8.
+    InvalidUtf8,
+    /// A padding byte was not zero. *Strict*.
+    NonZeroPadding,
+    /// The size of a packet or bundle element was not a multiple of four bytes. *Strict*.
+    InvalidSize { len: usize },
+    /// The packet begins with neither a `/` nor `#bundle`. *Strict*.
+    InvalidPacket,
+    /// The address of a message does not begin with a `/`. *Strict*.
+    InvalidAddress,
+    /// A message has no type tag string. *Strict*.
+    MissingTypeTags,
+    /// A message's type tag string does not begin with a `,`.
+    InvalidTypeTags,
+    /// The type tag string contains an unknown type tag.
+    UnknownTypeTag { tag: char },
+    /// A `[` within the type tag string is not closed by a `]`.
+    UnclosedArray,
+    /// A `]` within the type tag string does not close a `[`.
+    UnexpectedArrayEnd,
+    /// A `[` within the type tag string opens an array nested deeper than `MAX_ARRAY_DEPTH`.
+    ArrayTooDeep,
+    /// A `char` argument is not a valid unicode scalar value. *Strict*.
+    InvalidChar { value: u32 },
+    /// A message contains bytes following its last argument. *Strict*.
+    TrailingBytes { len: usize },
+}
+
+// A cursor over the bytes of a packet.
+#[derive(Copy, Clone)]
+pub(super) struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    // The offset of `bytes` from the start of the outermost packet, used for error reporting.
+    base: usize,
+    mode: Mode,
+}
+
+/// Decode the given bytes as an OSC packet using `Mode::Strict`.
+///
+/// Unlike `nannou_osc::decode`, this does not allocate. The returned `Packet` borrows its address,
+/// strings and blobs from the given bytes.
+///
+/// The whole packet is checked while decoding, so reading its arguments and the contents of its
+/// bundles cannot fail.
+///
+/// ```
+/// use nannou_osc::borrowed::{self, Arg};
+///
+/// fn main() {
+///     let mut bytes = vec![];
+///     borrowed::encode_message_into("/synth/freq", &[Arg::Float(440.0)], &mut bytes);
+///     match borrowed::decode(&bytes).unwrap() {
+///         borrowed::Packet::Message(msg) => {
+///             assert_eq!(msg.addr(), "/synth/freq");
+///             assert_eq!(msg.args().next(), Some(Arg::Float(440.0)));
+///         }
+///         borrowed::Packet::Bundle(_) => unreachable!(),
+///     }
+/// }
+/// ```
+pub fn decode(bytes: &[u8]) -> Result<Packet<'_>, DecodeError> {
+    decode_with(bytes, Mode::Strict)
+}
+
+/// The same as `decode`, but allows for specifying how strictly the packet is checked.
+pub fn decode_with(bytes: &[u8], mode: Mode) -> Result<Packet<'_>, DecodeError> {
+    packet(bytes, 0, mode)
+}
+
+// Decode a packet located at `base` within the outermost packet.
+fn packet(bytes: &[u8], base: usize, mode: Mode) -> Result<Packet<'_>, DecodeError> {
+    let reader = Reader::new(bytes, base, mode);
+    // The size of every packet should be a multiple of four bytes.
+    let excess = bytes.len() % 4;
+    if mode == Mode::Strict && excess != 0 {
+        return Err(reader.err(ErrorKind::InvalidSize { len: bytes.len() }));
+    }
+    match bytes.first() {
+        None => Err(reader.err(ErrorKind::UnexpectedEnd { expected: "packet" })),
+        Some(b'#') if bytes.starts_with(BUNDLE_TAG) => bundle(reader).map(Packet::Bundle),
+        Some(b'/') => message(reader).map(Packet::Message),
+        Some(_) if mode == Mode::Lenient => message(reader).map(Packet::Message),
+        Some(_) => Err(reader.err(ErrorKind::InvalidPacket)),
+    }
+}
+
+fn message(mut reader: Reader<'_>) -> Result<Message<'_>, DecodeError> {
+    let strict = reader.mode == Mode::Strict;
+    let addr = reader.str("address")?;
+    if strict && !addr.starts_with('/') {
+        return Err(reader.err_at(0, ErrorKind::InvalidAddress));
+    }
+    let type_tags = if reader.is_empty() {
+        if strict {
+            return Err(reader.err(ErrorKind::MissingTypeTags));
+        }
+        ""
+    } else {
+        let pos = reader.pos;
+        let type_tags = reader.str("type tags")?;
+        if !type_tags.starts_with(',') {
+            return Err(reader.err_at(pos, ErrorKind::InvalidTypeTags));
+        }
+        check_type_tags(&type_tags[1..], &reader, pos + 1)?;
+        &type_tags[1..]
+    };
+    let start = reader.pos;
+    let mut tags = type_tags;
+    while !tags.is_empty() {
+        tags = read_arg(tags, &mut reader)?.1;
+    }
+    let args = reader.slice(start);
+    if strict && !reader.is_empty() {
+        let len = reader.remaining().len();
+        return Err(reader.err(ErrorKind::TrailingBytes { len }));
+    }
+    Ok(Message {
+        addr,
+        type_tags,
+        args,
+        mode: reader.mode,
+        bytes: reader.bytes,
+    })
+}
+
+fn bundle(mut reader: Reader<'_>) -> Result<Bundle<'_>, DecodeError> {
+    reader.take(BUNDLE_TAG.len(), "bundle tag")?;
+    let timetag = reader.time_tag()?;
+    let start = reader.pos;
+    while !reader.is_empty() {
+        let len = reader.u32("bundle element size")? as usize;
+        let offset = reader.base + reader.pos;
+        let element = reader.take(len, "bundle element")?;
+        packet(element, offset, reader.mode)?;
+    }
+    Ok(Bundle {
+        timetag,
+        content: reader.slice(start),
+        mode: reader.mode,
+        bytes: reader.bytes,
+    })
+}
+
+// Check that each type tag is known and that arrays are balanced and not nested too deeply.
+//
+// `pos` is the position of the type tags within the reader, used for error reporting.
+fn check_type_tags(type_tags: &str, reader: &Reader<'_>, pos: usize) -> Result<(), DecodeError> {
+    let mut depth = 0;
+    let mut outer_array = 0;
+    for (i, tag) in type_tags.char_indices() {
+        match tag {
+            'i' | 'f' | 's' | 'S' | 'b' | 'h' | 'd' | 't' | 'c' | 'r' | 'm' | 'T' | 'F' | 'N'
+            | 'I' => (),
+            '[' if depth == MAX_ARRAY_DEPTH => {
+                return Err(reader.err_at(pos + i, ErrorKind::ArrayTooDeep));
+            }
+            '[' => {
+                if depth == 0 {
+                    outer_array = i;
+                }
+                depth += 1;
+            }
+            ']' if depth > 0 => depth -= 1,
+            ']' => return Err(reader.err_at(pos + i, ErrorKind::UnexpectedArrayEnd)),
+            tag => return Err(reader.err_at(pos + i, ErrorKind::UnknownTypeTag { tag })),
+        }
+    }
+    if depth > 0 {
+        return Err(reader.err_at(pos + outer_array, ErrorKind::UnclosedArray));
+    }
+    Ok(())
+}
+
+// Read the argument for the first of the given type tags, returning it along with the remaining
+// type tags.
+//
+// The type tags must have been checked by `check_type_tags`.
+pub(super) fn read_arg<'a>(
+    type_tags: &'a str,
+    reader: &mut Reader<'a>,
+) -> Result<(Arg<'a>, &'a str), DecodeError> {
+    let tag = type_tags.chars().next().expect("no type tags");
+    let mut rest = &type_tags[tag.len_utf8()..];
+    let arg = match tag {
+        'i' => Arg::Int(reader.u32("int")? as i32),
+        'f' => Arg::Float(f32::from_bits(reader.u32("float")?)),
+        's' => Arg::String(reader.str("string")?),
+        'S' => Arg::Symbol(reader.str("symbol")?),
+        'b' => Arg::Blob(reader.blob()?),
+        'h' => Arg::Long(reader.u64("long")? as i64),
+        'd' => Arg::Double(f64::from_bits(reader.u64("double")?)),
+        't' => {
+            let (secs, frac) = reader.time_tag()?;
+            Arg::Time(secs, frac)
+        }
+        'c' => {
+            let pos = reader.pos;
+            let value = reader.u32("char")?;
+            match std::char::from_u32(value) {
+                Some(c) => Arg::Char(c),
+                None if reader.mode == Mode::Lenient => Arg::Char(std::char::REPLACEMENT_CHARACTER),
+                None => return Err(reader.err_at(pos, ErrorKind::InvalidChar { value })),
+            }
+        }
+        'r' => {
+            let b = reader.take(4, "color")?;
+            Arg::Color(Color {
+                red: b[0],
+                green: b[1],
+                blue: b[2],
+                alpha: b[3],
+            })
+        }
+        'm' => {
+            let b = reader.take(4, "midi message")?;
+            Arg::Midi(MidiMessage {
+                port: b[0],
+                status: b[1],
+                data1: b[2],
+                data2: b[3],
+            })
+        }
+        'T' => Arg::Bool(true),
+        'F' => Arg::Bool(false),
+        'N' => Arg::Nil,
+        'I' => Arg::Inf,
+        '[' => {
+            let len = array_type_tags_len(rest);
+            let array_tags = &rest[..len];
+            rest = &rest[len + 1..];
+            let start = reader.pos;
+            let mut tags = array_tags;
+            while !tags.is_empty() {
+                tags = read_arg(tags, reader)?.1;
+            }
+            Arg::Array(Array::encoded(array_tags, reader.slice(start), reader.mode))
+        }
+        tag => unreachable!("unchecked type tag `{}`", tag),
+    };
+    Ok((arg, rest))
+}
+
+// The length of the type tags of the array whose `[` precedes the given type tags.
+fn array_type_tags_len(type_tags: &str) -> usize {
+    let mut depth = 0;
+    for (i, tag) in type_tags.char_indices() {
+        match tag {
+            '[' => depth += 1,
+            ']' if depth == 0 => return i,
+            ']' => depth -= 1,
+            _ => (),
+        }
+    }
+    unreachable!("unchecked array type tags")
+}
+
+impl<'a> Reader<'a> {
+    pub(super) fn new(bytes: &'a [u8], base: usize, mode: Mode) -> Self {
+        Reader {
+            bytes,
+            pos: 0,
+            base,
+            mode,
+        }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+
+    fn remaining(&self) -> &'a [u8] {
+        &self.bytes[self.pos..]
+    }
+
+    // The bytes read since the given position.
+    fn slice(&self, start: usize) -> &'a [u8] {
+        &self.bytes[start..self.pos]
+    }
+
+    fn err(&self, kind: ErrorKind) -> DecodeError {
+        self.err_at(self.pos, kind)
+    }
+
+    fn err_at(&self, pos: usize, kind: ErrorKind) -> DecodeError {
+        let offset = self.base + pos;
+        DecodeError { offset, kind }
+    }
+
+    pub(super) fn take(
+        &mut self,
+        len: usize,
+        expected: &'static str,
+    ) -> Result<&'a [u8], DecodeError> {
+        if self.remaining().len() < len {
+            return Err(self.err(ErrorKind::UnexpectedEnd { expected }));
+        }
+        let bytes = &self.remaining()[..len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    pub(super) fn u32(&mut self, expected: &'static str) -> Result<u32, DecodeError> {
+        let b = self.take(4, expected)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn u64(&mut self, expected: &'static str) -> Result<u64, DecodeError> {
+        let hi = self.u32(expected)? as u64;
+        let lo = self.u32(expected)? as u64;
+        Ok(hi << 32 | lo)
+    }
+
+    pub(super) fn time_tag(&mut self) -> Result<(u32, u32), DecodeError> {
+        let secs = self.u32("time tag")?;
+        let frac = self.u32("time tag")?;
+        Ok((secs, frac))
+    }
+
+    // Read a nul-terminated string followed by padding to a multiple of four bytes.
+    fn str(&mut self, expected: &'static str) -> Result<&'a str, DecodeError> {
+        let start = self.pos;
+        let len = match self.remaining().iter().position(|&b| b == 0) {
+            Some(len) => len,
+            None if self.is_empty() => {
+                return Err(self.err(ErrorKind::UnexpectedEnd { expected }));
+            }
+            None if self.mode == Mode::Lenient => self.remaining().len(),
+            None => return Err(self.err(ErrorKind::UnterminatedString)),
+        };
+        let bytes = self.take(len, expected)?;
+        let s = std::str::from_utf8(bytes).map_err(|e| {
+            let pos = start + e.valid_up_to();
+            self.err_at(pos, ErrorKind::InvalidUtf8)
+        })?;
+        // The nul terminator counts towards the padding.
+        self.padding(4 - len % 4)?;
+        Ok(s)
+    }
+
+    // Read a size-prefixed blob followed by padding to a multiple of four bytes.
+    fn blob(&mut self) -> Result<&'a [u8], DecodeError> {
+        let len = self.u32("blob size")? as usize;
+        let bytes = self.take(len, "blob")?;
+        self.padding((4 - len % 4) % 4)?;
+        Ok(bytes)
+    }
+
+    // Skip the given number of padding bytes.
+    //
+    // When decoding leniently, padding may be missing at the end of the packet.
+    fn padding(&mut self, len: usize) -> Result<(), DecodeError> {
+        let len = match self.mode {
+            Mode::Strict => len,
+            Mode::Lenient => len.min(self.remaining().len()),
+        };
+        let start = self.pos;
+        let padding = self.take(len, "padding")?;
+        if self.mode == Mode::Strict {
+            if let Some(i) = padding.iter().position(|&b| b != 0) {
+                return Err(self.err_at(start + i, ErrorKind::NonZeroPadding));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} at byte {}", self.kind, self.offset)
+    }
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ErrorKind::UnexpectedEnd { expected } => {
+                write!(f, "the packet ended before the expected {}", expected)
+            }
+            ErrorKind::UnterminatedString => write!(f, "unterminated string"),
+            ErrorKind::InvalidUtf8 => write!(f, "invalid UTF-8 within string"),
+            ErrorKind::NonZeroPadding => write!(f, "non-zero padding byte"),
+            ErrorKind::InvalidSize { len } => {
+                write!(f, "size of {} bytes is not a multiple of four", len)
+            }
+            ErrorKind::InvalidPacket => {
+                write!(f, "the packet is neither a message nor a bundle")
+            }
+            ErrorKind::InvalidAddress => write!(f, "the address does not begin with a `/`"),
+            ErrorKind::MissingTypeTags => write!(f, "the message has no type tag string"),
+            ErrorKind::InvalidTypeTags => {
+                write!(f, "the type tag string does not begin with a `,`")
+            }
+            ErrorKind::UnknownTypeTag { tag } => write!(f, "unknown type tag `{}`", tag),
+            ErrorKind::UnclosedArray => write!(f, "the `[` is not closed by a `]`"),
+            ErrorKind::UnexpectedArrayEnd => write!(f, "the `]` does not close a `[`"),
+            ErrorKind::ArrayTooDeep => write!(
+                f,
+                "the `[` opens an array nested deeper than {}",
+                MAX_ARRAY_DEPTH
+            ),
+            ErrorKind::InvalidChar { value } => {
+                write!(f, "`0x{:08X}` is not a valid char", value)
+            }
+            ErrorKind::TrailingBytes { len } => {
+                write!(f, "{} unexpected bytes following the last argument", len)
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Items related to encoding OSC packets into a reusable buffer.
+
+use super::Arg;
+
+/// Encode a message with the given address and arguments, appending it to `out`.
+///
+/// Unlike `nannou_osc::encode`, this supports all OSC 1.0 and 1.1 type tags and only allocates
+/// when `out` needs to grow, allowing for a single buffer to be reused between packets.
+///
+/// Strings must not contain nul bytes, as these terminate strings within OSC packets.
+///
+/// ```
+/// use nannou_osc::borrowed::{self, Arg, Array};
+///
+/// fn main() {
+///     let mut bytes = vec![];
+///     let xy = [Arg::Float(0.5), Arg::Float(0.25)];
+///     let args = [Arg::Symbol("cursor"), Arg::Array(Array::new(&xy))];
+///     borrowed::encode_message_into("/touch", &args, &mut bytes);
+///     if let borrowed::Packet::Message(msg) = borrowed::decode(&bytes).unwrap() {
+///         assert_eq!(msg.type_tags(), "S[ff]");
+///     }
+/// }
+/// ```
+pub fn encode_message_into(addr: &str, args: &[Arg<'_>], out: &mut Vec<u8>) {
+    write_str(addr, out);
+    // Write the type tags in place, followed by a nul terminator and padding.
+    let start = out.len();
+    out.push(b',');
+    write_type_tags(args.iter().cloned(), out);
+    out.push(0);
+    write_padding(out.len() - start, out);
+    write_args(args.iter().cloned(), out);
+}
+
+/// Encode a bundle with the given time tag and contents, appending it to `out`.
+///
+/// Each element of `content` must be an encoded packet, e.g. as produced by `encode_message_into`
+/// or `Packet::as_bytes`.
+pub fn encode_bundle_into<'a, I>(timetag: (u32, u32), content: I, out: &mut Vec<u8>)
+where
+    I: IntoIterator<Item = &'a [u8]>,
+{
+    out.extend_from_slice(b"#bundle\0");
+    out.extend_from_slice(&timetag.0.to_be_bytes());
+    out.extend_from_slice(&timetag.1.to_be_bytes());
+    for packet in content {
+        out.extend_from_slice(&(packet.len() as u32).to_be_bytes());
+        out.extend_from_slice(packet);
+    }
+}
+
+fn write_type_tags<'a, I>(args: I, out: &mut Vec<u8>)
+where
+    I: Iterator<Item = Arg<'a>>,
+{
+    for arg in args {
+        let mut tag = [0; 4];
+        out.extend_from_slice(arg.type_tag().encode_utf8(&mut tag).as_bytes());
+        if let Arg::Array(array) = arg {
+            write_type_tags(array.args(), out);
+            out.push(b']');
+        }
+    }
+}
+
+fn write_args<'a, I>(args: I, out: &mut Vec<u8>)
+where
+    I: Iterator<Item = Arg<'a>>,
+{
+    for arg in args {
+        match arg {
+            Arg::Int(i) => out.extend_from_slice(&i.to_be_bytes()),
+            Arg::Float(f) => out.extend_from_slice(&f.to_bits().to_be_bytes()),
+            Arg::String(s) | Arg::Symbol(s) => write_str(s, out),
+            Arg::Blob(b) => {
+                out.extend_from_slice(&(b.len() as u32).to_be_bytes());
+                out.extend_from_slice(b);
+                write_padding(b.len(), out);
+            }
+            Arg::Long(l) => out.extend_from_slice(&l.to_be_bytes()),
+            Arg::Double(d) => out.extend_from_slice(&d.to_bits().to_be_bytes()),
+            Arg::Char(c) => out.extend_from_slice(&(c as u32).to_be_bytes()),
+            Arg::Color(c) => out.extend_from_slice(&[c.red, c.green, c.blue, c.alpha]),
+            Arg::Midi(m) => out.extend_from_slice(&[m.port, m.status, m.data1, m.data2]),
+            Arg::Time(secs, frac) => {
+                out.extend_from_slice(&secs.to_be_bytes());
+                out.extend_from_slice(&frac.to_be_bytes());
+            }
+            Arg::Array(array) => write_args(array.args(), out),
+            Arg::Bool(_) | Arg::Nil | Arg::Inf => (),
+        }
+    }
+}
+
+// Write the string followed by a nul terminator and padding to a multiple of four bytes.
+fn write_str(s: &str, out: &mut Vec<u8>) {
+    out.extend_from_slice(s.as_bytes());
+    out.push(0);
+    write_padding(s.len() + 1, out);
+}
+
+// Write the zeros required to pad an item of the given length to a multiple of four bytes.
+fn write_padding(len: usize, out: &mut Vec<u8>) {
+    let padding = (4 - len % 4) % 4;
+    out.resize(out.len() + padding, 0);
+}
@@ -0,0 +1,440 @@
+//! OSC packets decoded without allocating, borrowing from the bytes in which they were received.
+//!
+//! `nannou_osc::decode` produces a `Packet` that owns its address, arguments and bundle contents,
+//! allocating for each. When receiving many packets per second, e.g. from sensors, this cost can
+//! add up. The types within this module instead refer directly to the bytes of the packet, reading
+//! each argument only when requested.
+//!
+//! This module also supports the full set of OSC 1.0 and 1.1 type tags, including arrays and
+//! symbols which `rosc` does not, and can decode packets leniently for compatibility with
+//! implementations that do not quite follow the specification. See `Mode` for details.
+//!
+//! ```no_run
+//! use nannou_osc as osc;
+//! use nannou_osc::borrowed::{self, Arg};
+//!
+//! fn main() {
+//!     let receiver = osc::receiver(34254).unwrap();
+//!     loop {
+//!         receiver
+//!             .recv_borrowed(borrowed::Mode::Lenient, |packet, _addr| {
+//!                 if let borrowed::Packet::Message(msg) = packet {
+//!                     for arg in msg.args() {
+//!                         if let Arg::Float(f) = arg {
+//!                             println!("{}: {}", msg.addr(), f);
+//!                         }
+//!                     }
+//!                 }
+//!             })
+//!             .unwrap();
+//!     }
+//! }
+//! ```
+
+use self::decode::{read_arg, Reader};
+use crate::{rosc, Color, MidiMessage, Type};
+
+pub use self::decode::{decode, decode_with, DecodeError, ErrorKind, Mode, MAX_ARRAY_DEPTH};
+pub use self::encode::{encode_bundle_into, encode_message_into};
+
+mod decode;
+mod encode;
+
+/// An OSC packet borrowed from the bytes in which it was encoded.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Packet<'a> {
+    Message(Message<'a>),
+    Bundle(Bundle<'a>),
+}
+
+/// An OSC message borrowed from the bytes in which it was encoded.
+#[derive(Copy, Clone)]
+pub struct Message<'a> {
+    addr: &'a str,
+    // The type tags without the leading `,`.
+    type_tags: &'a str,
+    args: &'a [u8],
+    mode: Mode,
+    bytes: &'a [u8],
+}
+
+/// An OSC bundle borrowed from the bytes in which it was encoded.
+#[derive(Copy, Clone)]
+pub struct Bundle<'a> {
+    timetag: (u32, u32),
+    content: &'a [u8],
+    mode: Mode,
+    bytes: &'a [u8],
+}
+
+/// A single argument of an OSC message.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Arg<'a> {
+    /// `i` - a 32-bit integer.
+    Int(i32),
+    /// `f` - a 32-bit float.
+    Float(f32),
+    /// `s` - a string.
+    String(&'a str),
+    /// `b` - a blob of bytes.
+    Blob(&'a [u8]),
+    /// `h` - a 64-bit integer.
+    Long(i64),
+    /// `d` - a 64-bit float.
+    Double(f64),
+    /// `S` - an alternate string type, e.g. for representing symbols.
+    Symbol(&'a str),
+    /// `c` - a character.
+    Char(char),
+    /// `r` - a 32-bit RGBA color.
+    Color(Color),
+    /// `m` - a MIDI message consisting of a port ID, status byte and two data bytes.
+    Midi(MidiMessage),
+    /// `t` - an OSC time tag.
+    Time(u32, u32),
+    /// `T` or `F` - a boolean.
+    Bool(bool),
+    /// `N` - nil.
+    Nil,
+    /// `I` - infinitum, also known as impulse in OSC 1.1.
+    Inf,
+    /// `[` and `]` - an array of arguments.
+    Array(Array<'a>),
+}
+
+/// An array of OSC arguments.
+///
+/// Arrays are either borrowed from the bytes of a decoded message, or from a slice of arguments
+/// via `Array::new` in order to be encoded.
+#[derive(Copy, Clone)]
+pub struct Array<'a> {
+    kind: ArrayKind<'a>,
+}
+
+#[derive(Copy, Clone)]
+enum ArrayKind<'a> {
+    Encoded {
+        type_tags: &'a str,
+        args: &'a [u8],
+        mode: Mode,
+    },
+    Args(&'a [Arg<'a>]),
+}
+
+/// An iterator yielding the arguments of a message or array.
+#[derive(Clone)]
+pub struct Args<'a> {
+    kind: ArgsKind<'a>,
+}
+
+#[derive(Clone)]
+enum ArgsKind<'a> {
+    Encoded {
+        type_tags: &'a str,
+        reader: Reader<'a>,
+    },
+    Args(std::slice::Iter<'a, Arg<'a>>),
+}
+
+/// An iterator yielding the packets within a bundle.
+#[derive(Clone)]
+pub struct Packets<'a> {
+    reader: Reader<'a>,
+    mode: Mode,
+}
+
+impl<'a> Packet<'a> {
+    /// The bytes of the encoded packet.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        match *self {
+            Packet::Message(ref msg) => msg.as_bytes(),
+            Packet::Bundle(ref bundle) => bundle.as_bytes(),
+        }
+    }
+
+    /// Convert the packet to an owned `nannou_osc::Packet`.
+    ///
+    /// Returns `None` if the packet contains an array, which `rosc` does not support.
+    pub fn to_packet(&self) -> Option<crate::Packet> {
+        match *self {
+            Packet::Message(ref msg) => msg.to_message().map(crate::Packet::Message),
+            Packet::Bundle(ref bundle) => bundle.to_bundle().map(crate::Packet::Bundle),
+        }
+    }
+}
+
+impl<'a> Message<'a> {
+    /// The address of the message.
+    pub fn addr(&self) -> &'a str {
+        self.addr
+    }
+
+    /// The type tag of each argument, without the leading `,`.
+    pub fn type_tags(&self) -> &'a str {
+        self.type_tags
+    }
+
+    /// An iterator yielding each of the message's arguments.
+    pub fn args(&self) -> Args<'a> {
+        Args::encoded(self.type_tags, self.args, self.mode)
+    }
+
+    /// The bytes of the encoded message.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Convert the message to an owned `nannou_osc::Message`.
+    ///
+    /// Returns `None` if the message contains an array, which `rosc` does not support.
+    pub fn to_message(&self) -> Option<crate::Message> {
+        let args = self
+            .args()
+            .map(|arg| arg.to_type())
+            .collect::<Option<_>>()?;
+        Some(crate::Message {
+            addr: self.addr.to_string(),
+            args: Some(args),
+        })
+    }
+}
+
+impl<'a> Bundle<'a> {
+    /// The time at which the bundle's contents should be delivered.
+    ///
+    /// See the `router` module for converting between time tags and `SystemTime`s.
+    pub fn timetag(&self) -> (u32, u32) {
+        self.timetag
+    }
+
+    /// An iterator yielding the packets within the bundle.
+    pub fn packets(&self) -> Packets<'a> {
+        Packets {
+            reader: Reader::new(self.content, 0, self.mode),
+            mode: self.mode,
+        }
+    }
+
+    /// The bytes of the encoded bundle.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Convert the bundle to an owned `nannou_osc::Bundle`.
+    ///
+    /// Returns `None` if the bundle contains an array, which `rosc` does not support.
+    pub fn to_bundle(&self) -> Option<crate::Bundle> {
+        let content = self
+            .packets()
+            .map(|packet| packet.to_packet().map(|packet| packet.into()))
+            .collect::<Option<Vec<rosc::OscPacket>>>()?;
+        let (secs, frac) = self.timetag;
+        Some(crate::Bundle {
+            timetag: Type::Time(secs, frac),
+            content,
+        })
+    }
+}
+
+impl<'a> Arg<'a> {
+    /// The type tag of the argument.
+    ///
+    /// Returns `[` for arrays.
+    pub fn type_tag(&self) -> char {
+        match *self {
+            Arg::Int(_) => 'i',
+            Arg::Float(_) => 'f',
+            Arg::String(_) => 's',
+            Arg::Blob(_) => 'b',
+            Arg::Long(_) => 'h',
+            Arg::Double(_) => 'd',
+            Arg::Symbol(_) => 'S',
+            Arg::Char(_) => 'c',
+            Arg::Color(_) => 'r',
+            Arg::Midi(_) => 'm',
+            Arg::Time(..) => 't',
+            Arg::Bool(true) => 'T',
+            Arg::Bool(false) => 'F',
+            Arg::Nil => 'N',
+            Arg::Inf => 'I',
+            Arg::Array(_) => '[',
+        }
+    }
+
+    /// Convert the argument to an owned `nannou_osc::Type`.
+    ///
+    /// Symbols become `Type::String`. Returns `None` for arrays, which `rosc` does not support.
+    pub fn to_type(&self) -> Option<Type> {
+        let ty = match *self {
+            Arg::Int(i) => Type::Int(i),
+            Arg::Float(f) => Type::Float(f),
+            Arg::String(s) | Arg::Symbol(s) => Type::String(s.to_string()),
+            Arg::Blob(b) => Type::Blob(b.to_vec()),
+            Arg::Long(l) => Type::Long(l),
+            Arg::Double(d) => Type::Double(d),
+            Arg::Char(c) => Type::Char(c),
+            Arg::Color(ref c) => Type::Color(c.clone()),
+            Arg::Midi(ref m) => Type::Midi(m.clone()),
+            Arg::Time(secs, frac) => Type::Time(secs, frac),
+            Arg::Bool(b) => Type::Bool(b),
+            Arg::Nil => Type::Nil,
+            Arg::Inf => Type::Inf,
+            Arg::Array(_) => return None,
+        };
+        Some(ty)
+    }
+}
+
+impl<'a> Array<'a> {
+    /// An array of the given arguments.
+    pub fn new(args: &'a [Arg<'a>]) -> Self {
+        let kind = ArrayKind::Args(args);
+        Array { kind }
+    }
+
+    // An array borrowed from the bytes of a decoded message.
+    fn encoded(type_tags: &'a str, args: &'a [u8], mode: Mode) -> Self {
+        let kind = ArrayKind::Encoded {
+            type_tags,
+            args,
+            mode,
+        };
+        Array { kind }
+    }
+
+    /// An iterator yielding each of the array's arguments.
+    pub fn args(&self) -> Args<'a> {
+        match self.kind {
+            ArrayKind::Encoded {
+                type_tags,
+                args,
+                mode,
+            } => Args::encoded(type_tags, args, mode),
+            ArrayKind::Args(args) => Args {
+                kind: ArgsKind::Args(args.iter()),
+            },
+        }
+    }
+}
+
+impl<'a> Args<'a> {
+    fn encoded(type_tags: &'a str, args: &'a [u8], mode: Mode) -> Self {
+        let reader = Reader::new(args, 0, mode);
+        let kind = ArgsKind::Encoded { type_tags, reader };
+        Args { kind }
+    }
+}
+
+impl<'a> From<&'a Type> for Arg<'a> {
+    fn from(ty: &'a Type) -> Self {
+        match *ty {
+            Type::Int(i) => Arg::Int(i),
+            Type::Float(f) => Arg::Float(f),
+            Type::String(ref s) => Arg::String(s),
+            Type::Blob(ref b) => Arg::Blob(b),
+            Type::Time(secs, frac) => Arg::Time(secs, frac),
+            Type::Long(l) => Arg::Long(l),
+            Type::Double(d) => Arg::Double(d),
+            Type::Char(c) => Arg::Char(c),
+            Type::Color(ref c) => Arg::Color(c.clone()),
+            Type::Midi(ref m) => Arg::Midi(m.clone()),
+            Type::Bool(b) => Arg::Bool(b),
+            Type::Nil => Arg::Nil,
+            Type::Inf => Arg::Inf,
+        }
+    }
+}
+
+impl<'a> Iterator for Args<'a> {
+    type Item = Arg<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.kind {
+            ArgsKind::Encoded {
+                ref mut type_tags,
+                ref mut reader,
+            } => {
+                if type_tags.is_empty() {
+                    return None;
+                }
+                let (arg, rest) = read_arg(type_tags, reader).expect("checked while decoding");
+                *type_tags = rest;
+                Some(arg)
+            }
+            ArgsKind::Args(ref mut args) => args.next().cloned(),
+        }
+    }
+}
+
+impl<'a> Iterator for Packets<'a> {
+    type Item = Packet<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.reader.is_empty() {
+            return None;
+        }
+        let len = self
+            .reader
+            .u32("bundle element size")
+            .expect("checked while decoding");
+        let bytes = self
+            .reader
+            .take(len as usize, "bundle element")
+            .expect("checked while decoding");
+        let packet = decode_with(bytes, self.mode).expect("checked while decoding");
+        Some(packet)
+    }
+}
+
+impl<'a> PartialEq for Message<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.addr == other.addr && self.args().eq(other.args())
+    }
+}
+
+impl<'a> PartialEq for Bundle<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.timetag == other.timetag && self.packets().eq(other.packets())
+    }
+}
+
+impl<'a> PartialEq for Array<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.args().eq(other.args())
+    }
+}
+
+impl<'a> std::fmt::Debug for Message<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Message")
+            .field("addr", &self.addr)
+            .field("args", &DebugIter(self.args()))
+            .finish()
+    }
+}
+
+impl<'a> std::fmt::Debug for Bundle<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Bundle")
+            .field("timetag", &self.timetag)
+            .field("content", &DebugIter(self.packets()))
+            .finish()
+    }
+}
+
+impl<'a> std::fmt::Debug for Array<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&DebugIter(self.args()), f)
+    }
+}
+
+// Formats the items of an iterator as a list.
+struct DebugIter<I>(I);
+
+impl<I> std::fmt::Debug for DebugIter<I>
+where
+    I: Clone + Iterator,
+    I::Item: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list().entries(self.0.clone()).finish()
+    }
+}
@@ -0,0 +1,254 @@
+//! Discovering other nannou programs on the local network via multicast.
+//!
+//! Each `Discovery` periodically announces its name and OSC port to a multicast group and
+//! listens for the announcements of its peers. This allows installations spanning multiple
+//! machines to find each other without configuring IP addresses by hand.
+//!
+//! ```no_run
+//! use nannou_osc as osc;
+//!
+//! fn main() {
+//!     let mut discovery = osc::discovery::Discovery::new("projector-left", 34254).unwrap();
+//!     loop {
+//!         for event in discovery.update() {
+//!             println!("{:?}", event);
+//!         }
+//!         for peer in discovery.peers() {
+//!             println!("{} is receiving OSC at {}", peer.name, peer.addr);
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::{args, CommunicationError, Receiver, Sender, Type};
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// The multicast group to which peers are announced by default.
+///
+/// This lies within the "administratively scoped" range reserved for use within an organisation.
+pub const DEFAULT_GROUP: Ipv4Addr = Ipv4Addr::new(239, 255, 78, 78);
+
+/// The port on which peers are announced by default.
+pub const DEFAULT_PORT: u16 = 34250;
+
+/// The default duration between announcements.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The default duration after which a peer that has not been heard from is considered gone.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The OSC address of announcements, with the arguments `(id, name, osc_port)`.
+pub const ANNOUNCE_ADDR: &str = "/nannou/peer/announce";
+
+/// The OSC address of the message sent by peers that are shutting down, with the argument `id`.
+pub const LEAVE_ADDR: &str = "/nannou/peer/leave";
+
+/// Announces this program to, and discovers, other programs on the local network.
+pub struct Discovery {
+    id: i64,
+    name: String,
+    osc_port: u16,
+    group: SocketAddrV4,
+    receiver: Receiver,
+    sender: Sender,
+    interval: Duration,
+    timeout: Duration,
+    last_announcement: Option<Instant>,
+    peers: Vec<Peer>,
+}
+
+/// Another program discovered on the local network.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Peer {
+    /// The name that the peer announced.
+    pub name: String,
+    /// The address at which the peer receives OSC.
+    pub addr: SocketAddr,
+    /// The last time at which an announcement was received from the peer.
+    pub last_seen: Instant,
+    // Distinguishes between peers with the same name and address.
+    id: i64,
+}
+
+/// Changes to the set of discovered peers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// A new peer was discovered.
+    Joined(Peer),
+    /// A peer shut down or has not been heard from within the timeout.
+    Left(Peer),
+}
+
+impl Discovery {
+    /// Announce the given name and the port on which this program receives OSC to the
+    /// `DEFAULT_GROUP` on the `DEFAULT_PORT`.
+    pub fn new(name: &str, osc_port: u16) -> Result<Self, io::Error> {
+        let group = SocketAddrV4::new(DEFAULT_GROUP, DEFAULT_PORT);
+        Self::with_group(name, osc_port, group)
+    }
+
+    /// The same as `new`, but announces to the given multicast group address.
+    ///
+    /// Only peers using the same group address will be discovered.
+    pub fn with_group(name: &str, osc_port: u16, group: SocketAddrV4) -> Result<Self, io::Error> {
+        let receiver = Receiver::builder()
+            .port(group.port())
+            .reuse_address(true)
+            .join_multicast_v4(*group.ip(), Ipv4Addr::UNSPECIFIED)
+            .build()?;
+        // Limit announcements to the local network, but include peers on the same machine.
+        let sender = Sender::builder()
+            .multicast_ttl_v4(1)
+            .multicast_loop_v4(true)
+            .build()?;
+        Ok(Discovery {
+            id: unique_id(),
+            name: name.to_string(),
+            osc_port,
+            group,
+            receiver,
+            sender,
+            interval: DEFAULT_INTERVAL,
+            timeout: DEFAULT_TIMEOUT,
+            last_announcement: None,
+            peers: vec![],
+        })
+    }
+
+    /// Set the duration between announcements.
+    ///
+    /// By default this is `DEFAULT_INTERVAL`.
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// Set the duration after which a peer that has not been heard from is considered gone.
+    ///
+    /// This should be several times the announcement interval of peers. By default this is
+    /// `DEFAULT_TIMEOUT`.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// The currently known peers.
+    pub fn peers(&self) -> &[Peer] {
+        &self.peers
+    }
+
+    /// Announce this program if the interval has elapsed, process the announcements of peers
+    /// and forget peers that have timed out.
+    ///
+    /// This should be called regularly, e.g. within the app's `update` function.
+    pub fn update(&mut self) -> Vec<Event> {
+        let now = Instant::now();
+        let due = self
+            .last_announcement
+            .map(|last| now.duration_since(last) >= self.interval)
+            .unwrap_or(true);
+        if due {
+            let args = vec![
+                Type::Long(self.id),
+                Type::String(self.name.clone()),
+                Type::Int(self.osc_port as i32),
+            ];
+            // Failing to announce is not fatal, so try again next interval.
+            let _ = self.sender.send((ANNOUNCE_ADDR, args), self.group);
+            self.last_announcement = Some(now);
+        }
+
+        let mut events = vec![];
+        loop {
+            // Skip packets that fail to decode rather than stopping at them.
+            let (packet, src) = match self.receiver.try_recv() {
+                Ok(Some(received)) => received,
+                Ok(None) | Err(CommunicationError::Poisoned) => break,
+                Err(_) => continue,
+            };
+            for msg in packet.into_msgs() {
+                let msg_args = msg.args.unwrap_or_default();
+                if msg.addr == ANNOUNCE_ADDR {
+                    if let Ok((id, name, port)) = args::extract::<(i64, String, i32)>(&msg_args) {
+                        let addr = SocketAddr::new(src.ip(), port as u16);
+                        self.announced(id, name, addr, now, &mut events);
+                    }
+                } else if msg.addr == LEAVE_ADDR {
+                    if let Ok((id,)) = args::extract::<(i64,)>(&msg_args) {
+                        self.remove_peers(|peer| peer.id == id, &mut events);
+                    }
+                }
+            }
+        }
+
+        let timeout = self.timeout;
+        self.remove_peers(
+            |peer| now.duration_since(peer.last_seen) > timeout,
+            &mut events,
+        );
+        events
+    }
+
+    // Update or add the peer that sent an announcement.
+    fn announced(
+        &mut self,
+        id: i64,
+        name: String,
+        addr: SocketAddr,
+        now: Instant,
+        events: &mut Vec<Event>,
+    ) {
+        if id == self.id {
+            return;
+        }
+        match self.peers.iter_mut().find(|peer| peer.id == id) {
+            Some(peer) => {
+                peer.name = name;
+                peer.addr = addr;
+                peer.last_seen = now;
+            }
+            None => {
+                let peer = Peer {
+                    name,
+                    addr,
+                    last_seen: now,
+                    id,
+                };
+                events.push(Event::Joined(peer.clone()));
+                self.peers.push(peer);
+            }
+        }
+    }
+
+    // Remove the peers for which the predicate is true.
+    fn remove_peers<F>(&mut self, mut predicate: F, events: &mut Vec<Event>)
+    where
+        F: FnMut(&Peer) -> bool,
+    {
+        let mut ix = 0;
+        while ix < self.peers.len() {
+            if predicate(&self.peers[ix]) {
+                events.push(Event::Left(self.peers.remove(ix)));
+            } else {
+                ix += 1;
+            }
+        }
+    }
+}
+
+impl Drop for Discovery {
+    fn drop(&mut self) {
+        let args = vec![Type::Long(self.id)];
+        let _ = self.sender.send((LEAVE_ADDR, args), self.group);
+    }
+}
+
+// An identifier that is unique to this instance with high probability.
+fn unique_id() -> i64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    let pid = std::process::id() as u64;
+    (nanos ^ pid.rotate_left(32)) as i64
+}
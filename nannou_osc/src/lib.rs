@@ -1,5 +1,13 @@
 //! Tools for working with OSC. [**sender()**](./fn.sender.html) creates an OSC sender,
-//! [**receiver(port)**](./fn.receiver.html) creates an OSC receiver.
+//! [**receiver(port)**](./fn.receiver.html) creates an OSC receiver and
+//! [**Router**](./router/struct.Router.html) dispatches received messages to handlers registered
+//! for OSC address patterns. The [**tcp**](./tcp/index.html) module provides a sender and
+//! receiver for OSC over TCP, while the [**query**](./query/index.html) module provides an
+//! OSCQuery server when the `query` feature is enabled. Multicast and broadcast sockets can be
+//! created via `Receiver::builder` and `Sender::builder`, while the
+//! [**discovery**](./discovery/index.html) module helps to find peers on the local network. The
+//! [**borrowed**](./borrowed/index.html) module decodes packets without allocating and supports
+//! all OSC 1.0 and 1.1 types.
 
 pub use rosc;
 
@@ -7,19 +15,31 @@ pub use rosc;
 //
 // Remove `Osc` prefix as items are already namespaced via a module, e.g. `OscMessage` becomes
 // `nannou_osc::Message`.
+pub use self::args::{FromArg, FromArgs};
+pub use self::pattern::Pattern;
 pub use self::recv::Receiver;
 #[doc(inline)]
 pub use self::rosc::{
     decoder, encoder, OscBundle as Bundle, OscColor as Color, OscError as Error,
     OscMessage as Message, OscMidiMessage as MidiMessage, OscType as Type,
 };
+pub use self::router::Router;
 pub use self::send::Sender;
 
 use std;
 use std::net::{Ipv4Addr, SocketAddr};
 
+pub mod args;
+pub mod borrowed;
+pub mod discovery;
+pub mod pattern;
+#[cfg(feature = "query")]
+pub mod query;
 pub mod recv;
+pub mod router;
 pub mod send;
+mod socket;
+pub mod tcp;
 
 /// Indicates that a `Sender` is not currently connected to a target address, and that the target
 /// address will have to be supplied manually when sending packets.
@@ -145,6 +165,7 @@ pub fn encode(packet: Packet) -> Result<Vec<u8>, Error> {
 pub enum CommunicationError {
     Io(std::io::Error),
     Osc(Error),
+    Decode(borrowed::DecodeError),
     Poisoned,
 }
 
@@ -160,6 +181,12 @@ impl From<Error> for CommunicationError {
     }
 }
 
+impl From<borrowed::DecodeError> for CommunicationError {
+    fn from(err: borrowed::DecodeError) -> Self {
+        CommunicationError::Decode(err)
+    }
+}
+
 impl<T> From<std::sync::PoisonError<T>> for CommunicationError {
     fn from(_: std::sync::PoisonError<T>) -> Self {
         CommunicationError::Poisoned
@@ -173,6 +200,7 @@ impl std::error::Error for CommunicationError {
             // TODO: Error isn't implemented for OscError - should fix this upstream.
             CommunicationError::Osc(ref _err) => None,
             // CommunicationError::Osc(ref err) => Some(err),
+            CommunicationError::Decode(ref err) => Some(err),
             _ => None,
         }
     }
@@ -184,6 +212,9 @@ impl std::fmt::Display for CommunicationError {
             CommunicationError::Io(ref err) => std::fmt::Display::fmt(err, f),
             // TODO: Error isn't implemented for OscError - should fix this upstream.
             CommunicationError::Osc(ref _err) => write!(f, "Failed to decode the OSC packet"),
+            CommunicationError::Decode(ref err) => {
+                write!(f, "Failed to decode the OSC packet: {}", err)
+            }
             CommunicationError::Poisoned => write!(f, "The inner buffer's mutex was poisoned"),
         }
     }
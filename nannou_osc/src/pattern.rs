@@ -0,0 +1,231 @@
+//! Items related to matching OSC addresses against OSC address patterns.
+
+/// A compiled OSC address pattern, e.g. `/synth/*/freq` or `/mixer/{left,right}/gain[1-4]`.
+///
+/// The following special characters are supported within each part of the address, as described
+/// by the OSC 1.0 specification:
+///
+/// - `?` matches any single character.
+/// - `*` matches any sequence of zero or more characters.
+/// - `[abc]` matches any of the characters within the brackets. `[a-z]` matches any character
+///   within the given range. `[!abc]` matches any character *not* within the brackets.
+/// - `{foo,bar}` matches any of the comma-separated strings within the braces.
+///
+/// None of these match the `/` that separates the parts of an address.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern {
+    pattern: String,
+    tokens: Vec<Token>,
+}
+
+/// Errors that might occur while parsing an OSC address pattern.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PatternError {
+    /// Address patterns must begin with a `/`.
+    MissingLeadingSlash,
+    /// A `[` was not closed by a `]`.
+    UnclosedBracket { position: usize },
+    /// A `{` was not closed by a `}`.
+    UnclosedBrace { position: usize },
+    /// The given character may not appear within brackets or braces.
+    UnexpectedChar { position: usize, c: char },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Char(char),
+    AnyChar,
+    AnySequence,
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+    Alternatives(Vec<Vec<char>>),
+}
+
+impl Pattern {
+    /// Parse the given OSC address pattern.
+    ///
+    /// ```
+    /// use nannou_osc::Pattern;
+    ///
+    /// fn main() {
+    ///     let pattern = Pattern::new("/synth/[0-9]/{freq,amp}").unwrap();
+    ///     assert!(pattern.matches("/synth/3/amp"));
+    ///     assert!(!pattern.matches("/synth/10/amp"));
+    /// }
+    /// ```
+    pub fn new(pattern: &str) -> Result<Self, PatternError> {
+        if !pattern.starts_with('/') {
+            return Err(PatternError::MissingLeadingSlash);
+        }
+        let mut tokens = vec![];
+        let mut chars = pattern.char_indices().peekable();
+        while let Some((position, c)) = chars.next() {
+            let token = match c {
+                '?' => Token::AnyChar,
+                '*' => {
+                    // Consecutive `*`s are equivalent to one.
+                    while let Some(&(_, '*')) = chars.peek() {
+                        chars.next();
+                    }
+                    Token::AnySequence
+                }
+                '[' => {
+                    let negated = match chars.peek() {
+                        Some(&(_, '!')) => {
+                            chars.next();
+                            true
+                        }
+                        _ => false,
+                    };
+                    let mut ranges = vec![];
+                    loop {
+                        let start = match chars.next() {
+                            None => return Err(PatternError::UnclosedBracket { position }),
+                            Some((_, ']')) => break,
+                            Some((p, c @ '/')) => {
+                                return Err(PatternError::UnexpectedChar { position: p, c })
+                            }
+                            Some((_, c)) => c,
+                        };
+                        // A `-` at the end of the class is literal.
+                        let is_range = match chars.peek() {
+                            Some(&(_, '-')) => {
+                                let mut ahead = chars.clone();
+                                ahead.next();
+                                !matches!(ahead.peek(), Some(&(_, ']')) | None)
+                            }
+                            _ => false,
+                        };
+                        if is_range {
+                            chars.next();
+                            let (p, end) = chars.next().expect("checked above");
+                            if end == '/' {
+                                return Err(PatternError::UnexpectedChar {
+                                    position: p,
+                                    c: end,
+                                });
+                            }
+                            ranges.push((start.min(end), start.max(end)));
+                        } else {
+                            ranges.push((start, start));
+                        }
+                    }
+                    Token::Class { negated, ranges }
+                }
+                '{' => {
+                    let mut alternatives = vec![vec![]];
+                    loop {
+                        match chars.next() {
+                            None => return Err(PatternError::UnclosedBrace { position }),
+                            Some((_, '}')) => break,
+                            Some((_, ',')) => alternatives.push(vec![]),
+                            Some((p, c @ '/')) | Some((p, c @ '{')) | Some((p, c @ '[')) => {
+                                return Err(PatternError::UnexpectedChar { position: p, c })
+                            }
+                            Some((_, c)) => alternatives.last_mut().unwrap().push(c),
+                        }
+                    }
+                    Token::Alternatives(alternatives)
+                }
+                c => Token::Char(c),
+            };
+            tokens.push(token);
+        }
+        let pattern = pattern.to_string();
+        Ok(Pattern { pattern, tokens })
+    }
+
+    /// The pattern as a string.
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Whether or not the pattern contains any special characters.
+    ///
+    /// Literal patterns only match the address that is equal to the pattern.
+    pub fn is_literal(&self) -> bool {
+        self.tokens.iter().all(|t| matches!(t, Token::Char(_)))
+    }
+
+    /// Whether or not the given OSC address matches the pattern.
+    pub fn matches(&self, addr: &str) -> bool {
+        let addr: Vec<char> = addr.chars().collect();
+        match_tokens(&self.tokens, &addr)
+    }
+}
+
+impl std::str::FromStr for Pattern {
+    type Err = PatternError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Pattern::new(s)
+    }
+}
+
+impl std::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.pattern, f)
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+impl std::fmt::Display for PatternError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            PatternError::MissingLeadingSlash => {
+                write!(f, "OSC address patterns must begin with a `/`")
+            }
+            PatternError::UnclosedBracket { position } => {
+                write!(f, "the `[` at position {} is not closed by a `]`", position)
+            }
+            PatternError::UnclosedBrace { position } => {
+                write!(
+                    f,
+                    "the `{{` at position {} is not closed by a `}}`",
+                    position
+                )
+            }
+            PatternError::UnexpectedChar { position, c } => {
+                write!(f, "unexpected `{}` at position {}", c, position)
+            }
+        }
+    }
+}
+
+// Recursively match the tokens against the address, backtracking on `*`.
+fn match_tokens(tokens: &[Token], addr: &[char]) -> bool {
+    let (token, rest) = match tokens.split_first() {
+        None => return addr.is_empty(),
+        Some(split) => split,
+    };
+    match *token {
+        Token::Char(c) => match addr.split_first() {
+            Some((&a, addr)) if a == c => match_tokens(rest, addr),
+            _ => false,
+        },
+        Token::AnyChar => match addr.split_first() {
+            Some((&a, addr)) if a != '/' => match_tokens(rest, addr),
+            _ => false,
+        },
+        Token::AnySequence => {
+            // Try each length up to the end of the current part of the address.
+            let part_len = addr.iter().position(|&c| c == '/').unwrap_or(addr.len());
+            (0..=part_len).any(|len| match_tokens(rest, &addr[len..]))
+        }
+        Token::Class {
+            negated,
+            ref ranges,
+        } => match addr.split_first() {
+            Some((&a, addr)) if a != '/' => {
+                let in_class = ranges.iter().any(|&(start, end)| start <= a && a <= end);
+                in_class != negated && match_tokens(rest, addr)
+            }
+            _ => false,
+        },
+        Token::Alternatives(ref alternatives) => alternatives
+            .iter()
+            .any(|alt| addr.starts_with(alt) && match_tokens(rest, &addr[alt.len()..])),
+    }
+}
@@ -0,0 +1,398 @@
+//! Serving the OSCQuery namespace over HTTP and WebSocket.
+
+use super::Param;
+use crate::{decode, Packet};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+use tungstenite::handshake::server::{create_response, Request};
+use tungstenite::protocol::Role;
+use tungstenite::{Message as WsMessage, WebSocket};
+
+/// How often the background threads check whether or not the `Server` has been dropped.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The maximum size of an HTTP request head.
+const MAX_REQUEST_LEN: usize = 16 * 1024;
+
+/// The maximum number of headers within an HTTP request.
+const MAX_HEADERS: usize = 64;
+
+// State shared between the `Server` and its background threads.
+pub(crate) struct Shared {
+    pub closed: AtomicBool,
+    pub namespace: Mutex<Namespace>,
+}
+
+// The published parameters along with the connected WebSocket clients.
+pub(crate) struct Namespace {
+    pub name: String,
+    pub osc_port: u16,
+    pub params: BTreeMap<String, Param>,
+    pub clients: Vec<Client>,
+    pub next_client_id: u64,
+}
+
+// A connected WebSocket client.
+pub(crate) struct Client {
+    id: u64,
+    pub listening: HashSet<String>,
+    tx: mpsc::Sender<WsMessage>,
+}
+
+// A parsed HTTP request.
+struct HttpRequest {
+    path: String,
+    query: Option<String>,
+    is_upgrade: bool,
+    request: Request,
+}
+
+impl Client {
+    // Returns `false` if the client has disconnected.
+    pub fn send_binary(&self, bytes: Vec<u8>) -> bool {
+        self.tx.send(WsMessage::Binary(bytes)).is_ok()
+    }
+
+    // Returns `false` if the client has disconnected.
+    pub fn send_text(&self, text: String) -> bool {
+        self.tx.send(WsMessage::Text(text)).is_ok()
+    }
+}
+
+impl Namespace {
+    // The host info, as described by the OSCQuery specification.
+    fn host_info(&self) -> Value {
+        json!({
+            "NAME": self.name,
+            "OSC_PORT": self.osc_port,
+            "OSC_TRANSPORT": "UDP",
+            "EXTENSIONS": {
+                "ACCESS": true,
+                "CLIPMODE": false,
+                "CONTENTS": true,
+                "DESCRIPTION": true,
+                "FULL_PATH": true,
+                "LISTEN": true,
+                "PATH_ADDED": true,
+                "PATH_REMOVED": true,
+                "RANGE": true,
+                "TAGS": false,
+                "TYPE": true,
+                "UNIT": true,
+                "VALUE": true,
+            },
+        })
+    }
+
+    // The JSON for the node at the given path, or `None` if there is no such node.
+    fn node(&self, path: &str) -> Option<Value> {
+        let prefix = if path == "/" {
+            "/".to_string()
+        } else {
+            format!("{}/", path)
+        };
+        let children: BTreeSet<&str> = self
+            .params
+            .range(prefix.clone()..)
+            .take_while(|(p, _)| p.starts_with(&prefix))
+            .filter_map(|(p, _)| p[prefix.len()..].split('/').next())
+            .collect();
+        let param = self.params.get(path);
+        if param.is_none() && children.is_empty() && path != "/" {
+            return None;
+        }
+        let mut node = Map::new();
+        node.insert("FULL_PATH".into(), path.into());
+        match param {
+            Some(param) => param.attributes(&mut node),
+            None => {
+                node.insert("ACCESS".into(), 0.into());
+            }
+        }
+        if !children.is_empty() {
+            let contents = children
+                .into_iter()
+                .filter_map(|name| {
+                    let child = format!("{}{}", prefix, name);
+                    self.node(&child).map(|node| (name.to_string(), node))
+                })
+                .collect();
+            node.insert("CONTENTS".into(), Value::Object(contents));
+        }
+        Some(Value::Object(node))
+    }
+
+    fn client_mut(&mut self, id: u64) -> Option<&mut Client> {
+        self.clients.iter_mut().find(|client| client.id == id)
+    }
+}
+
+// Accept incoming connections, spawning a thread to serve each.
+pub(crate) fn listen(listener: TcpListener, incoming: mpsc::Sender<Packet>, shared: Arc<Shared>) {
+    while !shared.closed.load(atomic::Ordering::Relaxed) {
+        let (stream, addr) = match listener.accept() {
+            Ok(accepted) => accepted,
+            Err(_) => {
+                thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+        };
+        let incoming = incoming.clone();
+        let shared = shared.clone();
+        let _ = thread::Builder::new()
+            .name(format!("nannou_osc query connection {}", addr))
+            .spawn(move || {
+                let _ = serve(stream, incoming, &shared);
+            });
+    }
+}
+
+// Respond to a single HTTP request, or upgrade the connection to a WebSocket.
+fn serve(
+    mut stream: TcpStream,
+    incoming: mpsc::Sender<Packet>,
+    shared: &Shared,
+) -> Result<(), tungstenite::Error> {
+    // Accepted streams may inherit the listener's non-blocking mode on some platforms.
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let (request, rest) = match read_request(&mut stream)? {
+        Some(request) => request,
+        None => return Ok(write_response(&mut stream, "400 Bad Request", None)?),
+    };
+    if request.is_upgrade {
+        return serve_websocket(stream, request, rest, incoming, shared);
+    }
+    let ns = lock(shared);
+    let (status, body) = match (request.query.as_deref(), ns.node(&request.path)) {
+        (Some("HOST_INFO"), _) => ("200 OK", Some(ns.host_info())),
+        (_, None) => ("404 Not Found", None),
+        (None, Some(node)) => ("200 OK", Some(node)),
+        (Some(attr), Some(node)) => match node.get(attr) {
+            Some(value) => ("200 OK", Some(json!({ attr: value }))),
+            None if is_attribute(attr) => ("204 No Content", None),
+            None => ("400 Bad Request", None),
+        },
+    };
+    drop(ns);
+    Ok(write_response(&mut stream, status, body)?)
+}
+
+// Relay value changes to the client and receive commands and values from it.
+fn serve_websocket(
+    mut stream: TcpStream,
+    request: HttpRequest,
+    rest: Vec<u8>,
+    incoming: mpsc::Sender<Packet>,
+    shared: &Shared,
+) -> Result<(), tungstenite::Error> {
+    let response = match create_response(&request.request) {
+        Ok(response) => response,
+        Err(_) => return Ok(write_response(&mut stream, "400 Bad Request", None)?),
+    };
+    let mut head = format!("HTTP/1.1 {}\r\n", response.status());
+    for (name, value) in response.headers() {
+        let value = value.to_str().unwrap_or_default();
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes())?;
+    stream.set_read_timeout(Some(POLL_INTERVAL))?;
+    let mut ws = WebSocket::from_partially_read(stream, rest, Role::Server, None);
+
+    let (tx, rx) = mpsc::channel();
+    let id = {
+        let mut ns = lock(shared);
+        let id = ns.next_client_id;
+        ns.next_client_id += 1;
+        let listening = HashSet::new();
+        ns.clients.push(Client { id, listening, tx });
+        id
+    };
+    let result = relay(&mut ws, id, &rx, incoming, shared);
+    lock(shared).clients.retain(|client| client.id != id);
+    result
+}
+
+fn relay(
+    ws: &mut WebSocket<TcpStream>,
+    id: u64,
+    rx: &mpsc::Receiver<WsMessage>,
+    incoming: mpsc::Sender<Packet>,
+    shared: &Shared,
+) -> Result<(), tungstenite::Error> {
+    while !shared.closed.load(atomic::Ordering::Relaxed) {
+        match ws.read_message() {
+            Ok(WsMessage::Text(text)) => {
+                let command: Value = match serde_json::from_str(&text) {
+                    Ok(command) => command,
+                    Err(_) => continue,
+                };
+                let path = match command["DATA"].as_str() {
+                    Some(path) => path.to_string(),
+                    None => continue,
+                };
+                let mut ns = lock(shared);
+                if let Some(client) = ns.client_mut(id) {
+                    match command["COMMAND"].as_str() {
+                        Some("LISTEN") => {
+                            client.listening.insert(path);
+                        }
+                        Some("IGNORE") => {
+                            client.listening.remove(&path);
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            Ok(WsMessage::Binary(bytes)) => {
+                if let Ok(packet) = decode(&bytes) {
+                    if incoming.send(packet).is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+            Ok(WsMessage::Close(_)) => return Ok(()),
+            Ok(_) => (),
+            Err(tungstenite::Error::Io(ref err)) if is_timeout(err) => (),
+            Err(err) => return Err(err),
+        }
+        for msg in rx.try_iter() {
+            ws.write_message(msg)?;
+        }
+        match ws.write_pending() {
+            Err(tungstenite::Error::Io(ref err)) if is_timeout(err) => (),
+            result => result?,
+        }
+    }
+    Ok(())
+}
+
+// Read and parse the head of an HTTP request, returning any bytes read beyond it.
+fn read_request(stream: &mut TcpStream) -> Result<Option<(HttpRequest, Vec<u8>)>, io::Error> {
+    let mut buffer = vec![];
+    let mut chunk = [0u8; 1024];
+    loop {
+        let len = stream.read(&mut chunk)?;
+        if len == 0 || buffer.len() + len > MAX_REQUEST_LEN {
+            return Ok(None);
+        }
+        buffer.extend_from_slice(&chunk[..len]);
+        let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+        let mut parsed = httparse::Request::new(&mut headers);
+        let head_len = match parsed.parse(&buffer) {
+            Ok(httparse::Status::Complete(head_len)) => head_len,
+            Ok(httparse::Status::Partial) => continue,
+            Err(_) => return Ok(None),
+        };
+        let (method, target) = match (parsed.method, parsed.path) {
+            (Some(method), Some(target)) => (method, target),
+            _ => return Ok(None),
+        };
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(target)
+            .version(tungstenite::http::Version::HTTP_11);
+        let mut is_upgrade = false;
+        for header in parsed.headers.iter() {
+            if header.name.eq_ignore_ascii_case("upgrade") {
+                is_upgrade = header.value.eq_ignore_ascii_case(b"websocket");
+            }
+            builder = builder.header(header.name, header.value);
+        }
+        let request = match builder.body(()) {
+            Ok(request) => request,
+            Err(_) => return Ok(None),
+        };
+        let mut parts = target.splitn(2, '?');
+        let path = percent_decode(parts.next().unwrap_or("/"));
+        let path = match path.trim_end_matches('/') {
+            "" => "/".to_string(),
+            path => path.to_string(),
+        };
+        let query = parts.next().map(|query| query.to_string());
+        let request = HttpRequest {
+            path,
+            query,
+            is_upgrade,
+            request,
+        };
+        let rest = buffer[head_len..].to_vec();
+        return Ok(Some((request, rest)));
+    }
+}
+
+fn write_response(
+    stream: &mut TcpStream,
+    status: &str,
+    body: Option<Value>,
+) -> Result<(), io::Error> {
+    let body = body.map(|body| body.to_string()).unwrap_or_default();
+    let response = format!(
+        "HTTP/1.1 {}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes())?;
+    stream.flush()
+}
+
+// Decode `%XX` escapes, e.g. `%20` for a space.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut ix = 0;
+    while ix < bytes.len() {
+        let escaped = match bytes[ix] {
+            b'%' if ix + 2 < bytes.len() => std::str::from_utf8(&bytes[ix + 1..ix + 3])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                ix += 3;
+            }
+            None => {
+                decoded.push(bytes[ix]);
+                ix += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// Whether or not the given query is one of the attributes described by the specification.
+fn is_attribute(attr: &str) -> bool {
+    matches!(
+        attr,
+        "ACCESS" | "CONTENTS" | "DESCRIPTION" | "FULL_PATH" | "RANGE" | "TYPE" | "UNIT" | "VALUE"
+    )
+}
+
+// The namespace is never left in an invalid state, so recover from poisoning.
+fn lock(shared: &Shared) -> MutexGuard<'_, Namespace> {
+    shared
+        .namespace
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn is_timeout(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
@@ -0,0 +1,325 @@
+//! An OSCQuery server for publishing parameters to control surfaces.
+//!
+//! [OSCQuery](https://github.com/Vidvox/OSCQueryProposal) allows applications like TouchOSC or
+//! Chataigne to discover the OSC addresses of a program along with their types, ranges and units.
+//! The `Server` publishes its namespace of `Param`s as JSON over HTTP, receives new values via
+//! OSC over UDP or WebSocket, and sends changed values to the WebSocket clients that are
+//! listening to them.
+//!
+//! This module requires the `query` feature.
+//!
+//! ```no_run
+//! use nannou_osc as osc;
+//! use nannou_osc::query::{Param, Range, Server};
+//!
+//! struct Model {
+//!     radius: f32,
+//! }
+//!
+//! fn main() {
+//!     let mut server = Server::bind("nannou", 9000, 9001).unwrap();
+//!     let radius = Param::new("/circle/radius", vec![osc::Type::Float(50.0)])
+//!         .range(Range::new(osc::Type::Float(0.0), osc::Type::Float(200.0)))
+//!         .unit("distance.px");
+//!     server
+//!         .add(radius, |model: &mut Model, _addr, (radius,): (f32,)| {
+//!             model.radius = radius;
+//!         })
+//!         .unwrap();
+//!
+//!     let mut model = Model { radius: 50.0 };
+//!     loop {
+//!         for err in server.poll(&mut model) {
+//!             eprintln!("{}", err);
+//!         }
+//!     }
+//! }
+//! ```
+
+use self::http::{Client, Namespace, Shared};
+use crate::args::{ArgsError, FromArgs};
+use crate::router::RouteError;
+use crate::{Message, Packet, Receiver, Type};
+use std::collections::HashMap;
+use std::io;
+use std::net::{SocketAddr, SocketAddrV4, TcpListener, ToSocketAddrs};
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use std::thread;
+
+pub use self::param::{type_tag, Access, Param, Range};
+
+mod http;
+pub mod param;
+
+/// An OSCQuery server publishing a namespace of parameters.
+///
+/// Values received from remote clients are delivered to the handlers of their parameters during
+/// `poll`, which should be called regularly, e.g. within the app's `update` function. Messages
+/// for unknown or read-only addresses are ignored.
+pub struct Server<T = ()> {
+    osc: Receiver,
+    http_addr: SocketAddr,
+    shared: Arc<Shared>,
+    // Packets received via WebSocket connections.
+    incoming: mpsc::Receiver<Packet>,
+    handlers: HashMap<String, Box<HandlerFn<T>>>,
+}
+
+/// Errors that might occur while adding, updating or removing parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParamError {
+    /// Parameter paths must begin with a `/` and may not contain OSC pattern characters.
+    InvalidPath { path: String },
+    /// A parameter already exists at the given path.
+    AlreadyExists { path: String },
+    /// No parameter exists at the given path.
+    NotFound { path: String },
+    /// The new value is not of the same type as the parameter.
+    Type(ArgsError),
+}
+
+// A parameter's handler, wrapped to extract the message's arguments.
+type HandlerFn<T> = dyn FnMut(&mut T, &Message) -> Result<(), ArgsError>;
+
+impl<T> Server<T> {
+    /// Create a server receiving OSC over UDP on the given `osc_port` and serving the namespace
+    /// over HTTP and WebSocket on the given `http_port`.
+    ///
+    /// Both sockets are bound to the `default_ipv4_addr`. The `name` is presented to clients
+    /// within the server's host info.
+    pub fn bind(name: &str, osc_port: u16, http_port: u16) -> Result<Self, io::Error> {
+        let osc_addr = SocketAddrV4::new(crate::default_ipv4_addr(), osc_port);
+        let http_addr = SocketAddrV4::new(crate::default_ipv4_addr(), http_port);
+        Self::bind_to(name, osc_addr, http_addr)
+    }
+
+    /// The same as `bind`, but allows for specifying the addresses of both sockets.
+    pub fn bind_to<A, B>(name: &str, osc_addr: A, http_addr: B) -> Result<Self, io::Error>
+    where
+        A: ToSocketAddrs,
+        B: ToSocketAddrs,
+    {
+        let osc = Receiver::bind_to(osc_addr)?;
+        let listener = TcpListener::bind(http_addr)?;
+        // Allows the listener thread to check whether or not the server has been dropped.
+        listener.set_nonblocking(true)?;
+        let http_addr = listener.local_addr()?;
+        let namespace = Namespace {
+            name: name.to_string(),
+            osc_port: osc.local_addr()?.port(),
+            params: Default::default(),
+            clients: vec![],
+            next_client_id: 0,
+        };
+        let shared = Arc::new(Shared {
+            closed: AtomicBool::new(false),
+            namespace: Mutex::new(namespace),
+        });
+        let (incoming_tx, incoming) = mpsc::channel();
+        let thread_shared = shared.clone();
+        thread::Builder::new()
+            .name(format!("nannou_osc query server {}", http_addr))
+            .spawn(move || http::listen(listener, incoming_tx, thread_shared))?;
+        Ok(Server {
+            osc,
+            http_addr,
+            shared,
+            incoming,
+            handlers: HashMap::new(),
+        })
+    }
+
+    /// The address on which OSC packets are received over UDP.
+    pub fn osc_addr(&self) -> Result<SocketAddr, io::Error> {
+        self.osc.local_addr()
+    }
+
+    /// The address on which the namespace is served over HTTP and WebSocket.
+    pub fn http_addr(&self) -> SocketAddr {
+        self.http_addr
+    }
+
+    /// Publish the given parameter, calling `handler` with values received from remote clients.
+    ///
+    /// The handler is only called for values of the same type as the parameter, and only if
+    /// those values can be extracted into `A`. Otherwise, a `RouteError` is returned from `poll`
+    /// and the parameter's value remains unchanged.
+    pub fn add<A, F>(&mut self, param: Param, mut handler: F) -> Result<&mut Self, ParamError>
+    where
+        A: FromArgs,
+        F: 'static + FnMut(&mut T, &str, A),
+    {
+        let path = param.path().to_string();
+        self.add_value(param)?;
+        let handler = move |state: &mut T, msg: &Message| {
+            let args = msg.args.as_ref().map(|args| &args[..]).unwrap_or(&[]);
+            let args = A::from_args(args)?;
+            handler(state, &msg.addr, args);
+            Ok(())
+        };
+        self.handlers.insert(path, Box::new(handler));
+        Ok(self)
+    }
+
+    /// Publish the given parameter without a handler.
+    ///
+    /// This is useful for read-only parameters whose value is updated via `set`, or for
+    /// parameters whose value is only read via `get`.
+    pub fn add_value(&mut self, param: Param) -> Result<&mut Self, ParamError> {
+        let path = param.path().to_string();
+        if !is_valid_path(&path) {
+            return Err(ParamError::InvalidPath { path });
+        }
+        let mut ns = self.namespace();
+        if ns.params.contains_key(&path) {
+            return Err(ParamError::AlreadyExists { path });
+        }
+        ns.params.insert(path.clone(), param);
+        ns.notify_path_changed("PATH_ADDED", &path);
+        drop(ns);
+        Ok(self)
+    }
+
+    /// Stop publishing the parameter at the given path, returning it.
+    pub fn remove(&mut self, path: &str) -> Result<Param, ParamError> {
+        self.handlers.remove(path);
+        let mut ns = self.namespace();
+        let param = ns.params.remove(path).ok_or_else(|| ParamError::NotFound {
+            path: path.to_string(),
+        })?;
+        ns.notify_path_changed("PATH_REMOVED", path);
+        Ok(param)
+    }
+
+    /// The current value of the parameter at the given path.
+    pub fn get(&self, path: &str) -> Option<Vec<Type>> {
+        self.namespace()
+            .params
+            .get(path)
+            .map(|param| param.value().to_vec())
+    }
+
+    /// Update the value of the parameter at the given path and send it to listening clients.
+    ///
+    /// The parameter's handler is not called.
+    pub fn set(&self, path: &str, value: Vec<Type>) -> Result<(), ParamError> {
+        let mut ns = self.namespace();
+        let param = ns
+            .params
+            .get_mut(path)
+            .ok_or_else(|| ParamError::NotFound {
+                path: path.to_string(),
+            })?;
+        param.check_args(&value).map_err(ParamError::Type)?;
+        param.set_value(value);
+        ns.notify_value_changed(path);
+        Ok(())
+    }
+
+    /// Deliver the values received since the last call to the handlers of their parameters.
+    pub fn poll(&mut self, state: &mut T) -> Vec<RouteError> {
+        let mut msgs = vec![];
+        for (packet, _addr) in self.osc.try_iter() {
+            packet.unfold(&mut msgs);
+        }
+        for packet in self.incoming.try_iter() {
+            packet.unfold(&mut msgs);
+        }
+        let mut errors = vec![];
+        for msg in msgs {
+            if let Err(err) = self.apply(state, &msg) {
+                errors.push(RouteError {
+                    addr: msg.addr.clone(),
+                    pattern: msg.addr,
+                    err,
+                });
+            }
+        }
+        errors
+    }
+
+    // Validate and deliver a value received from a remote client.
+    fn apply(&mut self, state: &mut T, msg: &Message) -> Result<(), ArgsError> {
+        let args = msg.args.clone().unwrap_or_default();
+        match self.namespace().params.get(&msg.addr) {
+            Some(param) if param.is_writable() => param.check_args(&args)?,
+            _ => return Ok(()),
+        }
+        if let Some(handler) = self.handlers.get_mut(&msg.addr) {
+            handler(state, msg)?;
+        }
+        let mut ns = self.namespace();
+        if let Some(param) = ns.params.get_mut(&msg.addr) {
+            param.set_value(args);
+            ns.notify_value_changed(&msg.addr);
+        }
+        Ok(())
+    }
+
+    fn namespace(&self) -> MutexGuard<'_, Namespace> {
+        // The namespace is never left in an invalid state, so recover from poisoning.
+        self.shared
+            .namespace
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<T> Drop for Server<T> {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, atomic::Ordering::Relaxed);
+    }
+}
+
+impl Namespace {
+    // Send the current value of the parameter to all clients listening to it.
+    fn notify_value_changed(&mut self, path: &str) {
+        let param = match self.params.get(path) {
+            Some(param) if param.is_readable() => param,
+            _ => return,
+        };
+        let msg = crate::msg(path, param.value().to_vec());
+        let bytes = match crate::encode(msg.into()) {
+            Ok(bytes) => bytes,
+            Err(_) => return,
+        };
+        self.clients
+            .retain(|client| !client.listening.contains(path) || client.send_binary(bytes.clone()));
+    }
+
+    // Inform all clients that the namespace has changed.
+    fn notify_path_changed(&mut self, command: &str, path: &str) {
+        let text = serde_json::json!({ "COMMAND": command, "DATA": path }).to_string();
+        self.clients
+            .retain(|client: &Client| client.send_text(text.clone()));
+    }
+}
+
+// Whether or not the given path is a valid OSC address for a parameter.
+fn is_valid_path(path: &str) -> bool {
+    path.starts_with('/')
+        && path.len() > 1
+        && !path.ends_with('/')
+        && !path.contains("//")
+        && !path
+            .chars()
+            .any(|c| matches!(c, ' ' | '#' | '*' | ',' | '?' | '[' | ']' | '{' | '}'))
+}
+
+impl std::error::Error for ParamError {}
+
+impl std::fmt::Display for ParamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ParamError::InvalidPath { ref path } => {
+                write!(f, "\"{}\" is not a valid parameter path", path)
+            }
+            ParamError::AlreadyExists { ref path } => {
+                write!(f, "a parameter already exists at \"{}\"", path)
+            }
+            ParamError::NotFound { ref path } => write!(f, "no parameter exists at \"{}\"", path),
+            ParamError::Type(ref err) => std::fmt::Display::fmt(err, f),
+        }
+    }
+}
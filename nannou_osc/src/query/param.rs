@@ -0,0 +1,265 @@
+//! Items related to describing the parameters published by an OSCQuery `Server`.
+
+use crate::args::ArgsError;
+use crate::{Color, Type};
+use serde_json::{json, Map, Value};
+
+/// A parameter published by an OSCQuery `Server`.
+///
+/// The parameter's OSC type is inferred from its initial value, e.g. a value of
+/// `vec![Type::Float(0.5)]` produces a parameter of type `f`. Remote values must have the same
+/// types in order to be accepted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Param {
+    path: String,
+    value: Vec<Type>,
+    access: Access,
+    ranges: Vec<Range>,
+    units: Vec<String>,
+    description: Option<String>,
+}
+
+/// Whether or not a parameter's value may be read and/or written by remote clients.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Access {
+    None = 0,
+    Read = 1,
+    Write = 2,
+    ReadWrite = 3,
+}
+
+/// Describes the valid values of a single argument of a parameter.
+///
+/// Ranges are informational, allowing control surfaces to present a suitable widget. They are
+/// not enforced by the `Server`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Range {
+    pub min: Option<Type>,
+    pub max: Option<Type>,
+    /// The set of valid values, if the argument is restricted to a fixed set of values.
+    pub values: Vec<Type>,
+}
+
+impl Param {
+    /// Create a parameter at the given OSC address with the given initial value.
+    ///
+    /// Parameters are readable and writable by default.
+    pub fn new<P>(path: P, value: Vec<Type>) -> Self
+    where
+        P: Into<String>,
+    {
+        Param {
+            path: path.into(),
+            value,
+            access: Access::ReadWrite,
+            ranges: vec![],
+            units: vec![],
+            description: None,
+        }
+    }
+
+    /// Specify whether the parameter may be read and/or written by remote clients.
+    pub fn access(mut self, access: Access) -> Self {
+        self.access = access;
+        self
+    }
+
+    /// Describe the range of the next argument.
+    ///
+    /// Call once for each argument in order.
+    pub fn range(mut self, range: Range) -> Self {
+        self.ranges.push(range);
+        self
+    }
+
+    /// Describe the unit of the next argument, e.g. `"distance.m"` or `"frequency.Hz"`.
+    ///
+    /// Call once for each argument in order.
+    pub fn unit<U>(mut self, unit: U) -> Self
+    where
+        U: Into<String>,
+    {
+        self.units.push(unit.into());
+        self
+    }
+
+    /// A human-readable description of the parameter.
+    pub fn description<D>(mut self, description: D) -> Self
+    where
+        D: Into<String>,
+    {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// The OSC address of the parameter.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The current value of the parameter.
+    pub fn value(&self) -> &[Type] {
+        &self.value
+    }
+
+    /// The OSC type tags of the parameter's arguments, e.g. `"ff"`.
+    pub fn type_tags(&self) -> String {
+        self.value.iter().map(type_tag).collect()
+    }
+
+    /// Whether or not remote clients may read the parameter's value.
+    pub fn is_readable(&self) -> bool {
+        matches!(self.access, Access::Read | Access::ReadWrite)
+    }
+
+    /// Whether or not remote clients may write the parameter's value.
+    pub fn is_writable(&self) -> bool {
+        matches!(self.access, Access::Write | Access::ReadWrite)
+    }
+
+    // Check that the given arguments are of the same types as the parameter's value.
+    pub(crate) fn check_args(&self, args: &[Type]) -> Result<(), ArgsError> {
+        if args.len() != self.value.len() {
+            let expected = self.value.len();
+            let found = args.len();
+            return Err(ArgsError::Count { expected, found });
+        }
+        for (index, (arg, current)) in args.iter().zip(&self.value).enumerate() {
+            if !same_type(arg, current) {
+                return Err(ArgsError::Type {
+                    index,
+                    expected: type_name(current),
+                    found: arg.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn set_value(&mut self, value: Vec<Type>) {
+        self.value = value;
+    }
+
+    // The attributes of the parameter, as described by the OSCQuery specification.
+    pub(crate) fn attributes(&self, attrs: &mut Map<String, Value>) {
+        attrs.insert("TYPE".into(), self.type_tags().into());
+        attrs.insert("ACCESS".into(), (self.access as u8).into());
+        if self.is_readable() {
+            let value = self.value.iter().map(type_to_json).collect();
+            attrs.insert("VALUE".into(), Value::Array(value));
+        }
+        if !self.ranges.is_empty() {
+            let ranges = self.ranges.iter().map(Range::to_json).collect();
+            attrs.insert("RANGE".into(), Value::Array(ranges));
+        }
+        if !self.units.is_empty() {
+            attrs.insert("UNIT".into(), json!(self.units));
+        }
+        if let Some(ref description) = self.description {
+            attrs.insert("DESCRIPTION".into(), description.clone().into());
+        }
+    }
+}
+
+impl Range {
+    /// A range between the given minimum and maximum values, inclusive.
+    pub fn new(min: Type, max: Type) -> Self {
+        Range {
+            min: Some(min),
+            max: Some(max),
+            values: vec![],
+        }
+    }
+
+    /// A range restricted to the given set of values.
+    pub fn values(values: Vec<Type>) -> Self {
+        Range {
+            min: None,
+            max: None,
+            values,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut range = Map::new();
+        if let Some(ref min) = self.min {
+            range.insert("MIN".into(), type_to_json(min));
+        }
+        if let Some(ref max) = self.max {
+            range.insert("MAX".into(), type_to_json(max));
+        }
+        if !self.values.is_empty() {
+            let values = self.values.iter().map(type_to_json).collect();
+            range.insert("VALS".into(), Value::Array(values));
+        }
+        Value::Object(range)
+    }
+}
+
+/// The OSC type tag of the given argument.
+pub fn type_tag(arg: &Type) -> char {
+    match *arg {
+        Type::Int(_) => 'i',
+        Type::Float(_) => 'f',
+        Type::String(_) => 's',
+        Type::Blob(_) => 'b',
+        Type::Time(..) => 't',
+        Type::Long(_) => 'h',
+        Type::Double(_) => 'd',
+        Type::Char(_) => 'c',
+        Type::Color(_) => 'r',
+        Type::Midi(_) => 'm',
+        Type::Bool(true) => 'T',
+        Type::Bool(false) => 'F',
+        Type::Nil => 'N',
+        Type::Inf => 'I',
+    }
+}
+
+// A name for the type of the given argument, used when describing errors.
+fn type_name(arg: &Type) -> &'static str {
+    match *arg {
+        Type::Int(_) => "int",
+        Type::Float(_) => "float",
+        Type::String(_) => "string",
+        Type::Blob(_) => "blob",
+        Type::Time(..) => "time",
+        Type::Long(_) => "long",
+        Type::Double(_) => "double",
+        Type::Char(_) => "char",
+        Type::Color(_) => "color",
+        Type::Midi(_) => "midi",
+        Type::Bool(_) => "bool",
+        Type::Nil => "nil",
+        Type::Inf => "inf",
+    }
+}
+
+// Whether or not the arguments are of the same type. `true` and `false` are both booleans.
+fn same_type(a: &Type, b: &Type) -> bool {
+    match (a, b) {
+        (Type::Bool(_), Type::Bool(_)) => true,
+        (a, b) => type_tag(a) == type_tag(b),
+    }
+}
+
+// The JSON representation of the given argument.
+fn type_to_json(arg: &Type) -> Value {
+    match *arg {
+        Type::Int(i) => i.into(),
+        Type::Float(f) => f.into(),
+        Type::String(ref s) => s.clone().into(),
+        Type::Long(l) => l.into(),
+        Type::Double(d) => d.into(),
+        Type::Char(c) => c.to_string().into(),
+        Type::Bool(b) => b.into(),
+        Type::Color(Color {
+            red,
+            green,
+            blue,
+            alpha,
+        }) => format!("#{:02x}{:02x}{:02x}{:02x}", red, green, blue, alpha).into(),
+        Type::Midi(ref m) => json!([m.port, m.status, m.data1, m.data2]),
+        Type::Blob(_) | Type::Time(..) | Type::Nil | Type::Inf => Value::Null,
+    }
+}
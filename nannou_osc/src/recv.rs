@@ -1,10 +1,12 @@
 //! Items related to the `osc::Receiver` implementation.
 
-use super::{decode, rosc, CommunicationError, Connected, Packet, Unconnected};
+use super::{borrowed, decode, rosc, CommunicationError, Connected, Packet, Unconnected};
 use std;
-use std::net::{SocketAddr, SocketAddrV4, ToSocketAddrs, UdpSocket};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, ToSocketAddrs, UdpSocket};
 use std::sync::atomic::{self, AtomicBool};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 
 /// The default "maximum transmission unit" size as a number of bytes.
 ///
@@ -14,6 +16,9 @@ pub const DEFAULT_MTU: usize = rosc::decoder::MTU;
 /// initialised.
 pub const DEFAULT_NON_BLOCKING: bool = false;
 
+// How often a `Forwarder`'s thread checks whether or not it should stop while waiting for packets.
+const FORWARD_READ_TIMEOUT: Duration = Duration::from_millis(100);
+
 /// A type used for receiving OSC packets.
 pub struct Receiver<M = Unconnected> {
     buffer: Mutex<Vec<u8>>,
@@ -22,6 +27,43 @@ pub struct Receiver<M = Unconnected> {
     mode: M,
 }
 
+/// A builder for a `Receiver` with control over the options of its UDP socket.
+///
+/// This is useful for receiving packets sent to a multicast group.
+///
+/// ```no_run
+/// use nannou_osc::Receiver;
+/// use std::net::Ipv4Addr;
+///
+/// fn main() {
+///     let group = Ipv4Addr::new(239, 255, 0, 1);
+///     let rx = Receiver::builder()
+///         .port(34254)
+///         .reuse_address(true)
+///         .join_multicast_v4(group, Ipv4Addr::UNSPECIFIED)
+///         .build()
+///         .expect("Couldn't bind socket and join multicast group");
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Builder {
+    addr: SocketAddr,
+    mtu: usize,
+    reuse_address: bool,
+    groups_v4: Vec<(Ipv4Addr, Ipv4Addr)>,
+    groups_v6: Vec<(Ipv6Addr, u32)>,
+}
+
+/// A handle to a thread that receives OSC packets in the background, returned by
+/// `Receiver::forward`.
+///
+/// The thread stops when the `Forwarder` is dropped.
+pub struct Forwarder {
+    local_addr: SocketAddr,
+    closed: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
 /// An iterator that calls `recv` on the inner `Receiver` and yields the results.
 ///
 /// If the `Receiver` is `Connected`, this will yield `Packet`s.
@@ -58,6 +100,44 @@ impl<M> Receiver<M> {
         self.socket.local_addr()
     }
 
+    /// Join the IPv4 multicast group with the given address.
+    ///
+    /// The `interface` is the address of the local interface on which to join the group. If
+    /// `Ipv4Addr::UNSPECIFIED`, an appropriate interface is chosen by the system.
+    pub fn join_multicast_v4(
+        &self,
+        group: Ipv4Addr,
+        interface: Ipv4Addr,
+    ) -> Result<(), std::io::Error> {
+        self.socket.join_multicast_v4(&group, &interface)
+    }
+
+    /// Leave the IPv4 multicast group previously joined on the given interface.
+    pub fn leave_multicast_v4(
+        &self,
+        group: Ipv4Addr,
+        interface: Ipv4Addr,
+    ) -> Result<(), std::io::Error> {
+        self.socket.leave_multicast_v4(&group, &interface)
+    }
+
+    /// Join the IPv6 multicast group with the given address.
+    ///
+    /// The `interface` is the index of the local interface on which to join the group. If `0`,
+    /// an appropriate interface is chosen by the system.
+    pub fn join_multicast_v6(&self, group: Ipv6Addr, interface: u32) -> Result<(), std::io::Error> {
+        self.socket.join_multicast_v6(&group, interface)
+    }
+
+    /// Leave the IPv6 multicast group previously joined on the given interface.
+    pub fn leave_multicast_v6(
+        &self,
+        group: Ipv6Addr,
+        interface: u32,
+    ) -> Result<(), std::io::Error> {
+        self.socket.leave_multicast_v6(&group, interface)
+    }
+
     // Switch the `Receiver`'s inner socket to blocking mode.
     // This is for internal use only - the `recv` methods will call this automatically.
     fn switch_to_blocking(&self) -> Result<(), std::io::Error> {
@@ -80,6 +160,11 @@ impl<M> Receiver<M> {
 }
 
 impl Receiver<Unconnected> {
+    /// Begin building a `Receiver` with control over the options of its UDP socket.
+    pub fn builder() -> Builder {
+        Builder::new()
+    }
+
     /// Create a `Receiver` that listen for OSC packets on the given address.
     ///
     /// ```no_run
@@ -250,6 +335,49 @@ impl Receiver<Unconnected> {
         Ok(Some((packet, addr)))
     }
 
+    /// Waits for the next OSC packet to be received and calls `f` with the packet and its source
+    /// address, returning the result.
+    ///
+    /// Unlike `recv`, the packet is decoded without allocating, borrowing directly from the
+    /// `Receiver`'s inner buffer. See the `borrowed` module for details.
+    ///
+    /// This will return a `CommunicationError` in the same cases as `recv`.
+    pub fn recv_borrowed<F, T>(&self, mode: borrowed::Mode, f: F) -> Result<T, CommunicationError>
+    where
+        F: FnOnce(borrowed::Packet, SocketAddr) -> T,
+    {
+        self.switch_to_blocking()?;
+        let mut buffer = self.buffer.lock()?;
+        let (len, addr) = self.socket.recv_from(&mut buffer)?;
+        let packet = borrowed::decode_with(&buffer[..len], mode)?;
+        Ok(f(packet, addr))
+    }
+
+    /// Checks for a pending OSC packet and, if there is one waiting, calls `f` with the packet
+    /// and its source address, returning the result.
+    ///
+    /// Unlike `try_recv`, the packet is decoded without allocating, borrowing directly from the
+    /// `Receiver`'s inner buffer. See the `borrowed` module for details.
+    ///
+    /// This will return a `CommunicationError` in the same cases as `try_recv`.
+    pub fn try_recv_borrowed<F, T>(
+        &self,
+        mode: borrowed::Mode,
+        f: F,
+    ) -> Result<Option<T>, CommunicationError>
+    where
+        F: FnOnce(borrowed::Packet, SocketAddr) -> T,
+    {
+        self.switch_to_non_blocking()?;
+        let mut buffer = self.buffer.lock()?;
+        let (len, addr) = match self.socket.recv_from(&mut buffer) {
+            Ok(tuple) => tuple,
+            Err(_) => return Ok(None),
+        };
+        let packet = borrowed::decode_with(&buffer[..len], mode)?;
+        Ok(Some(f(packet, addr)))
+    }
+
     /// An iterator yielding OSC `Packet`s along with their source address.
     ///
     /// Each call to `next` will block until the next packet is received or until some error
@@ -265,6 +393,56 @@ impl Receiver<Unconnected> {
     pub fn try_iter(&self) -> TryIter<Unconnected> {
         TryIter { receiver: self }
     }
+
+    /// Move the `Receiver` to a background thread that calls `f` with each packet as soon as it
+    /// is received, along with its source address.
+    ///
+    /// This is useful for handling packets without polling, e.g. by sending each one to a nannou
+    /// app via `app::Proxy::send_event` so that the app wakes up immediately, even when using
+    /// `LoopMode::Wait`. Packets that cannot be decoded are ignored.
+    ///
+    /// The thread stops when the returned `Forwarder` is dropped.
+    ///
+    /// ```no_run
+    /// use nannou_osc::Receiver;
+    /// use std::sync::mpsc;
+    ///
+    /// fn main() {
+    ///     let (tx, rx) = mpsc::channel();
+    ///     let _forwarder = Receiver::bind(34254)
+    ///         .expect("Couldn't bind socket to default address")
+    ///         .forward(move |packet, addr| tx.send((packet, addr)).unwrap())
+    ///         .expect("Couldn't spawn receiver thread");
+    ///     for (packet, addr) in rx {
+    ///         println!("{}: {:?}", addr, packet);
+    ///     }
+    /// }
+    /// ```
+    pub fn forward<F>(self, mut f: F) -> Result<Forwarder, std::io::Error>
+    where
+        F: 'static + Send + FnMut(Packet, SocketAddr),
+    {
+        let local_addr = self.socket.local_addr()?;
+        self.switch_to_blocking()?;
+        self.socket.set_read_timeout(Some(FORWARD_READ_TIMEOUT))?;
+        let closed = Arc::new(AtomicBool::new(false));
+        let thread_closed = closed.clone();
+        let thread = thread::Builder::new()
+            .name(format!("nannou_osc receiver {}", local_addr))
+            .spawn(move || {
+                while !thread_closed.load(atomic::Ordering::Relaxed) {
+                    // Timeouts allow for checking whether or not the thread should stop.
+                    if let Ok((packet, addr)) = self.recv() {
+                        f(packet, addr);
+                    }
+                }
+            })?;
+        Ok(Forwarder {
+            local_addr,
+            closed,
+            thread: Some(thread),
+        })
+    }
 }
 
 impl Receiver<Connected> {
@@ -320,6 +498,49 @@ impl Receiver<Connected> {
         Ok(Some(packet))
     }
 
+    /// Waits for the next OSC packet to be received and calls `f` with the packet, returning the
+    /// result.
+    ///
+    /// Unlike `recv`, the packet is decoded without allocating, borrowing directly from the
+    /// `Receiver`'s inner buffer. See the `borrowed` module for details.
+    ///
+    /// This will return a `CommunicationError` in the same cases as `recv`.
+    pub fn recv_borrowed<F, T>(&self, mode: borrowed::Mode, f: F) -> Result<T, CommunicationError>
+    where
+        F: FnOnce(borrowed::Packet) -> T,
+    {
+        self.switch_to_blocking()?;
+        let mut buffer = self.buffer.lock()?;
+        let len = self.socket.recv(&mut buffer)?;
+        let packet = borrowed::decode_with(&buffer[..len], mode)?;
+        Ok(f(packet))
+    }
+
+    /// Checks for a pending OSC packet and, if there is one waiting, calls `f` with the packet,
+    /// returning the result.
+    ///
+    /// Unlike `try_recv`, the packet is decoded without allocating, borrowing directly from the
+    /// `Receiver`'s inner buffer. See the `borrowed` module for details.
+    ///
+    /// This will return a `CommunicationError` in the same cases as `try_recv`.
+    pub fn try_recv_borrowed<F, T>(
+        &self,
+        mode: borrowed::Mode,
+        f: F,
+    ) -> Result<Option<T>, CommunicationError>
+    where
+        F: FnOnce(borrowed::Packet) -> T,
+    {
+        self.switch_to_non_blocking()?;
+        let mut buffer = self.buffer.lock()?;
+        let len = match self.socket.recv(&mut buffer) {
+            Ok(len) => len,
+            Err(_) => return Ok(None),
+        };
+        let packet = borrowed::decode_with(&buffer[..len], mode)?;
+        Ok(Some(f(packet)))
+    }
+
     /// An iterator yielding OSC `Packet`s.
     ///
     /// Each call to `next` will block until the next packet is received or until some error
@@ -337,6 +558,105 @@ impl Receiver<Connected> {
     }
 }
 
+impl Builder {
+    /// A builder for a `Receiver` bound to `0.0.0.0:0` with the `DEFAULT_MTU`.
+    pub fn new() -> Self {
+        let addr = SocketAddrV4::new(super::default_ipv4_addr(), 0).into();
+        Builder {
+            addr,
+            mtu: DEFAULT_MTU,
+            reuse_address: false,
+            groups_v4: vec![],
+            groups_v6: vec![],
+        }
+    }
+
+    /// The address to which the socket will be bound.
+    pub fn addr(mut self, addr: SocketAddr) -> Self {
+        self.addr = addr;
+        self
+    }
+
+    /// The port to which the socket will be bound, retaining the current IP address.
+    pub fn port(mut self, port: u16) -> Self {
+        self.addr.set_port(port);
+        self
+    }
+
+    /// The maximum size of a received packet in bytes.
+    ///
+    /// By default this is `DEFAULT_MTU`.
+    pub fn mtu(mut self, mtu: usize) -> Self {
+        self.mtu = mtu;
+        self
+    }
+
+    /// Whether or not other sockets may bind to the same address.
+    ///
+    /// This should be enabled when multiple programs on the same machine receive from the same
+    /// multicast group. By default this is `false`.
+    pub fn reuse_address(mut self, reuse_address: bool) -> Self {
+        self.reuse_address = reuse_address;
+        self
+    }
+
+    /// Join the given IPv4 multicast group once the socket is bound.
+    ///
+    /// See `Receiver::join_multicast_v4` for details.
+    pub fn join_multicast_v4(mut self, group: Ipv4Addr, interface: Ipv4Addr) -> Self {
+        self.groups_v4.push((group, interface));
+        self
+    }
+
+    /// Join the given IPv6 multicast group once the socket is bound.
+    ///
+    /// See `Receiver::join_multicast_v6` for details.
+    pub fn join_multicast_v6(mut self, group: Ipv6Addr, interface: u32) -> Self {
+        self.groups_v6.push((group, interface));
+        self
+    }
+
+    /// Bind the socket and join any multicast groups.
+    pub fn build(self) -> Result<Receiver, std::io::Error> {
+        let socket = super::socket::bind_udp(self.addr, self.reuse_address)?;
+        for (group, interface) in &self.groups_v4 {
+            socket.join_multicast_v4(group, interface)?;
+        }
+        for &(ref group, interface) in &self.groups_v6 {
+            socket.join_multicast_v6(group, interface)?;
+        }
+        let receiver = Receiver {
+            buffer: Mutex::new(vec![0; self.mtu]),
+            socket: socket.into_udp_socket(),
+            non_blocking: AtomicBool::new(DEFAULT_NON_BLOCKING),
+            mode: Unconnected,
+        };
+        Ok(receiver)
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Forwarder {
+    /// The socket address on which the background thread is receiving packets.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for Forwarder {
+    fn drop(&mut self) {
+        self.closed.store(true, atomic::Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
 impl<'a> Iterator for Iter<'a, Connected> {
     type Item = Packet;
     fn next(&mut self) -> Option<Self::Item> {
@@ -0,0 +1,332 @@
+//! Items related to dispatching OSC messages to handlers registered for address patterns.
+
+use super::args::{ArgsError, FromArgs};
+use super::pattern::{Pattern, PatternError};
+use super::{Bundle, Message, Packet, Type};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The number of seconds between the NTP epoch (1900) used by OSC time tags and the UNIX epoch.
+pub const NTP_UNIX_OFFSET_SECS: u64 = 2_208_988_800;
+
+/// The special time tag indicating that a bundle should be delivered immediately.
+pub const IMMEDIATELY: (u32, u32) = (0, 1);
+
+/// Dispatches OSC messages to the handlers registered for the address patterns that match their
+/// address.
+///
+/// Each handler receives a mutable reference to some state `T` provided when dispatching, the
+/// address of the message and its arguments extracted into some `FromArgs` type, e.g. a tuple.
+///
+/// Messages within bundles whose time tag lies in the future are scheduled and delivered by a
+/// later call to `poll` once their time has arrived.
+///
+/// ```
+/// use nannou_osc as osc;
+///
+/// #[derive(Default)]
+/// struct Synth {
+///     freqs: [f32; 4],
+/// }
+///
+/// fn main() {
+///     let mut router = osc::Router::new();
+///     router
+///         .route("/synth/[0-3]/freq", |synth: &mut Synth, addr, (hz,): (f32,)| {
+///             let ix = addr.as_bytes()[7] - b'0';
+///             synth.freqs[ix as usize] = hz;
+///         })
+///         .unwrap();
+///
+///     let mut synth = Synth::default();
+///     let packet = osc::msg("/synth/2/freq", vec![osc::Type::Float(440.0)]);
+///     let errors = router.dispatch(&mut synth, packet);
+///     assert!(errors.is_empty());
+///     assert_eq!(synth.freqs[2], 440.0);
+/// }
+/// ```
+pub struct Router<T = ()> {
+    routes: Vec<Route<T>>,
+    unmatched: Option<Box<UnmatchedFn<T>>>,
+    scheduled: BinaryHeap<Reverse<Scheduled>>,
+    next_seq: u64,
+}
+
+/// An error produced by a handler whose pattern matched a message, but whose arguments could not
+/// be extracted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RouteError {
+    /// The address of the message.
+    pub addr: String,
+    /// The pattern of the route whose handler failed.
+    pub pattern: String,
+    /// The reason the message's arguments could not be extracted.
+    pub err: ArgsError,
+}
+
+// A handler registered for an address pattern.
+struct Route<T> {
+    pattern: Pattern,
+    handler: Box<HandlerFn<T>>,
+}
+
+// A route's handler, wrapped to extract the message's arguments.
+type HandlerFn<T> = dyn FnMut(&mut T, &Message) -> Result<(), ArgsError>;
+
+// Called for messages that match none of the routes.
+type UnmatchedFn<T> = dyn FnMut(&mut T, &Message);
+
+// A message awaiting delivery.
+struct Scheduled {
+    time: SystemTime,
+    // Preserves the order of messages scheduled for the same time.
+    seq: u64,
+    msg: Message,
+}
+
+impl<T> Router<T> {
+    /// Create a router with no routes.
+    pub fn new() -> Self {
+        Router {
+            routes: vec![],
+            unmatched: None,
+            scheduled: BinaryHeap::new(),
+            next_seq: 0,
+        }
+    }
+
+    /// Register a handler for messages whose address matches the given pattern.
+    ///
+    /// See `Pattern` for the supported syntax. If a message matches the patterns of multiple
+    /// routes, each of their handlers are called in the order in which they were registered.
+    ///
+    /// The handler is only called if the message's arguments can be extracted into `A`.
+    /// Otherwise, a `RouteError` is returned from the call that delivered the message.
+    pub fn route<A, F>(&mut self, pattern: &str, mut handler: F) -> Result<&mut Self, PatternError>
+    where
+        A: FromArgs,
+        F: 'static + FnMut(&mut T, &str, A),
+    {
+        let pattern = Pattern::new(pattern)?;
+        let handler = move |state: &mut T, msg: &Message| {
+            let args = msg.args.as_ref().map(|args| &args[..]).unwrap_or(&[]);
+            let args = A::from_args(args)?;
+            handler(state, &msg.addr, args);
+            Ok(())
+        };
+        let handler = Box::new(handler);
+        self.routes.push(Route { pattern, handler });
+        Ok(self)
+    }
+
+    /// Register a handler for messages that do not match the pattern of any route.
+    ///
+    /// This replaces any previously registered handler.
+    pub fn unmatched<F>(&mut self, handler: F) -> &mut Self
+    where
+        F: 'static + FnMut(&mut T, &Message),
+    {
+        self.unmatched = Some(Box::new(handler));
+        self
+    }
+
+    /// Deliver the messages within the given packet to the matching handlers.
+    ///
+    /// Messages within bundles whose time tag lies in the future are scheduled for delivery by
+    /// `poll`, while all others are delivered immediately.
+    pub fn dispatch<P>(&mut self, state: &mut T, packet: P) -> Vec<RouteError>
+    where
+        P: Into<Packet>,
+    {
+        self.dispatch_at(state, packet, SystemTime::now())
+    }
+
+    /// The same as `dispatch`, but compares time tags against the given time rather than the
+    /// system clock.
+    pub fn dispatch_at<P>(&mut self, state: &mut T, packet: P, now: SystemTime) -> Vec<RouteError>
+    where
+        P: Into<Packet>,
+    {
+        let mut errors = vec![];
+        let mut due = vec![];
+        self.schedule(packet.into(), None, now, &mut due);
+        for msg in due {
+            self.deliver(state, &msg, &mut errors);
+        }
+        errors
+    }
+
+    /// Deliver any scheduled messages whose time has arrived.
+    ///
+    /// This should be called regularly, e.g. within the app's `update` function.
+    pub fn poll(&mut self, state: &mut T) -> Vec<RouteError> {
+        self.poll_at(state, SystemTime::now())
+    }
+
+    /// The same as `poll`, but delivers messages due by the given time rather than the current
+    /// time of the system clock.
+    pub fn poll_at(&mut self, state: &mut T, now: SystemTime) -> Vec<RouteError> {
+        let mut errors = vec![];
+        while let Some(Reverse(scheduled)) = self.scheduled.peek() {
+            if scheduled.time > now {
+                break;
+            }
+            let Reverse(scheduled) = self.scheduled.pop().expect("peeked above");
+            self.deliver(state, &scheduled.msg, &mut errors);
+        }
+        errors
+    }
+
+    /// The time at which the next scheduled message is due, if any.
+    pub fn next_scheduled(&self) -> Option<SystemTime> {
+        self.scheduled.peek().map(|Reverse(s)| s.time)
+    }
+
+    /// The number of messages awaiting delivery.
+    pub fn num_scheduled(&self) -> usize {
+        self.scheduled.len()
+    }
+
+    /// Discard all messages awaiting delivery.
+    pub fn clear_scheduled(&mut self) {
+        self.scheduled.clear();
+    }
+
+    // Collect the messages that are due into `due` and schedule the rest.
+    //
+    // `time` is the time tag of the enclosing bundle, if any.
+    fn schedule(
+        &mut self,
+        packet: Packet,
+        time: Option<SystemTime>,
+        now: SystemTime,
+        due: &mut Vec<Message>,
+    ) {
+        match packet {
+            Packet::Message(msg) => match time {
+                Some(time) if time > now => {
+                    let seq = self.next_seq;
+                    self.next_seq += 1;
+                    self.scheduled.push(Reverse(Scheduled { time, seq, msg }));
+                }
+                _ => due.push(msg),
+            },
+            Packet::Bundle(bundle) => {
+                // Nested bundles may not be delivered before their enclosing bundle.
+                let time = match (time, bundle_time(&bundle)) {
+                    (Some(outer), Some(inner)) => Some(outer.max(inner)),
+                    (outer, inner) => outer.or(inner),
+                };
+                for packet in bundle.content {
+                    self.schedule(packet.into(), time, now, due);
+                }
+            }
+        }
+    }
+
+    // Call the handlers of all routes matching the message.
+    fn deliver(&mut self, state: &mut T, msg: &Message, errors: &mut Vec<RouteError>) {
+        let mut matched = false;
+        for route in &mut self.routes {
+            if !route.pattern.matches(&msg.addr) {
+                continue;
+            }
+            matched = true;
+            if let Err(err) = (route.handler)(state, msg) {
+                errors.push(RouteError {
+                    addr: msg.addr.clone(),
+                    pattern: route.pattern.as_str().to_string(),
+                    err,
+                });
+            }
+        }
+        if !matched {
+            if let Some(ref mut unmatched) = self.unmatched {
+                unmatched(state, msg);
+            }
+        }
+    }
+}
+
+/// Convert an OSC time tag to a `SystemTime`.
+///
+/// Time tags are the number of seconds since 1900 along with the fractional part of a second in
+/// units of 2^-32 seconds.
+pub fn time_tag_to_system_time((secs, frac): (u32, u32)) -> SystemTime {
+    let frac = Duration::from_nanos((frac as u64 * 1_000_000_000) >> 32);
+    let secs = secs as u64;
+    if secs >= NTP_UNIX_OFFSET_SECS {
+        UNIX_EPOCH + Duration::from_secs(secs - NTP_UNIX_OFFSET_SECS) + frac
+    } else {
+        UNIX_EPOCH - Duration::from_secs(NTP_UNIX_OFFSET_SECS - secs) + frac
+    }
+}
+
+/// Convert a `SystemTime` to an OSC time tag.
+///
+/// Times before 1900 produce the earliest possible time tag, `(0, 0)`.
+pub fn system_time_to_time_tag(time: SystemTime) -> (u32, u32) {
+    let since_ntp_epoch = match time.duration_since(UNIX_EPOCH) {
+        Ok(since_unix) => since_unix + Duration::from_secs(NTP_UNIX_OFFSET_SECS),
+        Err(err) => match Duration::from_secs(NTP_UNIX_OFFSET_SECS).checked_sub(err.duration()) {
+            Some(since_ntp_epoch) => since_ntp_epoch,
+            None => return (0, 0),
+        },
+    };
+    let secs = since_ntp_epoch.as_secs() as u32;
+    let frac = ((since_ntp_epoch.subsec_nanos() as u64) << 32) / 1_000_000_000;
+    (secs, frac as u32)
+}
+
+// The time at which the given bundle should be delivered, or `None` if immediately.
+fn bundle_time(bundle: &Bundle) -> Option<SystemTime> {
+    match bundle.timetag {
+        Type::Time(secs, frac) if (secs, frac) != IMMEDIATELY => {
+            Some(time_tag_to_system_time((secs, frac)))
+        }
+        _ => None,
+    }
+}
+
+impl<T> Default for Router<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialEq for Scheduled {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Scheduled {}
+
+impl PartialOrd for Scheduled {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scheduled {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.time, self.seq).cmp(&(other.time, other.seq))
+    }
+}
+
+impl std::error::Error for RouteError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.err)
+    }
+}
+
+impl std::fmt::Display for RouteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "failed to route \"{}\" via \"{}\": {}",
+            self.addr, self.pattern, self.err
+        )
+    }
+}
@@ -2,7 +2,7 @@
 
 use super::{encode, CommunicationError, Connected, Packet, Unconnected};
 use std;
-use std::net::{SocketAddr, SocketAddrV4, ToSocketAddrs, UdpSocket};
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, ToSocketAddrs, UdpSocket};
 
 /// The default port bound to by the `Sender`.
 ///
@@ -16,6 +16,35 @@ pub struct Sender<M = Unconnected> {
     mode: M,
 }
 
+/// A builder for a `Sender` with control over the options of its UDP socket.
+///
+/// This is useful for sending packets to a multicast group or broadcast address.
+///
+/// ```no_run
+/// use nannou_osc as osc;
+///
+/// fn main() {
+///     let tx = osc::Sender::builder()
+///         .multicast_ttl_v4(4)
+///         .multicast_loop_v4(true)
+///         .build()
+///         .expect("Couldn't bind socket");
+///     let packet = ("/hello", vec![]);
+///     tx.send(packet, "239.255.0.1:34254").expect("Couldn't send packet");
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Builder {
+    addr: SocketAddr,
+    broadcast: bool,
+    multicast_ttl_v4: Option<u32>,
+    multicast_hops_v6: Option<u32>,
+    multicast_loop_v4: Option<bool>,
+    multicast_loop_v6: Option<bool>,
+    multicast_if_v4: Option<Ipv4Addr>,
+    multicast_if_v6: Option<u32>,
+}
+
 /// The default socket address bound to by the `Sender`.
 ///
 /// This address is the `default_ipv4_addr` with the `DEFAULT_PORT`.
@@ -28,9 +57,37 @@ impl<M> Sender<M> {
     pub fn local_addr(&self) -> Result<SocketAddr, std::io::Error> {
         self.socket.local_addr()
     }
+
+    /// Whether or not the `Sender` may send packets to broadcast addresses, e.g.
+    /// `255.255.255.255` or the broadcast address of the local subnet.
+    pub fn set_broadcast(&self, broadcast: bool) -> Result<(), std::io::Error> {
+        self.socket.set_broadcast(broadcast)
+    }
+
+    /// The number of network hops that IPv4 multicast packets may pass through.
+    ///
+    /// By default this is `1`, limiting packets to the local network.
+    pub fn set_multicast_ttl_v4(&self, ttl: u32) -> Result<(), std::io::Error> {
+        self.socket.set_multicast_ttl_v4(ttl)
+    }
+
+    /// Whether or not IPv4 multicast packets are delivered to receivers on the same machine.
+    pub fn set_multicast_loop_v4(&self, multicast_loop: bool) -> Result<(), std::io::Error> {
+        self.socket.set_multicast_loop_v4(multicast_loop)
+    }
+
+    /// Whether or not IPv6 multicast packets are delivered to receivers on the same machine.
+    pub fn set_multicast_loop_v6(&self, multicast_loop: bool) -> Result<(), std::io::Error> {
+        self.socket.set_multicast_loop_v6(multicast_loop)
+    }
 }
 
 impl Sender<Unconnected> {
+    /// Begin building a `Sender` with control over the options of its UDP socket.
+    pub fn builder() -> Builder {
+        Builder::new()
+    }
+
     /// Creates a new `Sender` with the UDP socket bound to the given address. **Note: this is
     /// not the target address**, rather it is the address of the socket used by the `Sender` to
     /// send packets. Use the `bind` constructor instead if you would like the `Sender`'s socket
@@ -143,3 +200,108 @@ impl Sender<Connected> {
         Ok(bytes_written)
     }
 }
+
+impl Builder {
+    /// A builder for a `Sender` bound to the `default_sender_socket_addr_v4`.
+    pub fn new() -> Self {
+        Builder {
+            addr: default_sender_socket_addr_v4().into(),
+            broadcast: false,
+            multicast_ttl_v4: None,
+            multicast_hops_v6: None,
+            multicast_loop_v4: None,
+            multicast_loop_v6: None,
+            multicast_if_v4: None,
+            multicast_if_v6: None,
+        }
+    }
+
+    /// The address to which the socket will be bound.
+    ///
+    /// This must be an IPv6 address in order to send to IPv6 multicast groups.
+    pub fn addr(mut self, addr: SocketAddr) -> Self {
+        self.addr = addr;
+        self
+    }
+
+    /// Whether or not the `Sender` may send packets to broadcast addresses.
+    ///
+    /// By default this is `false`.
+    pub fn broadcast(mut self, broadcast: bool) -> Self {
+        self.broadcast = broadcast;
+        self
+    }
+
+    /// The number of network hops that IPv4 multicast packets may pass through.
+    ///
+    /// By default the system default is used, which is typically `1`.
+    pub fn multicast_ttl_v4(mut self, ttl: u32) -> Self {
+        self.multicast_ttl_v4 = Some(ttl);
+        self
+    }
+
+    /// The number of network hops that IPv6 multicast packets may pass through.
+    pub fn multicast_hops_v6(mut self, hops: u32) -> Self {
+        self.multicast_hops_v6 = Some(hops);
+        self
+    }
+
+    /// Whether or not IPv4 multicast packets are delivered to receivers on the same machine.
+    ///
+    /// By default the system default is used, which is typically `true`.
+    pub fn multicast_loop_v4(mut self, multicast_loop: bool) -> Self {
+        self.multicast_loop_v4 = Some(multicast_loop);
+        self
+    }
+
+    /// Whether or not IPv6 multicast packets are delivered to receivers on the same machine.
+    pub fn multicast_loop_v6(mut self, multicast_loop: bool) -> Self {
+        self.multicast_loop_v6 = Some(multicast_loop);
+        self
+    }
+
+    /// The address of the local interface from which IPv4 multicast packets are sent.
+    pub fn multicast_if_v4(mut self, interface: Ipv4Addr) -> Self {
+        self.multicast_if_v4 = Some(interface);
+        self
+    }
+
+    /// The index of the local interface from which IPv6 multicast packets are sent.
+    pub fn multicast_if_v6(mut self, interface: u32) -> Self {
+        self.multicast_if_v6 = Some(interface);
+        self
+    }
+
+    /// Bind the socket and apply the options.
+    pub fn build(self) -> Result<Sender, std::io::Error> {
+        let socket = super::socket::bind_udp(self.addr, false)?;
+        socket.set_broadcast(self.broadcast)?;
+        if let Some(ttl) = self.multicast_ttl_v4 {
+            socket.set_multicast_ttl_v4(ttl)?;
+        }
+        if let Some(hops) = self.multicast_hops_v6 {
+            socket.set_multicast_hops_v6(hops)?;
+        }
+        if let Some(multicast_loop) = self.multicast_loop_v4 {
+            socket.set_multicast_loop_v4(multicast_loop)?;
+        }
+        if let Some(multicast_loop) = self.multicast_loop_v6 {
+            socket.set_multicast_loop_v6(multicast_loop)?;
+        }
+        if let Some(ref interface) = self.multicast_if_v4 {
+            socket.set_multicast_if_v4(interface)?;
+        }
+        if let Some(interface) = self.multicast_if_v6 {
+            socket.set_multicast_if_v6(interface)?;
+        }
+        let socket = socket.into_udp_socket();
+        let mode = Unconnected;
+        Ok(Sender { socket, mode })
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
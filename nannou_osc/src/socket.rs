@@ -0,0 +1,24 @@
+//! Creation of the UDP sockets used by the `Sender` and `Receiver` builders.
+
+use socket2::{Domain, Protocol, Socket};
+use std::io;
+use std::net::SocketAddr;
+
+// Create a UDP socket bound to the given address.
+//
+// If `reuse_address` is `true`, other sockets may bind to the same address. This is necessary
+// for multiple programs on the same machine to receive from the same multicast group.
+pub(crate) fn bind_udp(addr: SocketAddr, reuse_address: bool) -> Result<Socket, io::Error> {
+    let domain = match addr {
+        SocketAddr::V4(_) => Domain::ipv4(),
+        SocketAddr::V6(_) => Domain::ipv6(),
+    };
+    let socket = Socket::new(domain, socket2::Type::dgram(), Some(Protocol::udp()))?;
+    if reuse_address {
+        socket.set_reuse_address(true)?;
+        #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+        socket.set_reuse_port(true)?;
+    }
+    socket.bind(&addr.into())?;
+    Ok(socket)
+}
@@ -0,0 +1,198 @@
+//! Items related to framing OSC packets within a TCP byte stream.
+
+/// The default maximum size of a single frame as a number of bytes.
+///
+/// Frames larger than this are considered invalid in order to avoid buffering unbounded amounts of
+/// data from misbehaving peers.
+pub const DEFAULT_MAX_FRAME_LEN: usize = 16 * 1024 * 1024;
+
+// The special bytes used by SLIP as described by RFC 1055.
+const END: u8 = 0xC0;
+const ESC: u8 = 0xDB;
+const ESC_END: u8 = 0xDC;
+const ESC_ESC: u8 = 0xDD;
+
+/// The method used to delimit OSC packets within a stream.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Framing {
+    /// Each packet is encoded using SLIP (RFC 1055) with an `END` byte at both the beginning and
+    /// end of the packet, as recommended by the OSC 1.1 specification.
+    Slip,
+    /// Each packet is preceded by its size as a big-endian `int32`, as described by the OSC 1.0
+    /// specification.
+    LengthPrefixed,
+}
+
+/// Splits a stream of bytes into the frames that it contains.
+///
+/// Bytes may be pushed in chunks of any size as they arrive, after which each complete frame can
+/// be retrieved via `next_frame`.
+#[derive(Clone, Debug)]
+pub struct Decoder {
+    framing: Framing,
+    max_frame_len: usize,
+    buffer: Vec<u8>,
+    // The number of bytes at the beginning of the buffer known not to contain a SLIP `END`.
+    scanned: usize,
+}
+
+/// Errors that might occur while decoding a stream of frames.
+///
+/// Once an error has occurred the rest of the stream cannot be trusted, so the connection should
+/// be closed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FrameError {
+    /// A SLIP `ESC` byte was followed by a byte other than `ESC_END` or `ESC_ESC`.
+    InvalidEscape { byte: u8 },
+    /// The frame exceeded the maximum frame size.
+    TooLarge { len: usize, max: usize },
+}
+
+impl Framing {
+    /// Frame the given encoded packet, appending the result to `out`.
+    pub fn encode_into(&self, bytes: &[u8], out: &mut Vec<u8>) {
+        match *self {
+            Framing::Slip => {
+                out.reserve(bytes.len() + 2);
+                out.push(END);
+                for &byte in bytes {
+                    match byte {
+                        END => out.extend_from_slice(&[ESC, ESC_END]),
+                        ESC => out.extend_from_slice(&[ESC, ESC_ESC]),
+                        byte => out.push(byte),
+                    }
+                }
+                out.push(END);
+            }
+            Framing::LengthPrefixed => {
+                out.reserve(bytes.len() + 4);
+                out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+                out.extend_from_slice(bytes);
+            }
+        }
+    }
+
+    /// Frame the given encoded packet.
+    pub fn encode(&self, bytes: &[u8]) -> Vec<u8> {
+        let mut out = vec![];
+        self.encode_into(bytes, &mut out);
+        out
+    }
+}
+
+impl Decoder {
+    /// Create a decoder for the given framing with the `DEFAULT_MAX_FRAME_LEN`.
+    pub fn new(framing: Framing) -> Self {
+        Self::with_max_frame_len(framing, DEFAULT_MAX_FRAME_LEN)
+    }
+
+    /// Create a decoder that rejects frames larger than the given number of bytes.
+    pub fn with_max_frame_len(framing: Framing, max_frame_len: usize) -> Self {
+        Decoder {
+            framing,
+            max_frame_len,
+            buffer: vec![],
+            scanned: 0,
+        }
+    }
+
+    /// The framing expected by the decoder.
+    pub fn framing(&self) -> Framing {
+        self.framing
+    }
+
+    /// Append the given bytes received from the stream.
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Produce the next complete frame, or `None` if more bytes are required.
+    ///
+    /// Empty frames are skipped.
+    pub fn next_frame(&mut self) -> Result<Option<Vec<u8>>, FrameError> {
+        match self.framing {
+            Framing::Slip => self.next_slip_frame(),
+            Framing::LengthPrefixed => self.next_length_prefixed_frame(),
+        }
+    }
+
+    fn next_slip_frame(&mut self) -> Result<Option<Vec<u8>>, FrameError> {
+        loop {
+            let end = match self.buffer[self.scanned..].iter().position(|&b| b == END) {
+                Some(ix) => self.scanned + ix,
+                None => {
+                    self.scanned = self.buffer.len();
+                    return self.check_len(self.buffer.len()).map(|_| None);
+                }
+            };
+            let raw: Vec<u8> = self.buffer.drain(..=end).collect();
+            self.scanned = 0;
+            let raw = &raw[..raw.len() - 1];
+            if raw.is_empty() {
+                continue;
+            }
+            self.check_len(raw.len())?;
+            let mut frame = Vec::with_capacity(raw.len());
+            let mut bytes = raw.iter();
+            while let Some(&byte) = bytes.next() {
+                let byte = match byte {
+                    ESC => match bytes.next() {
+                        Some(&ESC_END) => END,
+                        Some(&ESC_ESC) => ESC,
+                        Some(&byte) => return Err(FrameError::InvalidEscape { byte }),
+                        None => return Err(FrameError::InvalidEscape { byte: END }),
+                    },
+                    byte => byte,
+                };
+                frame.push(byte);
+            }
+            return Ok(Some(frame));
+        }
+    }
+
+    fn next_length_prefixed_frame(&mut self) -> Result<Option<Vec<u8>>, FrameError> {
+        loop {
+            if self.buffer.len() < 4 {
+                return Ok(None);
+            }
+            let mut len = [0u8; 4];
+            len.copy_from_slice(&self.buffer[..4]);
+            let len = u32::from_be_bytes(len) as usize;
+            self.check_len(len)?;
+            if self.buffer.len() < 4 + len {
+                return Ok(None);
+            }
+            let frame: Vec<u8> = self.buffer.drain(..4 + len).skip(4).collect();
+            if !frame.is_empty() {
+                return Ok(Some(frame));
+            }
+        }
+    }
+
+    fn check_len(&mut self, len: usize) -> Result<(), FrameError> {
+        if len > self.max_frame_len {
+            self.buffer.clear();
+            self.scanned = 0;
+            let max = self.max_frame_len;
+            return Err(FrameError::TooLarge { len, max });
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+impl std::fmt::Display for FrameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            FrameError::InvalidEscape { byte } => {
+                write!(f, "invalid SLIP escape sequence `0xDB 0x{:02X}`", byte)
+            }
+            FrameError::TooLarge { len, max } => write!(
+                f,
+                "frame of {} bytes exceeds the maximum of {} bytes",
+                len, max
+            ),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+//! Sending and receiving OSC packets over TCP.
+//!
+//! Unlike UDP, TCP provides a stream of bytes rather than individual datagrams, so each packet is
+//! delimited using one of the `Framing` methods. OSC 1.1 recommends `Framing::Slip`, which is
+//! used by default, while some older applications expect `Framing::LengthPrefixed`.
+//!
+//! ```no_run
+//! use nannou_osc as osc;
+//!
+//! fn main() {
+//!     let receiver = osc::tcp::Receiver::bind(9000).unwrap();
+//!     let sender = osc::tcp::Sender::connect("127.0.0.1:9000").unwrap();
+//!     sender.send(("/hello", vec![osc::Type::Int(1)])).unwrap();
+//!     for (packet, addr) in receiver.iter() {
+//!         println!("{}: {:?}", addr, packet);
+//!     }
+//! }
+//! ```
+
+pub use self::framing::{Decoder, FrameError, Framing, DEFAULT_MAX_FRAME_LEN};
+pub use self::recv::Receiver;
+pub use self::send::Sender;
+
+pub mod framing;
+pub mod recv;
+pub mod send;
@@ -0,0 +1,293 @@
+//! Items related to the `osc::tcp::Receiver` implementation.
+
+use super::{Decoder, Framing};
+use crate::{decode, CommunicationError, Packet};
+use std::io::{self, Read};
+use std::net::{Shutdown, SocketAddr, SocketAddrV4, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// How often the background threads check whether or not the `Receiver` has been dropped.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The size of the buffer used for each read from a connection.
+const READ_BUFFER_LEN: usize = 4096;
+
+/// A type used for receiving OSC packets over TCP.
+///
+/// The `Receiver` listens for incoming connections, any number of which may be open at once.
+/// Packets are read from each connection on a background thread and queued until they are
+/// retrieved via `recv`, `try_recv` or their iterators. Connections are closed when the
+/// `Receiver` is dropped.
+pub struct Receiver {
+    local_addr: SocketAddr,
+    framing: Framing,
+    rx: Mutex<mpsc::Receiver<Received>>,
+    shared: Arc<Shared>,
+}
+
+// The result of reading a packet from one of the connections.
+type Received = Result<(Packet, SocketAddr), CommunicationError>;
+
+// State shared with the background threads.
+struct Shared {
+    closed: AtomicBool,
+    peers: Mutex<Vec<SocketAddr>>,
+}
+
+/// An iterator that calls `recv` on the inner `Receiver` and yields the results.
+///
+/// Each call to `next` will block until the next packet is received or until some error
+/// occurs.
+pub struct Iter<'a> {
+    receiver: &'a Receiver,
+}
+
+/// An iterator that calls `try_recv` on the inner `Receiver` and yields the results.
+///
+/// Each call to `next` will only return `Some` while there are pending messages and will
+/// return `None` otherwise.
+pub struct TryIter<'a> {
+    receiver: &'a Receiver,
+}
+
+impl Receiver {
+    /// Create a `Receiver` that listens for connections on the given port using `Framing::Slip`.
+    ///
+    /// The listener is bound to the `default_ipv4_addr` with the given port.
+    pub fn bind(port: u16) -> Result<Self, io::Error> {
+        Self::bind_with_framing(port, Framing::Slip)
+    }
+
+    /// The same as `bind`, but allows for specifying the method used to delimit packets.
+    pub fn bind_with_framing(port: u16, framing: Framing) -> Result<Self, io::Error> {
+        let addr = SocketAddrV4::new(crate::default_ipv4_addr(), port);
+        Self::bind_to_with_framing(addr, framing)
+    }
+
+    /// Create a `Receiver` that listens for connections on the given address using
+    /// `Framing::Slip`.
+    pub fn bind_to<A>(addr: A) -> Result<Self, io::Error>
+    where
+        A: ToSocketAddrs,
+    {
+        Self::bind_to_with_framing(addr, Framing::Slip)
+    }
+
+    /// The same as `bind_to`, but allows for specifying the method used to delimit packets.
+    pub fn bind_to_with_framing<A>(addr: A, framing: Framing) -> Result<Self, io::Error>
+    where
+        A: ToSocketAddrs,
+    {
+        let listener = TcpListener::bind(addr)?;
+        // Allows the listener thread to check whether or not the receiver has been dropped.
+        listener.set_nonblocking(true)?;
+        let local_addr = listener.local_addr()?;
+        let shared = Arc::new(Shared {
+            closed: AtomicBool::new(false),
+            peers: Mutex::new(vec![]),
+        });
+        let (tx, rx) = mpsc::channel();
+        let thread_shared = shared.clone();
+        thread::Builder::new()
+            .name(format!("nannou_osc tcp listener {}", local_addr))
+            .spawn(move || listen(listener, framing, tx, thread_shared))?;
+        let rx = Mutex::new(rx);
+        Ok(Receiver {
+            local_addr,
+            framing,
+            rx,
+            shared,
+        })
+    }
+
+    /// The address on which the `Receiver` is listening for connections.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The method used to delimit packets.
+    pub fn framing(&self) -> Framing {
+        self.framing
+    }
+
+    /// The addresses of the currently connected peers.
+    pub fn peers(&self) -> Vec<SocketAddr> {
+        self.shared
+            .peers
+            .lock()
+            .map(|peers| peers.clone())
+            .unwrap_or_default()
+    }
+
+    /// Waits for the next packet from any of the connected peers.
+    ///
+    /// On success, returns the packet alongside the address of the peer that sent it.
+    ///
+    /// This will return a `CommunicationError` if a received packet could not be decoded, or if
+    /// a connection was closed due to invalid framing.
+    pub fn recv(&self) -> Result<(Packet, SocketAddr), CommunicationError> {
+        let rx = self.rx.lock()?;
+        match rx.recv() {
+            Ok(received) => received,
+            Err(_) => Err(listener_closed().into()),
+        }
+    }
+
+    /// Checks for a pending packet from any of the connected peers without blocking.
+    ///
+    /// Returns `Ok(None)` if there are no pending packets.
+    pub fn try_recv(&self) -> Result<Option<(Packet, SocketAddr)>, CommunicationError> {
+        let rx = self.rx.lock()?;
+        match rx.try_recv() {
+            Ok(received) => received.map(Some),
+            Err(mpsc::TryRecvError::Empty) => Ok(None),
+            Err(mpsc::TryRecvError::Disconnected) => Err(listener_closed().into()),
+        }
+    }
+
+    /// An iterator yielding OSC `Packet`s along with their sender's address.
+    ///
+    /// Each call to `next` will block until the next packet is received or until some error
+    /// occurs.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { receiver: self }
+    }
+
+    /// An iterator yielding all pending OSC `Packet`s along with their sender's address.
+    ///
+    /// Each call to `next` will only return `Some` while there are pending packets and will
+    /// return `None` otherwise.
+    pub fn try_iter(&self) -> TryIter<'_> {
+        TryIter { receiver: self }
+    }
+}
+
+impl Drop for Receiver {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, atomic::Ordering::Relaxed);
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (Packet, SocketAddr);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.recv().ok()
+    }
+}
+
+impl<'a> Iterator for TryIter<'a> {
+    type Item = (Packet, SocketAddr);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.try_recv().ok().and_then(|p| p)
+    }
+}
+
+// Accept incoming connections, spawning a thread to read from each.
+fn listen(
+    listener: TcpListener,
+    framing: Framing,
+    tx: mpsc::Sender<Received>,
+    shared: Arc<Shared>,
+) {
+    while !shared.closed.load(atomic::Ordering::Relaxed) {
+        let (stream, addr) = match listener.accept() {
+            Ok(accepted) => accepted,
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+            Err(err) => {
+                if tx.send(Err(err.into())).is_err() {
+                    return;
+                }
+                thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+        };
+        if let Ok(mut peers) = shared.peers.lock() {
+            peers.push(addr);
+        }
+        let thread_tx = tx.clone();
+        let thread_shared = shared.clone();
+        let spawned = thread::Builder::new()
+            .name(format!("nannou_osc tcp connection {}", addr))
+            .spawn(move || {
+                read_connection(stream, addr, framing, &thread_tx, &thread_shared);
+                remove_peer(&thread_shared, addr);
+            });
+        if let Err(err) = spawned {
+            remove_peer(&shared, addr);
+            let _ = tx.send(Err(err.into()));
+        }
+    }
+}
+
+// Read and decode packets from the connection until it is closed.
+fn read_connection(
+    mut stream: TcpStream,
+    addr: SocketAddr,
+    framing: Framing,
+    tx: &mpsc::Sender<Received>,
+    shared: &Shared,
+) {
+    // Accepted streams may inherit the listener's non-blocking mode on some platforms.
+    let setup = stream
+        .set_nonblocking(false)
+        .and_then(|_| stream.set_read_timeout(Some(POLL_INTERVAL)));
+    if let Err(err) = setup {
+        let _ = tx.send(Err(err.into()));
+        return;
+    }
+    let mut decoder = Decoder::new(framing);
+    let mut buffer = [0u8; READ_BUFFER_LEN];
+    while !shared.closed.load(atomic::Ordering::Relaxed) {
+        let len = match stream.read(&mut buffer) {
+            Ok(0) => return,
+            Ok(len) => len,
+            Err(ref err) if is_timeout(err) => continue,
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(_) => return,
+        };
+        decoder.push(&buffer[..len]);
+        loop {
+            let received = match decoder.next_frame() {
+                Ok(None) => break,
+                Ok(Some(frame)) => decode(&frame)
+                    .map(|packet| (packet, addr))
+                    .map_err(CommunicationError::from),
+                Err(err) => {
+                    let err = io::Error::new(io::ErrorKind::InvalidData, err);
+                    let _ = tx.send(Err(err.into()));
+                    let _ = stream.shutdown(Shutdown::Both);
+                    return;
+                }
+            };
+            if tx.send(received).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn remove_peer(shared: &Shared, addr: SocketAddr) {
+    if let Ok(mut peers) = shared.peers.lock() {
+        peers.retain(|&peer| peer != addr);
+    }
+}
+
+fn is_timeout(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+fn listener_closed() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::BrokenPipe,
+        "the OSC receiver's listener has stopped",
+    )
+}
@@ -0,0 +1,204 @@
+//! Items related to the `osc::tcp::Sender` implementation.
+
+use super::Framing;
+use crate::{encode, CommunicationError, Packet};
+use std::io::{self, Write};
+use std::net::{Shutdown, SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The default duration to wait between attempts to re-establish a lost connection.
+pub const DEFAULT_RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The maximum duration to wait for a single connection attempt.
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A type used for sending OSC packets over a TCP connection.
+///
+/// If the connection is lost or could not be established, the `Sender` attempts to reconnect
+/// during the next call to `send` once the reconnect interval has elapsed. Packets sent while
+/// disconnected are dropped and an error is returned.
+pub struct Sender {
+    remote_addr: SocketAddr,
+    framing: Framing,
+    conn: Mutex<Connection>,
+}
+
+// The state of the connection to the remote address.
+struct Connection {
+    stream: Option<TcpStream>,
+    // The earliest time at which the next connection attempt may be made.
+    next_attempt: Instant,
+    reconnect_interval: Duration,
+    // Reused between calls to `send` to avoid re-allocating.
+    buffer: Vec<u8>,
+}
+
+impl Sender {
+    /// Create a `Sender` that delivers packets to the given address using `Framing::Slip`.
+    ///
+    /// A connection is attempted immediately, however failing to connect is not an error. The
+    /// `Sender` will keep attempting to connect as packets are sent, so it is fine to start the
+    /// sender before the receiver. Use `is_connected` to check the state of the connection.
+    ///
+    /// Returns an error if the address could not be resolved.
+    pub fn connect<A>(addr: A) -> Result<Self, io::Error>
+    where
+        A: ToSocketAddrs,
+    {
+        Self::connect_with_framing(addr, Framing::Slip)
+    }
+
+    /// The same as `connect`, but allows for specifying the method used to delimit packets.
+    pub fn connect_with_framing<A>(addr: A, framing: Framing) -> Result<Self, io::Error>
+    where
+        A: ToSocketAddrs,
+    {
+        let mut addrs = addr.to_socket_addrs()?;
+        let remote_addr = addrs.next().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "could not resolve any address")
+        })?;
+        let mut conn = Connection {
+            stream: None,
+            next_attempt: Instant::now(),
+            reconnect_interval: DEFAULT_RECONNECT_INTERVAL,
+            buffer: vec![],
+        };
+        // Failing to connect is fine, as we'll try again on the next `send`.
+        let _ = conn.try_connect(remote_addr);
+        Ok(Sender {
+            remote_addr,
+            framing,
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// The address to which the `Sender` delivers packets.
+    pub fn remote_addr(&self) -> SocketAddr {
+        self.remote_addr
+    }
+
+    /// The method used to delimit packets.
+    pub fn framing(&self) -> Framing {
+        self.framing
+    }
+
+    /// The local address of the current connection.
+    ///
+    /// Returns an error if the `Sender` is not currently connected.
+    pub fn local_addr(&self) -> Result<SocketAddr, CommunicationError> {
+        let conn = self.conn.lock()?;
+        match conn.stream {
+            Some(ref stream) => Ok(stream.local_addr()?),
+            None => Err(not_connected().into()),
+        }
+    }
+
+    /// Whether or not the `Sender` is currently connected.
+    ///
+    /// A connection that was closed by the remote peer is only detected when the next packet
+    /// fails to send.
+    pub fn is_connected(&self) -> bool {
+        self.conn
+            .lock()
+            .map(|conn| conn.stream.is_some())
+            .unwrap_or(false)
+    }
+
+    /// Set the duration to wait between attempts to re-establish a lost connection.
+    ///
+    /// By default this is `DEFAULT_RECONNECT_INTERVAL`.
+    pub fn set_reconnect_interval(&self, interval: Duration) -> Result<(), CommunicationError> {
+        let mut conn = self.conn.lock()?;
+        conn.next_attempt = conn.next_attempt.min(Instant::now() + interval);
+        conn.reconnect_interval = interval;
+        Ok(())
+    }
+
+    /// Close the current connection, if any, and attempt to connect again immediately.
+    pub fn reconnect(&self) -> Result<(), CommunicationError> {
+        let mut conn = self.conn.lock()?;
+        conn.disconnect();
+        conn.try_connect(self.remote_addr)?;
+        Ok(())
+    }
+
+    /// Sends the given packet to the remote address.
+    ///
+    /// The given `packet` can be of any type that can be converted directly into a `Packet`. This
+    /// includes `Message`, `Bundle` and `(String, Vec<Type>)` (which will be interpreted as a
+    /// `Message`).
+    ///
+    /// On success, returns the number of bytes written, including those used for framing.
+    ///
+    /// This will return a `CommunicationError` if:
+    ///
+    /// - The given packet fails to be encoded to bytes.
+    /// - The `Sender` is not connected and either the reconnect interval has not yet elapsed or
+    ///   the attempt to reconnect fails.
+    /// - Writing to the connection fails, in which case the connection is closed.
+    pub fn send<P>(&self, packet: P) -> Result<usize, CommunicationError>
+    where
+        P: Into<Packet>,
+    {
+        let bytes = encode(packet.into())?;
+        let mut conn = self.conn.lock()?;
+        if conn.stream.is_none() {
+            if Instant::now() < conn.next_attempt {
+                return Err(not_connected().into());
+            }
+            conn.try_connect(self.remote_addr)?;
+        }
+        let Connection {
+            ref mut stream,
+            ref mut buffer,
+            ..
+        } = *conn;
+        buffer.clear();
+        self.framing.encode_into(&bytes, buffer);
+        let result = stream.as_mut().expect("connected above").write_all(buffer);
+        match result {
+            Ok(()) => Ok(buffer.len()),
+            Err(err) => {
+                conn.disconnect();
+                Err(err.into())
+            }
+        }
+    }
+}
+
+impl Connection {
+    // Attempt to connect, scheduling the next attempt on failure.
+    fn try_connect(&mut self, addr: SocketAddr) -> Result<(), io::Error> {
+        let result = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).and_then(|stream| {
+            // OSC is typically used for real-time control, so avoid delaying small packets.
+            stream.set_nodelay(true)?;
+            Ok(stream)
+        });
+        match result {
+            Ok(stream) => {
+                self.stream = Some(stream);
+                Ok(())
+            }
+            Err(err) => {
+                self.next_attempt = Instant::now() + self.reconnect_interval;
+                Err(err)
+            }
+        }
+    }
+
+    // Close the connection. The next attempt to reconnect may be made immediately.
+    fn disconnect(&mut self) {
+        if let Some(stream) = self.stream.take() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+        self.next_attempt = Instant::now();
+    }
+}
+
+fn not_connected() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotConnected,
+        "the OSC sender is not connected",
+    )
+}
@@ -0,0 +1,226 @@
+use nannou_osc as osc;
+use nannou_osc::borrowed::{self, Arg, Array, DecodeError, ErrorKind, Mode, Packet};
+use nannou_osc::{Color, MidiMessage, Receiver, Sender, Type};
+
+fn encode(addr: &str, args: &[Arg]) -> Vec<u8> {
+    let mut bytes = vec![];
+    borrowed::encode_message_into(addr, args, &mut bytes);
+    bytes
+}
+
+fn message(packet: Packet) -> borrowed::Message {
+    match packet {
+        Packet::Message(msg) => msg,
+        Packet::Bundle(_) => panic!("expected a message"),
+    }
+}
+
+fn error(offset: usize, kind: ErrorKind) -> DecodeError {
+    DecodeError { offset, kind }
+}
+
+#[test]
+fn round_trip_all_types() {
+    let xy = [Arg::Float(0.5), Arg::Array(Array::new(&[Arg::Nil]))];
+    let args = [
+        Arg::Int(-1),
+        Arg::Float(1.5),
+        Arg::String("abc"),
+        Arg::Blob(&[1, 2, 3, 4, 5]),
+        Arg::Long(1 << 40),
+        Arg::Double(0.25),
+        Arg::Symbol("sym"),
+        Arg::Char('é'),
+        Arg::Color(Color {
+            red: 1,
+            green: 2,
+            blue: 3,
+            alpha: 4,
+        }),
+        Arg::Midi(MidiMessage {
+            port: 0,
+            status: 0x90,
+            data1: 60,
+            data2: 127,
+        }),
+        Arg::Time(1, 2),
+        Arg::Bool(true),
+        Arg::Bool(false),
+        Arg::Nil,
+        Arg::Inf,
+        Arg::Array(Array::new(&xy)),
+        Arg::String(""),
+    ];
+    let bytes = encode("/all", &args);
+    assert_eq!(bytes.len() % 4, 0);
+
+    let msg = message(borrowed::decode(&bytes).unwrap());
+    assert_eq!(msg.addr(), "/all");
+    assert_eq!(msg.type_tags(), "ifsbhdScrmtTFNI[f[N]]s");
+    assert!(msg.args().eq(args.iter().cloned()));
+    assert_eq!(msg.as_bytes(), &bytes[..]);
+    // Arrays are equal whether borrowed from bytes or a slice of arguments.
+    assert_eq!(msg.args().nth(15), Some(Arg::Array(Array::new(&xy))));
+}
+
+#[test]
+fn matches_rosc() {
+    let args = vec![
+        Type::Int(3),
+        Type::String("kick".into()),
+        Type::Blob(vec![9; 6]),
+        Type::Double(2.0),
+        Type::Time(7, 8),
+        Type::Bool(true),
+        Type::Nil,
+    ];
+    let packet: osc::Packet = osc::msg("/drum", args.clone()).into();
+    let rosc_bytes = osc::encode(packet.clone()).unwrap();
+    let borrowed_args: Vec<Arg> = args.iter().map(Arg::from).collect();
+    assert_eq!(encode("/drum", &borrowed_args), rosc_bytes);
+
+    let decoded = borrowed::decode(&rosc_bytes).unwrap();
+    assert_eq!(decoded.to_packet(), Some(packet));
+}
+
+#[test]
+fn bundles() {
+    let a = encode("/a", &[Arg::Int(1)]);
+    let b = encode("/b", &[Arg::Array(Array::new(&[Arg::Int(2)]))]);
+    let mut inner = vec![];
+    borrowed::encode_bundle_into((0, 1), vec![&b[..]], &mut inner);
+    let mut bytes = vec![];
+    borrowed::encode_bundle_into((5, 6), vec![&a[..], &inner[..]], &mut bytes);
+
+    let bundle = match borrowed::decode(&bytes).unwrap() {
+        Packet::Bundle(bundle) => bundle,
+        Packet::Message(_) => panic!("expected a bundle"),
+    };
+    assert_eq!(bundle.timetag(), (5, 6));
+    let packets: Vec<_> = bundle.packets().collect();
+    assert_eq!(packets.len(), 2);
+    assert_eq!(message(packets[0]).addr(), "/a");
+    assert_eq!(packets[1].as_bytes(), &inner[..]);
+    // `rosc` does not support arrays.
+    assert_eq!(bundle.to_bundle(), None);
+}
+
+#[test]
+fn strict_errors() {
+    let decode = |bytes: &[u8]| borrowed::decode(bytes).unwrap_err();
+    assert_eq!(
+        decode(b""),
+        error(0, ErrorKind::UnexpectedEnd { expected: "packet" })
+    );
+    assert_eq!(decode(b"/a\0"), error(0, ErrorKind::InvalidSize { len: 3 }));
+    assert_eq!(
+        decode(b"a\0\0\0,\0\0\0"),
+        error(0, ErrorKind::InvalidPacket)
+    );
+    assert_eq!(decode(b"/a\0\0"), error(4, ErrorKind::MissingTypeTags));
+    assert_eq!(
+        decode(b"/a\0\0i\0\0\0"),
+        error(4, ErrorKind::InvalidTypeTags)
+    );
+    assert_eq!(
+        decode(b"/a\0\x01,\0\0\0"),
+        error(3, ErrorKind::NonZeroPadding)
+    );
+    assert_eq!(
+        decode(b"/a\0\0,ix\0"),
+        error(6, ErrorKind::UnknownTypeTag { tag: 'x' })
+    );
+    assert_eq!(decode(b"/a\0\0,i[\0"), error(6, ErrorKind::UnclosedArray));
+    assert_eq!(
+        decode(b"/a\0\0,]\0\0"),
+        error(5, ErrorKind::UnexpectedArrayEnd)
+    );
+    assert_eq!(
+        decode(b"/a\0\0,ii\0\0\0\0\x01"),
+        error(12, ErrorKind::UnexpectedEnd { expected: "int" })
+    );
+    assert_eq!(
+        decode(b"/a\0\0,c\0\0\0\x11\0\0"),
+        error(8, ErrorKind::InvalidChar { value: 0x0011_0000 })
+    );
+    assert_eq!(
+        decode(b"/a\0\0,\0\0\0\0\0\0\0"),
+        error(8, ErrorKind::TrailingBytes { len: 4 })
+    );
+    assert_eq!(
+        decode(b"/a\0\0,s\0\0\xFF\0\0\0"),
+        error(8, ErrorKind::InvalidUtf8)
+    );
+
+    // Errors within bundles are reported relative to the start of the outer packet.
+    let mut bytes = vec![];
+    borrowed::encode_bundle_into((0, 1), vec![&b"/a\0\0"[..]], &mut bytes);
+    assert_eq!(decode(&bytes), error(24, ErrorKind::MissingTypeTags));
+}
+
+#[test]
+fn lenient_decoding() {
+    let decode = |bytes| borrowed::decode_with(bytes, Mode::Lenient).map(message);
+
+    // No type tags.
+    let msg = decode(b"/a\0\0").unwrap();
+    assert_eq!(msg.args().count(), 0);
+    // Missing padding, non-zero padding and an unterminated string.
+    let msg = decode(b"a\0\x01\x02,ss\0x\0\0\0yz").unwrap();
+    assert_eq!(msg.addr(), "a");
+    assert!(msg.args().eq(vec![Arg::String("x"), Arg::String("yz")]));
+    // Trailing bytes and invalid chars.
+    let msg = decode(b"/a\0\0,c\0\0\0\x11\0\0\0\0\0\0").unwrap();
+    assert_eq!(
+        msg.args().next(),
+        Some(Arg::Char(std::char::REPLACEMENT_CHARACTER))
+    );
+    // Some errors are unrecoverable.
+    assert_eq!(
+        decode(b"/a\0\0,i\0\0"),
+        Err(error(8, ErrorKind::UnexpectedEnd { expected: "int" }))
+    );
+}
+
+// A message whose type tags are the given number of nested, empty arrays.
+fn nested_arrays(depth: usize) -> Vec<u8> {
+    let mut bytes = b"/a\0\0,".to_vec();
+    bytes.extend(vec![b'['; depth]);
+    bytes.extend(vec![b']'; depth]);
+    bytes.push(0);
+    bytes.resize((bytes.len() + 3) & !3, 0);
+    bytes
+}
+
+#[test]
+fn deeply_nested_arrays_are_rejected() {
+    for &mode in &[Mode::Strict, Mode::Lenient] {
+        // The number of arguments of the decoded message.
+        let decode = |depth| {
+            let bytes = nested_arrays(depth);
+            borrowed::decode_with(&bytes, mode).map(|packet| message(packet).args().count())
+        };
+        assert_eq!(decode(borrowed::MAX_ARRAY_DEPTH), Ok(1));
+        let offset = 5 + borrowed::MAX_ARRAY_DEPTH;
+        for &depth in &[borrowed::MAX_ARRAY_DEPTH + 1, 100_000] {
+            assert_eq!(decode(depth), Err(error(offset, ErrorKind::ArrayTooDeep)));
+        }
+    }
+}
+
+#[test]
+fn receive_borrowed() {
+    let receiver = Receiver::bind_to("127.0.0.1:0").unwrap();
+    let addr = receiver.local_addr().unwrap();
+    let sender = Sender::bind_to("127.0.0.1:0").unwrap();
+    sender.send(("/x", vec![Type::Float(2.0)]), addr).unwrap();
+
+    let arg = receiver
+        .recv_borrowed(Mode::Strict, |packet, _addr| {
+            message(packet).args().next().unwrap().to_type()
+        })
+        .unwrap();
+    assert_eq!(arg, Some(Type::Float(2.0)));
+    let none = receiver.try_recv_borrowed(Mode::Strict, |_, _| ()).unwrap();
+    assert_eq!(none, None);
+}
@@ -0,0 +1,39 @@
+use nannou_osc as osc;
+use nannou_osc::{Receiver, Sender, Type};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+#[test]
+fn forward_received_packets() {
+    let (tx, rx) = mpsc::channel();
+    let forwarder = Receiver::bind_to("127.0.0.1:0")
+        .unwrap()
+        .forward(move |packet, _addr| tx.send(packet).unwrap())
+        .unwrap();
+
+    let sender = Sender::bind_to("127.0.0.1:0").unwrap();
+    let addr = forwarder.local_addr();
+    sender.send(("/a", vec![Type::Int(1)]), addr).unwrap();
+    sender.send(("/b", vec![Type::Int(2)]), addr).unwrap();
+
+    let timeout = Duration::from_secs(5);
+    let a = rx.recv_timeout(timeout).unwrap();
+    let b = rx.recv_timeout(timeout).unwrap();
+    assert_eq!(a, osc::msg("/a", vec![Type::Int(1)]).into());
+    assert_eq!(b, osc::msg("/b", vec![Type::Int(2)]).into());
+}
+
+#[test]
+fn forwarder_stops_on_drop() {
+    let (tx, rx) = mpsc::channel::<osc::Packet>();
+    let forwarder = Receiver::bind_to("127.0.0.1:0")
+        .unwrap()
+        .forward(move |packet, _addr| tx.send(packet).unwrap())
+        .unwrap();
+
+    let start = Instant::now();
+    drop(forwarder);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    // The thread owned the only `Sender`, so the channel closes once the thread has stopped.
+    assert!(rx.recv().is_err());
+}
@@ -0,0 +1,140 @@
+use nannou_osc as osc;
+use nannou_osc::discovery::{Discovery, Event};
+use nannou_osc::{Receiver, Sender};
+use std::net::{Ipv4Addr, SocketAddrV4, UdpSocket};
+use std::time::{Duration, Instant};
+
+// Multicast may be unavailable, e.g. on machines without a network interface.
+fn multicast_available(group: SocketAddrV4) -> bool {
+    let sender = Sender::builder().multicast_loop_v4(true).build().unwrap();
+    sender.send(("/probe", vec![]), group).is_ok()
+}
+
+#[test]
+fn receivers_share_address() {
+    let a = Receiver::builder()
+        .addr("127.0.0.1:0".parse().unwrap())
+        .reuse_address(true)
+        .build()
+        .unwrap();
+    let b = Receiver::builder()
+        .addr(a.local_addr().unwrap())
+        .reuse_address(true)
+        .build()
+        .unwrap();
+    assert_eq!(a.local_addr().unwrap(), b.local_addr().unwrap());
+}
+
+#[test]
+fn sender_builder_options() {
+    let sender = Sender::builder()
+        .broadcast(true)
+        .multicast_ttl_v4(4)
+        .multicast_loop_v4(false)
+        .build()
+        .unwrap();
+    sender.set_broadcast(false).unwrap();
+    sender.set_multicast_ttl_v4(1).unwrap();
+}
+
+#[test]
+fn multicast_send_and_receive() {
+    let group = SocketAddrV4::new(Ipv4Addr::new(239, 255, 78, 79), 34261);
+    if !multicast_available(group) {
+        return;
+    }
+    let receiver = Receiver::builder()
+        .port(group.port())
+        .reuse_address(true)
+        .join_multicast_v4(*group.ip(), Ipv4Addr::UNSPECIFIED)
+        .build()
+        .unwrap();
+    let sender = Sender::builder().multicast_loop_v4(true).build().unwrap();
+    let msg = osc::msg("/group", vec![osc::Type::Int(7)]);
+    let start = Instant::now();
+    let packet = loop {
+        assert!(start.elapsed() < Duration::from_secs(5));
+        sender.send(msg.clone(), group).unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        if let Some((packet, _addr)) = receiver.try_recv().unwrap() {
+            break packet;
+        }
+    };
+    assert_eq!(packet, osc::Packet::Message(msg));
+    receiver
+        .leave_multicast_v4(*group.ip(), Ipv4Addr::UNSPECIFIED)
+        .unwrap();
+}
+
+#[test]
+fn discover_peers() {
+    let group = SocketAddrV4::new(Ipv4Addr::new(239, 255, 78, 80), 34262);
+    if !multicast_available(group) {
+        return;
+    }
+    let mut a = Discovery::with_group("a", 9000, group).unwrap();
+    let mut b = Discovery::with_group("b", 9001, group).unwrap();
+    a.set_interval(Duration::from_millis(10));
+    b.set_interval(Duration::from_millis(10));
+
+    let start = Instant::now();
+    while a.peers().is_empty() || b.peers().is_empty() {
+        assert!(start.elapsed() < Duration::from_secs(5));
+        a.update();
+        b.update();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(a.peers()[0].name, "b");
+    assert_eq!(a.peers()[0].addr.port(), 9001);
+    assert_eq!(b.peers()[0].name, "a");
+
+    // Peers announce when they are shutting down.
+    drop(b);
+    let start = Instant::now();
+    loop {
+        assert!(start.elapsed() < Duration::from_secs(5));
+        let events = a.update();
+        if let Some(Event::Left(peer)) = events.first() {
+            assert_eq!(peer.name, "b");
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert!(a.peers().is_empty());
+}
+
+#[test]
+fn discovery_skips_malformed_packets() {
+    let group = SocketAddrV4::new(Ipv4Addr::new(239, 255, 78, 81), 34263);
+    if !multicast_available(group) {
+        return;
+    }
+    let mut a = Discovery::with_group("a", 9000, group).unwrap();
+    let mut b = Discovery::with_group("b", 9001, group).unwrap();
+    // Observes the group in order to know when both packets have been delivered.
+    let probe = Receiver::builder()
+        .port(group.port())
+        .reuse_address(true)
+        .join_multicast_v4(*group.ip(), Ipv4Addr::UNSPECIFIED)
+        .build()
+        .unwrap();
+    let garbage = UdpSocket::bind("0.0.0.0:0").unwrap();
+    garbage.set_multicast_loop_v4(true).unwrap();
+
+    // A packet that is not valid OSC arrives before the announcement from `b`.
+    garbage.send_to(b"not osc", group).unwrap();
+    b.update();
+    let (mut errors, mut packets) = (0, 0);
+    let start = Instant::now();
+    while errors == 0 || packets == 0 {
+        assert!(start.elapsed() < Duration::from_secs(5));
+        match probe.try_recv() {
+            Ok(Some(_)) => packets += 1,
+            Ok(None) => std::thread::sleep(Duration::from_millis(10)),
+            Err(_) => errors += 1,
+        }
+    }
+    a.update();
+    assert_eq!(a.peers().len(), 1);
+    assert_eq!(a.peers()[0].name, "b");
+}
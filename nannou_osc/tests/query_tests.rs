@@ -0,0 +1,159 @@
+#![cfg(feature = "query")]
+
+use nannou_osc as osc;
+use nannou_osc::query::{Access, Param, ParamError, Range, Server};
+use nannou_osc::Type;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::{Duration, Instant};
+
+fn server() -> Server<Vec<f32>> {
+    let mut server = Server::bind_to("test", "127.0.0.1:0", "127.0.0.1:0").unwrap();
+    let freq = Param::new("/synth/freq", vec![Type::Float(440.0)])
+        .range(Range::new(Type::Float(20.0), Type::Float(20_000.0)))
+        .unit("frequency.Hz")
+        .description("The oscillator frequency");
+    let level = Param::new("/synth/level", vec![Type::Float(0.0)]).access(Access::Read);
+    server
+        .add(freq, |freqs: &mut Vec<f32>, _, (hz,): (f32,)| {
+            freqs.push(hz)
+        })
+        .unwrap()
+        .add_value(level)
+        .unwrap();
+    server
+}
+
+// Send a GET request, returning the status code and body.
+fn get(addr: SocketAddr, target: &str) -> (u16, String) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", target);
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let status = response[9..12].parse().unwrap();
+    let body = response.split_once("\r\n\r\n").unwrap().1.to_string();
+    (status, body)
+}
+
+fn json(body: &str) -> serde_json::Value {
+    serde_json::from_str(body).unwrap()
+}
+
+#[test]
+fn serves_namespace() {
+    let server = server();
+    let addr = server.http_addr();
+
+    let (status, body) = get(addr, "/");
+    assert_eq!(status, 200);
+    let root = json(&body);
+    let freq = &root["CONTENTS"]["synth"]["CONTENTS"]["freq"];
+    assert_eq!(freq["FULL_PATH"], "/synth/freq");
+    assert_eq!(freq["TYPE"], "f");
+    assert_eq!(freq["ACCESS"], 3);
+    assert_eq!(freq["VALUE"][0], 440.0);
+    assert_eq!(freq["RANGE"][0]["MAX"], 20_000.0);
+    assert_eq!(freq["UNIT"][0], "frequency.Hz");
+    assert_eq!(root["CONTENTS"]["synth"]["CONTENTS"]["level"]["ACCESS"], 1);
+
+    let (status, body) = get(addr, "/synth/freq?VALUE");
+    assert_eq!(status, 200);
+    assert_eq!(json(&body), serde_json::json!({ "VALUE": [440.0] }));
+
+    let (status, body) = get(addr, "/?HOST_INFO");
+    assert_eq!(status, 200);
+    let osc_port = server.osc_addr().unwrap().port();
+    assert_eq!(json(&body)["OSC_PORT"], osc_port);
+
+    assert_eq!(get(addr, "/synth/amp").0, 404);
+    assert_eq!(get(addr, "/synth?VALUE").0, 204);
+}
+
+#[test]
+fn routes_values_to_handlers() {
+    let mut server = server();
+    let osc_addr = format!("127.0.0.1:{}", server.osc_addr().unwrap().port());
+    let sender = osc::sender().unwrap();
+    sender
+        .send(
+            osc::msg("/synth/freq", vec![Type::Float(220.0)]),
+            &osc_addr[..],
+        )
+        .unwrap();
+    // Read-only parameters and values of the wrong type are not accepted.
+    sender
+        .send(
+            osc::msg("/synth/level", vec![Type::Float(1.0)]),
+            &osc_addr[..],
+        )
+        .unwrap();
+    sender
+        .send(osc::msg("/synth/freq", vec![Type::Int(1)]), &osc_addr[..])
+        .unwrap();
+
+    let mut freqs = vec![];
+    let mut errors = vec![];
+    let start = Instant::now();
+    while errors.is_empty() && start.elapsed() < Duration::from_secs(5) {
+        errors.extend(server.poll(&mut freqs));
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(freqs, vec![220.0]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].addr, "/synth/freq");
+    assert_eq!(server.get("/synth/freq"), Some(vec![Type::Float(220.0)]));
+    assert_eq!(server.get("/synth/level"), Some(vec![Type::Float(0.0)]));
+}
+
+#[test]
+fn param_errors() {
+    let mut server = server();
+    let exists = Param::new("/synth/freq", vec![]);
+    assert_eq!(
+        server.add_value(exists).err(),
+        Some(ParamError::AlreadyExists {
+            path: "/synth/freq".into()
+        })
+    );
+    let invalid = Param::new("/synth/*", vec![]);
+    assert_eq!(
+        server.add_value(invalid).err(),
+        Some(ParamError::InvalidPath {
+            path: "/synth/*".into()
+        })
+    );
+    assert!(server.set("/synth/level", vec![Type::Int(1)]).is_err());
+    assert!(server.remove("/synth/level").is_ok());
+    assert_eq!(
+        server.set("/synth/level", vec![Type::Float(1.0)]),
+        Err(ParamError::NotFound {
+            path: "/synth/level".into()
+        })
+    );
+}
+
+#[test]
+fn announces_changes_to_listeners() {
+    let server = server();
+    let url = format!("ws://{}/", server.http_addr());
+    let stream = TcpStream::connect(server.http_addr()).unwrap();
+    let (mut ws, _) = tungstenite::client(&url[..], stream).unwrap();
+    let listen = r#"{"COMMAND":"LISTEN","DATA":"/synth/level"}"#;
+    ws.write_message(tungstenite::Message::text(listen))
+        .unwrap();
+    ws.get_mut()
+        .set_read_timeout(Some(Duration::from_millis(20)))
+        .unwrap();
+
+    let start = Instant::now();
+    loop {
+        assert!(start.elapsed() < Duration::from_secs(5));
+        server.set("/synth/level", vec![Type::Float(0.5)]).unwrap();
+        if let Ok(tungstenite::Message::Binary(bytes)) = ws.read_message() {
+            let msg = osc::decode(&bytes).unwrap().into_msgs().remove(0);
+            assert_eq!(msg, osc::msg("/synth/level", vec![Type::Float(0.5)]));
+            break;
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use nannou_osc as osc;
+use nannou_osc::args::ArgsError;
+use nannou_osc::pattern::PatternError;
+use nannou_osc::router::{system_time_to_time_tag, time_tag_to_system_time};
+use nannou_osc::{Bundle, Packet, Pattern, Router, Type};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn matches(pattern: &str, addr: &str) -> bool {
+    Pattern::new(pattern).unwrap().matches(addr)
+}
+
+#[test]
+fn pattern_literal() {
+    assert!(matches("/a/b", "/a/b"));
+    assert!(!matches("/a/b", "/a/bc"));
+    assert!(!matches("/a/b", "/a"));
+    assert!(Pattern::new("/a/b").unwrap().is_literal());
+}
+
+#[test]
+fn pattern_wildcards() {
+    assert!(matches("/synth/?/freq", "/synth/1/freq"));
+    assert!(!matches("/synth/?/freq", "/synth/12/freq"));
+    assert!(matches("/synth/*/freq", "/synth/lead/freq"));
+    assert!(matches("/synth/*/freq", "/synth//freq"));
+    assert!(matches("/synth/*", "/synth/lead"));
+    assert!(matches("/synth/l*d*", "/synth/lead"));
+    // Wildcards never match the `/` separating parts.
+    assert!(!matches("/synth/*", "/synth/lead/freq"));
+    assert!(!matches("/*/freq", "/synth/lead/freq"));
+    assert!(!matches("/synth?lead", "/synth/lead"));
+}
+
+#[test]
+fn pattern_brackets() {
+    assert!(matches("/ch[1-4]", "/ch3"));
+    assert!(!matches("/ch[1-4]", "/ch5"));
+    assert!(matches("/ch[!1-4]", "/ch5"));
+    assert!(!matches("/ch[!1-4]", "/ch2"));
+    assert!(matches("/[abc]", "/b"));
+    assert!(matches("/x[a-]", "/x-"));
+    assert!(matches("/[4-1]", "/2"));
+}
+
+#[test]
+fn pattern_braces() {
+    assert!(matches("/mixer/{left,right}/gain", "/mixer/left/gain"));
+    assert!(matches("/mixer/{left,right}/gain", "/mixer/right/gain"));
+    assert!(!matches("/mixer/{left,right}/gain", "/mixer/centre/gain"));
+    assert!(matches("/{a,ab}c", "/abc"));
+    assert!(matches("/x{,y}", "/x"));
+}
+
+#[test]
+fn pattern_errors() {
+    assert_eq!(
+        Pattern::new("synth"),
+        Err(PatternError::MissingLeadingSlash)
+    );
+    assert_eq!(
+        Pattern::new("/ch[1-4"),
+        Err(PatternError::UnclosedBracket { position: 3 })
+    );
+    assert_eq!(
+        Pattern::new("/{a,b"),
+        Err(PatternError::UnclosedBrace { position: 1 })
+    );
+    assert_eq!(
+        Pattern::new("/{a/b}"),
+        Err(PatternError::UnexpectedChar {
+            position: 3,
+            c: '/',
+        })
+    );
+}
+
+#[test]
+fn extract_args() {
+    let args = vec![Type::String("kick".into()), Type::Int(3), Type::Nil];
+    let (name, ix, gain): (String, i64, Option<f32>) = osc::args::extract(&args).unwrap();
+    assert_eq!((name.as_str(), ix, gain), ("kick", 3, None));
+
+    let count = osc::args::extract::<(String,)>(&args);
+    assert_eq!(
+        count,
+        Err(ArgsError::Count {
+            expected: 1,
+            found: 3,
+        })
+    );
+
+    let wrong = osc::args::extract::<(String, f32, Option<f32>)>(&args);
+    assert_eq!(
+        wrong,
+        Err(ArgsError::Type {
+            index: 1,
+            expected: "float",
+            found: Type::Int(3),
+        })
+    );
+
+    let floats: Vec<f64> = osc::args::extract(&[Type::Float(1.0), Type::Double(2.0)]).unwrap();
+    assert_eq!(floats, vec![1.0, 2.0]);
+}
+
+#[test]
+fn router_dispatch() {
+    let mut router = Router::new();
+    router
+        .route("/note/*", |log: &mut Vec<String>, addr, (key,): (i32,)| {
+            log.push(format!("{} {}", addr, key));
+        })
+        .unwrap()
+        .route("/note/on", |log: &mut Vec<String>, _, (_key,): (i32,)| {
+            log.push("on".into());
+        })
+        .unwrap()
+        .unmatched(|log: &mut Vec<String>, msg| log.push(format!("? {}", msg.addr)));
+
+    let mut log = vec![];
+    let errors = router.dispatch(&mut log, osc::msg("/note/on", vec![Type::Int(60)]));
+    assert!(errors.is_empty());
+    let errors = router.dispatch(&mut log, osc::msg("/cc", vec![]));
+    assert!(errors.is_empty());
+    assert_eq!(log, vec!["/note/on 60", "on", "? /cc"]);
+
+    let errors = router.dispatch(&mut log, osc::msg("/note/off", vec![Type::Float(1.0)]));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].addr, "/note/off");
+    assert_eq!(errors[0].pattern, "/note/*");
+}
+
+#[test]
+fn router_schedules_bundles() {
+    let now = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+    let later = now + Duration::from_secs(1);
+    let bundle = |time: SystemTime, value: i32| -> Packet {
+        let (secs, frac) = system_time_to_time_tag(time);
+        Bundle {
+            timetag: Type::Time(secs, frac),
+            content: vec![osc::rosc::OscPacket::Message(osc::msg(
+                "/v",
+                vec![Type::Int(value)],
+            ))],
+        }
+        .into()
+    };
+
+    let mut router = Router::new();
+    router
+        .route("/v", |values: &mut Vec<i32>, _, (v,): (i32,)| {
+            values.push(v)
+        })
+        .unwrap();
+
+    let mut values = vec![];
+    router.dispatch_at(&mut values, bundle(later, 2), now);
+    router.dispatch_at(&mut values, bundle(now, 1), now);
+    assert_eq!(values, vec![1]);
+    assert_eq!(router.num_scheduled(), 1);
+
+    router.poll_at(&mut values, now + Duration::from_millis(500));
+    assert_eq!(values, vec![1]);
+    router.poll_at(&mut values, later + Duration::from_millis(1));
+    assert_eq!(values, vec![1, 2]);
+    assert_eq!(router.next_scheduled(), None);
+}
+
+#[test]
+fn time_tag_round_trip() {
+    let time = UNIX_EPOCH + Duration::from_millis(1_600_000_000_250);
+    let tag = system_time_to_time_tag(time);
+    let diff = match time_tag_to_system_time(tag).duration_since(time) {
+        Ok(d) => d,
+        Err(e) => e.duration(),
+    };
+    assert!(diff < Duration::from_micros(1));
+    assert_eq!(time_tag_to_system_time((2_208_988_800, 0)), UNIX_EPOCH);
+}
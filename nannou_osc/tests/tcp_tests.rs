@@ -0,0 +1,106 @@
+use nannou_osc as osc;
+use nannou_osc::tcp::{Decoder, FrameError, Framing, Receiver, Sender};
+use nannou_osc::Type;
+use std::time::{Duration, Instant};
+
+// Wait for the next packet, failing the test if none arrives in time.
+fn recv_timeout(receiver: &Receiver) -> osc::Packet {
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(5) {
+        if let Some((packet, _addr)) = receiver.try_recv().unwrap() {
+            return packet;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    panic!("timed out waiting for packet");
+}
+
+#[test]
+fn slip_framing() {
+    let framed = Framing::Slip.encode(&[1, 0xC0, 2, 0xDB]);
+    assert_eq!(framed, vec![0xC0, 1, 0xDB, 0xDC, 2, 0xDB, 0xDD, 0xC0]);
+
+    let mut decoder = Decoder::new(Framing::Slip);
+    // Feed the frame byte by byte, followed by a second frame.
+    for &byte in &framed {
+        assert_eq!(decoder.next_frame(), Ok(None));
+        decoder.push(&[byte]);
+    }
+    decoder.push(&Framing::Slip.encode(&[3]));
+    assert_eq!(decoder.next_frame(), Ok(Some(vec![1, 0xC0, 2, 0xDB])));
+    assert_eq!(decoder.next_frame(), Ok(Some(vec![3])));
+    assert_eq!(decoder.next_frame(), Ok(None));
+
+    decoder.push(&[0xC0, 0xDB, 0x01, 0xC0]);
+    assert_eq!(
+        decoder.next_frame(),
+        Err(FrameError::InvalidEscape { byte: 0x01 })
+    );
+}
+
+#[test]
+fn length_prefixed_framing() {
+    let framed = Framing::LengthPrefixed.encode(&[7, 8, 9]);
+    assert_eq!(framed, vec![0, 0, 0, 3, 7, 8, 9]);
+
+    let mut decoder = Decoder::with_max_frame_len(Framing::LengthPrefixed, 4);
+    decoder.push(&framed[..5]);
+    assert_eq!(decoder.next_frame(), Ok(None));
+    decoder.push(&framed[5..]);
+    assert_eq!(decoder.next_frame(), Ok(Some(vec![7, 8, 9])));
+
+    decoder.push(&[0, 0, 0, 5]);
+    assert_eq!(
+        decoder.next_frame(),
+        Err(FrameError::TooLarge { len: 5, max: 4 })
+    );
+}
+
+#[test]
+fn send_and_receive() {
+    for &framing in &[Framing::Slip, Framing::LengthPrefixed] {
+        let receiver = Receiver::bind_to_with_framing("127.0.0.1:0", framing).unwrap();
+        let sender = Sender::connect_with_framing(receiver.local_addr(), framing).unwrap();
+        assert!(sender.is_connected());
+
+        let msg = osc::msg("/tcp", vec![Type::Int(1), Type::String("two".into())]);
+        sender.send(msg.clone()).unwrap();
+        sender.send(("/tcp", vec![Type::Float(3.0)])).unwrap();
+        assert_eq!(recv_timeout(&receiver), osc::Packet::Message(msg));
+        assert_eq!(
+            recv_timeout(&receiver).into_msgs()[0].args,
+            Some(vec![Type::Float(3.0)])
+        );
+        assert_eq!(receiver.peers(), vec![sender.local_addr().unwrap()]);
+    }
+}
+
+#[test]
+fn sender_reconnects() {
+    // Find a free port, then release it so that nothing is listening yet.
+    let addr = Receiver::bind_to("127.0.0.1:0").unwrap().local_addr();
+    std::thread::sleep(Duration::from_millis(50));
+
+    let sender = Sender::connect(addr).unwrap();
+    assert!(!sender.is_connected());
+    sender
+        .set_reconnect_interval(Duration::from_millis(0))
+        .unwrap();
+    assert!(sender.send(("/a", vec![])).is_err());
+
+    let receiver = Receiver::bind_to(addr).unwrap();
+    sender.send(("/b", vec![])).unwrap();
+    assert!(sender.is_connected());
+    assert_eq!(recv_timeout(&receiver).into_msgs()[0].addr, "/b");
+
+    // Once the receiver is gone, sending eventually fails and the sender reconnects to the next.
+    drop(receiver);
+    let start = Instant::now();
+    while sender.send(("/c", vec![])).is_ok() {
+        assert!(start.elapsed() < Duration::from_secs(5));
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    let receiver = Receiver::bind_to(addr).unwrap();
+    sender.send(("/d", vec![])).unwrap();
+    assert_eq!(recv_timeout(&receiver).into_msgs()[0].addr, "/d");
+}